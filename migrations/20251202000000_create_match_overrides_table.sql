@@ -0,0 +1,12 @@
+-- Add migration script here
+-- Manual identify corrections (parsed title -> chosen provider match)
+CREATE TABLE IF NOT EXISTS match_overrides (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    parsed_title TEXT NOT NULL UNIQUE,
+    provider TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    media_type TEXT NOT NULL,
+    title TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
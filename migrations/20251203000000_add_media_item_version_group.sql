@@ -0,0 +1,5 @@
+-- Add migration script here
+-- Files that are versions of the same movie (e.g. 1080p and 2160p) share a version group
+ALTER TABLE media_items ADD COLUMN version_group TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_media_items_version_group ON media_items(version_group);
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- Glob patterns excluded from library scans, stored as a JSON array
+ALTER TABLE library_folders ADD COLUMN exclude_patterns TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,5 @@
+-- Add migration script here
+-- Set when a scan no longer finds the item's file, cleared when it reappears
+ALTER TABLE media_items ADD COLUMN missing_since TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_media_items_missing_since ON media_items(missing_since);
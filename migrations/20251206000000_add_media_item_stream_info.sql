@@ -0,0 +1,9 @@
+-- Add migration script here
+-- Technical details read with ffprobe when a video file is imported
+ALTER TABLE media_items ADD COLUMN duration REAL;
+ALTER TABLE media_items ADD COLUMN container TEXT;
+ALTER TABLE media_items ADD COLUMN video_codec TEXT;
+ALTER TABLE media_items ADD COLUMN audio_codec TEXT;
+ALTER TABLE media_items ADD COLUMN width INTEGER;
+ALTER TABLE media_items ADD COLUMN height INTEGER;
+ALTER TABLE media_items ADD COLUMN bit_rate INTEGER;
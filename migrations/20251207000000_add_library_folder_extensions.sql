@@ -0,0 +1,3 @@
+-- Add migration script here
+-- Per-library changes to the scanned file extensions, stored as a JSON object
+ALTER TABLE library_folders ADD COLUMN extensions TEXT NOT NULL DEFAULT '{}';
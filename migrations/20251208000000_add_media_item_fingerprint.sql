@@ -0,0 +1,5 @@
+-- Add migration script here
+-- Hash of the file size, head and tail, used to follow files that are renamed or moved
+ALTER TABLE media_items ADD COLUMN fingerprint TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_media_items_fingerprint ON media_items(fingerprint);
@@ -0,0 +1,104 @@
+-- Add migration script here
+-- Allow the 'music' media type. SQLite can't alter CHECK constraints, so the tables are
+-- rebuilt. Migrations run in a transaction with foreign keys on, where dropping a parent
+-- table cascades into its children, so all rows are set aside and the tables dropped
+-- child first.
+CREATE TEMP TABLE library_folders_backup AS SELECT * FROM library_folders;
+CREATE TEMP TABLE media_items_backup AS SELECT * FROM media_items;
+CREATE TEMP TABLE video_metadata_backup AS SELECT * FROM video_metadata;
+
+DROP TABLE video_metadata;
+DROP TABLE media_items;
+DROP TABLE library_folders;
+
+CREATE TABLE library_folders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL UNIQUE,
+    media_type TEXT NOT NULL CHECK(media_type IN ('movie', 'tv', 'comic', 'book', 'music')),
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    exclude_patterns TEXT NOT NULL DEFAULT '[]',
+    extensions TEXT NOT NULL DEFAULT '{}'
+);
+
+CREATE TABLE media_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    library_folder_id INTEGER NOT NULL,
+    media_type TEXT NOT NULL CHECK(media_type IN ('movie', 'tv', 'comic', 'book', 'music')),
+    title TEXT NOT NULL,
+    file_path TEXT NOT NULL UNIQUE,
+    file_size INTEGER NOT NULL,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    version_group TEXT,
+    missing_since TIMESTAMP,
+    duration REAL,
+    container TEXT,
+    video_codec TEXT,
+    audio_codec TEXT,
+    width INTEGER,
+    height INTEGER,
+    bit_rate INTEGER,
+    fingerprint TEXT,
+    FOREIGN KEY (library_folder_id) REFERENCES library_folders(id) ON DELETE CASCADE
+);
+
+CREATE TABLE video_metadata (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    media_item_id INTEGER NOT NULL UNIQUE,
+    tmdb_id INTEGER,
+    tvdb_id INTEGER,
+    imdb_id TEXT,
+    overview TEXT,
+    poster_path TEXT,
+    backdrop_path TEXT,
+    release_date TEXT,
+    runtime INTEGER,
+    vote_average REAL,
+    vote_count INTEGER,
+    genres TEXT, -- JSON array
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (media_item_id) REFERENCES media_items(id) ON DELETE CASCADE
+);
+
+INSERT INTO library_folders (
+    id, name, path, media_type, enabled, created_at, updated_at, exclude_patterns, extensions
+)
+SELECT id, name, path, media_type, enabled, created_at, updated_at, exclude_patterns, extensions
+FROM library_folders_backup;
+
+INSERT INTO media_items (
+    id, library_folder_id, media_type, title, file_path, file_size, added_at, updated_at,
+    version_group, missing_since, duration, container, video_codec, audio_codec, width, height,
+    bit_rate, fingerprint
+)
+SELECT
+    id, library_folder_id, media_type, title, file_path, file_size, added_at, updated_at,
+    version_group, missing_since, duration, container, video_codec, audio_codec, width, height,
+    bit_rate, fingerprint
+FROM media_items_backup;
+
+INSERT INTO video_metadata (
+    id, media_item_id, tmdb_id, tvdb_id, imdb_id, overview, poster_path, backdrop_path,
+    release_date, runtime, vote_average, vote_count, genres, created_at, updated_at
+)
+SELECT
+    id, media_item_id, tmdb_id, tvdb_id, imdb_id, overview, poster_path, backdrop_path,
+    release_date, runtime, vote_average, vote_count, genres, created_at, updated_at
+FROM video_metadata_backup;
+
+DROP TABLE library_folders_backup;
+DROP TABLE media_items_backup;
+DROP TABLE video_metadata_backup;
+
+CREATE INDEX IF NOT EXISTS idx_library_folders_enabled ON library_folders(enabled);
+CREATE INDEX IF NOT EXISTS idx_media_items_library_folder ON media_items(library_folder_id);
+CREATE INDEX IF NOT EXISTS idx_media_items_type ON media_items(media_type);
+CREATE INDEX IF NOT EXISTS idx_media_items_version_group ON media_items(version_group);
+CREATE INDEX IF NOT EXISTS idx_media_items_missing_since ON media_items(missing_since);
+CREATE INDEX IF NOT EXISTS idx_media_items_fingerprint ON media_items(fingerprint);
+CREATE INDEX IF NOT EXISTS idx_video_metadata_media_item ON video_metadata(media_item_id);
+CREATE INDEX IF NOT EXISTS idx_video_metadata_tmdb ON video_metadata(tmdb_id);
@@ -0,0 +1,19 @@
+-- Add migration script here
+-- Tags read from audio files; tracks of one album share an album key (their album folder)
+CREATE TABLE IF NOT EXISTS music_metadata (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    media_item_id INTEGER NOT NULL UNIQUE,
+    title TEXT,
+    artist TEXT,
+    album_artist TEXT,
+    album TEXT,
+    track_number INTEGER,
+    disc_number INTEGER,
+    year INTEGER,
+    album_key TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (media_item_id) REFERENCES media_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_music_metadata_album_key ON music_metadata(album_key);
@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Set when a scan finds the folder unreachable (e.g. a dropped network share), cleared
+-- by the next complete scan
+ALTER TABLE library_folders ADD COLUMN offline_since TIMESTAMP;
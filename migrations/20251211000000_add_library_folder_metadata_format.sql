@@ -0,0 +1,3 @@
+-- Add migration script here
+-- Sidecars written for the library's media: 'nfo', 'json' or 'both'
+ALTER TABLE library_folders ADD COLUMN metadata_format TEXT NOT NULL DEFAULT 'nfo';
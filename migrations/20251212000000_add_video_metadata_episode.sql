@@ -0,0 +1,5 @@
+-- Add migration script here
+-- Season and episode an episode file was resolved to, e.g. from its air date or an
+-- absolute number (NULL for movies and unresolved files)
+ALTER TABLE video_metadata ADD COLUMN season_number INTEGER;
+ALTER TABLE video_metadata ADD COLUMN episode_number INTEGER;
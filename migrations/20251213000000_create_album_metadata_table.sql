@@ -0,0 +1,18 @@
+-- Add migration script here
+-- MusicBrainz details of an album, shared by its tracks through their album key
+CREATE TABLE IF NOT EXISTS album_metadata (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    album_key TEXT NOT NULL UNIQUE,
+    musicbrainz_id TEXT NOT NULL,
+    release_id TEXT,
+    artist_id TEXT,
+    title TEXT NOT NULL,
+    artist TEXT,
+    release_date TEXT,
+    kind TEXT,
+    label TEXT,
+    genres TEXT, -- JSON array
+    cover_url TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,27 @@
+-- Add migration script here
+-- Book metadata table (for ebooks), from Open Library or Google Books
+CREATE TABLE IF NOT EXISTS book_metadata (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    media_item_id INTEGER NOT NULL UNIQUE,
+    provider TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    subtitle TEXT,
+    authors TEXT, -- JSON array
+    description TEXT,
+    publisher TEXT,
+    published_date TEXT,
+    language TEXT,
+    page_count INTEGER,
+    isbn TEXT,
+    subjects TEXT, -- JSON array
+    series TEXT,
+    series_index REAL,
+    rating REAL,
+    cover_url TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (media_item_id) REFERENCES media_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_book_metadata_isbn ON book_metadata(isbn);
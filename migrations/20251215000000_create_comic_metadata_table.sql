@@ -0,0 +1,27 @@
+-- Add migration script here
+-- Comic metadata table: the series a comic or manga file belongs to, from MangaDex, and the
+-- volume and chapter the file holds
+CREATE TABLE IF NOT EXISTS comic_metadata (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    media_item_id INTEGER NOT NULL UNIQUE,
+    provider TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    authors TEXT, -- JSON array
+    artists TEXT, -- JSON array
+    genres TEXT, -- JSON array
+    status TEXT,
+    year INTEGER,
+    volume REAL,
+    chapter REAL,
+    chapter_title TEXT,
+    cover_url TEXT,
+    anilist_id TEXT,
+    mal_id TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (media_item_id) REFERENCES media_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_comic_metadata_provider_id ON comic_metadata(provider_id);
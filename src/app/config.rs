@@ -1,8 +1,10 @@
 use std::{
+    collections::HashMap,
     fs,
     net::SocketAddr,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use config::{Config as ConfigBuilder, Environment, File as ConfigFile};
@@ -12,6 +14,9 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use crate::error::ConfigError;
+use crate::scraper::{
+    CacheConfig, MatchStrategyKind, MediaType, MergeRules, NetworkOptions, WebhookOptions,
+};
 
 // Global configuration manager instance
 static CONFIG_MANAGER: OnceCell<ConfigManager> = OnceCell::new();
@@ -41,6 +46,9 @@ pub struct AppConfig {
 
     #[serde(default)]
     pub scraper: ScraperConfig,
+
+    #[serde(default)]
+    pub library: LibraryConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,14 +89,265 @@ impl Default for LoggingConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScraperConfig {
+    /// TMDB v3 API key or v4 read access token, told apart by their format
     #[serde(default)]
     pub tmdb_api_key: Option<String>,
 
     #[serde(default)]
     pub tvdb_api_key: Option<String>,
 
+    /// Adds IMDb, Rotten Tomatoes and Metacritic ratings to fetched metadata
+    #[serde(default)]
+    pub omdb_api_key: Option<String>,
+
+    /// Client ID of a Trakt API app; adds Trakt IDs, aliases and ratings
+    #[serde(default)]
+    pub trakt_client_id: Option<String>,
+
+    /// Client name registered with AniDB's HTTP API
     #[serde(default)]
+    pub anidb_client: Option<String>,
+
+    /// Version of the registered AniDB client
+    #[serde(default = "default_anidb_client_version")]
+    pub anidb_client_version: u32,
+
+    /// Fetch Chinese titles and summaries from Douban
+    #[serde(default)]
+    pub douban_enabled: bool,
+
+    /// Google Books API key; books are looked up without one too, within a
+    /// small shared daily quota
+    #[serde(default)]
+    pub google_books_api_key: Option<String>,
+
+    /// Folder of the IMDb datasets (`title.*.tsv.gz`), served offline from
+    /// an `imdb.db` imported there; missing files are downloaded on first start
+    #[serde(default)]
+    pub imdb_datasets_dir: Option<String>,
+
+    /// How long fetched metadata stays cached
+    #[serde(default = "default_cache_ttl_seconds")]
     pub cache_ttl_seconds: u64,
+
+    /// How long search results stay cached
+    #[serde(default = "default_search_cache_ttl_seconds")]
+    pub search_cache_ttl_seconds: u64,
+
+    /// How long a search that found nothing is remembered, so unmatched
+    /// files don't repeat it on every rescan
+    #[serde(default = "default_miss_cache_ttl_seconds")]
+    pub miss_cache_ttl_seconds: u64,
+
+    /// SQLite database keeping the scraper cache across restarts; empty keeps
+    /// it in memory only
+    #[serde(default = "default_cache_path")]
+    pub cache_path: String,
+
+    /// Most entries kept in the cache database
+    #[serde(default = "default_cache_max_entries")]
+    pub cache_max_entries: u64,
+
+    /// Folder keeping downloaded posters and backdrops, so artwork is fetched
+    /// once; empty downloads it every time
+    #[serde(default = "default_image_cache_dir")]
+    pub image_cache_dir: String,
+
+    /// Megabytes of artwork the image cache keeps before removing the
+    /// images used least recently (0 for no limit)
+    #[serde(default = "default_image_cache_max_mb")]
+    pub image_cache_max_mb: u64,
+
+    /// Artwork downloads running at once
+    #[serde(default = "default_download_concurrency")]
+    pub download_concurrency: usize,
+
+    /// Artwork downloads running at once from one host (e.g. image.tmdb.org)
+    #[serde(default = "default_download_per_host")]
+    pub download_per_host: usize,
+
+    /// yt-dlp binary saving movie trailers next to organized movies; unset
+    /// disables trailers
+    #[serde(default)]
+    pub ytdlp_path: Option<String>,
+
+    /// Trailers larger than this many megabytes are skipped (0 for no limit)
+    #[serde(default = "default_trailer_max_size_mb")]
+    pub trailer_max_size_mb: u64,
+
+    /// Languages whose leading articles (The, Le, Der, El...) are ignored
+    /// for sort titles and matching; articles in an item's original language
+    /// are always stripped from its sort title
+    #[serde(default = "default_title_article_languages")]
+    pub title_article_languages: Vec<String>,
+
+    /// Preferred languages by provider ID, most preferred first; titles and
+    /// overviews fall back along the list, then to the provider's default
+    /// (e.g. `tmdb = ["zh-CN", "zh-TW"]` before English)
+    #[serde(default)]
+    pub languages: HashMap<String, Vec<String>>,
+
+    /// Preferred metadata languages of providers without their own entry in
+    /// `languages`, most preferred first. Titles and overviews still blank
+    /// in all of them are fetched in the title's original language
+    #[serde(default)]
+    pub metadata_languages: Vec<String>,
+
+    /// Strategy used to score search results
+    #[serde(default)]
+    pub match_strategy: MatchStrategyKind,
+
+    /// Calls per second allowed to a provider, by provider ID, on top of the
+    /// provider's own API limit (e.g. `anilist = 0.5` for slow bulk scrapes)
+    #[serde(default)]
+    pub rate_limits: HashMap<String, f64>,
+
+    /// Seconds a provider gets to answer a search before it is skipped
+    /// (0 waits for it however long it takes)
+    #[serde(default = "default_provider_timeout_seconds")]
+    pub provider_timeout_seconds: u64,
+
+    /// Search timeouts in seconds by provider ID, for providers slower or
+    /// faster than the rest (e.g. `anidb = 30`)
+    #[serde(default)]
+    pub provider_timeouts: HashMap<String, u64>,
+
+    /// Provider priorities by media type, replacing the built-in ones (higher
+    /// is searched first), e.g. `[scraper.priorities.anime]` with
+    /// `bangumi = 110` to prefer Bangumi over AniList, or
+    /// `[scraper.priorities.tv]` with `tvdb = 100` for TVDB over TMDB
+    #[serde(default)]
+    pub priorities: HashMap<MediaType, HashMap<String, i32>>,
+
+    /// Providers each metadata field is taken from, most preferred first,
+    /// merging their records of a matched title (e.g. `[scraper.merge]`
+    /// with `overview = ["tmdb"]` and `tags = ["anilist", "tmdb"]`)
+    #[serde(default)]
+    pub merge: MergeRules,
+
+    /// Provider calls running at once across all providers
+    #[serde(default = "default_max_concurrent_calls")]
+    pub max_concurrent_calls: usize,
+
+    /// Of those, calls kept free for API requests, so library scans and
+    /// bulk organizing can't take every slot
+    #[serde(default = "default_interactive_reserve")]
+    pub interactive_reserve: usize,
+
+    /// Provider calls per second scans and bulk jobs may make, across all
+    /// providers (0 for no limit)
+    #[serde(default)]
+    pub background_rate: f64,
+
+    /// Proxy for providers without one of their own, e.g. `socks5h://127.0.0.1:1080`
+    #[serde(default)]
+    pub proxy: Option<String>,
+
+    /// Proxies and API mirrors by provider ID (e.g. `[scraper.network.bangumi]`
+    /// with `base_url`, or `[scraper.network.tmdb]` with `image_base_url`)
+    #[serde(default)]
+    pub network: HashMap<String, NetworkOptions>,
+
+    /// External metadata services, as `[[scraper.webhooks]]` tables with
+    /// `id`, `url` and optionally `name`, `token`, `media_types` and `priority`
+    #[serde(default)]
+    pub webhooks: Vec<WebhookOptions>,
+}
+
+impl ScraperConfig {
+    /// Network settings of a provider, falling back to the shared proxy
+    #[must_use]
+    pub fn network_for(&self, provider: &str) -> NetworkOptions {
+        let mut network = self.network.get(provider).cloned().unwrap_or_default();
+        if network.proxy.is_none() {
+            network.proxy.clone_from(&self.proxy);
+        }
+        network
+    }
+
+    /// Scraper cache settings
+    #[must_use]
+    pub fn cache_config(&self) -> CacheConfig {
+        CacheConfig {
+            search_ttl: Duration::from_secs(self.search_cache_ttl_seconds),
+            miss_ttl: Duration::from_secs(self.miss_cache_ttl_seconds),
+            metadata_ttl: Duration::from_secs(self.cache_ttl_seconds),
+            disk_max_entries: self.cache_max_entries,
+            ..CacheConfig::default()
+        }
+    }
+
+    /// Preferred languages of a provider, most preferred first
+    #[must_use]
+    pub fn languages_for(&self, provider: &str) -> Vec<String> {
+        self.languages
+            .get(provider)
+            .unwrap_or(&self.metadata_languages)
+            .clone()
+    }
+}
+
+const fn default_anidb_client_version() -> u32 {
+    1
+}
+
+const fn default_cache_ttl_seconds() -> u64 {
+    86400 // 24 hours
+}
+
+const fn default_search_cache_ttl_seconds() -> u64 {
+    3600
+}
+
+const fn default_miss_cache_ttl_seconds() -> u64 {
+    900
+}
+
+fn default_cache_path() -> String {
+    "./scraper_cache.db".to_string()
+}
+
+const fn default_cache_max_entries() -> u64 {
+    100_000
+}
+
+fn default_image_cache_dir() -> String {
+    "./image_cache".to_string()
+}
+
+const fn default_image_cache_max_mb() -> u64 {
+    crate::scraper::ImageCache::DEFAULT_MAX_BYTES / (1024 * 1024)
+}
+
+const fn default_download_concurrency() -> usize {
+    crate::scraper::DownloadQueue::DEFAULT_CONCURRENCY
+}
+
+const fn default_download_per_host() -> usize {
+    crate::scraper::DownloadQueue::DEFAULT_PER_HOST
+}
+
+const fn default_max_concurrent_calls() -> usize {
+    crate::scraper::ScrapeBudget::DEFAULT_CONCURRENCY
+}
+
+const fn default_interactive_reserve() -> usize {
+    crate::scraper::ScrapeBudget::DEFAULT_RESERVED
+}
+
+const fn default_provider_timeout_seconds() -> u64 {
+    10
+}
+
+const fn default_trailer_max_size_mb() -> u64 {
+    200
+}
+
+fn default_title_article_languages() -> Vec<String> {
+    crate::scraper::DEFAULT_ARTICLE_LANGUAGES
+        .iter()
+        .map(ToString::to_string)
+        .collect()
 }
 
 impl Default for ScraperConfig {
@@ -96,11 +355,102 @@ impl Default for ScraperConfig {
         Self {
             tmdb_api_key: None,
             tvdb_api_key: None,
-            cache_ttl_seconds: 86400, // 24 hours
+            omdb_api_key: None,
+            trakt_client_id: None,
+            anidb_client: None,
+            anidb_client_version: default_anidb_client_version(),
+            douban_enabled: false,
+            google_books_api_key: None,
+            imdb_datasets_dir: None,
+            cache_ttl_seconds: default_cache_ttl_seconds(),
+            search_cache_ttl_seconds: default_search_cache_ttl_seconds(),
+            miss_cache_ttl_seconds: default_miss_cache_ttl_seconds(),
+            cache_path: default_cache_path(),
+            cache_max_entries: default_cache_max_entries(),
+            image_cache_dir: default_image_cache_dir(),
+            image_cache_max_mb: default_image_cache_max_mb(),
+            download_concurrency: default_download_concurrency(),
+            download_per_host: default_download_per_host(),
+            ytdlp_path: None,
+            trailer_max_size_mb: default_trailer_max_size_mb(),
+            title_article_languages: default_title_article_languages(),
+            languages: HashMap::new(),
+            metadata_languages: Vec::new(),
+            match_strategy: MatchStrategyKind::default(),
+            rate_limits: HashMap::new(),
+            provider_timeout_seconds: default_provider_timeout_seconds(),
+            provider_timeouts: HashMap::new(),
+            priorities: HashMap::new(),
+            merge: MergeRules::default(),
+            max_concurrent_calls: default_max_concurrent_calls(),
+            interactive_reserve: default_interactive_reserve(),
+            background_rate: 0.0,
+            proxy: None,
+            network: HashMap::new(),
+            webhooks: Vec::new(),
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryConfig {
+    /// Delete media items whose files have been missing for this many days
+    /// (kept forever when unset)
+    #[serde(default)]
+    pub purge_missing_after_days: Option<u32>,
+
+    /// Read duration, codecs and resolution of new video files with ffprobe
+    #[serde(default = "default_true")]
+    pub probe_media: bool,
+
+    /// ffprobe binary, looked up on `PATH` unless absolute
+    #[serde(default = "default_ffprobe_path")]
+    pub ffprobe_path: String,
+
+    /// How many folder levels below a library root are scanned
+    #[serde(default = "default_max_scan_depth")]
+    pub max_scan_depth: usize,
+
+    /// Slow scans down so they don't starve playback from the same disks
+    #[serde(default)]
+    pub scan_throttle: crate::services::ScanThrottle,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+const fn default_max_scan_depth() -> usize {
+    crate::scraper::DEFAULT_MAX_DEPTH
+}
+
+fn default_ffprobe_path() -> String {
+    crate::services::DEFAULT_FFPROBE_PATH.to_string()
+}
+
+impl Default for LibraryConfig {
+    fn default() -> Self {
+        Self {
+            purge_missing_after_days: None,
+            probe_media: true,
+            ffprobe_path: default_ffprobe_path(),
+            max_scan_depth: default_max_scan_depth(),
+            scan_throttle: crate::services::ScanThrottle::default(),
+        }
+    }
+}
+
+impl LibraryConfig {
+    /// Media probe for scans, if enabled
+    #[must_use]
+    pub fn media_probe(&self) -> Option<crate::services::MediaProbe> {
+        self.probe_media.then(|| {
+            crate::services::MediaProbe::new(&self.ffprobe_path)
+                .with_idle_io(self.scan_throttle.idle_io_priority)
+        })
+    }
+}
+
 impl ConfigManager {
     /// Create a new configuration manager instance
     pub fn new<P: AsRef<Path>>(config_path: Option<P>) -> Result<Self, ConfigError> {
@@ -5,8 +5,17 @@ use std::time::Duration;
 
 pub type Database = Pool<Sqlite>;
 
+/// Database file location
+const DB_PATH: &str = "./ayiah.db";
+
+/// Absolute path of the database file, for files kept next to it
+#[must_use]
+pub fn path() -> PathBuf {
+    std::path::absolute(DB_PATH).unwrap_or_else(|_| PathBuf::from(DB_PATH))
+}
+
 pub async fn init() -> Result<Database, AyiahError> {
-    let db_path = PathBuf::from("./ayiah.db");
+    let db_path = path();
 
     // Ensure the parent directory exists
     if let Some(parent) = db_path.parent() {
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Album metadata entity, looked up on MusicBrainz for the tracks of an album folder
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AlbumMetadata {
+    pub id: i64,
+    /// Album folder, as in [`MusicMetadata::album_key`](super::MusicMetadata)
+    pub album_key: String,
+    /// MusicBrainz release group
+    pub musicbrainz_id: String,
+    /// MusicBrainz release the track list was taken from
+    pub release_id: Option<String>,
+    /// MusicBrainz ID of the first credited artist
+    pub artist_id: Option<String>,
+    pub title: String,
+    pub artist: Option<String>,
+    pub release_date: Option<String>,
+    pub kind: Option<String>,
+    pub label: Option<String>,
+    pub genres: Option<String>, // JSON array
+    pub cover_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create album metadata request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAlbumMetadata {
+    pub album_key: String,
+    pub musicbrainz_id: String,
+    pub release_id: Option<String>,
+    pub artist_id: Option<String>,
+    pub title: String,
+    pub artist: Option<String>,
+    pub release_date: Option<String>,
+    pub kind: Option<String>,
+    pub label: Option<String>,
+    pub genres: Vec<String>,
+    pub cover_url: Option<String>,
+}
+
+impl AlbumMetadata {
+    /// Create or update the metadata of an album
+    pub async fn upsert(
+        db: &sqlx::SqlitePool,
+        metadata: CreateAlbumMetadata,
+    ) -> Result<Self, sqlx::Error> {
+        let genres_json =
+            serde_json::to_string(&metadata.genres).unwrap_or_else(|_| "[]".to_string());
+
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            INSERT INTO album_metadata (
+                album_key, musicbrainz_id, release_id, artist_id, title, artist,
+                release_date, kind, label, genres, cover_url
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(album_key) DO UPDATE SET
+                musicbrainz_id = excluded.musicbrainz_id,
+                release_id = excluded.release_id,
+                artist_id = excluded.artist_id,
+                title = excluded.title,
+                artist = excluded.artist,
+                release_date = excluded.release_date,
+                kind = excluded.kind,
+                label = excluded.label,
+                genres = excluded.genres,
+                cover_url = excluded.cover_url,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            ",
+        )
+        .bind(metadata.album_key)
+        .bind(metadata.musicbrainz_id)
+        .bind(metadata.release_id)
+        .bind(metadata.artist_id)
+        .bind(metadata.title)
+        .bind(metadata.artist)
+        .bind(metadata.release_date)
+        .bind(metadata.kind)
+        .bind(metadata.label)
+        .bind(genres_json)
+        .bind(metadata.cover_url)
+        .fetch_one(db)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Find the metadata of an album folder
+    pub async fn find_by_album_key(
+        db: &sqlx::SqlitePool,
+        album_key: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM album_metadata WHERE album_key = ?
+            ",
+        )
+        .bind(album_key)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Book metadata entity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BookMetadata {
+    pub id: i64,
+    pub media_item_id: i64,
+    /// "openlibrary" or "googlebooks"
+    pub provider: String,
+    pub provider_id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub authors: Option<String>, // JSON array
+    pub description: Option<String>,
+    pub publisher: Option<String>,
+    pub published_date: Option<String>,
+    pub language: Option<String>,
+    pub page_count: Option<i32>,
+    pub isbn: Option<String>,
+    pub subjects: Option<String>, // JSON array
+    pub series: Option<String>,
+    pub series_index: Option<f32>,
+    pub rating: Option<f64>,
+    pub cover_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create book metadata request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBookMetadata {
+    pub media_item_id: i64,
+    pub provider: String,
+    pub provider_id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub authors: Vec<String>,
+    pub description: Option<String>,
+    pub publisher: Option<String>,
+    pub published_date: Option<String>,
+    pub language: Option<String>,
+    pub page_count: Option<i32>,
+    pub isbn: Option<String>,
+    pub subjects: Vec<String>,
+    pub series: Option<String>,
+    pub series_index: Option<f32>,
+    pub rating: Option<f64>,
+    pub cover_url: Option<String>,
+}
+
+impl BookMetadata {
+    /// Create or update book metadata
+    pub async fn upsert(
+        db: &sqlx::SqlitePool,
+        metadata: CreateBookMetadata,
+    ) -> Result<Self, sqlx::Error> {
+        let authors_json =
+            serde_json::to_string(&metadata.authors).unwrap_or_else(|_| "[]".to_string());
+        let subjects_json =
+            serde_json::to_string(&metadata.subjects).unwrap_or_else(|_| "[]".to_string());
+
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            INSERT INTO book_metadata (
+                media_item_id, provider, provider_id, title, subtitle, authors,
+                description, publisher, published_date, language, page_count, isbn,
+                subjects, series, series_index, rating, cover_url
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(media_item_id) DO UPDATE SET
+                provider = excluded.provider,
+                provider_id = excluded.provider_id,
+                title = excluded.title,
+                subtitle = excluded.subtitle,
+                authors = excluded.authors,
+                description = excluded.description,
+                publisher = excluded.publisher,
+                published_date = excluded.published_date,
+                language = excluded.language,
+                page_count = excluded.page_count,
+                isbn = excluded.isbn,
+                subjects = excluded.subjects,
+                series = excluded.series,
+                series_index = excluded.series_index,
+                rating = excluded.rating,
+                cover_url = excluded.cover_url,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            ",
+        )
+        .bind(metadata.media_item_id)
+        .bind(metadata.provider)
+        .bind(metadata.provider_id)
+        .bind(metadata.title)
+        .bind(metadata.subtitle)
+        .bind(authors_json)
+        .bind(metadata.description)
+        .bind(metadata.publisher)
+        .bind(metadata.published_date)
+        .bind(metadata.language)
+        .bind(metadata.page_count)
+        .bind(metadata.isbn)
+        .bind(subjects_json)
+        .bind(metadata.series)
+        .bind(metadata.series_index)
+        .bind(metadata.rating)
+        .bind(metadata.cover_url)
+        .fetch_one(db)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Find metadata by media item ID
+    pub async fn find_by_media_item_id(
+        db: &sqlx::SqlitePool,
+        media_item_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM book_metadata WHERE media_item_id = ?
+            ",
+        )
+        .bind(media_item_id)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(result)
+    }
+}
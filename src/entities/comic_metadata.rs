@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Comic metadata entity: a file's series, with the volume and chapter it holds
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ComicMetadata {
+    pub id: i64,
+    pub media_item_id: i64,
+    /// "mangadex"
+    pub provider: String,
+    /// Provider ID of the series
+    pub provider_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub authors: Option<String>, // JSON array
+    pub artists: Option<String>, // JSON array
+    pub genres: Option<String>,  // JSON array
+    pub status: Option<String>,
+    pub year: Option<i32>,
+    /// Volume the file holds or its chapter was collected in
+    pub volume: Option<f32>,
+    /// Chapter the file holds
+    pub chapter: Option<f32>,
+    pub chapter_title: Option<String>,
+    /// Cover of the volume, or of the series
+    pub cover_url: Option<String>,
+    pub anilist_id: Option<String>,
+    pub mal_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create comic metadata request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateComicMetadata {
+    pub media_item_id: i64,
+    pub provider: String,
+    pub provider_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    pub artists: Vec<String>,
+    pub genres: Vec<String>,
+    pub status: Option<String>,
+    pub year: Option<i32>,
+    pub volume: Option<f32>,
+    pub chapter: Option<f32>,
+    pub chapter_title: Option<String>,
+    pub cover_url: Option<String>,
+    pub anilist_id: Option<String>,
+    pub mal_id: Option<String>,
+}
+
+impl ComicMetadata {
+    /// Create or update comic metadata
+    pub async fn upsert(
+        db: &sqlx::SqlitePool,
+        metadata: CreateComicMetadata,
+    ) -> Result<Self, sqlx::Error> {
+        let json = |values: &[String]| {
+            serde_json::to_string(values).unwrap_or_else(|_| "[]".to_string())
+        };
+
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            INSERT INTO comic_metadata (
+                media_item_id, provider, provider_id, title, description, authors,
+                artists, genres, status, year, volume, chapter, chapter_title,
+                cover_url, anilist_id, mal_id
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(media_item_id) DO UPDATE SET
+                provider = excluded.provider,
+                provider_id = excluded.provider_id,
+                title = excluded.title,
+                description = excluded.description,
+                authors = excluded.authors,
+                artists = excluded.artists,
+                genres = excluded.genres,
+                status = excluded.status,
+                year = excluded.year,
+                volume = excluded.volume,
+                chapter = excluded.chapter,
+                chapter_title = excluded.chapter_title,
+                cover_url = excluded.cover_url,
+                anilist_id = excluded.anilist_id,
+                mal_id = excluded.mal_id,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            ",
+        )
+        .bind(metadata.media_item_id)
+        .bind(metadata.provider)
+        .bind(metadata.provider_id)
+        .bind(metadata.title)
+        .bind(metadata.description)
+        .bind(json(&metadata.authors))
+        .bind(json(&metadata.artists))
+        .bind(json(&metadata.genres))
+        .bind(metadata.status)
+        .bind(metadata.year)
+        .bind(metadata.volume)
+        .bind(metadata.chapter)
+        .bind(metadata.chapter_title)
+        .bind(metadata.cover_url)
+        .bind(metadata.anilist_id)
+        .bind(metadata.mal_id)
+        .fetch_one(db)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Find metadata by media item ID
+    pub async fn find_by_media_item_id(
+        db: &sqlx::SqlitePool,
+        media_item_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM comic_metadata WHERE media_item_id = ?
+            ",
+        )
+        .bind(media_item_id)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(result)
+    }
+}
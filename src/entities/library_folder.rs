@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
 use super::MediaType;
+use crate::scraper::MetadataFormat;
 
 /// Library folder entity
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -14,6 +15,52 @@ pub struct LibraryFolder {
     pub enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Glob patterns skipped when scanning, relative to `path`
+    #[sqlx(json)]
+    pub exclude_patterns: Vec<String>,
+    /// Changes to the file extensions scanned for this library's media type
+    #[sqlx(json)]
+    pub extensions: ExtensionOverrides,
+    /// When a scan found the folder unreachable (`None` while it is online)
+    pub offline_since: Option<DateTime<Utc>>,
+    /// Sidecars written when exporting metadata of this library's media
+    pub metadata_format: MetadataFormat,
+}
+
+/// Per-library changes to the default extension list
+///
+/// Extensions are matched case-insensitively, with or without a leading dot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionOverrides {
+    /// Scan only these extensions instead of the defaults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace: Option<Vec<String>>,
+    /// Extensions scanned in addition, e.g. `rmvb` or `strm`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Extensions never scanned, e.g. `iso`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl ExtensionOverrides {
+    /// The extension list to scan, starting from `defaults`
+    #[must_use]
+    pub fn apply(&self, defaults: &[&str]) -> Vec<String> {
+        let normalize = |ext: &str| ext.trim().trim_start_matches('.').to_lowercase();
+        let exclude: Vec<String> = self.exclude.iter().map(|e| normalize(e)).collect();
+
+        let mut extensions: Vec<String> = match self.replace {
+            Some(ref replace) => replace.iter().map(|e| normalize(e)).collect(),
+            None => defaults.iter().map(|e| normalize(e)).collect(),
+        };
+        extensions.extend(self.include.iter().map(|e| normalize(e)));
+        extensions.retain(|ext| !ext.is_empty() && !exclude.contains(ext));
+
+        let mut seen = std::collections::HashSet::new();
+        extensions.retain(|ext| seen.insert(ext.clone()));
+        extensions
+    }
 }
 
 /// Create library folder request
@@ -22,6 +69,9 @@ pub struct CreateLibraryFolder {
     pub name: String,
     pub path: String,
     pub media_type: MediaType,
+    pub exclude_patterns: Vec<String>,
+    pub extensions: ExtensionOverrides,
+    pub metadata_format: MetadataFormat,
 }
 
 impl LibraryFolder {
@@ -32,14 +82,18 @@ impl LibraryFolder {
     ) -> Result<Self, sqlx::Error> {
         let result = sqlx::query_as::<_, Self>(
             r"
-            INSERT INTO library_folders (name, path, media_type)
-            VALUES (?, ?, ?)
+            INSERT INTO library_folders
+                (name, path, media_type, exclude_patterns, extensions, metadata_format)
+            VALUES (?, ?, ?, ?, ?, ?)
             RETURNING *
             ",
         )
         .bind(folder.name)
         .bind(folder.path)
         .bind(folder.media_type)
+        .bind(sqlx::types::Json(folder.exclude_patterns))
+        .bind(sqlx::types::Json(folder.extensions))
+        .bind(folder.metadata_format)
         .fetch_one(db)
         .await?;
 
@@ -91,7 +145,8 @@ impl LibraryFolder {
         sqlx::query(
             r"
             UPDATE library_folders
-            SET name = ?, path = ?, media_type = ?, enabled = ?, updated_at = CURRENT_TIMESTAMP
+            SET name = ?, path = ?, media_type = ?, enabled = ?, exclude_patterns = ?,
+                extensions = ?, metadata_format = ?, updated_at = CURRENT_TIMESTAMP
             WHERE id = ?
             ",
         )
@@ -99,6 +154,9 @@ impl LibraryFolder {
         .bind(&self.path)
         .bind(self.media_type)
         .bind(self.enabled)
+        .bind(sqlx::types::Json(&self.exclude_patterns))
+        .bind(sqlx::types::Json(&self.extensions))
+        .bind(self.metadata_format)
         .bind(self.id)
         .execute(db)
         .await?;
@@ -106,6 +164,27 @@ impl LibraryFolder {
         Ok(())
     }
 
+    /// Flag a folder offline, keeping the time it first went offline, or back online
+    pub async fn set_offline(
+        db: &sqlx::SqlitePool,
+        id: i64,
+        offline: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r"
+            UPDATE library_folders
+            SET offline_since = CASE WHEN ? THEN COALESCE(offline_since, CURRENT_TIMESTAMP) END
+            WHERE id = ?
+            ",
+        )
+        .bind(offline)
+        .bind(id)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
     /// Delete library folder
     pub async fn delete(db: &sqlx::SqlitePool, id: i64) -> Result<(), sqlx::Error> {
         sqlx::query(
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Manual match override entity, recorded when a user identifies an item
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MatchOverride {
+    pub id: i64,
+    /// Normalized title parsed from the filename
+    pub parsed_title: String,
+    pub provider: String,
+    pub provider_id: String,
+    pub media_type: String,
+    /// Title of the chosen match
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create match override request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMatchOverride {
+    pub parsed_title: String,
+    pub provider: String,
+    pub provider_id: String,
+    pub media_type: String,
+    pub title: String,
+}
+
+impl MatchOverride {
+    /// Create or replace the override for a parsed title
+    pub async fn upsert(
+        db: &sqlx::SqlitePool,
+        item: CreateMatchOverride,
+    ) -> Result<Self, sqlx::Error> {
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            INSERT INTO match_overrides (parsed_title, provider, provider_id, media_type, title)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(parsed_title) DO UPDATE SET
+                provider = excluded.provider,
+                provider_id = excluded.provider_id,
+                media_type = excluded.media_type,
+                title = excluded.title,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            ",
+        )
+        .bind(item.parsed_title)
+        .bind(item.provider)
+        .bind(item.provider_id)
+        .bind(item.media_type)
+        .bind(item.title)
+        .fetch_one(db)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// List all overrides
+    pub async fn list_all(db: &sqlx::SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let results = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM match_overrides ORDER BY updated_at DESC
+            ",
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(results)
+    }
+
+    /// Delete an override by parsed title
+    pub async fn delete_by_parsed_title(
+        db: &sqlx::SqlitePool,
+        parsed_title: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r"
+            DELETE FROM match_overrides WHERE parsed_title = ?
+            ",
+        )
+        .bind(parsed_title)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+}
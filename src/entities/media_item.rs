@@ -11,6 +11,7 @@ pub enum MediaType {
     Tv,
     Comic,
     Book,
+    Music,
 }
 
 impl std::fmt::Display for MediaType {
@@ -20,6 +21,7 @@ impl std::fmt::Display for MediaType {
             Self::Tv => write!(f, "tv"),
             Self::Comic => write!(f, "comic"),
             Self::Book => write!(f, "book"),
+            Self::Music => write!(f, "music"),
         }
     }
 }
@@ -35,6 +37,30 @@ pub struct MediaItem {
     pub file_size: i64,
     pub added_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Shared by files that are versions of the same movie
+    pub version_group: Option<String>,
+    /// When a scan first noticed the file was gone (`None` while it exists)
+    pub missing_since: Option<DateTime<Utc>>,
+    /// Hash of the file's size, head and tail; follows the file across renames
+    pub fingerprint: Option<String>,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub stream_info: MediaStreamInfo,
+}
+
+/// Technical details of a video file, as reported by ffprobe
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct MediaStreamInfo {
+    /// Duration in seconds
+    pub duration: Option<f64>,
+    /// Container format, e.g. `matroska` or `mp4`
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    /// Overall bitrate in bits per second
+    pub bit_rate: Option<i64>,
 }
 
 /// Create media item request
@@ -45,6 +71,9 @@ pub struct CreateMediaItem {
     pub title: String,
     pub file_path: String,
     pub file_size: i64,
+    pub version_group: Option<String>,
+    pub fingerprint: Option<String>,
+    pub stream_info: MediaStreamInfo,
 }
 
 impl MediaItem {
@@ -52,8 +81,11 @@ impl MediaItem {
     pub async fn create(db: &sqlx::SqlitePool, item: CreateMediaItem) -> Result<Self, sqlx::Error> {
         let result = sqlx::query_as::<_, Self>(
             r"
-            INSERT INTO media_items (library_folder_id, media_type, title, file_path, file_size)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO media_items (
+                library_folder_id, media_type, title, file_path, file_size, version_group,
+                fingerprint, duration, container, video_codec, audio_codec, width, height, bit_rate
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             RETURNING *
             ",
         )
@@ -62,12 +94,66 @@ impl MediaItem {
         .bind(item.title)
         .bind(item.file_path)
         .bind(item.file_size)
+        .bind(item.version_group)
+        .bind(item.fingerprint)
+        .bind(item.stream_info.duration)
+        .bind(item.stream_info.container)
+        .bind(item.stream_info.video_codec)
+        .bind(item.stream_info.audio_codec)
+        .bind(item.stream_info.width)
+        .bind(item.stream_info.height)
+        .bind(item.stream_info.bit_rate)
         .fetch_one(db)
         .await?;
 
         Ok(result)
     }
 
+    /// Insert media items in one transaction, skipping paths already in the library
+    ///
+    /// Returns the number of items actually added.
+    pub async fn create_many(
+        db: &sqlx::SqlitePool,
+        items: &[CreateMediaItem],
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = db.begin().await?;
+        let mut added = 0;
+
+        for item in items {
+            added += sqlx::query(
+                r"
+                INSERT INTO media_items (
+                    library_folder_id, media_type, title, file_path, file_size, version_group,
+                    fingerprint, duration, container, video_codec, audio_codec, width, height,
+                    bit_rate
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(file_path) DO NOTHING
+                ",
+            )
+            .bind(item.library_folder_id)
+            .bind(item.media_type)
+            .bind(&item.title)
+            .bind(&item.file_path)
+            .bind(item.file_size)
+            .bind(&item.version_group)
+            .bind(&item.fingerprint)
+            .bind(item.stream_info.duration)
+            .bind(&item.stream_info.container)
+            .bind(&item.stream_info.video_codec)
+            .bind(&item.stream_info.audio_codec)
+            .bind(item.stream_info.width)
+            .bind(item.stream_info.height)
+            .bind(item.stream_info.bit_rate)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(added)
+    }
+
     /// Find media item by ID
     pub async fn find_by_id(db: &sqlx::SqlitePool, id: i64) -> Result<Option<Self>, sqlx::Error> {
         let result = sqlx::query_as::<_, Self>(
@@ -129,6 +215,168 @@ impl MediaItem {
         Ok(results)
     }
 
+    /// List all media items of a library folder
+    pub async fn list_by_folder(
+        db: &sqlx::SqlitePool,
+        library_folder_id: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let results = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM media_items WHERE library_folder_id = ? ORDER BY added_at DESC
+            ",
+        )
+        .bind(library_folder_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(results)
+    }
+
+    /// List the media items of a library folder that have no metadata yet
+    ///
+    /// Tracks count as having metadata once their album does.
+    pub async fn list_without_metadata(
+        db: &sqlx::SqlitePool,
+        library_folder_id: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let results = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM media_items
+            WHERE library_folder_id = ?
+              AND id NOT IN (SELECT media_item_id FROM video_metadata)
+              AND id NOT IN (SELECT media_item_id FROM book_metadata)
+              AND id NOT IN (SELECT media_item_id FROM comic_metadata)
+              AND id NOT IN (
+                  SELECT t.media_item_id FROM music_metadata t
+                  JOIN album_metadata a ON a.album_key = t.album_key
+              )
+            ORDER BY added_at DESC
+            ",
+        )
+        .bind(library_folder_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(results)
+    }
+
+    /// Flag media items as missing from disk, or clear the flag when they are back
+    pub async fn set_missing(
+        db: &sqlx::SqlitePool,
+        ids: &[i64],
+        missing: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = db.begin().await?;
+
+        for id in ids {
+            sqlx::query(
+                r"
+                UPDATE media_items
+                SET missing_since = CASE WHEN ? THEN CURRENT_TIMESTAMP END,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE id = ?
+                ",
+            )
+            .bind(missing)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Point an item at the new location of its renamed or moved file
+    ///
+    /// The item keeps its ID, so its metadata stays attached.
+    pub async fn relink(
+        db: &sqlx::SqlitePool,
+        id: i64,
+        file_path: &str,
+        version_group: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r"
+            UPDATE media_items
+            SET file_path = ?, version_group = ?, missing_since = NULL,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            ",
+        )
+        .bind(file_path)
+        .bind(version_group)
+        .bind(id)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store fingerprints of items added before fingerprints were recorded
+    pub async fn set_fingerprints(
+        db: &sqlx::SqlitePool,
+        fingerprints: &[(i64, String)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = db.begin().await?;
+
+        for (id, fingerprint) in fingerprints {
+            sqlx::query(
+                r"
+                UPDATE media_items SET fingerprint = ? WHERE id = ?
+                ",
+            )
+            .bind(fingerprint)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Delete items of a library folder that have been missing since before `cutoff`
+    ///
+    /// Returns the number of items removed.
+    pub async fn purge_missing(
+        db: &sqlx::SqlitePool,
+        library_folder_id: i64,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r"
+            DELETE FROM media_items
+            WHERE library_folder_id = ?
+              AND missing_since IS NOT NULL
+              AND datetime(missing_since) < datetime(?)
+            ",
+        )
+        .bind(library_folder_id)
+        .bind(cutoff)
+        .execute(db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// List all versions of the same movie
+    pub async fn list_versions(
+        db: &sqlx::SqlitePool,
+        version_group: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let results = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM media_items WHERE version_group = ? ORDER BY file_size DESC
+            ",
+        )
+        .bind(version_group)
+        .fetch_all(db)
+        .await?;
+
+        Ok(results)
+    }
+
     /// Update media item
     pub async fn update(&self, db: &sqlx::SqlitePool) -> Result<(), sqlx::Error> {
         sqlx::query(
@@ -1,7 +1,17 @@
+mod album_metadata;
+mod book_metadata;
+mod comic_metadata;
 mod library_folder;
+mod match_override;
 mod media_item;
+mod music_metadata;
 mod video_metadata;
 
-pub use library_folder::{CreateLibraryFolder, LibraryFolder};
-pub use media_item::{CreateMediaItem, MediaItem, MediaType};
+pub use album_metadata::{AlbumMetadata, CreateAlbumMetadata};
+pub use book_metadata::{BookMetadata, CreateBookMetadata};
+pub use comic_metadata::{ComicMetadata, CreateComicMetadata};
+pub use library_folder::{CreateLibraryFolder, ExtensionOverrides, LibraryFolder};
+pub use match_override::{CreateMatchOverride, MatchOverride};
+pub use media_item::{CreateMediaItem, MediaItem, MediaStreamInfo, MediaType};
+pub use music_metadata::{CreateMusicMetadata, MusicMetadata};
 pub use video_metadata::{CreateVideoMetadata, MediaItemWithMetadata, VideoMetadata};
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Music metadata entity, read from the tags of an audio file
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MusicMetadata {
+    pub id: i64,
+    pub media_item_id: i64,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub track_number: Option<i64>,
+    pub disc_number: Option<i64>,
+    pub year: Option<i64>,
+    /// Album folder the track belongs to; shared by all tracks of an album
+    pub album_key: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create music metadata request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMusicMetadata {
+    pub media_item_id: i64,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub track_number: Option<i64>,
+    pub disc_number: Option<i64>,
+    pub year: Option<i64>,
+    pub album_key: String,
+}
+
+impl MusicMetadata {
+    /// Create or update the metadata of many tracks in a single transaction
+    pub async fn upsert_many(
+        db: &sqlx::SqlitePool,
+        metadata: &[CreateMusicMetadata],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = db.begin().await?;
+
+        for track in metadata {
+            sqlx::query(
+                r"
+                INSERT INTO music_metadata (
+                    media_item_id, title, artist, album_artist, album,
+                    track_number, disc_number, year, album_key
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(media_item_id) DO UPDATE SET
+                    title = excluded.title,
+                    artist = excluded.artist,
+                    album_artist = excluded.album_artist,
+                    album = excluded.album,
+                    track_number = excluded.track_number,
+                    disc_number = excluded.disc_number,
+                    year = excluded.year,
+                    album_key = excluded.album_key,
+                    updated_at = CURRENT_TIMESTAMP
+                ",
+            )
+            .bind(track.media_item_id)
+            .bind(&track.title)
+            .bind(&track.artist)
+            .bind(&track.album_artist)
+            .bind(&track.album)
+            .bind(track.track_number)
+            .bind(track.disc_number)
+            .bind(track.year)
+            .bind(&track.album_key)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Find metadata by media item ID
+    pub async fn find_by_media_item_id(
+        db: &sqlx::SqlitePool,
+        media_item_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM music_metadata WHERE media_item_id = ?
+            ",
+        )
+        .bind(media_item_id)
+        .fetch_optional(db)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Tracks of one album in disc and track order
+    pub async fn list_album(
+        db: &sqlx::SqlitePool,
+        album_key: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let result = sqlx::query_as::<_, Self>(
+            r"
+            SELECT * FROM music_metadata
+            WHERE album_key = ?
+            ORDER BY COALESCE(disc_number, 1), track_number IS NULL, track_number, title
+            ",
+        )
+        .bind(album_key)
+        .fetch_all(db)
+        .await?;
+
+        Ok(result)
+    }
+}
@@ -18,6 +18,10 @@ pub struct VideoMetadata {
     pub vote_average: Option<f64>,
     pub vote_count: Option<i32>,
     pub genres: Option<String>, // JSON array
+    /// Season an episode file was resolved to
+    pub season_number: Option<i32>,
+    /// Episode within that season
+    pub episode_number: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -37,6 +41,8 @@ pub struct CreateVideoMetadata {
     pub vote_average: Option<f64>,
     pub vote_count: Option<i32>,
     pub genres: Vec<String>,
+    pub season_number: Option<i32>,
+    pub episode_number: Option<i32>,
 }
 
 /// Media item with video metadata
@@ -61,9 +67,9 @@ impl VideoMetadata {
             INSERT INTO video_metadata (
                 media_item_id, tmdb_id, tvdb_id, imdb_id, overview,
                 poster_path, backdrop_path, release_date, runtime,
-                vote_average, vote_count, genres
+                vote_average, vote_count, genres, season_number, episode_number
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(media_item_id) DO UPDATE SET
                 tmdb_id = excluded.tmdb_id,
                 tvdb_id = excluded.tvdb_id,
@@ -76,6 +82,8 @@ impl VideoMetadata {
                 vote_average = excluded.vote_average,
                 vote_count = excluded.vote_count,
                 genres = excluded.genres,
+                season_number = excluded.season_number,
+                episode_number = excluded.episode_number,
                 updated_at = CURRENT_TIMESTAMP
             RETURNING *
             ",
@@ -92,6 +100,8 @@ impl VideoMetadata {
         .bind(metadata.vote_average)
         .bind(metadata.vote_count)
         .bind(genres_json)
+        .bind(metadata.season_number)
+        .bind(metadata.episode_number)
         .fetch_one(db)
         .await?;
 
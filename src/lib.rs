@@ -54,4 +54,13 @@ pub struct Context {
 
     /// Metadata agent for fetching and saving metadata
     pub metadata_agent: Option<Arc<services::MetadataAgent>>,
+
+    /// Background organize jobs
+    pub organize_jobs: Arc<services::OrganizeJobs>,
+
+    /// Targets being organized to, shared by every organize run
+    pub organize_claims: Arc<scraper::OrganizeClaims>,
+
+    /// Background library scans
+    pub scan_jobs: Arc<services::ScanJobs>,
 }
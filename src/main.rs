@@ -1,4 +1,4 @@
-use std::{env, path::PathBuf, sync::Arc};
+use std::{env, path::PathBuf, sync::Arc, time::Duration};
 
 use axum::{Router, http::HeaderName, middleware};
 use tokio::net::TcpListener;
@@ -9,16 +9,23 @@ use tower_http::{
     request_id::{MakeRequestUuid, SetRequestIdLayer},
     services::{ServeDir, ServeFile},
 };
-use tracing::info;
+use tracing::{info, warn};
 
 use ayiah::{
     Context,
     app::config::ConfigManager,
     db,
+    entities::MatchOverride,
     middleware::logger as middleware_logger,
     routes,
-    scraper::{ScraperManager, TmdbProvider},
-    services::MetadataAgent,
+    scraper::{
+        AniDbProvider, DoubanProvider, DownloadQueue, GoogleBooksProvider, IMDB_DATASET_FILES,
+        ImageCache, ImdbProvider, MangaDexProvider, MediaInfo, MusicBrainzProvider,
+        NetworkOptions, OmdbProvider, OpenLibraryProvider, ScraperCache, ScraperConfig,
+        ScraperManager, TitleArticles, TmdbCredential, TmdbProvider, TrailerDownloader,
+        TraktProvider, TvMazeProvider, TvdbProvider, WebhookProvider,
+    },
+    services::{MetadataAgent, OrganizeJobs, ScanJobs},
     utils::{graceful_shutdown::shutdown_signal, logger},
 };
 
@@ -37,35 +44,207 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let conn = db::init().await?;
 
+    // Open the offline IMDb database, importing the datasets on first start
+    let imdb_datasets_dir = config_manager.read().scraper.imdb_datasets_dir.clone();
+    let imdb_provider = match imdb_datasets_dir.map(PathBuf::from) {
+        Some(dir) => {
+            std::fs::create_dir_all(&dir)?;
+            let provider = ImdbProvider::open(dir.join("imdb.db")).await?;
+            if provider.imported_at().await?.is_none() {
+                let network = config_manager.read().scraper.network_for("imdb");
+                tokio::spawn(import_imdb_datasets(provider.clone(), dir, network));
+            }
+            Some(provider)
+        }
+        None => None,
+    };
+
+    // Open the scraper cache, kept on disk across restarts unless disabled
+    let (cache_path, cache_config) = {
+        let scraper = &config_manager.read().scraper;
+        (scraper.cache_path.clone(), scraper.cache_config())
+    };
+    let scraper_cache = if cache_path.is_empty() {
+        ScraperCache::with_config(cache_config)
+    } else {
+        match ScraperCache::open(&cache_path, cache_config.clone()).await {
+            Ok(cache) => cache,
+            Err(e) => {
+                warn!("Failed to open scraper cache {cache_path}, keeping it in memory: {e}");
+                ScraperCache::with_config(cache_config)
+            }
+        }
+    };
+
     // Initialize scraper manager and metadata agent
     let (scraper_manager, metadata_agent) = {
         let config = config_manager.read();
 
+        TitleArticles::set_active(TitleArticles::for_languages(
+            &config.scraper.title_article_languages,
+        ));
+
+        let mut scraper_manager = ScraperManager::with_config(ScraperConfig {
+            languages: config.scraper.metadata_languages.clone(),
+            match_strategy: config.scraper.match_strategy,
+            rate_limits: config.scraper.rate_limits.clone(),
+            provider_timeout: Some(Duration::from_secs(config.scraper.provider_timeout_seconds)),
+            provider_timeouts: config
+                .scraper
+                .provider_timeouts
+                .iter()
+                .map(|(id, &secs)| (id.clone(), Duration::from_secs(secs)))
+                .collect(),
+            priorities: config.scraper.priorities.clone(),
+            merge: config.scraper.merge.clone(),
+            max_concurrent_calls: config.scraper.max_concurrent_calls,
+            interactive_reserve: config.scraper.interactive_reserve,
+            background_rate: Some(config.scraper.background_rate),
+            ..ScraperConfig::default()
+        });
+        scraper_manager.set_cache(scraper_cache);
+        if !config.scraper.image_cache_dir.is_empty() {
+            scraper_manager.set_image_cache(
+                ImageCache::new(&config.scraper.image_cache_dir)
+                    .with_max_bytes(config.scraper.image_cache_max_mb * 1024 * 1024),
+            );
+        }
+        scraper_manager.set_download_queue(DownloadQueue::new(
+            config.scraper.download_concurrency,
+            config.scraper.download_per_host,
+        ));
+        if let Some(ytdlp_path) = &config.scraper.ytdlp_path {
+            let mut trailers = TrailerDownloader::new(ytdlp_path);
+            if config.scraper.trailer_max_size_mb > 0 {
+                trailers = trailers.with_max_size(config.scraper.trailer_max_size_mb * 1024 * 1024);
+            }
+            scraper_manager.set_trailer_downloader(trailers);
+        }
+
+        // Add TMDB provider
         if let Some(tmdb_api_key) = &config.scraper.tmdb_api_key {
-            let mut scraper_manager = ScraperManager::new();
+            let credential = TmdbCredential::detect(tmdb_api_key.clone());
+            let kind = credential.kind();
+            scraper_manager.add_provider(
+                TmdbProvider::with_credential(credential)?
+                    .with_languages(config.scraper.languages_for("tmdb"))
+                    .with_network(&config.scraper.network_for("tmdb"))?,
+            );
+            info!("Added TMDB provider ({kind})");
+        } else {
+            info!("No TMDB API key configured, falling back to keyless providers");
+        }
 
-            // Add TMDB provider
-            let tmdb_provider = TmdbProvider::new(tmdb_api_key.clone());
-            scraper_manager.add_provider(tmdb_provider);
+        // Add TVDB provider
+        if let Some(tvdb_api_key) = &config.scraper.tvdb_api_key {
+            scraper_manager.add_provider(
+                TvdbProvider::new(tvdb_api_key.clone())
+                    .with_languages(config.scraper.languages_for("tvdb"))
+                    .with_network(&config.scraper.network_for("tvdb"))?,
+            );
+            info!("Added TVDB provider");
+        }
 
-            let scraper_manager = Arc::new(scraper_manager);
-            let metadata_agent =
-                Arc::new(MetadataAgent::new(scraper_manager.clone(), conn.clone()));
+        // Add OMDb provider
+        if let Some(omdb_api_key) = &config.scraper.omdb_api_key {
+            scraper_manager.add_provider(
+                OmdbProvider::new(omdb_api_key.clone())
+                    .with_network(&config.scraper.network_for("omdb"))?,
+            );
+            info!("Added OMDb provider");
+        }
 
-            info!("Initialized scraper manager with TMDB provider");
-            (Some(scraper_manager), Some(metadata_agent))
-        } else {
-            info!("No TMDB API key configured, metadata fetching disabled");
-            (None, None)
+        // Add Trakt provider
+        if let Some(trakt_client_id) = &config.scraper.trakt_client_id {
+            scraper_manager.add_provider(
+                TraktProvider::new(trakt_client_id.clone())?
+                    .with_network(&config.scraper.network_for("trakt"))?,
+            );
+            info!("Added Trakt provider");
+        }
+
+        // Add AniDB provider
+        if let Some(anidb_client) = &config.scraper.anidb_client {
+            scraper_manager.add_provider(
+                AniDbProvider::new(anidb_client.clone(), config.scraper.anidb_client_version)
+                    .with_network(&config.scraper.network_for("anidb"))?,
+            );
+            info!("Added AniDB provider");
+        }
+
+        // Add Douban provider
+        if config.scraper.douban_enabled {
+            scraper_manager.add_provider(
+                DoubanProvider::new().with_network(&config.scraper.network_for("douban"))?,
+            );
+            info!("Added Douban provider");
+        }
+
+        // Add offline IMDb provider
+        if let Some(imdb_provider) = imdb_provider {
+            scraper_manager.add_provider(imdb_provider);
+            info!("Added offline IMDb provider");
         }
+
+        // Add TVmaze provider, which needs no API key
+        scraper_manager.add_provider(
+            TvMazeProvider::new().with_network(&config.scraper.network_for("tvmaze"))?,
+        );
+
+        // Add external webhook providers
+        for webhook in &config.scraper.webhooks {
+            let network = config.scraper.network_for(&webhook.id);
+            scraper_manager
+                .add_provider(WebhookProvider::new(webhook.clone())?.with_network(&network)?);
+            info!("Added webhook provider {} ({})", webhook.id, webhook.url);
+        }
+
+        let scraper_manager = Arc::new(scraper_manager);
+
+        // Add MusicBrainz for music libraries, Open Library then Google Books
+        // for ebooks, and MangaDex for comics; none of them needs an API key
+        let metadata_agent = MetadataAgent::new(scraper_manager.clone(), conn.clone())
+            .with_music_provider(
+                MusicBrainzProvider::new()
+                    .with_network(&config.scraper.network_for("musicbrainz"))?,
+            )
+            .with_open_library(
+                OpenLibraryProvider::new()
+                    .with_network(&config.scraper.network_for("openlibrary"))?,
+            )
+            .with_google_books(
+                GoogleBooksProvider::new(config.scraper.google_books_api_key.clone())
+                    .with_network(&config.scraper.network_for("googlebooks"))?,
+            )
+            .with_manga_provider(
+                MangaDexProvider::new().with_network(&config.scraper.network_for("mangadex"))?,
+            );
+        let metadata_agent = Arc::new(metadata_agent);
+
+        info!("Initialized scraper manager");
+        (Some(scraper_manager), Some(metadata_agent))
     };
 
+    // Restore manual identify corrections
+    if let Some(ref scraper_manager) = scraper_manager {
+        for o in MatchOverride::list_all(&conn).await? {
+            scraper_manager.set_override(
+                &o.parsed_title,
+                MediaInfo::new(o.provider_id, o.title, o.provider)
+                    .with_type(o.media_type.parse().unwrap_or_default()),
+            );
+        }
+    }
+
     // Create shared application state
     let ctx = Arc::new(Context {
         db: conn,
         config: config_manager.clone(),
         scraper_manager,
         metadata_agent,
+        organize_jobs: Arc::new(OrganizeJobs::new()),
+        organize_claims: Arc::default(),
+        scan_jobs: Arc::new(ScanJobs::new()),
     });
 
     // Create application router
@@ -99,3 +278,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Download the IMDb datasets missing from `dir`, then import them
+async fn import_imdb_datasets(provider: ImdbProvider, dir: PathBuf, network: NetworkOptions) {
+    if IMDB_DATASET_FILES.iter().any(|file| !dir.join(file).is_file()) {
+        info!("Downloading IMDb datasets to {}", dir.display());
+        if let Err(e) = ImdbProvider::download(&dir, &network).await {
+            warn!("Failed to download IMDb datasets: {e}");
+            return;
+        }
+    }
+
+    info!("Importing IMDb datasets from {}", dir.display());
+    match provider.import(&dir).await {
+        Ok(summary) => info!(
+            "Imported {} IMDb titles, {} episodes and {} alternative titles",
+            summary.titles, summary.episodes, summary.akas
+        ),
+        Err(e) => warn!("Failed to import IMDb datasets: {e}"),
+    }
+}
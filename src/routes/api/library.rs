@@ -5,10 +5,15 @@ use axum::{
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, hash_map::Entry};
+use std::path::{Path as FsPath, PathBuf};
 
 use crate::{
     ApiResponse, ApiResult, Ctx,
-    entities::{MediaItem, MediaItemWithMetadata, MediaType},
+    entities::{LibraryFolder, MediaItem, MediaItemWithMetadata, MediaType},
+    error::{ApiError, AyiahError, ScrapeError},
+    scraper::{EpisodeInfo, MetadataFormat, Parser, Scanner, Writer},
+    services::MetadataAgentError,
 };
 
 /// Library API response
@@ -31,6 +36,8 @@ pub struct LibraryQuery {
     pub order: Option<String>,
     /// Search query
     pub search: Option<String>,
+    /// Only items whose files are missing (`true`) or present (`false`)
+    pub missing: Option<bool>,
 }
 
 /// Identify request - match a media item with online metadata
@@ -65,6 +72,22 @@ pub struct BatchRefreshError {
     pub error: String,
 }
 
+/// Query parameters for exporting a series' metadata
+#[derive(Debug, Deserialize)]
+pub struct ExportNfoQuery {
+    /// "nfo", "json" or "both" (defaults to the library's format)
+    pub format: Option<MetadataFormat>,
+}
+
+/// NFO files written for a series or album
+#[derive(Debug, Serialize)]
+pub struct ExportNfoResponse {
+    /// Series folder holding `tvshow.nfo`, or album folder holding `album.nfo`
+    pub root: String,
+    pub format: MetadataFormat,
+    pub written: Vec<String>,
+}
+
 /// Get movies
 async fn get_movies(
     State(ctx): State<Ctx>,
@@ -244,7 +267,7 @@ async fn identify_item(
     })?;
 
     // Verify the media item exists
-    let _item = MediaItem::find_by_id(&ctx.db, id)
+    let item = MediaItem::find_by_id(&ctx.db, id)
         .await
         .map_err(|e| {
             (
@@ -268,16 +291,16 @@ async fn identify_item(
         })?;
 
     // Parse media type
-    let media_type = match req.media_type.to_lowercase().as_str() {
-        "movie" => crate::scraper::MediaType::Movie,
-        "tv" | "tvshow" | "series" => crate::scraper::MediaType::Tv,
-        "anime" => crate::scraper::MediaType::Anime,
-        _ => crate::scraper::MediaType::Unknown,
-    };
+    let media_type = req
+        .media_type
+        .parse::<crate::scraper::MediaType>()
+        .unwrap_or(crate::scraper::MediaType::Unknown);
 
-    // Create MediaInfo and fetch metadata
+    // Create MediaInfo and fetch fresh metadata, not what was cached before
+    // the correction
     let info =
         crate::scraper::MediaInfo::new(&req.provider_id, "", &req.provider).with_type(media_type);
+    scraper.invalidate_metadata(&req.provider, &req.provider_id).await;
 
     let metadata = scraper.get_metadata(&info).await.map_err(|e| {
         (
@@ -290,6 +313,10 @@ async fn identify_item(
         )
     })?;
 
+    // Place an episode file within the newly identified series
+    let parsed = crate::scraper::Parser::parse(std::path::Path::new(&item.file_path));
+    let episode = scraper.resolve_episode(&parsed, &metadata).await;
+
     // Save metadata to database
     let create_metadata = crate::entities::CreateVideoMetadata {
         media_item_id: id,
@@ -312,6 +339,8 @@ async fn identify_item(
         vote_average: metadata.rating,
         vote_count: metadata.vote_count,
         genres: metadata.genres.clone(),
+        season_number: episode.map(|e| e.season),
+        episode_number: episode.map(|e| e.episode),
     };
 
     crate::entities::VideoMetadata::upsert(&ctx.db, create_metadata)
@@ -327,6 +356,35 @@ async fn identify_item(
             )
         })?;
 
+    // Remember the correction so rescans and refreshes keep this match,
+    // keyed like scrapes of the file look it up
+    let create_override = crate::entities::CreateMatchOverride {
+        parsed_title: parsed.title.clone(),
+        provider: req.provider.clone(),
+        provider_id: req.provider_id.clone(),
+        media_type: media_type.to_string(),
+        title: metadata.title.clone(),
+    };
+
+    crate::entities::MatchOverride::upsert(&ctx.db, create_override)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    code: 500,
+                    message: format!("Failed to save match override: {e}"),
+                    data: None,
+                }),
+            )
+        })?;
+
+    scraper.set_override(
+        &parsed.title,
+        crate::scraper::MediaInfo::new(&req.provider_id, &metadata.title, &req.provider)
+            .with_type(media_type),
+    );
+
     Ok(Json(ApiResponse {
         code: 200,
         message: "Item identified and metadata saved".to_string(),
@@ -410,8 +468,182 @@ async fn search_identify_candidates(
     }))
 }
 
+/// Write `tvshow.nfo`, `season.nfo`s and episode NFOs for the series a TV
+/// item belongs to, or `album.nfo` and `artist.nfo` for the album of a track
+///
+/// The series is identified from the item's file; every episode file found
+/// in its folder that the provider knows gets an NFO. With the JSON format
+/// of the item's library (or `?format=json`), `.json` sidecars are written
+/// instead or as well.
+async fn export_series_nfo(
+    State(ctx): State<Ctx>,
+    Path(id): Path<i64>,
+    Query(params): Query<ExportNfoQuery>,
+) -> ApiResult<ExportNfoResponse> {
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        AyiahError::ApiError(ApiError::InternalServerError(
+            "Scraper not available".to_string(),
+        ))
+    })?;
+
+    let item = MediaItem::find_by_id(&ctx.db, id)
+        .await
+        .map_err(|e| AyiahError::DatabaseError(format!("Failed to fetch media item: {e}")))?
+        .ok_or_else(|| {
+            AyiahError::ApiError(ApiError::NotFound(format!("Media item {id} not found")))
+        })?;
+    if item.media_type == MediaType::Music {
+        return export_album_nfo(&ctx, &item, params.format).await;
+    }
+    if !matches!(item.media_type, MediaType::Tv) {
+        return Err(AyiahError::ApiError(ApiError::BadRequest(format!(
+            "Media item {id} is not a TV episode or a track"
+        ))));
+    }
+    let format = match params.format {
+        Some(format) => format,
+        None => LibraryFolder::find_by_id(&ctx.db, item.library_folder_id)
+            .await
+            .map_err(|e| AyiahError::DatabaseError(format!("Failed to fetch library: {e}")))?
+            .map(|folder| folder.metadata_format)
+            .unwrap_or_default(),
+    };
+
+    let file = PathBuf::from(&item.file_path);
+    let scraped = scraper
+        .scrape(&file)
+        .await
+        .map_err(|e| ScrapeError::MetadataFetchError(e.to_string()))?;
+    let metadata = match scraped.metadata {
+        Some(metadata) => metadata,
+        None => scraper
+            .get_metadata(&scraped.info)
+            .await
+            .map_err(|e| ScrapeError::MetadataFetchError(e.to_string()))?,
+    };
+
+    let root = series_root(&file);
+    let mut files = Scanner::scan(&root);
+    files.sort();
+
+    let mut seasons: HashMap<i32, Vec<EpisodeInfo>> = HashMap::new();
+    let mut episodes = Vec::new();
+    for video in files {
+        let parsed = Parser::parse(&video);
+        let Some(number) = parsed.episode else {
+            continue;
+        };
+        let season = parsed.season.unwrap_or(1);
+        let known = match seasons.entry(season) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(
+                scraper
+                    .get_season(&metadata.provider, &metadata.id, season)
+                    .await
+                    .unwrap_or_else(|e| {
+                        let title = &metadata.title;
+                        tracing::warn!("Failed to fetch season {season} of {title}: {e}");
+                        Vec::new()
+                    }),
+            ),
+        };
+        if let Some(episode) = known.iter().find(|e| e.episode == number) {
+            episodes.push((video, episode.clone()));
+        }
+    }
+
+    let write_failed = |e: anyhow::Error| {
+        AyiahError::ApiError(ApiError::InternalServerError(format!(
+            "Failed to write metadata files: {e}"
+        )))
+    };
+    let mut written = Vec::new();
+    if format.writes_nfo() {
+        let files = Writer::write_series_tree(&root, &metadata, &episodes).await;
+        written.extend(files.map_err(write_failed)?);
+    }
+    if format.writes_json() {
+        let files = Writer::write_series_json(&root, &metadata, &episodes).await;
+        written.extend(files.map_err(write_failed)?);
+    }
+
+    Ok(ApiResponse {
+        code: 200,
+        message: format!("Wrote {} metadata files", written.len()),
+        data: Some(ExportNfoResponse {
+            root: root.display().to_string(),
+            format,
+            written: written.iter().map(|p| p.display().to_string()).collect(),
+        }),
+    })
+}
+
+/// Write `album.nfo` and `artist.nfo` for the album a track belongs to
+async fn export_album_nfo(
+    ctx: &Ctx,
+    item: &MediaItem,
+    format: Option<MetadataFormat>,
+) -> ApiResult<ExportNfoResponse> {
+    if format.is_some_and(|f| !f.writes_nfo()) {
+        return Err(AyiahError::ApiError(ApiError::BadRequest(
+            "Albums are exported as NFOs only".to_string(),
+        )));
+    }
+    let agent = ctx.metadata_agent.as_ref().ok_or_else(|| {
+        AyiahError::ApiError(ApiError::InternalServerError(
+            "Metadata agent not available".to_string(),
+        ))
+    })?;
+
+    let written = agent.export_album_nfos(item).await.map_err(|e| match e {
+        MetadataAgentError::NoMatchingResults => {
+            AyiahError::ApiError(ApiError::NotFound(format!("No album found for {}", item.title)))
+        }
+        e => AyiahError::ApiError(ApiError::InternalServerError(format!(
+            "Failed to export album: {e}"
+        ))),
+    })?;
+
+    Ok(ApiResponse {
+        code: 200,
+        message: format!("Wrote {} metadata files", written.len()),
+        data: Some(ExportNfoResponse {
+            // `album.nfo` comes first
+            root: written
+                .first()
+                .and_then(|p| p.parent())
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+            format: MetadataFormat::Nfo,
+            written: written.iter().map(|p| p.display().to_string()).collect(),
+        }),
+    })
+}
+
 // ============ Helpers ============
 
+/// Folder of the series an episode file is in, above its season folder
+fn series_root(video: &FsPath) -> PathBuf {
+    let parent = video.parent().unwrap_or(video);
+    let in_season = parent
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| {
+            let name = name.to_lowercase();
+            name.starts_with("season")
+                || name == "specials"
+                || name
+                    .strip_prefix('s')
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        });
+
+    if in_season {
+        parent.parent().unwrap_or(parent).to_path_buf()
+    } else {
+        parent.to_path_buf()
+    }
+}
+
 fn apply_filters_and_sort(
     mut items: Vec<MediaItemWithMetadata>,
     params: &LibraryQuery,
@@ -422,6 +654,11 @@ fn apply_filters_and_sort(
         items.retain(|item| item.media_item.title.to_lowercase().contains(&search_lower));
     }
 
+    // Apply missing filter
+    if let Some(missing) = params.missing {
+        items.retain(|item| item.media_item.missing_since.is_some() == missing);
+    }
+
     // Apply sorting
     if let Some(ref sort) = params.sort {
         let desc = params.order.as_deref() == Some("desc");
@@ -491,6 +728,7 @@ pub fn mount() -> Router<Ctx> {
         .route("/library/items/{id}", get(get_media_item))
         .route("/library/items/{id}/refresh", post(refresh_metadata))
         .route("/library/items/{id}/identify", post(identify_item))
+        .route("/library/items/{id}/export-nfo", post(export_series_nfo))
         .route(
             "/library/items/{id}/candidates",
             get(search_identify_candidates),
@@ -2,22 +2,41 @@ use axum::{
     Json, Router,
     extract::{Path, State},
     http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
     routing::{get, post},
 };
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, sync::Arc, time::Duration};
 
 use crate::{
     ApiResponse, ApiResult, Ctx,
-    entities::{CreateLibraryFolder, LibraryFolder},
-    services::{FileScanner, ScanResult},
+    entities::{CreateLibraryFolder, ExtensionOverrides, LibraryFolder},
+    scraper::{IgnoreRules, MetadataFormat},
+    services::{FileScanner, JobStatus, ScanJob, ScanResult, ScanSnapshot},
 };
 
+/// How often scan progress is pushed to event stream clients
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+type ApiError = (StatusCode, Json<ApiResponse<String>>);
+
 /// Create library folder request
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateLibraryFolderRequest {
     pub name: String,
     pub path: String,
     pub media_type: crate::entities::MediaType,
+    /// Glob patterns to skip when scanning, e.g. `@eaDir` or `Seeding/*`
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    /// Changes to the scanned extensions, e.g. `{"include": ["strm"], "exclude": ["iso"]}`
+    #[serde(default)]
+    pub extensions: ExtensionOverrides,
+    /// Sidecars to export for the library's media: "nfo", "json" or "both"
+    #[serde(default)]
+    pub metadata_format: MetadataFormat,
 }
 
 /// Scan response
@@ -27,6 +46,38 @@ pub struct ScanResponse {
     pub result: ScanResult,
 }
 
+/// Scan job accepted response
+#[derive(Debug, Serialize)]
+pub struct ScanJobAccepted {
+    /// ID to poll, stream or cancel the scan with
+    pub job_id: String,
+}
+
+/// Scan job state
+#[derive(Debug, Serialize)]
+pub struct ScanJobResponse {
+    pub id: String,
+    pub status: JobStatus,
+    pub folder_ids: Vec<i64>,
+    pub progress: ScanSnapshot,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Error message when the job failed
+    pub error: Option<String>,
+    /// Results of scanned folders (omitted in job listings)
+    pub results: Option<Vec<ScanResponse>>,
+}
+
+/// File scanner configured from the library settings
+fn configured_scanner(ctx: &Ctx) -> FileScanner {
+    let config = ctx.config.read();
+    FileScanner::new(ctx.db.clone())
+        .with_purge_after_days(config.library.purge_missing_after_days)
+        .with_probe(config.library.media_probe())
+        .with_max_depth(config.library.max_scan_depth)
+        .with_throttle(config.library.scan_throttle.clone())
+}
+
 /// List all library folders
 async fn list_folders(State(ctx): State<Ctx>) -> ApiResult<Vec<LibraryFolder>> {
     let folders = LibraryFolder::list_all(&ctx.db).await.map_err(|e| {
@@ -82,10 +133,19 @@ async fn create_folder(
         ));
     }
 
+    if let Err(e) = IgnoreRules::validate(&request.exclude_patterns) {
+        return Err(crate::error::AyiahError::ApiError(
+            crate::error::ApiError::BadRequest(format!("Invalid exclude pattern: {e}")),
+        ));
+    }
+
     let create_folder = CreateLibraryFolder {
         name: request.name,
         path: request.path,
         media_type: request.media_type,
+        exclude_patterns: request.exclude_patterns,
+        extensions: request.extensions,
+        metadata_format: request.metadata_format,
     };
 
     let folder = LibraryFolder::create(&ctx.db, create_folder)
@@ -124,11 +184,11 @@ async fn delete_folder(
     }))
 }
 
-/// Scan a specific library folder
+/// Start scanning a specific library folder in the background
 async fn scan_folder(
     State(ctx): State<Ctx>,
     Path(id): Path<i64>,
-) -> Result<Json<ApiResponse<ScanResponse>>, (StatusCode, Json<ApiResponse<String>>)> {
+) -> Result<(StatusCode, Json<ApiResponse<ScanJobAccepted>>), ApiError> {
     let folder = LibraryFolder::find_by_id(&ctx.db, id)
         .await
         .map_err(|e| {
@@ -152,32 +212,22 @@ async fn scan_folder(
             )
         })?;
 
-    let scanner = FileScanner::new(ctx.db.clone());
-    let result = scanner.scan_library_folder(&folder).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                code: 500,
-                message: format!("Failed to scan library folder: {e}"),
-                data: None,
-            }),
-        )
-    })?;
+    let job = ctx.scan_jobs.start(configured_scanner(&ctx), vec![folder]);
 
-    // If metadata agent is available, fetch metadata for new items
+    // If metadata agent is available, fetch metadata for new items once the scan is done
     if let Some(metadata_agent) = &ctx.metadata_agent {
         tokio::spawn({
             let metadata_agent = metadata_agent.clone();
             let db = ctx.db.clone();
-            let folder_id = folder.id;
+            let job = job.clone();
             async move {
+                job.wait().await;
+                if job.status() != JobStatus::Completed {
+                    return;
+                }
+
                 // Get all media items without metadata from this folder
-                let items = match sqlx::query_as::<_, crate::entities::MediaItem>(
-                    "SELECT * FROM media_items WHERE library_folder_id = ? AND id NOT IN (SELECT media_item_id FROM video_metadata)"
-                )
-                .bind(folder_id)
-                .fetch_all(&db)
-                .await {
+                let items = match crate::entities::MediaItem::list_without_metadata(&db, id).await {
                     Ok(items) => items,
                     Err(e) => {
                         tracing::error!("Failed to fetch items without metadata: {}", e);
@@ -198,41 +248,149 @@ async fn scan_folder(
         });
     }
 
-    Ok(Json(ApiResponse {
-        code: 200,
-        message: "Library folder scanned successfully".to_string(),
-        data: Some(ScanResponse { folder, result }),
-    }))
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ApiResponse {
+            code: 202,
+            message: format!("Scan job {} started", job.id),
+            data: Some(ScanJobAccepted {
+                job_id: job.id.clone(),
+            }),
+        }),
+    ))
 }
 
-/// Scan all library folders
+/// Start scanning all enabled library folders in the background
 async fn scan_all_folders(
     State(ctx): State<Ctx>,
-) -> Result<Json<ApiResponse<Vec<ScanResponse>>>, (StatusCode, Json<ApiResponse<String>>)> {
-    let scanner = FileScanner::new(ctx.db.clone());
-    let results = scanner.scan_all_libraries().await.map_err(|e| {
+) -> Result<(StatusCode, Json<ApiResponse<ScanJobAccepted>>), ApiError> {
+    let folders = LibraryFolder::list_enabled(&ctx.db).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ApiResponse {
                 code: 500,
-                message: format!("Failed to scan libraries: {e}"),
+                message: format!("Failed to fetch library folders: {e}"),
                 data: None,
             }),
         )
     })?;
 
-    let response: Vec<ScanResponse> = results
-        .into_iter()
-        .map(|(folder, result)| ScanResponse { folder, result })
+    let job = ctx.scan_jobs.start(configured_scanner(&ctx), folders);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ApiResponse {
+            code: 202,
+            message: format!("Scan job {} started", job.id),
+            data: Some(ScanJobAccepted {
+                job_id: job.id.clone(),
+            }),
+        }),
+    ))
+}
+
+fn job_response(job: &ScanJob, with_results: bool) -> ScanJobResponse {
+    ScanJobResponse {
+        id: job.id.clone(),
+        status: job.status(),
+        folder_ids: job.folder_ids.clone(),
+        progress: job.progress(),
+        created_at: job.created_at,
+        finished_at: job.finished_at(),
+        error: job.error(),
+        results: with_results.then(|| {
+            job.results()
+                .into_iter()
+                .map(|(folder, result)| ScanResponse { folder, result })
+                .collect()
+        }),
+    }
+}
+
+fn job_not_found(id: &str) -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ApiResponse {
+            code: 404,
+            message: format!("Scan job not found: {id}"),
+            data: None,
+        }),
+    )
+}
+
+/// List scan jobs
+async fn list_scan_jobs(State(ctx): State<Ctx>) -> Json<ApiResponse<Vec<ScanJobResponse>>> {
+    let jobs = ctx
+        .scan_jobs
+        .list()
+        .iter()
+        .map(|job| job_response(job, false))
         .collect();
 
+    Json(ApiResponse {
+        code: 200,
+        message: "Success".to_string(),
+        data: Some(jobs),
+    })
+}
+
+/// Get scan job progress and the results of folders scanned so far
+async fn get_scan_job(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<ScanJobResponse>>, ApiError> {
+    let job = ctx.scan_jobs.get(&id).ok_or_else(|| job_not_found(&id))?;
+
     Ok(Json(ApiResponse {
         code: 200,
-        message: "All libraries scanned successfully".to_string(),
-        data: Some(response),
+        message: "Success".to_string(),
+        data: Some(job_response(&job, true)),
     }))
 }
 
+/// Cancel a scan job
+async fn cancel_scan_job(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    if !ctx.scan_jobs.cancel(&id) {
+        return Err(job_not_found(&id));
+    }
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: format!("Scan job {id} cancelling"),
+        data: None,
+    }))
+}
+
+/// Stream scan job progress as server-sent events
+async fn scan_job_events(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let job = ctx.scan_jobs.get(&id).ok_or_else(|| job_not_found(&id))?;
+
+    // Emit progress until the job finishes, then one final event with the results
+    let events = stream::unfold(Some(job), |job: Option<Arc<ScanJob>>| async move {
+        let job = job?;
+        let finished = job.is_finished();
+        if !finished {
+            tokio::time::sleep(PROGRESS_INTERVAL).await;
+        }
+
+        let name = if finished { "finished" } else { "progress" };
+        let event = Event::default()
+            .event(name)
+            .json_data(job_response(&job, finished))
+            .unwrap_or_default();
+
+        Some((Ok(event), (!finished).then_some(job)))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
 /// Mount library folder routes
 pub fn mount() -> Router<Ctx> {
     Router::new()
@@ -243,4 +401,14 @@ pub fn mount() -> Router<Ctx> {
         )
         .route("/library-folders/{id}/scan", post(scan_folder))
         .route("/library-folders/scan-all", post(scan_all_folders))
+        .route("/library-folders/scan-jobs", get(list_scan_jobs))
+        .route("/library-folders/scan-jobs/{id}", get(get_scan_job))
+        .route(
+            "/library-folders/scan-jobs/{id}/cancel",
+            post(cancel_scan_job),
+        )
+        .route(
+            "/library-folders/scan-jobs/{id}/events",
+            get(scan_job_events),
+        )
 }
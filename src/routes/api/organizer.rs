@@ -1,20 +1,41 @@
-use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{convert::Infallible, path::PathBuf, sync::Arc, time::Duration};
 
 use crate::{
     ApiResponse, Ctx,
-    scraper::{NamingTemplate, OrganizeMethod, Organizer, OrganizerConfig},
+    scraper::{
+        AnimeLayout, ArtworkOptions, ArtworkResult, BatchOrganizeResult, ConflictPolicy, ExtrasLayout,
+        MediaInfo, MetadataFormat, NamingTemplate, NfoProfile, OrganizeMethod, OrganizeResult,
+        Organizer, OrganizerConfig, ProgressSnapshot, ScraperError, TargetOs, journal_path_for,
+    },
+    services::{JobStatus, OrganizeJob},
 };
 
+/// How often job progress is pushed to event stream clients
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+type ApiError = (StatusCode, Json<ApiResponse<()>>);
+
 /// Organize request
 #[derive(Debug, Deserialize)]
 pub struct OrganizeRequest {
     /// Source directory containing media files
+    #[serde(default)]
     pub source: String,
-    /// Target directory for organized files
-    pub target: String,
-    /// Organization method: symlink, hardlink, move, copy
+    /// Target directory for organized files; only renaming in place does
+    /// without one
+    #[serde(default)]
+    pub target: Option<PathBuf>,
+    /// Organization method: symlink, hardlink, move, copy, rename
     #[serde(default)]
     pub method: String,
     /// Whether to separate by media type (Movies/TV/Anime)
@@ -23,21 +44,99 @@ pub struct OrganizeRequest {
     /// Dry run mode (preview without making changes)
     #[serde(default)]
     pub dry_run: bool,
-    /// Overwrite existing files
-    #[serde(default)]
-    pub overwrite: bool,
+    /// Conflict policy for existing targets: skip, overwrite, rename_with_suffix,
+    /// keep_larger, keep_newer, prefer_proper. The former `overwrite` flag is
+    /// still accepted: `true` overwrites, `false` skips
+    #[serde(default, alias = "overwrite", deserialize_with = "conflict_or_overwrite")]
+    pub conflict: ConflictPolicy,
     /// Custom naming templates (optional)
     pub templates: Option<TemplateConfig>,
+    /// Sibling file extensions to organize with the media (optional)
+    pub companion_extensions: Option<Vec<String>>,
+    /// Minimum video file size in bytes (optional, 0 disables)
+    pub min_file_size: Option<u64>,
+    /// Remove source folders emptied by a move
+    #[serde(default)]
+    pub cleanup_source: bool,
+    /// Keep existing copies and add this one as another version
+    #[serde(default)]
+    pub keep_versions: bool,
+    /// Number of files organized concurrently (optional)
+    pub concurrency: Option<usize>,
+    /// Verify checksums after copying or moving across filesystems
+    #[serde(default)]
+    pub verify_checksum: bool,
+    /// Place extras next to their movie: "jellyfin" or "plex" (optional)
+    pub extras_layout: Option<ExtrasLayout>,
+    /// In rename mode, also rename movie folders
+    #[serde(default)]
+    pub rename_folder: bool,
+    /// Write movie.nfo/tvshow.nfo for identified media
+    #[serde(default)]
+    pub write_nfo: bool,
+    /// Download poster and fanart for identified media
+    #[serde(default)]
+    pub download_artwork: bool,
+    /// Which artwork to download and whether to replace existing files (optional)
+    pub artwork: Option<ArtworkOptions>,
+    /// Sidecars written with `write_nfo`: "nfo", "json" or "both" (optional)
+    pub metadata_format: Option<MetadataFormat>,
+    /// Target media center and extra NFO fields, e.g. Jellyfin's lockdata (optional)
+    pub nfo: Option<NfoProfile>,
+    /// Folder receiving `collection.nfo` and artwork of movie collections (optional)
+    pub movie_sets_dir: Option<String>,
+    /// Anime folder layout: "seasons" or "absolute" (optional)
+    pub anime_layout: Option<AnimeLayout>,
+    /// Name and path length rules of the library filesystem: "unix" or "windows" (optional)
+    pub target_os: Option<TargetOs>,
 }
 
 const fn default_true() -> bool {
     true
 }
 
+/// A conflict policy, or the boolean of the `overwrite` flag it replaced
+fn conflict_or_overwrite<'de, D>(deserializer: D) -> Result<ConflictPolicy, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PolicyOrFlag {
+        Policy(ConflictPolicy),
+        Flag(bool),
+    }
+
+    Ok(match PolicyOrFlag::deserialize(deserializer)? {
+        PolicyOrFlag::Policy(policy) => policy,
+        PolicyOrFlag::Flag(true) => ConflictPolicy::Overwrite,
+        PolicyOrFlag::Flag(false) => ConflictPolicy::Skip,
+    })
+}
+
+/// Organize a single file request
+#[derive(Debug, Deserialize)]
+pub struct OrganizeFileRequest {
+    /// Media file to organize
+    pub file: String,
+    /// Provider to identify the file with (tmdb, anilist, bangumi)
+    pub provider: Option<String>,
+    /// Media ID from the provider
+    pub provider_id: Option<String>,
+    /// Media type: movie, tv, anime
+    #[serde(rename = "type")]
+    pub media_type: Option<String>,
+    /// Organize options; `source` defaults to the file's folder
+    #[serde(flatten)]
+    pub options: OrganizeRequest,
+}
+
 /// Custom naming templates
 #[derive(Debug, Deserialize)]
 pub struct TemplateConfig {
-    /// Movie folder template, e.g., "{title} ({year})"
+    /// Library root layout, e.g., "{media_type}/{genre}" (replaces separate_by_type)
+    pub library_root: Option<String>,
+    /// Movie folder template, e.g., "{title}{ ({year})}"
     pub movie_folder: Option<String>,
     /// Movie file template
     pub movie_file: Option<String>,
@@ -45,13 +144,17 @@ pub struct TemplateConfig {
     pub tv_folder: Option<String>,
     /// Season folder template, e.g., "Season {season:02}"
     pub season_folder: Option<String>,
-    /// Episode file template, e.g., "{title} - S{season:02}E{episode:02}"
+    /// Episode file template, e.g., "{title} - S{season:02}{episode_range}"
     pub episode_file: Option<String>,
+    /// Anime file template for the absolute layout, e.g., "{title} - {absolute:03}"
+    pub anime_file: Option<String>,
 }
 
 /// Organize response
 #[derive(Debug, Serialize)]
 pub struct OrganizeResponse {
+    /// Batch ID to pass to rollback (absent for dry runs)
+    pub batch_id: Option<String>,
     /// Total files processed
     pub total: usize,
     /// Successfully organized
@@ -75,6 +178,18 @@ pub struct OrganizedFile {
     pub media_type: String,
     pub season: Option<i32>,
     pub episode: Option<i32>,
+    /// Companion files (subtitles, NFO, artwork) organized alongside
+    pub companions: Vec<String>,
+    /// Version label when added next to an existing copy
+    pub version: Option<String>,
+    /// Whether the target checksum matched the source (absent when not verified)
+    pub verified: Option<bool>,
+    /// Conflict policy applied because the target already existed
+    pub conflict: Option<ConflictPolicy>,
+    /// NFO and artwork files written alongside
+    pub artifacts: Vec<String>,
+    /// Outcome of each artwork file, failures included
+    pub artwork: Vec<ArtworkResult>,
 }
 
 /// Organize error
@@ -84,13 +199,52 @@ pub struct OrganizeError {
     pub error: String,
 }
 
+/// Organize job accepted response
+#[derive(Debug, Serialize)]
+pub struct OrganizeJobAccepted {
+    /// ID to poll, stream or cancel the job with
+    pub job_id: String,
+}
+
+/// Organize job state
+#[derive(Debug, Serialize)]
+pub struct OrganizeJobResponse {
+    pub id: String,
+    pub status: JobStatus,
+    pub dry_run: bool,
+    pub progress: ProgressSnapshot,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Error message when the job failed
+    pub error: Option<String>,
+    /// Full result once finished (omitted in job listings)
+    pub result: Option<OrganizeResponse>,
+}
+
+/// Rollback request
+#[derive(Debug, Deserialize)]
+pub struct RollbackRequest {
+    /// Batch ID returned by organize
+    pub batch_id: String,
+}
+
+/// Rollback response
+#[derive(Debug, Serialize)]
+pub struct RollbackResponse {
+    /// Number of reverted operations
+    pub reverted: usize,
+    /// Operations that could not be reverted
+    pub errors: Vec<OrganizeError>,
+}
+
 /// Preview organize request (same as organize but always dry run)
 #[derive(Debug, Deserialize)]
 pub struct PreviewRequest {
     /// Source directory containing media files
     pub source: String,
-    /// Target directory for organized files
-    pub target: String,
+    /// Target directory for organized files (not needed to rename in place)
+    #[serde(default)]
+    pub target: Option<PathBuf>,
     /// Organization method: symlink, hardlink, move, copy
     #[serde(default)]
     pub method: String,
@@ -99,20 +253,25 @@ pub struct PreviewRequest {
     pub separate_by_type: bool,
     /// Custom naming templates
     pub templates: Option<TemplateConfig>,
+    /// Sibling file extensions to organize with the media
+    pub companion_extensions: Option<Vec<String>>,
+    /// Minimum video file size in bytes
+    pub min_file_size: Option<u64>,
+    /// Anime folder layout: "seasons" or "absolute" (optional)
+    pub anime_layout: Option<AnimeLayout>,
+    /// Name and path length rules of the library filesystem: "unix" or "windows" (optional)
+    pub target_os: Option<TargetOs>,
 }
 
-/// Organize media files
-/// POST /api/organizer/organize
-async fn organize(
-    State(_ctx): State<Ctx>,
-    Json(req): Json<OrganizeRequest>,
-) -> Result<Json<ApiResponse<OrganizeResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+/// Build organizer configuration from a request
+fn build_config(req: OrganizeRequest) -> Result<OrganizerConfig, ApiError> {
     // Parse method
     let method = req.method.parse::<OrganizeMethod>().unwrap_or_default();
 
     // Build naming template
     let mut template = NamingTemplate::default();
     if let Some(ref t) = req.templates {
+        template.library_root.clone_from(&t.library_root);
         if let Some(ref s) = t.movie_folder {
             template.movie_folder = s.clone();
         }
@@ -128,18 +287,74 @@ async fn organize(
         if let Some(ref s) = t.episode_file {
             template.episode_file = s.clone();
         }
+        if let Some(ref s) = t.anime_file {
+            template.anime_file = s.clone();
+        }
     }
 
+    // Renaming in place leaves files in their folders; every other method
+    // needs somewhere to put them
+    let target_dir = match req.target {
+        Some(target) => target,
+        None if method == OrganizeMethod::Rename => PathBuf::new(),
+        None => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    code: 400,
+                    message: format!("A target directory is required to {method} files"),
+                    data: None,
+                }),
+            ));
+        }
+    };
+
     // Build config
-    let config = OrganizerConfig {
+    let mut config = OrganizerConfig {
         source_dir: PathBuf::from(&req.source),
-        target_dir: PathBuf::from(&req.target),
+        target_dir,
         method,
         template,
         separate_by_type: req.separate_by_type,
         dry_run: req.dry_run,
-        overwrite: req.overwrite,
+        conflict: req.conflict,
+        journal_path: Some(journal_path_for(&crate::db::path())),
+        cleanup_source: req.cleanup_source,
+        keep_versions: req.keep_versions,
+        verify_checksum: req.verify_checksum,
+        rename_folder: req.rename_folder,
+        write_nfo: req.write_nfo,
+        download_artwork: req.download_artwork,
+        movie_sets_dir: req.movie_sets_dir.map(PathBuf::from),
+        ..Default::default()
     };
+    if let Some(extensions) = req.companion_extensions {
+        config.companion_extensions = extensions;
+    }
+    if let Some(min_file_size) = req.min_file_size {
+        config.min_file_size = min_file_size;
+    }
+    if let Some(concurrency) = req.concurrency {
+        config.concurrency = concurrency;
+    }
+    if let Some(artwork) = req.artwork {
+        config.artwork = artwork;
+    }
+    if let Some(format) = req.metadata_format {
+        config.metadata_format = format;
+    }
+    if let Some(nfo) = req.nfo {
+        config.nfo = nfo;
+    }
+    if let Some(layout) = req.extras_layout {
+        config.extras_layout = layout;
+    }
+    if let Some(layout) = req.anime_layout {
+        config.anime_layout = layout;
+    }
+    if let Some(os) = req.target_os {
+        config.target_os = os;
+    }
 
     // Validate paths
     if !config.source_dir.exists() {
@@ -153,40 +368,47 @@ async fn organize(
         ));
     }
 
-    // Create organizer
-    let organizer = Organizer::new(config);
+    Ok(config)
+}
 
-    // Run organize
-    let result = organizer.organize_all().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                code: 500,
-                message: format!("Organize failed: {e}"),
-                data: None,
-            }),
-        )
-    })?;
+fn organized_file(r: &OrganizeResult) -> OrganizedFile {
+    OrganizedFile {
+        source: r.source.display().to_string(),
+        target: r.target.display().to_string(),
+        title: r
+            .metadata
+            .as_ref()
+            .map_or_else(|| r.parsed.title.clone(), |m| m.title.clone()),
+        media_type: r.metadata.as_ref().map_or_else(
+            || format!("{:?}", r.parsed.hint),
+            |m| m.media_type.to_string(),
+        ),
+        season: r.parsed.season,
+        episode: r.parsed.episode,
+        companions: r
+            .companions
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect(),
+        version: r.version.clone(),
+        verified: r.verified,
+        conflict: r.conflict,
+        artifacts: r
+            .artifacts
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect(),
+        artwork: r.artwork.clone(),
+    }
+}
 
-    // Build response
+/// Build the API response for a finished batch
+fn build_response(result: &BatchOrganizeResult, dry_run: bool) -> OrganizeResponse {
     let mut results = Vec::new();
     let mut errors = Vec::new();
 
     for r in &result.success {
-        results.push(OrganizedFile {
-            source: r.source.display().to_string(),
-            target: r.target.display().to_string(),
-            title: r
-                .metadata
-                .as_ref()
-                .map_or_else(|| r.parsed.title.clone(), |m| m.title.clone()),
-            media_type: r.metadata.as_ref().map_or_else(
-                || format!("{:?}", r.parsed.hint),
-                |m| m.media_type.to_string(),
-            ),
-            season: r.parsed.season,
-            episode: r.parsed.episode,
-        });
+        results.push(organized_file(r));
     }
 
     for r in &result.failed {
@@ -206,30 +428,295 @@ async fn organize(
         });
     }
 
-    let response = OrganizeResponse {
+    OrganizeResponse {
+        batch_id: (!dry_run).then(|| result.batch_id.clone()),
         total: result.total(),
         success: result.success_count(),
         failed: result.failed_count(),
         skipped: result.skipped.len(),
         results,
         errors,
+    }
+}
+
+fn job_response(job: &OrganizeJob, with_result: bool) -> OrganizeJobResponse {
+    OrganizeJobResponse {
+        id: job.id.clone(),
+        status: job.status(),
+        dry_run: job.dry_run,
+        progress: job.progress(),
+        created_at: job.created_at,
+        finished_at: job.finished_at(),
+        error: job.error(),
+        result: with_result
+            .then(|| job.result())
+            .flatten()
+            .map(|r| build_response(&r, job.dry_run)),
+    }
+}
+
+fn job_not_found(id: &str) -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ApiResponse {
+            code: 404,
+            message: format!("Organize job not found: {id}"),
+            data: None,
+        }),
+    )
+}
+
+/// Start organizing media files in the background
+/// POST /api/organizer/organize
+async fn organize(
+    State(ctx): State<Ctx>,
+    Json(req): Json<OrganizeRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<OrganizeJobAccepted>>), ApiError> {
+    let dry_run = req.dry_run;
+    let config = build_config(req)?;
+
+    let mut organizer = Organizer::new(config).with_claims(ctx.organize_claims.clone());
+    if let Some(ref scraper) = ctx.scraper_manager {
+        organizer = organizer.with_scraper(scraper.clone());
+    }
+
+    let job = ctx.organize_jobs.start(organizer, dry_run);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ApiResponse {
+            code: 202,
+            message: format!("Organize job {} started", job.id),
+            data: Some(OrganizeJobAccepted {
+                job_id: job.id.clone(),
+            }),
+        }),
+    ))
+}
+
+/// Organize one file, optionally as an explicit provider entry
+/// POST /api/organizer/organize-file
+async fn organize_file(
+    State(ctx): State<Ctx>,
+    Json(req): Json<OrganizeFileRequest>,
+) -> Result<Json<ApiResponse<OrganizedFile>>, ApiError> {
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                code: 400,
+                message,
+                data: None,
+            }),
+        )
     };
 
-    let message = if req.dry_run {
-        format!(
-            "[DRY RUN] Would organize {} files ({} success, {} failed)",
-            response.total, response.success, response.failed
+    let file = PathBuf::from(&req.file);
+    if !file.is_file() {
+        return Err(bad_request(format!("File does not exist: {}", req.file)));
+    }
+
+    let identity = match (req.provider, req.provider_id) {
+        (Some(provider), Some(id)) => Some(
+            MediaInfo::new(id, "", provider).with_type(
+                req.media_type
+                    .as_deref()
+                    .and_then(|t| t.parse().ok())
+                    .unwrap_or_default(),
+            ),
+        ),
+        (None, None) => None,
+        _ => {
+            return Err(bad_request(
+                "provider and provider_id must be given together".to_string(),
+            ));
+        }
+    };
+
+    let mut options = req.options;
+    if options.source.is_empty() {
+        options.source = file
+            .parent()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+    }
+    let config = build_config(options)?;
+
+    let mut organizer = Organizer::new(config).with_claims(ctx.organize_claims.clone());
+    if let Some(ref scraper) = ctx.scraper_manager {
+        organizer = organizer.with_scraper(scraper.clone());
+    }
+
+    let result = match identity {
+        Some(ref info) => organizer.organize_file_as(&file, info).await,
+        None => organizer.organize_file(&file).await,
+    }
+    .map_err(|e| {
+        let status = match e {
+            ScraperError::NotFound(_) | ScraperError::Api { status: 404, .. } => {
+                StatusCode::NOT_FOUND
+            }
+            ScraperError::Config(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (
+            status,
+            Json(ApiResponse {
+                code: status.as_u16(),
+                message: format!("Organize failed: {e}"),
+                data: None,
+            }),
         )
-    } else {
-        format!(
-            "Organized {} files ({} success, {} failed)",
-            response.total, response.success, response.failed
+    })?;
+
+    if !result.success {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ApiResponse {
+                code: 409,
+                message: result
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string()),
+                data: None,
+            }),
+        ));
+    }
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: format!("Organized {}", result.target.display()),
+        data: Some(organized_file(&result)),
+    }))
+}
+
+/// List organize jobs
+/// GET /api/organizer/jobs
+async fn list_jobs(State(ctx): State<Ctx>) -> Json<ApiResponse<Vec<OrganizeJobResponse>>> {
+    let jobs = ctx
+        .organize_jobs
+        .list()
+        .iter()
+        .map(|job| job_response(job, false))
+        .collect();
+
+    Json(ApiResponse {
+        code: 200,
+        message: "Success".to_string(),
+        data: Some(jobs),
+    })
+}
+
+/// Get organize job progress and, once finished, its result
+/// GET /api/organizer/jobs/{id}
+async fn get_job(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<OrganizeJobResponse>>, ApiError> {
+    let job = ctx.organize_jobs.get(&id).ok_or_else(|| job_not_found(&id))?;
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: "Success".to_string(),
+        data: Some(job_response(&job, true)),
+    }))
+}
+
+/// Cancel an organize job
+/// POST /api/organizer/jobs/{id}/cancel
+async fn cancel_job(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    if !ctx.organize_jobs.cancel(&id) {
+        return Err(job_not_found(&id));
+    }
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: format!("Organize job {id} cancelling"),
+        data: None,
+    }))
+}
+
+/// Stream organize job progress as server-sent events
+/// GET /api/organizer/jobs/{id}/events
+async fn job_events(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let job = ctx.organize_jobs.get(&id).ok_or_else(|| job_not_found(&id))?;
+
+    // Emit progress until the job finishes, then one final event with the result
+    let events = stream::unfold(Some(job), |job: Option<Arc<OrganizeJob>>| async move {
+        let job = job?;
+        let finished = job.is_finished();
+        if !finished {
+            tokio::time::sleep(PROGRESS_INTERVAL).await;
+        }
+
+        let name = if finished { "finished" } else { "progress" };
+        let event = Event::default()
+            .event(name)
+            .json_data(job_response(&job, finished))
+            .unwrap_or_default();
+
+        Some((Ok(event), (!finished).then_some(job)))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Revert an organize run
+/// POST /api/organizer/rollback
+async fn rollback(
+    State(_ctx): State<Ctx>,
+    Json(req): Json<RollbackRequest>,
+) -> Result<Json<ApiResponse<RollbackResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let organizer = Organizer::new(OrganizerConfig {
+        journal_path: Some(journal_path_for(&crate::db::path())),
+        ..Default::default()
+    });
+
+    // Moving files back (and copying them across filesystems) would stall
+    // the runtime
+    let batch_id = req.batch_id;
+    let result = tokio::task::spawn_blocking(move || organizer.rollback(&batch_id))
+        .await
+        .unwrap_or_else(|e| Err(ScraperError::Io(e.into())));
+    let result = result.map_err(|e| {
+        let status = match e {
+            ScraperError::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (
+            status,
+            Json(ApiResponse {
+                code: status.as_u16(),
+                message: format!("Rollback failed: {e}"),
+                data: None,
+            }),
         )
+    })?;
+
+    let response = RollbackResponse {
+        reverted: result.reverted.len(),
+        errors: result
+            .failed
+            .into_iter()
+            .map(|(path, error)| OrganizeError {
+                source: path.display().to_string(),
+                error,
+            })
+            .collect(),
     };
 
     Ok(Json(ApiResponse {
         code: 200,
-        message,
+        message: format!(
+            "Rolled back {} operations ({} failed)",
+            response.reverted,
+            response.errors.len()
+        ),
         data: Some(response),
     }))
 }
@@ -239,7 +726,7 @@ async fn organize(
 async fn preview(
     State(ctx): State<Ctx>,
     Json(req): Json<PreviewRequest>,
-) -> Result<Json<ApiResponse<OrganizeResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+) -> Result<Json<ApiResponse<OrganizeResponse>>, ApiError> {
     // Convert to organize request with dry_run = true
     let organize_req = OrganizeRequest {
         source: req.source,
@@ -247,16 +734,64 @@ async fn preview(
         method: req.method,
         separate_by_type: req.separate_by_type,
         dry_run: true,
-        overwrite: false,
+        conflict: ConflictPolicy::Skip,
         templates: req.templates,
+        companion_extensions: req.companion_extensions,
+        min_file_size: req.min_file_size,
+        cleanup_source: false,
+        keep_versions: false,
+        concurrency: None,
+        verify_checksum: false,
+        extras_layout: None,
+        rename_folder: false,
+        write_nfo: false,
+        download_artwork: false,
+        artwork: None,
+        metadata_format: None,
+        nfo: None,
+        movie_sets_dir: None,
+        anime_layout: req.anime_layout,
+        target_os: req.target_os,
     };
 
-    organize(State(ctx), Json(organize_req)).await
+    let config = build_config(organize_req)?;
+    let mut organizer = Organizer::new(config).with_claims(ctx.organize_claims.clone());
+    if let Some(ref scraper) = ctx.scraper_manager {
+        organizer = organizer.with_scraper(scraper.clone());
+    }
+
+    let result = organizer.organize_all().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                code: 500,
+                message: format!("Preview failed: {e}"),
+                data: None,
+            }),
+        )
+    })?;
+
+    let response = build_response(&result, true);
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: format!(
+            "[DRY RUN] Would organize {} files ({} success, {} failed)",
+            response.total, response.success, response.failed
+        ),
+        data: Some(response),
+    }))
 }
 
 /// Mount organizer routes
 pub fn mount() -> Router<Ctx> {
     Router::new()
         .route("/organizer/organize", post(organize))
+        .route("/organizer/organize-file", post(organize_file))
         .route("/organizer/preview", post(preview))
+        .route("/organizer/rollback", post(rollback))
+        .route("/organizer/jobs", get(list_jobs))
+        .route("/organizer/jobs/{id}", get(get_job))
+        .route("/organizer/jobs/{id}/cancel", post(cancel_job))
+        .route("/organizer/jobs/{id}/events", get(job_events))
 }
@@ -2,13 +2,21 @@ use axum::{
     Json, Router,
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, post},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{delete, get, post, put},
 };
+use futures::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::{
     ApiResponse, Ctx,
-    scraper::{MediaInfo, MediaMetadata, MediaType, ScoredMatch},
+    scraper::{
+        DiscoverList, DiscoverOptions, EpisodeInfo, MatchExplanation, MediaInfo, MediaMetadata,
+        MediaType, ParsedMedia, ProviderHealth, ProviderSearch, ScoreBreakdown, ScoredMatch,
+        WatchAvailability,
+    },
 };
 
 /// Search request parameters
@@ -46,6 +54,10 @@ pub struct SearchResult {
     pub provider: String,
     pub score: i32,
     pub confidence: String,
+    /// Providers that agreed on this result
+    pub providers: Vec<String>,
+    /// Why this result scored and ranked the way it did
+    pub reasons: Vec<String>,
 }
 
 impl From<ScoredMatch> for SearchResult {
@@ -62,6 +74,8 @@ impl From<ScoredMatch> for SearchResult {
             provider: m.info.provider.clone(),
             score: m.score,
             confidence: format!("{:?}", m.confidence),
+            providers: m.providers,
+            reasons: m.reasons,
         }
     }
 }
@@ -69,7 +83,7 @@ impl From<ScoredMatch> for SearchResult {
 /// Metadata request
 #[derive(Debug, Deserialize)]
 pub struct MetadataRequest {
-    /// Provider ID (tmdb, anilist, bangumi)
+    /// Provider ID (tmdb, tvdb, tvmaze, trakt, anilist, anidb, bangumi, douban)
     pub provider: String,
     /// Media ID from the provider
     pub id: String,
@@ -91,6 +105,61 @@ pub struct EpisodeQuery {
     pub episode: i32,
 }
 
+/// Season request parameters
+#[derive(Debug, Deserialize)]
+pub struct SeasonQuery {
+    /// Provider ID
+    pub provider: String,
+    /// Series ID from the provider
+    pub series_id: String,
+    /// Season number
+    pub season: i32,
+}
+
+/// Discover request parameters
+#[derive(Debug, Deserialize)]
+pub struct DiscoverQuery {
+    /// Provider ID (default: tmdb)
+    pub provider: Option<String>,
+    /// List: trending_day, trending_week, popular, discover (default: trending_week)
+    #[serde(default)]
+    pub list: DiscoverList,
+    /// Optional media type filter: movie, tv, anime
+    #[serde(rename = "type")]
+    pub media_type: Option<String>,
+    /// Page of results, from 1
+    pub page: Option<u32>,
+    /// Optional language (ISO 639-1)
+    pub language: Option<String>,
+    /// Optional year filter
+    pub year: Option<i32>,
+    /// Provider-specific genre IDs, comma separated
+    pub genres: Option<String>,
+    /// Provider-specific sort order
+    pub sort_by: Option<String>,
+}
+
+/// Discover response
+#[derive(Debug, Serialize)]
+pub struct DiscoverResponse {
+    pub results: Vec<MediaInfo>,
+    pub total: usize,
+}
+
+/// Streaming availability request parameters
+#[derive(Debug, Deserialize)]
+pub struct WatchProvidersQuery {
+    /// Provider ID
+    pub provider: String,
+    /// Media ID from the provider
+    pub id: String,
+    /// Media type: movie, tv, anime
+    #[serde(rename = "type")]
+    pub media_type: String,
+    /// Optional region filter (ISO 3166-1, e.g. US)
+    pub region: Option<String>,
+}
+
 /// Episode response
 #[derive(Debug, Serialize)]
 pub struct EpisodeResponse {
@@ -106,6 +175,23 @@ pub struct EpisodeResponse {
     pub still_url: Option<String>,
 }
 
+impl From<EpisodeInfo> for EpisodeResponse {
+    fn from(episode: EpisodeInfo) -> Self {
+        Self {
+            id: episode.id,
+            title: episode.title,
+            season: episode.season,
+            episode: episode.episode,
+            absolute_number: episode.absolute_number,
+            air_date: episode.air_date,
+            overview: episode.overview,
+            runtime: episode.runtime,
+            rating: episode.rating,
+            still_url: episode.still_url,
+        }
+    }
+}
+
 /// Parse filename request
 #[derive(Debug, Deserialize)]
 pub struct ParseRequest {
@@ -127,6 +213,96 @@ pub struct ParseResponse {
     pub hint: String,
 }
 
+impl From<ParsedMedia> for ParseResponse {
+    fn from(parsed: ParsedMedia) -> Self {
+        Self {
+            title: parsed.title,
+            original_title: parsed.original_title,
+            year: parsed.year,
+            season: parsed.season,
+            episode: parsed.episode,
+            resolution: parsed.resolution,
+            quality: parsed.quality,
+            codec: parsed.codec,
+            release_group: parsed.release_group,
+            hint: format!("{:?}", parsed.hint),
+        }
+    }
+}
+
+/// Match explanation request parameters
+#[derive(Debug, Deserialize)]
+pub struct ExplainQuery {
+    /// File path (e.g. `/movies/Heat (1995)/Heat.1995.mkv`) or search query
+    pub query: String,
+}
+
+/// How a file or query would be matched
+#[derive(Debug, Serialize)]
+pub struct ExplainResponse {
+    pub parsed: ParseResponse,
+    /// Each provider asked, with its status: cached, searched, not_found,
+    /// timed_out or failed
+    pub searches: Vec<ProviderSearch>,
+    /// Every candidate, the winner first
+    pub candidates: Vec<ExplainCandidate>,
+    /// Why the winner was chosen, or why there is none
+    pub decision: Vec<String>,
+    /// Whether a scrape would fetch the winner's metadata
+    pub accepted: bool,
+}
+
+/// Candidate of a match explanation
+#[derive(Debug, Serialize)]
+pub struct ExplainCandidate {
+    #[serde(flatten)]
+    pub result: SearchResult,
+    pub breakdown: ScoreBreakdown,
+}
+
+impl From<MatchExplanation> for ExplainResponse {
+    fn from(explained: MatchExplanation) -> Self {
+        Self {
+            parsed: explained.parsed.into(),
+            searches: explained.searches,
+            candidates: explained
+                .candidates
+                .into_iter()
+                .map(|m| ExplainCandidate {
+                    breakdown: m.breakdown.clone(),
+                    result: m.into(),
+                })
+                .collect(),
+            decision: explained.decision,
+            accepted: explained.accepted,
+        }
+    }
+}
+
+/// Providers request parameters
+#[derive(Debug, Deserialize)]
+pub struct ProvidersQuery {
+    /// Check each provider's API and credentials (default: false)
+    #[serde(default)]
+    pub health: bool,
+}
+
+/// Provider toggle request
+#[derive(Debug, Deserialize)]
+pub struct ProviderEnabledRequest {
+    /// `false` skips the provider until it is enabled again
+    pub enabled: bool,
+}
+
+/// Cache invalidation parameters; neither clears the whole cache
+#[derive(Debug, Deserialize)]
+pub struct CacheQuery {
+    /// Only entries of this provider
+    pub provider: Option<String>,
+    /// Only the metadata of this provider ID (requires `provider`)
+    pub id: Option<String>,
+}
+
 /// Provider info
 #[derive(Debug, Serialize)]
 pub struct ProviderInfo {
@@ -134,6 +310,11 @@ pub struct ProviderInfo {
     pub name: String,
     pub supported_types: Vec<String>,
     pub requires_api_key: bool,
+    /// Whether the provider is in use; see `PUT /api/scraper/providers/{id}/enabled`
+    pub enabled: bool,
+    /// Present when requested with `health=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<ProviderHealth>,
 }
 
 /// Providers response
@@ -266,18 +447,137 @@ async fn get_episode(
     Ok(Json(ApiResponse {
         code: 200,
         message: "Episode retrieved".to_string(),
-        data: Some(EpisodeResponse {
-            id: episode.id,
-            title: episode.title,
-            season: episode.season,
-            episode: episode.episode,
-            absolute_number: episode.absolute_number,
-            air_date: episode.air_date,
-            overview: episode.overview,
-            runtime: episode.runtime,
-            rating: episode.rating,
-            still_url: episode.still_url,
-        }),
+        data: Some(episode.into()),
+    }))
+}
+
+/// Get all episodes of a season
+/// GET /`api/scraper/season?provider=...&series_id=...&season`=...
+async fn get_season(
+    State(ctx): State<Ctx>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<ApiResponse<Vec<EpisodeResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                code: 503,
+                message: "Scraper not available".to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    let episodes = scraper
+        .get_season(&params.provider, &params.series_id, params.season)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse {
+                    code: 404,
+                    message: format!("Season not found: {e}"),
+                    data: None,
+                }),
+            )
+        })?;
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: "Season retrieved".to_string(),
+        data: Some(episodes.into_iter().map(Into::into).collect()),
+    }))
+}
+
+/// Browse trending, popular or filtered titles
+/// GET /`api/scraper/discover?provider=...&list=...&type=...&page`=...
+async fn discover(
+    State(ctx): State<Ctx>,
+    Query(params): Query<DiscoverQuery>,
+) -> Result<Json<ApiResponse<DiscoverResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                code: 503,
+                message: "Scraper not available".to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    let provider = params.provider.as_deref().unwrap_or("tmdb");
+    let mut options = DiscoverOptions::new(params.list)
+        .with_type(params.media_type.as_deref().and_then(parse_media_type))
+        .with_page(params.page)
+        .with_year(params.year)
+        .with_genres(params.genres)
+        .with_sort_by(params.sort_by);
+    if let Some(language) = params.language {
+        options = options.with_language(language);
+    }
+
+    let results = scraper.discover(provider, options).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                code: 500,
+                message: format!("Discover failed: {e}"),
+                data: None,
+            }),
+        )
+    })?;
+    let total = results.len();
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: "Discover completed".to_string(),
+        data: Some(DiscoverResponse { results, total }),
+    }))
+}
+
+/// Get where a movie or TV show can be watched, by region
+/// GET /`api/scraper/watch-providers?provider=...&id=...&type=...&region`=...
+async fn get_watch_providers(
+    State(ctx): State<Ctx>,
+    Query(params): Query<WatchProvidersQuery>,
+) -> Result<Json<ApiResponse<WatchAvailability>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                code: 503,
+                message: "Scraper not available".to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    let media_type = parse_media_type(&params.media_type).unwrap_or(MediaType::Unknown);
+
+    let mut availability = scraper
+        .get_watch_providers(&params.provider, &params.id, media_type)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse {
+                    code: 404,
+                    message: format!("Watch providers not found: {e}"),
+                    data: None,
+                }),
+            )
+        })?;
+
+    if let Some(region) = params.region {
+        let region = region.to_ascii_uppercase();
+        availability.regions.retain(|code, _| *code == region);
+    }
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: "Watch providers retrieved".to_string(),
+        data: Some(availability),
     }))
 }
 
@@ -293,18 +593,7 @@ async fn parse_filename(Json(req): Json<ParseRequest>) -> Json<ApiResponse<Parse
     Json(ApiResponse {
         code: 200,
         message: "Filename parsed".to_string(),
-        data: Some(ParseResponse {
-            title: parsed.title,
-            original_title: parsed.original_title,
-            year: parsed.year,
-            season: parsed.season,
-            episode: parsed.episode,
-            resolution: parsed.resolution,
-            quality: parsed.quality,
-            codec: parsed.codec,
-            release_group: parsed.release_group,
-            hint: format!("{:?}", parsed.hint),
-        }),
+        data: Some(parsed.into()),
     })
 }
 
@@ -362,10 +651,37 @@ async fn scrape_from_filename(
     }))
 }
 
-/// List available providers
-/// GET /api/scraper/providers
+/// Explain how a file path or query would be matched, for troubleshooting
+/// GET /api/scraper/explain?query=...
+async fn explain_match(
+    State(ctx): State<Ctx>,
+    Query(params): Query<ExplainQuery>,
+) -> Result<Json<ApiResponse<ExplainResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                code: 503,
+                message: "Scraper not available".to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    let explained = scraper.explain(&params.query).await;
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: "Match explained".to_string(),
+        data: Some(explained.into()),
+    }))
+}
+
+/// List available providers, optionally checking their health
+/// GET /`api/scraper/providers?health`=...
 async fn list_providers(
     State(ctx): State<Ctx>,
+    Query(params): Query<ProvidersQuery>,
 ) -> Result<Json<ApiResponse<ProvidersResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
     let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
         (
@@ -378,6 +694,13 @@ async fn list_providers(
         )
     })?;
 
+    let health = if params.health {
+        scraper.health().await
+    } else {
+        Vec::new()
+    };
+    let mut health = health.into_iter();
+
     let providers: Vec<ProviderInfo> = scraper
         .providers()
         .iter()
@@ -386,6 +709,8 @@ async fn list_providers(
             name: p.name().to_string(),
             supported_types: p.supported_types().iter().map(std::string::ToString::to_string).collect(),
             requires_api_key: p.requires_api_key(),
+            enabled: scraper.is_provider_enabled(p.id()),
+            health: health.next(),
         })
         .collect();
 
@@ -396,6 +721,126 @@ async fn list_providers(
     }))
 }
 
+/// Enable or disable a provider until the server restarts
+/// PUT /api/scraper/providers/{id}/enabled
+async fn set_provider_enabled(
+    State(ctx): State<Ctx>,
+    Path(id): Path<String>,
+    Json(req): Json<ProviderEnabledRequest>,
+) -> Result<Json<ApiResponse<()>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                code: 503,
+                message: "Scraper not available".to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    if !scraper.set_provider_enabled(&id, req.enabled) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                code: 404,
+                message: format!("Provider not found: {id}"),
+                data: None,
+            }),
+        ));
+    }
+
+    let state = if req.enabled { "enabled" } else { "disabled" };
+    Ok(Json(ApiResponse {
+        code: 200,
+        message: format!("Provider {id} {state}"),
+        data: None,
+    }))
+}
+
+/// Clear the scraper cache, entirely, for one provider or for one item
+/// DELETE /`api/scraper/cache?provider=...&id`=...
+async fn clear_cache(
+    State(ctx): State<Ctx>,
+    Query(params): Query<CacheQuery>,
+) -> Result<Json<ApiResponse<()>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                code: 503,
+                message: "Scraper not available".to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    let message = match (params.provider, params.id) {
+        (Some(provider), Some(id)) => {
+            scraper.invalidate_metadata(&provider, &id).await;
+            format!("Cache cleared for {provider}:{id}")
+        }
+        (Some(provider), None) => {
+            scraper.invalidate_provider(&provider).await;
+            format!("Cache cleared for {provider}")
+        }
+        (None, Some(_)) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    code: 400,
+                    message: "An id needs a provider".to_string(),
+                    data: None,
+                }),
+            ));
+        }
+        (None, None) => {
+            scraper.clear_cache().await;
+            "Cache cleared".to_string()
+        }
+    };
+
+    Ok(Json(ApiResponse {
+        code: 200,
+        message,
+        data: None,
+    }))
+}
+
+/// Stream the steps of running scrapes as server-sent events
+/// GET /api/scraper/events
+async fn scrape_events(
+    State(ctx): State<Ctx>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ApiResponse<()>>)>
+{
+    let scraper = ctx.scraper_manager.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                code: 503,
+                message: "Scraper not available".to_string(),
+                data: None,
+            }),
+        )
+    })?;
+
+    let events = stream::unfold(scraper.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let event = Event::default().json_data(&event).unwrap_or_default();
+                    return Some((Ok(event), rx));
+                }
+                // A client too slow to keep up misses the oldest events
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
 /// Refresh metadata for a media item by ID
 /// POST /api/scraper/refresh/{id}
 async fn refresh_item_metadata(
@@ -448,8 +893,15 @@ pub fn mount() -> Router<Ctx> {
         .route("/scraper/search", get(search))
         .route("/scraper/metadata", post(get_metadata))
         .route("/scraper/episode", get(get_episode))
+        .route("/scraper/season", get(get_season))
+        .route("/scraper/discover", get(discover))
+        .route("/scraper/watch-providers", get(get_watch_providers))
         .route("/scraper/parse", post(parse_filename))
         .route("/scraper/scrape", post(scrape_from_filename))
+        .route("/scraper/explain", get(explain_match))
         .route("/scraper/providers", get(list_providers))
+        .route("/scraper/providers/{id}/enabled", put(set_provider_enabled))
+        .route("/scraper/cache", delete(clear_cache))
+        .route("/scraper/events", get(scrape_events))
         .route("/scraper/refresh/{id}", post(refresh_item_metadata))
 }
@@ -0,0 +1,174 @@
+use crate::scraper::provider::RateLimiter;
+use futures::stream::{self, Stream, StreamExt};
+use std::future::{self, Future};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static PRIORITY: ScrapePriority;
+}
+
+/// Who a provider call is made for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrapePriority {
+    /// Someone waiting on an API response
+    #[default]
+    Interactive,
+    /// Batch jobs: library scans, bulk organizing and refreshes
+    Background,
+}
+
+/// Provider calls the whole scraper may make at once
+///
+/// Shared by searches, metadata and episode lookups of every provider, on
+/// top of each provider's own rate limit. Background work can only take
+/// the slots left after the reserved ones, and can be slowed down further,
+/// so a batch job never leaves interactive requests waiting behind it.
+/// Clones share their limits.
+#[derive(Debug, Clone)]
+pub struct ScrapeBudget {
+    slots: Arc<Semaphore>,
+    background: Arc<Semaphore>,
+    background_rate: Option<Arc<RateLimiter>>,
+}
+
+impl ScrapeBudget {
+    /// Default provider calls running at once
+    pub const DEFAULT_CONCURRENCY: usize = 16;
+    /// Default calls kept free for interactive requests
+    pub const DEFAULT_RESERVED: usize = 4;
+
+    /// Budget of `concurrency` calls at once (at least 2), `reserved` of them
+    /// (at least 1, leaving at least 1) only for interactive requests, and
+    /// background calls limited to `background_rate` per second
+    #[must_use]
+    pub fn new(concurrency: usize, reserved: usize, background_rate: Option<f64>) -> Self {
+        let concurrency = concurrency.max(2);
+        let reserved = reserved.clamp(1, concurrency - 1);
+        Self {
+            slots: Arc::new(Semaphore::new(concurrency)),
+            background: Arc::new(Semaphore::new(concurrency - reserved)),
+            background_rate: background_rate
+                .filter(|rate| *rate > 0.0)
+                .map(|rate| Arc::new(RateLimiter::new(rate))),
+        }
+    }
+
+    /// Run `task` with its provider calls counted as background work
+    pub async fn background<F: Future>(task: F) -> F::Output {
+        PRIORITY.scope(ScrapePriority::Background, task).await
+    }
+
+    /// Run `task` on each of `items`, `concurrency` at a time, as background
+    /// work
+    ///
+    /// Outcomes stream in the order of `items` as they finish. Once `cancel`
+    /// fires no more items are started; the ones in progress still finish.
+    pub fn batch<'a, I, F, Fut>(
+        items: I,
+        concurrency: usize,
+        cancel: CancellationToken,
+        mut task: F,
+    ) -> impl Stream<Item = Fut::Output> + 'a
+    where
+        I: IntoIterator,
+        I::IntoIter: 'a,
+        F: FnMut(I::Item) -> Fut + 'a,
+        Fut: Future + 'a,
+    {
+        stream::iter(items)
+            .take_while(move |_| future::ready(!cancel.is_cancelled()))
+            .map(move |item| Self::background(task(item)))
+            .buffered(concurrency.max(1))
+    }
+
+    /// Priority of the current task's calls
+    #[must_use]
+    pub fn priority() -> ScrapePriority {
+        PRIORITY.try_with(|priority| *priority).unwrap_or_default()
+    }
+
+    /// Wait for a turn to call a provider, which lasts until the permit drops
+    pub async fn acquire(&self) -> ScrapePermit {
+        // Never closed, so acquiring can't fail
+        let background = match Self::priority() {
+            ScrapePriority::Interactive => None,
+            ScrapePriority::Background => {
+                let permit = self.background.clone().acquire_owned().await;
+                let permit = permit.expect("semaphore closed");
+                if let Some(rate) = &self.background_rate {
+                    rate.acquire().await;
+                }
+                Some(permit)
+            }
+        };
+        let slot = self.slots.clone().acquire_owned().await.expect("semaphore closed");
+        ScrapePermit {
+            _slot: slot,
+            _background: background,
+        }
+    }
+}
+
+impl Default for ScrapeBudget {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CONCURRENCY, Self::DEFAULT_RESERVED, None)
+    }
+}
+
+/// Turn of a provider call in a [`ScrapeBudget`], given back when dropped
+#[derive(Debug)]
+pub struct ScrapePermit {
+    _slot: OwnedSemaphorePermit,
+    _background: Option<OwnedSemaphorePermit>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_interactive_calls_keep_reserved_slots() {
+        let budget = ScrapeBudget::new(3, 1, None);
+        let wait = Duration::from_millis(20);
+
+        let (first, second) = ScrapeBudget::background(async {
+            assert_eq!(ScrapeBudget::priority(), ScrapePriority::Background);
+            (budget.acquire().await, budget.acquire().await)
+        })
+        .await;
+        // Background work has used up its share
+        let third = ScrapeBudget::background(budget.acquire());
+        assert!(tokio::time::timeout(wait, third).await.is_err());
+
+        // The reserved slot is still there for an interactive request
+        assert_eq!(ScrapeBudget::priority(), ScrapePriority::Interactive);
+        let interactive = budget.acquire().await;
+        assert!(tokio::time::timeout(wait, budget.acquire()).await.is_err());
+
+        drop((first, second, interactive));
+        let third = ScrapeBudget::background(budget.acquire());
+        assert!(tokio::time::timeout(wait, third).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_batch_stops_when_cancelled() {
+        let cancel = CancellationToken::new();
+        let batch = ScrapeBudget::batch(1..=10, 2, cancel.clone(), |n| {
+            let cancel = cancel.clone();
+            async move {
+                assert_eq!(ScrapeBudget::priority(), ScrapePriority::Background);
+                if n == 3 {
+                    cancel.cancel();
+                }
+                n
+            }
+        });
+
+        // Started before the cancellation, the third and fourth still finish
+        let done: Vec<i32> = batch.collect().await;
+        assert_eq!(done, [1, 2, 3, 4]);
+    }
+}
@@ -1,7 +1,37 @@
-use crate::scraper::types::{MediaInfo, MediaMetadata};
+use crate::scraper::{
+    Result,
+    types::{MediaInfo, MediaMetadata},
+};
+use chrono::Utc;
 use moka::future::Cache;
+use serde::{Serialize, de::DeserializeOwned};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqliteSynchronous};
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
+use tracing::{debug, warn};
+
+const DISK_SCHEMA: &str = r"
+    CREATE TABLE IF NOT EXISTS entries (
+        kind TEXT NOT NULL,
+        provider TEXT NOT NULL,
+        key TEXT NOT NULL,
+        -- JSON
+        value TEXT NOT NULL,
+        -- Unix timestamps
+        expires_at INTEGER NOT NULL,
+        accessed_at INTEGER NOT NULL,
+        PRIMARY KEY (kind, provider, key)
+    );
+    CREATE INDEX IF NOT EXISTS entries_accessed_at ON entries (accessed_at);
+";
+
+/// Writes between two trims of the on-disk cache
+const PRUNE_INTERVAL: u64 = 100;
+
+const SEARCH: &str = "search";
+const METADATA: &str = "metadata";
 
 /// Cache key for search results
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -18,11 +48,28 @@ struct MetadataKey {
     id: String,
 }
 
+impl SearchKey {
+    /// Key of the on-disk entry; the year prefix ends at the first `:`
+    fn disk_key(&self) -> String {
+        match self.year {
+            Some(year) => format!("{year}:{}", self.query),
+            None => format!(":{}", self.query),
+        }
+    }
+}
+
 /// Scraper cache for API responses
+///
+/// Entries live in memory, and with [`ScraperCache::open`] also in a SQLite
+/// database behind it, so restarts don't re-fetch a whole library.
 #[derive(Clone)]
 pub struct ScraperCache {
     search_cache: Cache<SearchKey, Arc<Vec<MediaInfo>>>,
+    /// Searches that found nothing, kept for a shorter time
+    miss_cache: Cache<SearchKey, ()>,
     metadata_cache: Cache<MetadataKey, Arc<MediaMetadata>>,
+    disk: Option<DiskCache>,
+    config: CacheConfig,
 }
 
 impl ScraperCache {
@@ -38,19 +85,40 @@ impl ScraperCache {
         let search_cache = Cache::builder()
             .max_capacity(config.search_max_entries)
             .time_to_live(config.search_ttl)
+            .support_invalidation_closures()
+            .build();
+
+        let miss_cache = Cache::builder()
+            .max_capacity(config.search_max_entries)
+            .time_to_live(config.miss_ttl)
+            .support_invalidation_closures()
             .build();
 
         let metadata_cache = Cache::builder()
             .max_capacity(config.metadata_max_entries)
             .time_to_live(config.metadata_ttl)
+            .support_invalidation_closures()
             .build();
 
         Self {
             search_cache,
+            miss_cache,
             metadata_cache,
+            disk: None,
+            config,
         }
     }
 
+    /// Create a cache backed by the SQLite database at `path`, creating it
+    /// when missing
+    pub async fn open(path: impl AsRef<Path>, config: CacheConfig) -> Result<Self> {
+        let disk = DiskCache::open(path.as_ref(), config.disk_max_entries).await?;
+        Ok(Self {
+            disk: Some(disk),
+            ..Self::with_config(config)
+        })
+    }
+
     /// Get cached search results
     pub async fn get_search(
         &self,
@@ -64,10 +132,25 @@ impl ScraperCache {
             year,
         };
 
-        self.search_cache.get(&key).await.map(|arc| (*arc).clone())
+        if let Some(results) = self.search_cache.get(&key).await {
+            return Some((*results).clone());
+        }
+        if self.miss_cache.contains_key(&key) {
+            return Some(Vec::new());
+        }
+
+        let disk = self.disk.as_ref()?;
+        let results: Vec<MediaInfo> = disk.get(SEARCH, provider, &key.disk_key()).await?;
+        if results.is_empty() {
+            self.miss_cache.insert(key, ()).await;
+        } else {
+            self.search_cache.insert(key, Arc::new(results.clone())).await;
+        }
+        Some(results)
     }
 
-    /// Cache search results
+    /// Cache search results; no results are cached as a miss, for
+    /// [`CacheConfig::miss_ttl`]
     pub async fn set_search(
         &self,
         provider: &str,
@@ -81,7 +164,21 @@ impl ScraperCache {
             year,
         };
 
-        self.search_cache.insert(key, Arc::new(results)).await;
+        let ttl = if results.is_empty() {
+            self.config.miss_ttl
+        } else {
+            self.config.search_ttl
+        };
+        if let Some(ref disk) = self.disk {
+            disk.set(SEARCH, provider, &key.disk_key(), &results, ttl).await;
+        }
+
+        if results.is_empty() {
+            self.miss_cache.insert(key, ()).await;
+        } else {
+            self.miss_cache.invalidate(&key).await;
+            self.search_cache.insert(key, Arc::new(results)).await;
+        }
     }
 
     /// Get cached metadata
@@ -91,10 +188,16 @@ impl ScraperCache {
             id: id.to_string(),
         };
 
+        if let Some(metadata) = self.metadata_cache.get(&key).await {
+            return Some((*metadata).clone());
+        }
+
+        let disk = self.disk.as_ref()?;
+        let metadata: MediaMetadata = disk.get(METADATA, provider, id).await?;
         self.metadata_cache
-            .get(&key)
-            .await
-            .map(|arc| (*arc).clone())
+            .insert(key, Arc::new(metadata.clone()))
+            .await;
+        Some(metadata)
     }
 
     /// Cache metadata
@@ -104,13 +207,55 @@ impl ScraperCache {
             id: id.to_string(),
         };
 
+        if let Some(ref disk) = self.disk {
+            disk.set(METADATA, provider, id, &metadata, self.config.metadata_ttl)
+                .await;
+        }
         self.metadata_cache.insert(key, Arc::new(metadata)).await;
     }
 
-    /// Clear all caches
-    pub fn clear(&self) {
+    /// Forget the cached metadata of one item, on disk too
+    pub async fn invalidate_metadata(&self, provider: &str, id: &str) {
+        let key = MetadataKey {
+            provider: provider.to_string(),
+            id: id.to_string(),
+        };
+
+        self.metadata_cache.invalidate(&key).await;
+        if let Some(ref disk) = self.disk {
+            disk.remove(Some(METADATA), provider, Some(id)).await;
+        }
+    }
+
+    /// Forget everything cached from one provider, on disk too
+    pub async fn invalidate_provider(&self, provider: &str) {
+        let searches = provider.to_string();
+        let misses = provider.to_string();
+        let metadata = provider.to_string();
+        // Only fails when closures aren't supported, and they are
+        let _ = self
+            .search_cache
+            .invalidate_entries_if(move |key, _| key.provider == searches);
+        let _ = self
+            .miss_cache
+            .invalidate_entries_if(move |key, ()| key.provider == misses);
+        let _ = self
+            .metadata_cache
+            .invalidate_entries_if(move |key, _| key.provider == metadata);
+
+        if let Some(ref disk) = self.disk {
+            disk.remove(None, provider, None).await;
+        }
+    }
+
+    /// Clear all caches, on disk too
+    pub async fn clear(&self) {
         self.search_cache.invalidate_all();
+        self.miss_cache.invalidate_all();
         self.metadata_cache.invalidate_all();
+        if let Some(ref disk) = self.disk {
+            disk.clear().await;
+        }
     }
 
     /// Get cache statistics
@@ -118,6 +263,7 @@ impl ScraperCache {
     pub fn stats(&self) -> CacheStats {
         CacheStats {
             search_entries: self.search_cache.entry_count(),
+            miss_entries: self.miss_cache.entry_count(),
             metadata_entries: self.metadata_cache.entry_count(),
         }
     }
@@ -129,6 +275,145 @@ impl Default for ScraperCache {
     }
 }
 
+/// SQLite tier of the cache, holding JSON entries
+///
+/// Failures are logged and treated as misses; a broken cache shouldn't stop
+/// a scrape.
+#[derive(Clone)]
+struct DiskCache {
+    db: SqlitePool,
+    max_entries: u64,
+    writes: Arc<AtomicU64>,
+}
+
+impl DiskCache {
+    async fn open(path: &Path, max_entries: u64) -> Result<Self> {
+        let db = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(path)
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal)
+                .synchronous(SqliteSynchronous::Normal)
+                .busy_timeout(Duration::from_secs(30)),
+        )
+        .await?;
+        sqlx::raw_sql(DISK_SCHEMA).execute(&db).await?;
+
+        let cache = Self {
+            db,
+            max_entries,
+            writes: Arc::default(),
+        };
+        cache.prune().await?;
+        Ok(cache)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, kind: &str, provider: &str, key: &str) -> Option<T> {
+        let now = Utc::now().timestamp();
+        let value: Option<String> = sqlx::query_scalar(
+            "UPDATE entries SET accessed_at = ?
+             WHERE kind = ? AND provider = ? AND key = ? AND expires_at > ?
+             RETURNING value",
+        )
+        .bind(now)
+        .bind(kind)
+        .bind(provider)
+        .bind(key)
+        .bind(now)
+        .fetch_optional(&self.db)
+        .await
+        .inspect_err(|e| warn!("Scraper cache read failed: {e}"))
+        .ok()?;
+
+        // Entries written by an older version may no longer parse
+        serde_json::from_str(&value?)
+            .inspect_err(|e| debug!("Stale {kind} cache entry {provider}:{key}: {e}"))
+            .ok()
+    }
+
+    async fn set<T: Serialize>(
+        &self,
+        kind: &str,
+        provider: &str,
+        key: &str,
+        value: &T,
+        ttl: Duration,
+    ) {
+        let Ok(value) = serde_json::to_string(value) else {
+            return;
+        };
+        let now = Utc::now().timestamp();
+        let expires_at = now.saturating_add(i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX));
+
+        let result = sqlx::query(
+            "INSERT INTO entries (kind, provider, key, value, expires_at, accessed_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (kind, provider, key) DO UPDATE SET
+                value = excluded.value,
+                expires_at = excluded.expires_at,
+                accessed_at = excluded.accessed_at",
+        )
+        .bind(kind)
+        .bind(provider)
+        .bind(key)
+        .bind(value)
+        .bind(expires_at)
+        .bind(now)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            warn!("Scraper cache write failed: {e}");
+            return;
+        }
+
+        if self.writes.fetch_add(1, Ordering::Relaxed) % PRUNE_INTERVAL == PRUNE_INTERVAL - 1
+            && let Err(e) = self.prune().await
+        {
+            warn!("Scraper cache prune failed: {e}");
+        }
+    }
+
+    /// Drop expired entries, then the least recently used ones over the limit
+    async fn prune(&self) -> Result<()> {
+        sqlx::query("DELETE FROM entries WHERE expires_at <= ?")
+            .bind(Utc::now().timestamp())
+            .execute(&self.db)
+            .await?;
+        sqlx::query(
+            "DELETE FROM entries WHERE rowid IN
+                (SELECT rowid FROM entries ORDER BY accessed_at DESC LIMIT -1 OFFSET ?)",
+        )
+        .bind(i64::try_from(self.max_entries).unwrap_or(i64::MAX))
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete the entries of `provider`, of one kind or key if given
+    async fn remove(&self, kind: Option<&str>, provider: &str, key: Option<&str>) {
+        let result = sqlx::query(
+            "DELETE FROM entries
+             WHERE provider = ? AND (? IS NULL OR kind = ?) AND (? IS NULL OR key = ?)",
+        )
+        .bind(provider)
+        .bind(kind)
+        .bind(kind)
+        .bind(key)
+        .bind(key)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            warn!("Scraper cache invalidation failed: {e}");
+        }
+    }
+
+    async fn clear(&self) {
+        if let Err(e) = sqlx::query("DELETE FROM entries").execute(&self.db).await {
+            warn!("Scraper cache clear failed: {e}");
+        }
+    }
+}
+
 /// Cache configuration
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
@@ -136,10 +421,16 @@ pub struct CacheConfig {
     pub search_max_entries: u64,
     /// TTL for search results
     pub search_ttl: Duration,
+    /// TTL for searches that found nothing, short so new releases and
+    /// provider fixes are picked up soon
+    pub miss_ttl: Duration,
     /// Maximum number of metadata entries
     pub metadata_max_entries: u64,
     /// TTL for metadata
     pub metadata_ttl: Duration,
+    /// Maximum number of entries of the on-disk cache, searches and metadata
+    /// together
+    pub disk_max_entries: u64,
 }
 
 impl Default for CacheConfig {
@@ -147,8 +438,10 @@ impl Default for CacheConfig {
         Self {
             search_max_entries: 1000,
             search_ttl: Duration::from_secs(3600), // 1 hour
+            miss_ttl: Duration::from_secs(900), // 15 minutes
             metadata_max_entries: 500,
             metadata_ttl: Duration::from_secs(86400), // 24 hours
+            disk_max_entries: 100_000,
         }
     }
 }
@@ -157,6 +450,7 @@ impl Default for CacheConfig {
 #[derive(Debug, Clone)]
 pub struct CacheStats {
     pub search_entries: u64,
+    pub miss_entries: u64,
     pub metadata_entries: u64,
 }
 
@@ -223,7 +517,7 @@ mod tests {
             )
             .await;
 
-        cache.clear();
+        cache.clear().await;
 
         let cached = cache.get_search("tmdb", "test", None).await;
         assert!(cached.is_none());
@@ -254,6 +548,63 @@ mod tests {
         assert!(stats.search_entries <= 2);
     }
 
+    #[tokio::test]
+    async fn test_cache_miss() {
+        let cache = ScraperCache::with_config(CacheConfig {
+            miss_ttl: Duration::from_millis(100),
+            ..CacheConfig::default()
+        });
+
+        cache.set_search("anilist", "unknown", None, Vec::new()).await;
+        let cached = cache.get_search("anilist", "unknown", None).await;
+        assert!(cached.is_some_and(|results| results.is_empty()));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(cache.get_search("anilist", "unknown", None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.db");
+        let config = CacheConfig {
+            disk_max_entries: 2,
+            ..CacheConfig::default()
+        };
+
+        let cache = ScraperCache::open(&path, config.clone()).await.unwrap();
+        let metadata = MediaMetadata {
+            id: "123".to_string(),
+            title: "Test Movie".to_string(),
+            provider: "tmdb".to_string(),
+            ..Default::default()
+        };
+        cache.set_metadata("tmdb", "123", metadata.clone()).await;
+        cache
+            .set_search("tmdb", "Test", Some(2020), vec![MediaInfo::new("1", "Test", "tmdb")])
+            .await;
+        drop(cache);
+
+        // A new process starts with an empty memory tier
+        let cache = ScraperCache::open(&path, config).await.unwrap();
+        assert_eq!(cache.get_metadata("tmdb", "123").await.unwrap().title, "Test Movie");
+        assert_eq!(cache.get_search("tmdb", "test", Some(2020)).await.unwrap().len(), 1);
+        assert!(cache.get_search("tmdb", "test", None).await.is_none());
+
+        cache.invalidate_metadata("tmdb", "123").await;
+        assert!(cache.get_metadata("tmdb", "123").await.is_none());
+        assert!(cache.get_search("tmdb", "test", Some(2020)).await.is_some());
+
+        cache.invalidate_provider("tmdb").await;
+        let cache = ScraperCache::open(&path, CacheConfig::default()).await.unwrap();
+        assert!(cache.get_search("tmdb", "test", Some(2020)).await.is_none());
+
+        cache.set_metadata("tmdb", "123", metadata).await;
+        cache.clear().await;
+        let cache = ScraperCache::open(&path, CacheConfig::default()).await.unwrap();
+        assert!(cache.get_metadata("tmdb", "123").await.is_none());
+    }
+
     #[test]
     fn test_cache_config_default() {
         let config = CacheConfig::default();
@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// ISO 9660 and UDF sector size
+const SECTOR_SIZE: u64 = 2048;
+
+/// Volume descriptors start after the 32 KiB system area
+const FIRST_DESCRIPTOR: u64 = 16;
+
+/// How far into a UDF-only image directory names are searched for
+const UDF_SEARCH_LIMIT: u64 = 32 * 1024 * 1024;
+
+/// Video disc format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscKind {
+    Dvd,
+    BluRay,
+}
+
+impl DiscKind {
+    /// Source name used in release names
+    #[must_use]
+    pub const fn source(self) -> &'static str {
+        match self {
+            Self::Dvd => "DVD",
+            Self::BluRay => "BluRay",
+        }
+    }
+}
+
+/// What a disc image holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsoContent {
+    /// A `VIDEO_TS` or `BDMV` folder was found
+    Video(DiscKind),
+    /// A readable file system without video disc folders
+    Data,
+    /// Not an image this probe understands; treated as possibly video
+    Unknown,
+}
+
+impl IsoContent {
+    /// Disc format for video images
+    #[must_use]
+    pub const fn disc(self) -> Option<DiscKind> {
+        match self {
+            Self::Video(kind) => Some(kind),
+            Self::Data | Self::Unknown => None,
+        }
+    }
+
+    /// Whether the image should be imported as video
+    #[must_use]
+    pub const fn may_be_video(self) -> bool {
+        !matches!(self, Self::Data)
+    }
+}
+
+/// Whether a path has an `.iso` extension
+#[must_use]
+pub fn is_iso(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("iso"))
+}
+
+/// Look inside a disc image for DVD (`VIDEO_TS`) or Blu-ray (`BDMV`) folders
+///
+/// The ISO 9660 root directory is checked first. Blu-rays are usually UDF
+/// only, so for those the start of the image is searched for the folder
+/// names as UDF stores them.
+#[must_use]
+pub fn probe_iso(path: &Path) -> IsoContent {
+    File::open(path)
+        .and_then(|mut file| probe_image(&mut file))
+        .unwrap_or(IsoContent::Unknown)
+}
+
+fn probe_image<R: Read + Seek>(image: &mut R) -> io::Result<IsoContent> {
+    let mut root = None;
+    let mut udf = false;
+
+    let mut sector = [0u8; SECTOR_SIZE as usize];
+    for index in FIRST_DESCRIPTOR..FIRST_DESCRIPTOR + 16 {
+        image.seek(SeekFrom::Start(index * SECTOR_SIZE))?;
+        if image.read_exact(&mut sector).is_err() {
+            break;
+        }
+
+        match &sector[1..6] {
+            // Primary volume descriptor; the root directory record sits at offset 156
+            b"CD001" if sector[0] == 1 => {
+                root = Some((le_u32(&sector[158..162]), le_u32(&sector[166..170])));
+            }
+            b"CD001" | b"BEA01" => {}
+            b"NSR02" | b"NSR03" => udf = true,
+            _ => break,
+        }
+    }
+
+    if let Some((extent, size)) = root
+        && let Some(kind) = iso9660_root_disc(image, extent, size)?
+    {
+        return Ok(IsoContent::Video(kind));
+    }
+
+    if udf {
+        return Ok(udf_disc(image)?.map_or(IsoContent::Unknown, IsoContent::Video));
+    }
+
+    Ok(if root.is_some() {
+        IsoContent::Data
+    } else {
+        IsoContent::Unknown
+    })
+}
+
+/// Find a video disc folder among the ISO 9660 root directory records
+fn iso9660_root_disc<R: Read + Seek>(
+    image: &mut R,
+    extent: u32,
+    size: u32,
+) -> io::Result<Option<DiscKind>> {
+    let mut records = vec![0u8; size.min(64 * 1024) as usize];
+    image.seek(SeekFrom::Start(u64::from(extent) * SECTOR_SIZE))?;
+    image.read_exact(&mut records)?;
+
+    let mut offset = 0;
+    while offset < records.len() {
+        let len = records[offset] as usize;
+        if len == 0 {
+            // Records don't cross sectors; the rest of this one is padding
+            offset = (offset / SECTOR_SIZE as usize + 1) * SECTOR_SIZE as usize;
+            continue;
+        }
+
+        let Some(record) = records.get(offset..offset + len) else {
+            break;
+        };
+        let name_len = record.get(32).copied().unwrap_or(0) as usize;
+        if let Some(name) = record.get(33..33 + name_len) {
+            let name = name.split(|&b| b == b';').next().unwrap_or(name);
+            if name.eq_ignore_ascii_case(b"VIDEO_TS") {
+                return Ok(Some(DiscKind::Dvd));
+            }
+            if name.eq_ignore_ascii_case(b"BDMV") {
+                return Ok(Some(DiscKind::BluRay));
+            }
+        }
+
+        offset += len;
+    }
+
+    Ok(None)
+}
+
+/// Search the start of a UDF image for `BDMV`/`VIDEO_TS` file identifiers
+///
+/// UDF names are prefixed with their encoding: 8 for one byte per character,
+/// 16 for UTF-16BE.
+fn udf_disc<R: Read + Seek>(image: &mut R) -> io::Result<Option<DiscKind>> {
+    const SIGNATURES: &[(&[u8], DiscKind)] = &[
+        (b"\x08BDMV", DiscKind::BluRay),
+        (b"\x10\0B\0D\0M\0V", DiscKind::BluRay),
+        (b"\x08VIDEO_TS", DiscKind::Dvd),
+        (b"\x10\0V\0I\0D\0E\0O\0_\0T\0S", DiscKind::Dvd),
+    ];
+    const OVERLAP: usize = 32;
+
+    image.seek(SeekFrom::Start(0))?;
+    let mut chunk = vec![0u8; 1024 * 1024 + OVERLAP];
+    let mut carried = 0;
+    let mut read_total = 0;
+
+    while read_total < UDF_SEARCH_LIMIT {
+        let read = image.read(&mut chunk[carried..])?;
+        if read == 0 {
+            break;
+        }
+        read_total += read as u64;
+
+        let data = &chunk[..carried + read];
+        for (signature, kind) in SIGNATURES {
+            if data.windows(signature.len()).any(|w| w == *signature) {
+                return Ok(Some(*kind));
+            }
+        }
+
+        // Keep the tail so names split across reads are still found
+        carried = data.len().min(OVERLAP);
+        let start = data.len() - carried;
+        chunk.copy_within(start..start + carried, 0);
+    }
+
+    Ok(None)
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Minimal ISO 9660 image whose root directory holds one folder
+    fn iso_image(folder: &str) -> Vec<u8> {
+        let sector = SECTOR_SIZE as usize;
+        let mut image = vec![0u8; sector * 19];
+
+        let pvd = &mut image[sector * 16..sector * 17];
+        pvd[0] = 1;
+        pvd[1..6].copy_from_slice(b"CD001");
+        pvd[158..162].copy_from_slice(&18u32.to_le_bytes());
+        pvd[166..170].copy_from_slice(&(SECTOR_SIZE as u32).to_le_bytes());
+
+        let terminator = &mut image[sector * 17..sector * 18];
+        terminator[0] = 255;
+        terminator[1..6].copy_from_slice(b"CD001");
+
+        let root = &mut image[sector * 18..];
+        let mut offset = 0;
+        for name in [&b"\0"[..], b"\x01", folder.as_bytes()] {
+            let len = 33 + name.len() + (33 + name.len()) % 2;
+            root[offset] = len as u8;
+            root[offset + 32] = name.len() as u8;
+            root[offset + 33..offset + 33 + name.len()].copy_from_slice(name);
+            offset += len;
+        }
+
+        image
+    }
+
+    #[test]
+    fn test_probe_iso9660_images() {
+        let probe = |image: Vec<u8>| probe_image(&mut Cursor::new(image)).unwrap();
+
+        assert_eq!(probe(iso_image("VIDEO_TS")), IsoContent::Video(DiscKind::Dvd));
+        assert_eq!(probe(iso_image("BDMV")), IsoContent::Video(DiscKind::BluRay));
+        assert_eq!(probe(iso_image("SETUP")), IsoContent::Data);
+        assert_eq!(probe(vec![0u8; 64 * 1024]), IsoContent::Unknown);
+    }
+
+    #[test]
+    fn test_probe_udf_image() {
+        let sector = SECTOR_SIZE as usize;
+        let mut image = vec![0u8; sector * 40];
+        for (index, id) in [(16, b"BEA01"), (17, b"NSR03"), (18, b"TEA01")] {
+            image[sector * index + 1..sector * index + 6].copy_from_slice(id);
+        }
+        image[sector * 30..sector * 30 + 5].copy_from_slice(b"\x08BDMV");
+
+        let content = probe_image(&mut Cursor::new(image)).unwrap();
+
+        assert_eq!(content, IsoContent::Video(DiscKind::BluRay));
+        assert!(!IsoContent::Data.may_be_video());
+    }
+}
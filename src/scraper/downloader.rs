@@ -1,34 +1,1528 @@
-use anyhow::Result;
-use std::path::Path;
+use dashmap::DashMap;
+use futures::future::join_all;
+use image::imageops::FilterType;
+use image::{ImageFormat, ImageReader};
+use reqwest::header::{CONTENT_TYPE, RANGE};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use moka::future::Cache;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, warn};
+
+use super::organizer::sanitize_filename;
+use super::{CollectionInfo, EpisodeInfo, ExternalIds, MediaMetadata, MediaType};
+
+/// Attempts made at a download before giving up
+const MAX_ATTEMPTS: u32 = 4;
+/// Wait before the first retry, doubled after each failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Client shared by downloads, so connections to image servers are reused
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .user_agent("Ayiah/0.1.0")
+        .connect_timeout(Duration::from_secs(10))
+        .read_timeout(Duration::from_secs(30))
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// Images [`Downloader::link_duplicate`] remembers, and folders it has
+/// looked through, before forgetting the least recently used
+const SAVED_CAPACITY: u64 = 10_000;
+
+/// Download result type
+pub type Result<T> = std::result::Result<T, DownloadError>;
+
+/// Why a download failed
+#[derive(Debug, thiserror::Error)]
+pub enum DownloadError {
+    #[error("No URL to download")]
+    EmptyUrl,
+
+    #[error("Download failed with status {0}")]
+    Status(u16),
+
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("Not an image: {0}")]
+    NotAnImage(String),
+
+    #[error("Image too small: {width}x{height}")]
+    TooSmall { width: u32, height: u32 },
+}
+
+impl DownloadError {
+    /// Whether trying again may succeed: network errors, 5xx and 429
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(_) => true,
+            Self::Status(status) => *status >= 500 || *status == 429,
+            Self::EmptyUrl
+            | Self::Io(_)
+            | Self::Image(_)
+            | Self::NotAnImage(_)
+            | Self::TooSmall { .. } => false,
+        }
+    }
+}
+
+/// Theme songs of shows, by TVDB ID
+pub const DEFAULT_THEME_MUSIC_URL: &str = "https://tvthemes.plexapp.com/{tvdb}.mp3";
+
+/// Artwork saved by [`Downloader::save_artwork`], named the way Kodi and
+/// Jellyfin look for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArtworkOptions {
+    /// `poster.jpg`
+    pub poster: bool,
+    /// `fanart.jpg`
+    pub fanart: bool,
+    /// `logo.png`
+    pub logo: bool,
+    /// `season01-poster.jpg`, or `season-specials-poster.jpg` for season 0
+    pub season_posters: bool,
+    /// `<episode file name>-thumb.jpg` next to each episode
+    pub episode_thumbs: bool,
+    /// `.actors/Actor_Name.jpg` for each cast member with a photo
+    pub actor_thumbs: bool,
+    /// `theme.mp3`, and `theme.mp4` with a [`ArtworkOptions::theme_video_url`],
+    /// in show folders
+    pub themes: bool,
+    /// Where theme songs come from, with `{tvdb}`, `{tmdb}`, `{imdb}`,
+    /// `{anidb}` or `{anilist}` replaced by the show's ID (Plex's theme
+    /// server by default)
+    pub theme_music_url: String,
+    /// Where theme videos come from, with the same placeholders
+    pub theme_video_url: Option<String>,
+    /// `<movie file name>-trailer.mp4` next to movies, when the scraper has
+    /// a [`TrailerDownloader`](super::TrailerDownloader)
+    pub trailers: bool,
+    /// Replace existing files instead of skipping them
+    pub overwrite: bool,
+    /// Widths of smaller copies saved next to each poster, e.g.
+    /// `poster-w300.jpg`, so web clients don't load the full-size image
+    pub poster_widths: Vec<u32>,
+    /// Hard-link images identical to ones saved before (e.g. a season poster
+    /// that is the show poster) instead of storing them twice
+    pub link_duplicates: bool,
+    /// Images narrower than this are rejected (0 accepts any width)
+    pub min_width: u32,
+    /// Images shorter than this are rejected (0 accepts any height)
+    pub min_height: u32,
+    /// Convert every image to this format, named with its extension;
+    /// `None` keeps what the provider serves
+    pub format: Option<ArtworkFormat>,
+    /// JPEG quality of converted images, 1 to 100
+    pub quality: u8,
+}
+
+/// Format images are converted to, see [`ArtworkOptions::format`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtworkFormat {
+    Jpeg,
+    /// Lossless, so [`ArtworkOptions::quality`] doesn't apply
+    Webp,
+}
+
+impl ArtworkFormat {
+    /// File extension of the format
+    #[must_use]
+    pub const fn ext(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+
+    const fn image_format(self) -> ImageFormat {
+        match self {
+            Self::Jpeg => ImageFormat::Jpeg,
+            Self::Webp => ImageFormat::WebP,
+        }
+    }
+}
+
+impl ArtworkOptions {
+    /// Extension an image from `url` is saved with
+    #[must_use]
+    pub fn ext<'a>(&self, url: &'a str) -> &'a str {
+        match self.format {
+            Some(format) => format.ext(),
+            None => image_ext(url),
+        }
+    }
+}
+
+/// What happened to a file of the artwork pipeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub enum ArtworkStatus {
+    /// Downloaded and written
+    Saved,
+    /// Identical to an image saved before, and hard-linked to it
+    Linked,
+    /// Already there and not overwritten
+    Skipped,
+    /// Not saved, with why
+    Failed(String),
+}
+
+/// Result of one file of [`Downloader::save_artwork`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtworkResult {
+    /// Where the file is saved
+    pub path: PathBuf,
+    /// Where it was downloaded from
+    pub url: String,
+    pub status: ArtworkStatus,
+    /// Scaled-down copies written next to it
+    pub variants: Vec<PathBuf>,
+}
+
+impl ArtworkResult {
+    /// A file that was already there and not overwritten
+    #[must_use]
+    pub const fn skipped(path: PathBuf, url: String) -> Self {
+        Self {
+            path,
+            url,
+            status: ArtworkStatus::Skipped,
+            variants: Vec::new(),
+        }
+    }
+
+    /// Whether the file was written, as a copy or a link
+    #[must_use]
+    pub fn written(&self) -> bool {
+        matches!(self.status, ArtworkStatus::Saved | ArtworkStatus::Linked)
+    }
+}
+
+impl Default for ArtworkOptions {
+    fn default() -> Self {
+        Self {
+            poster: true,
+            fanart: true,
+            logo: true,
+            season_posters: true,
+            episode_thumbs: true,
+            actor_thumbs: false,
+            themes: false,
+            theme_music_url: DEFAULT_THEME_MUSIC_URL.to_string(),
+            theme_video_url: None,
+            trailers: false,
+            overwrite: false,
+            poster_widths: vec![300, 600],
+            link_duplicates: true,
+            min_width: 0,
+            min_height: 0,
+            format: None,
+            quality: 90,
+        }
+    }
+}
+
+/// Limits on simultaneous downloads, overall and per host
+///
+/// A library scan organizes many files at once, each with several images;
+/// without a limit that opens hundreds of connections to the same image
+/// server. Clones share their limits. A host is forgotten once none of its
+/// downloads are running or waiting.
+#[derive(Debug, Clone)]
+pub struct DownloadQueue {
+    slots: Arc<Semaphore>,
+    per_host: usize,
+    hosts: Arc<DashMap<String, Arc<Semaphore>>>,
+}
+
+impl DownloadQueue {
+    /// Default downloads running at once
+    pub const DEFAULT_CONCURRENCY: usize = 8;
+    /// Default downloads running at once from one host
+    pub const DEFAULT_PER_HOST: usize = 4;
+
+    /// Queue running `concurrency` downloads at once, at most `per_host` of
+    /// them from the same host (both at least 1)
+    #[must_use]
+    pub fn new(concurrency: usize, per_host: usize) -> Self {
+        Self {
+            slots: Arc::new(Semaphore::new(concurrency.max(1))),
+            per_host: per_host.max(1),
+            hosts: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Wait for a turn to download `url`, which lasts until the permit drops
+    ///
+    /// The host's turn comes first, so a busy host doesn't hold overall slots
+    /// that downloads from other hosts could use.
+    pub async fn acquire(&self, url: &str) -> DownloadPermit {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        let host_slots = self
+            .hosts
+            .entry(host.clone())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_host)))
+            .clone();
+        // Forgets the host if this turn is given up while still waiting
+        let mut permit = DownloadPermit {
+            slot: None,
+            host_slot: None,
+            host,
+            hosts: Arc::clone(&self.hosts),
+        };
+
+        // Never closed, so acquiring can't fail
+        permit.host_slot = Some(host_slots.acquire_owned().await.expect("semaphore closed"));
+        permit.slot = Some(self.slots.clone().acquire_owned().await.expect("semaphore closed"));
+        permit
+    }
+}
+
+impl Default for DownloadQueue {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CONCURRENCY, Self::DEFAULT_PER_HOST)
+    }
+}
+
+/// Turn of a download in a [`DownloadQueue`], given back when dropped
+#[derive(Debug)]
+pub struct DownloadPermit {
+    slot: Option<OwnedSemaphorePermit>,
+    host_slot: Option<OwnedSemaphorePermit>,
+    host: String,
+    hosts: Arc<DashMap<String, Arc<Semaphore>>>,
+}
+
+impl Drop for DownloadPermit {
+    fn drop(&mut self) {
+        self.slot = None;
+        self.host_slot = None;
+        // Only the map still holds the semaphore: nobody uses the host
+        self.hosts
+            .remove_if(&self.host, |_, slots| Arc::strong_count(slots) == 1);
+    }
+}
 
 /// Downloader for media assets
-pub struct Downloader;
+///
+/// Every download waits for its turn in the [`DownloadQueue`], and images go
+/// through the [`ImageCache`] when there is one. Clones share both, and the
+/// images remembered for [`Downloader::link_duplicate`].
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    queue: DownloadQueue,
+    images: Option<ImageCache>,
+    /// Artwork saved so far by content hash
+    saved: Cache<blake3::Hash, PathBuf>,
+    /// Folders whose existing artwork is in `saved`
+    indexed: Cache<PathBuf, ()>,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new(DownloadQueue::default())
+    }
+}
 
 impl Downloader {
+    /// Downloader waiting in `queue`
+    #[must_use]
+    pub fn new(queue: DownloadQueue) -> Self {
+        Self {
+            queue,
+            images: None,
+            saved: Cache::new(SAVED_CAPACITY),
+            indexed: Cache::new(SAVED_CAPACITY),
+        }
+    }
+
+    /// Keep downloaded images in `images` instead of fetching them every time
+    #[must_use]
+    pub fn with_image_cache(mut self, images: ImageCache) -> Self {
+        self.images = Some(images);
+        self
+    }
+
+    /// Queue downloads wait in
+    #[must_use]
+    pub const fn queue(&self) -> &DownloadQueue {
+        &self.queue
+    }
+
+    /// Cache of downloaded images, if one is set
+    #[must_use]
+    pub const fn image_cache(&self) -> Option<&ImageCache> {
+        self.images.as_ref()
+    }
+
     /// Download an image from a URL to a specific path
-    pub async fn download_image(url: &str, output_path: &Path) -> Result<()> {
+    ///
+    /// Written to `<path>.part` first, so a failed download never leaves a
+    /// truncated image behind. See [`Downloader::download_to`].
+    pub async fn download_image(&self, url: &str, output_path: &Path) -> Result<()> {
+        let partial = partial_path(output_path, url);
+        self.download_partial(url, &partial).await?;
+        tokio::fs::rename(&partial, output_path).await?;
+        Ok(())
+    }
+
+    /// Download `url` into `partial`
+    ///
+    /// Each attempt waits for its turn in the [`Downloader::queue`]. Network
+    /// errors, 5xx and 429 are retried with exponential backoff, with the turn
+    /// given back meanwhile. An attempt asks for the rest of what `partial`
+    /// holds with a `Range` header, so a large file cut off halfway isn't
+    /// downloaded again from the start. When the last attempt fails on such an
+    /// error, `partial` is kept for the next download of the URL (e.g. after
+    /// a restart); other failures remove it.
+    async fn download_partial(&self, url: &str, partial: &Path) -> Result<()> {
         if url.is_empty() {
+            return Err(DownloadError::EmptyUrl);
+        }
+        if let Some(parent) = partial.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            let permit = self.queue.acquire(url).await;
+            let attempted = Self::attempt(url, partial).await;
+            drop(permit);
+            match attempted {
+                Ok(()) => break,
+                Err(e) if e.is_transient() && attempt < MAX_ATTEMPTS => {
+                    debug!("Download of {url} failed ({e}), retrying in {backoff:?}");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    if !e.is_transient() {
+                        let _ = tokio::fs::remove_file(partial).await;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// One attempt at a download, resuming what `partial` already holds
+    async fn attempt(url: &str, partial: &Path) -> Result<()> {
+        let offset = tokio::fs::metadata(partial).await.map_or(0, |m| m.len());
+        let mut request = CLIENT.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={offset}-"));
+        }
+
+        let mut response = request.send().await?;
+        let status = response.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+            // Nothing left to send: the earlier attempt got the whole file
             return Ok(());
         }
+        if !status.is_success() {
+            return Err(DownloadError::Status(status.as_u16()));
+        }
+        // Error pages served with 200, which would be saved as a broken .jpg
+        if let Some(content_type) = response.headers().get(CONTENT_TYPE)
+            && let Ok(content_type) = content_type.to_str()
+            && content_type.starts_with("text/")
+        {
+            return Err(DownloadError::NotAnImage(content_type.to_string()));
+        }
 
-        let response = reqwest::get(url).await?;
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Failed to download image: {}",
-                response.status()
-            ));
+        // A server ignoring the range sends the whole file again
+        let resumed = status == StatusCode::PARTIAL_CONTENT;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(partial)
+            .await?;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
         }
+        file.flush().await?;
 
-        let bytes = response.bytes().await?;
+        Ok(())
+    }
 
-        if let Some(parent) = output_path.parent() {
-            tokio::fs::create_dir_all(parent).await?;
+    /// Download an image, through `images` when there is a cache
+    ///
+    /// Theme songs and videos planned with the artwork are always downloaded,
+    /// as the cache only keeps images.
+    pub async fn save_image(&self, url: &str, path: &Path) -> Result<()> {
+        let partial = partial_path(path, url);
+        self.save_partial(url, path, &partial).await?;
+        tokio::fs::rename(&partial, path).await?;
+        Ok(())
+    }
+
+    /// [`Downloader::save_image`] into `partial`, leaving the move to `path`
+    /// to the caller
+    async fn save_partial(&self, url: &str, path: &Path, partial: &Path) -> Result<()> {
+        match &self.images {
+            Some(images) if !is_theme(path) => self.copy_partial(images, url, partial).await,
+            _ => self.download_partial(url, partial).await,
         }
+    }
 
-        let mut file = tokio::fs::File::create(output_path).await?;
-        file.write_all(&bytes).await?;
+    /// Local copy of the image in `images`, downloaded when not cached yet
+    pub async fn fetch_cached(&self, images: &ImageCache, url: &str) -> Result<PathBuf> {
+        if url.is_empty() {
+            return Err(DownloadError::EmptyUrl);
+        }
+        let path = images.path_for(url);
+
+        // One download per image, the others wait for it to be cached
+        let lock = images.fetching.entry(path.clone()).or_default().clone();
+        let guard = lock.lock().await;
+        let fetched = async {
+            if tokio::fs::try_exists(&path).await? {
+                ImageCache::touch(&path).await;
+                return Ok(());
+            }
+
+            // Written aside first, so a failed download never looks cached
+            let partial = partial_path(&path, url);
+            self.download_partial(url, &partial).await?;
+
+            // Error pages served as something other than text would
+            // otherwise be handed out for good
+            if let Err(e) = Self::validate_image(&partial, &ArtworkOptions::default()).await {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(e);
+            }
+            let bytes = tokio::fs::metadata(&partial).await?.len();
+            tokio::fs::rename(&partial, &path).await?;
+            images.added(bytes).await;
+            Ok(())
+        }
+        .await;
+        drop(guard);
+        images
+            .fetching
+            .remove_if(&path, |_, lock| Arc::strong_count(lock) == 2);
+
+        fetched.map(|()| path)
+    }
+
+    /// Copy the image to `partial` from `images`, downloading it only when
+    /// not cached
+    ///
+    /// Copied beside the output and renamed over it by the caller, so an
+    /// existing file hard-linked by [`Downloader::link_duplicate`] is
+    /// replaced rather than written through, which would change every linked
+    /// copy.
+    async fn copy_partial(&self, images: &ImageCache, url: &str, partial: &Path) -> Result<()> {
+        let cached = self.fetch_cached(images, url).await?;
+        if let Some(parent) = partial.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        if let Err(e) = tokio::fs::copy(&cached, partial).await {
+            let _ = tokio::fs::remove_file(partial).await;
+            return Err(e.into());
+        }
 
         Ok(())
     }
+
+    /// Replace `path` with a hard link to an identical image saved before,
+    /// returning whether it was linked
+    ///
+    /// Images are remembered by content hash, as they are saved and from the
+    /// artwork already in the folder of `path` (e.g. a show poster saved
+    /// before a restart). A remembered file that changed or is gone is
+    /// forgotten, and `path` is kept as a copy where hard links aren't
+    /// possible (e.g. across filesystems).
+    pub async fn link_duplicate(&self, path: &Path) -> Result<bool> {
+        let hash = file_hash(path).await?;
+        if let Some(folder) = path.parent() {
+            self.index_folder(folder, path).await;
+        }
+        let entry = self
+            .saved
+            .entry(hash)
+            .or_insert_with(async { path.to_path_buf() })
+            .await;
+        if entry.is_fresh() {
+            return Ok(false);
+        }
+        let original = entry.into_value();
+        if original == path {
+            return Ok(false);
+        }
+
+        // Hashed again rather than trusted, as it may have been replaced since
+        let unchanged = file_hash(&original).await.is_ok_and(|h| h == hash);
+        if !unchanged {
+            self.saved.insert(hash, path.to_path_buf()).await;
+            return Ok(false);
+        }
+
+        // Linked beside `path` and renamed over it, so it is never missing
+        let mut link = path.as_os_str().to_owned();
+        link.push(".link");
+        let link = PathBuf::from(link);
+        let _ = tokio::fs::remove_file(&link).await;
+        tokio::fs::hard_link(&original, &link).await?;
+        if let Err(e) = tokio::fs::rename(&link, path).await {
+            let _ = tokio::fs::remove_file(&link).await;
+            return Err(e.into());
+        }
+        Ok(true)
+    }
+
+    /// Remember the artwork already in `folder`, other than `path`, once
+    async fn index_folder(&self, folder: &Path, path: &Path) {
+        if self.indexed.contains_key(folder) {
+            return;
+        }
+        self.indexed.insert(folder.to_path_buf(), ()).await;
+
+        let Ok(mut entries) = tokio::fs::read_dir(folder).await else {
+            return;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let file = entry.path();
+            if file == path || ArtworkKind::of(&file).is_none() {
+                continue;
+            }
+            if let Ok(hash) = file_hash(&file).await {
+                self.saved.entry(hash).or_insert(file).await;
+            }
+        }
+    }
+
+    /// Poster and fanart of a movie collection saved in its `folder`, the
+    /// way Kodi's Movie Set Information Folder expects them
+    #[must_use]
+    pub fn plan_collection_artwork(
+        collection: &CollectionInfo,
+        folder: &Path,
+        options: &ArtworkOptions,
+    ) -> Vec<(PathBuf, String)> {
+        [
+            ("poster", &collection.poster_url, options.poster),
+            ("fanart", &collection.backdrop_url, options.fanart),
+        ]
+        .into_iter()
+        .filter(|(_, _, wanted)| *wanted)
+        .filter_map(|(name, url, _)| {
+            let url = url.as_ref()?;
+            Some((folder.join(format!("{name}.{}", options.ext(url))), url.clone()))
+        })
+        .collect()
+    }
+
+    /// Artwork files of a movie or show saved in its `folder`, with their URLs
+    ///
+    /// Extensions follow the URL, so a PNG poster is saved as `poster.png`,
+    /// unless images are converted to an [`ArtworkOptions::format`].
+    #[must_use]
+    pub fn plan_artwork(
+        metadata: &MediaMetadata,
+        folder: &Path,
+        options: &ArtworkOptions,
+    ) -> Vec<(PathBuf, String)> {
+        let images = &metadata.images;
+        let mut planned = Vec::new();
+        for (name, url, wanted) in [
+            ("poster", &images.poster, options.poster),
+            ("fanart", &images.backdrop, options.fanart),
+            ("logo", &images.logo, options.logo),
+        ] {
+            if wanted && let Some(url) = url {
+                planned.push((folder.join(format!("{name}.{}", options.ext(url))), url.clone()));
+            }
+        }
+
+        if options.season_posters && metadata.media_type != MediaType::Movie {
+            for season in &metadata.seasons {
+                let Some(ref url) = season.poster_url else {
+                    continue;
+                };
+                let name = if season.number == 0 {
+                    format!("season-specials-poster.{}", options.ext(url))
+                } else {
+                    format!("season{:02}-poster.{}", season.number, options.ext(url))
+                };
+                planned.push((folder.join(name), url.clone()));
+            }
+        }
+
+        if options.themes && metadata.media_type != MediaType::Movie {
+            let ids = &metadata.external_ids;
+            let themes = [
+                ("theme.mp3", Some(&options.theme_music_url)),
+                ("theme.mp4", options.theme_video_url.as_ref()),
+            ];
+            for (name, template) in themes {
+                if let Some(url) = template.and_then(|t| theme_url(t, ids)) {
+                    planned.push((folder.join(name), url));
+                }
+            }
+        }
+
+        if options.actor_thumbs {
+            let actors = folder.join(".actors");
+            for person in &metadata.cast {
+                let Some(ref url) = person.image_url else {
+                    continue;
+                };
+                let name = sanitize_filename(&person.name).replace(' ', "_");
+                let path = actors.join(format!("{name}.{}", options.ext(url)));
+                if !name.is_empty() && !planned.iter().any(|(p, _)| *p == path) {
+                    planned.push((path, url.clone()));
+                }
+            }
+        }
+
+        planned
+    }
+
+    /// Thumb of an episode, saved next to its `video` file
+    #[must_use]
+    pub fn episode_thumb(
+        video: &Path,
+        episode: &EpisodeInfo,
+        options: &ArtworkOptions,
+    ) -> Option<(PathBuf, String)> {
+        let url = episode.still_url.as_ref()?;
+        let stem = video.file_stem()?.to_string_lossy();
+        let name = format!("{stem}-thumb.{}", options.ext(url));
+        Some((video.with_file_name(name), url.clone()))
+    }
+
+    /// Save the artwork of `metadata` into `folder`, with the result of each file
+    ///
+    /// Existing files are kept unless [`ArtworkOptions::overwrite`] is set.
+    /// Images download concurrently, within the limits of the
+    /// [`Downloader::queue`]. Episode thumbs need the episode files, see
+    /// [`Downloader::episode_thumb`].
+    pub async fn save_artwork(
+        &self,
+        metadata: &MediaMetadata,
+        folder: &Path,
+        options: &ArtworkOptions,
+    ) -> Vec<ArtworkResult> {
+        let downloads = Self::plan_artwork(metadata, folder, options)
+            .into_iter()
+            .map(|(path, url)| async move {
+                if !options.overwrite && path.exists() {
+                    return ArtworkResult::skipped(path, url);
+                }
+                self.save_artwork_file(&url, &path, options).await
+            });
+        join_all(downloads).await
+    }
+
+    /// Download one file of the artwork, then check, convert, deduplicate
+    /// and scale it as `options` ask
+    ///
+    /// The download is checked before it replaces `path`, so an image that
+    /// isn't one, or is smaller than the minimum size, never takes the place
+    /// of an existing file. Theme songs and videos are only downloaded.
+    pub async fn save_artwork_file(
+        &self,
+        url: &str,
+        path: &Path,
+        options: &ArtworkOptions,
+    ) -> ArtworkResult {
+        let mut result = ArtworkResult {
+            path: path.to_path_buf(),
+            url: url.to_string(),
+            status: ArtworkStatus::Saved,
+            variants: Vec::new(),
+        };
+
+        let partial = partial_path(path, url);
+        let mut saved = self.save_partial(url, path, &partial).await;
+        if saved.is_ok() && !is_theme(path) {
+            saved = Self::validate_image(&partial, options).await;
+        }
+        if saved.is_ok() {
+            saved = tokio::fs::rename(&partial, path).await.map_err(Into::into);
+        }
+        if let Err(e) = saved {
+            // Kept to resume from when only the network failed
+            if !e.is_transient() {
+                let _ = tokio::fs::remove_file(&partial).await;
+            }
+            warn!("Failed to download {url} to {:?}: {e}", path);
+            result.status = ArtworkStatus::Failed(e.to_string());
+            return result;
+        }
+
+        if options.link_duplicates {
+            match self.link_duplicate(path).await {
+                Ok(true) => result.status = ArtworkStatus::Linked,
+                Ok(false) => {}
+                Err(e) => debug!("Keeping {:?} as a copy: {e}", path),
+            }
+        }
+        if is_poster(path) {
+            match Self::poster_variants(path, &options.poster_widths).await {
+                Ok(variants) => result.variants = variants,
+                Err(e) => warn!("Failed to resize {:?}: {e}", path),
+            }
+        }
+        result
+    }
+
+    /// Check that `path` holds an image of at least the minimum size, and
+    /// convert it to the [`ArtworkOptions::format`]
+    ///
+    /// Conversion writes beside `path` and renames over it, like downloads.
+    pub async fn validate_image(path: &Path, options: &ArtworkOptions) -> Result<()> {
+        let path = path.to_path_buf();
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || {
+            // Guessed from the content only, as the extension is what's in doubt
+            let file = std::io::BufReader::new(std::fs::File::open(&path)?);
+            let reader = ImageReader::new(file).with_guessed_format()?;
+            let Some(format) = reader.format() else {
+                return Err(DownloadError::NotAnImage(path.display().to_string()));
+            };
+
+            let target = options.format.filter(|f| f.image_format() != format);
+            let (width, height) = match target {
+                None => reader.into_dimensions()?,
+                Some(target) => {
+                    let image = reader.decode()?;
+                    let partial = sibling(&path, ".part");
+                    let mut file = std::io::BufWriter::new(std::fs::File::create(&partial)?);
+                    let encoded = match target {
+                        ArtworkFormat::Jpeg => image.to_rgb8().write_with_encoder(
+                            image::codecs::jpeg::JpegEncoder::new_with_quality(
+                                &mut file,
+                                options.quality.clamp(1, 100),
+                            ),
+                        ),
+                        ArtworkFormat::Webp => image.to_rgba8().write_to(&mut file, ImageFormat::WebP),
+                    };
+                    drop(file);
+                    if let Err(e) = encoded {
+                        let _ = std::fs::remove_file(&partial);
+                        return Err(e.into());
+                    }
+                    std::fs::rename(&partial, &path)?;
+                    (image.width(), image.height())
+                }
+            };
+
+            if width < options.min_width || height < options.min_height {
+                return Err(DownloadError::TooSmall { width, height });
+            }
+            Ok(())
+        })
+        .await
+        .map_err(std::io::Error::other)?
+    }
+
+    /// Where the copy of `image` scaled to `width` pixels is saved
+    #[must_use]
+    pub fn variant_path(image: &Path, width: u32) -> PathBuf {
+        let stem = image.file_stem().unwrap_or_default().to_string_lossy();
+        let mut name = format!("{stem}-w{width}");
+        if let Some(ext) = image.extension() {
+            name.push('.');
+            name.push_str(&ext.to_string_lossy());
+        }
+        image.with_file_name(name)
+    }
+
+    /// Save copies of `image` scaled down to each of `widths`, keeping the
+    /// aspect ratio and format, returning the files written
+    ///
+    /// Widths not smaller than the image are skipped, as upscaling saves no
+    /// bandwidth.
+    pub async fn poster_variants(image: &Path, widths: &[u32]) -> Result<Vec<PathBuf>> {
+        if widths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let image = image.to_path_buf();
+        let widths = widths.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let original = image::ImageReader::open(&image)?
+                .with_guessed_format()?
+                .decode()?;
+            let mut written = Vec::new();
+            for width in widths {
+                if width == 0 || width >= original.width() {
+                    continue;
+                }
+                let height = (u64::from(original.height()) * u64::from(width)
+                    / u64::from(original.width()))
+                .max(1);
+                let height = u32::try_from(height).unwrap_or(u32::MAX);
+                let path = Self::variant_path(&image, width);
+                // Written aside and renamed over it, like downloads
+                let format = ImageFormat::from_path(&path)?;
+                let partial = sibling(&path, ".part");
+                let resized = original.resize_exact(width, height, FilterType::Lanczos3);
+                if let Err(e) = resized.save_with_format(&partial, format) {
+                    let _ = std::fs::remove_file(&partial);
+                    return Err(e.into());
+                }
+                std::fs::rename(&partial, &path)?;
+                written.push(path);
+            }
+            Ok(written)
+        })
+        .await
+        .map_err(std::io::Error::other)?
+    }
+}
+
+/// Posters and backdrops kept on disk by URL, so artwork is downloaded once
+/// by a [`Downloader`] using it
+///
+/// Files live at `<dir>/<2 hex chars>/<blake3 of the URL>.<ext>`. Artwork URLs
+/// of providers are immutable (a new image gets a new path), so entries don't
+/// expire; once the cache holds more than its limit, the images used least
+/// recently are removed. Clones share the limit.
+#[derive(Debug, Clone)]
+pub struct ImageCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Bytes stored, [`UNKNOWN_USAGE`] until the folder is first measured
+    usage: Arc<AtomicU64>,
+    /// Images being downloaded, so concurrent fetches of one share it
+    fetching: Arc<DashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+/// [`ImageCache::usage`] before the cache folder was measured
+const UNKNOWN_USAGE: u64 = u64::MAX;
+
+impl ImageCache {
+    /// Default limit on the bytes kept, 2 GiB
+    pub const DEFAULT_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+    /// Create a cache stored in `dir`, created on the first download
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: Self::DEFAULT_MAX_BYTES,
+            usage: Arc::new(AtomicU64::new(UNKNOWN_USAGE)),
+            fetching: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Keep at most `max_bytes` of images (0 for no limit)
+    #[must_use]
+    pub const fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Mark a cached image as just used, so it is removed last
+    async fn touch(path: &Path) {
+        let path = path.to_path_buf();
+        let _ = tokio::task::spawn_blocking(move || {
+            std::fs::File::options()
+                .write(true)
+                .open(path)?
+                .set_modified(std::time::SystemTime::now())
+        })
+        .await;
+    }
+
+    /// Count `bytes` just stored, removing the least recently used images
+    /// when the cache grew past its limit
+    async fn added(&self, bytes: u64) {
+        if self.max_bytes == 0 {
+            return;
+        }
+        let usage = self.usage.load(Ordering::Relaxed);
+        if usage != UNKNOWN_USAGE {
+            let usage = self.usage.fetch_add(bytes, Ordering::Relaxed) + bytes;
+            if usage <= self.max_bytes {
+                return;
+            }
+        }
+
+        let (dir, max_bytes) = (self.dir.clone(), self.max_bytes);
+        match tokio::task::spawn_blocking(move || Self::prune(&dir, max_bytes)).await {
+            Ok(usage) => self.usage.store(usage, Ordering::Relaxed),
+            Err(e) => warn!("Failed to prune the image cache: {e}"),
+        }
+    }
+
+    /// Remove the least recently used images until the cache is a tenth
+    /// below `max_bytes`, returning the bytes left
+    fn prune(dir: &Path, max_bytes: u64) -> u64 {
+        let mut images: Vec<_> = walkdir::WalkDir::new(dir)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            // Downloads still in progress
+            .filter(|entry| {
+                entry
+                    .path()
+                    .extension()
+                    .is_none_or(|ext| !ext.to_string_lossy().starts_with("part"))
+            })
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                Some((meta.modified().ok()?, meta.len(), entry.into_path()))
+            })
+            .collect();
+        let mut usage: u64 = images.iter().map(|(_, len, _)| len).sum();
+        if usage <= max_bytes {
+            return usage;
+        }
+
+        let target = max_bytes - max_bytes / 10;
+        images.sort_by_key(|(modified, _, _)| *modified);
+        for (_, len, path) in images {
+            if usage <= target {
+                break;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => usage -= len,
+                Err(e) => debug!("Failed to evict {:?}: {e}", path),
+            }
+        }
+        usage
+    }
+
+    /// Where the image of `url` is, or will be, stored
+    #[must_use]
+    pub fn path_for(&self, url: &str) -> PathBuf {
+        let hash = blake3::hash(url.as_bytes()).to_hex();
+        self.dir
+            .join(&hash[..2])
+            .join(format!("{hash}.{}", image_ext(url)))
+    }
+}
+
+/// BLAKE3 hash of a file, read in chunks off the runtime
+async fn file_hash(path: &Path) -> std::io::Result<blake3::Hash> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_reader(std::fs::File::open(path)?)?;
+        Ok(hasher.finalize())
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+/// Where a download of `url` to `path` is written until complete, then
+/// renamed to `path`
+///
+/// Named after the URL, so what a failed download left behind is resumed by
+/// the next download of the same URL, never of another one.
+fn partial_path(path: &Path, url: &str) -> PathBuf {
+    let hash = blake3::hash(url.as_bytes()).to_hex();
+    sibling(path, &format!(".{}.part", &hash[..8]))
+}
+
+/// `path` with `suffix` appended to its file name
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Theme URL from a template, or `None` when the show lacks an ID it uses
+fn theme_url(template: &str, ids: &ExternalIds) -> Option<String> {
+    let mut url = template.to_string();
+    for (placeholder, id) in [
+        ("{tvdb}", &ids.tvdb),
+        ("{tmdb}", &ids.tmdb),
+        ("{imdb}", &ids.imdb),
+        ("{anidb}", &ids.anidb),
+        ("{anilist}", &ids.anilist),
+    ] {
+        if url.contains(placeholder) {
+            url = url.replace(placeholder, id.as_deref()?);
+        }
+    }
+    (!url.is_empty()).then_some(url)
+}
+
+/// What a file planned by [`Downloader::plan_artwork`] shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ArtworkKind {
+    Poster,
+    Fanart,
+    Logo,
+    /// Poster of a season, 0 for specials
+    SeasonPoster(i32),
+}
+
+impl ArtworkKind {
+    /// Kind of the artwork file at `path`, from the names artwork is saved as
+    pub(super) fn of(path: &Path) -> Option<Self> {
+        let stem = path.file_stem()?.to_str()?;
+        match stem {
+            "poster" => Some(Self::Poster),
+            "fanart" => Some(Self::Fanart),
+            "logo" => Some(Self::Logo),
+            "season-specials-poster" => Some(Self::SeasonPoster(0)),
+            _ => {
+                let number = stem.strip_prefix("season")?.strip_suffix("-poster")?;
+                number.parse().ok().map(Self::SeasonPoster)
+            }
+        }
+    }
+}
+
+/// Whether `path` is a `theme.mp3` or `theme.mp4` rather than an image
+fn is_theme(path: &Path) -> bool {
+    path.file_stem().is_some_and(|stem| stem == "theme")
+}
+
+/// Whether `path` is a poster, which gets [`ArtworkOptions::poster_widths`]
+pub(super) fn is_poster(path: &Path) -> bool {
+    path.file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().ends_with("poster"))
+}
+
+/// Image file extension from its URL, defaulting to jpg
+pub(super) fn image_ext(url: &str) -> &str {
+    url.rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        .filter(|ext| matches!(*ext, "jpg" | "jpeg" | "png" | "webp"))
+        .unwrap_or("jpg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_image_cache_hit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ImageCache::new(dir.path().join("images"));
+        let downloader = Downloader::default().with_image_cache(cache.clone());
+        // Nothing listens there, so only a cached copy can succeed
+        let url = "http://127.0.0.1:9/t/p/w500/poster.png";
+
+        let path = cache.path_for(url);
+        assert_eq!(path, cache.path_for(url));
+        assert_eq!(path.extension().unwrap(), "png");
+        assert!(downloader.fetch_cached(&cache, url).await.is_err());
+
+        tokio::fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        tokio::fs::write(&path, b"image").await.unwrap();
+
+        let output = dir.path().join("movie/poster.png");
+        downloader.save_image(url, &output).await.unwrap();
+        assert_eq!(tokio::fs::read(&output).await.unwrap(), b"image");
+    }
+
+    #[tokio::test]
+    async fn test_image_cache_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ImageCache::new(dir.path()).with_max_bytes(12);
+        let now = std::time::SystemTime::now();
+
+        let mut images = Vec::new();
+        for (i, age) in [30, 20, 10].into_iter().enumerate() {
+            let path = cache.path_for(&format!("https://image.tmdb.org/{i}.jpg"));
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"image").unwrap();
+            let modified = now - Duration::from_secs(age);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+            images.push(path);
+        }
+
+        // The oldest image was used again, so the next one goes instead
+        ImageCache::touch(&images[0]).await;
+        cache.added(5).await;
+        assert!(images[0].exists());
+        assert!(!images[1].exists());
+        assert!(images[2].exists());
+        assert_eq!(cache.usage.load(Ordering::Relaxed), 10);
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes() {
+        use tokio::io::AsyncReadExt;
+
+        // Cuts the first response off halfway, then serves the requested range
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/poster.jpg", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut ranges = Vec::new();
+            for _ in 0..2 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let range = request
+                    .lines()
+                    .find_map(|l| l.strip_prefix("range: bytes="))
+                    .map(|r| r.trim_end_matches('-').parse::<usize>().unwrap());
+                let response = match range {
+                    Some(offset) => format!(
+                        "HTTP/1.1 206 Partial Content\r\ncontent-length: {}\r\n\r\n{}",
+                        10 - offset,
+                        &"0123456789"[offset..]
+                    ),
+                    None => "HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\n01234".to_string(),
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+                ranges.push(range);
+            }
+            ranges
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("poster.jpg");
+        let downloader = Downloader::default();
+        downloader.download_image(&url, &output).await.unwrap();
+
+        assert_eq!(tokio::fs::read(&output).await.unwrap(), b"0123456789");
+        assert_eq!(server.await.unwrap(), [None, Some(5)]);
+        assert!(!partial_path(&output, &url).exists());
+        assert!(matches!(
+            downloader.download_image("", &output).await,
+            Err(DownloadError::EmptyUrl)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_after_restart() {
+        use tokio::io::AsyncReadExt;
+
+        // Only serves the rest of the file
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/poster.jpg", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let request = String::from_utf8_lossy(&request).to_lowercase();
+            let response = "HTTP/1.1 206 Partial Content\r\ncontent-length: 5\r\n\r\n56789";
+            socket.write_all(response.as_bytes()).await.unwrap();
+            request.contains("range: bytes=5-")
+        });
+
+        // Left behind by a download that failed before the restart
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("poster.jpg");
+        tokio::fs::write(partial_path(&output, &url), b"01234").await.unwrap();
+        tokio::fs::write(partial_path(&output, "https://other/poster.jpg"), b"xx")
+            .await
+            .unwrap();
+
+        Downloader::default().download_image(&url, &output).await.unwrap();
+        assert!(server.await.unwrap());
+        assert_eq!(tokio::fs::read(&output).await.unwrap(), b"0123456789");
+    }
+
+    #[tokio::test]
+    async fn test_download_queue_limits() {
+        let queue = DownloadQueue::new(2, 1);
+        let wait = Duration::from_millis(20);
+
+        let first = queue.acquire("https://image.tmdb.org/t/p/a.jpg").await;
+        // Same host, case aside: waits for the first download
+        let same_host = queue.acquire("https://IMAGE.tmdb.org/t/p/b.jpg");
+        assert!(tokio::time::timeout(wait, same_host).await.is_err());
+
+        let _other = queue.acquire("https://artworks.thetvdb.com/c.jpg").await;
+        // Both overall slots taken
+        let third = queue.acquire("https://s4.anilist.co/d.jpg");
+        assert!(tokio::time::timeout(wait, third).await.is_err());
+
+        drop(first);
+        let next = queue.acquire("https://image.tmdb.org/t/p/b.jpg");
+        let next = tokio::time::timeout(wait, next).await.unwrap();
+
+        // Hosts nothing is downloading from are forgotten
+        drop(next);
+        assert!(!queue.hosts.contains_key("image.tmdb.org"));
+        assert!(queue.hosts.contains_key("artworks.thetvdb.com"));
+    }
+
+    /// Serve `body` as `image/jpeg` to each of `requests` connections
+    async fn serve(body: &'static [u8], requests: usize) -> String {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/poster.jpg", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for _ in 0..requests {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: image/jpeg\r\ncontent-length: {}\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body).await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_rejected_download_keeps_existing_artwork() {
+        let url = serve(b"<html>502 Bad Gateway</html>", 2).await;
+        let dir = tempfile::tempdir().unwrap();
+        let poster = dir.path().join("poster.jpg");
+        tokio::fs::write(&poster, b"existing").await.unwrap();
+        let options = ArtworkOptions {
+            overwrite: true,
+            ..ArtworkOptions::default()
+        };
+
+        let downloader = Downloader::default();
+        let result = downloader.save_artwork_file(&url, &poster, &options).await;
+        assert!(matches!(result.status, ArtworkStatus::Failed(_)));
+        assert_eq!(tokio::fs::read(&poster).await.unwrap(), b"existing");
+        assert!(!partial_path(&poster, &url).exists());
+
+        // Nor does the cache keep it
+        let cache = ImageCache::new(dir.path().join("images"));
+        let fetched = downloader.fetch_cached(&cache, &url).await;
+        assert!(matches!(fetched, Err(DownloadError::NotAnImage(_))));
+        assert!(!cache.path_for(&url).exists());
+    }
+
+    #[tokio::test]
+    async fn test_poster_variants() {
+        let dir = tempfile::tempdir().unwrap();
+        let poster = dir.path().join("poster.png");
+        image::RgbImage::new(800, 1200).save(&poster).unwrap();
+
+        let written = Downloader::poster_variants(&poster, &[300, 600, 1000])
+            .await
+            .unwrap();
+        assert_eq!(
+            written,
+            [dir.path().join("poster-w300.png"), dir.path().join("poster-w600.png")]
+        );
+        let small = image::image_dimensions(&written[0]).unwrap();
+        assert_eq!(small, (300, 450));
+        assert!(!dir.path().join("poster-w300.png.part").exists());
+
+        assert!(is_poster(&poster));
+        assert_eq!(ArtworkKind::of(&poster), Some(ArtworkKind::Poster));
+        assert_eq!(
+            ArtworkKind::of(Path::new("Show/season02-poster.webp")),
+            Some(ArtworkKind::SeasonPoster(2))
+        );
+        assert_eq!(ArtworkKind::of(Path::new("poster-w300.jpg")), None);
+        assert!(is_poster(Path::new("season01-poster.jpg")));
+        assert!(!is_poster(Path::new("fanart.jpg")));
+    }
+
+    #[tokio::test]
+    async fn test_validate_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let poster = dir.path().join("poster.jpg");
+        let options = ArtworkOptions {
+            min_width: 100,
+            min_height: 100,
+            format: Some(ArtworkFormat::Jpeg),
+            ..ArtworkOptions::default()
+        };
+
+        tokio::fs::write(&poster, "<html>502 Bad Gateway</html>").await.unwrap();
+        let result = Downloader::validate_image(&poster, &options).await;
+        assert!(matches!(result, Err(DownloadError::NotAnImage(_))));
+
+        image::RgbImage::new(50, 75)
+            .save_with_format(&poster, ImageFormat::Png)
+            .unwrap();
+        let result = Downloader::validate_image(&poster, &options).await;
+        assert!(matches!(result, Err(DownloadError::TooSmall { width: 50, height: 75 })));
+
+        // A PNG behind a .jpg URL is converted to what its name says
+        image::RgbImage::new(200, 300)
+            .save_with_format(&poster, ImageFormat::Png)
+            .unwrap();
+        Downloader::validate_image(&poster, &options).await.unwrap();
+        let bytes = tokio::fs::read(&poster).await.unwrap();
+        assert_eq!(image::guess_format(&bytes).unwrap(), ImageFormat::Jpeg);
+        assert!(!dir.path().join("poster.jpg.part").exists());
+    }
+
+    #[tokio::test]
+    async fn test_link_duplicate() {
+        let dir = tempfile::tempdir().unwrap();
+        let show = dir.path().join("poster.jpg");
+        let season = dir.path().join("season01-poster.jpg");
+        let other = dir.path().join("season02-poster.jpg");
+        let content = format!("poster {:?}", dir.path());
+
+        // Saved one after another, as the artwork pipeline does
+        let downloader = Downloader::default();
+        tokio::fs::write(&show, &content).await.unwrap();
+        assert!(!downloader.link_duplicate(&show).await.unwrap());
+        tokio::fs::write(&season, &content).await.unwrap();
+        assert!(downloader.link_duplicate(&season).await.unwrap());
+        tokio::fs::write(&other, "another poster").await.unwrap();
+        assert!(!downloader.link_duplicate(&other).await.unwrap());
+        assert_eq!(tokio::fs::read_to_string(&season).await.unwrap(), content);
+        assert!(!dir.path().join("season01-poster.jpg.link").exists());
+
+        // Artwork already in the folder counts after a restart too
+        let restarted = Downloader::default();
+        let third = dir.path().join("season03-poster.jpg");
+        tokio::fs::write(&third, "another poster").await.unwrap();
+        assert!(restarted.link_duplicate(&third).await.unwrap());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let show = std::fs::metadata(&show).unwrap();
+            let season = std::fs::metadata(&season).unwrap();
+            assert_eq!(show.ino(), season.ino());
+            assert_eq!(season.nlink(), 2);
+        }
+    }
+
+    #[test]
+    fn test_plan_artwork() {
+        assert_eq!(image_ext("https://image.tmdb.org/t/p/original/abc.png"), "png");
+        assert_eq!(image_ext("https://example.com/poster"), "jpg");
+
+        let mut metadata = MediaMetadata {
+            media_type: MediaType::Tv,
+            ..Default::default()
+        };
+        metadata.images.poster = Some("https://image.tmdb.org/t/p/w500/p.jpg".to_string());
+        metadata.images.logo = Some("https://image.tmdb.org/t/p/original/l.png".to_string());
+        for number in [0, 1] {
+            metadata.seasons.push(crate::scraper::SeasonInfo {
+                number,
+                name: None,
+                overview: None,
+                air_date: None,
+                episode_count: None,
+                poster_url: Some(format!("https://image.tmdb.org/t/p/w500/s{number}.jpg")),
+            });
+        }
+
+        let folder = Path::new("Show");
+        let names = |options: &ArtworkOptions| {
+            Downloader::plan_artwork(&metadata, folder, options)
+                .into_iter()
+                .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(&ArtworkOptions::default()),
+            ["poster.jpg", "logo.png", "season-specials-poster.jpg", "season01-poster.jpg"]
+        );
+        let posters_only = ArtworkOptions {
+            logo: false,
+            season_posters: false,
+            ..ArtworkOptions::default()
+        };
+        assert_eq!(names(&posters_only), ["poster.jpg"]);
+
+        let themes = ArtworkOptions {
+            themes: true,
+            theme_video_url: Some("https://themes.example/{anidb}/video.mp4".to_string()),
+            ..posters_only.clone()
+        };
+        assert_eq!(names(&themes), ["poster.jpg"]);
+        metadata.external_ids.tvdb = Some("81189".to_string());
+        let planned = Downloader::plan_artwork(&metadata, folder, &themes);
+        assert_eq!(planned[1].0, folder.join("theme.mp3"));
+        assert_eq!(planned[1].1, "https://tvthemes.plexapp.com/81189.mp3");
+        assert_eq!(planned.len(), 2);
+        assert!(is_theme(&planned[1].0));
+
+        for (name, image) in [("Aoi Yūki", Some("a.jpg")), ("AC/DC", Some("b.png")), ("Nobody", None)] {
+            metadata.cast.push(crate::scraper::PersonInfo {
+                id: name.to_string(),
+                name: name.to_string(),
+                role: None,
+                image_url: image.map(|i| format!("https://image.tmdb.org/t/p/w185/{i}")),
+                order: None,
+            });
+        }
+        let actors = ArtworkOptions {
+            actor_thumbs: true,
+            ..posters_only
+        };
+        let planned = Downloader::plan_artwork(&metadata, folder, &actors);
+        let paths: Vec<_> = planned.iter().map(|(path, _)| path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("Show/poster.jpg"),
+                Path::new("Show/.actors/Aoi_Yūki.jpg"),
+                Path::new("Show/.actors/AC_DC.png"),
+            ]
+        );
+
+        let episode = EpisodeInfo {
+            id: "1".to_string(),
+            title: "Pilot".to_string(),
+            season: 1,
+            episode: 1,
+            absolute_number: None,
+            original_title: None,
+            air_date: None,
+            overview: None,
+            runtime: None,
+            rating: None,
+            still_url: Some("https://image.tmdb.org/t/p/w300/e.jpg".to_string()),
+            provider: "tmdb".to_string(),
+        };
+        let video = Path::new("Show/Season 01/Show - S01E01.mkv");
+        let (thumb, _) =
+            Downloader::episode_thumb(video, &episode, &ArtworkOptions::default()).unwrap();
+        assert_eq!(thumb, Path::new("Show/Season 01/Show - S01E01-thumb.jpg"));
+
+        let webp = ArtworkOptions {
+            format: Some(ArtworkFormat::Webp),
+            ..ArtworkOptions::default()
+        };
+        let (thumb, _) = Downloader::episode_thumb(video, &episode, &webp).unwrap();
+        assert_eq!(thumb, Path::new("Show/Season 01/Show - S01E01-thumb.webp"));
+    }
 }
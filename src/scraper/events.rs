@@ -0,0 +1,166 @@
+use crate::scraper::{matcher::ScoredMatch, types::MediaMetadata};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::broadcast;
+
+tokio::task_local! {
+    static SCRAPE: u64;
+}
+
+/// Step of a scrape, as seen by subscribers of [`ScrapeEvents`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrapeEvent {
+    /// Scrape the step belongs to; the same for every event of one file
+    pub scrape: u64,
+    pub time: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: ScrapeEventKind,
+}
+
+/// What happened in a scrape
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScrapeEventKind {
+    /// Reading title, year and episode from a file name
+    Parsing { path: String },
+    /// Asking one provider for matches, or finding its answer cached
+    Searching { query: String, provider: String },
+    /// Best match chosen
+    Matched {
+        title: String,
+        provider: String,
+        id: String,
+        score: i32,
+        confidence: String,
+    },
+    /// Fetching full metadata, from the match's provider or a fallback
+    FetchingMetadata { provider: String, id: String },
+    /// Finished; without a provider and ID when nothing was matched
+    Done {
+        title: String,
+        provider: Option<String>,
+        id: Option<String>,
+    },
+    Failed { error: String },
+}
+
+impl ScrapeEventKind {
+    pub(crate) fn matched(m: &ScoredMatch) -> Self {
+        Self::Matched {
+            title: m.info.title.clone(),
+            provider: m.info.provider.clone(),
+            id: m.info.id.clone(),
+            score: m.score,
+            confidence: format!("{:?}", m.confidence),
+        }
+    }
+
+    pub(crate) fn done(metadata: &MediaMetadata) -> Self {
+        Self::Done {
+            title: metadata.title.clone(),
+            provider: Some(metadata.provider.clone()),
+            id: Some(metadata.id.clone()),
+        }
+    }
+}
+
+/// Broadcasts the steps of scrapes to whoever is listening
+///
+/// Events are only sent from within [`ScrapeEvents::track`], which tells
+/// one scrape's events from another's, and dropped when nobody subscribed.
+/// Subscribers that fall more than [`ScrapeEvents::CAPACITY`] events behind
+/// miss the oldest ones.
+#[derive(Debug)]
+pub struct ScrapeEvents {
+    sender: broadcast::Sender<ScrapeEvent>,
+    next: AtomicU64,
+}
+
+impl ScrapeEvents {
+    /// Events kept for slow subscribers
+    pub const CAPACITY: usize = 256;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(Self::CAPACITY).0,
+            next: AtomicU64::new(1),
+        }
+    }
+
+    /// Receive events of every scrape from now on
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ScrapeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Run `task` as one scrape, its events sharing an ID
+    ///
+    /// Nested calls join the scrape already running.
+    pub async fn track<F: Future>(&self, task: F) -> F::Output {
+        if SCRAPE.try_with(|_| ()).is_ok() {
+            return task.await;
+        }
+        let scrape = self.next.fetch_add(1, Ordering::Relaxed);
+        SCRAPE.scope(scrape, task).await
+    }
+
+    /// Send an event of the scrape the current task runs
+    pub fn emit(&self, kind: ScrapeEventKind) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        if let Ok(scrape) = SCRAPE.try_with(|scrape| *scrape) {
+            // Nobody listening anymore is fine
+            let _ = self.sender.send(ScrapeEvent {
+                scrape,
+                time: Utc::now(),
+                kind,
+            });
+        }
+    }
+}
+
+impl Default for ScrapeEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_events_grouped_by_scrape() {
+        let events = ScrapeEvents::new();
+        let mut rx = events.subscribe();
+        let failed = |error: &str| ScrapeEventKind::Failed {
+            error: error.to_string(),
+        };
+
+        // Outside a scrape nothing is sent
+        events.emit(failed("untracked"));
+        events
+            .track(async {
+                events.emit(failed("first"));
+                events.track(async { events.emit(failed("nested")) }).await;
+            })
+            .await;
+        events.track(async { events.emit(failed("second")) }).await;
+
+        let first = rx.recv().await.unwrap();
+        let nested = rx.recv().await.unwrap();
+        let second = rx.recv().await.unwrap();
+        assert_eq!(first.kind, failed("first"));
+        assert_eq!(nested.scrape, first.scrape);
+        assert_ne!(second.scrape, first.scrape);
+        assert!(rx.try_recv().is_err());
+
+        let json = serde_json::to_value(&second).unwrap();
+        assert_eq!(json["event"], "failed");
+        assert_eq!(json["error"], "second");
+    }
+}
@@ -0,0 +1,151 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Ignore files honored in any scanned directory
+pub const IGNORE_FILES: &[&str] = &[".ayiahignore", ".plexignore"];
+
+/// Exclude rules for a scan: configured globs plus ignore files found on the way
+///
+/// Patterns follow `.plexignore`: one glob per line, `#` starts a comment (write
+/// `\#recycle` for a name starting with `#`; configured patterns need no escape). A
+/// pattern without `/` matches a file or folder name anywhere below the folder
+/// it applies to (`@eaDir`, `*.part`); one with `/` matches the path relative to
+/// that folder (`Seeding/*`). An ignored folder is skipped with everything in it.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    root: PathBuf,
+    excludes: GlobSet,
+    /// Rules from ignore files, keyed by the folder holding them
+    files: HashMap<PathBuf, GlobSet>,
+}
+
+impl IgnoreRules {
+    /// Rules for a scan of `root`, honoring only ignore files
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            excludes: GlobSet::empty(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Add exclude patterns, relative to the scan root
+    pub fn with_excludes<S: AsRef<str>>(mut self, patterns: &[S]) -> Result<Self, globset::Error> {
+        self.excludes = build_globs(patterns.iter().map(AsRef::as_ref), false)?;
+        Ok(self)
+    }
+
+    /// Check exclude patterns without building a rule set
+    pub fn validate<S: AsRef<str>>(patterns: &[S]) -> Result<(), globset::Error> {
+        build_globs(patterns.iter().map(AsRef::as_ref), false).map(|_| ())
+    }
+
+    /// Whether a walked entry should be scanned
+    ///
+    /// Meant as a `WalkDir::filter_entry` predicate: folders must be visited
+    /// before their contents so their ignore files are picked up.
+    pub fn allows(&mut self, path: &Path, is_dir: bool) -> bool {
+        if path == self.root {
+            self.load(path);
+            return true;
+        }
+
+        if let Ok(relative) = path.strip_prefix(&self.root)
+            && self.excludes.is_match(relative)
+        {
+            return false;
+        }
+
+        let ignored = path.ancestors().skip(1).any(|dir| {
+            self.files.get(dir).is_some_and(|globs| {
+                path.strip_prefix(dir)
+                    .is_ok_and(|relative| globs.is_match(relative))
+            })
+        });
+        if ignored {
+            return false;
+        }
+
+        if is_dir {
+            self.load(path);
+        }
+        true
+    }
+
+    /// Read the ignore files of a folder, if any
+    fn load(&mut self, dir: &Path) {
+        let lines: Vec<String> = IGNORE_FILES
+            .iter()
+            .filter_map(|name| fs::read_to_string(dir.join(name)).ok())
+            .flat_map(|content| content.lines().map(str::to_string).collect::<Vec<_>>())
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+
+        match build_globs(lines.iter().map(String::as_str), true) {
+            Ok(globs) => {
+                self.files.insert(dir.to_path_buf(), globs);
+            }
+            Err(e) => warn!("Invalid ignore file in {}: {}", dir.display(), e),
+        }
+    }
+}
+
+/// Compile ignore patterns, skipping blank lines and, in ignore files, comments
+fn build_globs<'a>(
+    patterns: impl Iterator<Item = &'a str>,
+    comments: bool,
+) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let pattern = pattern.trim();
+        if pattern.is_empty() || (comments && pattern.starts_with('#')) {
+            continue;
+        }
+
+        let pattern = pattern.trim_end_matches('/');
+        let pattern = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if pattern.contains('/') => pattern.to_string(),
+            None => format!("**/{pattern}"),
+        };
+
+        // Match the entry itself and, for folders, everything inside
+        builder.add(Glob::new(&pattern)?);
+        builder.add(Glob::new(&format!("{pattern}/**"))?);
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("Shows").join("Seeding")).unwrap();
+        fs::write(root.join("Shows").join(".plexignore"), "# torrents\nSeeding/*\n").unwrap();
+
+        let mut rules = IgnoreRules::new(root)
+            .with_excludes(&["@eaDir", "*.part"])
+            .unwrap();
+        assert!(rules.allows(root, true));
+        assert!(!rules.allows(&root.join("Movies").join("@eaDir"), true));
+        assert!(!rules.allows(&root.join("Movies").join("Heat.mkv.part"), false));
+        assert!(rules.allows(&root.join("Movies").join("Heat.mkv"), false));
+
+        assert!(rules.allows(&root.join("Shows"), true));
+        assert!(!rules.allows(&root.join("Shows").join("Seeding").join("ep.mkv"), false));
+        assert!(rules.allows(&root.join("Seeding").join("ep.mkv"), false));
+
+        assert!(IgnoreRules::validate(&["[unclosed"]).is_err());
+    }
+}
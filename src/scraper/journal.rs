@@ -0,0 +1,229 @@
+//! Organizer journal - records file operations so an organize run can be reverted
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use super::OrganizeMethod;
+
+/// File name of the journal, kept next to the database
+const JOURNAL_FILE_NAME: &str = "organizer_journal.jsonl";
+
+/// Held while the journal file is written, so a rewrite never loses an append
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Journal location next to the database file `database`
+#[must_use]
+pub fn journal_path_for(database: &Path) -> PathBuf {
+    database.with_file_name(JOURNAL_FILE_NAME)
+}
+
+/// A single recorded organizer operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Organize run this operation belongs to
+    pub batch_id: String,
+    pub method: OrganizeMethod,
+    pub source: PathBuf,
+    pub target: PathBuf,
+    /// Where a file previously at `target` was moved before being overwritten
+    pub backup: Option<PathBuf>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Append-only JSON lines journal of organizer operations
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Journal file path
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry
+    pub fn append(&self, entry: &JournalEntry) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+        line.push('\n');
+
+        // One write on an append handle, so lines of concurrent organizes
+        // never interleave
+        let _guard = WRITE_LOCK.lock();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+
+    /// Read all entries, skipping malformed lines
+    pub fn entries(&self) -> std::io::Result<Vec<JournalEntry>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect())
+    }
+
+    /// Entries of a single batch, in the order they were performed
+    pub fn batch(&self, batch_id: &str) -> std::io::Result<Vec<JournalEntry>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .filter(|e| e.batch_id == batch_id)
+            .collect())
+    }
+
+    /// Drop all entries of a batch
+    pub fn remove_batch(&self, batch_id: &str) -> std::io::Result<()> {
+        self.retain(|e| e.batch_id != batch_id)
+    }
+
+    /// Drop the batches last written to before `cutoff`, deleting the files
+    /// they replaced, which only a rollback would have put back
+    ///
+    /// Returns how many batches were dropped.
+    pub fn prune(&self, cutoff: DateTime<Utc>) -> std::io::Result<usize> {
+        let mut newest: HashMap<String, DateTime<Utc>> = HashMap::new();
+        for entry in self.entries()? {
+            let at = newest.entry(entry.batch_id).or_insert(entry.created_at);
+            *at = (*at).max(entry.created_at);
+        }
+        let expired: HashSet<String> = newest
+            .into_iter()
+            .filter(|(_, at)| *at < cutoff)
+            .map(|(batch_id, _)| batch_id)
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let mut backups = Vec::new();
+        self.retain(|e| {
+            let keep = !expired.contains(&e.batch_id);
+            if !keep {
+                backups.extend(e.backup.clone());
+            }
+            keep
+        })?;
+
+        // Only once no entry points at them anymore
+        for backup in backups {
+            match fs::remove_file(&backup) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    warn!("Failed to remove replaced file {:?}: {}", backup, e);
+                }
+                _ => {}
+            }
+        }
+        Ok(expired.len())
+    }
+
+    /// Keep only the entries `keep` returns true for
+    ///
+    /// The journal is rewritten to a temporary file which then replaces it,
+    /// so a crash leaves either the old or the new journal behind.
+    pub fn retain(&self, keep: impl FnMut(&JournalEntry) -> bool) -> std::io::Result<()> {
+        let _guard = WRITE_LOCK.lock();
+        let mut remaining = self.entries()?;
+        remaining.retain(keep);
+
+        let dir = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        for entry in &remaining {
+            let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+            writeln!(file, "{line}")?;
+        }
+        file.as_file().sync_all()?;
+        file.persist(&self.path).map_err(|e| e.error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_appends_keep_lines_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path().join("journal.jsonl"));
+
+        std::thread::scope(|scope| {
+            for worker in 0..8 {
+                let journal = &journal;
+                scope.spawn(move || {
+                    for i in 0..50 {
+                        let entry = JournalEntry {
+                            batch_id: format!("batch-{worker}"),
+                            method: OrganizeMethod::Move,
+                            source: PathBuf::from(format!("/in/{worker}/{i}.mkv")),
+                            target: PathBuf::from(format!("/out/{worker}/{i}.mkv")),
+                            backup: None,
+                            created_at: Utc::now(),
+                        };
+                        journal.append(&entry).unwrap();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(journal.entries().unwrap().len(), 400);
+        assert_eq!(journal.batch("batch-3").unwrap().len(), 50);
+    }
+
+    #[test]
+    fn test_prune_drops_old_batches_and_their_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path().join("journal.jsonl"));
+        let backup = dir.path().join("Heat (1995).mkv.orig");
+        fs::write(&backup, b"replaced").unwrap();
+
+        let now = Utc::now();
+        let entry = |batch_id: &str, backup: Option<PathBuf>, days_ago| JournalEntry {
+            batch_id: batch_id.to_string(),
+            method: OrganizeMethod::Copy,
+            source: PathBuf::from("/in/Heat.1995.mkv"),
+            target: dir.path().join("Heat (1995).mkv"),
+            backup,
+            created_at: now - chrono::Duration::days(days_ago),
+        };
+        journal.append(&entry("old", Some(backup.clone()), 40)).unwrap();
+        journal.append(&entry("recent", None, 40)).unwrap();
+        journal.append(&entry("recent", None, 1)).unwrap();
+
+        let dropped = journal.prune(now - chrono::Duration::days(30)).unwrap();
+
+        assert_eq!(dropped, 1);
+        assert!(!backup.exists());
+        assert!(journal.batch("old").unwrap().is_empty());
+        assert_eq!(journal.batch("recent").unwrap().len(), 2);
+    }
+}
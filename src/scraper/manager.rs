@@ -1,13 +1,29 @@
 use crate::scraper::{
     Result, ScraperError,
+    budget::ScrapeBudget,
     cache::ScraperCache,
-    matcher::{Confidence, Matcher, ScoredMatch},
+    downloader::{DownloadQueue, Downloader, ImageCache},
+    events::{ScrapeEvent, ScrapeEventKind, ScrapeEvents},
+    matcher::{Confidence, EpisodeMatch, Matcher, ScoreBreakdown, ScoredMatch},
+    merge::MergeRules,
     parser::{MediaHint, ParsedMedia, Parser},
-    provider::{MetadataProvider, SearchOptions},
-    types::{EpisodeInfo, MediaInfo, MediaMetadata, MediaType},
+    scanner::VIDEO_EXTENSIONS,
+    provider::{
+        DiscoverOptions, MetadataProvider, ProviderHealth, RateLimitedProvider, SearchOptions,
+    },
+    strategy::{MatchStrategy, MatchStrategyKind},
+    trailer::TrailerDownloader,
+    types::{EpisodeInfo, MediaInfo, MediaMetadata, MediaType, SeasonInfo, WatchAvailability},
 };
-use std::path::Path;
+use dashmap::{DashMap, DashSet};
+use futures::Stream;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 /// Scraper manager configuration
@@ -21,6 +37,30 @@ pub struct ScraperConfig {
     pub use_cache: bool,
     /// Default language for searches
     pub language: Option<String>,
+    /// Languages blank titles and overviews are fetched again in, most
+    /// preferred first, before falling back to the original language
+    pub languages: Vec<String>,
+    /// Strategy used to score search results
+    pub match_strategy: MatchStrategyKind,
+    /// Calls per second allowed to a provider, by provider ID
+    pub rate_limits: HashMap<String, f64>,
+    /// How long a provider gets to answer a search before it's skipped
+    pub provider_timeout: Option<Duration>,
+    /// Timeouts of slower or faster providers, by provider ID
+    pub provider_timeouts: HashMap<String, Duration>,
+    /// Provider priorities replacing [`MetadataProvider::priority_for`], by
+    /// media type and provider ID
+    pub priorities: HashMap<MediaType, HashMap<String, i32>>,
+    /// Providers whose fields are merged into fetched metadata; empty keeps
+    /// the matched provider's record alone
+    pub merge: MergeRules,
+    /// Provider calls running at once across all providers
+    pub max_concurrent_calls: usize,
+    /// Of those, calls only interactive requests may make, so batch jobs
+    /// can't take every slot
+    pub interactive_reserve: usize,
+    /// Provider calls per second batch jobs may make, across all providers
+    pub background_rate: Option<f64>,
 }
 
 impl Default for ScraperConfig {
@@ -30,6 +70,16 @@ impl Default for ScraperConfig {
             max_results: 20,
             use_cache: true,
             language: None,
+            languages: Vec::new(),
+            match_strategy: MatchStrategyKind::default(),
+            rate_limits: HashMap::new(),
+            provider_timeout: Some(Duration::from_secs(10)),
+            provider_timeouts: HashMap::new(),
+            priorities: HashMap::new(),
+            merge: MergeRules::default(),
+            max_concurrent_calls: ScrapeBudget::DEFAULT_CONCURRENCY,
+            interactive_reserve: ScrapeBudget::DEFAULT_RESERVED,
+            background_rate: None,
         }
     }
 }
@@ -47,13 +97,71 @@ pub struct ScrapeResult {
     pub score: i32,
     /// Parsed filename info
     pub parsed: ParsedMedia,
+    /// Episode position resolved against the series (for episodic media)
+    pub episode: Option<EpisodeMatch>,
+    /// Provider that served `metadata`; not `info.provider` when the matched
+    /// provider failed and another one was used instead
+    pub metadata_provider: Option<String>,
+}
+
+/// How one provider's part of a search went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SearchOutcome {
+    /// Answered from the cache, a remembered miss included
+    Cached,
+    /// Answered by the provider
+    Searched,
+    NotFound,
+    /// Skipped after [`ScraperConfig::provider_timeout`]
+    TimedOut,
+    Failed { error: String },
+}
+
+/// One provider's answer to a search
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSearch {
+    pub provider: String,
+    #[serde(flatten)]
+    pub outcome: SearchOutcome,
+    /// Results it returned
+    pub results: usize,
+}
+
+/// Everything a scrape of a file or query would base its match on
+#[derive(Debug, Clone)]
+pub struct MatchExplanation {
+    /// What was read from the file name or query
+    pub parsed: ParsedMedia,
+    /// Each provider asked, in priority order
+    pub searches: Vec<ProviderSearch>,
+    /// Every candidate with its score breakdown, the winner first
+    pub candidates: Vec<ScoredMatch>,
+    /// Why the winner was chosen, or why there is none
+    pub decision: Vec<String>,
+    /// Whether the winner is sure enough for its metadata to be fetched
+    pub accepted: bool,
 }
 
 /// Main scraper manager
 pub struct ScraperManager {
     providers: Vec<Arc<dyn MetadataProvider>>,
     cache: ScraperCache,
+    /// Downloads artwork in turns, through the cache shared by everything
+    /// writing or serving images
+    downloader: Downloader,
+    /// Saves trailers of organized movies, when yt-dlp is configured
+    trailers: Option<TrailerDownloader>,
     config: ScraperConfig,
+    strategy: Arc<dyn MatchStrategy>,
+    /// Manual identify corrections, keyed by normalized parsed title
+    overrides: DashMap<String, MediaInfo>,
+    /// IDs of providers switched off at runtime
+    disabled: DashSet<String>,
+    /// Provider calls allowed at once, shared by every lookup
+    budget: ScrapeBudget,
+    /// Steps of running scrapes, for the API to forward
+    events: ScrapeEvents,
 }
 
 impl ScraperManager {
@@ -63,7 +171,14 @@ impl ScraperManager {
         Self {
             providers: Vec::new(),
             cache: ScraperCache::new(),
+            downloader: Downloader::default(),
+            trailers: None,
             config: ScraperConfig::default(),
+            strategy: MatchStrategyKind::default().build(),
+            overrides: DashMap::new(),
+            disabled: DashSet::new(),
+            budget: ScrapeBudget::default(),
+            events: ScrapeEvents::new(),
         }
     }
 
@@ -73,29 +188,272 @@ impl ScraperManager {
         Self {
             providers: Vec::new(),
             cache: ScraperCache::new(),
+            downloader: Downloader::default(),
+            trailers: None,
+            strategy: config.match_strategy.build(),
+            budget: ScrapeBudget::new(
+                config.max_concurrent_calls,
+                config.interactive_reserve,
+                config.background_rate,
+            ),
             config,
+            overrides: DashMap::new(),
+            disabled: DashSet::new(),
+            events: ScrapeEvents::new(),
         }
     }
 
-    /// Add a provider
+    /// Add a provider, rate limited if its ID is in [`ScraperConfig::rate_limits`]
     pub fn add_provider<P: MetadataProvider + 'static>(&mut self, provider: P) {
-        self.providers.push(Arc::new(provider));
+        match self.config.rate_limits.get(provider.id()) {
+            Some(&limit) if limit > 0.0 => {
+                self.providers.push(Arc::new(RateLimitedProvider::new(provider, limit)));
+            }
+            _ => self.providers.push(Arc::new(provider)),
+        }
+    }
+
+    /// Replace the cache, e.g. with one persisted by [`ScraperCache::open`]
+    pub fn set_cache(&mut self, cache: ScraperCache) {
+        self.cache = cache;
+    }
+
+    /// Keep downloaded artwork in `cache` instead of fetching it every time
+    pub fn set_image_cache(&mut self, cache: ImageCache) {
+        self.downloader = self.downloader.clone().with_image_cache(cache);
+    }
+
+    /// Cache of downloaded artwork, if one is set
+    #[must_use]
+    pub fn image_cache(&self) -> Option<&ImageCache> {
+        self.downloader.image_cache()
+    }
+
+    /// Limit artwork downloads with `queue` (e.g. with limits from the config)
+    pub fn set_download_queue(&mut self, queue: DownloadQueue) {
+        let mut downloader = Downloader::new(queue);
+        if let Some(images) = self.downloader.image_cache() {
+            downloader = downloader.with_image_cache(images.clone());
+        }
+        self.downloader = downloader;
+    }
+
+    /// Downloads artwork within the queue's limits, through the image cache
+    #[must_use]
+    pub const fn downloader(&self) -> &Downloader {
+        &self.downloader
+    }
+
+    /// Save trailers with `trailers` when artwork asks for them
+    pub fn set_trailer_downloader(&mut self, trailers: TrailerDownloader) {
+        self.trailers = Some(trailers);
+    }
+
+    /// Trailer downloader, if one is set
+    #[must_use]
+    pub fn trailer_downloader(&self) -> Option<&TrailerDownloader> {
+        self.trailers.as_ref()
     }
 
-    /// Get all providers
+    /// Replace the matching strategy (e.g. with a custom scorer)
+    pub fn set_strategy(&mut self, strategy: Arc<dyn MatchStrategy>) {
+        self.strategy = strategy;
+    }
+
+    /// Get all providers, disabled ones included
     #[must_use] 
     pub fn providers(&self) -> &[Arc<dyn MetadataProvider>] {
         &self.providers
     }
 
+    /// Switch a provider off or back on without removing it
+    ///
+    /// A disabled provider is skipped by searches, metadata fetches,
+    /// fallbacks and merges until it is enabled again. Returns `false` if no
+    /// provider has this ID.
+    pub fn set_provider_enabled(&self, id: &str, enabled: bool) -> bool {
+        if !self.providers.iter().any(|p| p.id() == id) {
+            return false;
+        }
+        if enabled {
+            self.disabled.remove(id);
+        } else {
+            info!("Disabling provider {id}");
+            self.disabled.insert(id.to_string());
+        }
+        true
+    }
+
+    /// Whether a provider is in use, i.e. not disabled
+    #[must_use]
+    pub fn is_provider_enabled(&self, id: &str) -> bool {
+        !self.disabled.contains(id)
+    }
+
+    /// Providers in use
+    fn enabled_providers(&self) -> impl Iterator<Item = &Arc<dyn MetadataProvider>> {
+        self.providers.iter().filter(|p| self.is_provider_enabled(p.id()))
+    }
+
+    /// The provider with this ID, if it is in use
+    fn provider(&self, id: &str) -> Result<&Arc<dyn MetadataProvider>> {
+        let provider = self
+            .providers
+            .iter()
+            .find(|p| p.id() == id)
+            .ok_or_else(|| ScraperError::Config(format!("Provider not found: {id}")))?;
+        if !self.is_provider_enabled(id) {
+            return Err(ScraperError::Config(format!("Provider disabled: {id}")));
+        }
+        Ok(provider)
+    }
+
+    /// Receive the steps of every scrape from now on
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ScrapeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Run `task` as one scrape, so the events of the lookups it makes share
+    /// an ID (e.g. organizing a file)
+    pub async fn track<F: Future>(&self, task: F) -> F::Output {
+        self.events.track(task).await
+    }
+
+    /// Send an event of the scrape the current task runs
+    pub(crate) fn emit(&self, kind: ScrapeEventKind) {
+        self.events.emit(kind);
+    }
+
+    /// Priority of a provider for a media type, from
+    /// [`ScraperConfig::priorities`] if set there (higher = preferred)
+    #[must_use]
+    pub fn priority_of(&self, provider: &dyn MetadataProvider, media_type: MediaType) -> i32 {
+        self.config
+            .priorities
+            .get(&media_type)
+            .and_then(|priorities| priorities.get(provider.id()))
+            .copied()
+            .unwrap_or_else(|| provider.priority_for(media_type))
+    }
+
+    /// Check every provider concurrently, in the order of [`Self::providers`]
+    pub async fn health(&self) -> Vec<ProviderHealth> {
+        futures::future::join_all(self.providers.iter().map(|p| p.health())).await
+    }
+
+    /// Remember a manual identification so future matches for this title use it
+    pub fn set_override(&self, parsed_title: &str, info: MediaInfo) {
+        self.overrides
+            .insert(Matcher::normalize_title(parsed_title), info);
+    }
+
+    /// Forget a manual identification
+    pub fn remove_override(&self, parsed_title: &str) {
+        self.overrides.remove(&Matcher::normalize_title(parsed_title));
+    }
+
+    /// Get the manual identification for a parsed title, if any
+    #[must_use]
+    pub fn get_override(&self, parsed_title: &str) -> Option<MediaInfo> {
+        self.overrides
+            .get(&Matcher::normalize_title(parsed_title))
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Put a manual identification ahead of automatic matches
+    fn apply_override(&self, mut ranked: Vec<ScoredMatch>, parsed_title: &str) -> Vec<ScoredMatch> {
+        let Some(info) = self.get_override(parsed_title) else {
+            return ranked;
+        };
+
+        debug!(
+            "Using manual match for {}: {}:{}",
+            parsed_title, info.provider, info.id
+        );
+
+        // Prefer the search result entry (richer info) when the override is among the results
+        let pos = ranked
+            .iter()
+            .position(|m| m.info.provider == info.provider && m.info.id == info.id);
+        let mut chosen = pos.map_or_else(
+            || ScoredMatch {
+                providers: vec![info.provider.clone()],
+                info,
+                score: 100,
+                confidence: Confidence::Exact,
+                breakdown: ScoreBreakdown::default(),
+                reasons: Vec::new(),
+            },
+            |pos| ranked.remove(pos),
+        );
+        chosen.score = 100;
+        chosen.confidence = Confidence::Exact;
+        chosen.reasons = vec!["Manually identified".to_string()];
+
+        ranked.insert(0, chosen);
+        ranked
+    }
+
     /// Scrape metadata for a file path
     pub async fn scrape(&self, path: &Path) -> Result<ScrapeResult> {
-        let parsed = Parser::parse(path);
-        self.scrape_parsed(&parsed).await
+        self.scrape_file(path, Parser::parse(path)).await
+    }
+
+    /// Scrape metadata for a file the caller already parsed, e.g. with the
+    /// runtime probing found
+    pub async fn scrape_file(&self, path: &Path, parsed: ParsedMedia) -> Result<ScrapeResult> {
+        self.track(async {
+            self.emit(ScrapeEventKind::Parsing {
+                path: path.display().to_string(),
+            });
+            self.scrape_parsed(&parsed).await
+        })
+        .await
+    }
+
+    /// Scrape parsed `files`, at most `concurrency` at a time, as background
+    /// work
+    ///
+    /// Results stream in the order of `files` as each one finishes, so
+    /// callers can act on them before the whole batch is done. Provider calls
+    /// stay within the budget and rate limits shared with every other lookup.
+    /// Once `cancel` fires no more files are started.
+    pub fn scrape_many<'a>(
+        &'a self,
+        files: impl IntoIterator<Item = (PathBuf, ParsedMedia), IntoIter: 'a>,
+        concurrency: usize,
+        cancel: CancellationToken,
+    ) -> impl Stream<Item = (PathBuf, Result<ScrapeResult>)> + 'a {
+        ScrapeBudget::batch(files, concurrency, cancel, move |(path, parsed)| async move {
+            let result = self.scrape_file(&path, parsed).await;
+            (path, result)
+        })
     }
 
     /// Scrape metadata using pre-parsed info
     pub async fn scrape_parsed(&self, parsed: &ParsedMedia) -> Result<ScrapeResult> {
+        self.track(async {
+            let result = self.run_scrape(parsed).await;
+            self.emit(match &result {
+                Ok(scraped) => match &scraped.metadata {
+                    Some(metadata) => ScrapeEventKind::done(metadata),
+                    None => ScrapeEventKind::Done {
+                        title: scraped.info.title.clone(),
+                        provider: Some(scraped.info.provider.clone()),
+                        id: Some(scraped.info.id.clone()),
+                    },
+                },
+                Err(e) => ScrapeEventKind::Failed {
+                    error: e.to_string(),
+                },
+            });
+            result
+        })
+        .await
+    }
+
+    async fn run_scrape(&self, parsed: &ParsedMedia) -> Result<ScrapeResult> {
         info!("Scraping: {} (hint: {:?})", parsed.title, parsed.hint);
 
         // Search all relevant providers
@@ -104,7 +462,10 @@ impl ScraperManager {
             .await?;
 
         // Rank results
-        let ranked = Matcher::rank(results, parsed);
+        let ranked = self.apply_override(
+            Matcher::rank_with(self.strategy.as_ref(), results, parsed),
+            &parsed.title,
+        );
 
         if ranked.is_empty() {
             return Err(ScraperError::NotFound(format!(
@@ -122,6 +483,7 @@ impl ScraperManager {
             "Best match: {} (score: {}, confidence: {:?})",
             best.info.title, best.score, best.confidence
         );
+        self.emit(ScrapeEventKind::matched(&best));
 
         // Fetch full metadata if confidence is high enough
         let metadata = if best.confidence >= self.config.min_confidence {
@@ -136,15 +498,153 @@ impl ScraperManager {
             None
         };
 
+        let episode = match &metadata {
+            Some(m) => self.resolve_episode(parsed, m).await,
+            None => None,
+        };
+
         Ok(ScrapeResult {
+            metadata_provider: metadata.as_ref().map(|m| m.provider.clone()),
             info: best.info,
             metadata,
             confidence: best.confidence,
             score: best.score,
             parsed: parsed.clone(),
+            episode,
         })
     }
 
+    /// Resolve the season/episode of a parsed file against series metadata
+    ///
+    /// Numbered files are tried against the season summaries first. Air
+    /// dates, and numbers the summaries can't place, need the provider's
+    /// real episode lists, fetched season by season until one matches;
+    /// date-named files start with the last season to air before the date.
+    pub async fn resolve_episode(
+        &self,
+        parsed: &ParsedMedia,
+        metadata: &MediaMetadata,
+    ) -> Option<EpisodeMatch> {
+        if metadata.media_type == MediaType::Movie || metadata.seasons.is_empty() {
+            return None;
+        }
+
+        if parsed.air_date.is_none() {
+            let episodes = Matcher::episodes_from_seasons(&metadata.seasons, &metadata.provider);
+            if let Some(episode) = Matcher::match_episode(parsed, &episodes) {
+                return Some(episode);
+            }
+        }
+
+        // A named season is the only one to look in; dates start with the
+        // season airing at the time, absolute numbers go through all in order
+        let mut seasons: Vec<i32> = metadata.seasons.iter().map(|s| s.number).collect();
+        if let Some(date) = parsed.air_date.as_deref() {
+            let mut dated: Vec<&SeasonInfo> = metadata.seasons.iter().collect();
+            dated.sort_by_key(|s| std::cmp::Reverse(s.air_date.as_deref().filter(|d| *d <= date)));
+            seasons = dated.into_iter().map(|s| s.number).collect();
+        } else if let Some(season) = parsed.season {
+            seasons = vec![season];
+        }
+
+        let mut episodes = Vec::new();
+        for season in seasons {
+            match self.get_season(&metadata.provider, &metadata.id, season).await {
+                Ok(list) => episodes.extend(list),
+                Err(e) => {
+                    debug!(
+                        "No episodes of {}:{} season {}: {}",
+                        metadata.provider, metadata.id, season, e
+                    );
+                    break;
+                }
+            }
+            if let Some(episode) = Matcher::match_episode(parsed, &episodes) {
+                return Some(episode);
+            }
+        }
+        None
+    }
+
+    /// Explain how a file path or search query would be matched
+    ///
+    /// Runs the same search and ranking as a scrape, without fetching
+    /// metadata, and reports each provider's answer and every candidate.
+    /// Input with a directory separator or a video extension is parsed as
+    /// a path, anything else as a query.
+    pub async fn explain(&self, input: &str) -> MatchExplanation {
+        let path = Path::new(input);
+        let is_path = input.contains(['/', '\\'])
+            || path.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+                VIDEO_EXTENSIONS.iter().any(|v| v.eq_ignore_ascii_case(e))
+            });
+        let parsed = if is_path {
+            Parser::parse(path)
+        } else {
+            Parser::parse_filename(input)
+        };
+
+        let searches = self
+            .search_each(&parsed.title, parsed.year, parsed.hint)
+            .await;
+        let mut results: Vec<MediaInfo> = Vec::new();
+        let searches = searches
+            .into_iter()
+            .map(|(search, found)| {
+                results.extend(found);
+                search
+            })
+            .collect();
+        results.truncate(self.config.max_results);
+
+        let candidates = self.apply_override(
+            Matcher::rank_with(self.strategy.as_ref(), results, &parsed),
+            &parsed.title,
+        );
+        let min = self.config.min_confidence;
+        let accepted = candidates.first().is_some_and(|best| best.confidence >= min);
+
+        let mut decision = Vec::new();
+        match candidates.as_slice() {
+            [] => decision.push(format!("No provider found anything for {}", parsed.title)),
+            [best] => decision.push(format!(
+                "{} from {} is the only candidate, scoring {}",
+                best.info.title, best.info.provider, best.score
+            )),
+            [best, runner_up, ..] => decision.push(format!(
+                "{} from {} scored {}, ahead of {} from {} with {}",
+                best.info.title,
+                best.info.provider,
+                best.score,
+                runner_up.info.title,
+                runner_up.info.provider,
+                runner_up.score
+            )),
+        }
+        if let Some(best) = candidates.first() {
+            decision.extend(best.reasons.iter().cloned());
+            decision.push(if accepted {
+                format!(
+                    "Confidence {:?} reaches the minimum {:?}, so its metadata is fetched",
+                    best.confidence, min
+                )
+            } else {
+                format!(
+                    "Confidence {:?} is below the minimum {:?}, so no metadata is fetched",
+                    best.confidence, min
+                )
+            });
+        }
+
+        MatchExplanation {
+            parsed,
+            searches,
+            candidates,
+            decision,
+            accepted,
+        }
+    }
+
     /// Search for media across all providers
     pub async fn search(
         &self,
@@ -186,7 +686,10 @@ impl ScraperManager {
             ..Default::default()
         };
 
-        Ok(Matcher::rank(results, &parsed))
+        Ok(self.apply_override(
+            Matcher::rank_with(self.strategy.as_ref(), results, &parsed),
+            query,
+        ))
     }
 
     /// Get full metadata for a media item
@@ -199,17 +702,21 @@ impl ScraperManager {
             return Ok(cached);
         }
 
-        // Find the provider
-        let provider = self
-            .providers
-            .iter()
-            .find(|p| p.id() == info.provider)
-            .ok_or_else(|| {
-                ScraperError::Config(format!("Provider not found: {}", info.provider))
-            })?;
+        // Fetch metadata, from another provider if the matched one fails
+        let mut metadata = self.fetch_with_fallback(info).await?;
+        self.fill_untranslated(&mut metadata).await;
+
+        // Combine it with other providers' records of the same title
+        if !self.config.merge.is_empty() {
+            metadata = self.merge_metadata(info, metadata).await;
+        }
 
-        // Fetch metadata
-        let metadata = provider.get_metadata(&info.id, info.media_type).await?;
+        // Let the other providers fill in ratings and gaps
+        for other in self.enabled_providers().filter(|p| p.id() != info.provider) {
+            if let Err(e) = self.call(other.enrich(&mut metadata)).await {
+                debug!("Provider {} enrichment failed: {}", other.id(), e);
+            }
+        }
 
         // Cache the result
         if self.config.use_cache {
@@ -221,6 +728,164 @@ impl ScraperManager {
         Ok(metadata)
     }
 
+    /// Fill a title and overview left blank by a missing translation
+    ///
+    /// The provider is asked again in each configured language, then in the
+    /// title's original language. What is still blank then falls back to
+    /// the original title, or no overview at all rather than an empty one.
+    async fn fill_untranslated(&self, metadata: &mut MediaMetadata) {
+        let untranslated =
+            |m: &MediaMetadata| is_blank(Some(&m.title)) || is_blank(m.overview.as_deref());
+        if untranslated(metadata)
+            && let Ok(provider) = self.provider(&metadata.provider)
+        {
+            let mut languages: Vec<&str> = Vec::new();
+            for lang in self.config.languages.iter().chain(&metadata.language) {
+                if !languages.iter().any(|l| l.eq_ignore_ascii_case(lang)) {
+                    languages.push(lang);
+                }
+            }
+            let (id, media_type) = (metadata.id.clone(), metadata.media_type);
+            for lang in languages {
+                let translated = match self.call(provider.get_metadata_in(&id, media_type, lang)).await
+                {
+                    Ok(translated) => translated,
+                    // The provider can't fetch by language at all
+                    Err(ScraperError::NotFound(_)) => break,
+                    Err(e) => {
+                        debug!("Fetching {}:{} in {} failed: {}", provider.id(), id, lang, e);
+                        continue;
+                    }
+                };
+                if is_blank(Some(&metadata.title)) {
+                    metadata.title = translated.title;
+                }
+                if is_blank(metadata.overview.as_deref()) {
+                    metadata.overview = translated.overview;
+                }
+                if is_blank(metadata.tagline.as_deref()) {
+                    metadata.tagline = translated.tagline;
+                }
+                if !untranslated(metadata) {
+                    break;
+                }
+            }
+        }
+
+        if is_blank(Some(&metadata.title))
+            && let Some(original) = metadata.original_title.clone()
+        {
+            metadata.title = original;
+        }
+        if is_blank(metadata.overview.as_deref()) {
+            metadata.overview = None;
+        }
+        if is_blank(metadata.tagline.as_deref()) {
+            metadata.tagline = None;
+        }
+    }
+
+    /// Fetch metadata from the matched provider, or else from the next one
+    /// that knows the title
+    ///
+    /// Fallbacks are tried in priority order for the media type, each with
+    /// its own ID among the match's external IDs. The metadata's `provider`
+    /// tells which one served it; if all fail, the matched provider's error
+    /// is returned.
+    async fn fetch_with_fallback(&self, info: &MediaInfo) -> Result<MediaMetadata> {
+        let error = match self.provider(&info.provider) {
+            Ok(provider) => match self.fetch_from(provider, &info.id, info.media_type).await {
+                Ok(metadata) => return Ok(metadata),
+                Err(e) => e,
+            },
+            Err(e) => e,
+        };
+
+        let mut fallbacks: Vec<_> = self
+            .enabled_providers()
+            .filter(|p| p.id() != info.provider)
+            .filter_map(|p| Some((p, info.external_ids.get(p.id())?)))
+            .collect();
+        fallbacks
+            .sort_by_key(|(p, _)| std::cmp::Reverse(self.priority_of(p.as_ref(), info.media_type)));
+
+        for (provider, id) in fallbacks {
+            match self.fetch_from(provider, id, info.media_type).await {
+                Ok(metadata) => {
+                    info!(
+                        "Provider {} failed for {} ({}), using {}:{}",
+                        info.provider,
+                        info.title,
+                        error,
+                        provider.id(),
+                        id
+                    );
+                    return Ok(metadata);
+                }
+                Err(e) => debug!("Fallback provider {} failed: {}", provider.id(), e),
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Fetch metadata from one provider, within the call budget
+    async fn fetch_from(
+        &self,
+        provider: &Arc<dyn MetadataProvider>,
+        id: &str,
+        media_type: MediaType,
+    ) -> Result<MediaMetadata> {
+        self.emit(ScrapeEventKind::FetchingMetadata {
+            provider: provider.id().to_string(),
+            id: id.to_string(),
+        });
+        self.call(provider.get_metadata(id, media_type)).await
+    }
+
+    /// Merge the records [`ScraperConfig::merge`] names for the title
+    ///
+    /// Other providers' records are found by the IDs the match and its
+    /// metadata carry; ones that can't be fetched are left out.
+    async fn merge_metadata(&self, info: &MediaInfo, primary: MediaMetadata) -> MediaMetadata {
+        let mut ids = info.external_ids.clone();
+        ids.merge(&primary.external_ids);
+
+        let fetches = self
+            .config
+            .merge
+            .providers()
+            .filter(|&id| id != primary.provider)
+            .filter_map(|id| {
+                let provider = self.provider(id).ok()?;
+                Some((provider, ids.get(id)?.to_string()))
+            })
+            .map(|(provider, id)| async move {
+                let _permit = self.budget.acquire().await;
+                let fetch = provider.get_metadata(&id, info.media_type);
+                let limit = self.timeout_for(provider.id()).unwrap_or(Duration::MAX);
+                match tokio::time::timeout(limit, fetch).await {
+                    Ok(Ok(metadata)) => Some(metadata),
+                    Ok(Err(e)) => {
+                        debug!("Provider {} merge fetch failed: {}", provider.id(), e);
+                        None
+                    }
+                    Err(_) => {
+                        warn!("Provider {} timed out after {:?} fetching {}", provider.id(), limit, id);
+                        None
+                    }
+                }
+            });
+
+        let others: Vec<_> = futures::future::join_all(fetches)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        self.config.merge.merge(&primary, &others)
+    }
+
     /// Get episode details
     pub async fn get_episode(
         &self,
@@ -229,13 +894,49 @@ impl ScraperManager {
         season: i32,
         episode: i32,
     ) -> Result<EpisodeInfo> {
-        let provider = self
-            .providers
-            .iter()
-            .find(|p| p.id() == provider)
-            .ok_or_else(|| ScraperError::Config(format!("Provider not found: {provider}")))?;
+        let provider = self.provider(provider)?;
+
+        self.call(provider.get_episode(series_id, season, episode)).await
+    }
+
+    /// Get all episodes of a season
+    pub async fn get_season(
+        &self,
+        provider: &str,
+        series_id: &str,
+        season: i32,
+    ) -> Result<Vec<EpisodeInfo>> {
+        let provider = self.provider(provider)?;
 
-        provider.get_episode(series_id, season, episode).await
+        self.call(provider.get_season(series_id, season)).await
+    }
+
+    /// Get a list of trending, popular or filtered titles to browse
+    pub async fn discover(
+        &self,
+        provider: &str,
+        options: DiscoverOptions,
+    ) -> Result<Vec<MediaInfo>> {
+        let provider = self.provider(provider)?;
+
+        let options = match (&options.language, &self.config.language) {
+            (None, Some(lang)) => options.with_language(lang.clone()),
+            _ => options,
+        };
+
+        self.call(provider.discover(&options)).await
+    }
+
+    /// Get where a movie or TV show can be watched
+    pub async fn get_watch_providers(
+        &self,
+        provider: &str,
+        id: &str,
+        media_type: MediaType,
+    ) -> Result<WatchAvailability> {
+        let provider = self.provider(provider)?;
+
+        self.call(provider.get_watch_providers(id, media_type)).await
     }
 
     /// Find by external ID
@@ -244,8 +945,11 @@ impl ScraperManager {
         external_id: &str,
         source: &str,
     ) -> Result<Option<MediaInfo>> {
-        for provider in &self.providers {
-            if let Ok(Some(info)) = provider.find_by_external_id(external_id, source).await {
+        for provider in self.enabled_providers() {
+            if let Ok(Some(info)) = self
+                .call(provider.find_by_external_id(external_id, source))
+                .await
+            {
                 return Ok(Some(info));
             }
         }
@@ -259,6 +963,32 @@ impl ScraperManager {
         year: Option<i32>,
         hint: MediaHint,
     ) -> Result<Vec<MediaInfo>> {
+        let mut all_results: Vec<MediaInfo> = self
+            .search_each(query, year, hint)
+            .await
+            .into_iter()
+            .flat_map(|(_, results)| results)
+            .collect();
+
+        if all_results.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        // Limit total results
+        all_results.truncate(self.config.max_results);
+
+        Ok(all_results)
+    }
+
+    /// Search every enabled provider at once, in priority order
+    async fn search_each(
+        &self,
+        query: &str,
+        year: Option<i32>,
+        hint: MediaHint,
+    ) -> Vec<(ProviderSearch, Vec<MediaInfo>)> {
         let media_type = match hint {
             MediaHint::Movie => Some(MediaType::Movie),
             MediaHint::TvShow => Some(MediaType::Tv),
@@ -267,12 +997,9 @@ impl ScraperManager {
         };
 
         // Sort providers by priority for this media type
-        let mut providers: Vec<_> = self.providers.iter().collect();
-        providers.sort_by(|a, b| {
-            let type_for_sort = media_type.unwrap_or(MediaType::Unknown);
-            b.priority_for(type_for_sort)
-                .cmp(&a.priority_for(type_for_sort))
-        });
+        let mut providers: Vec<_> = self.enabled_providers().collect();
+        let type_for_sort = media_type.unwrap_or(MediaType::Unknown);
+        providers.sort_by_key(|p| std::cmp::Reverse(self.priority_of(p.as_ref(), type_for_sort)));
 
         let options = SearchOptions::new()
             .with_year(year)
@@ -290,60 +1017,134 @@ impl ScraperManager {
             options
         };
 
-        let mut all_results = Vec::new();
+        // Query every provider at once; results keep the priority order
+        let searches = providers.into_iter().map(|provider| async {
+            let (outcome, results) =
+                self.search_provider(provider.as_ref(), query, year, &options).await;
+            let search = ProviderSearch {
+                provider: provider.id().to_string(),
+                outcome,
+                results: results.len(),
+            };
+            (search, results)
+        });
+        futures::future::join_all(searches).await
+    }
 
-        for provider in providers {
-            // Check cache first
-            if self.config.use_cache
-                && let Some(cached) = self.cache.get_search(provider.id(), query, year).await
-            {
-                debug!("Cache hit for search: {}:{}", provider.id(), query);
-                all_results.extend(cached);
-                continue;
-            }
+    /// Search one provider, giving up on it after its timeout
+    ///
+    /// Failures and timeouts are logged and leave the provider out of the
+    /// results, so one slow or broken provider doesn't fail the search.
+    async fn search_provider(
+        &self,
+        provider: &dyn MetadataProvider,
+        query: &str,
+        year: Option<i32>,
+        options: &SearchOptions,
+    ) -> (SearchOutcome, Vec<MediaInfo>) {
+        self.emit(ScrapeEventKind::Searching {
+            query: query.to_string(),
+            provider: provider.id().to_string(),
+        });
 
-            // Search provider
-            match provider.search(query, &options).await {
-                Ok(results) => {
-                    debug!(
-                        "Provider {} returned {} results",
+        // Check cache first
+        if self.config.use_cache
+            && let Some(cached) = self.cache.get_search(provider.id(), query, year).await
+        {
+            debug!("Cache hit for search: {}:{}", provider.id(), query);
+            return (SearchOutcome::Cached, cached);
+        }
+
+        let _permit = self.budget.acquire().await;
+        let search = provider.search(query, options);
+        let result = match self.timeout_for(provider.id()) {
+            Some(limit) => match tokio::time::timeout(limit, search).await {
+                Ok(result) => result,
+                Err(_) => {
+                    warn!(
+                        "Provider {} timed out after {:?} searching {}",
                         provider.id(),
-                        results.len()
+                        limit,
+                        query
                     );
+                    return (SearchOutcome::TimedOut, Vec::new());
+                }
+            },
+            None => search.await,
+        };
 
-                    // Cache results
-                    if self.config.use_cache {
-                        self.cache
-                            .set_search(provider.id(), query, year, results.clone())
-                            .await;
-                    }
+        match result {
+            Ok(results) => {
+                debug!(
+                    "Provider {} returned {} results",
+                    provider.id(),
+                    results.len()
+                );
 
-                    all_results.extend(results);
+                // Cache results
+                if self.config.use_cache {
+                    self.cache
+                        .set_search(provider.id(), query, year, results.clone())
+                        .await;
                 }
-                Err(e) => {
-                    debug!("Provider {} search failed: {}", provider.id(), e);
+
+                (SearchOutcome::Searched, results)
+            }
+            // Remember the miss, so rescans don't repeat the query
+            Err(ScraperError::NotFound(_)) => {
+                debug!("Provider {} found nothing for {}", provider.id(), query);
+                if self.config.use_cache {
+                    self.cache
+                        .set_search(provider.id(), query, year, Vec::new())
+                        .await;
                 }
+                (SearchOutcome::NotFound, Vec::new())
+            }
+            Err(e) => {
+                debug!("Provider {} search failed: {}", provider.id(), e);
+                let error = e.to_string();
+                (SearchOutcome::Failed { error }, Vec::new())
             }
         }
+    }
 
-        if all_results.is_empty() {
-            return Err(ScraperError::NotFound(format!(
-                "No results found for: {query}"
-            )));
-        }
-
-        // Limit total results
-        all_results.truncate(self.config.max_results);
+    /// Make a provider call within the budget shared by all lookups
+    async fn call<T>(&self, call: impl Future<Output = T>) -> T {
+        let _permit = self.budget.acquire().await;
+        call.await
+    }
 
-        Ok(all_results)
+    /// Search timeout of a provider, see [`ScraperConfig::provider_timeouts`]
+    fn timeout_for(&self, provider: &str) -> Option<Duration> {
+        self.config
+            .provider_timeouts
+            .get(provider)
+            .copied()
+            .or(self.config.provider_timeout)
+            .filter(|limit| !limit.is_zero())
     }
 
     /// Clear the cache
-    pub fn clear_cache(&self) {
-        self.cache.clear();
+    pub async fn clear_cache(&self) {
+        self.cache.clear().await;
+    }
+
+    /// Forget the cached metadata of one item, so it is fetched again
+    pub async fn invalidate_metadata(&self, provider: &str, id: &str) {
+        self.cache.invalidate_metadata(provider, id).await;
+    }
+
+    /// Forget everything cached from one provider
+    pub async fn invalidate_provider(&self, provider: &str) {
+        self.cache.invalidate_provider(provider).await;
     }
 }
 
+/// Whether a text is missing or only whitespace
+fn is_blank(text: Option<&str>) -> bool {
+    text.is_none_or(|t| t.trim().is_empty())
+}
+
 impl Default for ScraperManager {
     fn default() -> Self {
         Self::new()
@@ -353,7 +1154,196 @@ impl Default for ScraperManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scraper::{AniListProvider, BangumiProvider};
+    use crate::scraper::{
+        AniListProvider, BangumiProvider, EpisodeMatchKind, ExternalIds, ExternalRating,
+        MetadataField,
+    };
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serves one movie, or enriches metadata with a rating
+    struct FakeProvider(&'static str);
+
+    #[async_trait]
+    impl MetadataProvider for FakeProvider {
+        fn id(&self) -> &'static str {
+            self.0
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn supported_types(&self) -> &[MediaType] {
+            &[MediaType::Movie]
+        }
+
+        async fn search(&self, _query: &str, _options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+            Ok(MediaMetadata {
+                id: id.to_string(),
+                title: "Inception".to_string(),
+                overview: Some(format!("{} plot", self.0)),
+                external_ids: ExternalIds {
+                    imdb: Some("tt1375666".to_string()),
+                    ..Default::default()
+                },
+                provider: self.0.to_string(),
+                ..Default::default()
+            })
+        }
+
+        async fn get_episode(&self, id: &str, _season: i32, _episode: i32) -> Result<EpisodeInfo> {
+            Err(ScraperError::NotFound(id.to_string()))
+        }
+
+        async fn enrich(&self, metadata: &mut MediaMetadata) -> Result<()> {
+            if metadata.external_ids.imdb.is_some() {
+                metadata.ratings.push(ExternalRating {
+                    source: self.0.to_string(),
+                    score: 8.8,
+                    votes: None,
+                });
+            }
+            Ok(())
+        }
+    }
+
+    /// Finds one movie named after itself, after `delay`
+    struct SlowProvider(&'static str, Duration);
+
+    #[async_trait]
+    impl MetadataProvider for SlowProvider {
+        fn id(&self) -> &'static str {
+            self.0
+        }
+
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn supported_types(&self) -> &[MediaType] {
+            &[MediaType::Movie]
+        }
+
+        async fn search(&self, _query: &str, _options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+            tokio::time::sleep(self.1).await;
+            Ok(vec![MediaInfo::new("1", self.0, self.0).with_type(MediaType::Movie)])
+        }
+
+        async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+            Err(ScraperError::NotFound(id.to_string()))
+        }
+
+        async fn get_episode(&self, id: &str, _season: i32, _episode: i32) -> Result<EpisodeInfo> {
+            Err(ScraperError::NotFound(id.to_string()))
+        }
+    }
+
+    /// Has an anime translated into nothing but its original Japanese
+    struct UntranslatedProvider;
+
+    #[async_trait]
+    impl MetadataProvider for UntranslatedProvider {
+        fn id(&self) -> &'static str {
+            "untranslated"
+        }
+
+        fn name(&self) -> &'static str {
+            "Untranslated"
+        }
+
+        fn supported_types(&self) -> &[MediaType] {
+            &[MediaType::Anime]
+        }
+
+        async fn search(&self, _query: &str, _options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_metadata(&self, id: &str, media_type: MediaType) -> Result<MediaMetadata> {
+            Ok(MediaMetadata {
+                id: id.to_string(),
+                original_title: Some("葬送のフリーレン".to_string()),
+                overview: Some(String::new()),
+                language: Some("ja".to_string()),
+                media_type,
+                provider: "untranslated".to_string(),
+                ..Default::default()
+            })
+        }
+
+        async fn get_metadata_in(
+            &self,
+            id: &str,
+            media_type: MediaType,
+            language: &str,
+        ) -> Result<MediaMetadata> {
+            let mut metadata = self.get_metadata(id, media_type).await?;
+            if language == "ja" {
+                metadata.overview = Some("勇者一行の魔法使い".to_string());
+            }
+            Ok(metadata)
+        }
+
+        async fn get_episode(&self, id: &str, _season: i32, _episode: i32) -> Result<EpisodeInfo> {
+            Err(ScraperError::NotFound(id.to_string()))
+        }
+    }
+
+    /// Daily show airing every weekday, counting the seasons asked for
+    struct DailyProvider(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl MetadataProvider for DailyProvider {
+        fn id(&self) -> &'static str {
+            "daily"
+        }
+
+        fn name(&self) -> &'static str {
+            "Daily"
+        }
+
+        fn supported_types(&self) -> &[MediaType] {
+            &[MediaType::Tv]
+        }
+
+        async fn search(&self, _query: &str, _options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+            Err(ScraperError::NotFound(id.to_string()))
+        }
+
+        async fn get_episode(&self, id: &str, _season: i32, _episode: i32) -> Result<EpisodeInfo> {
+            Err(ScraperError::NotFound(id.to_string()))
+        }
+
+        async fn get_season(&self, _series_id: &str, season: i32) -> Result<Vec<EpisodeInfo>> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            let year = 2022 + season;
+            Ok((1..=20)
+                .map(|episode| EpisodeInfo {
+                    id: format!("{season}x{episode}"),
+                    title: String::new(),
+                    season,
+                    episode,
+                    absolute_number: None,
+                    original_title: None,
+                    air_date: Some(format!("{year}-03-{episode:02}")),
+                    overview: None,
+                    runtime: None,
+                    rating: None,
+                    still_url: None,
+                    provider: "daily".to_string(),
+                })
+                .collect())
+        }
+    }
 
     #[test]
     fn test_manager_creation() {
@@ -378,20 +1368,301 @@ mod tests {
             max_results: 10,
             use_cache: false,
             language: Some("zh-CN".to_string()),
+            languages: vec!["zh-CN".to_string(), "en".to_string()],
+            match_strategy: MatchStrategyKind::Strict,
+            rate_limits: HashMap::new(),
+            provider_timeout: None,
+            provider_timeouts: HashMap::new(),
+            priorities: HashMap::new(),
+            merge: MergeRules::default(),
+            max_concurrent_calls: 4,
+            interactive_reserve: 1,
+            background_rate: Some(2.0),
         };
 
         let manager = ScraperManager::with_config(config);
         assert!(manager.providers().is_empty());
     }
 
+    #[test]
+    fn test_manager_rate_limited_provider() {
+        let mut manager = ScraperManager::with_config(ScraperConfig {
+            rate_limits: HashMap::from([("anilist".to_string(), 0.5)]),
+            ..ScraperConfig::default()
+        });
+
+        manager.add_provider(AniListProvider::new());
+
+        assert_eq!(manager.providers()[0].id(), "anilist");
+        assert_eq!(manager.providers()[0].priority_for(MediaType::Anime), 100);
+    }
+
+    #[test]
+    fn test_manual_override_ranked_first() {
+        let manager = ScraperManager::new();
+        let parsed = ParsedMedia {
+            title: "Frieren".to_string(),
+            ..Default::default()
+        };
+        let ranked = Matcher::rank(
+            vec![
+                MediaInfo::new("1", "Frieren", "tmdb").with_type(MediaType::Tv),
+                MediaInfo::new("2", "Frieren Movie", "tmdb").with_type(MediaType::Movie),
+            ],
+            &parsed,
+        );
+
+        // Without override, results are untouched
+        let result = manager.apply_override(ranked.clone(), "Frieren");
+        assert_eq!(result[0].info.id, "1");
+
+        manager.set_override(
+            "frieren",
+            MediaInfo::new("2", "Frieren Movie", "tmdb").with_type(MediaType::Movie),
+        );
+        let result = manager.apply_override(ranked.clone(), "Frieren");
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].info.id, "2");
+        assert_eq!(result[0].confidence, Confidence::Exact);
+
+        // Override not among the results is still inserted first
+        manager.set_override("Frieren", MediaInfo::new("154587", "Frieren", "anilist"));
+        let result = manager.apply_override(ranked, "Frieren");
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].info.provider, "anilist");
+
+        manager.remove_override("Frieren");
+        assert!(manager.get_override("Frieren").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_untranslated_metadata_falls_back_to_original_language() {
+        let mut manager = ScraperManager::with_config(ScraperConfig {
+            languages: vec!["zh-CN".to_string(), "en".to_string()],
+            ..ScraperConfig::default()
+        });
+        manager.add_provider(UntranslatedProvider);
+
+        let info = MediaInfo::new("1", "Frieren", "untranslated").with_type(MediaType::Anime);
+        let metadata = manager.get_metadata(&info).await.unwrap();
+        assert_eq!(metadata.title, "葬送のフリーレン");
+        assert_eq!(metadata.overview.as_deref(), Some("勇者一行の魔法使い"));
+
+        // Providers that can't fetch by language keep what they have
+        manager.add_provider(FakeProvider("movies"));
+        let info = MediaInfo::new("2", "Inception", "movies").with_type(MediaType::Movie);
+        let metadata = manager.get_metadata(&info).await.unwrap();
+        assert_eq!(metadata.title, "Inception");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_date_named_episode() {
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let mut manager = ScraperManager::new();
+        manager.add_provider(DailyProvider(fetched.clone()));
+        let season = |number: i32| SeasonInfo {
+            number,
+            name: None,
+            overview: None,
+            air_date: Some(format!("{}-03-01", 2022 + number)),
+            episode_count: Some(12),
+            poster_url: None,
+        };
+        let metadata = MediaMetadata {
+            id: "1".to_string(),
+            media_type: MediaType::Tv,
+            seasons: vec![season(1), season(2), season(3)],
+            provider: "daily".to_string(),
+            ..Default::default()
+        };
+        let parsed = Parser::parse(Path::new("Show.2024.03.15.mkv"));
+        assert_eq!(parsed.air_date.as_deref(), Some("2024-03-15"));
+
+        let episode = manager.resolve_episode(&parsed, &metadata).await.unwrap();
+        assert_eq!((episode.season, episode.episode), (2, 15));
+        assert_eq!(episode.kind, EpisodeMatchKind::AirDate);
+
+        // Only the season airing at the time was fetched
+        assert_eq!(fetched.load(Ordering::Relaxed), 1);
+
+        let parsed = Parser::parse(Path::new("Show.S03E04.mkv"));
+        let episode = manager.resolve_episode(&parsed, &metadata).await.unwrap();
+        assert_eq!((episode.season, episode.episode), (3, 4));
+
+        // Aired after the summary was made: found in the season's own list
+        let parsed = Parser::parse(Path::new("Show.S02E15.mkv"));
+        let episode = manager.resolve_episode(&parsed, &metadata).await.unwrap();
+        assert_eq!((episode.season, episode.episode), (2, 15));
+        assert_eq!(episode.kind, EpisodeMatchKind::Exact);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_enriched_by_other_providers() {
+        let mut manager = ScraperManager::new();
+        manager.add_provider(FakeProvider("movies"));
+        manager.add_provider(FakeProvider("ratings"));
+
+        let info = MediaInfo::new("27205", "Inception", "movies").with_type(MediaType::Movie);
+        let metadata = manager.get_metadata(&info).await.unwrap();
+
+        assert_eq!(metadata.ratings.len(), 1);
+        assert_eq!(metadata.ratings[0].source, "ratings");
+    }
+
+    #[tokio::test]
+    async fn test_metadata_merged_by_field() {
+        let mut manager = ScraperManager::with_config(ScraperConfig {
+            merge: MergeRules::default().with(MetadataField::Overview, &["omdb", "tmdb"]),
+            ..ScraperConfig::default()
+        });
+        manager.add_provider(FakeProvider("tmdb"));
+        manager.add_provider(FakeProvider("omdb"));
+
+        // OMDb's record is found by the IMDb ID TMDB's metadata carries
+        let info = MediaInfo::new("27205", "Inception", "tmdb").with_type(MediaType::Movie);
+        let metadata = manager.get_metadata(&info).await.unwrap();
+
+        assert_eq!(metadata.overview.as_deref(), Some("omdb plot"));
+        assert_eq!(metadata.provider, "tmdb");
+        assert_eq!(metadata.id, "27205");
+    }
+
+    #[tokio::test]
+    async fn test_metadata_falls_back_to_next_provider() {
+        let mut manager = ScraperManager::new();
+        manager.add_provider(SlowProvider("tmdb", Duration::ZERO));
+        manager.add_provider(FakeProvider("omdb"));
+
+        let mut info = MediaInfo::new("27205", "Inception", "tmdb").with_type(MediaType::Movie);
+        assert!(manager.get_metadata(&info).await.is_err());
+
+        info.external_ids.imdb = Some("tt1375666".to_string());
+        let metadata = manager.get_metadata(&info).await.unwrap();
+        assert_eq!(metadata.provider, "omdb");
+        assert_eq!(metadata.id, "tt1375666");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_provider_skipped() {
+        let mut manager = ScraperManager::with_config(ScraperConfig {
+            use_cache: false,
+            ..ScraperConfig::default()
+        });
+        manager.add_provider(SlowProvider("tmdb", Duration::ZERO));
+        manager.add_provider(SlowProvider("tvdb", Duration::ZERO));
+
+        assert!(manager.set_provider_enabled("tmdb", false));
+        assert!(!manager.set_provider_enabled("missing", false));
+        assert!(!manager.is_provider_enabled("tmdb"));
+
+        let results = manager.search("Heat", None, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].provider, "tvdb");
+        let error = manager.get_episode("tmdb", "1", 1, 1).await.unwrap_err();
+        assert!(error.to_string().contains("disabled"));
+
+        manager.set_provider_enabled("tmdb", true);
+        assert_eq!(manager.search("Heat", None, None).await.unwrap().len(), 2);
+        assert_eq!(manager.providers().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_scrape_emits_events() {
+        let mut manager = ScraperManager::new();
+        manager.add_provider(SlowProvider("slow", Duration::ZERO));
+        let mut events = manager.subscribe();
+
+        let result = manager.scrape(Path::new("Movies/slow (2020).mkv")).await;
+        assert!(result.is_ok());
+
+        let mut kinds = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            kinds.push(serde_json::to_value(&event).unwrap()["event"].clone());
+        }
+        assert_eq!(
+            kinds,
+            ["parsing", "searching", "matched", "fetching_metadata", "done"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_priority_overrides() {
+        let mut manager = ScraperManager::with_config(ScraperConfig {
+            use_cache: false,
+            priorities: HashMap::from([(
+                MediaType::Movie,
+                HashMap::from([("second".to_string(), 90)]),
+            )]),
+            ..ScraperConfig::default()
+        });
+        manager.add_provider(SlowProvider("first", Duration::ZERO));
+        manager.add_provider(SlowProvider("second", Duration::ZERO));
+
+        let results = manager.search("Heat", None, Some(MediaType::Movie)).await.unwrap();
+        let providers: Vec<_> = results.iter().map(|r| r.provider.as_str()).collect();
+        assert_eq!(providers, ["second", "first"]);
+
+        // Other media types keep the providers' own priorities
+        let first = manager.providers()[0].as_ref();
+        assert_eq!(manager.priority_of(first, MediaType::Movie), 50);
+        let second = manager.providers()[1].as_ref();
+        assert_eq!(manager.priority_of(second, MediaType::Tv), 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_skips_timed_out_provider() {
+        let mut manager = ScraperManager::with_config(ScraperConfig {
+            use_cache: false,
+            provider_timeout: Some(Duration::from_millis(200)),
+            provider_timeouts: HashMap::from([("patient".to_string(), Duration::from_secs(5))]),
+            ..ScraperConfig::default()
+        });
+        manager.add_provider(SlowProvider("stalled", Duration::from_secs(60)));
+        manager.add_provider(SlowProvider("fast", Duration::from_millis(100)));
+        manager.add_provider(SlowProvider("patient", Duration::from_millis(300)));
+
+        let started = std::time::Instant::now();
+        let results = manager.search("Heat", None, Some(MediaType::Movie)).await.unwrap();
+
+        // Providers ran side by side, and the stalled one was dropped
+        assert!(started.elapsed() < Duration::from_secs(2));
+        let providers: Vec<_> = results.iter().map(|r| r.provider.as_str()).collect();
+        assert_eq!(providers, ["fast", "patient"]);
+    }
+
+    #[tokio::test]
+    async fn test_explain_match() {
+        let mut manager = ScraperManager::with_config(ScraperConfig {
+            provider_timeout: Some(Duration::from_millis(100)),
+            ..ScraperConfig::default()
+        });
+        manager.add_provider(SlowProvider("heat", Duration::ZERO));
+        manager.add_provider(SlowProvider("stalled", Duration::from_secs(60)));
+
+        let explained = manager.explain("/movies/Heat (1995)/Heat.1995.mkv").await;
+        assert_eq!(explained.parsed.title, "Heat");
+        assert_eq!(explained.searches[0].outcome, SearchOutcome::Searched);
+        assert_eq!(explained.searches[0].results, 1);
+        assert_eq!(explained.searches[1].outcome, SearchOutcome::TimedOut);
+        assert_eq!(explained.candidates[0].info.provider, "heat");
+        assert!(explained.candidates[0].breakdown.title_score > 0);
+        assert!(explained.decision[0].contains("only candidate"), "{:?}", explained.decision);
+
+        // The same search again is answered from the cache
+        let explained = manager.explain("Heat 1995").await;
+        assert_eq!(explained.searches[0].outcome, SearchOutcome::Cached);
+        let json = serde_json::to_value(&explained.searches[1]).unwrap();
+        assert_eq!(json, serde_json::json!({ "provider": "stalled", "status": "timed_out", "results": 0 }));
+    }
+
     #[test]
     fn test_default_manager_creation() {
         // Without API key
-        let manager = crate::scraper::create_default_manager(None);
-        assert_eq!(manager.providers().len(), 2);
+        let manager = crate::scraper::create_default_manager(None).unwrap();
+        assert_eq!(manager.providers().len(), 3);
 
         // With API key
-        let manager = crate::scraper::create_default_manager(Some("fake_key"));
-        assert_eq!(manager.providers().len(), 3);
+        let manager = crate::scraper::create_default_manager(Some("fake_key")).unwrap();
+        assert_eq!(manager.providers().len(), 4);
     }
 }
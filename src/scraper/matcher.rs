@@ -1,6 +1,10 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+
 use crate::scraper::{
-    parser::{MediaHint, ParsedMedia},
-    types::{MediaInfo, MediaType},
+    parser::{MediaHint, ParsedMedia, TitleArticles},
+    strategy::{DefaultStrategy, MatchStrategy},
+    types::{EpisodeInfo, MediaInfo, MediaType, SeasonInfo},
 };
 
 /// Match confidence level
@@ -29,16 +33,43 @@ pub struct ScoredMatch {
     pub confidence: Confidence,
     /// Breakdown of score components
     pub breakdown: ScoreBreakdown,
+    /// Providers that returned this media (first is the primary)
+    pub providers: Vec<String>,
+    /// Human-readable explanation of the score and ranking
+    pub reasons: Vec<String>,
 }
 
 /// Breakdown of how the score was calculated
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ScoreBreakdown {
     pub title_score: i32,
     pub year_score: i32,
     pub type_score: i32,
     pub provider_score: i32,
     pub popularity_score: i32,
+    /// Bonus when multiple providers agree on the same media
+    pub agreement_score: i32,
+}
+
+/// How an episode was resolved against a series' episode list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpisodeMatchKind {
+    /// Season and episode numbers matched directly
+    Exact,
+    /// Episode number was treated as an absolute number across seasons
+    Absolute,
+    /// Matched by air date
+    AirDate,
+}
+
+/// Episode resolved to its season/episode position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeMatch {
+    pub season: i32,
+    pub episode: i32,
+    /// Absolute episode number across all regular seasons
+    pub absolute_number: Option<i32>,
+    pub kind: EpisodeMatchKind,
 }
 
 /// Matcher for scoring and ranking search results
@@ -46,17 +77,92 @@ pub struct Matcher;
 
 impl Matcher {
     /// Score and rank search results against parsed media info
+    ///
+    /// Results describing the same media from different providers are merged
+    /// into a single match.
     #[must_use] 
     pub fn rank(results: Vec<MediaInfo>, parsed: &ParsedMedia) -> Vec<ScoredMatch> {
+        Self::rank_with(&DefaultStrategy, results, parsed)
+    }
+
+    /// Score and rank search results using a specific matching strategy
+    #[must_use]
+    pub fn rank_with(
+        strategy: &dyn MatchStrategy,
+        results: Vec<MediaInfo>,
+        parsed: &ParsedMedia,
+    ) -> Vec<ScoredMatch> {
         let mut scored: Vec<ScoredMatch> = results
             .into_iter()
-            .map(|info| Self::score_match(&info, parsed))
+            .map(|info| Self::score_match(strategy, &info, parsed, 0))
             .collect();
 
         // Sort by score descending
-        scored.sort_by(|a, b| b.score.cmp(&a.score));
+        Self::sort_matches(&mut scored, parsed);
+
+        let mut merged = Self::merge_duplicates(strategy, scored, parsed);
+        Self::sort_matches(&mut merged, parsed);
+
+        // Explain why the winner beat an equally scored runner-up
+        if let [first, second, ..] = merged.as_mut_slice()
+            && first.score == second.score
+            && let (_, Some(rule)) = Self::tie_break(first, second, parsed)
+        {
+            first
+                .reasons
+                .push(format!("Won tie-break against {} on {rule}", second.info.title));
+        }
+
+        merged
+    }
 
-        scored
+    /// Sort by score, breaking ties deterministically
+    fn sort_matches(matches: &mut [ScoredMatch], parsed: &ParsedMedia) {
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| Self::tie_break(a, b, parsed).0)
+        });
+    }
+
+    /// Order two equally scored matches, returning the rule that decided
+    ///
+    /// Rules in order: popularity, exact original-title match, provider priority,
+    /// then provider and ID so the order is always stable.
+    fn tie_break(
+        a: &ScoredMatch,
+        b: &ScoredMatch,
+        parsed: &ParsedMedia,
+    ) -> (Ordering, Option<&'static str>) {
+        let popularity = |m: &ScoredMatch| m.info.popularity.unwrap_or(0.0);
+        let query = Self::normalize_title(&parsed.title);
+        let original_exact = |m: &ScoredMatch| {
+            m.info
+                .original_title
+                .as_deref()
+                .is_some_and(|t| Self::normalize_title(t) == query)
+        };
+
+        let rules: [(Ordering, &'static str); 3] = [
+            (popularity(b).total_cmp(&popularity(a)), "popularity"),
+            (original_exact(b).cmp(&original_exact(a)), "original title"),
+            (
+                b.breakdown.provider_score.cmp(&a.breakdown.provider_score),
+                "provider priority",
+            ),
+        ];
+
+        if let Some(&(ordering, rule)) = rules.iter().find(|(o, _)| o.is_ne()) {
+            return (ordering, Some(rule));
+        }
+
+        (
+            a.info
+                .provider
+                .cmp(&b.info.provider)
+                .then_with(|| a.info.id.cmp(&b.info.id)),
+            None,
+        )
     }
 
     /// Get the best match if confidence is high enough
@@ -69,34 +175,270 @@ impl Matcher {
             .filter(|m| m.confidence >= Confidence::Medium)
     }
 
+    /// Resolve a parsed file to the correct season/episode of a series
+    ///
+    /// Date-named files are matched by air date. Files naming a season are matched
+    /// directly on the season/episode pair, and only then; files without one have
+    /// their episode number treated as absolute (e.g. `Show - 25` for the first
+    /// episode of a second cour).
+    #[must_use]
+    pub fn match_episode(parsed: &ParsedMedia, episodes: &[EpisodeInfo]) -> Option<EpisodeMatch> {
+        let mut regular: Vec<&EpisodeInfo> = episodes.iter().filter(|e| e.season > 0).collect();
+        regular.sort_by_key(|e| (e.season, e.episode));
+
+        let absolute_of = |ep: &EpisodeInfo| {
+            ep.absolute_number.or_else(|| {
+                regular
+                    .iter()
+                    .position(|e| e.season == ep.season && e.episode == ep.episode)
+                    .and_then(|i| i32::try_from(i + 1).ok())
+            })
+        };
+
+        if let Some(ref date) = parsed.air_date {
+            let ep = episodes
+                .iter()
+                .find(|e| e.air_date.as_deref() == Some(date.as_str()))?;
+            return Some(EpisodeMatch {
+                season: ep.season,
+                episode: ep.episode,
+                absolute_number: absolute_of(ep),
+                kind: EpisodeMatchKind::AirDate,
+            });
+        }
+
+        let number = parsed.episode?;
+
+        if let Some(season) = parsed.season {
+            let ep = episodes
+                .iter()
+                .find(|e| e.season == season && e.episode == number)?;
+            return Some(EpisodeMatch {
+                season: ep.season,
+                episode: ep.episode,
+                absolute_number: absolute_of(ep),
+                kind: EpisodeMatchKind::Exact,
+            });
+        }
+
+        let ep = regular
+            .iter()
+            .find(|e| e.absolute_number == Some(number))
+            .or_else(|| {
+                usize::try_from(number - 1)
+                    .ok()
+                    .and_then(|i| regular.get(i))
+            })?;
+
+        Some(EpisodeMatch {
+            season: ep.season,
+            episode: ep.episode,
+            absolute_number: Some(number),
+            kind: EpisodeMatchKind::Absolute,
+        })
+    }
+
+    /// Build a placeholder episode list from season summaries (episode counts only)
+    #[must_use]
+    pub fn episodes_from_seasons(seasons: &[SeasonInfo], provider: &str) -> Vec<EpisodeInfo> {
+        seasons
+            .iter()
+            .flat_map(|s| {
+                (1..=s.episode_count.unwrap_or(0)).map(move |episode| EpisodeInfo {
+                    id: String::new(),
+                    title: String::new(),
+                    season: s.number,
+                    episode,
+                    absolute_number: None,
+                    original_title: None,
+                    air_date: None,
+                    overview: None,
+                    runtime: None,
+                    rating: None,
+                    still_url: None,
+                    provider: provider.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Merge matches that describe the same media, keeping the best scored one as primary
+    fn merge_duplicates(
+        strategy: &dyn MatchStrategy,
+        scored: Vec<ScoredMatch>,
+        parsed: &ParsedMedia,
+    ) -> Vec<ScoredMatch> {
+        let mut merged: Vec<ScoredMatch> = Vec::with_capacity(scored.len());
+
+        for candidate in scored {
+            if let Some(existing) = merged
+                .iter_mut()
+                .find(|m| Self::is_duplicate(&m.info, &candidate.info))
+            {
+                Self::merge_info(&mut existing.info, &candidate.info);
+                if !existing.providers.contains(&candidate.info.provider) {
+                    existing.providers.push(candidate.info.provider.clone());
+                }
+            } else {
+                merged.push(candidate);
+            }
+        }
+
+        // Re-score merged entries: extra titles may match better, and agreement earns a bonus
+        for m in &mut merged {
+            if m.providers.len() > 1 {
+                let providers = std::mem::take(&mut m.providers);
+                let agreement = Self::score_agreement(providers.len());
+                *m = Self::score_match(strategy, &m.info, parsed, agreement);
+                m.providers = providers;
+            }
+        }
+
+        merged
+    }
+
+    /// Check whether two results from different providers refer to the same media
+    fn is_duplicate(a: &MediaInfo, b: &MediaInfo) -> bool {
+        if a.provider == b.provider {
+            return false;
+        }
+
+        // A movie and a series can share an ID number across TMDB's namespaces
+        if !a.media_type.is_compatible_with(b.media_type) {
+            return false;
+        }
+
+        if a.external_ids.overlaps(&b.external_ids) {
+            return true;
+        }
+
+        let (Some(year_a), Some(year_b)) = (a.year, b.year) else {
+            return false;
+        };
+        if year_a != year_b {
+            return false;
+        }
+
+        let titles_a: Vec<String> = a.all_titles().into_iter().map(Self::normalize_title).collect();
+        b.all_titles()
+            .into_iter()
+            .map(Self::normalize_title)
+            .any(|t| !t.is_empty() && titles_a.contains(&t))
+    }
+
+    /// Merge titles and external IDs from a duplicate into the primary info
+    fn merge_info(primary: &mut MediaInfo, other: &MediaInfo) {
+        for title in other.all_titles() {
+            if !primary.all_titles().contains(&title) {
+                primary.alt_titles.push(title.to_string());
+            }
+        }
+
+        // Keep the primary's IDs where both are set
+        let mut ids = other.external_ids.clone();
+        ids.merge(&primary.external_ids);
+        primary.external_ids = ids;
+
+        if primary.year.is_none() {
+            primary.year = other.year;
+        }
+        if primary.poster_url.is_none() {
+            primary.poster_url.clone_from(&other.poster_url);
+        }
+        if primary.overview.is_none() {
+            primary.overview.clone_from(&other.overview);
+        }
+        if primary.format.is_none() {
+            primary.format.clone_from(&other.format);
+        }
+    }
+
     /// Score a single match
-    fn score_match(info: &MediaInfo, parsed: &ParsedMedia) -> ScoredMatch {
+    fn score_match(
+        strategy: &dyn MatchStrategy,
+        info: &MediaInfo,
+        parsed: &ParsedMedia,
+        agreement_score: i32,
+    ) -> ScoredMatch {
         let breakdown = ScoreBreakdown {
-            // Title matching (0-40 points)
-            title_score: Self::score_title(&info.all_titles(), &parsed.title),
-            // Year matching (0-20 points)
-            year_score: Self::score_year(info.year, parsed.year),
-            // Type matching (0-20 points)
-            type_score: Self::score_type(info.media_type, parsed.hint),
-            // Provider priority (0-10 points)
-            provider_score: Self::score_provider(&info.provider, info.media_type),
-            // Popularity bonus (0-10 points)
-            popularity_score: Self::score_popularity(info.popularity),
+            // Cross-provider agreement bonus (0-10 points)
+            agreement_score,
+            ..strategy.score(info, parsed)
         };
 
-        let total_score = breakdown.title_score
+        let total_score = (breakdown.title_score
             + breakdown.year_score
             + breakdown.type_score
             + breakdown.provider_score
-            + breakdown.popularity_score;
+            + breakdown.popularity_score
+            + breakdown.agreement_score)
+            .min(100);
 
-        let confidence = Self::calculate_confidence(total_score, &breakdown);
+        let confidence = strategy.confidence(total_score, &breakdown);
+        let reasons = strategy.explain(info, parsed, &breakdown);
 
         ScoredMatch {
             info: info.clone(),
             score: total_score,
             confidence,
             breakdown,
+            providers: vec![info.provider.clone()],
+            reasons,
+        }
+    }
+
+    /// Describe the score components of a match
+    pub(crate) fn default_reasons(
+        info: &MediaInfo,
+        parsed: &ParsedMedia,
+        breakdown: &ScoreBreakdown,
+    ) -> Vec<String> {
+        let mut reasons = Vec::new();
+
+        match breakdown.title_score {
+            40 => reasons.push("Exact title match".to_string()),
+            0 => reasons.push("Title does not match".to_string()),
+            score => reasons.push(format!("Title similarity {score}/40")),
+        }
+
+        match (info.year, parsed.year) {
+            (Some(a), Some(b)) if a == b => reasons.push(format!("Year matches ({a})")),
+            (Some(a), Some(b)) => reasons.push(format!("Year {a} differs from {b}")),
+            (None, Some(_)) | (Some(_), None) => reasons.push("Year unknown".to_string()),
+            (None, None) => {}
+        }
+
+        match breakdown.type_score {
+            20 => reasons.push(format!("Media type matches ({})", info.media_type)),
+            15 => reasons.push(format!("Compatible media type ({})", info.media_type)),
+            0 if info.is_movie_format() && parsed.episode.is_some() => {
+                reasons.push("Movie release but file has an episode number".to_string());
+            }
+            0 => reasons.push(format!("Media type mismatch ({})", info.media_type)),
+            _ => {}
+        }
+
+        if breakdown.agreement_score > 0 {
+            reasons.push("Confirmed by multiple providers".to_string());
+        }
+
+        reasons
+    }
+
+    /// Score components used by the default strategy
+    pub(crate) fn default_breakdown(info: &MediaInfo, parsed: &ParsedMedia) -> ScoreBreakdown {
+        ScoreBreakdown {
+            // Title matching (0-40 points)
+            title_score: Self::score_title(&info.all_titles(), &parsed.title),
+            // Year matching (0-20 points)
+            year_score: Self::score_year(info.year, parsed.year),
+            // Type matching (0-20 points)
+            type_score: Self::score_info_type(info, parsed),
+            // Provider priority (0-10 points)
+            provider_score: Self::score_provider(&info.provider, info.media_type),
+            // Popularity bonus (0-10 points)
+            popularity_score: Self::score_popularity(info.popularity),
+            agreement_score: 0,
         }
     }
 
@@ -123,8 +465,9 @@ impl Matcher {
         best_score
     }
 
-    fn normalize_title(title: &str) -> String {
-        title
+    pub(crate) fn normalize_title(title: &str) -> String {
+        TitleArticles::active()
+            .strip(title)
             .to_lowercase()
             .chars()
             .filter(|c| c.is_alphanumeric() || c.is_whitespace())
@@ -181,6 +524,40 @@ impl Matcher {
         }
     }
 
+    /// Filename hint, falling back to one derived from the file's runtime
+    const fn effective_hint(parsed: &ParsedMedia) -> MediaHint {
+        match (parsed.hint, parsed.runtime) {
+            (MediaHint::Unknown, Some(minutes)) => Self::runtime_hint(minutes),
+            (hint, _) => hint,
+        }
+    }
+
+    /// A ~24 minute file is almost never a movie, a 2 hour file is rarely an episode
+    const fn runtime_hint(minutes: i32) -> MediaHint {
+        match minutes {
+            1..=60 => MediaHint::TvShow,
+            75.. => MediaHint::Movie,
+            _ => MediaHint::Unknown,
+        }
+    }
+
+    /// Type score, using the provider's release format when it is known
+    fn score_info_type(info: &MediaInfo, parsed: &ParsedMedia) -> i32 {
+        let hint = Self::effective_hint(parsed);
+
+        if info.is_movie_format() {
+            // Franchise films often share the series title; a numbered file is an episode
+            if parsed.episode.is_some() || parsed.air_date.is_some() {
+                return 0;
+            }
+            if hint == MediaHint::Movie {
+                return 20;
+            }
+        }
+
+        Self::score_type(info.media_type, hint)
+    }
+
     const fn score_type(info_type: MediaType, hint: MediaHint) -> i32 {
         match (info_type, hint) {
             // Exact matches
@@ -203,13 +580,27 @@ impl Matcher {
         match (provider, media_type) {
             ("anilist", MediaType::Anime) => 10,
             ("bangumi", MediaType::Anime) => 8,
+            ("anidb", MediaType::Anime) => 9,
             ("tmdb", MediaType::Movie) => 10,
             ("tmdb", MediaType::Tv) => 9,
             ("tmdb", MediaType::Anime) => 5,
+            ("tvdb", MediaType::Tv) => 9,
+            ("tvdb", MediaType::Anime) => 6,
+            ("tvmaze", MediaType::Tv) => 7,
+            ("imdb", MediaType::Movie) => 8,
+            ("imdb", MediaType::Tv) => 7,
             _ => 5,
         }
     }
 
+    const fn score_agreement(provider_count: usize) -> i32 {
+        match provider_count {
+            0 | 1 => 0,
+            2 => 5,
+            _ => 10,
+        }
+    }
+
     fn score_popularity(popularity: Option<f64>) -> i32 {
         match popularity {
             Some(p) if p > 1000.0 => 10,
@@ -220,7 +611,10 @@ impl Matcher {
         }
     }
 
-    const fn calculate_confidence(total_score: i32, breakdown: &ScoreBreakdown) -> Confidence {
+    pub(crate) const fn calculate_confidence(
+        total_score: i32,
+        breakdown: &ScoreBreakdown,
+    ) -> Confidence {
         // Must have decent title match
         if breakdown.title_score < 20 {
             return Confidence::None;
@@ -239,13 +633,16 @@ impl Matcher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scraper::types::ExternalIds;
 
     #[test]
     fn test_normalize_title() {
         assert_eq!(
             Matcher::normalize_title("The Matrix (1999)"),
-            "the matrix 1999"
+            "matrix 1999"
         );
+        // Only English articles are stripped by default
+        assert_eq!(Matcher::normalize_title("La La Land"), "la la land");
         assert_eq!(
             Matcher::normalize_title("Breaking Bad S01E01"),
             "breaking bad s01e01"
@@ -322,6 +719,23 @@ mod tests {
         assert_eq!(ranked[0].info.media_type, MediaType::Tv);
     }
 
+    #[test]
+    fn test_runtime_disambiguates_type() {
+        let results = vec![
+            create_test_info("Cowboy Bebop", Some(1998), MediaType::Movie),
+            create_test_info("Cowboy Bebop", Some(1998), MediaType::Tv),
+        ];
+
+        let mut parsed = create_parsed("Cowboy Bebop", Some(1998), MediaHint::Unknown);
+        parsed.runtime = Some(24);
+        let ranked = Matcher::rank(results.clone(), &parsed);
+        assert_eq!(ranked[0].info.media_type, MediaType::Tv);
+
+        parsed.runtime = Some(115);
+        let ranked = Matcher::rank(results, &parsed);
+        assert_eq!(ranked[0].info.media_type, MediaType::Movie);
+    }
+
     #[test]
     fn test_partial_title_match() {
         let results = vec![create_test_info(
@@ -352,6 +766,175 @@ mod tests {
         assert!(best.is_none());
     }
 
+    #[test]
+    fn test_duplicates_merged_across_providers() {
+        let results = vec![
+            MediaInfo::new("1", "Frieren: Beyond Journey's End", "tmdb")
+                .with_type(MediaType::Tv)
+                .with_year(Some(2023))
+                .with_original_title(Some("葬送のフリーレン".to_string())),
+            MediaInfo::new("154587", "Frieren: Beyond Journey's End", "anilist")
+                .with_type(MediaType::Anime)
+                .with_year(Some(2023))
+                .with_alt_title("Sousou no Frieren"),
+        ];
+        let parsed = create_parsed("Sousou no Frieren", Some(2023), MediaHint::Anime);
+
+        let ranked = Matcher::rank(results, &parsed);
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].providers.len(), 2);
+        assert_eq!(ranked[0].breakdown.agreement_score, 5);
+        assert!(ranked[0].info.all_titles().contains(&"Sousou no Frieren"));
+        assert!(ranked[0].info.all_titles().contains(&"葬送のフリーレン"));
+    }
+
+    #[test]
+    fn test_duplicates_merged_by_external_id() {
+        let a = MediaInfo::new("1", "Title A", "tmdb").with_external_ids(ExternalIds {
+            imdb: Some("tt0000001".to_string()),
+            tmdb: Some("1".to_string()),
+            ..Default::default()
+        });
+        let b = MediaInfo::new("9", "Title B", "other").with_external_ids(ExternalIds {
+            imdb: Some("tt0000001".to_string()),
+            tvdb: Some("42".to_string()),
+            ..Default::default()
+        });
+        let parsed = create_parsed("Title A", None, MediaHint::Unknown);
+
+        let ranked = Matcher::rank(vec![a, b], &parsed);
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].info.provider, "tmdb");
+        assert_eq!(ranked[0].info.external_ids.tmdb.as_deref(), Some("1"));
+        assert_eq!(ranked[0].info.external_ids.tvdb.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_movie_and_series_with_same_id_not_merged() {
+        let ids = ExternalIds {
+            tmdb: Some("1399".to_string()),
+            ..Default::default()
+        };
+        let results = vec![
+            MediaInfo::new("1399", "Game of Thrones", "tmdb")
+                .with_type(MediaType::Tv)
+                .with_external_ids(ids.clone()),
+            MediaInfo::new("1399", "Game Night", "trakt")
+                .with_type(MediaType::Movie)
+                .with_external_ids(ids),
+        ];
+        let parsed = create_parsed("Game of Thrones", None, MediaHint::Unknown);
+
+        let ranked = Matcher::rank(results, &parsed);
+
+        assert_eq!(ranked.len(), 2);
+    }
+
+    #[test]
+    fn test_different_years_not_merged() {
+        let results = vec![
+            MediaInfo::new("1", "Hunter x Hunter", "tmdb")
+                .with_type(MediaType::Tv)
+                .with_year(Some(1999)),
+            MediaInfo::new("2", "Hunter x Hunter", "anilist")
+                .with_type(MediaType::Anime)
+                .with_year(Some(2011)),
+        ];
+        let parsed = create_parsed("Hunter x Hunter", None, MediaHint::Anime);
+
+        let ranked = Matcher::rank(results, &parsed);
+
+        assert_eq!(ranked.len(), 2);
+    }
+
+    fn create_seasons(counts: &[i32]) -> Vec<SeasonInfo> {
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| SeasonInfo {
+                number: i32::try_from(i).unwrap(),
+                name: None,
+                overview: None,
+                air_date: None,
+                episode_count: Some(count),
+                poster_url: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_match_episode_exact() {
+        // Specials (season 0) have 2 episodes, then two seasons of 12
+        let episodes = Matcher::episodes_from_seasons(&create_seasons(&[2, 12, 12]), "tmdb");
+        let parsed = ParsedMedia {
+            season: Some(2),
+            episode: Some(3),
+            ..Default::default()
+        };
+
+        let m = Matcher::match_episode(&parsed, &episodes).unwrap();
+        assert_eq!((m.season, m.episode), (2, 3));
+        assert_eq!(m.absolute_number, Some(15));
+        assert_eq!(m.kind, EpisodeMatchKind::Exact);
+    }
+
+    #[test]
+    fn test_match_episode_absolute_multi_cour() {
+        let episodes = Matcher::episodes_from_seasons(&create_seasons(&[2, 12, 12]), "tmdb");
+        // "[Group] Show - 13" parses as episode 13 without a season
+        let parsed = ParsedMedia {
+            episode: Some(13),
+            ..Default::default()
+        };
+
+        let m = Matcher::match_episode(&parsed, &episodes).unwrap();
+        assert_eq!((m.season, m.episode), (2, 1));
+        assert_eq!(m.kind, EpisodeMatchKind::Absolute);
+
+        let parsed = ParsedMedia {
+            episode: Some(25),
+            ..Default::default()
+        };
+        assert!(Matcher::match_episode(&parsed, &episodes).is_none());
+    }
+
+    #[test]
+    fn test_match_episode_explicit_season_not_remapped() {
+        let episodes = Matcher::episodes_from_seasons(&create_seasons(&[0, 12, 12]), "tmdb");
+
+        // A newly aired episode missing from the summary
+        let parsed = ParsedMedia {
+            season: Some(2),
+            episode: Some(13),
+            ..Default::default()
+        };
+        assert!(Matcher::match_episode(&parsed, &episodes).is_none());
+
+        // A special the provider doesn't list
+        let parsed = ParsedMedia {
+            season: Some(0),
+            episode: Some(5),
+            ..Default::default()
+        };
+        assert!(Matcher::match_episode(&parsed, &episodes).is_none());
+    }
+
+    #[test]
+    fn test_match_episode_air_date() {
+        let mut episodes = Matcher::episodes_from_seasons(&create_seasons(&[0, 3]), "tmdb");
+        episodes[1].air_date = Some("2023-05-14".to_string());
+        let parsed = ParsedMedia {
+            air_date: Some("2023-05-14".to_string()),
+            ..Default::default()
+        };
+
+        let m = Matcher::match_episode(&parsed, &episodes).unwrap();
+        assert_eq!((m.season, m.episode), (1, 2));
+        assert_eq!(m.kind, EpisodeMatchKind::AirDate);
+    }
+
     #[test]
     fn test_anime_tv_compatibility() {
         let results = vec![create_test_info(
@@ -367,4 +950,64 @@ mod tests {
         assert!(!ranked.is_empty());
         assert!(ranked[0].confidence >= Confidence::Medium);
     }
+
+    #[test]
+    fn test_tie_break_on_popularity_with_reason() {
+        let mut original = create_test_info("Dune", Some(1984), MediaType::Movie);
+        original.id = "841".to_string();
+        original.popularity = Some(20.0);
+        let mut remake = create_test_info("Dune", Some(2021), MediaType::Movie);
+        remake.id = "438631".to_string();
+        remake.popularity = Some(50.0);
+        let parsed = create_parsed("Dune", None, MediaHint::Movie);
+
+        let ranked = Matcher::rank(vec![original, remake], &parsed);
+
+        assert_eq!(ranked[0].score, ranked[1].score);
+        assert_eq!(ranked[0].info.id, "438631");
+        assert!(ranked[0].reasons.contains(&"Exact title match".to_string()));
+        assert!(
+            ranked[0]
+                .reasons
+                .iter()
+                .any(|r| r.ends_with("on popularity"))
+        );
+    }
+
+    #[test]
+    fn test_tie_break_on_original_title() {
+        let mut localized = create_test_info("Spirited Away", Some(2001), MediaType::Movie);
+        localized.id = "1".to_string();
+        localized.alt_titles = vec!["Sen to Chihiro no Kamikakushi".to_string()];
+        let mut original = create_test_info("Chihiro", Some(2001), MediaType::Movie);
+        original.id = "2".to_string();
+        original.original_title = Some("Sen to Chihiro no Kamikakushi".to_string());
+        let parsed = create_parsed("Sen to Chihiro no Kamikakushi", Some(2001), MediaHint::Movie);
+
+        let ranked = Matcher::rank(vec![localized, original], &parsed);
+
+        assert_eq!(ranked[0].info.id, "2");
+    }
+
+    #[test]
+    fn test_anime_movie_format_penalized_for_episodes() {
+        let mut series = create_test_info("Demon Slayer", Some(2019), MediaType::Anime);
+        series.id = "1".to_string();
+        series.format = Some("TV".to_string());
+        let mut movie = create_test_info("Demon Slayer", Some(2019), MediaType::Anime);
+        movie.id = "2".to_string();
+        movie.format = Some("MOVIE".to_string());
+        movie.popularity = Some(5000.0);
+
+        let mut parsed = create_parsed("Demon Slayer", Some(2019), MediaHint::Anime);
+        parsed.episode = Some(5);
+        let ranked = Matcher::rank(vec![movie.clone(), series.clone()], &parsed);
+        assert_eq!(ranked[0].info.id, "1");
+        assert_eq!(ranked[1].breakdown.type_score, 0);
+
+        // Without an episode number the film is a fine match for a movie file
+        let parsed = create_parsed("Demon Slayer", Some(2019), MediaHint::Movie);
+        let ranked = Matcher::rank(vec![series, movie], &parsed);
+        assert_eq!(ranked[0].info.id, "2");
+    }
 }
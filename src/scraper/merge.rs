@@ -0,0 +1,269 @@
+use crate::scraper::types::MediaMetadata;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Fields of [`MediaMetadata`] whose source [`MergeRules`] can choose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataField {
+    /// Title and sort title
+    Title,
+    OriginalTitle,
+    Overview,
+    Tagline,
+    ReleaseDate,
+    Runtime,
+    /// Rating and vote count
+    Rating,
+    Genres,
+    Tags,
+    Studios,
+    ContentRating,
+    Cast,
+    Crew,
+    /// Poster, backdrop, logo, thumb and banner
+    Artwork,
+}
+
+/// Which provider each field of merged metadata comes from
+///
+/// Maps fields to provider IDs, most preferred first, e.g. TMDB's plot and
+/// cast with AniList's tags and studios and Bangumi's Chinese title:
+///
+/// ```toml
+/// overview = ["tmdb"]
+/// cast = ["tmdb"]
+/// tags = ["anilist", "tmdb"]
+/// studios = ["anilist"]
+/// title = ["bangumi"]
+/// ```
+///
+/// A field takes the first listed provider's value that is set, then the
+/// matched provider's own, then any other's. Fields without a rule keep the
+/// matched provider's value and only fill gaps from the others. Seasons and
+/// episodes always stay the matched provider's, as their IDs point there.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MergeRules(HashMap<MetadataField, Vec<String>>);
+
+impl MergeRules {
+    /// Rules taking `field` from `providers`, most preferred first
+    #[must_use]
+    pub fn with(mut self, field: MetadataField, providers: &[&str]) -> Self {
+        self.0
+            .insert(field, providers.iter().map(ToString::to_string).collect());
+        self
+    }
+
+    /// Whether there are no rules, so metadata isn't merged at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Providers named by any rule
+    pub fn providers(&self) -> impl Iterator<Item = &str> {
+        let mut providers: Vec<&str> = self.0.values().flatten().map(String::as_str).collect();
+        providers.sort_unstable();
+        providers.dedup();
+        providers.into_iter()
+    }
+
+    /// Combine the matched provider's `primary` metadata with the records
+    /// `others` have for the same title
+    #[must_use]
+    pub fn merge(&self, primary: &MediaMetadata, others: &[MediaMetadata]) -> MediaMetadata {
+        let mut merged = primary.clone();
+        if others.is_empty() {
+            return merged;
+        }
+
+        let title = self.order(MetadataField::Title, primary, others);
+        merged.title = pick(&title, |m| &m.title);
+        merged.sort_title = pick(&title, |m| &m.sort_title);
+
+        let order = |field| self.order(field, primary, others);
+        merged.original_title = pick(&order(MetadataField::OriginalTitle), |m| &m.original_title);
+        merged.overview = pick(&order(MetadataField::Overview), |m| &m.overview);
+        merged.tagline = pick(&order(MetadataField::Tagline), |m| &m.tagline);
+        merged.release_date = pick(&order(MetadataField::ReleaseDate), |m| &m.release_date);
+        merged.runtime = pick(&order(MetadataField::Runtime), |m| &m.runtime);
+        merged.genres = pick(&order(MetadataField::Genres), |m| &m.genres);
+        merged.tags = pick(&order(MetadataField::Tags), |m| &m.tags);
+        merged.studios = pick(&order(MetadataField::Studios), |m| &m.studios);
+        merged.content_rating = pick(&order(MetadataField::ContentRating), |m| &m.content_rating);
+        merged.cast = pick(&order(MetadataField::Cast), |m| &m.cast);
+        merged.crew = pick(&order(MetadataField::Crew), |m| &m.crew);
+
+        // Keep a rating with the vote count it was given with
+        let rating = order(MetadataField::Rating);
+        if let Some(source) = rating.iter().find(|m| m.rating.is_some()) {
+            merged.rating = source.rating;
+            merged.vote_count = source.vote_count;
+        }
+
+        let artwork = order(MetadataField::Artwork);
+        merged.images.poster = pick(&artwork, |m| &m.images.poster);
+        merged.images.backdrop = pick(&artwork, |m| &m.images.backdrop);
+        merged.images.logo = pick(&artwork, |m| &m.images.logo);
+        merged.images.thumb = pick(&artwork, |m| &m.images.thumb);
+        merged.images.banner = pick(&artwork, |m| &m.images.banner);
+
+        // Everything else only fills gaps
+        let gaps: Vec<_> = std::iter::once(primary).chain(others).collect();
+        merged.end_date = pick(&gaps, |m| &m.end_date);
+        merged.language = pick(&gaps, |m| &m.language);
+        merged.status = pick(&gaps, |m| &m.status);
+        merged.collection = pick(&gaps, |m| &m.collection);
+        merged.videos = pick(&gaps, |m| &m.videos);
+
+        for other in others {
+            for title in std::iter::once(&other.title).chain(&other.aliases) {
+                if *title != merged.title && !merged.aliases.contains(title) {
+                    merged.aliases.push(title.clone());
+                }
+            }
+            for rating in &other.ratings {
+                if !merged.ratings.iter().any(|r| r.source == rating.source) {
+                    merged.ratings.push(rating.clone());
+                }
+            }
+            merged.external_ids.merge(&other.external_ids);
+        }
+        // The matched provider's IDs win where both are set
+        merged.external_ids.merge(&primary.external_ids);
+
+        merged
+    }
+
+    /// Records in the order `field` is taken from them
+    fn order<'a>(
+        &self,
+        field: MetadataField,
+        primary: &'a MediaMetadata,
+        others: &'a [MediaMetadata],
+    ) -> Vec<&'a MediaMetadata> {
+        let all = || std::iter::once(primary).chain(others);
+        let mut order: Vec<&MediaMetadata> = Vec::with_capacity(others.len() + 1);
+        for provider in self.0.get(&field).into_iter().flatten() {
+            order.extend(all().filter(|m| m.provider == *provider));
+        }
+        for m in all() {
+            if !order.iter().any(|o| std::ptr::eq(*o, m)) {
+                order.push(m);
+            }
+        }
+        order
+    }
+}
+
+/// Value of the first record that has it set, or the first record's
+fn pick<T: Value + Clone>(order: &[&MediaMetadata], get: impl Fn(&MediaMetadata) -> &T) -> T {
+    order
+        .iter()
+        .map(|m| get(m))
+        .find(|v| v.is_set())
+        .unwrap_or_else(|| get(order[0]))
+        .clone()
+}
+
+/// A metadata value that can be missing
+trait Value {
+    fn is_set(&self) -> bool;
+}
+
+impl<T> Value for Option<T> {
+    fn is_set(&self) -> bool {
+        self.is_some()
+    }
+}
+
+impl<T> Value for Vec<T> {
+    fn is_set(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl Value for String {
+    fn is_set(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraper::types::{ExternalIds, MediaType};
+
+    fn record(provider: &str, title: &str) -> MediaMetadata {
+        MediaMetadata {
+            id: "1".to_string(),
+            title: title.to_string(),
+            media_type: MediaType::Anime,
+            provider: provider.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_by_field() {
+        let tmdb = MediaMetadata {
+            overview: Some("A mage outlives her party.".to_string()),
+            tags: vec!["based on manga".to_string()],
+            studios: vec!["Toho".to_string()],
+            rating: Some(8.8),
+            vote_count: Some(900),
+            external_ids: ExternalIds {
+                tmdb: Some("209867".to_string()),
+                ..Default::default()
+            },
+            ..record("tmdb", "Frieren: Beyond Journey's End")
+        };
+        let anilist = MediaMetadata {
+            overview: Some("AniList synopsis".to_string()),
+            tags: vec!["Elf".to_string(), "Travel".to_string()],
+            studios: vec!["Madhouse".to_string()],
+            rating: Some(9.1),
+            status: Some("Finished".to_string()),
+            external_ids: ExternalIds {
+                anilist: Some("154587".to_string()),
+                tmdb: Some("other".to_string()),
+                ..Default::default()
+            },
+            ..record("anilist", "Sousou no Frieren")
+        };
+        let bangumi = record("bangumi", "葬送的芙莉莲");
+
+        let rules = MergeRules::default()
+            .with(MetadataField::Title, &["bangumi"])
+            .with(MetadataField::Tags, &["anilist", "tmdb"])
+            .with(MetadataField::Studios, &["anilist"]);
+        let merged = rules.merge(&tmdb, &[anilist, bangumi]);
+
+        assert_eq!(merged.title, "葬送的芙莉莲");
+        assert_eq!(merged.tags, ["Elf", "Travel"]);
+        assert_eq!(merged.studios, ["Madhouse"]);
+        // Without a rule the matched provider's value stays, and gaps are filled
+        assert_eq!(merged.overview.as_deref(), Some("A mage outlives her party."));
+        assert_eq!((merged.rating, merged.vote_count), (Some(8.8), Some(900)));
+        assert_eq!(merged.status.as_deref(), Some("Finished"));
+        assert_eq!(merged.provider, "tmdb");
+        assert!(merged.aliases.contains(&"Sousou no Frieren".to_string()));
+        assert_eq!(merged.external_ids.tmdb.as_deref(), Some("209867"));
+        assert_eq!(merged.external_ids.anilist.as_deref(), Some("154587"));
+    }
+
+    #[test]
+    fn test_merge_rules_from_toml() {
+        let rules: MergeRules = toml::from_str(
+            r#"
+            overview = ["tmdb"]
+            original_title = ["anilist", "tmdb"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.providers().collect::<Vec<_>>(), ["anilist", "tmdb"]);
+        assert!(MergeRules::default().is_empty());
+    }
+}
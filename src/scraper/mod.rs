@@ -1,30 +1,68 @@
+mod budget;
 mod cache;
+mod disc;
 mod downloader;
+mod events;
+mod ignore;
+mod journal;
 mod manager;
 mod matcher;
+mod merge;
 mod organizer;
 mod parser;
 mod provider;
 mod scanner;
+mod strategy;
+mod trailer;
 mod types;
 mod writer;
 
+pub use budget::{ScrapeBudget, ScrapePermit, ScrapePriority};
 pub use cache::{CacheConfig, ScraperCache};
-pub use downloader::Downloader;
-pub use manager::{ScrapeResult, ScraperConfig, ScraperManager};
-pub use matcher::{Confidence, Matcher, ScoredMatch};
+pub use disc::{DiscKind, IsoContent, is_iso, probe_iso};
+pub use downloader::{
+    ArtworkFormat, ArtworkOptions, ArtworkResult, ArtworkStatus, DEFAULT_THEME_MUSIC_URL,
+    DownloadError, DownloadPermit, DownloadQueue, Downloader, ImageCache,
+};
+pub use events::{ScrapeEvent, ScrapeEventKind, ScrapeEvents};
+pub use ignore::{IGNORE_FILES, IgnoreRules};
+pub use journal::{Journal, JournalEntry, journal_path_for};
+pub use manager::{
+    MatchExplanation, ProviderSearch, ScrapeResult, ScraperConfig, ScraperManager, SearchOutcome,
+};
+pub use matcher::{Confidence, EpisodeMatch, EpisodeMatchKind, Matcher, ScoreBreakdown, ScoredMatch};
+pub use merge::{MergeRules, MetadataField};
 pub use organizer::{
-    BatchOrganizeResult, NamingTemplate, OrganizeMethod, OrganizeResult, Organizer, OrganizerConfig,
+    AnimeLayout, BatchOrganizeResult, ConflictPolicy, DEFAULT_COMPANION_EXTENSIONS,
+    DEFAULT_CONCURRENCY, DEFAULT_MIN_FILE_SIZE, ExtraKind, ExtrasLayout, NamingTemplate,
+    OrganizeClaims, OrganizeMethod, OrganizeProgress, OrganizeResult, Organizer, OrganizerConfig,
+    ProgressSnapshot, RollbackResult, TargetOs,
 };
-pub use parser::{MediaHint, ParsedMedia, Parser};
+pub use parser::{DEFAULT_ARTICLE_LANGUAGES, MediaHint, ParsedMedia, Parser, TitleArticles};
 pub use provider::{
-    AniListProvider, BangumiProvider, HttpClient, MetadataProvider, SearchOptions, TmdbProvider,
+    AniDbProvider, AniListProvider, BangumiProvider, DiscoverList, DiscoverOptions,
+    DoubanProvider, EpisodeMapping, GoogleBooksProvider, HealthStatus, HttpClient,
+    IMDB_DATASET_FILES, ImdbProvider, ImportSummary, MangaDexProvider, MetadataProvider,
+    MusicBrainzProvider, NetworkOptions, OmdbProvider, OpenLibraryProvider, ProviderHealth, Quota,
+    RateLimitedProvider, SearchOptions, TmdbCredential, TmdbProvider, TraktProvider,
+    TvMazeProvider, TvdbProvider, WebhookOptions, WebhookProvider,
+};
+pub use scanner::{DEFAULT_MAX_DEPTH, LoopGuard, ScanReport, Scanner, VIDEO_EXTENSIONS};
+pub use strategy::{
+    AnimeStrategy, DefaultStrategy, ExternalIdFirstStrategy, MatchStrategy, MatchStrategyKind,
+    StrictStrategy,
 };
-pub use scanner::Scanner;
+pub use trailer::{DEFAULT_YTDLP_PATH, TrailerDownloader, TrailerError};
 pub use types::{
-    EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType, PersonInfo, SeasonInfo,
+    normalize_isbn, AiringEpisode, AlbumInfo, AlbumMetadata, ArtistInfo, Artwork, BookInfo,
+    BookMetadata, ChapterInfo, CollectionInfo, ComicInfo, ComicMetadata, CoverInfo, EpisodeInfo,
+    ExternalIds, ExternalRating, ImageGallery, ImageSet, ImageSize, MediaInfo, MediaMetadata,
+    MediaType, PersonInfo, RegionAvailability, RelatedMedia, SeasonInfo, TrackInfo, VideoInfo,
+    WatchAvailability, WatchProvider,
+};
+pub use writer::{
+    METADATA_JSON, MetadataFormat, NfoDialect, NfoField, NfoProfile, Writer,
 };
-pub use writer::Writer;
 
 use std::time::Duration;
 
@@ -60,21 +98,26 @@ pub enum ScraperError {
 
     #[error("XML error: {0}")]
     Xml(#[from] quick_xml::DeError),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
 }
 
 /// Create a default scraper manager with all providers
-#[must_use] 
-pub fn create_default_manager(tmdb_api_key: Option<&str>) -> ScraperManager {
+///
+/// Fails when the TMDB key can't be sent in a request.
+pub fn create_default_manager(tmdb_api_key: Option<&str>) -> Result<ScraperManager> {
     let mut manager = ScraperManager::new();
 
     // Add TMDB if API key is provided
     if let Some(key) = tmdb_api_key {
-        manager.add_provider(TmdbProvider::new(key));
+        manager.add_provider(TmdbProvider::new(key)?);
     }
 
     // Add providers that don't require API keys
     manager.add_provider(AniListProvider::new());
     manager.add_provider(BangumiProvider::new());
+    manager.add_provider(TvMazeProvider::new());
 
-    manager
+    Ok(manager)
 }
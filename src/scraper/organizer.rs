@@ -1,13 +1,30 @@
 //! Media file organizer - organize media files into structured directories
 
+use chrono::Utc;
+use futures::future::{self, join_all};
+use futures::{StreamExt, stream};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tracing::{info, warn};
-
-use super::{MediaMetadata, MediaType, ParsedMedia, Parser, ScraperError, ScraperManager};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use super::journal::{Journal, JournalEntry};
+use super::{
+    ArtworkOptions, ArtworkResult, Downloader, EpisodeMatch, METADATA_JSON, MediaInfo,
+    MediaMetadata, MediaType, MetadataFormat, NfoProfile, ParsedMedia, Parser, ScrapeBudget,
+    ScrapeEventKind, ScrapeResult, ScraperError, ScraperManager, TrailerDownloader, Writer, is_iso,
+    probe_iso,
+};
 
 /// Organization method
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OrganizeMethod {
     /// Create symbolic links (default, safest)
     #[default]
@@ -18,6 +35,8 @@ pub enum OrganizeMethod {
     Move,
     /// Copy files
     Copy,
+    /// Rename files where they are, leaving the folder structure alone
+    Rename,
 }
 
 impl std::fmt::Display for OrganizeMethod {
@@ -27,6 +46,7 @@ impl std::fmt::Display for OrganizeMethod {
             Self::Hardlink => write!(f, "hardlink"),
             Self::Move => write!(f, "move"),
             Self::Copy => write!(f, "copy"),
+            Self::Rename => write!(f, "rename"),
         }
     }
 }
@@ -40,38 +60,362 @@ impl std::str::FromStr for OrganizeMethod {
             "hardlink" | "hard" => Ok(Self::Hardlink),
             "move" | "mv" => Ok(Self::Move),
             "copy" | "cp" => Ok(Self::Copy),
+            "rename" | "in-place" | "inplace" => Ok(Self::Rename),
             _ => Err(format!("Unknown method: {s}")),
         }
     }
 }
 
+/// What to do when the target path is already taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Leave the existing file and fail this one
+    #[default]
+    Skip,
+    /// Replace the existing file
+    Overwrite,
+    /// Keep both, adding ` (1)`, ` (2)`... to the new file's name
+    RenameWithSuffix,
+    /// Replace the existing file only if the new one is larger
+    KeepLarger,
+    /// Replace the existing file only if the new one was modified more recently
+    KeepNewer,
+    /// Replace the existing file only if the new one is a PROPER/REPACK release
+    PreferProper,
+}
+
+/// Kind of bonus material, from the file name or its folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtraKind {
+    Trailer,
+    Featurette,
+    BehindTheScenes,
+    DeletedScene,
+    Interview,
+    Scene,
+    Short,
+    Other,
+}
+
+impl ExtraKind {
+    /// Classify a file as an extra, if it is one
+    ///
+    /// Recognizes Plex-style suffixes (`Movie-trailer.mkv`, `Clip-deleted.mkv`), loose
+    /// keywords and extras folders (`Featurettes/Making Of.mkv`).
+    #[must_use]
+    pub fn detect(path: &Path) -> Option<Self> {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let folder = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if let Some(kind) = Self::from_folder(&folder) {
+            return Some(kind);
+        }
+
+        if let Some((_, suffix)) = stem.rsplit_once('-')
+            && let Some(kind) = Self::from_suffix(suffix.trim())
+        {
+            return Some(kind);
+        }
+
+        let words: Vec<&str> = stem
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.contains(&"trailer") {
+            Some(Self::Trailer)
+        } else if words.contains(&"featurette") {
+            Some(Self::Featurette)
+        } else {
+            None
+        }
+    }
+
+    fn from_folder(folder: &str) -> Option<Self> {
+        match folder {
+            "trailers" => Some(Self::Trailer),
+            "featurettes" => Some(Self::Featurette),
+            "behind the scenes" => Some(Self::BehindTheScenes),
+            "deleted scenes" => Some(Self::DeletedScene),
+            "interviews" => Some(Self::Interview),
+            "scenes" => Some(Self::Scene),
+            "shorts" => Some(Self::Short),
+            "extras" | "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+
+    fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix {
+            "trailer" => Some(Self::Trailer),
+            "featurette" => Some(Self::Featurette),
+            "behindthescenes" => Some(Self::BehindTheScenes),
+            "deleted" => Some(Self::DeletedScene),
+            "interview" => Some(Self::Interview),
+            "scene" => Some(Self::Scene),
+            "short" => Some(Self::Short),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Folder layout for anime episodes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimeLayout {
+    /// `Title/Season 01/Title - S01E45.mkv`, like other shows
+    #[default]
+    Seasons,
+    /// `Title/Title - 045.mkv`, numbered across the whole series
+    Absolute,
+}
+
+/// Filesystem naming rules organized paths must satisfy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetOs {
+    /// 255 bytes per file or folder name
+    Unix,
+    /// 255 characters per name and 260 for the whole path (`MAX_PATH`)
+    Windows,
+}
+
+impl Default for TargetOs {
+    fn default() -> Self {
+        if cfg!(windows) { Self::Windows } else { Self::Unix }
+    }
+}
+
+impl TargetOs {
+    /// How far `path` exceeds the name or path length limits (0 if it fits)
+    fn path_excess(self, path: &Path) -> usize {
+        let len = |s: &std::ffi::OsStr| match self {
+            Self::Unix => s.len(),
+            Self::Windows => s.to_string_lossy().encode_utf16().count(),
+        };
+
+        let name_excess = path
+            .components()
+            .map(|c| len(c.as_os_str()))
+            .max()
+            .unwrap_or(0)
+            .saturating_sub(MAX_NAME_LEN);
+        let path_excess = match self {
+            Self::Unix => 0,
+            Self::Windows => len(path.as_os_str()).saturating_sub(MAX_WINDOWS_PATH),
+        };
+
+        name_excess.max(path_excess)
+    }
+}
+
+/// Where extras (trailers, featurettes...) end up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtrasLayout {
+    /// Treat extras like any other file (skipped when `skip_extras` is set)
+    #[default]
+    None,
+    /// Subfolders of the movie folder named the Jellyfin way (`trailers/`, `extras/`)
+    Jellyfin,
+    /// Subfolders of the movie folder named the Plex way (`Trailers/`, `Other/`)
+    Plex,
+}
+
+impl ExtrasLayout {
+    /// Folder name for an extra of the given kind
+    #[must_use]
+    pub const fn folder(self, kind: ExtraKind) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Jellyfin => Some(match kind {
+                ExtraKind::Trailer => "trailers",
+                ExtraKind::Featurette => "featurettes",
+                ExtraKind::BehindTheScenes => "behind the scenes",
+                ExtraKind::DeletedScene => "deleted scenes",
+                ExtraKind::Interview => "interviews",
+                ExtraKind::Scene => "scenes",
+                ExtraKind::Short => "shorts",
+                ExtraKind::Other => "extras",
+            }),
+            Self::Plex => Some(match kind {
+                ExtraKind::Trailer => "Trailers",
+                ExtraKind::Featurette => "Featurettes",
+                ExtraKind::BehindTheScenes => "Behind The Scenes",
+                ExtraKind::DeletedScene => "Deleted Scenes",
+                ExtraKind::Interview => "Interviews",
+                ExtraKind::Scene => "Scenes",
+                ExtraKind::Short => "Shorts",
+                ExtraKind::Other => "Other",
+            }),
+        }
+    }
+}
+
 /// Naming template for organized files
+///
+/// Available variables: `{title}`, `{year}`, `{season}`, `{episode}`, `{episode_range}`,
+/// `{resolution}`, `{quality}`, `{codec}`, `{group}`, `{edition}`, `{imdb_id}`,
+/// `{tmdb_id}`, `{tvdb_id}`, `{media_type}`, `{genre}`, `{language}`, `{decade}` and
+/// `{absolute}` (anime episode number across the whole series).
+///
+/// Text wrapped in an outer pair of braces is a conditional segment, dropped entirely
+/// when any variable inside it is missing: `{title}{ ({year})}{ - {edition}}`.
 #[derive(Debug, Clone)]
 pub struct NamingTemplate {
-    /// Movie folder: {title} ({year})
+    /// Library root layout above the show/movie folder, e.g. `{media_type}/{genre}`
+    ///
+    /// `/` separates folders. Replaces the `separate_by_type` folders when set.
+    pub library_root: Option<String>,
+    /// Movie folder: {title}{ ({year})}
     pub movie_folder: String,
-    /// Movie file: {title} ({year})
+    /// Movie file: {title}{ ({year})}
     pub movie_file: String,
-    /// TV show folder: {title} ({year})
+    /// TV show folder: {title}{ ({year})}
     pub tv_folder: String,
     /// Season folder: Season {season:02}
     pub season_folder: String,
-    /// Episode file: {title} - S{season:02}E{episode:02}
+    /// Episode file: {title} - S{season:02}{episode_range}
+    ///
+    /// `{episode_range}` expands to `E01` or `E01-E02` for multi-episode files.
     pub episode_file: String,
+    /// Anime episode file in the absolute layout: {title} - {absolute:03}
+    pub anime_file: String,
 }
 
 impl Default for NamingTemplate {
     fn default() -> Self {
         Self {
-            movie_folder: "{title} ({year})".to_string(),
-            movie_file: "{title} ({year})".to_string(),
-            tv_folder: "{title} ({year})".to_string(),
+            library_root: None,
+            movie_folder: "{title}{ ({year})}".to_string(),
+            movie_file: "{title}{ ({year})}".to_string(),
+            tv_folder: "{title}{ ({year})}".to_string(),
             season_folder: "Season {season:02}".to_string(),
-            episode_file: "{title} - S{season:02}E{episode:02}".to_string(),
+            episode_file: "{title} - S{season:02}{episode_range}".to_string(),
+            anime_file: "{title} - {absolute:03}".to_string(),
         }
     }
 }
 
+/// Default companion file extensions
+pub const DEFAULT_COMPANION_EXTENSIONS: &[&str] = &[
+    "srt", "ass", "ssa", "sub", "idx", "vtt", "sup", "nfo", "jpg", "jpeg", "png", "webp",
+];
+
+/// Default minimum size of a video file to organize (50 MiB)
+pub const DEFAULT_MIN_FILE_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Default number of files organized concurrently
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Folder names holding bonus material rather than the main feature
+const EXTRAS_FOLDERS: &[&str] = &[
+    "extras",
+    "featurettes",
+    "behind the scenes",
+    "deleted scenes",
+    "interviews",
+    "scenes",
+    "shorts",
+    "trailers",
+];
+
+/// Leftover release files that don't keep a source folder alive
+const JUNK_EXTENSIONS: &[&str] = &["nfo", "txt", "jpg", "jpeg", "png", "sfv", "md5", "url", "nzb"];
+
+/// Junk files larger than this are kept, in case they matter to the user
+const JUNK_MAX_SIZE: u64 = 5 * 1024 * 1024;
+
+/// NFO of a collection in [`OrganizerConfig::movie_sets_dir`]
+const COLLECTION_NFO: &str = "collection.nfo";
+
+/// Longest file or folder name most filesystems accept
+const MAX_NAME_LEN: usize = 255;
+
+/// `MAX_PATH` minus the terminating NUL
+const MAX_WINDOWS_PATH: usize = 259;
+
+/// Windows device names, rejected even with an extension (`CON.mkv`)
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Folder-level artwork names kept as-is when a directory holds a single video
+const FOLDER_ARTWORK: &[&str] = &[
+    "poster", "folder", "cover", "fanart", "backdrop", "banner", "logo", "clearart", "landscape",
+    "thumb", "disc",
+];
+
+/// Values available to naming templates
+#[derive(Debug, Clone, Default)]
+struct TemplateVars {
+    title: String,
+    year: Option<i32>,
+    season: Option<i32>,
+    episode: Option<i32>,
+    episode_end: Option<i32>,
+    absolute: Option<i32>,
+    resolution: Option<String>,
+    quality: Option<String>,
+    codec: Option<String>,
+    group: Option<String>,
+    edition: Option<String>,
+    imdb_id: Option<String>,
+    tmdb_id: Option<String>,
+    tvdb_id: Option<String>,
+    media_type: Option<String>,
+    genre: Option<String>,
+    language: Option<String>,
+}
+
+impl TemplateVars {
+    /// Look up a variable: `None` if the name is unknown, `Some(None)` if it has no value
+    fn get(&self, name: &str) -> Option<Option<String>> {
+        let value = match name {
+            "title" => Some(self.title.clone()),
+            "year" => self.year.map(|y| y.to_string()),
+            "season" => self.season.map(|s| s.to_string()),
+            "season:02" => self.season.map(|s| format!("{s:02}")),
+            "episode" => self.episode.map(|e| e.to_string()),
+            "episode:02" => self.episode.map(|e| format!("{e:02}")),
+            "absolute" => self.absolute.map(|e| e.to_string()),
+            "absolute:02" => self.absolute.map(|e| format!("{e:02}")),
+            "absolute:03" => self.absolute.map(|e| format!("{e:03}")),
+            "episode_range" => self.episode.map(|e| match self.episode_end {
+                Some(end) if end > e => format!("E{e:02}-E{end:02}"),
+                _ => format!("E{e:02}"),
+            }),
+            "resolution" => self.resolution.clone(),
+            "quality" => self.quality.clone(),
+            "codec" => self.codec.clone(),
+            "group" => self.group.clone(),
+            "edition" => self.edition.clone(),
+            "imdb_id" => self.imdb_id.clone(),
+            "tmdb_id" => self.tmdb_id.clone(),
+            "tvdb_id" => self.tvdb_id.clone(),
+            "media_type" => self.media_type.clone(),
+            "genre" => self.genre.clone(),
+            "language" => self.language.clone(),
+            "decade" => self.year.map(|y| format!("{}s", y - y.rem_euclid(10))),
+            _ => return None,
+        };
+
+        Some(value.filter(|v| !v.is_empty()))
+    }
+}
+
 /// Organizer configuration
 #[derive(Debug, Clone)]
 pub struct OrganizerConfig {
@@ -87,8 +431,55 @@ pub struct OrganizerConfig {
     pub separate_by_type: bool,
     /// Dry run mode (don't actually move/link files)
     pub dry_run: bool,
-    /// Whether to overwrite existing files
-    pub overwrite: bool,
+    /// How to handle targets that already exist
+    pub conflict: ConflictPolicy,
+    /// Journal file recording operations for rollback (disabled when `None`)
+    pub journal_path: Option<PathBuf>,
+    /// Extensions of sibling files (subtitles, NFO, artwork) organized with the media
+    pub companion_extensions: Vec<String>,
+    /// Video files smaller than this many bytes are skipped (0 disables)
+    pub min_file_size: u64,
+    /// Skip sample clips (`*-sample.mkv`, `Sample/` folders)
+    pub skip_samples: bool,
+    /// Skip trailers, featurettes and files in extras folders
+    pub skip_extras: bool,
+    /// After a move, remove source folders left empty (or holding only small junk)
+    pub cleanup_source: bool,
+    /// When the target exists, add the file as another version (`Movie (2020) - 2160p.mkv`)
+    pub keep_versions: bool,
+    /// Number of files looked up and organized concurrently
+    pub concurrency: usize,
+    /// Hash source and target after copying (including moves across filesystems)
+    pub verify_checksum: bool,
+    /// Place extras in subfolders of their movie instead of skipping them
+    pub extras_layout: ExtrasLayout,
+    /// With [`OrganizeMethod::Rename`], also rename a movie's folder to the folder template
+    pub rename_folder: bool,
+    /// Write `movie.nfo`/`tvshow.nfo` next to identified media, or the
+    /// sidecars [`OrganizerConfig::metadata_format`] selects
+    pub write_nfo: bool,
+    /// NFOs, a `metadata.json` with the full metadata, or both
+    pub metadata_format: MetadataFormat,
+    /// Download poster, fanart and season posters next to identified media
+    pub download_artwork: bool,
+    /// Which artwork [`OrganizerConfig::download_artwork`] saves, and whether
+    /// it replaces existing files
+    pub artwork: ArtworkOptions,
+    /// Extra fields [`OrganizerConfig::write_nfo`] adds for the media server
+    pub nfo: NfoProfile,
+    /// Movie Set Information Folder: movies in a collection get its
+    /// `collection.nfo` and artwork in a subfolder named after it
+    pub movie_sets_dir: Option<PathBuf>,
+    /// Season folders or a flat absolute-numbered layout for anime
+    pub anime_layout: AnimeLayout,
+    /// Filesystem whose name and path length limits targets must fit (defaults to the host)
+    pub target_os: TargetOs,
+}
+
+impl OrganizerConfig {
+    fn journal(&self) -> Option<Journal> {
+        self.journal_path.as_ref().map(Journal::new)
+    }
 }
 
 impl Default for OrganizerConfig {
@@ -100,7 +491,29 @@ impl Default for OrganizerConfig {
             template: NamingTemplate::default(),
             separate_by_type: true,
             dry_run: false,
-            overwrite: false,
+            conflict: ConflictPolicy::Skip,
+            journal_path: None,
+            companion_extensions: DEFAULT_COMPANION_EXTENSIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            min_file_size: DEFAULT_MIN_FILE_SIZE,
+            skip_samples: true,
+            skip_extras: true,
+            cleanup_source: false,
+            keep_versions: false,
+            concurrency: DEFAULT_CONCURRENCY,
+            verify_checksum: false,
+            extras_layout: ExtrasLayout::None,
+            rename_folder: false,
+            write_nfo: false,
+            metadata_format: MetadataFormat::Nfo,
+            download_artwork: false,
+            artwork: ArtworkOptions::default(),
+            nfo: NfoProfile::default(),
+            movie_sets_dir: None,
+            anime_layout: AnimeLayout::Seasons,
+            target_os: TargetOs::default(),
         }
     }
 }
@@ -120,17 +533,57 @@ pub struct OrganizeResult {
     pub parsed: ParsedMedia,
     /// Matched metadata (if any)
     pub metadata: Option<MediaMetadata>,
+    /// Target paths of companion files organized with the media
+    pub companions: Vec<PathBuf>,
+    /// Version label when organized alongside an existing copy (e.g. "2160p")
+    pub version: Option<String>,
+    /// Checksum verification: `None` when not checked, `Some(false)` on mismatch
+    pub verified: Option<bool>,
+    /// Kind of extra when placed next to its movie
+    pub extra: Option<ExtraKind>,
+    /// Conflict policy that was applied because the target already existed
+    pub conflict: Option<ConflictPolicy>,
+    /// NFO and artwork files written for the media
+    pub artifacts: Vec<PathBuf>,
+    /// Outcome of each artwork file saved for the media, failures included
+    pub artwork: Vec<ArtworkResult>,
+}
+
+/// Outcome of a single filesystem operation
+#[derive(Debug, Default)]
+struct Transfer {
+    success: bool,
+    error: Option<String>,
+    verified: Option<bool>,
+    /// Conflict policy applied to an existing target
+    conflict: Option<ConflictPolicy>,
+    /// Actual target when the policy picked a different name
+    renamed: Option<PathBuf>,
+}
+
+impl Transfer {
+    fn failed(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            error: Some(error.into()),
+            ..Default::default()
+        }
+    }
 }
 
 /// Batch organize result
 #[derive(Debug, Default)]
 pub struct BatchOrganizeResult {
+    /// ID of this run in the journal, used for rollback
+    pub batch_id: String,
     /// Successfully organized files
     pub success: Vec<OrganizeResult>,
     /// Failed files
     pub failed: Vec<OrganizeResult>,
     /// Skipped files (not video, already exists, etc.)
     pub skipped: Vec<(PathBuf, String)>,
+    /// Whether the run was cancelled before all files were processed
+    pub cancelled: bool,
 }
 
 impl BatchOrganizeResult {
@@ -150,10 +603,183 @@ impl BatchOrganizeResult {
     }
 }
 
+/// Live progress of an organize run, shared with whoever is watching it
+#[derive(Debug, Default)]
+pub struct OrganizeProgress {
+    total: AtomicUsize,
+    scanned: AtomicUsize,
+    matched: AtomicUsize,
+    linked: AtomicUsize,
+    failed: AtomicUsize,
+    skipped: AtomicUsize,
+    cancelled: CancellationToken,
+}
+
+/// Point-in-time copy of [`OrganizeProgress`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressSnapshot {
+    /// Video files found in the source directory
+    pub total: usize,
+    /// Files processed so far
+    pub scanned: usize,
+    /// Files identified by a metadata provider
+    pub matched: usize,
+    /// Files organized into the library
+    pub linked: usize,
+    /// Files that failed to organize
+    pub failed: usize,
+    /// Files skipped (samples, extras, errors)
+    pub skipped: usize,
+}
+
+impl OrganizeProgress {
+    /// Ask the run to stop after the file currently being processed
+    pub fn cancel(&self) {
+        self.cancelled.cancel();
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.is_cancelled()
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        ProgressSnapshot {
+            total: self.total.load(Ordering::Relaxed),
+            scanned: self.scanned.load(Ordering::Relaxed),
+            matched: self.matched.load(Ordering::Relaxed),
+            linked: self.linked.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            skipped: self.skipped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record(&self, counter: &AtomicUsize) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Result of rolling back an organize run
+#[derive(Debug, Default)]
+pub struct RollbackResult {
+    /// Targets that were reverted
+    pub reverted: Vec<PathBuf>,
+    /// Targets that could not be reverted
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Organize runs stay revertible this long; older ones are dropped from the
+/// journal, and the files they replaced deleted
+const JOURNAL_RETENTION_DAYS: i64 = 30;
+
+/// Source each journaled target was organized from, for
+/// [`ConflictPolicy::PreferProper`]; read once per batch, and kept up to date
+/// as the batch organizes more
+#[derive(Default)]
+struct JournaledSources(Mutex<HashMap<PathBuf, PathBuf>>);
+
+impl JournaledSources {
+    fn read(journal: &Journal) -> Self {
+        let entries = journal.entries().unwrap_or_else(|e| {
+            warn!("Failed to read organizer journal: {}", e);
+            Vec::new()
+        });
+        // Later entries win
+        let sources = entries.into_iter().map(|e| (e.target, e.source)).collect();
+        Self(Mutex::new(sources))
+    }
+
+    fn get(&self, target: &Path) -> Option<PathBuf> {
+        self.0.lock().get(target).cloned()
+    }
+
+    fn insert(&self, target: &Path, source: &Path) {
+        self.0.lock().insert(target.to_path_buf(), source.to_path_buf());
+    }
+}
+
+/// Targets (and sources) of the files being organized, so concurrent files
+/// never race for a target
+///
+/// Organizers given the same claims with [`Organizer::with_claims`] also
+/// stay out of each other's way.
+#[derive(Debug, Default)]
+pub struct OrganizeClaims {
+    in_flight: Mutex<HashSet<PathBuf>>,
+    /// Woken whenever files leave `in_flight`
+    released: Notify,
+}
+
 /// Media file organizer
 pub struct Organizer {
-    config: OrganizerConfig,
-    scraper: Option<ScraperManager>,
+    config: Arc<OrganizerConfig>,
+    scraper: Option<Arc<ScraperManager>>,
+    claims: Arc<OrganizeClaims>,
+    /// Show, movie and collection folders whose artwork was saved already
+    artwork_folders: Mutex<HashSet<PathBuf>>,
+    /// Movie folders renamed in place, so the other files they held are
+    /// found at their new path instead of renaming the folder again
+    renamed_folders: Mutex<HashMap<PathBuf, PathBuf>>,
+    /// Read by [`Organizer::begin_batch`] for the batch being organized
+    journaled: Mutex<Arc<JournaledSources>>,
+}
+
+/// Where a file goes before looking at what's on disk there
+struct TargetClaim {
+    target: PathBuf,
+    /// Movie folder renamed along with a file renamed in place
+    folder_rename: Option<(PathBuf, PathBuf)>,
+}
+
+impl TargetClaim {
+    /// Paths no other file may be organized from or to meanwhile
+    fn paths(&self, source: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![source.to_path_buf(), self.target.clone()];
+        paths.extend(self.folder_rename.iter().map(|(from, _)| from.clone()));
+        paths
+    }
+}
+
+/// Where [`Organizer::plan_transfer`] decided a file goes
+struct TransferPlan {
+    target: PathBuf,
+    /// Movie folder renamed along with a file renamed in place
+    folder_rename: Option<(PathBuf, PathBuf)>,
+    version: Option<String>,
+    conflict: Option<ConflictPolicy>,
+    companions: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Paths claimed in [`OrganizeClaims`], given back when dropped
+struct Reservation<'a> {
+    claims: &'a OrganizeClaims,
+    paths: Vec<PathBuf>,
+}
+
+impl Reservation<'_> {
+    /// Claim `path` too, unless another file holds it
+    fn extend(&mut self, path: &Path) -> bool {
+        if self.paths.iter().any(|p| p == path) {
+            return true;
+        }
+        if !self.claims.in_flight.lock().insert(path.to_path_buf()) {
+            return false;
+        }
+        self.paths.push(path.to_path_buf());
+        true
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.claims.in_flight.lock();
+        for path in &self.paths {
+            in_flight.remove(path);
+        }
+        drop(in_flight);
+        self.claims.released.notify_waiters();
+    }
 }
 
 #[cfg(unix)]
@@ -169,23 +795,56 @@ fn create_symlink(src: &Path, dst: &Path) -> std::io::Result<()> {
 impl Organizer {
     /// Create a new organizer with configuration
     #[must_use] 
-    pub const fn new(config: OrganizerConfig) -> Self {
+    pub fn new(config: OrganizerConfig) -> Self {
         Self {
-            config,
+            config: Arc::new(config),
             scraper: None,
+            claims: Arc::default(),
+            artwork_folders: Mutex::new(HashSet::new()),
+            renamed_folders: Mutex::new(HashMap::new()),
+            journaled: Mutex::default(),
         }
     }
 
     /// Set scraper manager for metadata lookup
     #[must_use] 
-    pub fn with_scraper(mut self, scraper: ScraperManager) -> Self {
-        self.scraper = Some(scraper);
+    pub fn with_scraper(mut self, scraper: impl Into<Arc<ScraperManager>>) -> Self {
+        self.scraper = Some(scraper.into());
+        self
+    }
+
+    /// Share claimed targets with other organizers, so concurrent runs
+    /// never race for a target
+    #[must_use]
+    pub fn with_claims(mut self, claims: Arc<OrganizeClaims>) -> Self {
+        self.claims = claims;
         self
     }
 
     /// Organize all media files in the source directory
     pub async fn organize_all(&self) -> Result<BatchOrganizeResult, ScraperError> {
-        let mut result = BatchOrganizeResult::default();
+        self.organize_all_with_progress(&OrganizeProgress::default())
+            .await
+    }
+
+    /// Organize all media files, reporting progress and honouring cancellation
+    pub async fn organize_all_with_progress(
+        &self,
+        progress: &OrganizeProgress,
+    ) -> Result<BatchOrganizeResult, ScraperError> {
+        // Lookups of a whole folder run behind interactive ones
+        ScrapeBudget::background(self.organize_batch(progress)).await
+    }
+
+    async fn organize_batch(
+        &self,
+        progress: &OrganizeProgress,
+    ) -> Result<BatchOrganizeResult, ScraperError> {
+        let mut result = BatchOrganizeResult {
+            batch_id: new_batch_id(),
+            ..Default::default()
+        };
+        self.begin_batch().await;
 
         // Scan source directory for video files
         let files = self.scan_video_files(&self.config.source_dir)?;
@@ -196,20 +855,90 @@ impl Organizer {
             self.config.source_dir
         );
 
-        for file in files {
-            match self.organize_file(&file).await {
-                Ok(r) => {
+        progress.total.store(files.len(), Ordering::Relaxed);
+
+        // Files are looked up through the scraper, within its budget, and
+        // organized as their lookups finish
+        let batch_id = result.batch_id.clone();
+        let total = files.len();
+        let concurrency = self.config.concurrency.max(1);
+        let cancel = progress.cancelled.clone();
+        let skipped = Mutex::new(Vec::new());
+        let prepared = Mutex::new(HashMap::new());
+        let candidates = files.into_iter().filter_map(|file| {
+            progress.record(&progress.scanned);
+            let reason = match self.skip_reason(&file) {
+                Some(reason) => reason,
+                None => match self.prepare(&file) {
+                    Ok((lookup, parsed)) => {
+                        prepared.lock().insert(file.clone(), (lookup, parsed.clone()));
+                        return Some((file, parsed));
+                    }
+                    Err(e) => e.to_string(),
+                },
+            };
+            debug!("Skipping {:?}: {}", file, reason);
+            progress.record(&progress.skipped);
+            skipped.lock().push((file, reason));
+            None
+        });
+        let matched = match self.scraper {
+            Some(ref scraper) => scraper
+                .scrape_many(candidates, concurrency, cancel)
+                .map(|(file, scraped)| {
+                    let matched = Self::matched(&file, scraped);
+                    (file, matched)
+                })
+                .left_stream(),
+            None => stream::iter(candidates)
+                .take_while(move |_| future::ready(!cancel.is_cancelled()))
+                .map(|(file, _)| (file, (None, None)))
+                .right_stream(),
+        };
+        let outcomes = matched
+            .map(|(file, (metadata, episode))| {
+                let prepared = prepared.lock().remove(&file);
+                let batch_id = &batch_id;
+                async move {
+                    let Some((lookup, parsed)) = prepared else {
+                        return (file, Err("Not prepared for organizing".to_string()));
+                    };
+                    let outcome = self
+                        .organize_one(&file, &lookup, parsed, metadata, episode, batch_id)
+                        .await
+                        .map_err(|e| e.to_string());
+                    (file, outcome)
+                }
+            })
+            .buffered(concurrency);
+        let mut outcomes = std::pin::pin!(outcomes);
+
+        while let Some(outcome) = outcomes.next().await {
+            match outcome {
+                (file, Err(reason)) => {
+                    progress.record(&progress.skipped);
+                    result.skipped.push((file, reason));
+                }
+                (_, Ok(r)) => {
+                    if r.metadata.is_some() {
+                        progress.record(&progress.matched);
+                    }
                     if r.success {
+                        progress.record(&progress.linked);
                         result.success.push(r);
                     } else {
+                        progress.record(&progress.failed);
                         result.failed.push(r);
                     }
                 }
-                Err(e) => {
-                    result.skipped.push((file, e.to_string()));
-                }
             }
         }
+        result.skipped.append(&mut skipped.lock());
+        result.cancelled = result.total() < total;
+
+        if result.cancelled {
+            info!("Organize cancelled after {} files", result.total());
+        }
 
         info!(
             "Organize complete: {} success, {} failed, {} skipped",
@@ -223,245 +952,1324 @@ impl Organizer {
 
     /// Organize a single file
     pub async fn organize_file(&self, source: &Path) -> Result<OrganizeResult, ScraperError> {
-        // Parse filename
-        let parsed = Parser::parse(source);
-
-        // Try to get metadata from scraper
-        let metadata = if let Some(ref scraper) = self.scraper {
-            let media_type = match parsed.hint {
-                super::MediaHint::Movie => Some(MediaType::Movie),
-                super::MediaHint::TvShow => Some(MediaType::Tv),
-                super::MediaHint::Anime => Some(MediaType::Anime),
-                super::MediaHint::Unknown => None,
-            };
+        self.begin_batch().await;
+        self.organize_file_in_batch(source, &new_batch_id(), None).await
+    }
 
-            match scraper
-                .search_ranked(&parsed.title, parsed.year, media_type)
-                .await
-            {
-                Ok(results) => {
-                    if let Some(best) = results.into_iter().next() {
-                        match scraper.get_metadata(&best.info).await {
-                            Ok(meta) => Some(meta),
-                            Err(e) => {
-                                warn!("Failed to get metadata for {:?}: {}", source, e);
-                                None
-                            }
-                        }
-                    } else {
-                        None
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to search for {:?}: {}", source, e);
-                    None
+    /// Organize a single file as a specific provider entry, skipping automatic matching
+    ///
+    /// `info` needs the provider, ID and media type; the title is taken from the
+    /// fetched metadata.
+    pub async fn organize_file_as(
+        &self,
+        source: &Path,
+        info: &MediaInfo,
+    ) -> Result<OrganizeResult, ScraperError> {
+        self.begin_batch().await;
+        self.organize_file_in_batch(source, &new_batch_id(), Some(info)).await
+    }
+
+    /// Drop journal entries past [`JOURNAL_RETENTION_DAYS`], and read what
+    /// the batch's conflicts need from the rest
+    async fn begin_batch(&self) {
+        let config = Arc::clone(&self.config);
+        let read = tokio::task::spawn_blocking(move || {
+            let journal = config.journal()?;
+            if !config.dry_run {
+                let cutoff = Utc::now() - chrono::Duration::days(JOURNAL_RETENTION_DAYS);
+                match journal.prune(cutoff) {
+                    Ok(0) => {}
+                    Ok(n) => info!("Dropped {} expired organize runs from the journal", n),
+                    Err(e) => warn!("Failed to prune organizer journal: {}", e),
                 }
             }
-        } else {
-            None
-        };
-
-        // Build target path
-        let target = self.build_target_path(source, &parsed, metadata.as_ref())?;
+            (config.conflict == ConflictPolicy::PreferProper)
+                .then(|| JournaledSources::read(&journal))
+        })
+        .await;
 
-        // Perform the organization
-        let (success, error) = if self.config.dry_run {
-            info!(
-                "[DRY RUN] Would {} {:?} -> {:?}",
-                self.config.method,
-                source.file_name().unwrap_or_default(),
-                target
-            );
-            (true, None)
-        } else {
-            self.perform_organize(source, &target)
+        let sources = match read {
+            Ok(sources) => sources.unwrap_or_default(),
+            Err(e) => {
+                warn!("Reading organizer journal failed: {}", e);
+                JournaledSources::default()
+            }
         };
-
-        Ok(OrganizeResult {
-            source: source.to_path_buf(),
-            target,
-            success,
-            error,
-            parsed,
-            metadata,
-        })
+        *self.journaled.lock() = Arc::new(sources);
     }
 
-    /// Build target path based on parsed info and metadata
-    fn build_target_path(
+    async fn organize_file_in_batch(
         &self,
         source: &Path,
-        parsed: &ParsedMedia,
-        metadata: Option<&MediaMetadata>,
-    ) -> Result<PathBuf, ScraperError> {
-        let mut target = self.config.target_dir.clone();
-
-        // Get title and year from metadata or parsed info
-        let title = metadata.map_or_else(|| sanitize_filename(&parsed.title), |m| m.title.clone());
-
-        let year = metadata
-            .and_then(|m| m.release_date.as_ref())
-            .and_then(|d| d.split('-').next())
-            .and_then(|y| y.parse::<i32>().ok())
-            .or(parsed.year);
-
-        let media_type = metadata
-            .map(|m| m.media_type)
-            .unwrap_or_else(|| match parsed.hint {
-                super::MediaHint::Movie => MediaType::Movie,
-                super::MediaHint::TvShow => MediaType::Tv,
-                super::MediaHint::Anime => MediaType::Anime,
-                super::MediaHint::Unknown => MediaType::Unknown,
-            });
-
-        // Add type directory if configured
-        if self.config.separate_by_type {
-            let type_dir = match media_type {
-                MediaType::Movie => "Movies",
-                MediaType::Tv => "TV Shows",
-                MediaType::Anime => "Anime",
-                _ => "Other",
-            };
-            target.push(type_dir);
-        }
+        batch_id: &str,
+        identity: Option<&MediaInfo>,
+    ) -> Result<OrganizeResult, ScraperError> {
+        let (lookup, parsed) = self.prepare(source)?;
+
+        let (metadata, episode) = match (&self.scraper, identity) {
+            (None, None) => (None, None),
+            (None, Some(_)) => {
+                return Err(ScraperError::Config(
+                    "No scraper configured for provider lookup".to_string(),
+                ));
+            }
+            // Report the lookups as one scrape
+            (Some(scraper), Some(info)) => {
+                scraper
+                    .track(async {
+                        scraper.emit(ScrapeEventKind::Parsing {
+                            path: source.display().to_string(),
+                        });
+                        let metadata = scraper.get_metadata(info).await;
+                        scraper.emit(match &metadata {
+                            Ok(metadata) => ScrapeEventKind::done(metadata),
+                            Err(e) => ScrapeEventKind::Failed {
+                                error: e.to_string(),
+                            },
+                        });
+                        let metadata = metadata?;
+                        let episode = scraper.resolve_episode(&parsed, &metadata).await;
+                        Ok::<_, ScraperError>((Some(metadata), episode))
+                    })
+                    .await?
+            }
+            (Some(scraper), None) => {
+                let scraped = scraper.scrape_file(source, parsed.clone()).await;
+                Self::matched(source, scraped)
+            }
+        };
 
-        // Get file extension
-        let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("mkv");
+        self.organize_one(source, &lookup, parsed, metadata, episode, batch_id)
+            .await
+    }
 
-        // Build path based on media type
-        if media_type == MediaType::Movie {
-            // Movies/{title} ({year})/{title} ({year}).ext
-            let folder_name = self.format_template(
-                &self.config.template.movie_folder,
-                &title,
-                year,
-                None,
-                None,
-            );
-            let file_name = self.format_template(
-                &self.config.template.movie_file,
-                &title,
-                year,
-                None,
-                None,
-            );
-            target.push(sanitize_filename(&folder_name));
-            target.push(format!("{}.{}", sanitize_filename(&file_name), ext));
+    /// Path a file is looked up by, and what its name tells
+    ///
+    /// Extras are named after the main feature they belong to.
+    fn prepare(&self, source: &Path) -> Result<(PathBuf, ParsedMedia), ScraperError> {
+        let lookup = if self.extras_folder(source).is_some() {
+            Self::main_feature(source).ok_or_else(|| {
+                ScraperError::NotFound(format!("Main feature for extra {source:?}"))
+            })?
         } else {
-            // TV Shows/{title} ({year})/Season XX/{title} - SXXEXX.ext
-            let folder_name =
-                self.format_template(&self.config.template.tv_folder, &title, year, None, None);
-            target.push(sanitize_filename(&folder_name));
-
-            let season = parsed.season.unwrap_or(1);
-            let season_folder = self.format_template(
-                &self.config.template.season_folder,
-                &title,
-                year,
-                Some(season),
-                None,
-            );
-            target.push(sanitize_filename(&season_folder));
+            source.to_path_buf()
+        };
 
-            let episode = parsed.episode.unwrap_or(1);
-            let file_name = self.format_template(
-                &self.config.template.episode_file,
-                &title,
-                year,
-                Some(season),
-                Some(episode),
-            );
-            target.push(format!("{}.{}", sanitize_filename(&file_name), ext));
+        let mut parsed = Parser::parse(&lookup);
+        if is_iso(source) {
+            parsed.disc = probe_iso(source).disc();
+            if parsed.quality.is_none() {
+                parsed.quality = parsed.disc.map(|d| d.source().to_string());
+            }
         }
-
-        Ok(target)
+        Ok((lookup, parsed))
     }
 
-    /// Format a naming template
-    fn format_template(
-        &self,
-        template: &str,
-        title: &str,
-        year: Option<i32>,
-        season: Option<i32>,
-        episode: Option<i32>,
-    ) -> String {
-        let mut result = template.to_string();
-
-        result = result.replace("{title}", title);
-
-        if let Some(y) = year {
-            result = result.replace("{year}", &y.to_string());
-        } else {
-            // Remove year placeholder and surrounding parentheses if no year
-            result = result.replace(" ({year})", "");
-            result = result.replace("({year})", "");
-            result = result.replace("{year}", "");
-        }
-
-        if let Some(s) = season {
-            result = result.replace("{season:02}", &format!("{s:02}"));
-            result = result.replace("{season}", &s.to_string());
+    /// Metadata and episode a scrape found, none when it found nothing
+    fn matched(
+        source: &Path,
+        scraped: Result<ScrapeResult, ScraperError>,
+    ) -> (Option<MediaMetadata>, Option<EpisodeMatch>) {
+        match scraped {
+            Ok(scraped) => (scraped.metadata, scraped.episode),
+            Err(e) => {
+                warn!("Failed to look up {:?}: {}", source, e);
+                (None, None)
+            }
         }
+    }
 
-        if let Some(e) = episode {
-            result = result.replace("{episode:02}", &format!("{e:02}"));
-            result = result.replace("{episode}", &e.to_string());
+    async fn organize_one(
+        &self,
+        source: &Path,
+        lookup: &Path,
+        mut parsed: ParsedMedia,
+        metadata: Option<MediaMetadata>,
+        episode: Option<EpisodeMatch>,
+        batch_id: &str,
+    ) -> Result<OrganizeResult, ScraperError> {
+        let extra = self.extras_folder(source).map(|(kind, _)| kind);
+
+        // Absolute-numbered or date-named episodes take their real season/episode
+        if let Some(episode) = episode {
+            // Keep multi-episode ranges the same length after remapping
+            if let (Some(start), Some(end)) = (parsed.episode, parsed.episode_end) {
+                parsed.episode_end = Some(episode.episode + (end - start));
+            }
+            parsed.season = Some(episode.season);
+            parsed.episode = Some(episode.episode);
         }
 
-        result
-    }
+        // Claim the target, waiting while another file is headed for the same
+        // one; what's on disk there is only looked at once claimed
+        let (current, plan, reservation) = loop {
+            let released = self.claims.released.notified();
+            let current = self.relocate(source);
+            let claim = self.claim_target(&current, lookup, &parsed, metadata.as_ref())?;
+            let paths = claim.paths(&current);
+            let reserved = {
+                let mut in_flight = self.claims.in_flight.lock();
+                let free = !paths.iter().any(|p| in_flight.contains(p));
+                if free {
+                    in_flight.extend(paths.iter().cloned());
+                }
+                free
+            };
+            if reserved {
+                let mut reservation = Reservation {
+                    claims: &self.claims,
+                    paths,
+                };
+                let plan = self.plan_transfer(&current, claim, &parsed);
+                // A version or suffixed name is claimed as well
+                if reservation.extend(&plan.target) {
+                    break (current, plan, reservation);
+                }
+                drop(reservation);
 
-    /// Perform the actual file organization
-    fn perform_organize(&self, source: &Path, target: &Path) -> (bool, Option<String>) {
-        // Create parent directories
-        if let Some(parent) = target.parent()
-            && let Err(e) = fs::create_dir_all(parent)
+                // Wait for the file holding that name, not for the release above
+                let released = self.claims.released.notified();
+                if self.claims.in_flight.lock().contains(&plan.target) {
+                    released.await;
+                }
+                continue;
+            }
+            released.await;
+        };
+        let TransferPlan {
+            target,
+            folder_rename,
+            version,
+            mut conflict,
+            companions,
+        } = plan;
+
+        // Copies and checksums of large files would stall the runtime
+        let config = Arc::clone(&self.config);
+        let (from, to, batch) = (current.clone(), target.clone(), batch_id.to_string());
+        let renamed_folder = folder_rename.clone();
+        let sources = Arc::clone(&self.journaled.lock());
+        let done = tokio::task::spawn_blocking(move || {
+            Self::transfer(&config, &sources, &from, to, companions, folder_rename, &batch)
+        })
+        .await;
+        let (transfer, target, companion_targets) = done.unwrap_or_else(|e| {
+            let transfer = Transfer::failed(format!("Organize task failed: {e}"));
+            (transfer, target, Vec::new())
+        });
+        let Transfer {
+            success,
+            error,
+            verified,
+            ..
+        } = transfer;
+        conflict = conflict.or(transfer.conflict);
+
+        // Other files of the folder follow it rather than renaming it again
+        if let Some((from, to)) = renamed_folder
+            && !self.config.dry_run
+            && target.starts_with(&to)
         {
-            return (false, Some(format!("Failed to create directory: {e}")));
-        }
-
-        // Check if target already exists
-        if target.exists() && !self.config.overwrite {
-            return (false, Some("Target already exists".to_string()));
+            self.renamed_folders.lock().insert(from, to);
         }
 
-        // Remove existing target if overwriting
-        if target.exists()
-            && self.config.overwrite
-            && let Err(e) = fs::remove_file(target)
+        // Leave the source folder to the last file still being organized out of it
+        if success
+            && !self.config.dry_run
+            && self.config.cleanup_source
+            && self.config.method == OrganizeMethod::Move
+            && let Some(dir) = current.parent()
+            && !self
+                .claims
+                .in_flight
+                .lock()
+                .iter()
+                .any(|p| p.starts_with(dir) && !reservation.paths.contains(p))
         {
-            return (false, Some(format!("Failed to remove existing file: {e}")));
+            let config = Arc::clone(&self.config);
+            let cleanup =
+                tokio::task::spawn_blocking(move || Self::cleanup_source_dirs(&config, &current));
+            if let Err(e) = cleanup.await {
+                warn!("Source cleanup failed: {}", e);
+            }
         }
+        drop(reservation);
 
-        // Perform the operation
-        let result = match self.config.method {
-            OrganizeMethod::Symlink => {
-                // Use absolute path for symlink source
-                let abs_source = if source.is_absolute() {
-                    source.to_path_buf()
-                } else {
-                    std::env::current_dir().map_or_else(|_| source.to_path_buf(), |cwd| cwd.join(source))
-                };
-                create_symlink(&abs_source, target)
+        let (artifacts, artwork) = match metadata.as_ref() {
+            Some(m) if success && extra.is_none() => {
+                self.write_artifacts(&target, &parsed, m, batch_id).await
             }
-            OrganizeMethod::Hardlink => fs::hard_link(source, target),
-            OrganizeMethod::Move => fs::rename(source, target),
-            OrganizeMethod::Copy => fs::copy(source, target).map(|_| ()),
+            _ => (Vec::new(), Vec::new()),
         };
 
-        match result {
-            Ok(()) => {
-                info!(
-                    "{} {:?} -> {:?}",
-                    self.config.method,
-                    source.file_name().unwrap_or_default(),
-                    target
+        Ok(OrganizeResult {
+            source: source.to_path_buf(),
+            target,
+            success,
+            error,
+            parsed,
+            metadata,
+            companions: companion_targets,
+            version,
+            verified,
+            extra,
+            conflict,
+            artifacts,
+            artwork,
+        })
+    }
+
+    /// Write NFO and download artwork into the movie or show folder
+    ///
+    /// Artwork of a folder is saved once per organizer run, so a show's poster,
+    /// fanart and season posters are fetched for its first episode rather than
+    /// for every one, even when [`ArtworkOptions::overwrite`] replaces them.
+    /// Returns the files written and the outcome of each artwork file. Newly
+    /// written files are journaled and removed by rollback.
+    async fn write_artifacts(
+        &self,
+        target: &Path,
+        parsed: &ParsedMedia,
+        metadata: &MediaMetadata,
+        batch_id: &str,
+    ) -> (Vec<PathBuf>, Vec<ArtworkResult>) {
+        let mut written = Vec::new();
+        let mut artwork = Vec::new();
+        let is_movie = metadata.media_type == MediaType::Movie;
+        let flat = self.absolute_episode(parsed, Some(metadata)).is_some();
+        let folder = if is_movie || flat {
+            target.parent()
+        } else {
+            target.parent().and_then(Path::parent)
+        };
+        let Some(folder) = folder else {
+            return (written, artwork);
+        };
+        let downloader = self
+            .scraper
+            .as_ref()
+            .map(|s| s.downloader().clone())
+            .unwrap_or_default();
+        let options = &self.config.artwork;
+
+        if self.config.download_artwork {
+            let mut planned = Vec::new();
+            if self.artwork_folders.lock().insert(folder.to_path_buf()) {
+                planned = Downloader::plan_artwork(metadata, folder, options);
+            }
+            if !is_movie
+                && options.episode_thumbs
+                && let Some(thumb) = self.episode_thumb(target, parsed, metadata).await
+            {
+                planned.push(thumb);
+            }
+            self.save_artwork(&downloader, planned, batch_id, &mut written, &mut artwork)
+                .await;
+        }
+
+        // After the artwork, so the NFO can reference what was saved
+        let mut documents = Vec::new();
+        if self.config.write_nfo {
+            let format = self.config.metadata_format;
+            if format.writes_nfo() {
+                let name = if is_movie { "movie.nfo" } else { "tvshow.nfo" };
+                documents.push(folder.join(name));
+            }
+            if format.writes_json() {
+                documents.push(folder.join(METADATA_JSON));
+            }
+        }
+
+        if is_movie
+            && let Some(ref sets) = self.config.movie_sets_dir
+            && let Some(ref collection) = metadata.collection
+        {
+            let folder = sets.join(sanitize_filename(&collection.name));
+            if self.config.download_artwork
+                && self.artwork_folders.lock().insert(folder.clone())
+            {
+                let planned = Downloader::plan_collection_artwork(collection, &folder, options);
+                self.save_artwork(&downloader, planned, batch_id, &mut written, &mut artwork)
+                    .await;
+            }
+            if self.config.write_nfo && self.config.metadata_format.writes_nfo() {
+                documents.push(folder.join(COLLECTION_NFO));
+            }
+        }
+
+        for path in documents {
+            if path.exists() {
+                continue;
+            }
+            if self.config.dry_run {
+                written.push(path);
+                continue;
+            }
+
+            let profile = &self.config.nfo;
+            let result = if path.ends_with(METADATA_JSON) {
+                Writer::write_metadata_json(&path, metadata).await
+            } else if path.ends_with(COLLECTION_NFO)
+                && let Some(ref collection) = metadata.collection
+            {
+                Writer::write_collection_nfo(&path, collection, &metadata.provider).await
+            } else {
+                Writer::write_nfo_with(&path, metadata, options, profile).await
+            };
+            match result {
+                Ok(()) => {
+                    self.journal_artifact(&path, None, batch_id);
+                    written.push(path);
+                }
+                Err(e) => warn!("Failed to write {:?}: {}", path, e),
+            }
+        }
+
+        if is_movie
+            && self.config.download_artwork
+            && options.trailers
+            && let Some(trailers) = self.scraper.as_ref().and_then(|s| s.trailer_downloader())
+            && let Some((path, url)) = TrailerDownloader::plan(metadata, target)
+        {
+            let existed = path.exists();
+            if !existed || options.overwrite {
+                if self.config.dry_run {
+                    written.push(path);
+                } else {
+                    match trailers.download(&url, &path).await {
+                        Ok(()) => {
+                            if !existed {
+                                self.journal_artifact(&path, Some(&url), batch_id);
+                            }
+                            written.push(path);
+                        }
+                        Err(e) => warn!("Failed to save trailer {:?}: {}", path, e),
+                    }
+                }
+            }
+        }
+
+        (written, artwork)
+    }
+
+    /// Save `planned` artwork concurrently, recording what was written
+    ///
+    /// In a dry run, lists the files that would be written instead.
+    async fn save_artwork(
+        &self,
+        downloader: &Downloader,
+        planned: Vec<(PathBuf, String)>,
+        batch_id: &str,
+        written: &mut Vec<PathBuf>,
+        artwork: &mut Vec<ArtworkResult>,
+    ) {
+        let options = &self.config.artwork;
+        if self.config.dry_run {
+            written.extend(
+                planned
+                    .into_iter()
+                    .map(|(path, _)| path)
+                    .filter(|path| options.overwrite || !path.exists()),
+            );
+            return;
+        }
+
+        let saves = planned.into_iter().map(|(path, url)| async move {
+            // A replaced file isn't ours to remove on rollback
+            let existed = path.exists();
+            if existed && !options.overwrite {
+                return (existed, ArtworkResult::skipped(path, url));
+            }
+            (existed, downloader.save_artwork_file(&url, &path, options).await)
+        });
+        for (existed, result) in join_all(saves).await {
+            if result.written() {
+                if !existed {
+                    for file in result.variants.iter().chain([&result.path]) {
+                        self.journal_artifact(file, Some(&result.url), batch_id);
+                    }
+                }
+                written.extend(result.variants.iter().cloned());
+                written.push(result.path.clone());
+            }
+            artwork.push(result);
+        }
+    }
+
+    /// Thumb of the episode organized to `target`, looked up by its number
+    async fn episode_thumb(
+        &self,
+        target: &Path,
+        parsed: &ParsedMedia,
+        metadata: &MediaMetadata,
+    ) -> Option<(PathBuf, String)> {
+        let scraper = self.scraper.as_ref()?;
+        let season = parsed.season.unwrap_or(1);
+        let number = parsed.episode?;
+        match scraper.get_episode(&metadata.provider, &metadata.id, season, number).await {
+            Ok(episode) => Downloader::episode_thumb(target, &episode, &self.config.artwork),
+            Err(e) => {
+                debug!("No thumb for {:?}: {}", target, e);
+                None
+            }
+        }
+    }
+
+    fn journal_artifact(&self, path: &Path, url: Option<&str>, batch_id: &str) {
+        let Some(journal) = self.journal() else {
+            return;
+        };
+        let entry = JournalEntry {
+            batch_id: batch_id.to_string(),
+            method: OrganizeMethod::Copy,
+            source: url.map_or_else(PathBuf::new, PathBuf::from),
+            target: path.to_path_buf(),
+            backup: None,
+            created_at: Utc::now(),
+        };
+        if let Err(e) = journal.append(&entry) {
+            warn!("Failed to write organizer journal: {}", e);
+        }
+    }
+
+    /// Find sibling subtitles/NFO/artwork and the target path each should get
+    ///
+    /// `Movie.en.srt` next to `Movie.mkv` follows the target base name
+    /// (`Target.en.srt`). Folder artwork like `poster.jpg` keeps its name, but only
+    /// when the source directory holds a single video.
+    fn companion_targets(&self, source: &Path, target: &Path) -> Vec<(PathBuf, PathBuf)> {
+        let (Some(dir), Some(stem), Some(target_dir), Some(target_stem)) = (
+            source.parent(),
+            source.file_stem().and_then(|s| s.to_str()),
+            target.parent(),
+            target.file_stem().and_then(|s| s.to_str()),
+        ) else {
+            return Vec::new();
+        };
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let siblings: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p != source)
+            .collect();
+        let single_video = !siblings.iter().any(|p| is_video_file(p));
+
+        siblings
+            .into_iter()
+            .filter(|p| {
+                p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| {
+                        self.config
+                            .companion_extensions
+                            .iter()
+                            .any(|c| c.eq_ignore_ascii_case(e))
+                    })
+            })
+            .filter_map(|p| {
+                let name = p.file_name()?.to_str()?;
+
+                if let Some(suffix) = name.strip_prefix(stem)
+                    && (suffix.starts_with('.') || suffix.starts_with('-'))
+                {
+                    let companion_target = target_dir.join(format!("{target_stem}{suffix}"));
+                    return Some((p, companion_target));
+                }
+
+                let name_stem = p.file_stem()?.to_str()?.to_lowercase();
+                if single_video && FOLDER_ARTWORK.contains(&name_stem.as_str()) {
+                    let companion_target = target_dir.join(name);
+                    return Some((p, companion_target));
+                }
+
+                None
+            })
+            .collect()
+    }
+
+    /// Build target path based on parsed info and metadata
+    fn build_target_path(
+        &self,
+        source: &Path,
+        parsed: &ParsedMedia,
+        metadata: Option<&MediaMetadata>,
+    ) -> Result<PathBuf, ScraperError> {
+        // Get title and year from metadata or parsed info
+        let title = metadata.map_or_else(|| sanitize_filename(&parsed.title), |m| m.title.clone());
+
+        let year = metadata
+            .and_then(|m| m.release_date.as_ref())
+            .and_then(|d| d.split('-').next())
+            .and_then(|y| y.parse::<i32>().ok())
+            .or(parsed.year);
+
+        let media_type = media_type_of(parsed, metadata);
+
+        let type_dir = match media_type {
+            MediaType::Movie => "Movies",
+            MediaType::Tv => "TV Shows",
+            MediaType::Anime => "Anime",
+            _ => "Other",
+        };
+
+        // Get file extension
+        let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("mkv");
+
+        // IDs from metadata win over ones tagged in the filename
+        let mut ids = parsed.external_ids.clone();
+        if let Some(m) = metadata {
+            ids.merge(&m.external_ids);
+        }
+
+        let mut vars = TemplateVars {
+            title,
+            year,
+            resolution: parsed.resolution.clone(),
+            quality: parsed.quality.clone(),
+            codec: parsed.codec.clone(),
+            group: parsed.release_group.clone(),
+            edition: parsed.edition.clone(),
+            imdb_id: ids.imdb,
+            tmdb_id: ids.tmdb,
+            tvdb_id: ids.tvdb,
+            media_type: Some(type_dir.to_string()),
+            genre: metadata.and_then(|m| m.genres.first().cloned()),
+            language: metadata.and_then(|m| m.language.clone()),
+            ..Default::default()
+        };
+
+        // Shorten the title until the path fits, keeping year and episode numbers intact
+        loop {
+            let target = self.layout_target(parsed, metadata, media_type, &vars, ext);
+            let excess = self.config.target_os.path_excess(&target);
+            if excess == 0 {
+                return Ok(target);
+            }
+
+            // The title usually repeats in the folder and file name
+            let uses = target.to_string_lossy().matches(&vars.title).count().max(1);
+            let cut = excess.div_ceil(uses);
+            let keep = vars.title.floor_char_boundary(vars.title.len().saturating_sub(cut));
+            let title = vars.title[..keep].trim_end();
+            if title.is_empty() {
+                return Err(ScraperError::Config(format!(
+                    "Target path is too long: {}",
+                    target.display()
+                )));
+            }
+            vars.title = title.to_string();
+        }
+    }
+
+    /// Lay out the target path for a set of template values
+    fn layout_target(
+        &self,
+        parsed: &ParsedMedia,
+        metadata: Option<&MediaMetadata>,
+        media_type: MediaType,
+        vars: &TemplateVars,
+        ext: &str,
+    ) -> PathBuf {
+        let mut target = self.config.target_dir.clone();
+
+        // Library root: templated folders, or the fixed per-type split
+        if let Some(ref root) = self.config.template.library_root {
+            let root = self.format_template(root, vars);
+            for segment in root.split(['/', '\\']) {
+                let segment = sanitize_filename(segment.trim());
+                if !segment.is_empty() {
+                    target.push(segment);
+                }
+            }
+        } else if self.config.separate_by_type
+            && let Some(ref type_dir) = vars.media_type
+        {
+            target.push(type_dir);
+        }
+
+        // Build path based on media type
+        if media_type == MediaType::Movie {
+            // Movies/{title} ({year})/{title} ({year}).ext
+            let folder_name = self.format_template(&self.config.template.movie_folder, vars);
+            let file_name = self.format_template(&self.config.template.movie_file, vars);
+            target.push(sanitize_filename(&folder_name));
+            target.push(format!("{}.{}", sanitize_filename(&file_name), ext));
+        } else if let Some(absolute) = self.absolute_episode(parsed, metadata) {
+            // Anime/{title}/{title} - 045.ext
+            let folder_name = self.format_template(&self.config.template.tv_folder, vars);
+            target.push(sanitize_filename(&folder_name));
+
+            let vars = TemplateVars {
+                absolute: Some(absolute),
+                ..vars.clone()
+            };
+            let file_name = self.format_template(&self.config.template.anime_file, &vars);
+            target.push(format!("{}.{}", sanitize_filename(&file_name), ext));
+        } else {
+            // TV Shows/{title} ({year})/Season XX/{title} - SXXEXX.ext
+            let folder_name = self.format_template(&self.config.template.tv_folder, vars);
+            target.push(sanitize_filename(&folder_name));
+
+            let vars = TemplateVars {
+                season: Some(parsed.season.unwrap_or(1)),
+                ..vars.clone()
+            };
+            let season_folder = self.format_template(&self.config.template.season_folder, &vars);
+            target.push(sanitize_filename(&season_folder));
+
+            let vars = TemplateVars {
+                episode: Some(parsed.episode.unwrap_or(1)),
+                episode_end: parsed.episode_end,
+                ..vars
+            };
+            let file_name = self.format_template(&self.config.template.episode_file, &vars);
+            target.push(format!("{}.{}", sanitize_filename(&file_name), ext));
+        }
+
+        target
+    }
+
+    /// Absolute episode number, when this anime episode uses the absolute layout
+    ///
+    /// Files numbered without a season are already absolute. Season-numbered ones are
+    /// converted using the season episode counts from metadata; specials stay in
+    /// season folders.
+    fn absolute_episode(
+        &self,
+        parsed: &ParsedMedia,
+        metadata: Option<&MediaMetadata>,
+    ) -> Option<i32> {
+        if self.config.anime_layout != AnimeLayout::Absolute
+            || media_type_of(parsed, metadata) != MediaType::Anime
+        {
+            return None;
+        }
+
+        let episode = parsed.episode?;
+        let Some(season) = parsed.season else {
+            return Some(episode);
+        };
+        if season == 0 {
+            return None;
+        }
+
+        let seasons = &metadata?.seasons;
+        let mut offset = 0;
+        for number in 1..season {
+            offset += seasons
+                .iter()
+                .find(|s| s.number == number)?
+                .episode_count?;
+        }
+        Some(offset + episode)
+    }
+
+    /// Format a naming template
+    fn format_template(&self, template: &str, vars: &TemplateVars) -> String {
+        render_template(template, vars).0
+    }
+
+    /// Revert every operation recorded for an organize run, newest first
+    pub fn rollback(&self, batch_id: &str) -> Result<RollbackResult, ScraperError> {
+        let journal = self.journal().ok_or_else(|| {
+            ScraperError::Config("Organizer journal is not configured".to_string())
+        })?;
+
+        let entries = journal.batch(batch_id)?;
+        if entries.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No journal entries for batch: {batch_id}"
+            )));
+        }
+
+        let mut result = RollbackResult::default();
+        let mut unreverted = Vec::new();
+
+        for entry in entries.iter().rev() {
+            match Self::revert_entry(&self.config, entry) {
+                Ok(()) => result.reverted.push(entry.target.clone()),
+                Err(e) => {
+                    warn!("Failed to roll back {:?}: {}", entry.target, e);
+                    result.failed.push((entry.target.clone(), e.to_string()));
+                    unreverted.push(entry);
+                }
+            }
+        }
+
+        // Keep only failed entries, so a retry doesn't revert anything twice
+        journal.retain(|e| e.batch_id != batch_id || unreverted.contains(&e))?;
+
+        info!(
+            "Rollback of {}: {} reverted, {} failed",
+            batch_id,
+            result.reverted.len(),
+            result.failed.len()
+        );
+
+        Ok(result)
+    }
+
+    fn revert_entry(config: &OrganizerConfig, entry: &JournalEntry) -> std::io::Result<()> {
+        match entry.method {
+            OrganizeMethod::Move | OrganizeMethod::Rename => {
+                if let Some(parent) = entry.source.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                Self::move_file(config, &entry.target, &entry.source, &mut None)?;
+            }
+            OrganizeMethod::Symlink | OrganizeMethod::Hardlink | OrganizeMethod::Copy => {
+                match fs::remove_file(&entry.target) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(ref backup) = entry.backup {
+            fs::rename(backup, &entry.target)?;
+        }
+
+        Ok(())
+    }
+
+    /// Decide where a file goes from its name and metadata alone
+    fn claim_target(
+        &self,
+        source: &Path,
+        lookup: &Path,
+        parsed: &ParsedMedia,
+        metadata: Option<&MediaMetadata>,
+    ) -> Result<TargetClaim, ScraperError> {
+        let extras_folder = self.extras_folder(source);
+
+        // Build target path
+        let mut target = self.build_target_path(lookup, parsed, metadata)?;
+
+        if let Some((_, folder)) = extras_folder
+            && let (Some(dir), Some(name)) = (target.parent(), source.file_name())
+        {
+            target = dir.join(folder).join(name);
+        }
+
+        // Rename in place: keep the file's folder, take only the templated names
+        let mut folder_rename = None;
+        if self.config.method == OrganizeMethod::Rename
+            && let (Some(dir), Some(name)) = (source.parent(), target.file_name())
+        {
+            if self.config.rename_folder
+                && extras_folder.is_none()
+                && media_type_of(parsed, metadata) == MediaType::Movie
+                && dir != self.config.source_dir
+                && let Some(folder) = target.parent().and_then(|p| p.file_name())
+                && dir.file_name() != Some(folder)
+            {
+                folder_rename = Some((dir.to_path_buf(), dir.with_file_name(folder)));
+            }
+            target = dir.join(name);
+        }
+
+        Ok(TargetClaim {
+            target,
+            folder_rename,
+        })
+    }
+
+    /// Decide where a file and its companions go, given what is on disk at
+    /// its claimed target
+    fn plan_transfer(
+        &self,
+        source: &Path,
+        claim: TargetClaim,
+        parsed: &ParsedMedia,
+    ) -> TransferPlan {
+        let TargetClaim {
+            mut target,
+            folder_rename,
+        } = claim;
+        let extras_folder = self.extras_folder(source);
+
+        // Another copy already lives there: organize this one as an additional version
+        let mut version = None;
+        if extras_folder.is_none()
+            && self.config.keep_versions
+            && self.config.conflict != ConflictPolicy::Overwrite
+            && let Some((version_target, label)) = version_target(source, &target, parsed)
+        {
+            // Re-organizing one of the versions adds none
+            if version_target != source {
+                version = Some(label);
+            }
+            target = version_target;
+        }
+
+        // Pick the suffixed name up front so companions follow it
+        let mut conflict = None;
+        if self.config.conflict == ConflictPolicy::RenameWithSuffix
+            && source != target
+            && target.exists()
+        {
+            target = suffixed_target(&target);
+            conflict = Some(ConflictPolicy::RenameWithSuffix);
+        }
+
+        let companions = self.companion_targets(source, &target);
+
+        TransferPlan {
+            target,
+            folder_rename,
+            version,
+            conflict,
+            companions,
+        }
+    }
+
+    /// Organize a file and its companions, rename its folder and journal it
+    ///
+    /// Blocking; returns the outcome with the final target and the targets of
+    /// the companions that were organized.
+    fn transfer(
+        config: &OrganizerConfig,
+        sources: &JournaledSources,
+        source: &Path,
+        mut target: PathBuf,
+        companions: Vec<(PathBuf, PathBuf)>,
+        folder_rename: Option<(PathBuf, PathBuf)>,
+        batch_id: &str,
+    ) -> (Transfer, PathBuf, Vec<PathBuf>) {
+        let transfer = if config.dry_run {
+            info!(
+                "[DRY RUN] Would {} {:?} -> {:?}",
+                config.method,
+                source.file_name().unwrap_or_default(),
+                target
+            );
+            Transfer {
+                success: true,
+                ..Default::default()
+            }
+        } else {
+            Self::perform_organize(config, sources, source, &target, batch_id)
+        };
+        if let Some(ref renamed) = transfer.renamed {
+            target.clone_from(renamed);
+        }
+
+        let mut companion_targets = Vec::new();
+        if transfer.success {
+            for (companion, companion_target) in companions {
+                if config.dry_run {
+                    companion_targets.push(companion_target);
+                    continue;
+                }
+                let moved = Self::perform_organize(
+                    config,
+                    sources,
+                    &companion,
+                    &companion_target,
+                    batch_id,
+                );
+                if moved.success {
+                    companion_targets.push(companion_target);
+                } else {
+                    warn!(
+                        "Failed to organize companion {:?}: {}",
+                        companion,
+                        moved.error.unwrap_or_default()
+                    );
+                }
+            }
+        }
+
+        if transfer.success
+            && let Some((from, to)) = folder_rename
+            && (config.dry_run || Self::rename_folder(config, &from, &to, batch_id))
+        {
+            let relocate = |path: &Path| to.join(path.strip_prefix(&from).unwrap_or(path));
+            target = relocate(&target);
+            companion_targets = companion_targets.iter().map(|p| relocate(p)).collect();
+        }
+
+        (transfer, target, companion_targets)
+    }
+
+    fn journal(&self) -> Option<Journal> {
+        self.config.journal()
+    }
+
+    /// Where `path` is now, after a folder renamed for an earlier file moved it
+    fn relocate(&self, path: &Path) -> PathBuf {
+        self.renamed_folders
+            .lock()
+            .iter()
+            .find_map(|(from, to)| path.strip_prefix(from).ok().map(|rest| to.join(rest)))
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    /// Perform the actual file organization
+    fn perform_organize(
+        config: &OrganizerConfig,
+        sources: &JournaledSources,
+        source: &Path,
+        original: &Path,
+        batch_id: &str,
+    ) -> Transfer {
+        let mut target = original;
+
+        // Already named correctly
+        if source == target {
+            return Transfer {
+                success: true,
+                ..Default::default()
+            };
+        }
+
+        // Create parent directories
+        if let Some(parent) = target.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            return Transfer::failed(format!("Failed to create directory: {e}"));
+        }
+
+        // Apply the conflict policy to an existing target
+        let mut conflict = None;
+        let mut replace = false;
+        let suffixed;
+        if target.exists() {
+            let policy = config.conflict;
+            conflict = Some(policy);
+            match Self::resolve_conflict(config, sources, source, target) {
+                Ok(Resolution::Replace) => replace = true,
+                Ok(Resolution::Rename) => {
+                    suffixed = suffixed_target(target);
+                    target = &suffixed;
+                }
+                Err(reason) => {
+                    return Transfer {
+                        conflict,
+                        ..Transfer::failed(reason)
+                    };
+                }
+            }
+        }
+
+        let journal = config.journal();
+        let mut backup = None;
+
+        // Move an existing target aside if replacing, so it can be put back when
+        // the new file fails (and on rollback when journaling)
+        if replace {
+            let path = backup_path(target);
+            if let Err(e) = fs::rename(target, &path) {
+                return Transfer::failed(format!("Failed to move existing file aside: {e}"));
+            }
+            backup = Some(path);
+        }
+
+        // Perform the operation
+        let mut verified = None;
+        let result = match config.method {
+            OrganizeMethod::Symlink => {
+                // Use absolute path for symlink source
+                let abs_source = if source.is_absolute() {
+                    source.to_path_buf()
+                } else {
+                    std::env::current_dir().map_or_else(|_| source.to_path_buf(), |cwd| cwd.join(source))
+                };
+                create_symlink(&abs_source, target)
+            }
+            OrganizeMethod::Hardlink => fs::hard_link(source, target),
+            OrganizeMethod::Move => Self::move_file(config, source, target, &mut verified),
+            OrganizeMethod::Copy => Self::copy_verified(config, source, target, &mut verified),
+            OrganizeMethod::Rename => fs::rename(source, target),
+        };
+
+        match result {
+            Ok(()) => {
+                info!(
+                    "{} {:?} -> {:?}",
+                    config.method,
+                    source.file_name().unwrap_or_default(),
+                    target
                 );
-                (true, None)
+
+                if let Some(journal) = journal {
+                    let entry = JournalEntry {
+                        batch_id: batch_id.to_string(),
+                        method: config.method,
+                        source: source.to_path_buf(),
+                        target: target.to_path_buf(),
+                        backup,
+                        created_at: Utc::now(),
+                    };
+                    if let Err(e) = journal.append(&entry) {
+                        warn!("Failed to write organizer journal: {}", e);
+                    }
+                    sources.insert(target, source);
+                } else if let Some(backup) = backup
+                    && let Err(e) = fs::remove_file(&backup)
+                {
+                    warn!("Failed to remove replaced file {:?}: {}", backup, e);
+                }
+
+                Transfer {
+                    success: true,
+                    error: None,
+                    verified,
+                    conflict,
+                    renamed: (target != original).then(|| target.to_path_buf()),
+                }
+            }
+            Err(e) => {
+                // Put the replaced file back
+                if let Some(backup) = backup
+                    && let Err(e) = fs::rename(&backup, target)
+                {
+                    warn!("Failed to restore {:?} from {:?}: {}", target, backup, e);
+                }
+                Transfer {
+                    success: false,
+                    error: Some(e.to_string()),
+                    verified,
+                    conflict,
+                    renamed: None,
+                }
+            }
+        }
+    }
+
+    /// Decide whether an incoming file replaces an existing target
+    fn resolve_conflict(
+        config: &OrganizerConfig,
+        sources: &JournaledSources,
+        source: &Path,
+        target: &Path,
+    ) -> Result<Resolution, String> {
+        let policy = config.conflict;
+        let (Ok(new), Ok(old)) = (fs::metadata(source), fs::metadata(target)) else {
+            return (policy == ConflictPolicy::Overwrite)
+                .then_some(Resolution::Replace)
+                .ok_or_else(|| "Target already exists".to_string());
+        };
+
+        match policy {
+            ConflictPolicy::Skip => Err("Target already exists".to_string()),
+            ConflictPolicy::Overwrite => Ok(Resolution::Replace),
+            ConflictPolicy::RenameWithSuffix => Ok(Resolution::Rename),
+            ConflictPolicy::KeepLarger => (new.len() > old.len())
+                .then_some(Resolution::Replace)
+                .ok_or_else(|| "Existing file is at least as large".to_string()),
+            ConflictPolicy::KeepNewer => match (new.modified(), old.modified()) {
+                (Ok(a), Ok(b)) if a > b => Ok(Resolution::Replace),
+                _ => Err("Existing file is at least as new".to_string()),
+            },
+            ConflictPolicy::PreferProper => (proper_rank(source) > Self::existing_rank(sources, target))
+                .then_some(Resolution::Replace)
+                .ok_or_else(|| "Existing file kept (new file is not a later PROPER/REPACK)".to_string()),
+        }
+    }
+
+    /// PROPER/REPACK rank of the release an existing target came from
+    ///
+    /// Organized names rarely keep the tag, so the file a symlink points at and
+    /// the source the journal recorded for the target count too.
+    fn existing_rank(sources: &JournaledSources, target: &Path) -> u32 {
+        let linked = fs::read_link(target).ok();
+        let journaled = sources.get(target);
+
+        [Some(target.to_path_buf()), linked, journaled]
+            .iter()
+            .flatten()
+            .map(|path| proper_rank(path))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Rename a movie folder in place, journaled like a file rename
+    fn rename_folder(config: &OrganizerConfig, from: &Path, to: &Path, batch_id: &str) -> bool {
+        if to.exists() {
+            warn!("Not renaming {:?}: {:?} already exists", from, to);
+            return false;
+        }
+        if let Err(e) = fs::rename(from, to) {
+            warn!("Failed to rename folder {:?}: {}", from, e);
+            return false;
+        }
+
+        info!("rename {:?} -> {:?}", from, to);
+        if let Some(journal) = config.journal() {
+            let entry = JournalEntry {
+                batch_id: batch_id.to_string(),
+                method: OrganizeMethod::Rename,
+                source: from.to_path_buf(),
+                target: to.to_path_buf(),
+                backup: None,
+                created_at: Utc::now(),
+            };
+            if let Err(e) = journal.append(&entry) {
+                warn!("Failed to write organizer journal: {}", e);
+            }
+        }
+
+        true
+    }
+
+    /// Rename a file, or copy, verify and remove it when the target is on
+    /// another filesystem
+    fn move_file(
+        config: &OrganizerConfig,
+        source: &Path,
+        target: &Path,
+        verified: &mut Option<bool>,
+    ) -> std::io::Result<()> {
+        match fs::rename(source, target) {
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                Self::copy_verified(config, source, target, verified)
+                    .and_then(|()| fs::remove_file(source))
+            }
+            other => other,
+        }
+    }
+
+    /// Copy a file, hashing both sides afterwards when verification is enabled
+    ///
+    /// A target whose checksum does not match is removed again.
+    fn copy_verified(
+        config: &OrganizerConfig,
+        source: &Path,
+        target: &Path,
+        verified: &mut Option<bool>,
+    ) -> std::io::Result<()> {
+        fs::copy(source, target)?;
+        if !config.verify_checksum {
+            return Ok(());
+        }
+
+        let matches = file_checksum(source)? == file_checksum(target)?;
+        *verified = Some(matches);
+        if matches {
+            return Ok(());
+        }
+
+        warn!("Checksum mismatch after copying {:?} -> {:?}", source, target);
+        let _ = fs::remove_file(target);
+        Err(std::io::Error::other("Checksum mismatch between source and target"))
+    }
+
+    /// Remove the moved file's folder (and empty parents) when nothing worth keeping is left
+    ///
+    /// Never removes the configured source directory itself. Deleted junk files are not
+    /// recorded in the journal and cannot be rolled back.
+    fn cleanup_source_dirs(config: &OrganizerConfig, source: &Path) {
+        let mut dir = source.parent();
+
+        while let Some(current) = dir {
+            if current == config.source_dir || !current.starts_with(&config.source_dir) {
+                break;
+            }
+            if !Self::is_disposable(current) {
+                break;
             }
-            Err(e) => (false, Some(e.to_string())),
+            if let Err(e) = fs::remove_dir_all(current) {
+                warn!("Failed to clean up {:?}: {}", current, e);
+                break;
+            }
+            info!("Removed emptied source folder {:?}", current);
+            dir = current.parent();
+        }
+    }
+
+    /// Whether a directory tree only contains small junk files
+    fn is_disposable(dir: &Path) -> bool {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return false;
+        };
+
+        entries.flatten().all(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                return Self::is_disposable(&path);
+            }
+
+            let is_junk_ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| JUNK_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+            is_junk_ext && entry.metadata().is_ok_and(|m| m.len() <= JUNK_MAX_SIZE)
+        })
+    }
+
+    /// Why a video file should not be organized as a main feature, if at all
+    fn skip_reason(&self, path: &Path) -> Option<String> {
+        if self.config.min_file_size > 0
+            && let Ok(meta) = fs::metadata(path)
+            && meta.len() < self.config.min_file_size
+        {
+            return Some(format!("File too small ({} bytes)", meta.len()));
+        }
+
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let words: Vec<&str> = stem
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let folder = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if self.config.skip_samples
+            && (words.contains(&"sample") || folder == "sample" || folder == "samples")
+        {
+            return Some("Sample file".to_string());
+        }
+
+        if self.config.extras_layout != ExtrasLayout::None && ExtraKind::detect(path).is_some() {
+            return None;
+        }
+
+        if self.config.skip_extras
+            && (words.contains(&"trailer")
+                || words.contains(&"featurette")
+                || EXTRAS_FOLDERS.contains(&folder.as_str()))
+        {
+            return Some("Extra (trailer/featurette)".to_string());
+        }
+
+        None
+    }
+
+    /// Extra kind and target subfolder, when extras are laid out next to their movie
+    fn extras_folder(&self, path: &Path) -> Option<(ExtraKind, &'static str)> {
+        let kind = ExtraKind::detect(path)?;
+        Some((kind, self.config.extras_layout.folder(kind)?))
+    }
+
+    /// Largest non-extra video in the release folder an extra belongs to
+    fn main_feature(extra: &Path) -> Option<PathBuf> {
+        let mut dir = extra.parent()?;
+        let in_extras_folder = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| ExtraKind::from_folder(&n.to_lowercase()).is_some());
+        if in_extras_folder {
+            dir = dir.parent()?;
         }
+
+        fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && is_video_file(p) && ExtraKind::detect(p).is_none())
+            .max_by_key(|p| fs::metadata(p).map(|m| m.len()).unwrap_or(0))
     }
 
     /// Scan directory for video files
@@ -493,6 +2301,11 @@ impl Organizer {
             if path.is_dir() {
                 Self::scan_recursive(&path, files)?;
             } else if is_video_file(&path) {
+                // Images holding no DVD or Blu-ray folders are not video
+                if is_iso(&path) && !probe_iso(&path).may_be_video() {
+                    debug!("Skipping data disc image: {:?}", path);
+                    continue;
+                }
                 files.push(path);
             }
         }
@@ -501,10 +2314,195 @@ impl Organizer {
     }
 }
 
+/// How a conflicting target is resolved when the incoming file wins
+enum Resolution {
+    Replace,
+    Rename,
+}
+
+/// First free `name (n).ext` next to a taken target
+fn suffixed_target(target: &Path) -> PathBuf {
+    let stem = target
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let ext = target.extension().and_then(|e| e.to_str());
+
+    (1..)
+        .map(|n| {
+            let name = match ext {
+                Some(ext) => format!("{stem} ({n}).{ext}"),
+                None => format!("{stem} ({n})"),
+            };
+            target.with_file_name(name)
+        })
+        .find(|p| fs::symlink_metadata(p).is_err())
+        .unwrap_or_else(|| target.to_path_buf())
+}
+
+/// How many times a release was fixed: 0 untagged, 1 for PROPER or REPACK,
+/// `n` for `PROPERn`/`REPACKn`
+fn proper_rank(path: &Path) -> u32 {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter_map(|w| {
+            let w = w.to_ascii_lowercase();
+            let n = w.strip_prefix("proper").or_else(|| w.strip_prefix("repack"))?;
+            if n.is_empty() { Some(1) } else { n.parse().ok() }
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Media type from metadata, falling back to the filename hint
+fn media_type_of(parsed: &ParsedMedia, metadata: Option<&MediaMetadata>) -> MediaType {
+    metadata
+        .map(|m| m.media_type)
+        .unwrap_or_else(|| match parsed.hint {
+            super::MediaHint::Movie => MediaType::Movie,
+            super::MediaHint::TvShow => MediaType::Tv,
+            super::MediaHint::Anime => MediaType::Anime,
+            super::MediaHint::Unknown => MediaType::Unknown,
+        })
+}
+
+/// BLAKE3 hash of a file's contents
+fn file_checksum(path: &Path) -> std::io::Result<blake3::Hash> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(fs::File::open(path)?)?;
+    Ok(hasher.finalize())
+}
+
+/// Target path and label for an additional version, if `target` is taken by another file
+fn version_target(source: &Path, target: &Path, parsed: &ParsedMedia) -> Option<(PathBuf, String)> {
+    if fs::symlink_metadata(target).is_err() {
+        return None;
+    }
+
+    // Re-organizing a file that is already one of the versions is not a new version
+    let source = fs::canonicalize(source).ok();
+    let is_source = |path: &Path| source.is_some() && fs::canonicalize(path).ok() == source;
+    if is_source(target) {
+        return None;
+    }
+
+    let stem = target.file_stem()?.to_str()?;
+    let ext = target.extension().and_then(|e| e.to_str()).unwrap_or("mkv");
+    let label = parsed
+        .resolution
+        .as_deref()
+        .map(str::to_lowercase)
+        .or_else(|| parsed.edition.clone())
+        .or_else(|| parsed.quality.clone())
+        .unwrap_or_else(|| "Version".to_string());
+
+    for n in 1..100 {
+        let label = if n == 1 { label.clone() } else { format!("{label} {n}") };
+        let candidate = target.with_file_name(format!("{stem} - {label}.{ext}"));
+        // Already organized as this version
+        if is_source(&candidate) {
+            return Some((candidate, label));
+        }
+        if fs::symlink_metadata(&candidate).is_err() {
+            return Some((candidate, label));
+        }
+    }
+
+    None
+}
+
+/// Render a template, returning the text and whether every variable had a value
+///
+/// Missing variables render as nothing; a braced segment containing variables is
+/// dropped as a whole when any of them is missing. Unknown names are kept verbatim.
+fn render_template(template: &str, vars: &TemplateVars) -> (String, bool) {
+    let mut out = String::with_capacity(template.len());
+    let mut complete = true;
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let group = &rest[open..];
+
+        let Some(len) = braced_len(group) else {
+            // Unbalanced brace, keep the remainder as literal text
+            out.push_str(group);
+            return (out, complete);
+        };
+        let inner = &group[1..len - 1];
+
+        if inner.contains('{') {
+            let (text, segment_complete) = render_template(inner, vars);
+            if segment_complete {
+                out.push_str(&text);
+            }
+        } else {
+            match vars.get(inner) {
+                Some(Some(value)) => out.push_str(&value),
+                Some(None) => complete = false,
+                None => out.push_str(&group[..len]),
+            }
+        }
+
+        rest = &group[len..];
+    }
+
+    out.push_str(rest);
+    (out, complete)
+}
+
+/// Length of the brace group at the start of `s`, including both braces
+fn braced_len(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Generate an ID for an organize run
+fn new_batch_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Path an overwritten target is moved to so it can be restored on rollback
+///
+/// Beside the target, so moving it aside never copies across filesystems;
+/// `<name>.orig`, or `<name>.orig.1` and so on when an earlier run's backup
+/// is still there.
+fn backup_path(target: &Path) -> PathBuf {
+    let name = target.file_name().unwrap_or_default();
+    (0..)
+        .map(|n| {
+            let mut name = name.to_os_string();
+            name.push(".orig");
+            if n > 0 {
+                name.push(format!(".{n}"));
+            }
+            target.with_file_name(name)
+        })
+        .find(|path| fs::symlink_metadata(path).is_err())
+        .unwrap_or_default()
+}
+
 /// Check if a file is a video file
 fn is_video_file(path: &Path) -> bool {
     const VIDEO_EXTENSIONS: &[&str] = &[
         "mkv", "mp4", "avi", "mov", "wmv", "flv", "webm", "m4v", "mpg", "mpeg", "ts", "m2ts",
+        "iso",
     ];
 
     path.extension()
@@ -513,17 +2511,31 @@ fn is_video_file(path: &Path) -> bool {
 }
 
 /// Sanitize a string for use as a filename
-fn sanitize_filename(name: &str) -> String {
+pub(super) fn sanitize_filename(name: &str) -> String {
     // Characters not allowed in filenames on various systems
     const INVALID_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
 
     let mut result: String = name
         .chars()
-        .map(|c| if INVALID_CHARS.contains(&c) { '_' } else { c })
+        .map(|c| {
+            if INVALID_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
         .collect();
 
-    // Trim whitespace and dots from ends
-    result = result.trim().trim_matches('.').to_string();
+    // Trim whitespace and dots from ends (Windows drops trailing ones)
+    result = result
+        .trim_matches(|c: char| c.is_whitespace() || c == '.')
+        .to_string();
+
+    // Windows device names can't be used as file names, with or without extension
+    let stem = result.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        result.insert(stem.len(), '_');
+    }
 
     // Collapse multiple spaces/underscores
     while result.contains("  ") {
@@ -539,6 +2551,7 @@ fn sanitize_filename(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scraper::{ArtworkStatus, CollectionInfo, MediaHint, SeasonInfo};
 
     #[test]
     fn test_sanitize_filename() {
@@ -552,29 +2565,109 @@ mod tests {
     fn test_format_template() {
         let org = Organizer::new(OrganizerConfig::default());
 
+        let movie = TemplateVars {
+            title: "The Matrix".to_string(),
+            year: Some(1999),
+            ..Default::default()
+        };
         assert_eq!(
-            org.format_template("{title} ({year})", "The Matrix", Some(1999), None, None),
+            org.format_template("{title}{ ({year})}", &movie),
             "The Matrix (1999)"
         );
 
+        let episode = TemplateVars {
+            title: "Breaking Bad".to_string(),
+            season: Some(1),
+            episode: Some(5),
+            ..Default::default()
+        };
         assert_eq!(
-            org.format_template(
-                "{title} - S{season:02}E{episode:02}",
-                "Breaking Bad",
-                None,
-                Some(1),
-                Some(5)
-            ),
+            org.format_template("{title} - S{season:02}E{episode:02}", &episode),
             "Breaking Bad - S01E05"
         );
 
+        // Multi-episode range
+        let episodes = TemplateVars {
+            episode: Some(1),
+            episode_end: Some(2),
+            ..episode
+        };
+        assert_eq!(
+            org.format_template("{title} - S{season:02}{episode_range}", &episodes),
+            "Breaking Bad - S01E01-E02"
+        );
+
         // No year
+        let unknown = TemplateVars {
+            title: "Unknown Movie".to_string(),
+            ..Default::default()
+        };
         assert_eq!(
-            org.format_template("{title} ({year})", "Unknown Movie", None, None, None),
+            org.format_template("{title}{ ({year})}", &unknown),
             "Unknown Movie"
         );
     }
 
+    #[test]
+    fn test_format_template_conditional_segments() {
+        let org = Organizer::new(OrganizerConfig::default());
+
+        let vars = TemplateVars {
+            title: "Aliens".to_string(),
+            year: Some(1986),
+            edition: Some("Director's Cut".to_string()),
+            ..Default::default()
+        };
+        let template = "{title}{ ({year})}{ [{edition}]}{ [imdbid-{imdb_id}]}";
+
+        assert_eq!(
+            org.format_template(template, &vars),
+            "Aliens (1986) [Director's Cut]"
+        );
+
+        let vars = TemplateVars {
+            edition: None,
+            imdb_id: Some("tt0090605".to_string()),
+            ..vars
+        };
+        assert_eq!(
+            org.format_template(template, &vars),
+            "Aliens (1986) [imdbid-tt0090605]"
+        );
+
+        // Unknown variables and stray braces are left alone
+        assert_eq!(org.format_template("{title} {nope} {", &vars), "Aliens {nope} {");
+    }
+
+    #[test]
+    fn test_format_template_release_fields() {
+        let org = Organizer::new(OrganizerConfig::default());
+
+        let vars = TemplateVars {
+            title: "Tenet".to_string(),
+            year: Some(2020),
+            resolution: Some("2160P".to_string()),
+            quality: Some("Remux".to_string()),
+            codec: Some("HEVC".to_string()),
+            group: Some("FraMeSToR".to_string()),
+            edition: Some("IMAX".to_string()),
+            imdb_id: Some("tt6723592".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            org.format_template(
+                "{title} ({year}) [imdbid-{imdb_id}] - {resolution} {quality} {edition}",
+                &vars
+            ),
+            "Tenet (2020) [imdbid-tt6723592] - 2160P Remux IMAX"
+        );
+        assert_eq!(
+            org.format_template("{title}.{codec}-{group}", &vars),
+            "Tenet.HEVC-FraMeSToR"
+        );
+    }
+
     #[test]
     fn test_organize_method_parse() {
         assert_eq!(
@@ -594,4 +2687,977 @@ mod tests {
             OrganizeMethod::Copy
         );
     }
+
+    #[tokio::test]
+    async fn test_rollback_restores_moved_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let target_dir = dir.path().join("library");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("The.Matrix.1999.1080p.mkv"), b"video").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir,
+            method: OrganizeMethod::Move,
+            journal_path: Some(dir.path().join("journal.jsonl")),
+            min_file_size: 0,
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+        assert_eq!(result.success_count(), 1);
+        let target = result.success[0].target.clone();
+        assert!(target.exists());
+        assert!(!source_dir.join("The.Matrix.1999.1080p.mkv").exists());
+
+        let rollback = organizer.rollback(&result.batch_id).unwrap();
+        assert_eq!(rollback.reverted, vec![target.clone()]);
+        assert!(!target.exists());
+        assert!(source_dir.join("The.Matrix.1999.1080p.mkv").exists());
+
+        // Journal entries are consumed by a successful rollback
+        assert!(organizer.rollback(&result.batch_id).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_retry_skips_reverted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("The.Matrix.1999.mkv"), b"video").unwrap();
+        fs::write(source_dir.join("Heat.1995.mkv"), b"video").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Move,
+            journal_path: Some(dir.path().join("journal.jsonl")),
+            min_file_size: 0,
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+        assert_eq!(result.success_count(), 2);
+
+        // One target went missing, so only the other can be reverted
+        let missing = result.success[0].target.clone();
+        fs::rename(&missing, dir.path().join("aside.mkv")).unwrap();
+        let rollback = organizer.rollback(&result.batch_id).unwrap();
+        assert_eq!((rollback.reverted.len(), rollback.failed.len()), (1, 1));
+
+        // The retry only has the failed file left to revert
+        fs::rename(dir.path().join("aside.mkv"), &missing).unwrap();
+        let rollback = organizer.rollback(&result.batch_id).unwrap();
+        assert_eq!(rollback.reverted, vec![missing]);
+        assert!(rollback.failed.is_empty());
+        assert!(source_dir.join("The.Matrix.1999.mkv").exists());
+        assert!(source_dir.join("Heat.1995.mkv").exists());
+    }
+
+    #[tokio::test]
+    async fn test_companion_files_follow_media() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let target_dir = dir.path().join("library");
+        fs::create_dir_all(&source_dir).unwrap();
+        for name in [
+            "The.Matrix.1999.1080p.mkv",
+            "The.Matrix.1999.1080p.srt",
+            "The.Matrix.1999.1080p.en.ass",
+            "The.Matrix.1999.1080p.nfo",
+            "poster.jpg",
+            "notes.txt",
+        ] {
+            fs::write(source_dir.join(name), b"data").unwrap();
+        }
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir,
+            target_dir: target_dir.clone(),
+            method: OrganizeMethod::Copy,
+            min_file_size: 0,
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+        assert_eq!(result.success_count(), 1);
+
+        let movie_dir = target_dir.join("Movies").join("The Matrix (1999)");
+        assert!(movie_dir.join("The Matrix (1999).mkv").exists());
+        assert!(movie_dir.join("The Matrix (1999).srt").exists());
+        assert!(movie_dir.join("The Matrix (1999).en.ass").exists());
+        assert!(movie_dir.join("The Matrix (1999).nfo").exists());
+        assert!(movie_dir.join("poster.jpg").exists());
+        assert!(!movie_dir.join("notes.txt").exists());
+        assert_eq!(result.success[0].companions.len(), 4);
+    }
+
+    #[test]
+    fn test_skip_samples_and_extras() {
+        let dir = tempfile::tempdir().unwrap();
+        let release = dir.path().join("Movie.2020.1080p");
+        fs::create_dir_all(release.join("Sample")).unwrap();
+        fs::create_dir_all(release.join("Featurettes")).unwrap();
+        for name in [
+            "Movie.2020.1080p.mkv",
+            "movie-sample.mkv",
+            "Sample/movie.mkv",
+            "Featurettes/Making Of.mkv",
+            "Movie.2020.Trailer.mp4",
+        ] {
+            fs::write(release.join(name), b"data").unwrap();
+        }
+
+        let organizer = Organizer::new(OrganizerConfig {
+            min_file_size: 0,
+            ..Default::default()
+        });
+
+        assert!(organizer.skip_reason(&release.join("Movie.2020.1080p.mkv")).is_none());
+        assert_eq!(
+            organizer.skip_reason(&release.join("movie-sample.mkv")),
+            Some("Sample file".to_string())
+        );
+        assert!(organizer.skip_reason(&release.join("Sample/movie.mkv")).is_some());
+        assert!(organizer.skip_reason(&release.join("Featurettes/Making Of.mkv")).is_some());
+        assert!(organizer.skip_reason(&release.join("Movie.2020.Trailer.mp4")).is_some());
+
+        // Size threshold
+        let organizer = Organizer::new(OrganizerConfig {
+            min_file_size: 1024,
+            ..Default::default()
+        });
+        assert!(organizer.skip_reason(&release.join("Movie.2020.1080p.mkv")).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_source_after_move() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let release = source_dir.join("The.Matrix.1999.1080p");
+        let other = source_dir.join("Other.Release");
+        fs::create_dir_all(release.join("Subs")).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(release.join("The.Matrix.1999.1080p.mkv"), b"video").unwrap();
+        fs::write(release.join("RARBG.txt"), b"junk").unwrap();
+        fs::write(release.join("Subs").join("info.nfo"), b"junk").unwrap();
+        fs::write(other.join("readme.pdf"), b"keep").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Move,
+            min_file_size: 0,
+            cleanup_source: true,
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+        assert_eq!(result.success_count(), 1);
+        assert!(!release.exists());
+        assert!(other.exists());
+        assert!(source_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_keep_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let target_dir = dir.path().join("library");
+        fs::create_dir_all(source_dir.join("a")).unwrap();
+        fs::create_dir_all(source_dir.join("b")).unwrap();
+        fs::write(source_dir.join("a/Tenet.2020.1080p.mkv"), b"1080p").unwrap();
+        fs::write(source_dir.join("b/Tenet.2020.2160p.mkv"), b"2160p").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir: target_dir.clone(),
+            method: OrganizeMethod::Copy,
+            separate_by_type: false,
+            min_file_size: 0,
+            keep_versions: true,
+            ..Default::default()
+        });
+
+        organizer
+            .organize_file(&source_dir.join("a/Tenet.2020.1080p.mkv"))
+            .await
+            .unwrap();
+        let result = organizer
+            .organize_file(&source_dir.join("b/Tenet.2020.2160p.mkv"))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.version, Some("2160p".to_string()));
+        let movie_dir = target_dir.join("Tenet (2020)");
+        assert!(movie_dir.join("Tenet (2020).mkv").exists());
+        assert!(movie_dir.join("Tenet (2020) - 2160p.mkv").exists());
+
+        // Organizing the same file again does not add another version
+        let again = organizer
+            .organize_file(&movie_dir.join("Tenet (2020) - 2160p.mkv"))
+            .await
+            .unwrap();
+        assert!(again.version.is_none());
+        assert!(!movie_dir.join("Tenet (2020) - 2160p 2.mkv").exists());
+    }
+
+    #[tokio::test]
+    async fn test_progress_and_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("Tenet.2020.mkv"), b"video").unwrap();
+        fs::write(source_dir.join("Dune.2021.mkv"), b"video").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir,
+            target_dir: dir.path().join("library"),
+            separate_by_type: false,
+            dry_run: true,
+            min_file_size: 0,
+            ..Default::default()
+        });
+
+        let progress = OrganizeProgress::default();
+        organizer.organize_all_with_progress(&progress).await.unwrap();
+        let snapshot = progress.snapshot();
+        assert_eq!(snapshot.total, 2);
+        assert_eq!(snapshot.scanned, 2);
+        assert_eq!(snapshot.linked, 2);
+
+        let progress = OrganizeProgress::default();
+        progress.cancel();
+        let result = organizer.organize_all_with_progress(&progress).await.unwrap();
+        assert!(result.cancelled);
+        assert_eq!(result.total(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_organize() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        fs::create_dir_all(&source_dir).unwrap();
+        for year in 2000..2010 {
+            fs::write(source_dir.join(format!("Heat.{year}.mkv")), b"video").unwrap();
+        }
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir,
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Copy,
+            separate_by_type: false,
+            min_file_size: 0,
+            concurrency: 4,
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+
+        assert_eq!(result.success_count(), 10);
+        for year in 2000..2010 {
+            let name = format!("Heat ({year})");
+            assert!(dir.path().join("library").join(&name).join(format!("{name}.mkv")).exists());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_files_with_same_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        for release in ["a", "b", "c", "d"] {
+            let folder = source_dir.join(release);
+            fs::create_dir_all(&folder).unwrap();
+            fs::write(folder.join("Heat.1995.mkv"), release).unwrap();
+        }
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir,
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Copy,
+            conflict: ConflictPolicy::RenameWithSuffix,
+            separate_by_type: false,
+            min_file_size: 0,
+            concurrency: 4,
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+
+        // Each one waited for the last to land, then picked a free name
+        assert_eq!(result.success_count(), 4);
+        let mut targets: Vec<_> = result.success.iter().map(|r| r.target.clone()).collect();
+        targets.sort();
+        targets.dedup();
+        assert_eq!(targets.len(), 4);
+        assert!(targets.iter().all(|t| t.exists()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_organizers_sharing_claims() {
+        let dir = tempfile::tempdir().unwrap();
+        let claims = Arc::new(OrganizeClaims::default());
+        let organizers: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .map(|release| {
+                let source_dir = dir.path().join("downloads").join(release);
+                fs::create_dir_all(&source_dir).unwrap();
+                fs::write(source_dir.join("Heat.1995.mkv"), release).unwrap();
+                Organizer::new(OrganizerConfig {
+                    source_dir,
+                    target_dir: dir.path().join("library"),
+                    method: OrganizeMethod::Copy,
+                    conflict: ConflictPolicy::RenameWithSuffix,
+                    separate_by_type: false,
+                    min_file_size: 0,
+                    ..Default::default()
+                })
+                .with_claims(Arc::clone(&claims))
+            })
+            .collect();
+
+        let results = join_all(organizers.iter().map(Organizer::organize_all)).await;
+
+        // Separate runs wait for each other's files as files of one run do
+        let mut targets: Vec<_> = results
+            .into_iter()
+            .flat_map(|r| r.unwrap().success)
+            .map(|r| r.target)
+            .collect();
+        targets.sort();
+        targets.dedup();
+        assert_eq!(targets.len(), 3);
+        let mut contents: Vec<_> = targets.iter().map(|t| fs::read(t).unwrap()).collect();
+        contents.sort();
+        assert_eq!(contents, [b"a", b"b", b"c"]);
+    }
+
+    /// Knows one movie, Heat (1995), and counts the searches for it
+    struct HeatProvider(Arc<AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl crate::scraper::MetadataProvider for HeatProvider {
+        fn id(&self) -> &'static str {
+            "fake"
+        }
+
+        fn name(&self) -> &'static str {
+            "Fake"
+        }
+
+        fn supported_types(&self) -> &[MediaType] {
+            &[MediaType::Movie]
+        }
+
+        async fn search(
+            &self,
+            _query: &str,
+            _options: &crate::scraper::SearchOptions,
+        ) -> Result<Vec<MediaInfo>, ScraperError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![
+                MediaInfo::new("1", "Heat", "fake")
+                    .with_type(MediaType::Movie)
+                    .with_year(Some(1995)),
+            ])
+        }
+
+        async fn get_metadata(
+            &self,
+            id: &str,
+            media_type: MediaType,
+        ) -> Result<MediaMetadata, ScraperError> {
+            Ok(MediaMetadata {
+                id: id.to_string(),
+                title: "Heat".to_string(),
+                release_date: Some("1995-12-15".to_string()),
+                media_type,
+                provider: "fake".to_string(),
+                ..Default::default()
+            })
+        }
+
+        async fn get_episode(
+            &self,
+            id: &str,
+            _season: i32,
+            _episode: i32,
+        ) -> Result<crate::scraper::EpisodeInfo, ScraperError> {
+            Err(ScraperError::NotFound(id.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_looks_files_up_through_scraper() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("Heat.1995.1080p.mkv"), b"video").unwrap();
+        fs::write(source_dir.join("Heat.1995-sample.mkv"), b"sample").unwrap();
+
+        let searches = Arc::new(AtomicUsize::new(0));
+        let mut scraper = ScraperManager::new();
+        scraper.add_provider(HeatProvider(Arc::clone(&searches)));
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir,
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Copy,
+            separate_by_type: false,
+            min_file_size: 0,
+            ..Default::default()
+        })
+        .with_scraper(scraper);
+
+        let result = organizer.organize_all().await.unwrap();
+
+        // The sample is skipped before any lookup
+        assert_eq!(result.success_count(), 1, "{:?}", result.failed);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(searches.load(Ordering::SeqCst), 1);
+        let organized = &result.success[0];
+        assert_eq!(organized.metadata.as_ref().map(|m| m.id.as_str()), Some("1"));
+        assert!(dir.path().join("library/Heat (1995)/Heat (1995).mkv").exists());
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("Tenet.2020.mkv"), b"video").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Copy,
+            separate_by_type: false,
+            min_file_size: 0,
+            verify_checksum: true,
+            ..Default::default()
+        });
+
+        let result = organizer
+            .organize_file(&source_dir.join("Tenet.2020.mkv"))
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.verified, Some(true));
+        assert_eq!(
+            file_checksum(&result.target).unwrap(),
+            file_checksum(&source_dir.join("Tenet.2020.mkv")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_detect_extra_kind() {
+        assert_eq!(
+            ExtraKind::detect(Path::new("Movie (2020)/Movie-trailer.mkv")),
+            Some(ExtraKind::Trailer)
+        );
+        assert_eq!(
+            ExtraKind::detect(Path::new("Movie (2020)/Behind The Scenes/Making Of.mkv")),
+            Some(ExtraKind::BehindTheScenes)
+        );
+        assert_eq!(
+            ExtraKind::detect(Path::new("Movie (2020)/Movie-deleted.mkv")),
+            Some(ExtraKind::DeletedScene)
+        );
+        assert_eq!(ExtraKind::detect(Path::new("Spider-Man.2002.mkv")), None);
+    }
+
+    #[tokio::test]
+    async fn test_extras_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let release = source_dir.join("Tenet.2020.1080p");
+        fs::create_dir_all(release.join("Featurettes")).unwrap();
+        fs::write(release.join("Tenet.2020.1080p.mkv"), b"main feature").unwrap();
+        fs::write(release.join("Tenet-trailer.mkv"), b"trailer").unwrap();
+        fs::write(release.join("Featurettes/Making Of.mkv"), b"featurette").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir,
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Copy,
+            separate_by_type: false,
+            min_file_size: 0,
+            extras_layout: ExtrasLayout::Jellyfin,
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+
+        assert_eq!(result.success_count(), 3);
+        let movie_dir = dir.path().join("library/Tenet (2020)");
+        assert!(movie_dir.join("Tenet (2020).mkv").exists());
+        assert!(movie_dir.join("trailers/Tenet-trailer.mkv").exists());
+        assert!(movie_dir.join("featurettes/Making Of.mkv").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rename_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("movies");
+        let release = source_dir.join("Tenet.2020.1080p.BluRay");
+        fs::create_dir_all(&release).unwrap();
+        fs::write(release.join("Tenet.2020.1080p.BluRay.mkv"), b"video").unwrap();
+        fs::write(release.join("Tenet.2020.1080p.BluRay.en.srt"), b"subs").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            method: OrganizeMethod::Rename,
+            min_file_size: 0,
+            rename_folder: true,
+            journal_path: Some(dir.path().join("journal.jsonl")),
+            ..Default::default()
+        });
+
+        let result = organizer.organize_all().await.unwrap();
+
+        assert_eq!(result.success_count(), 1);
+        let movie_dir = source_dir.join("Tenet (2020)");
+        assert_eq!(result.success[0].target, movie_dir.join("Tenet (2020).mkv"));
+        assert!(movie_dir.join("Tenet (2020).mkv").exists());
+        assert!(movie_dir.join("Tenet (2020).en.srt").exists());
+        assert!(!release.exists());
+
+        // Rolling back restores the original names
+        organizer.rollback(&result.batch_id).unwrap();
+        assert!(release.join("Tenet.2020.1080p.BluRay.mkv").exists());
+        assert!(release.join("Tenet.2020.1080p.BluRay.en.srt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rename_folder_holding_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("movies");
+        let release = source_dir.join("Tenet.2020.BluRay");
+        fs::create_dir_all(&release).unwrap();
+        fs::write(release.join("Tenet.2020.1080p.BluRay.mkv"), b"video").unwrap();
+        fs::write(release.join("Tenet.2020.2160p.BluRay.mkv"), b"video").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            method: OrganizeMethod::Rename,
+            min_file_size: 0,
+            rename_folder: true,
+            keep_versions: true,
+            concurrency: 1,
+            ..Default::default()
+        });
+
+        // The folder is renamed once and the second file is found inside it
+        let result = organizer.organize_all().await.unwrap();
+        assert_eq!(result.success_count(), 2, "{:?}", result.failed);
+        let movie_dir = source_dir.join("Tenet (2020)");
+        assert!(!release.exists());
+        assert_eq!(fs::read_dir(&movie_dir).unwrap().count(), 2);
+
+        // Files that are already named correctly are left as they are
+        let organizer = Organizer::new(organizer.config.as_ref().clone());
+        let again = organizer.organize_all().await.unwrap();
+        assert_eq!(again.success_count(), 2, "{:?}", again.failed);
+        assert!(again.success.iter().all(|r| r.conflict.is_none()));
+        assert_eq!(fs::read_dir(&movie_dir).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_conflict_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let movie_dir = dir.path().join("library/Tenet (2020)");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::create_dir_all(&movie_dir).unwrap();
+        fs::write(source_dir.join("Tenet.2020.mkv"), b"small").unwrap();
+        fs::write(movie_dir.join("Tenet (2020).mkv"), b"larger file").unwrap();
+
+        let organizer = |conflict| {
+            Organizer::new(OrganizerConfig {
+                source_dir: source_dir.clone(),
+                target_dir: dir.path().join("library"),
+                method: OrganizeMethod::Copy,
+                separate_by_type: false,
+                min_file_size: 0,
+                conflict,
+                ..Default::default()
+            })
+        };
+        let source = source_dir.join("Tenet.2020.mkv");
+
+        let result = organizer(ConflictPolicy::KeepLarger)
+            .organize_file(&source)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.conflict, Some(ConflictPolicy::KeepLarger));
+        assert_eq!(fs::read(movie_dir.join("Tenet (2020).mkv")).unwrap(), b"larger file");
+
+        let result = organizer(ConflictPolicy::RenameWithSuffix)
+            .organize_file(&source)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.conflict, Some(ConflictPolicy::RenameWithSuffix));
+        assert_eq!(result.target, movie_dir.join("Tenet (2020) (1).mkv"));
+
+        let result = organizer(ConflictPolicy::Overwrite)
+            .organize_file(&source)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(fs::read(movie_dir.join("Tenet (2020).mkv")).unwrap(), b"small");
+        assert!(!movie_dir.join("Tenet (2020).mkv.orig").exists());
+    }
+
+    #[tokio::test]
+    async fn test_overwrites_keep_every_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let movie_dir = dir.path().join("library/Tenet (2020)");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::create_dir_all(&movie_dir).unwrap();
+        let source = source_dir.join("Tenet.2020.mkv");
+        let target = movie_dir.join("Tenet (2020).mkv");
+        fs::write(&target, b"existing").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Copy,
+            separate_by_type: false,
+            min_file_size: 0,
+            conflict: ConflictPolicy::Overwrite,
+            journal_path: Some(dir.path().join("journal.jsonl")),
+            ..Default::default()
+        });
+        fs::write(&source, b"first").unwrap();
+        let first = organizer.organize_all().await.unwrap();
+        fs::write(&source, b"second").unwrap();
+        let second = organizer.organize_all().await.unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"second");
+
+        // The second run's backup doesn't clobber the first one's
+        organizer.rollback(&second.batch_id).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"first");
+        organizer.rollback(&first.batch_id).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"existing");
+        assert_eq!(fs::read_dir(&movie_dir).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prefer_proper_keeps_later_fix() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        let movie_dir = dir.path().join("library/Tenet (2020)");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("Tenet.2020.PROPER.mkv"), b"proper").unwrap();
+        fs::write(source_dir.join("Tenet.2020.mkv"), b"original").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir: dir.path().join("library"),
+            method: OrganizeMethod::Symlink,
+            separate_by_type: false,
+            min_file_size: 0,
+            conflict: ConflictPolicy::PreferProper,
+            ..Default::default()
+        });
+
+        let proper = organizer
+            .organize_file(&source_dir.join("Tenet.2020.PROPER.mkv"))
+            .await
+            .unwrap();
+        assert!(proper.success);
+
+        // The untagged release does not replace the PROPER the link points at
+        let original = organizer
+            .organize_file(&source_dir.join("Tenet.2020.mkv"))
+            .await
+            .unwrap();
+        assert!(!original.success);
+        assert_eq!(fs::read(movie_dir.join("Tenet (2020).mkv")).unwrap(), b"proper");
+    }
+
+    #[test]
+    fn test_failed_replace_restores_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("Tenet (2020).mkv");
+        fs::write(&target, b"existing").unwrap();
+
+        let config = OrganizerConfig {
+            method: OrganizeMethod::Copy,
+            conflict: ConflictPolicy::Overwrite,
+            ..Default::default()
+        };
+        let missing = dir.path().join("missing.mkv");
+        let sources = JournaledSources::default();
+        let transfer = Organizer::perform_organize(&config, &sources, &missing, &target, "batch");
+
+        assert!(!transfer.success);
+        assert_eq!(fs::read(&target).unwrap(), b"existing");
+        assert!(!backup_path(&target).exists());
+    }
+
+    #[tokio::test]
+    async fn test_library_root_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("Heat.1995.mkv"), b"video").unwrap();
+
+        let organizer = Organizer::new(OrganizerConfig {
+            source_dir: source_dir.clone(),
+            target_dir: dir.path().join("library"),
+            template: NamingTemplate {
+                library_root: Some("{media_type}/{decade}{/{genre}}".to_string()),
+                ..Default::default()
+            },
+            dry_run: true,
+            min_file_size: 0,
+            ..Default::default()
+        });
+
+        let result = organizer
+            .organize_file(&source_dir.join("Heat.1995.mkv"))
+            .await
+            .unwrap();
+
+        // No metadata, so the genre folder drops out
+        assert_eq!(
+            result.target,
+            dir.path().join("library/Movies/1990s/Heat (1995)/Heat (1995).mkv")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_write_nfo_with_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("Heat (1995)/Heat (1995).mkv");
+        let metadata = MediaMetadata {
+            title: "Heat".to_string(),
+            media_type: MediaType::Movie,
+            ..Default::default()
+        };
+
+        let organizer = Organizer::new(OrganizerConfig {
+            write_nfo: true,
+            ..Default::default()
+        });
+        let (written, _) = organizer
+            .write_artifacts(&target, &ParsedMedia::default(), &metadata, "batch")
+            .await;
+
+        assert_eq!(written, vec![dir.path().join("Heat (1995)/movie.nfo")]);
+        let nfo = fs::read_to_string(&written[0]).unwrap();
+        assert!(nfo.contains("<title>Heat</title>"), "{nfo}");
+
+        let organizer = Organizer::new(OrganizerConfig {
+            write_nfo: true,
+            metadata_format: MetadataFormat::Json,
+            ..Default::default()
+        });
+        let (written, _) = organizer
+            .write_artifacts(&target, &ParsedMedia::default(), &metadata, "batch")
+            .await;
+
+        assert_eq!(written, vec![dir.path().join("Heat (1995)/metadata.json")]);
+        let json = fs::read_to_string(&written[0]).unwrap();
+        assert!(json.contains("\"title\": \"Heat\""), "{json}");
+    }
+
+    #[tokio::test]
+    async fn test_show_artwork_saved_once() {
+        use std::sync::atomic::AtomicUsize;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Serves a poster and counts how often it is asked for
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbImage::new(40, 60)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let png = png.into_inner();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/poster.png", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let served = Arc::clone(&requests);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                served.fetch_add(1, Ordering::SeqCst);
+                let head = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n", png.len());
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(&png).await.unwrap();
+            }
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let show = dir.path().join("Severance");
+        let mut metadata = MediaMetadata {
+            title: "Severance".to_string(),
+            media_type: MediaType::Tv,
+            ..Default::default()
+        };
+        metadata.images.poster = Some(url);
+        let organizer = Organizer::new(OrganizerConfig {
+            download_artwork: true,
+            artwork: ArtworkOptions {
+                overwrite: true,
+                poster_widths: Vec::new(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        for episode in ["S01E01", "S01E02"] {
+            let target = show.join(format!("Season 01/Severance - {episode}.mkv"));
+            let (written, artwork) = organizer
+                .write_artifacts(&target, &ParsedMedia::default(), &metadata, "batch")
+                .await;
+            if episode == "S01E01" {
+                assert_eq!(written, vec![show.join("poster.png")]);
+                assert_eq!(artwork.len(), 1);
+                assert_eq!(artwork[0].status, ArtworkStatus::Saved);
+            } else {
+                assert!(written.is_empty() && artwork.is_empty());
+            }
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_write_movie_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("Movies/Alien (1979)/Alien (1979).mkv");
+        let metadata = MediaMetadata {
+            title: "Alien".to_string(),
+            media_type: MediaType::Movie,
+            provider: "tmdb".to_string(),
+            collection: Some(CollectionInfo {
+                id: "8091".to_string(),
+                name: "Alien: Collection".to_string(),
+                poster_url: None,
+                backdrop_url: None,
+            }),
+            ..Default::default()
+        };
+
+        let organizer = Organizer::new(OrganizerConfig {
+            write_nfo: true,
+            movie_sets_dir: Some(dir.path().join("Sets")),
+            ..Default::default()
+        });
+        let (written, _) = organizer
+            .write_artifacts(&target, &ParsedMedia::default(), &metadata, "batch")
+            .await;
+
+        let set = dir.path().join("Sets/Alien_ Collection/collection.nfo");
+        assert_eq!(
+            written,
+            vec![dir.path().join("Movies/Alien (1979)/movie.nfo"), set.clone()]
+        );
+        let nfo = fs::read_to_string(&written[0]).unwrap();
+        assert!(
+            nfo.contains(r#"<set tmdbcolid="8091"><name>Alien: Collection</name></set>"#),
+            "{nfo}"
+        );
+        let nfo = fs::read_to_string(&set).unwrap();
+        assert!(nfo.contains(r#"<title>Alien: Collection</title><uniqueid type="tmdb""#));
+    }
+
+    #[test]
+    fn test_anime_absolute_layout() {
+        let organizer = Organizer::new(OrganizerConfig {
+            target_dir: PathBuf::from("library"),
+            separate_by_type: false,
+            anime_layout: AnimeLayout::Absolute,
+            ..Default::default()
+        });
+        let parsed = ParsedMedia {
+            title: "One Piece".to_string(),
+            episode: Some(45),
+            hint: MediaHint::Anime,
+            ..Default::default()
+        };
+
+        let target = organizer
+            .build_target_path(Path::new("op-045.mkv"), &parsed, None)
+            .unwrap();
+        assert_eq!(target, Path::new("library/One Piece/One Piece - 045.mkv"));
+
+        // Season numbering is converted with the season episode counts
+        let metadata = MediaMetadata {
+            title: "Frieren".to_string(),
+            media_type: MediaType::Anime,
+            seasons: vec![SeasonInfo {
+                number: 1,
+                name: None,
+                overview: None,
+                air_date: None,
+                episode_count: Some(28),
+                poster_url: None,
+            }],
+            ..Default::default()
+        };
+        let parsed = ParsedMedia {
+            title: "Frieren".to_string(),
+            season: Some(2),
+            episode: Some(3),
+            ..Default::default()
+        };
+        let target = organizer
+            .build_target_path(Path::new("frieren.mkv"), &parsed, Some(&metadata))
+            .unwrap();
+        assert_eq!(target, Path::new("library/Frieren/Frieren - 031.mkv"));
+    }
+
+    #[test]
+    fn test_windows_safe_names() {
+        assert_eq!(sanitize_filename("CON"), "CON_");
+        assert_eq!(sanitize_filename("nul.txt"), "nul_.txt");
+        assert_eq!(sanitize_filename("Console"), "Console");
+        assert_eq!(sanitize_filename("Title . "), "Title");
+        assert_eq!(sanitize_filename("Tab\tName"), "Tab_Name");
+    }
+
+    #[test]
+    fn test_long_titles_are_truncated() {
+        let parsed = ParsedMedia {
+            title: "Long ".repeat(80),
+            year: Some(2020),
+            hint: MediaHint::Movie,
+            ..Default::default()
+        };
+
+        let organizer = Organizer::new(OrganizerConfig {
+            target_dir: PathBuf::from("library"),
+            target_os: TargetOs::Unix,
+            ..Default::default()
+        });
+        let target = organizer
+            .build_target_path(Path::new("long.mkv"), &parsed, None)
+            .unwrap();
+        let name = target.file_name().unwrap().to_str().unwrap();
+        assert!(name.len() <= MAX_NAME_LEN);
+        assert!(name.ends_with(" (2020).mkv"));
+
+        let organizer = Organizer::new(OrganizerConfig {
+            target_dir: PathBuf::from("C:\\Media"),
+            target_os: TargetOs::Windows,
+            ..Default::default()
+        });
+        let target = organizer
+            .build_target_path(Path::new("long.mkv"), &parsed, None)
+            .unwrap();
+        assert!(target.as_os_str().len() <= MAX_WINDOWS_PATH);
+        assert!(target.to_str().unwrap().ends_with(" (2020).mkv"));
+    }
 }
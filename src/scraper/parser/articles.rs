@@ -0,0 +1,168 @@
+use parking_lot::RwLock;
+use std::sync::{Arc, LazyLock};
+
+/// Leading articles per language (lowercase). Elided forms end with an apostrophe.
+const LANGUAGE_ARTICLES: &[(&str, &[&str])] = &[
+    ("en", &["the", "a", "an"]),
+    ("fr", &["le", "la", "les", "l'", "un", "une", "des"]),
+    ("de", &["der", "die", "das", "ein", "eine"]),
+    ("es", &["el", "la", "los", "las", "un", "una"]),
+    ("it", &["il", "lo", "la", "gli", "le", "l'", "un", "uno", "una"]),
+];
+
+/// Languages whose articles are stripped from every title by default
+///
+/// Other languages' articles are only stripped from titles in that original
+/// language, so "Die Hard" and "La La Land" keep their first word.
+pub const DEFAULT_ARTICLE_LANGUAGES: &[&str] = &["en"];
+
+/// Leading articles stripped from titles for sorting and matching
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleArticles {
+    articles: Vec<String>,
+}
+
+impl TitleArticles {
+    /// Build the article list for the given language codes (ISO 639-1)
+    pub fn for_languages<S: AsRef<str>>(languages: &[S]) -> Self {
+        let mut articles: Vec<String> = Vec::new();
+
+        for lang in languages {
+            if let Some(list) = language_articles(lang.as_ref()) {
+                for article in list {
+                    if !articles.iter().any(|a| a == article) {
+                        articles.push((*article).to_string());
+                    }
+                }
+            }
+        }
+
+        Self { articles }
+    }
+
+    /// Remove a single leading article (case-insensitive) from a title
+    ///
+    /// The title is returned unchanged if nothing would be left after stripping.
+    #[must_use]
+    pub fn strip<'a>(&self, title: &'a str) -> &'a str {
+        strip_leading(title, &self.articles).unwrap_or_else(|| title.trim_start())
+    }
+
+    /// Like [`strip`](Self::strip), also trying the articles of the title's
+    /// original language (ISO 639-1, e.g. `fr` or `fr-FR`)
+    #[must_use]
+    pub fn strip_in<'a>(&self, title: &'a str, language: Option<&str>) -> &'a str {
+        strip_leading(title, &self.articles)
+            .or_else(|| strip_leading(title, language_articles(language?)?))
+            .unwrap_or_else(|| title.trim_start())
+    }
+
+    /// Get the globally configured article list
+    pub fn active() -> Arc<Self> {
+        ACTIVE.read().clone()
+    }
+
+    /// Replace the globally configured article list
+    pub fn set_active(articles: Self) {
+        *ACTIVE.write() = Arc::new(articles);
+    }
+}
+
+/// Articles of a language, ignoring any region suffix (`fr-CA`)
+fn language_articles(language: &str) -> Option<&'static [&'static str]> {
+    let lang = language.split(['-', '_']).next()?.to_lowercase();
+    LANGUAGE_ARTICLES
+        .iter()
+        .find(|(l, _)| *l == lang)
+        .map(|(_, list)| *list)
+}
+
+/// Strip the first matching leading article, if anything is left after it
+fn strip_leading<'a, A: AsRef<str>>(title: &'a str, articles: &[A]) -> Option<&'a str> {
+    let trimmed = title.trim_start();
+
+    for article in articles {
+        let article = article.as_ref();
+        let Some(head) = trimmed.get(..article.len()) else {
+            continue;
+        };
+        if !head.eq_ignore_ascii_case(article) {
+            continue;
+        }
+
+        let rest = &trimmed[article.len()..];
+        // Elided articles (L') attach directly, others need a following space
+        let rest = if article.ends_with('\'') {
+            rest
+        } else if rest.starts_with(char::is_whitespace) {
+            rest.trim_start()
+        } else {
+            continue;
+        };
+
+        if !rest.is_empty() {
+            return Some(rest);
+        }
+    }
+
+    None
+}
+
+impl Default for TitleArticles {
+    fn default() -> Self {
+        Self::for_languages(DEFAULT_ARTICLE_LANGUAGES)
+    }
+}
+
+/// Global article list used by title normalization and sort titles
+static ACTIVE: LazyLock<RwLock<Arc<TitleArticles>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(TitleArticles::default())));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_articles() {
+        let articles = TitleArticles::for_languages(&["en", "fr", "de", "es", "it"]);
+
+        assert_eq!(articles.strip("The Matrix"), "Matrix");
+        assert_eq!(articles.strip("Le Fabuleux Destin"), "Fabuleux Destin");
+        assert_eq!(articles.strip("L'Armée des ombres"), "Armée des ombres");
+        assert_eq!(articles.strip("Das Boot"), "Boot");
+        assert_eq!(
+            articles.strip("El Laberinto del fauno"),
+            "Laberinto del fauno"
+        );
+        assert_eq!(articles.strip("Il Postino"), "Postino");
+        // Only whole words are stripped
+        assert_eq!(articles.strip("Theodore Rex"), "Theodore Rex");
+        assert_eq!(
+            articles.strip("Lesbian Vampire Killers"),
+            "Lesbian Vampire Killers"
+        );
+        // Never strip to an empty title
+        assert_eq!(articles.strip("The"), "The");
+    }
+
+    #[test]
+    fn test_articles_for_languages() {
+        let english = TitleArticles::for_languages(&["en"]);
+
+        assert_eq!(english.strip("The Matrix"), "Matrix");
+        assert_eq!(english.strip("Der Untergang"), "Der Untergang");
+    }
+
+    #[test]
+    fn test_default_keeps_foreign_words() {
+        let articles = TitleArticles::default();
+
+        assert_eq!(articles.strip("Die Hard"), "Die Hard");
+        assert_eq!(articles.strip("La La Land"), "La La Land");
+        assert_eq!(articles.strip_in("La La Land", Some("en")), "La La Land");
+        // Articles in the title's original language are still stripped
+        assert_eq!(articles.strip_in("Das Boot", Some("de")), "Boot");
+        assert_eq!(articles.strip_in("La Haine", Some("fr-FR")), "Haine");
+        assert_eq!(articles.strip_in("The Matrix", Some("ja")), "Matrix");
+    }
+}
@@ -1,4 +1,5 @@
 use super::patterns::{MediaHint, PATTERNS};
+use crate::scraper::{disc::DiscKind, types::ExternalIds};
 use std::path::Path;
 
 /// Parsed information from a media filename
@@ -10,10 +11,15 @@ pub struct ParsedMedia {
     pub original_title: String,
     /// Release year if found
     pub year: Option<i32>,
-    /// Season number (1-indexed)
+    /// Season number (1-indexed), only when the name states one; `Show - 13`
+    /// leaves it unset as the episode may be an absolute number
     pub season: Option<i32>,
     /// Episode number (1-indexed)
     pub episode: Option<i32>,
+    /// Last episode for multi-episode files (`S01E01-E02`)
+    pub episode_end: Option<i32>,
+    /// Air date for date-named episodes (YYYY-MM-DD)
+    pub air_date: Option<String>,
     /// Video resolution (e.g., "1080p")
     pub resolution: Option<String>,
     /// Source quality (e.g., "`BluRay`", "WEB-DL")
@@ -22,8 +28,16 @@ pub struct ParsedMedia {
     pub codec: Option<String>,
     /// Release group name
     pub release_group: Option<String>,
+    /// Edition (e.g., "Director's Cut", "Extended")
+    pub edition: Option<String>,
     /// Hint about media type based on filename patterns
     pub hint: MediaHint,
+    /// Actual file duration in minutes (from ffprobe), used to disambiguate type
+    pub runtime: Option<i32>,
+    /// Provider IDs tagged in the filename (e.g. `[tmdbid-603]`)
+    pub external_ids: ExternalIds,
+    /// Disc format of an image (from probing its contents)
+    pub disc: Option<DiscKind>,
 }
 
 impl Default for ParsedMedia {
@@ -34,11 +48,17 @@ impl Default for ParsedMedia {
             year: None,
             season: None,
             episode: None,
+            episode_end: None,
+            air_date: None,
             resolution: None,
             quality: None,
             codec: None,
             release_group: None,
+            edition: None,
             hint: MediaHint::Unknown,
+            runtime: None,
+            external_ids: ExternalIds::default(),
+            disc: None,
         }
     }
 }
@@ -64,13 +84,27 @@ impl Parser {
 
         let patterns = &*PATTERNS;
 
+        // Extract external ID tags
+        for caps in patterns.external_id.captures_iter(filename) {
+            let id = caps[2].to_string();
+            match caps[1].to_lowercase().as_str() {
+                "imdb" => result.external_ids.imdb = Some(id),
+                "tmdb" => result.external_ids.tmdb = Some(id),
+                "tvdb" => result.external_ids.tvdb = Some(id),
+                "anidb" => result.external_ids.anidb = Some(id),
+                "anilist" => result.external_ids.anilist = Some(id),
+                _ => {}
+            }
+        }
+
         // Extract release group from start [GroupName]
         if let Some(caps) = patterns.release_group_start.captures(filename) {
             let group = caps.get(1).map(|m| m.as_str().to_string());
-            // Only set if it's not a hash or resolution
+            // Only set if it's not a hash, resolution or ID tag
             if let Some(ref g) = group
                 && !patterns.hash.is_match(&format!("[{g}]"))
                 && !patterns.resolution.is_match(g)
+                && !patterns.external_id.is_match(&format!("[{g}]"))
             {
                 result.release_group = Some(g.clone());
             }
@@ -91,14 +125,33 @@ impl Parser {
             result.codec = Some(m.as_str().to_uppercase());
         }
 
+        // Extract edition
+        if let Some(m) = patterns.edition.find(filename) {
+            result.edition = Some(m.as_str().replace(['.', '_'], " "));
+        }
+
         // Try different episode patterns in order of specificity
-        let (season, episode, title_end_pos) = Self::extract_episode_info(filename, patterns);
+        let (season, episode, mut title_end_pos) = Self::extract_episode_info(filename, patterns);
         result.season = season;
         result.episode = episode;
+        result.episode_end = Self::extract_episode_end(filename, episode, patterns);
 
         // Extract year
         result.year = Self::extract_year(filename, patterns);
 
+        // Date-named episodes (daily shows): a date before any episode marker wins
+        if let Some(caps) = patterns.air_date.captures(filename)
+            && let Some(date) = caps.get(0)
+            && title_end_pos.is_none_or(|pos| pos >= date.start())
+        {
+            result.air_date = Some(format!("{}-{}-{}", &caps[1], &caps[2], &caps[3]));
+            result.year = caps[1].parse().ok();
+            result.season = None;
+            result.episode = None;
+            result.episode_end = None;
+            title_end_pos = Some(date.start());
+        }
+
         // Determine media hint
         result.hint = Self::determine_hint(&result, filename, patterns);
 
@@ -132,26 +185,40 @@ impl Parser {
         if let Some(caps) = patterns.episode_dash.captures(filename) {
             let episode = caps.get(1).and_then(|m| m.as_str().parse().ok());
             let pos = caps.get(0).map(|m| m.start());
-            return (Some(1), episode, pos); // Assume season 1 for anime
+            return (None, episode, pos);
         }
 
         // Try E01 format
         if let Some(caps) = patterns.episode_only.captures(filename) {
             let episode = caps.get(1).and_then(|m| m.as_str().parse().ok());
             let pos = caps.get(0).map(|m| m.start());
-            return (Some(1), episode, pos);
+            return (None, episode, pos);
         }
 
         // Try [01] format
         if let Some(caps) = patterns.episode_bracket.captures(filename) {
             let episode = caps.get(1).and_then(|m| m.as_str().parse().ok());
             let pos = caps.get(0).map(|m| m.start());
-            return (Some(1), episode, pos);
+            return (None, episode, pos);
         }
 
         (None, None, None)
     }
 
+    /// Find the last episode of a `S01E01-E02` / `S01E01E02` / `S01E01-02` range
+    fn extract_episode_end(
+        filename: &str,
+        episode: Option<i32>,
+        patterns: &super::patterns::Patterns,
+    ) -> Option<i32> {
+        let start = episode?;
+        let marker = patterns.season_episode.find(filename)?;
+        let caps = patterns.episode_range_end.captures(&filename[marker.end()..])?;
+        let end: i32 = caps.get(1).or_else(|| caps.get(2))?.as_str().parse().ok()?;
+
+        (end > start).then_some(end)
+    }
+
     fn extract_year(filename: &str, patterns: &super::patterns::Patterns) -> Option<i32> {
         // Prefer year in parentheses
         if let Some(caps) = patterns.year_in_parens.captures(filename)
@@ -194,9 +261,14 @@ impl Parser {
             return MediaHint::Anime;
         }
 
+        // Date-named files are episodes of a daily show
+        if result.air_date.is_some() {
+            return MediaHint::TvShow;
+        }
+
         // Check for TV show indicators
-        if result.season.is_some() && result.episode.is_some() {
-            if result.season == Some(1) && has_dash_episode {
+        if result.episode.is_some() {
+            if result.season.is_none() && has_dash_episode {
                 return MediaHint::Anime;
             }
             return MediaHint::TvShow;
@@ -218,24 +290,19 @@ impl Parser {
     ) -> String {
         let mut title = filename.to_string();
 
-        // Remove release group from start
-        if patterns.release_group_start.is_match(&title) {
+        // Remove release group (or any leading bracket tag) from start
+        let removed = patterns
+            .release_group_start
+            .find(filename)
+            .map_or(0, |m| m.end());
+        if removed > 0 {
             title = patterns.release_group_start.replace(&title, "").to_string();
         }
 
         // Truncate at episode info position if available
         if let Some(pos) = title_end_pos {
             // Adjust position after removing release group
-            let adjusted_pos = if result.release_group.is_some() {
-                pos.saturating_sub(
-                    patterns
-                        .release_group_start
-                        .find(filename)
-                        .map_or(0, |m| m.end()),
-                )
-            } else {
-                pos
-            };
+            let adjusted_pos = pos.saturating_sub(removed);
             if adjusted_pos < title.len() {
                 title.truncate(adjusted_pos);
             }
@@ -261,6 +328,7 @@ impl Parser {
         title = patterns.resolution.replace_all(&title, " ").to_string();
         title = patterns.quality.replace_all(&title, " ").to_string();
         title = patterns.codec.replace_all(&title, " ").to_string();
+        title = patterns.edition.replace_all(&title, " ").to_string();
 
         // Replace separators with spaces
         title = title.replace(['.', '_', '-'], " ");
@@ -329,6 +397,17 @@ mod tests {
         assert_eq!(info.episode, Some(1));
     }
 
+    #[test]
+    fn test_parse_air_date_episode() {
+        let path = PathBuf::from("The.Daily.Show.2023.05.14.720p.WEB.mkv");
+        let info = Parser::parse(&path);
+        assert_eq!(info.title, "The Daily Show");
+        assert_eq!(info.air_date, Some("2023-05-14".to_string()));
+        assert_eq!(info.year, Some(2023));
+        assert!(info.episode.is_none());
+        assert_eq!(info.hint, MediaHint::TvShow);
+    }
+
     #[test]
     fn test_parse_movie_with_parens_year() {
         let path = PathBuf::from("Inception (2010) 2160p UHD BluRay.mkv");
@@ -337,4 +416,47 @@ mod tests {
         assert_eq!(info.year, Some(2010));
         assert_eq!(info.hint, MediaHint::Movie);
     }
+
+    #[test]
+    fn test_parse_edition() {
+        let info = Parser::parse_filename("Blade.Runner.1982.Final.Cut.1080p.BluRay");
+        assert_eq!(info.title, "Blade Runner");
+        assert_eq!(info.edition, Some("Final Cut".to_string()));
+
+        let info = Parser::parse_filename("Aliens Directors Cut 2160p");
+        assert_eq!(info.title, "Aliens");
+        assert_eq!(info.edition, Some("Directors Cut".to_string()));
+    }
+
+    #[test]
+    fn test_parse_multi_episode() {
+        let info = Parser::parse_filename("Breaking.Bad.S01E01-E02.720p.mkv");
+        assert_eq!(info.title, "Breaking Bad");
+        assert_eq!(info.episode, Some(1));
+        assert_eq!(info.episode_end, Some(2));
+
+        let info = Parser::parse_filename("Breaking.Bad.S01E01E02E03.720p");
+        assert_eq!(info.episode_end, Some(3));
+
+        let info = Parser::parse_filename("Breaking.Bad.S01E05-06");
+        assert_eq!(info.episode_end, Some(6));
+
+        // Resolution after a dash is not a range
+        let info = Parser::parse_filename("Breaking.Bad.S01E01-720p");
+        assert_eq!(info.episode_end, None);
+    }
+
+    #[test]
+    fn test_parse_external_id_tags() {
+        let path = PathBuf::from("The Matrix (1999) [tmdbid-603] {imdb-tt0133093}.mkv");
+        let info = Parser::parse(&path);
+        assert_eq!(info.title, "The Matrix");
+        assert_eq!(info.external_ids.tmdb, Some("603".to_string()));
+        assert_eq!(info.external_ids.imdb, Some("tt0133093".to_string()));
+
+        let info = Parser::parse_filename("[tvdbid=81189] Breaking Bad S01E01");
+        assert_eq!(info.title, "Breaking Bad");
+        assert!(info.release_group.is_none());
+        assert_eq!(info.external_ids.tvdb, Some("81189".to_string()));
+    }
 }
@@ -1,6 +1,8 @@
+mod articles;
 mod filename;
 mod patterns;
 
+pub use articles::{DEFAULT_ARTICLE_LANGUAGES, TitleArticles};
 pub use filename::{ParsedMedia, Parser};
 pub use patterns::MediaHint;
 
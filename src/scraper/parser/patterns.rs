@@ -19,11 +19,16 @@ pub struct Patterns {
 
     // Episode patterns (ordered by specificity)
     pub season_episode: Regex,   // S01E01, s1e1
+    pub episode_range_end: Regex, // -E02, E02E03, -02 right after S01E01
     pub season_x_episode: Regex, // 1x01
     pub episode_only: Regex,     // E01, Ep01, EP01
     pub episode_dash: Regex,     // - 01, - 01v2
     pub episode_bracket: Regex,  // [01], [01v2]
     pub episode_number: Regex,   // 01 (at end, after title)
+    pub air_date: Regex,         // 2023.05.14, 2023-05-14 (daily shows)
+
+    // External ID tags: [tmdbid-603], {imdb-tt0133093}
+    pub external_id: Regex,
 
     // Resolution patterns
     pub resolution: Regex,
@@ -34,6 +39,9 @@ pub struct Patterns {
     // Codec patterns
     pub codec: Regex,
 
+    // Edition patterns (Director's Cut, Extended, IMAX...)
+    pub edition: Regex,
+
     // Release group patterns (at start or end)
     pub release_group_start: Regex, // [GroupName]
     pub release_group_end: Regex,   // -GroupName at end
@@ -56,6 +64,8 @@ impl Patterns {
             // Season/Episode patterns
             season_episode: Regex::new(r"(?i)[Ss](\d{1,2})[Ee](\d{1,3})")
                 .expect("Invalid season_episode regex"),
+            episode_range_end: Regex::new(r"^(?i)(?:(?:-?E\d{1,3})*-?E(\d{1,3})|-(\d{1,3}))(?:[^\dp]|$)")
+                .expect("Invalid episode_range_end regex"),
             season_x_episode: Regex::new(r"(?i)(\d{1,2})[xX](\d{1,3})")
                 .expect("Invalid season_x_episode regex"),
             episode_only: Regex::new(r"(?i)(?:E|EP|Ep)\.?(\d{1,3})")
@@ -66,6 +76,10 @@ impl Patterns {
                 .expect("Invalid episode_bracket regex"),
             episode_number: Regex::new(r"(?:^|[\s._-])(\d{2,3})(?:v\d)?(?:[\s._\[\(-]|$)")
                 .expect("Invalid episode_number regex"),
+            air_date: Regex::new(r"\b((?:19|20)\d{2})[.\-_ ](0[1-9]|1[0-2])[.\-_ ](0[1-9]|[12]\d|3[01])\b")
+                .expect("Invalid air_date regex"),
+            external_id: Regex::new(r"(?i)[\[{](imdb|tmdb|tvdb|anidb|anilist)(?:id)?[-=](tt\d+|\d+)[\]}]")
+                .expect("Invalid external_id regex"),
 
             // Resolution
             resolution: Regex::new(r"(?i)(480p|576p|720p|1080p|2160p|4[kK]|UHD)")
@@ -81,6 +95,12 @@ impl Patterns {
             codec: Regex::new(r"(?i)(x264|x265|H\.?264|H\.?265|HEVC|AVC|XviD|DivX|VP9|AV1)")
                 .expect("Invalid codec regex"),
 
+            // Edition
+            edition: Regex::new(
+                r"(?i)\b(Director'?s[ ._]Cut|Extended(?:[ ._](?:Cut|Edition))?|Unrated|Uncut|Theatrical(?:[ ._]Cut)?|Remastered|IMAX|Criterion|Final[ ._]Cut|(?:Special|Ultimate|Collector'?s)[ ._]Edition)\b",
+            )
+            .expect("Invalid edition regex"),
+
             // Release groups
             release_group_start: Regex::new(r"^\[([^\]]+)\]").expect("Invalid release_group_start regex"),
             release_group_end: Regex::new(r"-([A-Za-z0-9]+)(?:\.[a-z]{2,4})?$")
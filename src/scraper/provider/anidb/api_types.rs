@@ -0,0 +1,238 @@
+use serde::Deserialize;
+
+// Failed requests still answer 200, with an `<error>` document
+#[derive(Debug, Deserialize)]
+pub struct Error {
+    #[serde(rename = "@code")]
+    pub code: Option<u16>,
+    #[serde(rename = "$text")]
+    pub message: String,
+}
+
+// Anime responses (`request=anime`)
+#[derive(Debug, Deserialize)]
+pub struct Anime {
+    #[serde(rename = "@id")]
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub episodecount: Option<i32>,
+    pub startdate: Option<String>,
+    pub enddate: Option<String>,
+    #[serde(default)]
+    pub titles: Titles,
+    pub description: Option<String>,
+    pub picture: Option<String>,
+    #[serde(default)]
+    pub ratings: Ratings,
+    #[serde(default)]
+    pub creators: Creators,
+    #[serde(default)]
+    pub resources: Resources,
+    #[serde(default)]
+    pub tags: Tags,
+    #[serde(default)]
+    pub characters: Characters,
+    #[serde(default)]
+    pub episodes: Episodes,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Titles {
+    #[serde(rename = "title", default)]
+    pub items: Vec<Title>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Title {
+    #[serde(rename = "@xml:lang")]
+    pub lang: String,
+    /// main, official, synonym or short; missing on episode titles
+    #[serde(rename = "@type")]
+    pub kind: Option<String>,
+    #[serde(rename = "$text")]
+    pub value: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Ratings {
+    pub permanent: Option<Rating>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Rating {
+    #[serde(rename = "@count", alias = "@votes")]
+    pub count: Option<i32>,
+    #[serde(rename = "$text")]
+    pub value: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Creators {
+    #[serde(rename = "name", default)]
+    pub items: Vec<Creator>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Creator {
+    #[serde(rename = "@id")]
+    pub id: i64,
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "$text")]
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Resources {
+    #[serde(rename = "resource", default)]
+    pub items: Vec<Resource>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Resource {
+    #[serde(rename = "@type")]
+    pub kind: i32,
+    #[serde(rename = "externalentity", default)]
+    pub entities: Vec<ExternalEntity>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExternalEntity {
+    #[serde(rename = "identifier", default)]
+    pub identifiers: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Tags {
+    #[serde(rename = "tag", default)]
+    pub items: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Tag {
+    /// 0-600, how strongly the tag applies
+    #[serde(rename = "@weight")]
+    pub weight: Option<i32>,
+    #[serde(rename = "@localspoiler", default)]
+    pub spoiler: bool,
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Characters {
+    #[serde(rename = "character", default)]
+    pub items: Vec<Character>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Character {
+    pub name: String,
+    pub seiyuu: Option<Seiyuu>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Seiyuu {
+    #[serde(rename = "@id")]
+    pub id: i64,
+    #[serde(rename = "@picture")]
+    pub picture: Option<String>,
+    #[serde(rename = "$text")]
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Episodes {
+    #[serde(rename = "episode", default)]
+    pub items: Vec<Episode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Episode {
+    #[serde(rename = "@id")]
+    pub id: i64,
+    pub epno: EpisodeNumber,
+    pub length: Option<i32>,
+    pub airdate: Option<String>,
+    pub rating: Option<Rating>,
+    #[serde(rename = "title", default)]
+    pub titles: Vec<Title>,
+    pub summary: Option<String>,
+}
+
+/// `1` for regular episodes, `S1` for specials, `C1` for credits...
+#[derive(Debug, Deserialize)]
+pub struct EpisodeNumber {
+    /// 1 regular, 2 special, 3 credits, 4 trailer, 5 parody, 6 other
+    #[serde(rename = "@type")]
+    pub kind: i32,
+    #[serde(rename = "$text")]
+    pub value: String,
+}
+
+// Title dump (`anime-titles.xml.gz`), the only way to search AniDB
+#[derive(Debug, Deserialize)]
+pub struct TitleDump {
+    #[serde(rename = "anime", default)]
+    pub anime: Vec<DumpEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DumpEntry {
+    #[serde(rename = "@aid")]
+    pub aid: i64,
+    #[serde(rename = "title", default)]
+    pub titles: Vec<Title>,
+}
+
+// Episode mappings (`anime-list.xml` of the Anime-Lists project)
+#[derive(Debug, Deserialize)]
+pub struct AnimeList {
+    #[serde(rename = "anime", default)]
+    pub anime: Vec<ListEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListEntry {
+    #[serde(rename = "@anidbid")]
+    pub anidb_id: i64,
+    /// Numeric, or "movie", "OVA", "unknown"...
+    #[serde(rename = "@tvdbid")]
+    pub tvdb_id: Option<String>,
+    /// Season number, or "a" when TVDB numbers the episodes absolutely
+    #[serde(rename = "@defaulttvdbseason")]
+    pub default_season: Option<String>,
+    #[serde(rename = "@episodeoffset")]
+    pub episode_offset: Option<String>,
+    #[serde(rename = "@tmdbid")]
+    pub tmdb_id: Option<String>,
+    /// Comma separated when a movie entry spans several IMDb titles
+    #[serde(rename = "@imdbid")]
+    pub imdb_id: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "mapping-list", default)]
+    pub mapping_list: MappingList,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MappingList {
+    #[serde(rename = "mapping", default)]
+    pub items: Vec<Mapping>,
+}
+
+/// Either explicit pairs (";1-5;2-6;") or a range shifted by `offset`
+#[derive(Debug, Deserialize)]
+pub struct Mapping {
+    #[serde(rename = "@anidbseason")]
+    pub anidb_season: i32,
+    #[serde(rename = "@tvdbseason")]
+    pub tvdb_season: i32,
+    #[serde(rename = "@start")]
+    pub start: Option<i32>,
+    #[serde(rename = "@end")]
+    pub end: Option<i32>,
+    #[serde(rename = "@offset")]
+    pub offset: Option<i32>,
+    #[serde(rename = "$text")]
+    pub episodes: Option<String>,
+}
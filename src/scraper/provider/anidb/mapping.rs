@@ -0,0 +1,200 @@
+use super::api_types::{AnimeList, ListEntry};
+use crate::scraper::Result;
+
+/// How the episodes of an AniDB entry line up with TVDB's seasons
+///
+/// AniDB gives every season its own entry numbered from 1, which is the
+/// absolute numbering most fansub releases use. TVDB groups the same episodes
+/// into seasons of one show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeMapping {
+    pub anidb_id: String,
+    pub name: Option<String>,
+    pub tvdb_id: Option<String>,
+    pub tmdb_id: Option<String>,
+    pub imdb_id: Option<String>,
+    /// TVDB season holding the regular episodes; `None` when TVDB numbers
+    /// them absolutely
+    pub default_season: Option<i32>,
+    /// Added to an AniDB episode number to get TVDB's
+    pub episode_offset: i32,
+    ranges: Vec<SeasonRange>,
+}
+
+/// Exception to the default season and offset
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SeasonRange {
+    anidb_season: i32,
+    tvdb_season: i32,
+    start: i32,
+    end: i32,
+    offset: i32,
+    /// Explicit (AniDB, TVDB) episode pairs; TVDB 0 means it has no match
+    episodes: Vec<(i32, i32)>,
+}
+
+impl EpisodeMapping {
+    /// Parse the mappings of `anime-list.xml`
+    pub fn parse_list(xml: &str) -> Result<Vec<Self>> {
+        let list: AnimeList = quick_xml::de::from_str(xml)?;
+        Ok(list.anime.into_iter().map(Self::from_entry).collect())
+    }
+
+    fn from_entry(entry: ListEntry) -> Self {
+        let ranges = entry
+            .mapping_list
+            .items
+            .into_iter()
+            .map(|m| SeasonRange {
+                anidb_season: m.anidb_season,
+                tvdb_season: m.tvdb_season,
+                start: m.start.unwrap_or(1),
+                end: m.end.unwrap_or(i32::MAX),
+                offset: m.offset.unwrap_or(0),
+                episodes: m.episodes.as_deref().map(episode_pairs).unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            anidb_id: entry.anidb_id.to_string(),
+            name: entry.name,
+            tvdb_id: entry.tvdb_id.and_then(numeric),
+            tmdb_id: entry.tmdb_id.and_then(numeric),
+            imdb_id: entry
+                .imdb_id
+                .and_then(|ids| ids.split(',').find(|id| id.starts_with("tt")).map(str::to_string)),
+            default_season: entry.default_season.and_then(|s| s.parse().ok()),
+            episode_offset: entry
+                .episode_offset
+                .and_then(|o| o.parse().ok())
+                .unwrap_or(0),
+            ranges,
+        }
+    }
+
+    /// TVDB season and episode of a regular (absolute) AniDB episode
+    #[must_use]
+    pub fn season_episode(&self, absolute: i32) -> Option<(i32, i32)> {
+        self.to_tvdb(1, absolute)
+    }
+
+    /// Regular (absolute) AniDB episode of a TVDB season and episode
+    #[must_use]
+    pub fn absolute(&self, season: i32, episode: i32) -> Option<i32> {
+        match self.from_tvdb(season, episode) {
+            Some((1, absolute)) => Some(absolute),
+            _ => None,
+        }
+    }
+
+    /// TVDB season and episode of an AniDB episode (season 0 holds specials)
+    #[must_use]
+    pub fn to_tvdb(&self, anidb_season: i32, episode: i32) -> Option<(i32, i32)> {
+        for range in self.ranges.iter().filter(|r| r.anidb_season == anidb_season) {
+            if let Some(&(_, tvdb)) = range.episodes.iter().find(|(a, _)| *a == episode) {
+                return (tvdb > 0).then_some((range.tvdb_season, tvdb));
+            }
+            if range.episodes.is_empty() && (range.start..=range.end).contains(&episode) {
+                return Some((range.tvdb_season, episode + range.offset));
+            }
+        }
+
+        if anidb_season != 1 {
+            return None;
+        }
+        self.default_season
+            .map(|season| (season, episode + self.episode_offset))
+    }
+
+    /// AniDB season and episode of a TVDB season and episode
+    #[must_use]
+    pub fn from_tvdb(&self, season: i32, episode: i32) -> Option<(i32, i32)> {
+        for range in self.ranges.iter().filter(|r| r.tvdb_season == season) {
+            if let Some(&(anidb, _)) = range.episodes.iter().find(|(_, t)| *t == episode) {
+                return Some((range.anidb_season, anidb));
+            }
+            let anidb = episode - range.offset;
+            if range.episodes.is_empty() && (range.start..=range.end).contains(&anidb) {
+                return Some((range.anidb_season, anidb));
+            }
+        }
+
+        if self.default_season != Some(season) {
+            return None;
+        }
+        let absolute = episode - self.episode_offset;
+        (absolute > 0).then_some((1, absolute))
+    }
+}
+
+/// Pairs of ";1-5;2-6+7;3-0;"; only the first of several TVDB episodes is kept
+fn episode_pairs(list: &str) -> Vec<(i32, i32)> {
+    list.split(';')
+        .filter_map(|pair| {
+            let (anidb, tvdb) = pair.split_once('-')?;
+            let tvdb = tvdb.split('+').next()?;
+            Some((anidb.trim().parse().ok()?, tvdb.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn numeric(id: String) -> Option<String> {
+    (!id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())).then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<anime-list>
+  <anime anidbid="3651" tvdbid="79151" defaulttvdbseason="1" episodeoffset="" tmdbid="" imdbid="">
+    <name>Suzumiya Haruhi no Yuuutsu</name>
+    <mapping-list>
+      <mapping anidbseason="0" tvdbseason="0">;1-2;2-3+4;3-0;</mapping>
+    </mapping-list>
+  </anime>
+  <anime anidbid="6999" tvdbid="79151" defaulttvdbseason="2" episodeoffset="0" tmdbid="" imdbid="">
+    <name>Suzumiya Haruhi no Yuuutsu (2009)</name>
+    <mapping-list>
+      <mapping anidbseason="1" tvdbseason="1" start="1" end="14" offset="14"/>
+    </mapping-list>
+  </anime>
+  <anime anidbid="69" tvdbid="81797" defaulttvdbseason="a" episodeoffset="" tmdbid="" imdbid="">
+    <name>One Piece</name>
+  </anime>
+  <anime anidbid="5975" tvdbid="movie" defaulttvdbseason="1"
+         tmdbid="23128" imdbid="tt1155650,tt1155651">
+    <name>Suzumiya Haruhi no Shoushitsu</name>
+  </anime>
+</anime-list>"#;
+
+    #[test]
+    fn test_episode_mapping() {
+        let list = EpisodeMapping::parse_list(LIST).unwrap();
+        let [first, second, absolute, movie] = list.as_slice() else {
+            panic!("expected four entries, got {list:?}");
+        };
+
+        // Default season, specials listed pair by pair
+        assert_eq!(first.season_episode(5), Some((1, 5)));
+        assert_eq!(first.absolute(1, 5), Some(5));
+        assert_eq!(first.to_tvdb(0, 2), Some((0, 3)));
+        assert_eq!(first.to_tvdb(0, 3), None);
+        assert_eq!(first.from_tvdb(0, 2), Some((0, 1)));
+
+        // A range moved into another season
+        assert_eq!(second.season_episode(1), Some((1, 15)));
+        assert_eq!(second.season_episode(15), Some((2, 15)));
+        assert_eq!(second.absolute(1, 15), Some(1));
+        assert_eq!(second.absolute(1, 14), None);
+
+        // TVDB numbering the show absolutely
+        assert_eq!(absolute.default_season, None);
+        assert_eq!(absolute.season_episode(1000), None);
+
+        assert_eq!(movie.tvdb_id, None);
+        assert_eq!(movie.tmdb_id.as_deref(), Some("23128"));
+        assert_eq!(movie.imdb_id.as_deref(), Some("tt1155650"));
+    }
+}
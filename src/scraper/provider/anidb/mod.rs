@@ -0,0 +1,6 @@
+mod api_types;
+mod mapping;
+mod provider;
+
+pub use mapping::EpisodeMapping;
+pub use provider::AniDbProvider;
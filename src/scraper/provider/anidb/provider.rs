@@ -0,0 +1,598 @@
+use super::api_types::{Anime, DumpEntry, Episode, EpisodeNumber, Error, Title, TitleDump};
+use super::mapping::EpisodeMapping;
+use crate::scraper::{
+    parser::TitleArticles,
+    provider::{HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions},
+    types::{EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType, PersonInfo},
+    Result, ScraperError,
+};
+use async_trait::async_trait;
+use moka::future::Cache;
+use regex::Regex;
+use std::io::Read;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::warn;
+
+const ANIDB_API_URL: &str = "http://api.anidb.net:9001";
+const ANIDB_TITLES_URL: &str = "https://anidb.net/api";
+const ANIDB_IMAGE_URL: &str = "https://cdn-eu.anidb.net/images/main";
+const ANIME_LISTS_URL: &str = "https://raw.githubusercontent.com/Anime-Lists/anime-lists/master";
+/// AniDB bans clients sending more than one request every two seconds
+const ANIDB_RATE_LIMIT: f64 = 0.5;
+/// AniDB asks for each anime and the title dump to be fetched at most once a day
+const REFRESH_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Resource types of `<resources>`
+const RESOURCE_MAL: i32 = 2;
+const RESOURCE_IMDB: i32 = 43;
+const RESOURCE_TMDB: i32 = 44;
+
+/// Tags applying at least this strongly (of 600) are kept
+const MIN_TAG_WEIGHT: i32 = 400;
+
+/// Links in descriptions, e.g. "http://anidb.net/ch7514 [Haruhi]"
+static LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"https?://anidb\.net/\S+ \[([^\]]+)\]").unwrap());
+
+/// A download refreshed once a day
+type Daily<T> = Mutex<Option<(Instant, Arc<T>)>>;
+
+/// Provider for AniDB, the reference for absolute anime episode numbering
+///
+/// Needs a client registered at <https://anidb.net/software/add>.
+pub struct AniDbProvider {
+    api: HttpClient,
+    titles_client: HttpClient,
+    lists_client: HttpClient,
+    client_name: String,
+    client_version: String,
+    anime: Cache<String, Arc<Anime>>,
+    titles: Daily<TitleDump>,
+    mappings: Daily<Vec<EpisodeMapping>>,
+}
+
+impl AniDbProvider {
+    pub fn new(client_name: impl Into<String>, client_version: u32) -> Self {
+        Self {
+            api: HttpClient::new(ANIDB_API_URL).with_rate_limit(ANIDB_RATE_LIMIT),
+            titles_client: HttpClient::new(ANIDB_TITLES_URL),
+            lists_client: HttpClient::new(ANIME_LISTS_URL),
+            client_name: client_name.into(),
+            client_version: client_version.to_string(),
+            anime: Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(REFRESH_INTERVAL)
+                .build(),
+            titles: Mutex::new(None),
+            mappings: Mutex::new(None),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the HTTP API
+    ///
+    /// The title dump and the anime-lists mappings are only proxied.
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        let proxy_only = NetworkOptions {
+            proxy: network.proxy.clone(),
+            ..NetworkOptions::default()
+        };
+        self.api = self.api.with_network(network)?;
+        self.titles_client = self.titles_client.with_network(&proxy_only)?;
+        self.lists_client = self.lists_client.with_network(&proxy_only)?;
+        Ok(self)
+    }
+
+    async fn anime(&self, aid: &str) -> Result<Arc<Anime>> {
+        if let Some(anime) = self.anime.get(aid).await {
+            return Ok(anime);
+        }
+
+        let body = self
+            .api
+            .get_bytes(
+                "/httpapi",
+                &[
+                    ("request", "anime"),
+                    ("client", &self.client_name),
+                    ("clientver", &self.client_version),
+                    ("protover", "1"),
+                    ("aid", aid),
+                ],
+            )
+            .await?;
+        let anime = Arc::new(parse_anime(&String::from_utf8_lossy(&body))?);
+
+        self.anime.insert(aid.to_string(), anime.clone()).await;
+        Ok(anime)
+    }
+
+    /// Titles of every anime, for searching
+    async fn titles(&self) -> Result<Arc<TitleDump>> {
+        daily(&self.titles, async {
+            let body = self.titles_client.get_bytes("/anime-titles.xml.gz", &[]).await?;
+            tokio::task::spawn_blocking(move || {
+                let xml = gunzip(body)?;
+                Ok(quick_xml::de::from_str(&xml)?)
+            })
+            .await
+            .map_err(|e| ScraperError::Parse(format!("Title dump error: {e}")))?
+        })
+        .await
+    }
+
+    /// Episode mappings of every anime
+    async fn mappings(&self) -> Result<Arc<Vec<EpisodeMapping>>> {
+        daily(&self.mappings, async {
+            let body = self.lists_client.get_bytes("/anime-list.xml", &[]).await?;
+            tokio::task::spawn_blocking(move || {
+                EpisodeMapping::parse_list(&String::from_utf8_lossy(&body))
+            })
+            .await
+            .map_err(|e| ScraperError::Parse(format!("Episode mapping error: {e}")))?
+        })
+        .await
+    }
+
+    /// How the episodes of an anime line up with TVDB's seasons, if known
+    pub async fn episode_mapping(&self, aid: &str) -> Result<Option<EpisodeMapping>> {
+        let mappings = self.mappings().await?;
+        Ok(mappings.iter().find(|m| m.anidb_id == aid).cloned())
+    }
+
+    /// Mapping of an anime, treating a failed download as unknown
+    async fn try_episode_mapping(&self, aid: &str) -> Option<EpisodeMapping> {
+        self.episode_mapping(aid)
+            .await
+            .inspect_err(|e| warn!("Failed to load AniDB episode mappings: {e}"))
+            .ok()
+            .flatten()
+    }
+
+    /// All regular episodes and specials of an anime
+    ///
+    /// Seasons and episode numbers follow TVDB where a mapping is known;
+    /// regular episodes keep AniDB's number as their absolute number.
+    pub async fn get_episodes(&self, aid: &str) -> Result<Vec<EpisodeInfo>> {
+        let anime = self.anime(aid).await?;
+        let mapping = self.try_episode_mapping(aid).await;
+
+        let mut episodes: Vec<EpisodeInfo> = anime
+            .episodes
+            .items
+            .iter()
+            .filter_map(|e| episode_info(e, mapping.as_ref()))
+            .collect();
+        episodes.sort_by_key(|e| (e.season, e.episode));
+
+        Ok(episodes)
+    }
+
+    fn anime_to_metadata(&self, anime: &Anime, mapping: Option<&EpisodeMapping>) -> MediaMetadata {
+        let titles = &anime.titles.items;
+        let title = main_title(titles).unwrap_or_default().to_string();
+        let year = anime.startdate.as_deref().and_then(year_of);
+
+        let mut external_ids = ExternalIds {
+            anidb: Some(anime.id.to_string()),
+            mal: resource_id(anime, RESOURCE_MAL, |_| true),
+            imdb: resource_id(anime, RESOURCE_IMDB, |id| id.starts_with("tt")),
+            tmdb: resource_id(anime, RESOURCE_TMDB, |id| id.bytes().all(|b| b.is_ascii_digit())),
+            ..Default::default()
+        };
+        if let Some(mapping) = mapping {
+            external_ids.tvdb = mapping.tvdb_id.clone();
+            external_ids.tmdb = external_ids.tmdb.or_else(|| mapping.tmdb_id.clone());
+            external_ids.imdb = external_ids.imdb.or_else(|| mapping.imdb_id.clone());
+        }
+
+        let cast = anime
+            .characters
+            .items
+            .iter()
+            .filter_map(|c| Some((c, c.seiyuu.as_ref()?)))
+            .enumerate()
+            .map(|(order, (character, seiyuu))| PersonInfo {
+                id: seiyuu.id.to_string(),
+                name: seiyuu.name.clone(),
+                role: Some(character.name.clone()),
+                image_url: seiyuu.picture.as_deref().map(image_url),
+                order: Some(order as i32),
+            })
+            .collect();
+        let crew = anime
+            .creators
+            .items
+            .iter()
+            .map(|c| PersonInfo {
+                id: c.id.to_string(),
+                name: c.name.clone(),
+                role: Some(c.kind.clone()),
+                image_url: None,
+                order: None,
+            })
+            .collect();
+
+        MediaMetadata {
+            id: anime.id.to_string(),
+            sort_title: Some(Self::generate_sort_title(&title, year)),
+            original_title: official_title(titles, "ja").map(ToString::to_string),
+            media_type: MediaType::Anime,
+            overview: anime.description.as_deref().and_then(plain_text),
+            release_date: anime.startdate.clone(),
+            end_date: anime.enddate.clone(),
+            runtime: anime
+                .episodes
+                .items
+                .iter()
+                .find(|e| e.epno.kind == 1)
+                .and_then(|e| e.length),
+            rating: anime.ratings.permanent.as_ref().map(|r| r.value),
+            vote_count: anime.ratings.permanent.as_ref().and_then(|r| r.count),
+            tags: anime
+                .tags
+                .items
+                .iter()
+                .filter(|t| !t.spoiler && t.weight.unwrap_or(0) >= MIN_TAG_WEIGHT)
+                .map(|t| t.name.clone())
+                .collect(),
+            images: ImageSet {
+                poster: anime.picture.as_deref().map(image_url),
+                ..Default::default()
+            },
+            external_ids,
+            provider: "anidb".to_string(),
+            episode_count: anime.episodecount,
+            cast,
+            crew,
+            title,
+            ..Default::default()
+        }
+    }
+
+    fn generate_sort_title(title: &str, year: Option<i32>) -> String {
+        let articles = TitleArticles::active();
+        let sort_title = articles.strip(title);
+
+        if let Some(year) = year {
+            format!("{sort_title} ({year})")
+        } else {
+            sort_title.to_string()
+        }
+    }
+}
+
+/// Cached value of a daily download, loading it when missing or stale
+async fn daily<T>(
+    slot: &Daily<T>,
+    load: impl Future<Output = Result<T>>,
+) -> Result<Arc<T>> {
+    let mut slot = slot.lock().await;
+    if let Some((loaded, ref value)) = *slot
+        && loaded.elapsed() < REFRESH_INTERVAL
+    {
+        return Ok(value.clone());
+    }
+
+    let value = Arc::new(load.await?);
+    *slot = Some((Instant::now(), value.clone()));
+    Ok(value)
+}
+
+/// Anime document, or the `<error>` AniDB answered instead
+fn parse_anime(xml: &str) -> Result<Anime> {
+    let root = xml.trim_start();
+    let root = match root.strip_prefix("<?xml") {
+        Some(rest) => rest.split_once("?>").map_or(rest, |(_, r)| r).trim_start(),
+        None => root,
+    };
+    if !root.starts_with("<error") {
+        return Ok(quick_xml::de::from_str(xml)?);
+    }
+
+    let error: Error = quick_xml::de::from_str(xml)?;
+    Err(if error.message == "No such anime" {
+        ScraperError::NotFound(error.message)
+    } else {
+        ScraperError::Api {
+            status: error.code.unwrap_or(200),
+            message: error.message,
+        }
+    })
+}
+
+/// Decompress the title dump, unless the HTTP client already did
+fn gunzip(body: Vec<u8>) -> Result<String> {
+    if !body.starts_with(&[0x1f, 0x8b]) {
+        return String::from_utf8(body).map_err(|e| ScraperError::Parse(e.to_string()));
+    }
+
+    let mut xml = String::new();
+    flate2::read::GzDecoder::new(body.as_slice()).read_to_string(&mut xml)?;
+    Ok(xml)
+}
+
+/// AniDB season (1 regular, 0 specials) and number of an episode
+fn episode_number(epno: &EpisodeNumber) -> Option<(i32, i32)> {
+    match epno.kind {
+        1 => Some((1, epno.value.parse().ok()?)),
+        2 => Some((0, epno.value.trim_start_matches('S').parse().ok()?)),
+        // Credits, trailers, parodies and other extras
+        _ => None,
+    }
+}
+
+fn episode_info(episode: &Episode, mapping: Option<&EpisodeMapping>) -> Option<EpisodeInfo> {
+    let (anidb_season, number) = episode_number(&episode.epno)?;
+    let (season, number_in_season) = mapping
+        .and_then(|m| m.to_tvdb(anidb_season, number))
+        .unwrap_or((anidb_season, number));
+
+    let title = official_title(&episode.titles, "en")
+        .or_else(|| official_title(&episode.titles, "x-jat"))
+        .or_else(|| episode.titles.first().map(|t| t.value.as_str()))
+        .unwrap_or_default();
+
+    Some(EpisodeInfo {
+        id: episode.id.to_string(),
+        title: title.to_string(),
+        season,
+        episode: number_in_season,
+        absolute_number: (anidb_season == 1).then_some(number),
+        original_title: official_title(&episode.titles, "ja").map(ToString::to_string),
+        air_date: episode.airdate.clone(),
+        overview: episode.summary.as_deref().and_then(plain_text),
+        runtime: episode.length,
+        rating: episode.rating.as_ref().map(|r| r.value),
+        still_url: None,
+        provider: "anidb".to_string(),
+    })
+}
+
+/// Romanized main title
+fn main_title(titles: &[Title]) -> Option<&str> {
+    titles
+        .iter()
+        .find(|t| t.kind.as_deref() == Some("main"))
+        .or_else(|| titles.first())
+        .map(|t| t.value.as_str())
+}
+
+/// Official title in a language; episode titles have no type
+fn official_title<'a>(titles: &'a [Title], lang: &str) -> Option<&'a str> {
+    titles
+        .iter()
+        .find(|t| t.lang == lang && matches!(t.kind.as_deref(), None | Some("official")))
+        .map(|t| t.value.as_str())
+}
+
+/// First identifier of a resource type matching `valid`
+fn resource_id(anime: &Anime, kind: i32, valid: impl Fn(&str) -> bool) -> Option<String> {
+    anime
+        .resources
+        .items
+        .iter()
+        .filter(|r| r.kind == kind)
+        .flat_map(|r| &r.entities)
+        .flat_map(|e| &e.identifiers)
+        .find(|id| valid(id))
+        .cloned()
+}
+
+fn image_url(picture: &str) -> String {
+    format!("{ANIDB_IMAGE_URL}/{picture}")
+}
+
+/// First year of "2006-04-03"
+fn year_of(value: &str) -> Option<i32> {
+    value.get(..4).and_then(|y| y.parse().ok())
+}
+
+/// Description with AniDB's links reduced to their labels
+fn plain_text(description: &str) -> Option<String> {
+    let text = LINK.replace_all(description, "$1");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Lowercase words of a title, without punctuation
+fn normalize(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn entry_to_info(entry: &DumpEntry) -> MediaInfo {
+    let title = main_title(&entry.titles).unwrap_or_default().to_string();
+    let mut info = MediaInfo::new(entry.aid.to_string(), title, "anidb")
+        .with_type(MediaType::Anime)
+        .with_original_title(official_title(&entry.titles, "ja").map(ToString::to_string))
+        .with_external_ids(ExternalIds {
+            anidb: Some(entry.aid.to_string()),
+            ..Default::default()
+        });
+    for title in entry.titles.iter().filter(|t| t.kind.as_deref() == Some("official")) {
+        info = info.with_alt_title(title.value.clone());
+    }
+    info
+}
+
+#[async_trait]
+impl MetadataProvider for AniDbProvider {
+    fn id(&self) -> &'static str {
+        "anidb"
+    }
+
+    fn name(&self) -> &'static str {
+        "AniDB"
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &[MediaType::Anime]
+    }
+
+    fn requires_api_key(&self) -> bool {
+        true
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        match media_type {
+            MediaType::Anime => 70,
+            _ => 0,
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let dump = self.titles().await?;
+        let needle = normalize(query);
+
+        // Exact titles first, then prefixes, then any containing the query
+        let mut matches: Vec<(u8, &DumpEntry)> = dump
+            .anime
+            .iter()
+            .filter_map(|entry| {
+                let rank = entry
+                    .titles
+                    .iter()
+                    .filter_map(|t| {
+                        let title = normalize(&t.value);
+                        if title == needle {
+                            Some(3)
+                        } else if title.starts_with(&needle) {
+                            Some(2)
+                        } else {
+                            title.contains(&needle).then_some(1)
+                        }
+                    })
+                    .max()?;
+                Some((rank, entry))
+            })
+            .collect();
+        matches.sort_by_key(|(rank, _)| std::cmp::Reverse(*rank));
+
+        let mut results: Vec<MediaInfo> = matches
+            .into_iter()
+            .map(|(_, entry)| entry_to_info(entry))
+            .collect();
+
+        if needle.is_empty() || results.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+        let anime = self.anime(id).await?;
+        let mapping = self.try_episode_mapping(id).await;
+        Ok(self.anime_to_metadata(&anime, mapping.as_ref()))
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        let anime = self.anime(series_id).await?;
+        let mapping = self.try_episode_mapping(series_id).await;
+        let wanted = mapping
+            .as_ref()
+            .and_then(|m| m.from_tvdb(season, episode))
+            .unwrap_or((season, episode));
+
+        anime
+            .episodes
+            .items
+            .iter()
+            .find(|e| episode_number(&e.epno) == Some(wanted))
+            .and_then(|e| episode_info(e, None))
+            .map(|info| EpisodeInfo {
+                season,
+                episode,
+                ..info
+            })
+            .ok_or_else(|| {
+                ScraperError::NotFound(format!(
+                    "Episode S{season:02}E{episode:02} of AniDB anime {series_id}"
+                ))
+            })
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        if source == "anidb" {
+            let anime = match self.anime(external_id).await {
+                Ok(anime) => anime,
+                Err(ScraperError::NotFound(_)) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            let metadata = self.anime_to_metadata(&anime, None);
+
+            return Ok(Some(
+                MediaInfo::new(metadata.id, metadata.title, "anidb")
+                    .with_type(MediaType::Anime)
+                    .with_year(metadata.release_date.as_deref().and_then(year_of))
+                    .with_original_title(metadata.original_title)
+                    .with_poster(metadata.images.poster)
+                    .with_overview(metadata.overview)
+                    .with_rating(metadata.rating)
+                    .with_external_ids(metadata.external_ids),
+            ));
+        }
+
+        let id_of = |m: &EpisodeMapping| match source {
+            "tvdb" => m.tvdb_id.clone(),
+            "tmdb" => m.tmdb_id.clone(),
+            "imdb" => m.imdb_id.clone(),
+            _ => None,
+        };
+        let mappings = self.mappings().await?;
+
+        // A show maps to one entry per season; its first season stands for it
+        let Some(mapping) = mappings
+            .iter()
+            .filter(|m| id_of(m).as_deref() == Some(external_id))
+            .min_by_key(|m| m.default_season.unwrap_or(0))
+        else {
+            return Ok(None);
+        };
+
+        let external_ids = ExternalIds {
+            anidb: Some(mapping.anidb_id.clone()),
+            tvdb: mapping.tvdb_id.clone(),
+            tmdb: mapping.tmdb_id.clone(),
+            imdb: mapping.imdb_id.clone(),
+            ..Default::default()
+        };
+        let title = mapping.name.clone().unwrap_or_else(|| mapping.anidb_id.clone());
+
+        Ok(Some(
+            MediaInfo::new(mapping.anidb_id.clone(), title, "anidb")
+                .with_type(MediaType::Anime)
+                .with_external_ids(external_ids),
+        ))
+    }
+
+    /// Checks the daily title dump; the HTTP API bans clients for
+    /// needless requests, so the client name is only checked on lookups
+    async fn health(&self) -> ProviderHealth {
+        let started = std::time::Instant::now();
+        let result = self.titles().await;
+        ProviderHealth::probe(&result, started)
+    }
+}
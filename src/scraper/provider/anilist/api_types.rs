@@ -22,6 +22,34 @@ pub struct Page {
     pub media: Vec<Media>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ScheduleData {
+    #[serde(rename = "Page")]
+    pub page: SchedulePage,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SchedulePage {
+    #[serde(rename = "pageInfo")]
+    pub page_info: PageInfo,
+    #[serde(rename = "airingSchedules")]
+    pub airing_schedules: Vec<ScheduleEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageInfo {
+    #[serde(rename = "hasNextPage")]
+    pub has_next_page: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleEntry {
+    pub episode: i32,
+    #[serde(rename = "airingAt")]
+    pub airing_at: i64,
+    pub media: Option<Media>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct MediaData {
     #[serde(rename = "Media")]
@@ -59,6 +87,13 @@ pub struct Media {
     pub synonyms: Option<Vec<String>>,
     pub characters: Option<Characters>,
     pub staff: Option<Staff>,
+    pub relations: Option<Relations>,
+    #[serde(rename = "nextAiringEpisode")]
+    pub next_airing_episode: Option<AiringNode>,
+    #[serde(rename = "airingSchedule")]
+    pub airing_schedule: Option<AiringSchedule>,
+    #[serde(rename = "streamingEpisodes")]
+    pub streaming_episodes: Option<Vec<StreamingEpisode>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -166,3 +201,48 @@ pub struct StaffNode {
     pub name: CharacterName,
     pub image: Option<CharacterImage>,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct Relations {
+    pub edges: Vec<RelationEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelationEdge {
+    #[serde(rename = "relationType")]
+    pub relation_type: Option<String>,
+    pub node: RelatedNode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedNode {
+    pub id: i32,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub title: Title,
+    pub format: Option<String>,
+    #[serde(rename = "seasonYear")]
+    pub season_year: Option<i32>,
+    #[serde(rename = "coverImage")]
+    pub cover_image: Option<CoverImage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AiringSchedule {
+    pub nodes: Vec<AiringNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AiringNode {
+    pub episode: i32,
+    /// Unix timestamp
+    #[serde(rename = "airingAt")]
+    pub airing_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamingEpisode {
+    /// e.g. "Episode 3 - The Title", as listed by the streaming site
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+}
@@ -1,12 +1,31 @@
-use super::api_types::{GraphQLResponse, Media, SearchData, MediaData};
+use super::api_types::{
+    AiringNode, GraphQLResponse, Media, SearchData, MediaData, Relations, ScheduleData,
+    StreamingEpisode, Title,
+};
 use crate::scraper::{
     Result, ScraperError,
-    provider::{HttpClient, MetadataProvider, SearchOptions},
-    types::{EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType, PersonInfo},
+    provider::{HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions},
+    types::{
+        AiringEpisode, EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType,
+        PersonInfo, RelatedMedia,
+    },
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::de::IgnoredAny;
+use std::sync::LazyLock;
+use std::time::Instant;
 
 const ANILIST_API_URL: &str = "https://graphql.anilist.co";
+/// AniList allows 90 requests per minute
+const ANILIST_RATE_LIMIT: f64 = 1.5;
+/// Pages of airing schedules fetched for one calendar
+const SCHEDULE_MAX_PAGES: i32 = 10;
+
+/// "Episode 12 - Title" of streaming episodes
+static STREAMING_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^\s*episode\s+(\d+)\s*[-:\u{2013}]\s*(.*?)\s*$").unwrap());
 
 pub struct AniListProvider {
     client: HttpClient,
@@ -22,10 +41,16 @@ impl AniListProvider {
     #[must_use] 
     pub fn new() -> Self {
         Self {
-            client: HttpClient::new(ANILIST_API_URL),
+            client: HttpClient::new(ANILIST_API_URL).with_rate_limit(ANILIST_RATE_LIMIT),
         }
     }
 
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
     async fn query<T: serde::de::DeserializeOwned>(
         &self,
         query: &str,
@@ -53,12 +78,7 @@ impl AniListProvider {
     }
 
     fn media_to_info(&self, media: &Media) -> MediaInfo {
-        let title = media
-            .title
-            .english
-            .clone()
-            .or_else(|| media.title.romaji.clone())
-            .unwrap_or_default();
+        let title = display_title(&media.title);
 
         let mut info = MediaInfo::new(media.id.to_string(), title, "anilist")
             .with_type(MediaType::Anime)
@@ -66,7 +86,13 @@ impl AniListProvider {
             .with_original_title(media.title.native.clone())
             .with_overview(media.description.clone())
             .with_rating(media.average_score.map(|s| f64::from(s) / 10.0))
-            .with_popularity(media.popularity.map(f64::from));
+            .with_popularity(media.popularity.map(f64::from))
+            .with_format(media.format.clone())
+            .with_external_ids(ExternalIds {
+                anilist: Some(media.id.to_string()),
+                mal: media.id_mal.map(|id| id.to_string()),
+                ..Default::default()
+            });
 
         // Add poster
         if let Some(ref cover) = media.cover_image {
@@ -87,18 +113,28 @@ impl AniListProvider {
     }
 
     fn media_to_metadata(&self, media: Media) -> MediaMetadata {
-        let title = media
-            .title
-            .english
-            .clone()
-            .or_else(|| media.title.romaji.clone())
-            .unwrap_or_default();
+        let title = display_title(&media.title);
+
+        let related = media.relations.map(related_media).unwrap_or_default();
+        let id = media.id.to_string();
+        let next_airing = media
+            .next_airing_episode
+            .and_then(|a| airing_episode(&id, &title, a));
+        let mut airing_schedule: Vec<AiringEpisode> = media
+            .airing_schedule
+            .map(|s| s.nodes)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|a| airing_episode(&id, &title, a))
+            .collect();
+        airing_schedule.sort_by_key(|a| a.airs_at);
 
         let mut metadata = MediaMetadata {
             id: media.id.to_string(),
             title: title.clone(),
             original_title: media.title.native.clone(),
             sort_title: Some(title),
+            aliases: media.synonyms.clone().unwrap_or_default(),
             media_type: MediaType::Anime,
             tagline: None,
             overview: media.description.map(|d| {
@@ -151,6 +187,12 @@ impl AniListProvider {
             seasons: Vec::new(),
             cast: Vec::new(),
             crew: Vec::new(),
+            ratings: Vec::new(),
+            collection: None,
+            related,
+            next_airing,
+            airing_schedule,
+            videos: Vec::new(),
         };
 
         // Add characters as cast
@@ -204,6 +246,182 @@ impl AniListProvider {
 
         metadata
     }
+
+    /// Every episode of an entry, titled from its streaming episodes
+    ///
+    /// AniList has no episode list of its own: episodes are numbered up to the
+    /// entry's episode count (or the latest aired one), dated from the airing
+    /// schedule and titled "Episode N" when no streaming site lists them.
+    async fn episodes(&self, series_id: &str) -> Result<Vec<EpisodeInfo>> {
+        let gql_query = r"
+            query ($id: Int) {
+                Media(id: $id, type: ANIME) {
+                    id
+                    title { romaji english native }
+                    episodes
+                    duration
+                    nextAiringEpisode { episode airingAt }
+                    airingSchedule(perPage: 50) {
+                        nodes { episode airingAt }
+                    }
+                    streamingEpisodes { title thumbnail }
+                }
+            }
+        ";
+
+        let anime_id: i32 = series_id
+            .parse()
+            .map_err(|_| ScraperError::Parse(format!("Invalid AniList ID: {series_id}")))?;
+        let data: MediaData = self
+            .query(gql_query, serde_json::json!({ "id": anime_id }))
+            .await?;
+        let media = data.media;
+
+        let streaming = streaming_titles(media.streaming_episodes.unwrap_or_default());
+        let schedule = media.airing_schedule.map(|s| s.nodes).unwrap_or_default();
+        // Running shows list no count, but have aired up to the next episode
+        let count = media
+            .episodes
+            .or_else(|| media.next_airing_episode.map(|a| a.episode - 1))
+            .unwrap_or(0)
+            .max(streaming.iter().map(|(number, ..)| *number).max().unwrap_or(0));
+
+        Ok((1..=count)
+            .map(|number| {
+                let (title, still_url) = streaming
+                    .iter()
+                    .find(|(n, ..)| *n == number)
+                    .map(|(_, title, thumbnail)| (title.clone(), thumbnail.clone()))
+                    .unwrap_or_else(|| (format!("Episode {number}"), None));
+                let air_date = schedule
+                    .iter()
+                    .find(|a| a.episode == number)
+                    .and_then(|a| DateTime::from_timestamp(a.airing_at, 0))
+                    .map(|date| date.format("%Y-%m-%d").to_string());
+
+                EpisodeInfo {
+                    id: format!("{series_id}-{number}"),
+                    title,
+                    season: 1,
+                    episode: number,
+                    absolute_number: Some(number),
+                    original_title: None,
+                    air_date,
+                    overview: None,
+                    runtime: media.duration,
+                    rating: None,
+                    still_url,
+                    provider: "anilist".to_string(),
+                }
+            })
+            .collect())
+    }
+
+    /// Episodes airing between `from` and `to`, in airing order, for a
+    /// calendar of upcoming episodes
+    pub async fn airing_schedule(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<AiringEpisode>> {
+        let gql_query = r"
+            query ($page: Int, $from: Int, $to: Int) {
+                Page(page: $page, perPage: 50) {
+                    pageInfo { hasNextPage }
+                    airingSchedules(airingAt_greater: $from, airingAt_lesser: $to, sort: TIME) {
+                        episode
+                        airingAt
+                        media { id title { romaji english native } }
+                    }
+                }
+            }
+        ";
+
+        let mut episodes = Vec::new();
+        for page in 1..=SCHEDULE_MAX_PAGES {
+            let variables = serde_json::json!({
+                "page": page,
+                "from": from.timestamp(),
+                "to": to.timestamp()
+            });
+            let data: ScheduleData = self.query(gql_query, variables).await?;
+
+            episodes.extend(data.page.airing_schedules.into_iter().filter_map(|entry| {
+                let media = entry.media?;
+                let airing = AiringNode {
+                    episode: entry.episode,
+                    airing_at: entry.airing_at,
+                };
+                airing_episode(&media.id.to_string(), &display_title(&media.title), airing)
+            }));
+
+            if data.page.page_info.has_next_page != Some(true) {
+                break;
+            }
+        }
+
+        Ok(episodes)
+    }
+}
+
+/// English title, falling back to romaji
+fn display_title(title: &Title) -> String {
+    title
+        .english
+        .clone()
+        .or_else(|| title.romaji.clone())
+        .unwrap_or_default()
+}
+
+fn airing_episode(
+    series_id: &str,
+    series_title: &str,
+    airing: AiringNode,
+) -> Option<AiringEpisode> {
+    Some(AiringEpisode {
+        series_id: series_id.to_string(),
+        series_title: series_title.to_string(),
+        episode: airing.episode,
+        airs_at: DateTime::from_timestamp(airing.airing_at, 0)?,
+        provider: "anilist".to_string(),
+    })
+}
+
+/// Number, title and thumbnail of streaming episodes titled "Episode N - Title"
+fn streaming_titles(episodes: Vec<StreamingEpisode>) -> Vec<(i32, String, Option<String>)> {
+    episodes
+        .into_iter()
+        .filter_map(|e| {
+            let caps = STREAMING_TITLE.captures(e.title.as_deref()?)?;
+            let number = caps[1].parse().ok()?;
+            let title = caps[2].to_string();
+            let title = if title.is_empty() { format!("Episode {number}") } else { title };
+            Some((number, title, e.thumbnail))
+        })
+        .collect()
+}
+
+/// Related anime; manga, novels and other sources are left out
+fn related_media(relations: Relations) -> Vec<RelatedMedia> {
+    relations
+        .edges
+        .into_iter()
+        .filter(|e| e.node.kind.as_deref() == Some("ANIME"))
+        .map(|e| RelatedMedia {
+            id: e.node.id.to_string(),
+            title: display_title(&e.node.title),
+            relation: e
+                .relation_type
+                .map_or_else(|| "other".to_string(), |r| r.to_ascii_lowercase()),
+            media_type: MediaType::Anime,
+            format: e.node.format,
+            year: e.node.season_year,
+            poster_url: e
+                .node
+                .cover_image
+                .and_then(|c| c.extra_large.or(c.large)),
+        })
+        .collect()
 }
 
 #[async_trait]
@@ -318,6 +536,20 @@ impl MetadataProvider for AniListProvider {
                             role
                         }
                     }
+                    nextAiringEpisode { episode airingAt }
+                    airingSchedule(notYetAired: true, perPage: 25) {
+                        nodes { episode airingAt }
+                    }
+                    relations {
+                        edges {
+                            relationType
+                            node {
+                                id type format seasonYear
+                                title { romaji english native }
+                                coverImage { large extraLarge }
+                            }
+                        }
+                    }
                 }
             }
         ";
@@ -335,13 +567,29 @@ impl MetadataProvider for AniListProvider {
 
     async fn get_episode(
         &self,
-        _series_id: &str,
-        _season: i32,
-        _episode: i32,
+        series_id: &str,
+        season: i32,
+        episode: i32,
     ) -> Result<EpisodeInfo> {
-        Err(ScraperError::NotFound(
-            "AniList does not provide individual episode details".to_string(),
-        ))
+        // Every season is an entry of its own, numbered from 1
+        let mut episode_info = self
+            .episodes(series_id)
+            .await?
+            .into_iter()
+            .find(|e| e.episode == episode)
+            .ok_or_else(|| {
+                ScraperError::NotFound(format!("Episode {episode} not found in {series_id}"))
+            })?;
+        episode_info.season = season;
+        Ok(episode_info)
+    }
+
+    async fn get_season(&self, series_id: &str, season: i32) -> Result<Vec<EpisodeInfo>> {
+        let mut episodes = self.episodes(series_id).await?;
+        for episode in &mut episodes {
+            episode.season = season;
+        }
+        Ok(episodes)
     }
 
     async fn find_by_external_id(
@@ -379,4 +627,40 @@ impl MetadataProvider for AniListProvider {
 
         Ok(Some(self.media_to_info(&data.media)))
     }
+
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        let result = self
+            .query::<IgnoredAny>("query { GenreCollection }", serde_json::json!({}))
+            .await;
+        ProviderHealth::probe(&result, started).with_quota(self.client.quota())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_titles() {
+        let episode = |title: &str| StreamingEpisode {
+            title: Some(title.to_string()),
+            thumbnail: None,
+        };
+        let titles = streaming_titles(vec![
+            episode("Episode 2 - The Melancholy of Haruhi Suzumiya II"),
+            episode("episode 10: Someday in the Rain"),
+            episode("Episode 11 -"),
+            episode("Special - Bonus"),
+        ]);
+
+        assert_eq!(
+            titles,
+            vec![
+                (2, "The Melancholy of Haruhi Suzumiya II".to_string(), None),
+                (10, "Someday in the Rain".to_string(), None),
+                (11, "Episode 11".to_string(), None),
+            ]
+        );
+    }
 }
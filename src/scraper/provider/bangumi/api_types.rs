@@ -83,6 +83,39 @@ pub struct EpisodesResponse {
     pub total: i32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RelatedCharacter {
+    pub id: i32,
+    pub name: String,
+    /// "主角" (main), "配角" (supporting) or "客串" (guest)
+    pub relation: Option<String>,
+    pub images: Option<Images>,
+    #[serde(default)]
+    pub actors: Vec<RelatedActor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedActor {
+    pub id: i32,
+    pub name: String,
+    pub images: Option<Images>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedPerson {
+    pub id: i32,
+    pub name: String,
+    /// Job on the subject, e.g. "导演"
+    pub relation: Option<String>,
+    /// 1 for individuals, 2 for companies, 3 for groups
+    #[serde(rename = "type")]
+    pub person_type: i32,
+    pub images: Option<Images>,
+}
+
 // Subject types
 pub const SUBJECT_TYPE_ANIME: i32 = 2;
 pub const SUBJECT_TYPE_MOVIE: i32 = 6;
+
+// Person types
+pub const PERSON_TYPE_INDIVIDUAL: i32 = 1;
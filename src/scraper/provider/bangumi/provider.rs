@@ -1,17 +1,28 @@
 use super::api_types::{
-    EpisodesResponse, InfoBoxValue, SUBJECT_TYPE_ANIME, SUBJECT_TYPE_MOVIE, SearchResponse, Subject,
+    EpisodesResponse, Images, InfoBoxValue, PERSON_TYPE_INDIVIDUAL, RelatedCharacter,
+    RelatedPerson, SUBJECT_TYPE_ANIME, SUBJECT_TYPE_MOVIE, SearchResponse, Subject,
 };
 use crate::scraper::{
     Result, ScraperError,
-    provider::{HttpClient, MetadataProvider, SearchOptions},
-    types::{EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType},
+    provider::{
+        language_matches, HttpClient, MetadataProvider, NetworkOptions, ProviderHealth,
+        SearchOptions,
+    },
+    types::{EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType, PersonInfo},
 };
 use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use std::time::Instant;
+use tracing::debug;
 
 const BANGUMI_API_URL: &str = "https://api.bgm.tv";
+/// Bangumi publishes no hard limit; stay polite
+const BANGUMI_RATE_LIMIT: f64 = 5.0;
 
 pub struct BangumiProvider {
     client: HttpClient,
+    /// Preferred languages for titles, most preferred first
+    languages: Vec<String>,
 }
 
 impl Default for BangumiProvider {
@@ -24,15 +35,44 @@ impl BangumiProvider {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            client: HttpClient::new(BANGUMI_API_URL),
+            client: HttpClient::new(BANGUMI_API_URL).with_rate_limit(BANGUMI_RATE_LIMIT),
+            languages: Vec::new(),
         }
     }
 
+    /// Prefer Chinese (`zh-CN`) or Japanese (`ja`) titles, whichever comes
+    /// first in `languages`; Chinese ones are preferred by default
+    #[must_use]
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Chinese or original title, in the preferred language where it has one
+    fn title(&self, name: Option<&str>, name_cn: Option<&str>) -> Option<String> {
+        let name = name.filter(|n| !n.is_empty());
+        let name_cn = name_cn.filter(|n| !n.is_empty());
+        let preferred = self.languages.iter().find_map(|tag| {
+            if language_matches(tag, "zh", Some("CN")) {
+                name_cn
+            } else if language_matches(tag, "ja", Some("JP")) {
+                name
+            } else {
+                None
+            }
+        });
+        preferred.or(name_cn).or(name).map(str::to_string)
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
     fn subject_to_info(&self, subject: &Subject) -> MediaInfo {
-        let title = subject
-            .name_cn
-            .clone()
-            .filter(|s| !s.is_empty())
+        let title = self
+            .title(Some(&subject.name), subject.name_cn.as_deref())
             .unwrap_or_else(|| subject.name.clone());
 
         let year = subject
@@ -56,13 +96,15 @@ impl BangumiProvider {
             .with_poster(poster)
             .with_overview(subject.summary.clone())
             .with_rating(rating)
+            .with_external_ids(ExternalIds {
+                bangumi: Some(subject.id.to_string()),
+                ..Default::default()
+            })
     }
 
     fn subject_to_metadata(&self, subject: Subject) -> MediaMetadata {
-        let title = subject
-            .name_cn
-            .clone()
-            .filter(|s| !s.is_empty())
+        let title = self
+            .title(Some(&subject.name), subject.name_cn.as_deref())
             .unwrap_or_else(|| subject.name.clone());
 
         let release_date = subject.date.clone().or_else(|| subject.air_date.clone());
@@ -111,6 +153,7 @@ impl BangumiProvider {
             title: title.clone(),
             original_title: Some(subject.name),
             sort_title: Some(title),
+            aliases: Vec::new(),
             media_type: MediaType::Anime,
             tagline: None,
             overview: subject.summary,
@@ -148,7 +191,7 @@ impl BangumiProvider {
             seasons: Vec::new(),
             cast: Vec::new(),
             crew: if let Some(dir) = director {
-                vec![crate::scraper::types::PersonInfo {
+                vec![PersonInfo {
                     id: String::new(),
                     name: dir,
                     role: Some("Director".to_string()),
@@ -158,9 +201,69 @@ impl BangumiProvider {
             } else {
                 Vec::new()
             },
+            ratings: Vec::new(),
+            collection: None,
+            related: Vec::new(),
+            next_airing: None,
+            airing_schedule: Vec::new(),
+            videos: Vec::new(),
         }
     }
 
+    /// Main and supporting characters, named after their voice actors
+    async fn cast(&self, id: &str) -> Result<Vec<PersonInfo>> {
+        let endpoint = format!("/v0/subjects/{id}/characters");
+        let characters: Vec<RelatedCharacter> = self.client.get(&endpoint).await?;
+
+        Ok(characters
+            .into_iter()
+            .filter(|c| matches!(c.relation.as_deref(), Some("主角" | "配角")))
+            .take(20)
+            .enumerate()
+            .map(|(order, character)| {
+                let actor = character.actors.into_iter().next();
+                PersonInfo {
+                    id: actor.as_ref().map_or(character.id, |a| a.id).to_string(),
+                    name: actor.as_ref().map_or_else(|| character.name.clone(), |a| a.name.clone()),
+                    role: Some(character.name),
+                    image_url: actor
+                        .and_then(|a| image(a.images))
+                        .or_else(|| image(character.images)),
+                    order: Some(order as i32),
+                }
+            })
+            .collect())
+    }
+
+    /// Key staff, with their jobs in English like the other providers
+    async fn crew(&self, id: &str) -> Result<Vec<PersonInfo>> {
+        let endpoint = format!("/v0/subjects/{id}/persons");
+        let persons: Vec<RelatedPerson> = self.client.get(&endpoint).await?;
+
+        Ok(persons
+            .into_iter()
+            .filter(|p| p.person_type == PERSON_TYPE_INDIVIDUAL)
+            .filter_map(|person| {
+                let role = match person.relation.as_deref()? {
+                    "导演" => "Director",
+                    "原作" => "Original Creator",
+                    "系列构成" => "Series Composition",
+                    "脚本" => "Writer",
+                    "人物设定" => "Character Design",
+                    "音乐" => "Music",
+                    _ => return None,
+                };
+                Some(PersonInfo {
+                    id: person.id.to_string(),
+                    name: person.name,
+                    role: Some(role.to_string()),
+                    image_url: image(person.images),
+                    order: None,
+                })
+            })
+            .collect())
+    }
+
     fn parse_duration(&self, duration: Option<&str>) -> Option<i32> {
         duration.and_then(|d| {
             // Parse formats like "24:00" or "24分"
@@ -181,6 +284,11 @@ impl BangumiProvider {
     }
 }
 
+/// Large image, falling back to the medium one
+fn image(images: Option<Images>) -> Option<String> {
+    images.and_then(|i| i.large.or(i.medium)).filter(|url| !url.is_empty())
+}
+
 #[async_trait]
 impl MetadataProvider for BangumiProvider {
     fn id(&self) -> &'static str {
@@ -254,8 +362,21 @@ impl MetadataProvider for BangumiProvider {
     async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
         let endpoint = format!("/v0/subjects/{id}");
         let subject: Subject = self.client.get(&endpoint).await?;
+        let mut metadata = self.subject_to_metadata(subject);
 
-        Ok(self.subject_to_metadata(subject))
+        // Credits are extras: without them the director of the infobox stays
+        let (cast, crew) = futures::future::join(self.cast(id), self.crew(id)).await;
+        match cast {
+            Ok(cast) => metadata.cast = cast,
+            Err(e) => debug!("Failed to fetch Bangumi characters of {id}: {e}"),
+        }
+        match crew {
+            Ok(crew) if !crew.is_empty() => metadata.crew = crew,
+            Ok(_) => {}
+            Err(e) => debug!("Failed to fetch Bangumi persons of {id}: {e}"),
+        }
+
+        Ok(metadata)
     }
 
     async fn get_episode(
@@ -273,11 +394,8 @@ impl MetadataProvider for BangumiProvider {
             .find(|e| e.ep.map(|n| n as i32) == Some(episode) || e.sort as i32 == episode)
             .ok_or_else(|| ScraperError::NotFound(format!("Episode {episode} not found")))?;
 
-        let title = ep
-            .name_cn
-            .clone()
-            .filter(|s| !s.is_empty())
-            .or(ep.name.clone())
+        let title = self
+            .title(ep.name.as_deref(), ep.name_cn.as_deref())
             .unwrap_or_else(|| format!("Episode {episode}"));
 
         Ok(EpisodeInfo {
@@ -286,6 +404,7 @@ impl MetadataProvider for BangumiProvider {
             season: 1,
             episode: ep.ep.map_or(ep.sort as i32, |n| n as i32),
             absolute_number: Some(ep.sort as i32),
+            original_title: ep.name.filter(|name| !name.is_empty()),
             air_date: ep.airdate,
             overview: ep.desc,
             runtime: self.parse_duration(ep.duration.as_deref()),
@@ -294,4 +413,11 @@ impl MetadataProvider for BangumiProvider {
             provider: "bangumi".to_string(),
         })
     }
+
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        // Any subject will do; a 404 still proves the API answers
+        let result = self.client.get::<IgnoredAny>("/v0/subjects/1").await;
+        ProviderHealth::probe(&result, started).with_quota(self.client.quota())
+    }
 }
@@ -0,0 +1,60 @@
+use serde::Deserialize;
+
+// Search suggestions (`/j/subject_suggest`)
+#[derive(Debug, Deserialize)]
+pub struct Suggestion {
+    pub id: String,
+    /// Chinese title
+    pub title: String,
+    /// Original title
+    pub sub_title: Option<String>,
+    pub year: Option<String>,
+    pub img: Option<String>,
+    /// Episode count, empty for movies
+    #[serde(default)]
+    pub episode: String,
+    /// "movie" for films and series alike, "celebrity" for people
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+// Structured data embedded in subject pages
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subject {
+    /// "Movie" or "TVSeries"
+    #[serde(rename = "@type")]
+    pub kind: String,
+    /// Chinese and original title, e.g. "肖申克的救赎 The Shawshank Redemption"
+    pub name: String,
+    pub image: Option<String>,
+    #[serde(default)]
+    pub director: Vec<Person>,
+    #[serde(default)]
+    pub author: Vec<Person>,
+    #[serde(default)]
+    pub actor: Vec<Person>,
+    pub date_published: Option<String>,
+    #[serde(default)]
+    pub genre: Vec<String>,
+    /// ISO 8601, e.g. "PT2H22M"
+    pub duration: Option<String>,
+    pub description: Option<String>,
+    pub aggregate_rating: Option<AggregateRating>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Person {
+    pub name: String,
+    /// e.g. "/celebrity/1047973/"
+    #[serde(default)]
+    pub url: String,
+}
+
+/// Empty strings for subjects with too few ratings
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateRating {
+    pub rating_count: String,
+    pub rating_value: String,
+}
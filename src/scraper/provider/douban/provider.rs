@@ -0,0 +1,407 @@
+use super::api_types::{Person, Subject, Suggestion};
+use crate::scraper::{
+    parser::TitleArticles,
+    provider::{
+        plain_text, HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions,
+    },
+    types::{
+        EpisodeInfo, ExternalIds, ExternalRating, ImageSet, MediaInfo, MediaMetadata, MediaType,
+        PersonInfo,
+    },
+    Result, ScraperError,
+};
+use async_trait::async_trait;
+use regex::Regex;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+const DOUBAN_BASE_URL: &str = "https://movie.douban.com";
+/// Douban blocks addresses that crawl quickly
+const DOUBAN_RATE_LIMIT: f64 = 1.0;
+/// Douban turns away clients that don't look like a browser
+const DOUBAN_USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 ",
+    "(KHTML, like Gecko) Chrome/120.0 Safari/537.36"
+);
+
+static PAGE_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<title>\s*(.*?)\s*\(豆瓣\)\s*</title>").unwrap());
+static STRUCTURED_DATA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<script type="application/ld\+json">(.*?)</script>"#).unwrap()
+});
+/// Full summary, shown after "展开全部" on long ones
+static FULL_SUMMARY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)<span class="all hidden">(.*?)</span>"#).unwrap());
+static SUMMARY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)<span property="v:summary"[^>]*>(.*?)</span>"#).unwrap());
+/// Labelled fields of the info box, e.g. `<span class="pl">语言:</span> 英语<br/>`
+static INFO_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<span class="pl">([^<:]+):?</span>:?\s*([^<]*)"#).unwrap());
+
+/// Provider for Douban, covering Chinese titles and summaries of films and series
+pub struct DoubanProvider {
+    client: HttpClient,
+}
+
+impl DoubanProvider {
+    pub fn new() -> Self {
+        Self {
+            client: HttpClient::new(DOUBAN_BASE_URL)
+                .with_user_agent(DOUBAN_USER_AGENT)
+                .with_rate_limit(DOUBAN_RATE_LIMIT),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    async fn suggest(&self, query: &str) -> Result<Vec<Suggestion>> {
+        let suggestions: Vec<Suggestion> = self
+            .client
+            .get_with_params("/j/subject_suggest", &[("q", query)])
+            .await?;
+
+        // Books, people and music share the suggestion box
+        Ok(suggestions.into_iter().filter(|s| s.kind == "movie").collect())
+    }
+
+    async fn subject_page(&self, id: &str) -> Result<String> {
+        if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ScraperError::Parse(format!("Invalid Douban ID: {id}")));
+        }
+
+        match self.client.get_bytes(&format!("/subject/{id}/"), &[]).await {
+            Ok(body) => Ok(String::from_utf8_lossy(&body).into_owned()),
+            Err(ScraperError::Api { status: 404, .. }) => Err(ScraperError::NotFound(format!(
+                "Douban subject {id}"
+            ))),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn page_to_metadata(&self, id: &str, page: &str) -> Result<MediaMetadata> {
+        let data = STRUCTURED_DATA
+            .captures(page)
+            .map(|c| c[1].to_string())
+            .ok_or_else(|| ScraperError::Parse(format!("No subject data on Douban page {id}")))?;
+        // Summaries may hold raw line breaks, which JSON doesn't allow in strings
+        let data = data.replace(['\n', '\r', '\t'], " ");
+        let subject: Subject = serde_json::from_str(&data)
+            .map_err(|e| ScraperError::Parse(format!("JSON parse error: {e}")))?;
+
+        let title = PAGE_TITLE
+            .captures(page)
+            .map(|c| c[1].trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| subject.name.clone());
+        let original_title = subject
+            .name
+            .strip_prefix(title.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(ToString::to_string);
+        let release_date = subject.date_published.filter(|d| !d.is_empty());
+        let year = release_date.as_deref().and_then(year_of);
+        let info = |label: &str| info_field(page, label);
+
+        let (rating, vote_count) = subject
+            .aggregate_rating
+            .map(|r| (r.rating_value.parse().ok(), r.rating_count.parse().ok()))
+            .unwrap_or_default();
+        let media_type = if subject.kind == "TVSeries" {
+            MediaType::Tv
+        } else {
+            MediaType::Movie
+        };
+
+        let cast = subject
+            .actor
+            .iter()
+            .enumerate()
+            .map(|(order, person)| person_info(person, None, Some(order as i32)))
+            .collect();
+        let crew = subject
+            .director
+            .iter()
+            .map(|person| person_info(person, Some("Director"), None))
+            .chain(
+                subject
+                    .author
+                    .iter()
+                    .map(|person| person_info(person, Some("Writer"), None)),
+            )
+            .collect();
+
+        Ok(MediaMetadata {
+            id: id.to_string(),
+            sort_title: Some(Self::generate_sort_title(&title, year)),
+            original_title,
+            media_type,
+            overview: FULL_SUMMARY
+                .captures(page)
+                .or_else(|| SUMMARY.captures(page))
+                .and_then(|c| summary(&c[1]))
+                .or_else(|| subject.description.as_deref().and_then(plain_text)),
+            release_date,
+            runtime: subject
+                .duration
+                .as_deref()
+                .and_then(minutes)
+                .or_else(|| info("单集片长").as_deref().and_then(leading_number)),
+            rating,
+            vote_count,
+            genres: subject.genre,
+            language: info("语言").as_deref().and_then(language_code),
+            images: ImageSet {
+                poster: subject
+                    .image
+                    .map(|url| url.replace("/s_ratio_poster/", "/l_ratio_poster/")),
+                ..Default::default()
+            },
+            external_ids: ExternalIds {
+                imdb: info("IMDb").filter(|id| id.starts_with("tt")),
+                douban: Some(id.to_string()),
+                ..Default::default()
+            },
+            provider: "douban".to_string(),
+            episode_count: info("集数").as_deref().and_then(leading_number),
+            cast,
+            crew,
+            ratings: rating
+                .map(|score| ExternalRating {
+                    source: "douban".to_string(),
+                    score,
+                    votes: vote_count,
+                })
+                .into_iter()
+                .collect(),
+            title,
+            ..Default::default()
+        })
+    }
+
+    fn suggestion_to_info(suggestion: Suggestion) -> MediaInfo {
+        let media_type = if suggestion.episode.is_empty() {
+            MediaType::Movie
+        } else {
+            MediaType::Tv
+        };
+        let external_ids = ExternalIds {
+            douban: Some(suggestion.id.clone()),
+            ..Default::default()
+        };
+
+        MediaInfo::new(suggestion.id, suggestion.title, "douban")
+            .with_type(media_type)
+            .with_year(suggestion.year.as_deref().and_then(year_of))
+            .with_original_title(suggestion.sub_title.filter(|t| !t.is_empty()))
+            .with_poster(suggestion.img)
+            .with_external_ids(external_ids)
+    }
+
+    fn generate_sort_title(title: &str, year: Option<i32>) -> String {
+        let articles = TitleArticles::active();
+        let sort_title = articles.strip(title);
+
+        if let Some(year) = year {
+            format!("{sort_title} ({year})")
+        } else {
+            sort_title.to_string()
+        }
+    }
+}
+
+impl Default for DoubanProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Value of a labelled info box field, e.g. "tt0111161" for "IMDb"
+fn info_field(page: &str, label: &str) -> Option<String> {
+    INFO_FIELD
+        .captures_iter(page)
+        .find(|c| c[1].trim() == label)
+        .map(|c| c[2].trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Summary paragraphs, without the full-width indents Douban adds
+fn summary(html: &str) -> Option<String> {
+    let text = plain_text(html)?;
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn person_info(person: &Person, job: Option<&str>, order: Option<i32>) -> PersonInfo {
+    PersonInfo {
+        id: person
+            .url
+            .trim_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        name: person.name.clone(),
+        role: job.map(ToString::to_string),
+        image_url: None,
+        order,
+    }
+}
+
+/// Code of the first of "英语 / 法语"
+fn language_code(languages: &str) -> Option<String> {
+    let code = match languages.split('/').next()?.trim() {
+        "汉语普通话" | "普通话" => "zh",
+        "粤语" => "yue",
+        "英语" => "en",
+        "日语" => "ja",
+        "韩语" => "ko",
+        "法语" => "fr",
+        "德语" => "de",
+        "西班牙语" => "es",
+        _ => return None,
+    };
+    Some(code.to_string())
+}
+
+/// Minutes of "PT2H22M"
+fn minutes(duration: &str) -> Option<i32> {
+    let time = duration.strip_prefix("PT")?;
+    let (hours, rest) = time.split_once('H').unwrap_or(("0", time));
+    let minutes = rest.strip_suffix('M').filter(|m| !m.is_empty()).unwrap_or("0");
+    let total = hours.parse::<i32>().ok()? * 60 + minutes.parse::<i32>().ok()?;
+    (total > 0).then_some(total)
+}
+
+/// Number at the start of "45分钟" or "20"
+fn leading_number(value: &str) -> Option<i32> {
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// First year of "1994-09-10"
+fn year_of(value: &str) -> Option<i32> {
+    value.get(..4).and_then(|y| y.parse().ok())
+}
+
+#[async_trait]
+impl MetadataProvider for DoubanProvider {
+    fn id(&self) -> &'static str {
+        "douban"
+    }
+
+    fn name(&self) -> &'static str {
+        "Douban"
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &[MediaType::Movie, MediaType::Tv]
+    }
+
+    fn requires_api_key(&self) -> bool {
+        false
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        match media_type {
+            MediaType::Movie | MediaType::Tv => 30,
+            MediaType::Anime => 10, // Bangumi covers anime in Chinese
+            MediaType::Unknown => 0,
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let mut results: Vec<MediaInfo> = self
+            .suggest(query)
+            .await?
+            .into_iter()
+            .map(Self::suggestion_to_info)
+            .filter(|info| match options.media_type {
+                Some(MediaType::Movie) => info.media_type == MediaType::Movie,
+                Some(MediaType::Tv) => info.media_type == MediaType::Tv,
+                _ => true,
+            })
+            .filter(|info| options.year.is_none_or(|year| info.year == Some(year)))
+            .collect();
+
+        if results.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+        let page = self.subject_page(id).await?;
+        self.page_to_metadata(id, &page)
+    }
+
+    async fn get_episode(
+        &self,
+        _series_id: &str,
+        _season: i32,
+        _episode: i32,
+    ) -> Result<EpisodeInfo> {
+        Err(ScraperError::NotFound(
+            "Douban does not provide individual episode details".to_string(),
+        ))
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        match source {
+            "douban" => match self.get_metadata(external_id, MediaType::Unknown).await {
+                Ok(metadata) => Ok(Some(
+                    MediaInfo::new(metadata.id, metadata.title, "douban")
+                        .with_type(metadata.media_type)
+                        .with_year(metadata.release_date.as_deref().and_then(year_of))
+                        .with_original_title(metadata.original_title)
+                        .with_poster(metadata.images.poster)
+                        .with_overview(metadata.overview)
+                        .with_rating(metadata.rating)
+                        .with_external_ids(metadata.external_ids),
+                )),
+                Err(ScraperError::NotFound(_)) => Ok(None),
+                Err(e) => Err(e),
+            },
+            // Douban's search understands IMDb IDs
+            "imdb" => Ok(self
+                .suggest(external_id)
+                .await?
+                .into_iter()
+                .next()
+                .map(|s| {
+                    let mut info = Self::suggestion_to_info(s);
+                    info.external_ids.imdb = Some(external_id.to_string());
+                    info
+                })),
+            _ => Ok(None),
+        }
+    }
+
+    /// Douban turns away clients it takes for bots with a 403
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        let result = self.client.get_bytes("/", &[]).await;
+        ProviderHealth::probe(&result, started)
+    }
+}
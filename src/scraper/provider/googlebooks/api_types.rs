@@ -0,0 +1,66 @@
+use serde::Deserialize;
+
+// Volume search response
+#[derive(Debug, Deserialize)]
+pub struct VolumeList {
+    /// Missing when nothing matched
+    #[serde(default)]
+    pub items: Vec<Volume>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Volume {
+    pub id: String,
+    #[serde(rename = "volumeInfo")]
+    pub info: VolumeInfo,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeInfo {
+    pub title: String,
+    pub subtitle: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    pub publisher: Option<String>,
+    /// "YYYY", "YYYY-MM" or "YYYY-MM-DD"
+    pub published_date: Option<String>,
+    /// HTML
+    pub description: Option<String>,
+    #[serde(default)]
+    pub industry_identifiers: Vec<IndustryIdentifier>,
+    pub page_count: Option<i32>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    pub average_rating: Option<f64>,
+    pub language: Option<String>,
+    pub image_links: Option<ImageLinks>,
+    pub series_info: Option<SeriesInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IndustryIdentifier {
+    /// "ISBN_10", "ISBN_13" or "OTHER"
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub identifier: String,
+}
+
+/// Searches only return the thumbnails, volume lookups the larger sizes too
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageLinks {
+    pub extra_large: Option<String>,
+    pub large: Option<String>,
+    pub medium: Option<String>,
+    pub small: Option<String>,
+    pub thumbnail: Option<String>,
+    pub small_thumbnail: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesInfo {
+    /// Position in the series, e.g. "1"
+    pub book_display_number: Option<String>,
+}
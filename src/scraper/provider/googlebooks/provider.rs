@@ -0,0 +1,179 @@
+use super::api_types::{ImageLinks, Volume, VolumeInfo, VolumeList};
+use crate::scraper::{
+    provider::{plain_text, HttpClient, NetworkOptions},
+    types::{normalize_isbn, BookInfo, BookMetadata},
+    Result, ScraperError,
+};
+
+const GOOGLE_BOOKS_BASE_URL: &str = "https://www.googleapis.com/books/v1";
+/// Google Books allows 100 requests per minute per user
+const GOOGLE_BOOKS_RATE_LIMIT: f64 = 1.5;
+
+/// Provider for Google Books, whose catalogue is wider than Open Library's
+///
+/// Works without an API key, with a small shared daily quota.
+pub struct GoogleBooksProvider {
+    client: HttpClient,
+    api_key: Option<String>,
+}
+
+impl GoogleBooksProvider {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: HttpClient::new(GOOGLE_BOOKS_BASE_URL).with_rate_limit(GOOGLE_BOOKS_RATE_LIMIT),
+            api_key: api_key.filter(|k| !k.is_empty()),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    /// GET request with the API key, with a 404 reported as not found
+    async fn request<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        let mut params = params.to_vec();
+        if let Some(key) = &self.api_key {
+            params.push(("key", key));
+        }
+
+        self.client.get_json_or_not_found(endpoint, &params).await
+    }
+
+    async fn volumes(&self, query: &str, limit: usize) -> Result<Vec<Volume>> {
+        let limit_str = limit.clamp(1, 40).to_string();
+        let list: VolumeList = self
+            .request(
+                "/volumes",
+                &[("q", query), ("maxResults", &limit_str), ("printType", "books")],
+            )
+            .await?;
+        Ok(list.items)
+    }
+
+    /// Search books by title, optionally narrowed to an author
+    pub async fn search(
+        &self,
+        title: &str,
+        author: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BookInfo>> {
+        let mut query = format!("intitle:{}", quoted(title));
+        if let Some(author) = author.filter(|a| !a.trim().is_empty()) {
+            query.push_str(&format!(" inauthor:{}", quoted(author)));
+        }
+
+        let volumes = self.volumes(&query, limit).await?;
+        if volumes.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {title}"
+            )));
+        }
+
+        Ok(volumes.into_iter().map(volume_to_info).collect())
+    }
+
+    /// Metadata of a volume, e.g. "zyTCAlFPjgYC"
+    pub async fn get_book(&self, volume_id: &str) -> Result<BookMetadata> {
+        let volume: Volume = self.request(&format!("/volumes/{volume_id}"), &[]).await?;
+        Ok(volume_to_metadata(volume))
+    }
+
+    /// Metadata of the volume with an ISBN
+    pub async fn find_by_isbn(&self, isbn: &str) -> Result<Option<BookMetadata>> {
+        let Some(isbn) = normalize_isbn(isbn) else {
+            return Ok(None);
+        };
+
+        let Some(volume) = self.volumes(&format!("isbn:{isbn}"), 1).await?.into_iter().next()
+        else {
+            return Ok(None);
+        };
+        // Search results carry thumbnails only
+        Ok(Some(self.get_book(&volume.id).await?))
+    }
+}
+
+fn volume_to_info(volume: Volume) -> BookInfo {
+    let info = volume.info;
+    BookInfo {
+        id: volume.id,
+        year: info
+            .published_date
+            .as_deref()
+            .and_then(|d| d.get(..4))
+            .and_then(|y| y.parse().ok()),
+        isbn: isbn_of(&info),
+        cover_url: info.image_links.and_then(largest_image),
+        title: info.title,
+        authors: info.authors,
+        provider: "googlebooks".to_string(),
+    }
+}
+
+fn volume_to_metadata(volume: Volume) -> BookMetadata {
+    let info = volume.info;
+    BookMetadata {
+        id: volume.id,
+        isbn: isbn_of(&info),
+        description: info.description.as_deref().and_then(plain_text),
+        series_index: info
+            .series_info
+            .and_then(|s| s.book_display_number)
+            .and_then(|n| n.parse().ok()),
+        cover_url: info.image_links.and_then(largest_image),
+        title: info.title,
+        subtitle: info.subtitle,
+        authors: info.authors,
+        publisher: info.publisher,
+        published_date: info.published_date,
+        language: info.language,
+        page_count: info.page_count.filter(|p| *p > 0),
+        // "Fiction / Fantasy / Epic"
+        subjects: info
+            .categories
+            .iter()
+            .flat_map(|c| c.split(" / "))
+            .fold(Vec::new(), |mut subjects, s| {
+                if !subjects.iter().any(|known| known == s) {
+                    subjects.push(s.to_string());
+                }
+                subjects
+            }),
+        rating: info.average_rating,
+        provider: "googlebooks".to_string(),
+        ..Default::default()
+    }
+}
+
+/// ISBN-13, converted from the ISBN-10 when that's all there is
+fn isbn_of(info: &VolumeInfo) -> Option<String> {
+    ["ISBN_13", "ISBN_10"].into_iter().find_map(|kind| {
+        info.industry_identifiers
+            .iter()
+            .find(|i| i.kind == kind)
+            .and_then(|i| normalize_isbn(&i.identifier))
+    })
+}
+
+/// Largest cover, over HTTPS and without the page curl
+fn largest_image(links: ImageLinks) -> Option<String> {
+    let url = links
+        .extra_large
+        .or(links.large)
+        .or(links.medium)
+        .or(links.small)
+        .or(links.thumbnail)
+        .or(links.small_thumbnail)?;
+    Some(url.replacen("http://", "https://", 1).replace("&edge=curl", ""))
+}
+
+/// Quoted search term, so multi-word titles stay one phrase
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.trim().replace('"', ""))
+}
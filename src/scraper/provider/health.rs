@@ -0,0 +1,172 @@
+use crate::scraper::{Result, ScraperError};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+use std::time::Instant;
+
+/// Outcome of a provider health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// Reachable and accepting our credentials
+    Ok,
+    /// Rejected our API key, token or client name
+    Unauthorized,
+    /// Out of requests for now
+    RateLimited,
+    /// No answer, e.g. blocked or behind a broken proxy
+    Unreachable,
+    /// Answered with an error
+    Error,
+    /// Not checked
+    Unknown,
+}
+
+/// Requests left in a provider's current rate limit window, as last reported
+/// by its API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Quota {
+    /// Requests allowed per window
+    pub limit: Option<u32>,
+    /// Requests left in the window
+    pub remaining: u32,
+    /// When the window starts over
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+impl Quota {
+    /// Quota from the `X-RateLimit-*` headers of a response
+    ///
+    /// `X-RateLimit-Reset` is read as a Unix timestamp, or as seconds from
+    /// now when too small to be one.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let number = |name: &str| -> Option<i64> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        };
+
+        let remaining = u32::try_from(number("x-ratelimit-remaining")?).ok()?;
+        let reset_at = number("x-ratelimit-reset").and_then(|reset| {
+            if reset > 1_000_000_000 {
+                DateTime::from_timestamp(reset, 0)
+            } else {
+                Some(Utc::now() + chrono::Duration::seconds(reset))
+            }
+        });
+
+        Some(Self {
+            limit: number("x-ratelimit-limit").and_then(|l| u32::try_from(l).ok()),
+            remaining,
+            reset_at,
+        })
+    }
+}
+
+/// Health of a provider, to diagnose bad API keys or blocked networks
+/// before a long scrape fails
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    pub status: HealthStatus,
+    /// What went wrong, or other details
+    pub message: Option<String>,
+    /// Round trip of the check, in milliseconds
+    pub latency_ms: Option<u64>,
+    /// Remaining rate limit, for APIs that report it
+    pub quota: Option<Quota>,
+}
+
+impl ProviderHealth {
+    #[must_use]
+    pub const fn new(status: HealthStatus) -> Self {
+        Self {
+            status,
+            message: None,
+            latency_ms: None,
+            quota: None,
+        }
+    }
+
+    /// Health from the outcome of a cheap request sent at `started`
+    ///
+    /// A missing item still proves the API answers and accepts our
+    /// credentials, so it counts as healthy.
+    #[must_use]
+    pub fn probe<T>(result: &Result<T>, started: Instant) -> Self {
+        let status = match result {
+            Ok(_)
+            | Err(ScraperError::NotFound(_) | ScraperError::Api { status: 404, .. }) => {
+                HealthStatus::Ok
+            }
+            Err(ScraperError::Api {
+                status: 401 | 403, ..
+            }) => HealthStatus::Unauthorized,
+            Err(ScraperError::RateLimit(_) | ScraperError::Api { status: 429, .. }) => {
+                HealthStatus::RateLimited
+            }
+            Err(ScraperError::Network(_)) => HealthStatus::Unreachable,
+            Err(_) => HealthStatus::Error,
+        };
+        let latency = started.elapsed().as_millis();
+
+        Self {
+            status,
+            message: result.as_ref().err().map(ToString::to_string),
+            latency_ms: Some(u64::try_from(latency).unwrap_or(u64::MAX)),
+            quota: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_quota(mut self, quota: Option<Quota>) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.status == HealthStatus::Ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_probe_status() {
+        let started = Instant::now();
+        assert!(ProviderHealth::probe(&Ok(()), started).is_ok());
+
+        let missing: Result<()> = Err(ScraperError::NotFound("tt0".to_string()));
+        assert!(ProviderHealth::probe(&missing, started).is_ok());
+
+        let rejected: Result<()> = Err(ScraperError::Api {
+            status: 401,
+            message: "Invalid API key".to_string(),
+        });
+        let health = ProviderHealth::probe(&rejected, started);
+        assert_eq!(health.status, HealthStatus::Unauthorized);
+        assert!(health.message.unwrap().contains("Invalid API key"));
+    }
+
+    #[test]
+    fn test_quota_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Quota::from_headers(&headers), None);
+
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("90"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("42"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1700000000"));
+        let quota = Quota::from_headers(&headers).unwrap();
+        assert_eq!(quota.limit, Some(90));
+        assert_eq!(quota.remaining, 42);
+        assert_eq!(quota.reset_at.unwrap().timestamp(), 1_700_000_000);
+    }
+}
@@ -1,28 +1,324 @@
+use super::health::Quota;
 use crate::scraper::{Result, ScraperError};
-use reqwest::Client;
+use moka::future::Cache;
+use reqwest::header::{
+    ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{Client, Proxy, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Times a rate limited (429) request is retried by default
+const DEFAULT_MAX_RETRIES: u32 = 3;
+/// Longest wait before a retry; longer Retry-After delays fail instead
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+/// Bytes of GET responses kept for revalidation by default
+const DEFAULT_REVALIDATION_BYTES: u64 = 32 * 1024 * 1024;
+
+/// A GET response kept with its validators, sent back to revalidate it
+#[derive(Debug)]
+struct Validated {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: Vec<u8>,
+}
+
+impl Validated {
+    /// Keep the response if the server gave it a validator
+    fn from_headers(headers: &HeaderMap, body: &[u8]) -> Option<Self> {
+        let etag = headers.get(ETAG).cloned();
+        let last_modified = headers.get(LAST_MODIFIED).cloned();
+        (etag.is_some() || last_modified.is_some()).then(|| Self {
+            etag,
+            last_modified,
+            body: body.to_vec(),
+        })
+    }
+}
+
+fn revalidation_cache(max_bytes: u64) -> Option<Cache<String, Arc<Validated>>> {
+    (max_bytes > 0).then(|| {
+        Cache::builder()
+            .max_capacity(max_bytes)
+            .weigher(|url: &String, response: &Arc<Validated>| {
+                u32::try_from(url.len() + response.body.len()).unwrap_or(u32::MAX)
+            })
+            .build()
+    })
+}
+
+/// Spaces requests out to stay under a provider's rate limit
+///
+/// A token bucket holding a single token, refilled every `interval`.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_second: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / requests_per_second),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait for the next free request slot
+    pub(crate) async fn acquire(&self) {
+        // Holding the lock while sleeping queues concurrent callers in order
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        if *next > now {
+            tokio::time::sleep_until(*next).await;
+        }
+        *next = (*next).max(now) + self.interval;
+    }
+
+    /// Hold every request back for `wait`, e.g. after being rate limited
+    async fn pause(&self, wait: Duration) {
+        let mut next = self.next.lock().await;
+        *next = (*next).max(Instant::now() + wait);
+    }
+}
+
+/// Network settings of a provider, for networks where its API is blocked
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkOptions {
+    /// Proxy for every request, e.g. `http://host:8080` or `socks5h://host:1080`
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Mirror of the API, replacing its base URL
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Mirror of the provider's image server, for providers building image
+    /// URLs themselves (TMDB)
+    #[serde(default)]
+    pub image_base_url: Option<String>,
+}
 
 /// HTTP client wrapper for providers
+///
+/// Clones share the same rate limiter, so a provider stays within its limit
+/// however many tasks use it concurrently, and the same reported quota.
+///
+/// GET responses carrying an `ETag` or `Last-Modified` are kept in memory, and
+/// fetching them again sends `If-None-Match`/`If-Modified-Since`; a 304 reuses
+/// the kept body, so refreshing unchanged metadata costs no download.
 #[derive(Clone)]
 pub struct HttpClient {
     client: Client,
     base_url: String,
+    limiter: Option<Arc<RateLimiter>>,
+    quota: Arc<parking_lot::Mutex<Option<Quota>>>,
+    /// GET responses by URL, for conditional requests
+    validated: Option<Cache<String, Arc<Validated>>>,
+    max_retries: u32,
+    user_agent: String,
+    headers: HeaderMap,
+    proxy: Option<Proxy>,
 }
 
 impl HttpClient {
     /// Create a new HTTP client
     pub fn new(base_url: impl Into<String>) -> Self {
-        let client = Client::builder()
-            .user_agent("Ayiah/0.1.0")
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to build HTTP client");
+        let user_agent = "Ayiah/0.1.0".to_string();
+        let headers = HeaderMap::new();
 
         Self {
-            client,
+            client: Self::build_client(&user_agent, &headers, None),
             base_url: base_url.into(),
+            limiter: None,
+            quota: Arc::default(),
+            validated: revalidation_cache(DEFAULT_REVALIDATION_BYTES),
+            max_retries: DEFAULT_MAX_RETRIES,
+            user_agent,
+            headers,
+            proxy: None,
+        }
+    }
+
+    /// Identify as `user_agent`, for sites turning away unknown clients
+    #[must_use]
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self.client = Self::build_client(&self.user_agent, &self.headers, self.proxy.as_ref());
+        self
+    }
+
+    /// Send a header with every request, e.g. an API key or version
+    ///
+    /// Surrounding whitespace, such as a newline pasted along with a key, is
+    /// dropped. The value is kept out of the error, as it's often a secret.
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Result<Self> {
+        let mut value = HeaderValue::from_str(value.trim())
+            .map_err(|_| ScraperError::Config(format!("Invalid value for header {name}")))?;
+        if name == "authorization" || name.ends_with("-key") {
+            value.set_sensitive(true);
+        }
+        self.headers.insert(name, value);
+        self.client = Self::build_client(&self.user_agent, &self.headers, self.proxy.as_ref());
+        Ok(self)
+    }
+
+    /// Send every request through `proxy`, an HTTP(S) or SOCKS5 proxy URL
+    pub fn with_proxy(mut self, proxy: &str) -> Result<Self> {
+        let proxy = Proxy::all(proxy)
+            .map_err(|e| ScraperError::Config(format!("Invalid proxy {proxy}: {e}")))?;
+        self.client = Self::build_client(&self.user_agent, &self.headers, Some(&proxy));
+        self.proxy = Some(proxy);
+        Ok(self)
+    }
+
+    /// Send requests to a mirror of the API instead
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Apply the proxy and API mirror of `network`
+    pub fn with_network(self, network: &NetworkOptions) -> Result<Self> {
+        let client = match network.base_url {
+            Some(ref base_url) => self.with_base_url(base_url),
+            None => self,
+        };
+        match network.proxy {
+            Some(ref proxy) => client.with_proxy(proxy),
+            None => Ok(client),
+        }
+    }
+
+    fn build_client(user_agent: &str, headers: &HeaderMap, proxy: Option<&Proxy>) -> Client {
+        let mut builder = Client::builder()
+            .user_agent(user_agent)
+            .default_headers(headers.clone())
+            .timeout(Duration::from_secs(30));
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        builder.build().expect("Failed to build HTTP client")
+    }
+
+    /// Limit outgoing requests to `requests_per_second`
+    #[must_use]
+    pub fn with_rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.limiter = (requests_per_second > 0.0)
+            .then(|| Arc::new(RateLimiter::new(requests_per_second)));
+        self
+    }
+
+    /// Retry rate limited (429) requests up to `max_retries` times
+    #[must_use]
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Keep up to `max_bytes` of GET responses for conditional requests; 0
+    /// turns them off
+    #[must_use]
+    pub fn with_revalidation(mut self, max_bytes: u64) -> Self {
+        self.validated = revalidation_cache(max_bytes);
+        self
+    }
+
+    async fn throttle(&self) {
+        if let Some(ref limiter) = self.limiter {
+            limiter.acquire().await;
+        }
+    }
+
+    /// Send a request, waiting and retrying when rate limited
+    ///
+    /// Waits as long as Retry-After asks, and at least twice as long as the
+    /// previous attempt.
+    async fn send(&self, mut request: RequestBuilder) -> Result<Response> {
+        let mut backoff = Duration::from_secs(1);
+        let mut attempt = 0;
+        loop {
+            let retry = request.try_clone();
+            self.throttle().await;
+            let response = request.send().await.map_err(ScraperError::Network)?;
+            if let Some(quota) = Quota::from_headers(response.headers()) {
+                *self.quota.lock() = Some(quota);
+            }
+
+            match (Self::check_status(response).await, retry) {
+                (Err(ScraperError::RateLimit(retry_after)), Some(next))
+                    if attempt < self.max_retries && retry_after <= MAX_RETRY_WAIT =>
+                {
+                    let wait = retry_after.max(backoff).min(MAX_RETRY_WAIT);
+                    debug!("Rate limited by {}, retrying in {wait:?}", self.base_url);
+                    match self.limiter {
+                        Some(ref limiter) => limiter.pause(wait).await,
+                        None => tokio::time::sleep(wait).await,
+                    }
+                    backoff *= 2;
+                    attempt += 1;
+                    request = next;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    /// Send a GET request and read its body, revalidating a kept copy
+    async fn fetch(&self, request: RequestBuilder) -> Result<Vec<u8>> {
+        let Some(ref validated) = self.validated else {
+            let response = self.send(request).await?;
+            let body = response.bytes().await.map_err(ScraperError::Network)?;
+            return Ok(body.to_vec());
+        };
+
+        let url = request
+            .try_clone()
+            .and_then(|r| r.build().ok())
+            .map(|r| r.url().to_string());
+        let kept = match url {
+            Some(ref url) => validated.get(url).await,
+            None => None,
+        };
+
+        let mut request = request;
+        if let Some(ref kept) = kept {
+            if let Some(ref etag) = kept.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(ref last_modified) = kept.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = self.send(request).await?;
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(kept) = kept
+        {
+            debug!("Not modified: {}", url.unwrap_or_default());
+            return Ok(kept.body.clone());
+        }
+
+        let headers = response.headers().clone();
+        let body = response.bytes().await.map_err(ScraperError::Network)?;
+        if let Some(url) = url
+            && let Some(response) = Validated::from_headers(&headers, &body)
+        {
+            validated.insert(url, Arc::new(response)).await;
         }
+        Ok(body.to_vec())
+    }
+
+    /// Rate limit quota the API reported with its last response, if any
+    #[must_use]
+    pub fn quota(&self) -> Option<Quota> {
+        *self.quota.lock()
     }
 
     /// Get the underlying reqwest client
@@ -40,14 +336,9 @@ impl HttpClient {
     /// Execute GET request and parse JSON response
     pub async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
         let url = self.url(endpoint);
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(ScraperError::Network)?;
+        let body = self.fetch(self.client.get(&url)).await?;
 
-        Self::handle_response(response).await
+        Self::parse_body(&body)
     }
 
     /// Execute GET request with query parameters
@@ -57,15 +348,38 @@ impl HttpClient {
         params: &[(&str, &str)],
     ) -> Result<T> {
         let url = self.url(endpoint);
-        let response = self
-            .client
-            .get(&url)
-            .query(params)
-            .send()
-            .await
-            .map_err(ScraperError::Network)?;
+        let body = self.fetch(self.client.get(&url).query(params)).await?;
+
+        Self::parse_body(&body)
+    }
 
-        Self::handle_response(response).await
+    /// Execute GET request with query parameters, with a 404 reported as not
+    /// found
+    pub async fn get_json_or_not_found<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        match self.get_with_params(endpoint, params).await {
+            Err(ScraperError::Api { status: 404, .. }) => {
+                Err(ScraperError::NotFound(format!("Not found: {endpoint}")))
+            }
+            result => result,
+        }
+    }
+
+    /// Execute GET request with query parameters and a bearer token
+    pub async fn get_with_bearer<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+        token: &str,
+    ) -> Result<T> {
+        let url = self.url(endpoint);
+        let request = self.client.get(&url).query(params).bearer_auth(token);
+        let body = self.fetch(request).await?;
+
+        Self::parse_body(&body)
     }
 
     /// Execute POST request with JSON body
@@ -75,24 +389,72 @@ impl HttpClient {
         body: &B,
     ) -> Result<T> {
         let url = self.url(endpoint);
-        let response = self
+        let request = self
             .client
             .post(&url)
             .header("Content-Type", "application/json")
             .header("Accept", "application/json")
-            .json(body)
-            .send()
+            .json(body);
+        let response = self.send(request).await?;
+
+        Self::parse_json(response).await
+    }
+
+    /// Execute GET request with query parameters, returning the raw body
+    pub async fn get_bytes(&self, endpoint: &str, params: &[(&str, &str)]) -> Result<Vec<u8>> {
+        let url = self.url(endpoint);
+        self.fetch(self.client.get(&url).query(params)).await
+    }
+
+    /// Execute GET request, streaming the body into a file
+    ///
+    /// Meant for large files, so the usual request timeout doesn't apply.
+    pub async fn download_to(&self, endpoint: &str, path: &Path) -> Result<u64> {
+        let url = self.url(endpoint);
+        let request = self.client.get(&url).timeout(Duration::from_secs(60 * 60));
+        let mut response = self.send(request).await?;
+
+        let mut file = tokio::fs::File::create(path).await?;
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await.map_err(ScraperError::Network)? {
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+        Ok(written)
+    }
+
+    /// Parse a JSON response
+    async fn parse_json<T: DeserializeOwned>(response: Response) -> Result<T> {
+        response
+            .json::<T>()
             .await
-            .map_err(ScraperError::Network)?;
+            .map_err(|e| ScraperError::Parse(format!("JSON parse error: {e}")))
+    }
 
-        Self::handle_response(response).await
+    /// Parse a JSON body
+    fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+        serde_json::from_slice(body)
+            .map_err(|e| ScraperError::Parse(format!("JSON parse error: {e}")))
     }
 
-    /// Handle response and parse JSON
-    async fn handle_response<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    /// Turn rate limiting and error statuses into errors
+    ///
+    /// A 304 passes, as only conditional requests get one.
+    async fn check_status(response: Response) -> Result<Response> {
         let status = response.status();
 
-        if !status.is_success() {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1);
+            return Err(ScraperError::RateLimit(Duration::from_secs(retry_after)));
+        }
+
+        if !status.is_success() && status != StatusCode::NOT_MODIFIED {
             let status_code = status.as_u16();
             let message = response.text().await.unwrap_or_default();
 
@@ -102,10 +464,7 @@ impl HttpClient {
             });
         }
 
-        response
-            .json::<T>()
-            .await
-            .map_err(|e| ScraperError::Parse(format!("JSON parse error: {e}")))
+        Ok(response)
     }
 }
 
@@ -114,3 +473,95 @@ impl Default for HttpClient {
         Self::new("")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(20.0);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_conditional_get() {
+        use tokio::io::AsyncReadExt;
+
+        // Serves a body with an ETag, and 304 to requests revalidating it
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut revalidated = 0;
+            for _ in 0..2 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let response = if request.contains("if-none-match: \"v1\"") {
+                    revalidated += 1;
+                    "HTTP/1.1 304 Not Modified\r\nconnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = r#"{"id":1}"#;
+                    format!(
+                        "HTTP/1.1 200 OK\r\netag: \"v1\"\r\ncontent-length: {}\r\n\
+                         connection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            revalidated
+        });
+
+        let client = HttpClient::new(base_url);
+        for _ in 0..2 {
+            let value: serde_json::Value = client.get("/item").await.unwrap();
+            assert_eq!(value["id"], 1);
+        }
+        assert_eq!(server.await.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_network_options() {
+        let network = NetworkOptions {
+            proxy: Some("socks5h://127.0.0.1:1080".to_string()),
+            base_url: Some("https://bgm.example.com/".to_string()),
+            image_base_url: None,
+        };
+        let client = HttpClient::new("https://api.bgm.tv").with_network(&network).unwrap();
+        assert_eq!(client.url("/v0/subjects/1"), "https://bgm.example.com/v0/subjects/1");
+
+        let invalid = NetworkOptions {
+            proxy: Some("not a proxy".to_string()),
+            ..NetworkOptions::default()
+        };
+        assert!(HttpClient::new("").with_network(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_header_values() {
+        let client = HttpClient::new("")
+            .with_header("authorization", "Bearer secret-token\n")
+            .unwrap();
+        assert_eq!(client.headers["authorization"], "Bearer secret-token");
+        assert!(client.headers["authorization"].is_sensitive());
+
+        let Err(ScraperError::Config(message)) =
+            HttpClient::new("").with_header("authorization", "Bearer secret\ntoken")
+        else {
+            panic!("invalid header value accepted");
+        };
+        assert!(!message.contains("secret"));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::scraper::{Result, ScraperError};
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+pub const DATASETS_URL: &str = "https://datasets.imdbws.com";
+const BASICS: &str = "title.basics.tsv.gz";
+const RATINGS: &str = "title.ratings.tsv.gz";
+const EPISODES: &str = "title.episode.tsv.gz";
+const AKAS: &str = "title.akas.tsv.gz";
+/// Dataset files read by an import; only the basics are required
+pub const DATASET_FILES: [&str; 4] = [BASICS, RATINGS, EPISODES, AKAS];
+
+/// Title types worth matching files against; shorts, videos and games are left out
+const KEPT_TYPES: [&str; 5] = ["movie", "tvMovie", "tvSeries", "tvMiniSeries", "tvEpisode"];
+const BATCH_SIZE: usize = 10_000;
+
+pub const SCHEMA: &str = r"
+    CREATE TABLE IF NOT EXISTS titles (
+        tconst TEXT PRIMARY KEY NOT NULL,
+        title_type TEXT NOT NULL,
+        primary_title TEXT NOT NULL,
+        original_title TEXT,
+        -- NULL for episodes, which are never searched
+        search_title TEXT,
+        start_year INTEGER,
+        end_year INTEGER,
+        runtime INTEGER,
+        genres TEXT,
+        rating REAL,
+        votes INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS akas (
+        tconst TEXT NOT NULL,
+        title TEXT NOT NULL,
+        search_title TEXT NOT NULL,
+        PRIMARY KEY (tconst, search_title)
+    );
+    CREATE TABLE IF NOT EXISTS episodes (
+        tconst TEXT PRIMARY KEY NOT NULL,
+        parent TEXT NOT NULL,
+        season INTEGER,
+        episode INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL
+    );
+";
+
+/// Created once the rows are in, which is much faster than keeping them up to date
+const INDEXES: &str = r"
+    CREATE INDEX IF NOT EXISTS idx_titles_search ON titles(search_title);
+    CREATE INDEX IF NOT EXISTS idx_akas_search ON akas(search_title);
+    CREATE INDEX IF NOT EXISTS idx_episodes_parent ON episodes(parent, season, episode);
+";
+
+const DROP_INDEXES: &str = r"
+    DROP INDEX IF EXISTS idx_titles_search;
+    DROP INDEX IF EXISTS idx_akas_search;
+    DROP INDEX IF EXISTS idx_episodes_parent;
+";
+
+/// Rows written by an import
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ImportSummary {
+    pub titles: u64,
+    pub ratings: u64,
+    pub episodes: u64,
+    pub akas: u64,
+}
+
+/// Replace the database contents with the datasets in `dir`
+pub async fn import(db: &SqlitePool, dir: &Path) -> Result<ImportSummary> {
+    let basics = dir.join(BASICS);
+    if !basics.is_file() {
+        return Err(ScraperError::Config(format!(
+            "IMDb dataset not found: {}",
+            basics.display()
+        )));
+    }
+
+    sqlx::raw_sql(DROP_INDEXES).execute(db).await?;
+    sqlx::raw_sql("DELETE FROM titles; DELETE FROM akas; DELETE FROM episodes; DELETE FROM meta;")
+        .execute(db)
+        .await?;
+
+    let mut summary = ImportSummary {
+        titles: import_basics(db, basics).await?,
+        ..Default::default()
+    };
+    if let Some(path) = optional(dir, RATINGS) {
+        summary.ratings = import_ratings(db, path).await?;
+    }
+    if let Some(path) = optional(dir, EPISODES) {
+        summary.episodes = import_episodes(db, path).await?;
+    }
+    if let Some(path) = optional(dir, AKAS) {
+        summary.akas = import_akas(db, path).await?;
+    }
+
+    sqlx::raw_sql(INDEXES).execute(db).await?;
+    sqlx::query("INSERT INTO meta (key, value) VALUES ('imported_at', datetime('now'))")
+        .execute(db)
+        .await?;
+
+    Ok(summary)
+}
+
+fn optional(dir: &Path, file: &str) -> Option<PathBuf> {
+    let path = dir.join(file);
+    path.is_file().then_some(path)
+}
+
+/// tconst, titleType, primaryTitle, originalTitle, isAdult, startYear, endYear,
+/// runtimeMinutes, genres
+async fn import_basics(db: &SqlitePool, path: PathBuf) -> Result<u64> {
+    let (mut rows, reader) = read_rows(path, |row| {
+        row.len() >= 9 && KEPT_TYPES.contains(&row[1]) && row[4] != "1"
+    });
+
+    let mut count = 0;
+    while let Some(batch) = rows.recv().await {
+        let mut tx = db.begin().await?;
+        for row in &batch {
+            let episode = row[1] == "tvEpisode";
+            let original = field(&row[3]).filter(|t| *t != row[2]);
+            sqlx::query(
+                r"
+                INSERT OR REPLACE INTO titles (
+                    tconst, title_type, primary_title, original_title, search_title,
+                    start_year, end_year, runtime, genres
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ",
+            )
+            .bind(&row[0])
+            .bind(&row[1])
+            .bind(&row[2])
+            .bind(original)
+            .bind((!episode).then(|| search_key(&row[2])))
+            .bind(number(&row[5]))
+            .bind(number(&row[6]))
+            .bind(number(&row[7]))
+            .bind(field(&row[8]))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        count += batch.len() as u64;
+    }
+
+    finish(reader).await?;
+    Ok(count)
+}
+
+/// tconst, averageRating, numVotes
+async fn import_ratings(db: &SqlitePool, path: PathBuf) -> Result<u64> {
+    let (mut rows, reader) = read_rows(path, |row| row.len() >= 3);
+
+    let mut count = 0;
+    while let Some(batch) = rows.recv().await {
+        let mut tx = db.begin().await?;
+        for row in &batch {
+            sqlx::query("UPDATE titles SET rating = ?, votes = ? WHERE tconst = ?")
+                .bind(field(&row[1]).and_then(|r| r.parse::<f64>().ok()))
+                .bind(number(&row[2]))
+                .bind(&row[0])
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        count += batch.len() as u64;
+    }
+
+    finish(reader).await?;
+    Ok(count)
+}
+
+/// tconst, parentTconst, seasonNumber, episodeNumber
+async fn import_episodes(db: &SqlitePool, path: PathBuf) -> Result<u64> {
+    let (mut rows, reader) = read_rows(path, |row| row.len() >= 4);
+
+    let mut count = 0;
+    while let Some(batch) = rows.recv().await {
+        let mut tx = db.begin().await?;
+        for row in &batch {
+            sqlx::query(
+                r"
+                INSERT OR REPLACE INTO episodes (tconst, parent, season, episode)
+                VALUES (?, ?, ?, ?)
+                ",
+            )
+            .bind(&row[0])
+            .bind(&row[1])
+            .bind(number(&row[2]))
+            .bind(number(&row[3]))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        count += batch.len() as u64;
+    }
+
+    finish(reader).await?;
+    Ok(count)
+}
+
+/// titleId, ordering, title, region, language, types, attributes, isOriginalTitle
+///
+/// Only kept for the searchable titles, as most akas belong to episodes.
+async fn import_akas(db: &SqlitePool, path: PathBuf) -> Result<u64> {
+    let searchable: HashSet<String> =
+        sqlx::query_scalar("SELECT tconst FROM titles WHERE search_title IS NOT NULL")
+            .fetch_all(db)
+            .await?
+            .into_iter()
+            .collect();
+    let (mut rows, reader) = read_rows(path, move |row| {
+        row.len() >= 3 && searchable.contains(row[0])
+    });
+
+    let mut count = 0;
+    while let Some(batch) = rows.recv().await {
+        let mut tx = db.begin().await?;
+        for row in &batch {
+            let key = search_key(&row[2]);
+            if key.is_empty() {
+                continue;
+            }
+            let result = sqlx::query(
+                "INSERT OR IGNORE INTO akas (tconst, title, search_title) VALUES (?, ?, ?)",
+            )
+            .bind(&row[0])
+            .bind(&row[2])
+            .bind(key)
+            .execute(&mut *tx)
+            .await?;
+            count += result.rows_affected();
+        }
+        tx.commit().await?;
+    }
+
+    finish(reader).await?;
+    Ok(count)
+}
+
+type Row = Vec<String>;
+
+/// Rows of a gzipped TSV file kept by `keep`, read in batches on a blocking thread
+fn read_rows(
+    path: PathBuf,
+    keep: impl Fn(&[&str]) -> bool + Send + 'static,
+) -> (mpsc::Receiver<Vec<Row>>, JoinHandle<io::Result<()>>) {
+    let (tx, rx) = mpsc::channel(4);
+
+    let reader = tokio::task::spawn_blocking(move || {
+        let lines = BufReader::new(GzDecoder::new(File::open(path)?)).lines();
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+
+        // The first line holds the column names
+        for line in lines.skip(1) {
+            let line = line?;
+            let fields: Vec<&str> = line.split('\t').collect();
+            if !keep(&fields) {
+                continue;
+            }
+
+            batch.push(fields.into_iter().map(str::to_string).collect());
+            if batch.len() == BATCH_SIZE {
+                let full = std::mem::replace(&mut batch, Vec::with_capacity(BATCH_SIZE));
+                if tx.blocking_send(full).is_err() {
+                    // The import gave up
+                    return Ok(());
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            let _ = tx.blocking_send(batch);
+        }
+        Ok(())
+    });
+
+    (rx, reader)
+}
+
+async fn finish(reader: JoinHandle<io::Result<()>>) -> Result<()> {
+    reader.await.map_err(io::Error::other)??;
+    Ok(())
+}
+
+/// `\N` marks missing values
+fn field(value: &str) -> Option<&str> {
+    (value != r"\N" && !value.is_empty()).then_some(value)
+}
+
+fn number(value: &str) -> Option<i64> {
+    field(value).and_then(|v| v.parse().ok())
+}
+
+/// Lowercase letters and digits separated by single spaces, as titles are indexed
+///
+/// Apostrophes are dropped rather than splitting words, so "Schindler's List"
+/// and "Schindlers List" share a key.
+pub fn search_key(title: &str) -> String {
+    title
+        .to_lowercase()
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '\u{2019}'))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,5 @@
+mod dataset;
+mod provider;
+
+pub use dataset::{DATASET_FILES, ImportSummary};
+pub use provider::ImdbProvider;
@@ -0,0 +1,508 @@
+use super::dataset::{self, DATASET_FILES, DATASETS_URL, ImportSummary, SCHEMA, search_key};
+use crate::scraper::{
+    parser::TitleArticles,
+    provider::{
+        HealthStatus, HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions,
+    },
+    types::{
+        EpisodeInfo, ExternalIds, ExternalRating, MediaInfo, MediaMetadata, MediaType, SeasonInfo,
+    },
+    Result, ScraperError,
+};
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqliteSynchronous};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const TITLE_COLUMNS: &str = "tconst, title_type, primary_title, original_title, start_year, \
+                             end_year, runtime, genres, rating, votes";
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+
+/// Provider serving the official IMDb datasets from a local SQLite database
+///
+/// Needs no network once the datasets are imported, so it suits air-gapped
+/// servers and makes a free first pass before the rate-limited providers.
+/// Titles carry no overviews or images.
+#[derive(Clone)]
+pub struct ImdbProvider {
+    db: SqlitePool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct TitleRow {
+    tconst: String,
+    title_type: String,
+    primary_title: String,
+    original_title: Option<String>,
+    start_year: Option<i32>,
+    end_year: Option<i32>,
+    runtime: Option<i32>,
+    genres: Option<String>,
+    rating: Option<f64>,
+    votes: Option<i32>,
+}
+
+impl ImdbProvider {
+    /// Open the database at `path`, creating it when missing
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let db = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(path)
+                .create_if_missing(true)
+                .journal_mode(SqliteJournalMode::Wal)
+                .synchronous(SqliteSynchronous::Normal)
+                .busy_timeout(Duration::from_secs(30)),
+        )
+        .await?;
+        sqlx::raw_sql(SCHEMA).execute(&db).await?;
+
+        Ok(Self { db })
+    }
+
+    /// When the datasets were last imported; `None` before the first import
+    pub async fn imported_at(&self) -> Result<Option<String>> {
+        Ok(
+            sqlx::query_scalar("SELECT value FROM meta WHERE key = 'imported_at'")
+                .fetch_optional(&self.db)
+                .await?,
+        )
+    }
+
+    /// Download the dataset files into `dir`, replacing older copies
+    pub async fn download(dir: &Path, network: &NetworkOptions) -> Result<()> {
+        tokio::fs::create_dir_all(dir).await?;
+        let client = HttpClient::new(DATASETS_URL).with_network(network)?;
+
+        for file in DATASET_FILES {
+            // Written aside first, so a failed download keeps the previous copy
+            let partial = dir.join(format!("{file}.part"));
+            client.download_to(&format!("/{file}"), &partial).await?;
+            tokio::fs::rename(&partial, dir.join(file)).await?;
+        }
+        Ok(())
+    }
+
+    /// Replace the database contents with the dataset files in `dir`
+    ///
+    /// Takes several minutes for the full datasets; searches made meanwhile
+    /// only see the rows imported so far.
+    pub async fn import(&self, dir: &Path) -> Result<ImportSummary> {
+        dataset::import(&self.db, dir).await
+    }
+
+    async fn title(&self, tconst: &str) -> Result<TitleRow> {
+        sqlx::query_as(&format!(
+            "SELECT {TITLE_COLUMNS} FROM titles WHERE tconst = ? AND search_title IS NOT NULL"
+        ))
+        .bind(tconst)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| ScraperError::NotFound(format!("Not found: {tconst}")))
+    }
+
+    fn row_to_metadata(
+        row: TitleRow,
+        aliases: Vec<String>,
+        seasons: Vec<SeasonInfo>,
+    ) -> MediaMetadata {
+        let media_type = media_type_of(&row.title_type);
+        let is_series = media_type == MediaType::Tv;
+
+        MediaMetadata {
+            sort_title: Some(Self::generate_sort_title(&row.primary_title, row.start_year)),
+            original_title: row.original_title,
+            aliases,
+            media_type,
+            release_date: row.start_year.map(|y| y.to_string()),
+            end_date: row.end_year.map(|y| y.to_string()),
+            runtime: row.runtime,
+            rating: row.rating,
+            vote_count: row.votes,
+            genres: genres_of(row.genres.as_deref()),
+            external_ids: ExternalIds {
+                imdb: Some(row.tconst.clone()),
+                ..Default::default()
+            },
+            provider: "imdb".to_string(),
+            season_count: is_series
+                .then(|| seasons.iter().filter(|s| s.number > 0).count() as i32),
+            episode_count: is_series
+                .then(|| seasons.iter().filter_map(|s| s.episode_count).sum()),
+            seasons,
+            ratings: imdb_rating(row.rating, row.votes).into_iter().collect(),
+            id: row.tconst,
+            title: row.primary_title,
+            ..Default::default()
+        }
+    }
+
+    fn row_to_info(row: TitleRow) -> MediaInfo {
+        let external_ids = ExternalIds {
+            imdb: Some(row.tconst.clone()),
+            ..Default::default()
+        };
+
+        MediaInfo::new(row.tconst, row.primary_title, "imdb")
+            .with_type(media_type_of(&row.title_type))
+            .with_original_title(row.original_title)
+            .with_year(row.start_year)
+            .with_rating(row.rating)
+            .with_external_ids(external_ids)
+    }
+
+    fn generate_sort_title(title: &str, year: Option<i32>) -> String {
+        let articles = TitleArticles::active();
+        let sort_title = articles.strip(title);
+
+        if let Some(year) = year {
+            format!("{sort_title} ({year})")
+        } else {
+            sort_title.to_string()
+        }
+    }
+}
+
+fn media_type_of(title_type: &str) -> MediaType {
+    match title_type {
+        "movie" | "tvMovie" => MediaType::Movie,
+        _ => MediaType::Tv,
+    }
+}
+
+/// Title types searched for a media type
+const fn types_for(media_type: Option<MediaType>) -> &'static str {
+    match media_type {
+        Some(MediaType::Movie) => "'movie', 'tvMovie'",
+        Some(MediaType::Tv | MediaType::Anime) => "'tvSeries', 'tvMiniSeries'",
+        _ => "'movie', 'tvMovie', 'tvSeries', 'tvMiniSeries'",
+    }
+}
+
+/// "Animation,Comedy,Family"
+fn genres_of(genres: Option<&str>) -> Vec<String> {
+    genres
+        .map(|g| g.split(',').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn imdb_rating(rating: Option<f64>, votes: Option<i32>) -> Option<ExternalRating> {
+    Some(ExternalRating {
+        source: "imdb".to_string(),
+        score: rating?,
+        votes,
+    })
+}
+
+#[async_trait]
+impl MetadataProvider for ImdbProvider {
+    fn id(&self) -> &'static str {
+        "imdb"
+    }
+
+    fn name(&self) -> &'static str {
+        "IMDb (offline)"
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &[MediaType::Movie, MediaType::Tv]
+    }
+
+    fn requires_api_key(&self) -> bool {
+        false
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        // Local and free, so searched before any network provider
+        match media_type {
+            MediaType::Movie => 110,
+            MediaType::Tv => 100,
+            MediaType::Anime => 20,
+            MediaType::Unknown => 60,
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let key = search_key(query);
+        if key.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        // The exact title, or the title followed by more words
+        let sql = format!(
+            r"
+            SELECT {TITLE_COLUMNS} FROM titles
+            WHERE tconst IN (
+                SELECT tconst FROM titles
+                WHERE search_title = ?1 OR (search_title > ?2 AND search_title < ?3)
+                UNION
+                SELECT tconst FROM akas
+                WHERE search_title = ?1 OR (search_title > ?2 AND search_title < ?3)
+            )
+            AND title_type IN ({types})
+            AND (?4 IS NULL OR start_year BETWEEN ?4 - 1 AND ?4 + 1)
+            ORDER BY
+                search_title = ?1 OR tconst IN (SELECT tconst FROM akas WHERE search_title = ?1)
+                    DESC,
+                COALESCE(votes, 0) DESC
+            LIMIT ?5
+            ",
+            types = types_for(options.media_type),
+        );
+        let rows: Vec<TitleRow> = sqlx::query_as(&sql)
+            .bind(&key)
+            .bind(format!("{key} "))
+            .bind(format!("{key} {}", char::MAX))
+            .bind(options.year)
+            .bind(options.limit.map_or(DEFAULT_SEARCH_LIMIT, |l| l as i64))
+            .fetch_all(&self.db)
+            .await?;
+
+        if rows.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        Ok(rows.into_iter().map(Self::row_to_info).collect())
+    }
+
+    async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+        let row = self.title(id).await?;
+
+        let aliases: Vec<String> = sqlx::query_scalar(
+            "SELECT title FROM akas WHERE tconst = ? AND title != ? GROUP BY search_title",
+        )
+        .bind(id)
+        .bind(&row.primary_title)
+        .fetch_all(&self.db)
+        .await?;
+
+        let seasons: Vec<(i32, i32, Option<i32>)> = sqlx::query_as(
+            r"
+            SELECT e.season, COUNT(*), MIN(t.start_year)
+            FROM episodes e LEFT JOIN titles t ON t.tconst = e.tconst
+            WHERE e.parent = ? AND e.season IS NOT NULL
+            GROUP BY e.season
+            ORDER BY e.season
+            ",
+        )
+        .bind(id)
+        .fetch_all(&self.db)
+        .await?;
+        let seasons = seasons
+            .into_iter()
+            .map(|(number, episode_count, year)| SeasonInfo {
+                number,
+                name: None,
+                overview: None,
+                air_date: year.map(|y| y.to_string()),
+                episode_count: Some(episode_count),
+                poster_url: None,
+            })
+            .collect();
+
+        Ok(Self::row_to_metadata(row, aliases, seasons))
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        let row: Option<(String, String, Option<i32>, Option<i32>, Option<f64>)> =
+            sqlx::query_as(
+                r"
+                SELECT t.tconst, t.primary_title, t.start_year, t.runtime, t.rating
+                FROM episodes e JOIN titles t ON t.tconst = e.tconst
+                WHERE e.parent = ? AND e.season = ? AND e.episode = ?
+                ",
+            )
+            .bind(series_id)
+            .bind(season)
+            .bind(episode)
+            .fetch_optional(&self.db)
+            .await?;
+        let Some((id, title, year, runtime, rating)) = row else {
+            return Err(ScraperError::NotFound(format!(
+                "Episode not found: {series_id} S{season:02}E{episode:02}"
+            )));
+        };
+
+        Ok(EpisodeInfo {
+            id,
+            title,
+            season,
+            episode,
+            absolute_number: None,
+            original_title: None,
+            air_date: year.map(|y| y.to_string()),
+            overview: None,
+            runtime,
+            rating,
+            still_url: None,
+            provider: "imdb".to_string(),
+        })
+    }
+
+    async fn enrich(&self, metadata: &mut MediaMetadata) -> Result<()> {
+        let Some(imdb_id) = metadata.external_ids.imdb.as_deref() else {
+            return Ok(());
+        };
+        if metadata.ratings.iter().any(|r| r.source == "imdb") {
+            return Ok(());
+        }
+
+        let rating: Option<(Option<f64>, Option<i32>)> =
+            sqlx::query_as("SELECT rating, votes FROM titles WHERE tconst = ?")
+                .bind(imdb_id)
+                .fetch_optional(&self.db)
+                .await?;
+        if let Some(rating) = rating.and_then(|(rating, votes)| imdb_rating(rating, votes)) {
+            metadata.ratings.push(rating);
+        }
+
+        Ok(())
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        if source != "imdb" {
+            return Ok(None);
+        }
+
+        match self.title(external_id).await {
+            Ok(row) => Ok(Some(Self::row_to_info(row))),
+            Err(ScraperError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        let result = self.imported_at().await;
+        match result {
+            Ok(Some(imported_at)) => ProviderHealth::probe(&Ok(()), started)
+                .with_message(format!("Datasets imported at {imported_at}")),
+            Ok(None) => ProviderHealth::new(HealthStatus::Unknown)
+                .with_message("Datasets not imported yet"),
+            Err(_) => ProviderHealth::probe(&result, started),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    fn write_dataset(dir: &Path, file: &str, rows: &[&str]) {
+        let mut encoder = GzEncoder::new(
+            std::fs::File::create(dir.join(file)).unwrap(),
+            Compression::fast(),
+        );
+        for row in rows {
+            writeln!(encoder, "{row}").unwrap();
+        }
+        encoder.finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        write_dataset(
+            dir.path(),
+            "title.basics.tsv.gz",
+            &[
+                concat!(
+                    "tconst\ttitleType\tprimaryTitle\toriginalTitle\tisAdult\t",
+                    "startYear\tendYear\truntimeMinutes\tgenres",
+                ),
+                "tt0133093\tmovie\tThe Matrix\tThe Matrix\t0\t1999\t\\N\t136\tAction,Sci-Fi",
+                concat!(
+                    "tt0234215\tmovie\tThe Matrix Reloaded\tThe Matrix Reloaded\t0\t",
+                    "2003\t\\N\t138\tAction,Sci-Fi",
+                ),
+                "tt0106062\tmovie\tMatrix\tMatrix\t0\t1993\t\\N\t\\N\tShort",
+                "tt0903747\ttvSeries\tBreaking Bad\tBreaking Bad\t0\t2008\t2013\t49\tCrime,Drama",
+                "tt0959621\ttvEpisode\tPilot\tPilot\t0\t2008\t\\N\t58\tCrime,Drama",
+                "tt0000001\tshort\tCarmencita\tCarmencita\t0\t1894\t\\N\t1\tDocumentary",
+            ],
+        );
+        write_dataset(
+            dir.path(),
+            "title.ratings.tsv.gz",
+            &[
+                "tconst\taverageRating\tnumVotes",
+                "tt0133093\t8.7\t2100000",
+                "tt0234215\t7.2\t650000",
+            ],
+        );
+        write_dataset(
+            dir.path(),
+            "title.episode.tsv.gz",
+            &["tconst\tparentTconst\tseasonNumber\tepisodeNumber", "tt0959621\ttt0903747\t1\t1"],
+        );
+        write_dataset(
+            dir.path(),
+            "title.akas.tsv.gz",
+            &[
+                "titleId\tordering\ttitle\tregion\tlanguage\ttypes\tattributes\tisOriginalTitle",
+                "tt0133093\t1\tMatrix\tDE\t\\N\timdbDisplay\t\\N\t0",
+                "tt0959621\t1\tPiloto\tES\t\\N\t\\N\t\\N\t0",
+            ],
+        );
+
+        let provider = ImdbProvider::open(dir.path().join("imdb.db")).await.unwrap();
+        assert_eq!(provider.imported_at().await.unwrap(), None);
+
+        let summary = provider.import(dir.path()).await.unwrap();
+        assert_eq!(summary.titles, 5);
+        assert_eq!(summary.episodes, 1);
+        // Akas of episodes are left out
+        assert_eq!(summary.akas, 1);
+        assert!(provider.imported_at().await.unwrap().is_some());
+
+        // Exact titles first (here through an aka), then by votes
+        let options = SearchOptions::new().with_type(MediaType::Movie);
+        let results = provider.search("Matrix", &options).await.unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["tt0133093", "tt0106062"]);
+
+        let results = provider.search("the matrix", &options).await.unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["tt0133093", "tt0234215"]);
+
+        let options = SearchOptions::new().with_year(Some(2003));
+        let results = provider.search("The Matrix", &options).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "The Matrix Reloaded");
+
+        let metadata = provider.get_metadata("tt0903747", MediaType::Tv).await.unwrap();
+        assert_eq!(metadata.media_type, MediaType::Tv);
+        assert_eq!(metadata.season_count, Some(1));
+        assert_eq!(metadata.episode_count, Some(1));
+        assert_eq!(metadata.end_date.as_deref(), Some("2013"));
+
+        let episode = provider.get_episode("tt0903747", 1, 1).await.unwrap();
+        assert_eq!(episode.title, "Pilot");
+        assert!(provider.get_episode("tt0903747", 1, 2).await.is_err());
+
+        let mut metadata = MediaMetadata {
+            external_ids: ExternalIds {
+                imdb: Some("tt0133093".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        provider.enrich(&mut metadata).await.unwrap();
+        assert_eq!(metadata.ratings[0].score, 8.7);
+        assert_eq!(metadata.ratings[0].votes, Some(2_100_000));
+    }
+}
@@ -0,0 +1,43 @@
+/// Languages to try in order: `requested` first, then `preferred`, without
+/// duplicates
+pub fn language_chain<'a>(requested: Option<&'a str>, preferred: &'a [String]) -> Vec<&'a str> {
+    let mut chain: Vec<&str> = Vec::new();
+    for language in requested.into_iter().chain(preferred.iter().map(String::as_str)) {
+        if !chain.iter().any(|l| l.eq_ignore_ascii_case(language)) {
+            chain.push(language);
+        }
+    }
+    chain
+}
+
+/// Whether text in `language` (ISO 639-1) for `region` (ISO 3166-1) suits
+/// `tag`, e.g. `zh-CN` or `ja`; a tag without region accepts any region
+pub fn language_matches(tag: &str, language: &str, region: Option<&str>) -> bool {
+    let mut parts = tag.split(['-', '_']);
+    let tag_language = parts.next().unwrap_or_default();
+    if !tag_language.eq_ignore_ascii_case(language) {
+        return false;
+    }
+
+    match parts.next() {
+        Some(tag_region) => region.is_some_and(|r| r.eq_ignore_ascii_case(tag_region)),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_chain() {
+        let preferred = vec!["zh-CN".to_string(), "en-US".to_string()];
+        assert_eq!(language_chain(None, &preferred), ["zh-CN", "en-US"]);
+        assert_eq!(language_chain(Some("en-us"), &preferred), ["en-us", "zh-CN"]);
+
+        assert!(language_matches("zh-CN", "zh", Some("CN")));
+        assert!(!language_matches("zh-CN", "zh", Some("TW")));
+        assert!(language_matches("ja", "ja", Some("JP")));
+        assert!(!language_matches("en", "ja", None));
+    }
+}
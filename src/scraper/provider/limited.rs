@@ -0,0 +1,116 @@
+use super::{health::ProviderHealth, http::RateLimiter};
+use crate::scraper::{
+    Result,
+    provider::{DiscoverOptions, MetadataProvider, SearchOptions},
+    types::{EpisodeInfo, MediaInfo, MediaMetadata, MediaType, WatchAvailability},
+};
+use async_trait::async_trait;
+
+/// Provider whose calls are limited to a number per second
+///
+/// On top of the limit of the provider's own HTTP client, so bulk scrapes
+/// can be slowed down further than the API requires.
+pub struct RateLimitedProvider<P> {
+    inner: P,
+    limiter: RateLimiter,
+}
+
+impl<P: MetadataProvider> RateLimitedProvider<P> {
+    pub fn new(inner: P, requests_per_second: f64) -> Self {
+        Self {
+            inner,
+            limiter: RateLimiter::new(requests_per_second),
+        }
+    }
+}
+
+#[async_trait]
+impl<P: MetadataProvider> MetadataProvider for RateLimitedProvider<P> {
+    fn id(&self) -> &'static str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        self.inner.supported_types()
+    }
+
+    fn requires_api_key(&self) -> bool {
+        self.inner.requires_api_key()
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        self.inner.priority_for(media_type)
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        self.limiter.acquire().await;
+        self.inner.search(query, options).await
+    }
+
+    async fn get_metadata(&self, id: &str, media_type: MediaType) -> Result<MediaMetadata> {
+        self.limiter.acquire().await;
+        self.inner.get_metadata(id, media_type).await
+    }
+
+    async fn get_metadata_in(
+        &self,
+        id: &str,
+        media_type: MediaType,
+        language: &str,
+    ) -> Result<MediaMetadata> {
+        self.limiter.acquire().await;
+        self.inner.get_metadata_in(id, media_type, language).await
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        self.limiter.acquire().await;
+        self.inner.get_episode(series_id, season, episode).await
+    }
+
+    async fn get_season(&self, series_id: &str, season: i32) -> Result<Vec<EpisodeInfo>> {
+        self.limiter.acquire().await;
+        self.inner.get_season(series_id, season).await
+    }
+
+    async fn discover(&self, options: &DiscoverOptions) -> Result<Vec<MediaInfo>> {
+        self.limiter.acquire().await;
+        self.inner.discover(options).await
+    }
+
+    async fn get_watch_providers(
+        &self,
+        id: &str,
+        media_type: MediaType,
+    ) -> Result<WatchAvailability> {
+        self.limiter.acquire().await;
+        self.inner.get_watch_providers(id, media_type).await
+    }
+
+    async fn enrich(&self, metadata: &mut MediaMetadata) -> Result<()> {
+        self.limiter.acquire().await;
+        self.inner.enrich(metadata).await
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        self.limiter.acquire().await;
+        self.inner.find_by_external_id(external_id, source).await
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        self.limiter.acquire().await;
+        self.inner.health().await
+    }
+}
@@ -0,0 +1,132 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Strings keyed by language code, e.g. {"en": "...", "ja-ro": "..."}
+pub type Localized = HashMap<String, String>;
+
+// Collection responses (manga search, chapter feed, covers)
+#[derive(Debug, Deserialize)]
+pub struct Collection<T> {
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+    #[serde(default)]
+    pub total: usize,
+}
+
+// Single entity responses
+#[derive(Debug, Deserialize)]
+pub struct Entity<T> {
+    pub data: T,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Manga {
+    pub id: String,
+    pub attributes: MangaAttributes,
+    #[serde(default)]
+    pub relationships: Vec<Relationship>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MangaAttributes {
+    #[serde(default)]
+    pub title: Localized,
+    #[serde(default)]
+    pub alt_titles: Vec<Localized>,
+    /// May come as an empty list instead of an empty map
+    #[serde(default, deserialize_with = "lenient_localized")]
+    pub description: Localized,
+    /// Keys are site abbreviations, e.g. "al" (AniList) or "mal"
+    #[serde(default, deserialize_with = "lenient_localized")]
+    pub links: Localized,
+    pub original_language: Option<String>,
+    pub last_volume: Option<String>,
+    pub last_chapter: Option<String>,
+    pub publication_demographic: Option<String>,
+    /// "ongoing", "completed", "hiatus" or "cancelled"
+    pub status: Option<String>,
+    pub year: Option<i32>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Tag {
+    pub attributes: TagAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagAttributes {
+    #[serde(default)]
+    pub name: Localized,
+    /// "genre", "theme", "format" or "content"
+    pub group: String,
+}
+
+/// Related entity, with attributes when asked for by `includes[]`
+#[derive(Debug, Deserialize)]
+pub struct Relationship {
+    pub id: String,
+    /// "author", "artist", "cover_art"...
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub attributes: Option<RelationshipAttributes>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipAttributes {
+    /// Authors and artists
+    pub name: Option<String>,
+    /// Cover art
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Chapter {
+    pub id: String,
+    pub attributes: ChapterAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterAttributes {
+    pub volume: Option<String>,
+    pub chapter: Option<String>,
+    pub title: Option<String>,
+    pub translated_language: Option<String>,
+    pub pages: Option<i32>,
+    pub publish_at: Option<String>,
+    /// Set for chapters hosted elsewhere, which have no pages here
+    pub external_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Cover {
+    pub attributes: CoverAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverAttributes {
+    pub volume: Option<String>,
+    pub file_name: String,
+}
+
+fn lenient_localized<'de, D>(deserializer: D) -> Result<Localized, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MapOrList {
+        Map(Localized),
+        List(Vec<serde_json::Value>),
+    }
+
+    Ok(match Option::<MapOrList>::deserialize(deserializer)? {
+        Some(MapOrList::Map(map)) => map,
+        Some(MapOrList::List(_)) | None => Localized::new(),
+    })
+}
@@ -0,0 +1,269 @@
+use super::api_types::{Chapter, Collection, Cover, Entity, Localized, Manga, Relationship};
+use crate::scraper::{
+    provider::{HttpClient, NetworkOptions},
+    types::{ChapterInfo, ComicInfo, ComicMetadata, CoverInfo, ExternalIds},
+    Result, ScraperError,
+};
+use std::collections::HashSet;
+
+const MANGADEX_BASE_URL: &str = "https://api.mangadex.org";
+const MANGADEX_COVER_URL: &str = "https://uploads.mangadex.org/covers";
+/// MangaDex allows about five requests per second per IP
+const MANGADEX_RATE_LIMIT: f64 = 4.0;
+/// Largest page the chapter feed hands out
+const FEED_PAGE_SIZE: usize = 500;
+
+/// Provider for MangaDex, a manga database with chapter lists and volume covers
+///
+/// Comics have no place in the video-centric [`MetadataProvider`] trait, so
+/// the provider is used directly by the [`MetadataAgent`] for comic libraries.
+///
+/// [`MetadataProvider`]: crate::scraper::MetadataProvider
+/// [`MetadataAgent`]: crate::services::MetadataAgent
+pub struct MangaDexProvider {
+    client: HttpClient,
+}
+
+impl MangaDexProvider {
+    pub fn new() -> Self {
+        Self {
+            client: HttpClient::new(MANGADEX_BASE_URL).with_rate_limit(MANGADEX_RATE_LIMIT),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    /// Search manga series by title
+    pub async fn search(&self, title: &str, limit: usize) -> Result<Vec<ComicInfo>> {
+        let limit_str = limit.clamp(1, 100).to_string();
+        let results: Collection<Manga> = self
+            .client
+            .get_json_or_not_found(
+                "/manga",
+                &[
+                    ("title", title),
+                    ("limit", &limit_str),
+                    ("includes[]", "cover_art"),
+                    ("order[relevance]", "desc"),
+                ],
+            )
+            .await?;
+
+        if results.data.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {title}"
+            )));
+        }
+
+        Ok(results
+            .data
+            .into_iter()
+            .map(|manga| ComicInfo {
+                cover_url: cover_of(&manga),
+                title: localized(&manga.attributes.title).unwrap_or_default(),
+                year: manga.attributes.year,
+                status: manga.attributes.status,
+                id: manga.id,
+                provider: "mangadex".to_string(),
+            })
+            .collect())
+    }
+
+    /// Metadata of a manga series
+    pub async fn get_series(&self, manga_id: &str) -> Result<ComicMetadata> {
+        let manga: Entity<Manga> = self
+            .client
+            .get_json_or_not_found(
+                &format!("/manga/{manga_id}"),
+                &[
+                    ("includes[]", "cover_art"),
+                    ("includes[]", "author"),
+                    ("includes[]", "artist"),
+                ],
+            )
+            .await?;
+
+        Ok(manga_to_metadata(manga.data))
+    }
+
+    /// Chapters of a series, optionally in one translation only, in reading order
+    ///
+    /// A chapter uploaded by several groups is listed once.
+    pub async fn get_chapters(
+        &self,
+        manga_id: &str,
+        language: Option<&str>,
+    ) -> Result<Vec<ChapterInfo>> {
+        let endpoint = format!("/manga/{manga_id}/feed");
+        let limit_str = FEED_PAGE_SIZE.to_string();
+        let mut chapters: Vec<ChapterInfo> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut offset = 0;
+
+        loop {
+            let offset_str = offset.to_string();
+            let mut params = vec![
+                ("limit", limit_str.as_str()),
+                ("offset", offset_str.as_str()),
+                ("order[volume]", "asc"),
+                ("order[chapter]", "asc"),
+                // Chapters hosted elsewhere have no pages to read here
+                ("includeExternalUrl", "0"),
+            ];
+            if let Some(language) = language {
+                params.push(("translatedLanguage[]", language));
+            }
+
+            let page: Collection<Chapter> = self
+                .client
+                .get_json_or_not_found(&endpoint, &params)
+                .await?;
+            let count = page.data.len();
+            for chapter in page.data {
+                let attributes = chapter.attributes;
+                if attributes.external_url.is_some() {
+                    continue;
+                }
+                let key = (
+                    attributes.volume.clone(),
+                    attributes.chapter.clone(),
+                    attributes.translated_language.clone(),
+                );
+                if !seen.insert(key) {
+                    continue;
+                }
+
+                chapters.push(ChapterInfo {
+                    id: chapter.id,
+                    volume: attributes.volume.as_deref().and_then(number),
+                    chapter: attributes.chapter.as_deref().and_then(number),
+                    title: attributes.title.filter(|t| !t.is_empty()),
+                    language: attributes.translated_language,
+                    pages: attributes.pages,
+                    published_at: attributes.publish_at,
+                });
+            }
+
+            offset += count;
+            if count == 0 || offset >= page.total {
+                break;
+            }
+        }
+
+        // Oneshots (no chapter number) go last
+        chapters.sort_by(|a, b| {
+            let key = |c: &ChapterInfo| (c.chapter.is_none(), c.chapter.unwrap_or(0.0));
+            key(a).partial_cmp(&key(b)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(chapters)
+    }
+
+    /// Covers of a series, one or more per volume
+    pub async fn get_covers(&self, manga_id: &str) -> Result<Vec<CoverInfo>> {
+        let covers: Collection<Cover> = self
+            .client
+            .get_json_or_not_found(
+                "/cover",
+                &[("manga[]", manga_id), ("limit", "100"), ("order[volume]", "asc")],
+            )
+            .await?;
+
+        Ok(covers
+            .data
+            .into_iter()
+            .map(|cover| CoverInfo {
+                volume: cover.attributes.volume.as_deref().and_then(number),
+                url: cover_url(manga_id, &cover.attributes.file_name),
+            })
+            .collect())
+    }
+}
+
+impl Default for MangaDexProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn manga_to_metadata(manga: Manga) -> ComicMetadata {
+    let cover_url = cover_of(&manga);
+    let attributes = manga.attributes;
+    let title = localized(&attributes.title).unwrap_or_default();
+
+    let mut aliases: Vec<String> = Vec::new();
+    for alias in attributes.alt_titles.iter().flat_map(|t| t.values()) {
+        if *alias != title && !aliases.contains(alias) {
+            aliases.push(alias.clone());
+        }
+    }
+
+    let genres = attributes
+        .tags
+        .iter()
+        .filter(|t| matches!(t.attributes.group.as_str(), "genre" | "theme"))
+        .filter_map(|t| localized(&t.attributes.name))
+        .collect();
+
+    ComicMetadata {
+        cover_url,
+        authors: names(&manga.relationships, "author"),
+        artists: names(&manga.relationships, "artist"),
+        id: manga.id,
+        title,
+        aliases,
+        description: localized(&attributes.description).filter(|d| !d.is_empty()),
+        genres,
+        status: attributes.status,
+        year: attributes.year,
+        original_language: attributes.original_language,
+        demographic: attributes.publication_demographic,
+        last_volume: attributes.last_volume.as_deref().and_then(number),
+        last_chapter: attributes.last_chapter.as_deref().and_then(number),
+        external_ids: ExternalIds {
+            anilist: attributes.links.get("al").cloned(),
+            mal: attributes.links.get("mal").cloned(),
+            ..Default::default()
+        },
+        provider: "mangadex".to_string(),
+    }
+}
+
+/// English text, then romanized Japanese, then whatever there is
+fn localized(strings: &Localized) -> Option<String> {
+    ["en", "ja-ro"]
+        .into_iter()
+        .find_map(|lang| strings.get(lang))
+        .or_else(|| strings.iter().min_by_key(|(lang, _)| *lang).map(|(_, s)| s))
+        .cloned()
+}
+
+fn names(relationships: &[Relationship], kind: &str) -> Vec<String> {
+    relationships
+        .iter()
+        .filter(|r| r.kind == kind)
+        .filter_map(|r| r.attributes.as_ref()?.name.clone())
+        .collect()
+}
+
+/// Main cover, when included in the response
+fn cover_of(manga: &Manga) -> Option<String> {
+    manga
+        .relationships
+        .iter()
+        .find(|r| r.kind == "cover_art")
+        .and_then(|r| r.attributes.as_ref()?.file_name.as_deref())
+        .map(|file_name| cover_url(&manga.id, file_name))
+}
+
+fn cover_url(manga_id: &str, file_name: &str) -> String {
+    format!("{MANGADEX_COVER_URL}/{manga_id}/{file_name}")
+}
+
+/// Volume or chapter number, e.g. "10.5"
+fn number(value: &str) -> Option<f32> {
+    value.trim().parse().ok()
+}
@@ -1,11 +1,44 @@
+mod anidb;
 mod anilist;
 mod bangumi;
+mod douban;
+mod googlebooks;
+mod health;
+mod html;
 mod http;
+mod imdb;
+mod language;
+mod limited;
+mod mangadex;
+mod musicbrainz;
+mod omdb;
+mod openlibrary;
 mod tmdb;
+mod trakt;
 mod traits;
+mod tvdb;
+mod tvmaze;
+mod webhook;
 
+pub use anidb::{AniDbProvider, EpisodeMapping};
 pub use anilist::AniListProvider;
 pub use bangumi::BangumiProvider;
-pub use http::HttpClient;
-pub use tmdb::TmdbProvider;
-pub use traits::{MetadataProvider, SearchOptions};
+pub use douban::DoubanProvider;
+pub use googlebooks::GoogleBooksProvider;
+pub use health::{HealthStatus, ProviderHealth, Quota};
+pub(crate) use html::plain_text;
+pub use http::{HttpClient, NetworkOptions};
+pub(crate) use http::RateLimiter;
+pub use imdb::{DATASET_FILES as IMDB_DATASET_FILES, ImdbProvider, ImportSummary};
+pub(crate) use language::{language_chain, language_matches};
+pub use limited::RateLimitedProvider;
+pub use mangadex::MangaDexProvider;
+pub use musicbrainz::MusicBrainzProvider;
+pub use omdb::OmdbProvider;
+pub use openlibrary::OpenLibraryProvider;
+pub use tmdb::{TmdbCredential, TmdbProvider};
+pub use trakt::TraktProvider;
+pub use traits::{DiscoverList, DiscoverOptions, MetadataProvider, SearchOptions};
+pub use tvdb::TvdbProvider;
+pub use tvmaze::TvMazeProvider;
+pub use webhook::{WebhookOptions, WebhookProvider};
@@ -0,0 +1,138 @@
+use serde::Deserialize;
+
+// Artist search response
+#[derive(Debug, Deserialize)]
+pub struct ArtistSearch {
+    #[serde(default)]
+    pub artists: Vec<Artist>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Artist {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "sort-name")]
+    pub sort_name: Option<String>,
+    /// "Person", "Group", "Orchestra"...
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub country: Option<String>,
+    pub disambiguation: Option<String>,
+    pub score: Option<i32>,
+}
+
+// Release group search response
+#[derive(Debug, Deserialize)]
+pub struct ReleaseGroupSearch {
+    #[serde(rename = "release-groups", default)]
+    pub release_groups: Vec<ReleaseGroup>,
+}
+
+// Release group (an album across all of its editions)
+#[derive(Debug, Deserialize)]
+pub struct ReleaseGroup {
+    pub id: String,
+    pub title: String,
+    /// "Album", "EP", "Single"...
+    #[serde(rename = "primary-type")]
+    pub primary_type: Option<String>,
+    /// "YYYY", "YYYY-MM" or "YYYY-MM-DD", empty when unknown
+    #[serde(rename = "first-release-date")]
+    pub first_release_date: Option<String>,
+    #[serde(rename = "artist-credit", default)]
+    pub artist_credit: Vec<ArtistCredit>,
+    pub score: Option<i32>,
+    /// With `inc=releases`
+    #[serde(default)]
+    pub releases: Vec<Release>,
+    /// With `inc=genres`
+    #[serde(default)]
+    pub genres: Vec<Genre>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ArtistCredit {
+    /// Name as credited, which may differ from the artist's
+    pub name: String,
+    /// Text joining it to the next credit, e.g. " & "
+    #[serde(default)]
+    pub joinphrase: String,
+    pub artist: CreditedArtist,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreditedArtist {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Genre {
+    pub name: String,
+    #[serde(default)]
+    pub count: i32,
+}
+
+// Release (a single edition), with `inc=recordings+artist-credits+labels`
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub id: String,
+    pub title: String,
+    /// "Official", "Promotion", "Bootleg"...
+    pub status: Option<String>,
+    pub date: Option<String>,
+    pub country: Option<String>,
+    #[serde(default)]
+    pub media: Vec<Medium>,
+    #[serde(rename = "label-info", default)]
+    pub label_info: Vec<LabelInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Medium {
+    pub position: i32,
+    /// "CD", "Digital Media", "12\" Vinyl"...
+    pub format: Option<String>,
+    #[serde(default)]
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Track {
+    pub position: i32,
+    pub title: String,
+    /// Milliseconds
+    pub length: Option<i64>,
+    #[serde(rename = "artist-credit", default)]
+    pub artist_credit: Vec<ArtistCredit>,
+    pub recording: Recording,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Recording {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LabelInfo {
+    pub label: Option<Label>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Label {
+    pub name: String,
+}
+
+// Cover Art Archive listing
+#[derive(Debug, Deserialize)]
+pub struct CoverArt {
+    #[serde(default)]
+    pub images: Vec<CoverImage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CoverImage {
+    #[serde(default)]
+    pub front: bool,
+    pub image: String,
+}
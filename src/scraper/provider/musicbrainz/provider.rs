@@ -0,0 +1,284 @@
+use super::api_types::{
+    ArtistCredit, ArtistSearch, CoverArt, Release, ReleaseGroup, ReleaseGroupSearch,
+};
+use crate::scraper::{
+    provider::{HttpClient, NetworkOptions},
+    types::{AlbumInfo, AlbumMetadata, ArtistInfo, TrackInfo},
+    Result, ScraperError,
+};
+use tracing::warn;
+
+const MUSICBRAINZ_BASE_URL: &str = "https://musicbrainz.org/ws/2";
+const COVER_ART_BASE_URL: &str = "https://coverartarchive.org";
+/// MusicBrainz allows one request per second per client
+const MUSICBRAINZ_RATE_LIMIT: f64 = 1.0;
+/// MusicBrainz blocks anonymous user agents; it asks for a way to reach the author
+const MUSICBRAINZ_USER_AGENT: &str = concat!(
+    "Ayiah/",
+    env!("CARGO_PKG_VERSION"),
+    " ( ",
+    env!("CARGO_PKG_REPOSITORY"),
+    " )"
+);
+
+/// Provider for MusicBrainz, with album covers from the Cover Art Archive
+///
+/// Music has no place in the video-centric [`MetadataProvider`] trait, so the
+/// provider is used directly by the [`MetadataAgent`] for music libraries.
+///
+/// [`MetadataProvider`]: crate::scraper::MetadataProvider
+/// [`MetadataAgent`]: crate::services::MetadataAgent
+pub struct MusicBrainzProvider {
+    client: HttpClient,
+    cover_client: HttpClient,
+}
+
+impl MusicBrainzProvider {
+    pub fn new() -> Self {
+        Self {
+            client: HttpClient::new(MUSICBRAINZ_BASE_URL)
+                .with_user_agent(MUSICBRAINZ_USER_AGENT)
+                .with_rate_limit(MUSICBRAINZ_RATE_LIMIT),
+            cover_client: HttpClient::new(COVER_ART_BASE_URL)
+                .with_user_agent(MUSICBRAINZ_USER_AGENT),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API, and fetch
+    /// covers from a mirror of the Cover Art Archive
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        self.cover_client = self.cover_client.with_network(&NetworkOptions {
+            base_url: network.image_base_url.clone(),
+            ..network.clone()
+        })?;
+        Ok(self)
+    }
+
+    /// GET request in JSON, with a 404 reported as not found
+    async fn request<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        let mut params = params.to_vec();
+        params.push(("fmt", "json"));
+
+        self.client.get_json_or_not_found(endpoint, &params).await
+    }
+
+    /// Search artists by name
+    pub async fn search_artists(&self, query: &str, limit: usize) -> Result<Vec<ArtistInfo>> {
+        let limit_str = limit.clamp(1, 100).to_string();
+        let search: ArtistSearch = self
+            .request(
+                "/artist",
+                &[("query", &format!("artist:{}", phrase(query))), ("limit", &limit_str)],
+            )
+            .await?;
+
+        if search.artists.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        Ok(search
+            .artists
+            .into_iter()
+            .map(|artist| ArtistInfo {
+                id: artist.id,
+                name: artist.name,
+                sort_name: artist.sort_name,
+                disambiguation: artist.disambiguation.filter(|d| !d.is_empty()),
+                kind: artist.kind,
+                country: artist.country,
+                score: artist.score,
+                provider: "musicbrainz".to_string(),
+            })
+            .collect())
+    }
+
+    /// Search albums (release groups) by title, optionally narrowed to an artist
+    pub async fn search_albums(
+        &self,
+        title: &str,
+        artist: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AlbumInfo>> {
+        let mut query = format!("releasegroup:{}", phrase(title));
+        if let Some(artist) = artist.filter(|a| !a.trim().is_empty()) {
+            query.push_str(&format!(" AND artist:{}", phrase(artist)));
+        }
+        let limit_str = limit.clamp(1, 100).to_string();
+
+        let search: ReleaseGroupSearch = self
+            .request("/release-group", &[("query", &query), ("limit", &limit_str)])
+            .await?;
+
+        if search.release_groups.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {title}"
+            )));
+        }
+
+        Ok(search
+            .release_groups
+            .into_iter()
+            .map(|group| AlbumInfo {
+                year: group.first_release_date.as_deref().and_then(year_of),
+                artist: credited_name(&group.artist_credit),
+                artist_id: group.artist_credit.first().map(|c| c.artist.id.clone()),
+                id: group.id,
+                title: group.title,
+                kind: group.primary_type,
+                score: group.score,
+                provider: "musicbrainz".to_string(),
+            })
+            .collect())
+    }
+
+    /// Album metadata of a release group, with the tracks of its main release
+    pub async fn get_album(&self, release_group_id: &str) -> Result<AlbumMetadata> {
+        let group: ReleaseGroup = self
+            .request(
+                &format!("/release-group/{release_group_id}"),
+                &[("inc", "artist-credits+releases+genres")],
+            )
+            .await?;
+
+        let release = match main_release(&group.releases) {
+            Some(id) => Some(
+                self.request::<Release>(
+                    &format!("/release/{id}"),
+                    &[("inc", "recordings+artist-credits+labels")],
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        // A missing cover shouldn't cost the rest of the metadata
+        let cover_url = self
+            .cover_art(release_group_id)
+            .await
+            .inspect_err(|e| warn!("Failed to fetch cover art for {release_group_id}: {e}"))
+            .unwrap_or_default();
+
+        Ok(album_metadata(group, release, cover_url))
+    }
+
+    /// Front cover of a release group, if the Cover Art Archive has one
+    pub async fn cover_art(&self, release_group_id: &str) -> Result<Option<String>> {
+        let endpoint = format!("/release-group/{release_group_id}");
+        let art: CoverArt = match self.cover_client.get_json_or_not_found(&endpoint, &[]).await {
+            Err(ScraperError::NotFound(_)) => return Ok(None),
+            result => result?,
+        };
+
+        let mut images = art.images;
+        let index = images.iter().position(|i| i.front).unwrap_or(0);
+        Ok((index < images.len()).then(|| images.swap_remove(index).image))
+    }
+}
+
+impl Default for MusicBrainzProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn album_metadata(
+    group: ReleaseGroup,
+    release: Option<Release>,
+    cover_url: Option<String>,
+) -> AlbumMetadata {
+    let artist = credited_name(&group.artist_credit);
+
+    let mut genres = group.genres;
+    genres.sort_by_key(|g| std::cmp::Reverse(g.count));
+
+    let (release_id, label, tracks) = match release {
+        Some(release) => {
+            let label = release
+                .label_info
+                .into_iter()
+                .find_map(|info| info.label)
+                .map(|label| label.name);
+            let mut tracks = Vec::new();
+            for medium in release.media {
+                for track in medium.tracks {
+                    // Only credit tracks whose artist differs from the album's
+                    let track_artist =
+                        credited_name(&track.artist_credit).filter(|a| Some(a) != artist.as_ref());
+                    tracks.push(TrackInfo {
+                        id: track.recording.id,
+                        title: track.title,
+                        artist: track_artist,
+                        disc_number: medium.position,
+                        track_number: track.position,
+                        length_ms: track.length,
+                    });
+                }
+            }
+            (Some(release.id), label, tracks)
+        }
+        None => (None, None, Vec::new()),
+    };
+
+    AlbumMetadata {
+        id: group.id,
+        title: group.title,
+        artist_id: group.artist_credit.first().map(|c| c.artist.id.clone()),
+        artist,
+        release_date: group.first_release_date.filter(|d| !d.is_empty()),
+        kind: group.primary_type,
+        genres: genres.into_iter().map(|g| g.name).collect(),
+        label,
+        release_id,
+        tracks,
+        cover_url,
+        provider: "musicbrainz".to_string(),
+    }
+}
+
+/// Release whose track list stands for the album: the earliest official one
+fn main_release(releases: &[Release]) -> Option<&str> {
+    releases
+        .iter()
+        .min_by_key(|r| {
+            let official = r.status.as_deref() == Some("Official");
+            // Undated releases sort last
+            let date = r.date.as_deref().filter(|d| !d.is_empty()).unwrap_or("9999");
+            (!official, date)
+        })
+        .map(|r| r.id.as_str())
+}
+
+/// "Simon & Garfunkel" of the credits, each followed by its join phrase
+fn credited_name(credits: &[ArtistCredit]) -> Option<String> {
+    let name: String = credits
+        .iter()
+        .map(|c| format!("{}{}", c.name, c.joinphrase))
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Quoted Lucene phrase, so the search syntax in titles stays literal
+fn phrase(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.trim().chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// First year of "1997-05-21"
+fn year_of(value: &str) -> Option<i32> {
+    value.get(..4).and_then(|y| y.parse().ok())
+}
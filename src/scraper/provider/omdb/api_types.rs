@@ -0,0 +1,83 @@
+use serde::Deserialize;
+
+// Failed lookups still answer 200, with `"Response": "False"`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Status {
+    pub response: String,
+    pub error: Option<String>,
+}
+
+// Search responses
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SearchResponse {
+    #[serde(default)]
+    pub search: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SearchResult {
+    pub title: String,
+    pub year: String,
+    #[serde(rename = "imdbID")]
+    pub imdb_id: String,
+    #[serde(rename = "Type")]
+    pub kind: String,
+    pub poster: Option<String>,
+}
+
+// Detail responses; missing values are "N/A"
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Title {
+    pub title: String,
+    pub year: Option<String>,
+    pub rated: Option<String>,
+    pub released: Option<String>,
+    pub runtime: Option<String>,
+    pub genre: Option<String>,
+    pub director: Option<String>,
+    pub writer: Option<String>,
+    pub actors: Option<String>,
+    pub plot: Option<String>,
+    pub language: Option<String>,
+    pub poster: Option<String>,
+    #[serde(default)]
+    pub ratings: Vec<Rating>,
+    #[serde(rename = "imdbRating")]
+    pub imdb_rating: Option<String>,
+    #[serde(rename = "imdbVotes")]
+    pub imdb_votes: Option<String>,
+    #[serde(rename = "imdbID")]
+    pub imdb_id: String,
+    #[serde(rename = "Type")]
+    pub kind: Option<String>,
+    #[serde(rename = "totalSeasons")]
+    pub total_seasons: Option<String>,
+    pub production: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Rating {
+    pub source: String,
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Episode {
+    pub title: String,
+    pub released: Option<String>,
+    pub season: String,
+    pub episode: String,
+    pub runtime: Option<String>,
+    pub plot: Option<String>,
+    pub poster: Option<String>,
+    #[serde(rename = "imdbRating")]
+    pub imdb_rating: Option<String>,
+    #[serde(rename = "imdbID")]
+    pub imdb_id: String,
+}
@@ -0,0 +1,4 @@
+mod api_types;
+mod provider;
+
+pub use provider::OmdbProvider;
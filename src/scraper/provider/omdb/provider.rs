@@ -0,0 +1,378 @@
+use super::api_types::{Episode, Rating, SearchResponse, Status, Title};
+use crate::scraper::{
+    parser::TitleArticles,
+    provider::{HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions},
+    types::{
+        EpisodeInfo, ExternalIds, ExternalRating, ImageSet, MediaInfo, MediaMetadata, MediaType,
+        PersonInfo,
+    },
+    Result, ScraperError,
+};
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use std::time::Instant;
+
+const OMDB_BASE_URL: &str = "https://www.omdbapi.com";
+/// Free OMDb keys allow 1000 requests per day, so bursts are kept short
+const OMDB_RATE_LIMIT: f64 = 5.0;
+
+pub struct OmdbProvider {
+    client: HttpClient,
+    api_key: String,
+}
+
+impl OmdbProvider {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: HttpClient::new(OMDB_BASE_URL).with_rate_limit(OMDB_RATE_LIMIT),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    async fn request<T: serde::de::DeserializeOwned>(&self, params: &[(&str, &str)]) -> Result<T> {
+        let mut all_params = vec![("apikey", self.api_key.as_str())];
+        all_params.extend_from_slice(params);
+
+        let value: serde_json::Value = self.client.get_json_or_not_found("/", &all_params).await?;
+        let status: Status = serde_json::from_value(value.clone())
+            .map_err(|e| ScraperError::Parse(format!("JSON parse error: {e}")))?;
+        if status.response != "True" {
+            let message = status.error.unwrap_or_default();
+            return Err(if message.ends_with("not found!") {
+                ScraperError::NotFound(message)
+            } else {
+                ScraperError::Api {
+                    status: 200,
+                    message,
+                }
+            });
+        }
+
+        serde_json::from_value(value)
+            .map_err(|e| ScraperError::Parse(format!("JSON parse error: {e}")))
+    }
+
+    async fn get_title(&self, imdb_id: &str) -> Result<Title> {
+        self.request(&[("i", imdb_id), ("plot", "full")]).await
+    }
+
+    fn title_to_metadata(&self, title: Title) -> MediaMetadata {
+        let media_type = match title.kind.as_deref() {
+            Some("series") => MediaType::Tv,
+            _ => MediaType::Movie,
+        };
+        let year = title.year.as_deref().and_then(year_of);
+        let site_ratings = ratings(&title.ratings, title.imdb_votes.as_deref());
+
+        MediaMetadata {
+            id: title.imdb_id.clone(),
+            sort_title: Some(Self::generate_sort_title(&title.title, year)),
+            media_type,
+            overview: available(title.plot),
+            release_date: available(title.released).and_then(|d| iso_date(&d)),
+            runtime: available(title.runtime).and_then(|r| minutes(&r)),
+            rating: available(title.imdb_rating).and_then(|r| r.parse().ok()),
+            vote_count: available(title.imdb_votes).and_then(|v| votes(&v)),
+            genres: list(title.genre),
+            studios: list(title.production),
+            language: list(title.language).into_iter().next(),
+            content_rating: available(title.rated),
+            images: ImageSet {
+                poster: available(title.poster),
+                ..Default::default()
+            },
+            external_ids: ExternalIds {
+                imdb: Some(title.imdb_id),
+                ..Default::default()
+            },
+            provider: "omdb".to_string(),
+            season_count: available(title.total_seasons).and_then(|s| s.parse().ok()),
+            cast: people(title.actors, None),
+            crew: people(title.director, Some("Director"))
+                .into_iter()
+                .chain(people(title.writer, Some("Writer")))
+                .collect(),
+            ratings: site_ratings,
+            title: title.title,
+            ..Default::default()
+        }
+    }
+
+    fn generate_sort_title(title: &str, year: Option<i32>) -> String {
+        let articles = TitleArticles::active();
+        let sort_title = articles.strip(title);
+
+        if let Some(year) = year {
+            format!("{sort_title} ({year})")
+        } else {
+            sort_title.to_string()
+        }
+    }
+}
+
+/// Value of a field OMDb may report as "N/A"
+fn available(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty() && v != "N/A")
+}
+
+/// Comma separated values, e.g. "Drama, Crime"
+fn list(value: Option<String>) -> Vec<String> {
+    available(value)
+        .map(|v| v.split(',').map(|s| s.trim().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Comma separated names; a part in parentheses is the person's role
+fn people(value: Option<String>, job: Option<&str>) -> Vec<PersonInfo> {
+    list(value)
+        .into_iter()
+        .enumerate()
+        .map(|(order, name)| {
+            let (name, role) = match name.split_once(" (") {
+                Some((name, role)) => (name.to_string(), Some(role.trim_end_matches(')'))),
+                None => (name, None),
+            };
+            PersonInfo {
+                id: String::new(),
+                name,
+                role: job.or(role).map(ToString::to_string),
+                image_url: None,
+                order: job.is_none().then_some(order as i32),
+            }
+        })
+        .collect()
+}
+
+/// First year of "2010" or "2008–2013"
+fn year_of(value: &str) -> Option<i32> {
+    value.get(..4).and_then(|y| y.parse().ok())
+}
+
+/// "16 Jul 2010" as "2010-07-16"
+fn iso_date(value: &str) -> Option<String> {
+    chrono::NaiveDate::parse_from_str(value, "%d %b %Y")
+        .ok()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// Minutes of "148 min"
+fn minutes(value: &str) -> Option<i32> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// Vote count of "2,512,345"
+fn votes(value: &str) -> Option<i32> {
+    value.replace(',', "").parse().ok()
+}
+
+/// Scores of the rating sites, on a 0-10 scale
+fn ratings(ratings: &[Rating], imdb_votes: Option<&str>) -> Vec<ExternalRating> {
+    ratings
+        .iter()
+        .filter_map(|rating| {
+            let source = match rating.source.as_str() {
+                "Internet Movie Database" => "imdb",
+                "Rotten Tomatoes" => "rotten_tomatoes",
+                "Metacritic" => "metacritic",
+                _ => return None,
+            };
+            Some(ExternalRating {
+                source: source.to_string(),
+                score: score(&rating.value)?,
+                votes: (source == "imdb")
+                    .then(|| imdb_votes.and_then(votes))
+                    .flatten(),
+            })
+        })
+        .collect()
+}
+
+/// "8.8/10", "87%" or "74/100" on a 0-10 scale
+fn score(value: &str) -> Option<f64> {
+    if let Some(percent) = value.strip_suffix('%') {
+        return percent.parse::<f64>().ok().map(|p| p / 10.0);
+    }
+
+    let (score, max) = value.split_once('/')?;
+    let (score, max) = (score.parse::<f64>().ok()?, max.parse::<f64>().ok()?);
+    (max > 0.0).then(|| score / max * 10.0)
+}
+
+#[async_trait]
+impl MetadataProvider for OmdbProvider {
+    fn id(&self) -> &'static str {
+        "omdb"
+    }
+
+    fn name(&self) -> &'static str {
+        "OMDb"
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &[MediaType::Movie, MediaType::Tv]
+    }
+
+    fn requires_api_key(&self) -> bool {
+        true
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        // Fallback search source, searched after the richer providers
+        match media_type {
+            MediaType::Movie => 20,
+            MediaType::Tv => 15,
+            MediaType::Anime => 0,
+            MediaType::Unknown => 10,
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let mut params = vec![("s", query)];
+        match options.media_type {
+            Some(MediaType::Movie) => params.push(("type", "movie")),
+            Some(MediaType::Tv | MediaType::Anime) => params.push(("type", "series")),
+            _ => {}
+        }
+        let year_str;
+        if let Some(year) = options.year {
+            year_str = year.to_string();
+            params.push(("y", &year_str));
+        }
+
+        let response: SearchResponse = self.request(&params).await?;
+        let mut results: Vec<MediaInfo> = response
+            .search
+            .into_iter()
+            .filter_map(|r| {
+                let media_type = match r.kind.as_str() {
+                    "movie" => MediaType::Movie,
+                    "series" => MediaType::Tv,
+                    // Single episodes and games
+                    _ => return None,
+                };
+                let external_ids = ExternalIds {
+                    imdb: Some(r.imdb_id.clone()),
+                    ..Default::default()
+                };
+
+                Some(
+                    MediaInfo::new(r.imdb_id, r.title, "omdb")
+                        .with_type(media_type)
+                        .with_year(year_of(&r.year))
+                        .with_poster(available(r.poster))
+                        .with_external_ids(external_ids),
+                )
+            })
+            .collect();
+
+        if results.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+        let title = self.get_title(id).await?;
+        Ok(self.title_to_metadata(title))
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        let season_str = season.to_string();
+        let episode_str = episode.to_string();
+        let ep: Episode = self
+            .request(&[
+                ("i", series_id),
+                ("Season", &season_str),
+                ("Episode", &episode_str),
+            ])
+            .await?;
+
+        Ok(EpisodeInfo {
+            id: ep.imdb_id,
+            title: ep.title,
+            season: ep.season.parse().unwrap_or(season),
+            episode: ep.episode.parse().unwrap_or(episode),
+            absolute_number: None,
+            original_title: None,
+            air_date: available(ep.released).and_then(|d| iso_date(&d)),
+            overview: available(ep.plot),
+            runtime: available(ep.runtime).and_then(|r| minutes(&r)),
+            rating: available(ep.imdb_rating).and_then(|r| r.parse().ok()),
+            still_url: available(ep.poster),
+            provider: "omdb".to_string(),
+        })
+    }
+
+    async fn enrich(&self, metadata: &mut MediaMetadata) -> Result<()> {
+        let Some(imdb_id) = metadata.external_ids.imdb.clone() else {
+            return Ok(());
+        };
+
+        let title = self.get_title(&imdb_id).await?;
+        for rating in ratings(&title.ratings, title.imdb_votes.as_deref()) {
+            if !metadata.ratings.iter().any(|r| r.source == rating.source) {
+                metadata.ratings.push(rating);
+            }
+        }
+        if metadata.overview.is_none() {
+            metadata.overview = available(title.plot);
+        }
+        if metadata.content_rating.is_none() {
+            metadata.content_rating = available(title.rated);
+        }
+
+        Ok(())
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        if source != "imdb" {
+            return Ok(None);
+        }
+
+        let title = match self.get_title(external_id).await {
+            Ok(title) => title,
+            Err(ScraperError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let metadata = self.title_to_metadata(title);
+
+        Ok(Some(
+            MediaInfo::new(metadata.id, metadata.title, "omdb")
+                .with_type(metadata.media_type)
+                .with_year(metadata.release_date.as_deref().and_then(year_of))
+                .with_poster(metadata.images.poster)
+                .with_overview(metadata.overview)
+                .with_rating(metadata.rating)
+                .with_external_ids(metadata.external_ids),
+        ))
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        // Any title will do; OMDb only checks the key on lookups
+        let result = self.request::<IgnoredAny>(&[("i", "tt0111161")]).await;
+        ProviderHealth::probe(&result, started).with_quota(self.client.quota())
+    }
+}
@@ -0,0 +1,98 @@
+use serde::Deserialize;
+
+// Search response
+#[derive(Debug, Deserialize)]
+pub struct SearchResponse {
+    #[serde(default)]
+    pub docs: Vec<SearchDoc>,
+}
+
+// Work found by a search
+#[derive(Debug, Deserialize)]
+pub struct SearchDoc {
+    /// "/works/OL45804W"
+    pub key: String,
+    pub title: String,
+    #[serde(default)]
+    pub author_name: Vec<String>,
+    pub first_publish_year: Option<i32>,
+    #[serde(default)]
+    pub isbn: Vec<String>,
+    pub cover_i: Option<i64>,
+}
+
+// Work (a book across all of its editions)
+#[derive(Debug, Deserialize)]
+pub struct Work {
+    pub key: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub description: Option<Text>,
+    #[serde(default)]
+    pub subjects: Vec<String>,
+    #[serde(default)]
+    pub covers: Vec<i64>,
+    #[serde(default)]
+    pub authors: Vec<WorkAuthor>,
+    pub first_publish_date: Option<String>,
+}
+
+/// Either a plain string or `{"type": "/type/text", "value": "..."}`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Text {
+    Plain(String),
+    Typed { value: String },
+}
+
+impl Text {
+    pub fn into_string(self) -> String {
+        match self {
+            Self::Plain(value) | Self::Typed { value } => value,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkAuthor {
+    pub author: Key,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Key {
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Author {
+    pub name: String,
+}
+
+// Edition, as returned for an ISBN
+#[derive(Debug, Deserialize)]
+pub struct Edition {
+    /// "/books/OL7353617M"
+    pub key: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub description: Option<Text>,
+    #[serde(default)]
+    pub publishers: Vec<String>,
+    /// Free-form, e.g. "1997" or "June 26, 1997"
+    pub publish_date: Option<String>,
+    pub number_of_pages: Option<i32>,
+    #[serde(default)]
+    pub isbn_13: Vec<String>,
+    #[serde(default)]
+    pub isbn_10: Vec<String>,
+    #[serde(default)]
+    pub covers: Vec<i64>,
+    /// e.g. "Harry Potter #1"
+    #[serde(default)]
+    pub series: Vec<String>,
+    /// "/languages/eng"
+    #[serde(default)]
+    pub languages: Vec<Key>,
+    #[serde(default)]
+    pub works: Vec<Key>,
+}
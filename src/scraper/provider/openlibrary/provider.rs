@@ -0,0 +1,223 @@
+use super::api_types::{Author, Edition, SearchDoc, SearchResponse, Work};
+use crate::scraper::{
+    provider::{HttpClient, NetworkOptions},
+    types::{normalize_isbn, BookInfo, BookMetadata},
+    Result, ScraperError,
+};
+use regex::Regex;
+use std::sync::LazyLock;
+
+const OPENLIBRARY_BASE_URL: &str = "https://openlibrary.org";
+const OPENLIBRARY_COVER_URL: &str = "https://covers.openlibrary.org/b/id";
+/// Open Library asks anonymous clients to keep to about one request per second
+const OPENLIBRARY_RATE_LIMIT: f64 = 1.0;
+const SEARCH_FIELDS: &str = "key,title,author_name,first_publish_year,isbn,cover_i";
+
+/// "Harry Potter #1", "Discworld, no. 3", "The Expanse ; bk. 2"
+static SERIES_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(.+?)[\s,;:(]*(?:#|no\.|nr\.|bk\.|book|vol\.?|volume)\s*(\d+(?:\.\d+)?)\)?$")
+        .unwrap()
+});
+static YEAR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d{4}\b").unwrap());
+
+/// Provider for Open Library, a free book catalogue that needs no API key
+pub struct OpenLibraryProvider {
+    client: HttpClient,
+}
+
+impl OpenLibraryProvider {
+    pub fn new() -> Self {
+        Self {
+            client: HttpClient::new(OPENLIBRARY_BASE_URL).with_rate_limit(OPENLIBRARY_RATE_LIMIT),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    /// Search books by title, optionally narrowed to an author
+    pub async fn search(
+        &self,
+        title: &str,
+        author: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BookInfo>> {
+        let limit_str = limit.clamp(1, 100).to_string();
+        let mut params = vec![("title", title), ("fields", SEARCH_FIELDS), ("limit", &limit_str)];
+        if let Some(author) = author.filter(|a| !a.trim().is_empty()) {
+            params.push(("author", author));
+        }
+
+        let response: SearchResponse = self
+            .client
+            .get_json_or_not_found("/search.json", &params)
+            .await?;
+        if response.docs.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {title}"
+            )));
+        }
+
+        Ok(response.docs.into_iter().map(doc_to_info).collect())
+    }
+
+    /// Metadata of a work, e.g. "OL45804W"
+    pub async fn get_book(&self, work_id: &str) -> Result<BookMetadata> {
+        let work: Work = self
+            .client
+            .get_json_or_not_found(&format!("/works/{work_id}.json"), &[])
+            .await?;
+        let authors = self.author_names(&work).await?;
+
+        Ok(work_to_metadata(work, authors))
+    }
+
+    /// Metadata of the edition with an ISBN, completed with its work's
+    pub async fn find_by_isbn(&self, isbn: &str) -> Result<Option<BookMetadata>> {
+        let Some(isbn) = normalize_isbn(isbn) else {
+            return Ok(None);
+        };
+
+        let edition: Edition = match self
+            .client
+            .get_json_or_not_found(&format!("/isbn/{isbn}.json"), &[])
+            .await
+        {
+            Ok(edition) => edition,
+            Err(ScraperError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let work = match edition.works.first() {
+            Some(key) => Some(
+                self.client
+                    .get_json_or_not_found::<Work>(&format!("{}.json", key.key), &[])
+                    .await?,
+            ),
+            None => None,
+        };
+        let authors = match &work {
+            Some(work) => self.author_names(work).await?,
+            None => Vec::new(),
+        };
+
+        Ok(Some(edition_to_metadata(edition, work, authors, isbn)))
+    }
+
+    async fn author_names(&self, work: &Work) -> Result<Vec<String>> {
+        let mut names = Vec::with_capacity(work.authors.len());
+        for author in &work.authors {
+            let author: Author = self
+                .client
+                .get_json_or_not_found(&format!("{}.json", author.author.key), &[])
+                .await?;
+            names.push(author.name);
+        }
+        Ok(names)
+    }
+}
+
+impl Default for OpenLibraryProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn doc_to_info(doc: SearchDoc) -> BookInfo {
+    BookInfo {
+        id: id_of(&doc.key),
+        title: doc.title,
+        authors: doc.author_name,
+        year: doc.first_publish_year,
+        isbn: doc.isbn.iter().find_map(|i| normalize_isbn(i)),
+        cover_url: doc.cover_i.map(cover_url),
+        provider: "openlibrary".to_string(),
+    }
+}
+
+fn work_to_metadata(work: Work, authors: Vec<String>) -> BookMetadata {
+    BookMetadata {
+        id: id_of(&work.key),
+        title: work.title,
+        subtitle: work.subtitle,
+        authors,
+        description: work.description.map(|d| d.into_string()),
+        published_date: work.first_publish_date.as_deref().and_then(year_in),
+        subjects: work.subjects,
+        cover_url: work.covers.iter().copied().find(|c| *c > 0).map(cover_url),
+        provider: "openlibrary".to_string(),
+        ..Default::default()
+    }
+}
+
+fn edition_to_metadata(
+    edition: Edition,
+    work: Option<Work>,
+    authors: Vec<String>,
+    isbn: String,
+) -> BookMetadata {
+    let mut metadata = match work {
+        Some(work) => work_to_metadata(work, authors),
+        None => BookMetadata {
+            id: id_of(&edition.key),
+            provider: "openlibrary".to_string(),
+            ..Default::default()
+        },
+    };
+
+    // The edition's own details win over the work's
+    metadata.title = edition.title;
+    metadata.subtitle = edition.subtitle.or(metadata.subtitle);
+    if let Some(description) = edition.description {
+        metadata.description = Some(description.into_string());
+    }
+    metadata.publisher = edition.publishers.into_iter().next();
+    if let Some(year) = edition.publish_date.as_deref().and_then(year_in) {
+        metadata.published_date = Some(year);
+    }
+    metadata.language = edition
+        .languages
+        .first()
+        .map(|l| l.key.trim_start_matches("/languages/").to_string());
+    metadata.page_count = edition.number_of_pages.filter(|p| *p > 0);
+    metadata.isbn = Some(isbn);
+    if let Some(cover) = edition.covers.iter().copied().find(|c| *c > 0) {
+        metadata.cover_url = Some(cover_url(cover));
+    }
+    if let Some((series, index)) = edition.series.iter().find_map(|s| parse_series(s)) {
+        metadata.series = Some(series);
+        metadata.series_index = index;
+    }
+
+    metadata
+}
+
+/// "OL45804W" of "/works/OL45804W"
+fn id_of(key: &str) -> String {
+    key.rsplit('/').next().unwrap_or(key).to_string()
+}
+
+fn cover_url(cover_id: i64) -> String {
+    format!("{OPENLIBRARY_COVER_URL}/{cover_id}-L.jpg")
+}
+
+/// Year of a free-form date, e.g. "June 26, 1997"
+fn year_in(value: &str) -> Option<String> {
+    YEAR_RE.find(value).map(|m| m.as_str().to_string())
+}
+
+/// Series name and position of an edition's series statement
+fn parse_series(value: &str) -> Option<(String, Option<f32>)> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    match SERIES_RE.captures(value) {
+        Some(caps) => Some((caps[1].trim().to_string(), caps[2].parse().ok())),
+        None => Some((value.to_string(), None)),
+    }
+}
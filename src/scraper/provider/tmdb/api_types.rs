@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 
 // Search responses
 #[derive(Debug, Deserialize)]
@@ -41,6 +42,16 @@ pub struct TvResult {
     pub genre_ids: Option<Vec<i32>>,
 }
 
+/// Trending lists mix movies, TV shows and people
+#[derive(Debug, Deserialize)]
+#[serde(tag = "media_type", rename_all = "lowercase")]
+pub enum TrendingResult {
+    Movie(MovieResult),
+    Tv(TvResult),
+    #[serde(other)]
+    Other,
+}
+
 // Detail responses
 #[derive(Debug, Deserialize)]
 pub struct MovieDetails {
@@ -61,8 +72,13 @@ pub struct MovieDetails {
     pub genres: Vec<Genre>,
     pub production_companies: Vec<Company>,
     pub production_countries: Vec<Country>,
+    pub belongs_to_collection: Option<CollectionRef>,
     pub external_ids: Option<ExternalIds>,
     pub credits: Option<Credits>,
+    pub alternative_titles: Option<AlternativeTitles>,
+    pub translations: Option<Translations>,
+    pub keywords: Option<Keywords>,
+    pub videos: Option<Videos>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -89,6 +105,10 @@ pub struct TvDetails {
     pub seasons: Vec<Season>,
     pub external_ids: Option<ExternalIds>,
     pub credits: Option<Credits>,
+    pub alternative_titles: Option<AlternativeTitles>,
+    pub translations: Option<Translations>,
+    pub keywords: Option<Keywords>,
+    pub videos: Option<Videos>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -104,6 +124,13 @@ pub struct EpisodeDetails {
     pub vote_average: Option<f64>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SeasonDetails {
+    pub id: i64,
+    pub season_number: i32,
+    pub episodes: Vec<EpisodeDetails>,
+}
+
 // Common types
 #[derive(Debug, Deserialize)]
 pub struct Genre {
@@ -168,6 +195,144 @@ pub struct CrewMember {
     pub profile_path: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Videos {
+    pub results: Vec<Video>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Video {
+    pub name: String,
+    pub key: String,
+    /// "YouTube" or "Vimeo"
+    pub site: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub official: bool,
+    pub iso_639_1: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Keywords {
+    /// "keywords" for movies, "results" for TV shows
+    #[serde(alias = "results")]
+    pub keywords: Vec<Genre>,
+}
+
+// Titles in other languages and regions, with `append_to_response`
+#[derive(Debug, Deserialize)]
+pub struct TitleVariants {
+    pub alternative_titles: Option<AlternativeTitles>,
+    pub translations: Option<Translations>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlternativeTitles {
+    /// "titles" for movies, "results" for TV shows
+    #[serde(alias = "results")]
+    pub titles: Vec<AlternativeTitle>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlternativeTitle {
+    pub iso_3166_1: Option<String>,
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Translations {
+    pub translations: Vec<Translation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Translation {
+    pub iso_639_1: Option<String>,
+    pub iso_3166_1: Option<String>,
+    pub data: TranslationData,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranslationData {
+    /// Movies
+    pub title: Option<String>,
+    /// TV shows
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub tagline: Option<String>,
+}
+
+// Image galleries
+#[derive(Debug, Deserialize)]
+pub struct ImagesResponse {
+    #[serde(default)]
+    pub posters: Vec<ImageEntry>,
+    #[serde(default)]
+    pub backdrops: Vec<ImageEntry>,
+    #[serde(default)]
+    pub logos: Vec<ImageEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageEntry {
+    pub file_path: String,
+    /// `None` for textless images
+    pub iso_639_1: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub vote_average: Option<f64>,
+    pub vote_count: Option<i32>,
+}
+
+// Collections
+#[derive(Debug, Deserialize)]
+pub struct CollectionRef {
+    pub id: i64,
+    pub name: String,
+    pub poster_path: Option<String>,
+    pub backdrop_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionDetails {
+    pub id: i64,
+    pub name: String,
+    pub overview: Option<String>,
+    pub poster_path: Option<String>,
+    pub backdrop_path: Option<String>,
+    pub parts: Vec<MovieResult>,
+}
+
+// Streaming availability, by region
+#[derive(Debug, Deserialize)]
+pub struct WatchProvidersResponse {
+    #[serde(default)]
+    pub results: HashMap<String, RegionProviders>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegionProviders {
+    pub link: Option<String>,
+    #[serde(default)]
+    pub flatrate: Vec<WatchProviderEntry>,
+    #[serde(default)]
+    pub ads: Vec<WatchProviderEntry>,
+    #[serde(default)]
+    pub free: Vec<WatchProviderEntry>,
+    #[serde(default)]
+    pub rent: Vec<WatchProviderEntry>,
+    #[serde(default)]
+    pub buy: Vec<WatchProviderEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchProviderEntry {
+    pub provider_id: i64,
+    pub provider_name: String,
+    pub logo_path: Option<String>,
+    pub display_priority: Option<i32>,
+}
+
 // Find by external ID
 #[derive(Debug, Deserialize)]
 pub struct FindResponse {
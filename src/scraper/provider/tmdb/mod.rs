@@ -1,4 +1,4 @@
 mod api_types;
 mod provider;
 
-pub use provider::TmdbProvider;
+pub use provider::{TmdbCredential, TmdbProvider};
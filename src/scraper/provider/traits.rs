@@ -1,8 +1,10 @@
+use super::health::{HealthStatus, ProviderHealth};
 use crate::scraper::{
-    Result,
-    types::{EpisodeInfo, MediaInfo, MediaMetadata, MediaType},
+    Result, ScraperError,
+    types::{EpisodeInfo, MediaInfo, MediaMetadata, MediaType, WatchAvailability},
 };
 use async_trait::async_trait;
+use serde::Deserialize;
 
 /// Search options for providers
 #[derive(Debug, Clone, Default)]
@@ -47,6 +49,85 @@ impl SearchOptions {
     }
 }
 
+/// List of titles to browse rather than search
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverList {
+    /// Most watched today
+    TrendingDay,
+    /// Most watched this week
+    #[default]
+    TrendingWeek,
+    /// Most popular overall
+    Popular,
+    /// Filtered and sorted with the other [`DiscoverOptions`]
+    Discover,
+}
+
+/// Discovery options for providers
+#[derive(Debug, Clone, Default)]
+pub struct DiscoverOptions {
+    /// Which list to fetch
+    pub list: DiscoverList,
+    /// Media type filter; `None` mixes movies and TV shows where supported
+    pub media_type: Option<MediaType>,
+    /// Page of results, from 1
+    pub page: Option<u32>,
+    /// Preferred language (ISO 639-1)
+    pub language: Option<String>,
+    /// Release year filter
+    pub year: Option<i32>,
+    /// Provider-specific genre IDs, e.g. "16,10765"
+    pub genres: Option<String>,
+    /// Provider-specific sort order, e.g. "vote_average.desc"
+    pub sort_by: Option<String>,
+}
+
+impl DiscoverOptions {
+    #[must_use]
+    pub fn new(list: DiscoverList) -> Self {
+        Self {
+            list,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub const fn with_type(mut self, media_type: Option<MediaType>) -> Self {
+        self.media_type = media_type;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_page(mut self, page: Option<u32>) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_year(mut self, year: Option<i32>) -> Self {
+        self.year = year;
+        self
+    }
+
+    #[must_use]
+    pub fn with_genres(mut self, genres: Option<String>) -> Self {
+        self.genres = genres;
+        self
+    }
+
+    #[must_use]
+    pub fn with_sort_by(mut self, sort_by: Option<String>) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+}
+
 /// Core trait for metadata providers
 #[async_trait]
 pub trait MetadataProvider: Send + Sync {
@@ -79,9 +160,57 @@ pub trait MetadataProvider: Send + Sync {
     /// Get detailed metadata by provider ID
     async fn get_metadata(&self, id: &str, media_type: MediaType) -> Result<MediaMetadata>;
 
+    /// Get detailed metadata with titles and overviews in `language` (e.g.
+    /// `ja`), for filling in what the preferred languages leave blank
+    async fn get_metadata_in(
+        &self,
+        _id: &str,
+        _media_type: MediaType,
+        _language: &str,
+    ) -> Result<MediaMetadata> {
+        Err(ScraperError::NotFound(format!(
+            "{} does not provide metadata by language",
+            self.name()
+        )))
+    }
+
     /// Get episode details
     async fn get_episode(&self, series_id: &str, season: i32, episode: i32) -> Result<EpisodeInfo>;
 
+    /// Get all episodes of a season, in one request where the API allows
+    async fn get_season(&self, _series_id: &str, _season: i32) -> Result<Vec<EpisodeInfo>> {
+        Err(ScraperError::NotFound(format!(
+            "{} does not provide season details",
+            self.name()
+        )))
+    }
+
+    /// Trending, popular or filtered titles, for browsing
+    async fn discover(&self, _options: &DiscoverOptions) -> Result<Vec<MediaInfo>> {
+        Err(ScraperError::NotFound(format!(
+            "{} does not provide discovery lists",
+            self.name()
+        )))
+    }
+
+    /// Streaming services, stores and channels offering a movie or TV show
+    async fn get_watch_providers(
+        &self,
+        _id: &str,
+        _media_type: MediaType,
+    ) -> Result<WatchAvailability> {
+        Err(ScraperError::NotFound(format!(
+            "{} does not provide streaming availability",
+            self.name()
+        )))
+    }
+
+    /// Add what this provider knows to metadata fetched from another provider,
+    /// matched through its external IDs
+    async fn enrich(&self, _metadata: &mut MediaMetadata) -> Result<()> {
+        Ok(())
+    }
+
     /// Search by external ID (e.g., IMDB ID)
     async fn find_by_external_id(
         &self,
@@ -90,6 +219,13 @@ pub trait MetadataProvider: Send + Sync {
     ) -> Result<Option<MediaInfo>> {
         Ok(None)
     }
+
+    /// Check that the API is reachable and accepts our credentials, with a
+    /// request as cheap as the API allows
+    async fn health(&self) -> ProviderHealth {
+        ProviderHealth::new(HealthStatus::Unknown)
+            .with_message(format!("{} has no health check", self.name()))
+    }
 }
 
 /// Provider capability flags
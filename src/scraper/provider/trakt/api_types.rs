@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+// Search and ID lookup responses
+#[derive(Debug, Deserialize)]
+pub struct SearchResult {
+    /// "movie" or "show"
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub movie: Option<Item>,
+    pub show: Option<Item>,
+}
+
+// Movie and show summaries, with `extended=full`
+#[derive(Debug, Deserialize)]
+pub struct Item {
+    pub title: String,
+    pub year: Option<i32>,
+    pub ids: Ids,
+    pub tagline: Option<String>,
+    pub overview: Option<String>,
+    /// Movie release date
+    pub released: Option<String>,
+    /// First airing of a show, e.g. "2008-01-20T02:00:00.000Z"
+    pub first_aired: Option<String>,
+    pub runtime: Option<i32>,
+    pub certification: Option<String>,
+    pub network: Option<String>,
+    pub status: Option<String>,
+    pub rating: Option<f64>,
+    pub votes: Option<i32>,
+    pub language: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    pub aired_episodes: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Ids {
+    pub trakt: Option<i64>,
+    pub slug: Option<String>,
+    pub imdb: Option<String>,
+    pub tmdb: Option<i64>,
+    pub tvdb: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Alias {
+    pub title: String,
+    /// ISO 3166-1 country code
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Episode {
+    pub season: i32,
+    pub number: i32,
+    pub title: Option<String>,
+    pub ids: Ids,
+    pub number_abs: Option<i32>,
+    pub overview: Option<String>,
+    pub rating: Option<f64>,
+    pub first_aired: Option<String>,
+    pub runtime: Option<i32>,
+}
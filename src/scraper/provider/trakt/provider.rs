@@ -0,0 +1,353 @@
+use super::api_types::{Alias, Episode, Ids, Item, SearchResult};
+use crate::scraper::{
+    parser::TitleArticles,
+    provider::{HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions},
+    types::{EpisodeInfo, ExternalIds, ExternalRating, MediaInfo, MediaMetadata, MediaType},
+    Result, ScraperError,
+};
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use std::time::Instant;
+
+const TRAKT_BASE_URL: &str = "https://api.trakt.tv";
+const TRAKT_API_VERSION: &str = "2";
+/// Trakt allows 1000 GET requests every 5 minutes
+const TRAKT_RATE_LIMIT: f64 = 3.0;
+
+/// Provider for Trakt, mostly used to cross-reference IDs and add aliases and
+/// community ratings to other providers' metadata
+pub struct TraktProvider {
+    client: HttpClient,
+}
+
+impl TraktProvider {
+    /// Provider authenticated with `client_id`, which fails when it can't
+    /// be sent in a header
+    pub fn new(client_id: impl Into<String>) -> Result<Self> {
+        Ok(Self {
+            client: HttpClient::new(TRAKT_BASE_URL)
+                .with_header("content-type", "application/json")?
+                .with_header("trakt-api-key", &client_id.into())?
+                .with_header("trakt-api-version", TRAKT_API_VERSION)?
+                .with_rate_limit(TRAKT_RATE_LIMIT),
+        })
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    /// Alternative titles of a movie or show, without duplicates of `title`
+    async fn aliases(&self, path: &str, id: &str, title: &str) -> Result<Vec<String>> {
+        let aliases: Vec<Alias> = self
+            .client
+            .get_json_or_not_found(&format!("/{path}/{id}/aliases"), &[])
+            .await?;
+
+        let mut titles: Vec<String> = Vec::new();
+        for alias in aliases {
+            if alias.title != title && !titles.contains(&alias.title) {
+                titles.push(alias.title);
+            }
+        }
+        Ok(titles)
+    }
+
+    /// Movie or show with an external ID (imdb, tmdb, tvdb or trakt)
+    async fn lookup(
+        &self,
+        source: &str,
+        id: &str,
+        media_type: MediaType,
+    ) -> Result<Option<(MediaType, Item)>> {
+        let mut params = vec![("extended", "full")];
+        match media_type {
+            MediaType::Movie => params.push(("type", "movie")),
+            MediaType::Tv | MediaType::Anime => params.push(("type", "show")),
+            MediaType::Unknown => {}
+        }
+
+        let results: Vec<SearchResult> = self
+            .client
+            .get_json_or_not_found(&format!("/search/{source}/{id}"), &params)
+            .await?;
+        Ok(results.into_iter().find_map(into_item))
+    }
+
+    fn item_to_metadata(
+        &self,
+        item: Item,
+        media_type: MediaType,
+        aliases: Vec<String>,
+    ) -> MediaMetadata {
+        let rating = trakt_rating(&item);
+        let release_date = item.released.or(item.first_aired).map(date_of);
+
+        MediaMetadata {
+            id: item_id(&item.ids).unwrap_or_default(),
+            sort_title: Some(Self::generate_sort_title(
+                &item.title,
+                item.year,
+                item.language.as_deref(),
+            )),
+            aliases,
+            media_type,
+            tagline: item.tagline.filter(|t| !t.is_empty()),
+            overview: item.overview.filter(|o| !o.is_empty()),
+            release_date,
+            runtime: item.runtime.filter(|r| *r > 0),
+            rating: item.rating,
+            vote_count: item.votes,
+            genres: item.genres,
+            studios: item.network.into_iter().collect(),
+            language: item.language,
+            content_rating: item.certification.filter(|c| !c.is_empty()),
+            status: item.status,
+            external_ids: external_ids(&item.ids),
+            provider: "trakt".to_string(),
+            episode_count: item.aired_episodes,
+            ratings: rating.into_iter().collect(),
+            title: item.title,
+            ..Default::default()
+        }
+    }
+
+    fn item_to_info(media_type: MediaType, item: Item) -> MediaInfo {
+        MediaInfo::new(item_id(&item.ids).unwrap_or_default(), item.title, "trakt")
+            .with_type(media_type)
+            .with_year(item.year)
+            .with_overview(item.overview.filter(|o| !o.is_empty()))
+            .with_rating(item.rating)
+            .with_external_ids(external_ids(&item.ids))
+    }
+
+    fn generate_sort_title(title: &str, year: Option<i32>, language: Option<&str>) -> String {
+        let articles = TitleArticles::active();
+        let sort_title = articles.strip_in(title, language);
+
+        if let Some(year) = year {
+            format!("{sort_title} ({year})")
+        } else {
+            sort_title.to_string()
+        }
+    }
+}
+
+/// Trakt keeps movies and shows apart, with IDs of their own
+const fn path_of(media_type: MediaType) -> &'static str {
+    match media_type {
+        MediaType::Movie | MediaType::Unknown => "movies",
+        MediaType::Tv | MediaType::Anime => "shows",
+    }
+}
+
+fn into_item(result: SearchResult) -> Option<(MediaType, Item)> {
+    match result.kind.as_str() {
+        "movie" => Some((MediaType::Movie, result.movie?)),
+        "show" => Some((MediaType::Tv, result.show?)),
+        // People, episodes and lists
+        _ => None,
+    }
+}
+
+fn item_id(ids: &Ids) -> Option<String> {
+    ids.trakt.map(|id| id.to_string())
+}
+
+fn external_ids(ids: &Ids) -> ExternalIds {
+    ExternalIds {
+        imdb: ids.imdb.clone(),
+        tmdb: ids.tmdb.map(|id| id.to_string()),
+        tvdb: ids.tvdb.map(|id| id.to_string()),
+        trakt: item_id(ids),
+        ..Default::default()
+    }
+}
+
+fn trakt_rating(item: &Item) -> Option<ExternalRating> {
+    Some(ExternalRating {
+        source: "trakt".to_string(),
+        score: item.rating.filter(|_| item.votes.unwrap_or(0) > 0)?,
+        votes: item.votes,
+    })
+}
+
+/// "2008-01-20" of "2008-01-20T02:00:00.000Z"
+fn date_of(mut value: String) -> String {
+    if value.is_char_boundary(10) {
+        value.truncate(10);
+    }
+    value
+}
+
+#[async_trait]
+impl MetadataProvider for TraktProvider {
+    fn id(&self) -> &'static str {
+        "trakt"
+    }
+
+    fn name(&self) -> &'static str {
+        "Trakt"
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &[MediaType::Movie, MediaType::Tv]
+    }
+
+    fn requires_api_key(&self) -> bool {
+        true
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        // Mostly a cross-reference, searched after the richer providers
+        match media_type {
+            MediaType::Movie | MediaType::Tv => 15,
+            MediaType::Anime => 0,
+            MediaType::Unknown => 5,
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let kind = match options.media_type {
+            Some(MediaType::Movie) => "movie",
+            Some(MediaType::Tv | MediaType::Anime) => "show",
+            _ => "movie,show",
+        };
+        let mut params = vec![("query", query), ("extended", "full")];
+        let year_str;
+        if let Some(year) = options.year {
+            year_str = year.to_string();
+            params.push(("years", &year_str));
+        }
+
+        let results: Vec<SearchResult> = self
+            .client
+            .get_json_or_not_found(&format!("/search/{kind}"), &params)
+            .await?;
+        let mut results: Vec<MediaInfo> = results
+            .into_iter()
+            .filter_map(into_item)
+            .map(|(media_type, item)| Self::item_to_info(media_type, item))
+            .collect();
+
+        if results.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, id: &str, media_type: MediaType) -> Result<MediaMetadata> {
+        let path = path_of(media_type);
+        let item: Item = self
+            .client
+            .get_json_or_not_found(&format!("/{path}/{id}"), &[("extended", "full")])
+            .await?;
+        let aliases = self.aliases(path, id, &item.title).await?;
+
+        let media_type = if path == "movies" {
+            MediaType::Movie
+        } else {
+            media_type
+        };
+        Ok(self.item_to_metadata(item, media_type, aliases))
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        let ep: Episode = self
+            .client
+            .get_json_or_not_found(
+                &format!("/shows/{series_id}/seasons/{season}/episodes/{episode}"),
+                &[("extended", "full")],
+            )
+            .await?;
+
+        Ok(EpisodeInfo {
+            id: item_id(&ep.ids).unwrap_or_default(),
+            title: ep.title.unwrap_or_default(),
+            season: ep.season,
+            episode: ep.number,
+            absolute_number: ep.number_abs,
+            original_title: None,
+            air_date: ep.first_aired.map(date_of),
+            overview: ep.overview.filter(|o| !o.is_empty()),
+            runtime: ep.runtime.filter(|r| *r > 0),
+            rating: ep.rating,
+            still_url: None,
+            provider: "trakt".to_string(),
+        })
+    }
+
+    async fn enrich(&self, metadata: &mut MediaMetadata) -> Result<()> {
+        let ids = &metadata.external_ids;
+        let Some((source, id)) = [("imdb", &ids.imdb), ("tmdb", &ids.tmdb), ("tvdb", &ids.tvdb)]
+            .into_iter()
+            .find_map(|(source, id)| Some((source, id.clone()?)))
+        else {
+            return Ok(());
+        };
+
+        let Some((media_type, item)) = self.lookup(source, &id, metadata.media_type).await? else {
+            return Ok(());
+        };
+
+        if let Some(rating) = trakt_rating(&item)
+            && !metadata.ratings.iter().any(|r| r.source == rating.source)
+        {
+            metadata.ratings.push(rating);
+        }
+
+        // IDs already known win over Trakt's
+        let mut external_ids = external_ids(&item.ids);
+        external_ids.merge(&metadata.external_ids);
+        metadata.external_ids = external_ids;
+
+        if let Some(trakt_id) = item_id(&item.ids) {
+            let aliases = self
+                .aliases(path_of(media_type), &trakt_id, &metadata.title)
+                .await?;
+            for alias in aliases {
+                if !metadata.aliases.contains(&alias) {
+                    metadata.aliases.push(alias);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        if !matches!(source, "imdb" | "tmdb" | "tvdb" | "trakt") {
+            return Ok(None);
+        }
+
+        match self.lookup(source, external_id, MediaType::Unknown).await {
+            Ok(found) => Ok(found.map(|(media_type, item)| Self::item_to_info(media_type, item))),
+            Err(ScraperError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        let result = self.client.get_json_or_not_found::<IgnoredAny>("/genres/movies", &[]).await;
+        ProviderHealth::probe(&result, started).with_quota(self.client.quota())
+    }
+}
@@ -0,0 +1,206 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Every response wraps its payload
+#[derive(Debug, Deserialize)]
+pub struct Response<T> {
+    pub data: T,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginData {
+    pub token: String,
+}
+
+// Search responses
+#[derive(Debug, Deserialize)]
+pub struct SearchResult {
+    pub tvdb_id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub year: Option<String>,
+    pub image_url: Option<String>,
+    pub overview: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Names keyed by ISO 639-2 language code
+    #[serde(default)]
+    pub translations: HashMap<String, String>,
+    /// Overviews keyed by ISO 639-2 language code
+    #[serde(default)]
+    pub overviews: HashMap<String, String>,
+    #[serde(default)]
+    pub remote_ids: Vec<RemoteId>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoteIdResult {
+    pub series: Option<BaseRecord>,
+    pub movie: Option<BaseRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseRecord {
+    pub id: i64,
+    pub name: String,
+    pub image: Option<String>,
+    pub year: Option<String>,
+    pub overview: Option<String>,
+    pub score: Option<f64>,
+}
+
+// Detail responses
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesExtended {
+    pub id: i64,
+    pub name: String,
+    pub image: Option<String>,
+    pub first_aired: Option<String>,
+    pub last_aired: Option<String>,
+    pub original_language: Option<String>,
+    pub average_runtime: Option<i32>,
+    pub status: Option<Status>,
+    #[serde(default)]
+    pub genres: Vec<Named>,
+    #[serde(default)]
+    pub companies: Vec<Named>,
+    #[serde(default)]
+    pub artworks: Vec<Artwork>,
+    #[serde(default)]
+    pub remote_ids: Vec<RemoteId>,
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+    #[serde(default)]
+    pub characters: Vec<Character>,
+    pub translations: Option<Translations>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovieExtended {
+    pub id: i64,
+    pub name: String,
+    pub image: Option<String>,
+    pub first_release: Option<Release>,
+    pub original_language: Option<String>,
+    pub runtime: Option<i32>,
+    pub status: Option<Status>,
+    #[serde(default)]
+    pub genres: Vec<Named>,
+    #[serde(default)]
+    pub companies: Option<MovieCompanies>,
+    #[serde(default)]
+    pub artworks: Vec<Artwork>,
+    #[serde(default)]
+    pub remote_ids: Vec<RemoteId>,
+    #[serde(default)]
+    pub characters: Vec<Character>,
+    pub translations: Option<Translations>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MovieCompanies {
+    #[serde(default)]
+    pub production: Vec<Named>,
+    #[serde(default)]
+    pub studio: Vec<Named>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Status {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Named {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Artwork {
+    pub image: String,
+    /// Artwork type ID, see `/artwork/types`
+    #[serde(rename = "type")]
+    pub kind: i32,
+    #[serde(default)]
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteId {
+    pub id: String,
+    pub source_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Season {
+    pub id: i64,
+    pub number: i32,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<SeasonType>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeasonType {
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Character {
+    pub name: Option<String>,
+    pub person_name: Option<String>,
+    pub people_id: Option<i64>,
+    pub person_img_url: Option<String>,
+    pub people_type: Option<String>,
+    pub sort: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Translations {
+    #[serde(default)]
+    pub name_translations: Vec<Translation>,
+    #[serde(default)]
+    pub overview_translations: Vec<Translation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Translation {
+    pub language: String,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+}
+
+// Episode responses
+#[derive(Debug, Deserialize)]
+pub struct EpisodePage {
+    #[serde(default)]
+    pub episodes: Vec<Episode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Episode {
+    pub id: i64,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub aired: Option<String>,
+    pub runtime: Option<i32>,
+    pub season_number: i32,
+    pub number: i32,
+    pub absolute_number: Option<i32>,
+    pub image: Option<String>,
+}
@@ -0,0 +1,581 @@
+use super::api_types::{
+    Artwork, Character, EpisodePage, LoginData, MovieExtended, RemoteId, RemoteIdResult, Response,
+    SearchResult, SeriesExtended, Translation, Translations,
+};
+use crate::scraper::{
+    parser::TitleArticles,
+    provider::{
+        language_chain, HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions,
+    },
+    types::{
+        EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType, PersonInfo,
+        SeasonInfo,
+    },
+    Result, ScraperError,
+};
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+const TVDB_BASE_URL: &str = "https://api4.thetvdb.com/v4";
+/// Language of names and overviews when none is requested (ISO 639-2)
+const DEFAULT_LANGUAGE: &str = "eng";
+/// TVDB publishes no limit; stay well below what its CDN tolerates
+const TVDB_RATE_LIMIT: f64 = 10.0;
+
+// Artwork type IDs, see `/artwork/types`
+const SERIES_BANNER: i32 = 1;
+const SERIES_POSTER: i32 = 2;
+const SERIES_BACKGROUND: i32 = 3;
+const SERIES_CLEARLOGO: i32 = 23;
+const MOVIE_POSTER: i32 = 14;
+const MOVIE_BACKGROUND: i32 = 15;
+const MOVIE_BANNER: i32 = 16;
+const MOVIE_CLEARLOGO: i32 = 25;
+
+pub struct TvdbProvider {
+    client: HttpClient,
+    api_key: String,
+    /// Bearer token from `/login`, valid for a month
+    token: Mutex<Option<String>>,
+    /// Preferred languages for titles and overviews, most preferred first
+    languages: Vec<String>,
+}
+
+impl TvdbProvider {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: HttpClient::new(TVDB_BASE_URL).with_rate_limit(TVDB_RATE_LIMIT),
+            api_key: api_key.into(),
+            token: Mutex::new(None),
+            languages: Vec::new(),
+        }
+    }
+
+    /// Prefer titles and overviews in `languages` (e.g. `zh-CN`), in order,
+    /// falling back to English where none has a translation
+    #[must_use]
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// TVDB languages to try in order: `requested`, the preferred ones, then
+    /// English
+    fn languages(&self, requested: Option<&str>) -> Vec<&'static str> {
+        let mut languages: Vec<&'static str> = Vec::new();
+        let chain = language_chain(requested, &self.languages);
+        for code in chain.into_iter().map(Some).map(tvdb_language).chain([DEFAULT_LANGUAGE]) {
+            if !languages.contains(&code) {
+                languages.push(code);
+            }
+        }
+        languages
+    }
+
+    /// Details of a movie or series, in `language` before the preferred ones
+    async fn get_details(
+        &self,
+        id: &str,
+        media_type: MediaType,
+        language: Option<&str>,
+    ) -> Result<MediaMetadata> {
+        let languages = self.languages(language);
+        match media_type {
+            MediaType::Movie => self.get_movie_metadata(id, &languages).await,
+            MediaType::Tv | MediaType::Anime => self.get_series_metadata(id, &languages).await,
+            MediaType::Unknown => {
+                // Try series first, then movie
+                if let Ok(metadata) = self.get_series_metadata(id, &languages).await {
+                    return Ok(metadata);
+                }
+                self.get_movie_metadata(id, &languages).await
+            }
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    /// Current bearer token, logging in when there is none yet
+    async fn token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(ref token) = *token {
+            return Ok(token.clone());
+        }
+
+        let body = serde_json::json!({ "apikey": self.api_key });
+        let login: Response<LoginData> = self.client.post_json("/login", &body).await?;
+        *token = Some(login.data.token.clone());
+        Ok(login.data.token)
+    }
+
+    async fn request<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        let token = self.token().await?;
+        let response: Result<Response<T>> =
+            self.client.get_with_bearer(endpoint, params, &token).await;
+
+        match response {
+            // The token expired: log in again once
+            Err(ScraperError::Api { status: 401, .. }) => {
+                self.token.lock().await.take();
+                let token = self.token().await?;
+                let response: Response<T> =
+                    self.client.get_with_bearer(endpoint, params, &token).await?;
+                Ok(response.data)
+            }
+            response => Ok(response?.data),
+        }
+    }
+
+    fn search_result_to_info(
+        &self,
+        result: SearchResult,
+        languages: &[&str],
+    ) -> Option<MediaInfo> {
+        let media_type = match result.kind.as_deref() {
+            Some("series") => MediaType::Tv,
+            Some("movie") => MediaType::Movie,
+            // People, companies and lists
+            _ => return None,
+        };
+
+        let title = languages
+            .iter()
+            .find_map(|l| result.translations.get(*l))
+            .cloned()
+            .unwrap_or_else(|| result.name.clone());
+        let overview = languages
+            .iter()
+            .find_map(|l| result.overviews.get(*l))
+            .cloned()
+            .or(result.overview);
+
+        let mut external_ids = ExternalIds {
+            tvdb: Some(result.tvdb_id.clone()),
+            ..Default::default()
+        };
+        apply_remote_ids(&mut external_ids, &result.remote_ids);
+
+        let mut info = MediaInfo::new(result.tvdb_id, title.clone(), "tvdb")
+            .with_type(media_type)
+            .with_year(result.year.and_then(|y| y.parse().ok()))
+            .with_original_title((result.name != title).then_some(result.name))
+            .with_poster(result.image_url)
+            .with_overview(overview)
+            .with_external_ids(external_ids);
+        for alias in result.aliases {
+            info = info.with_alt_title(alias);
+        }
+
+        Some(info)
+    }
+
+    async fn get_series_metadata(&self, id: &str, languages: &[&str]) -> Result<MediaMetadata> {
+        let endpoint = format!("/series/{id}/extended");
+        let series: SeriesExtended = self.request(&endpoint, &[("meta", "translations")]).await?;
+
+        let (title, overview) = translated(series.translations.as_ref(), languages);
+        let title = title.unwrap_or_else(|| series.name.clone());
+        let year = year_of(series.first_aired.as_deref());
+
+        let mut external_ids = ExternalIds {
+            tvdb: Some(series.id.to_string()),
+            ..Default::default()
+        };
+        apply_remote_ids(&mut external_ids, &series.remote_ids);
+
+        // Seasons come once per ordering; aired order is the one files follow
+        let seasons: Vec<SeasonInfo> = series
+            .seasons
+            .into_iter()
+            .filter(|s| s.kind.as_ref().is_some_and(|k| k.kind == "official"))
+            .map(|s| SeasonInfo {
+                number: s.number,
+                name: s.name,
+                overview: None,
+                air_date: None,
+                episode_count: None,
+                poster_url: s.image,
+            })
+            .collect();
+        let (cast, crew) = people(series.characters);
+
+        Ok(MediaMetadata {
+            id: series.id.to_string(),
+            sort_title: Some(Self::generate_sort_title(&title, year)),
+            original_title: (series.name != title).then_some(series.name),
+            title,
+            media_type: MediaType::Tv,
+            overview,
+            release_date: series.first_aired,
+            end_date: series.last_aired,
+            runtime: series.average_runtime,
+            genres: series.genres.into_iter().map(|g| g.name).collect(),
+            studios: series.companies.into_iter().map(|c| c.name).collect(),
+            language: series.original_language,
+            status: series.status.and_then(|s| s.name),
+            images: ImageSet {
+                poster: best_artwork(&series.artworks, SERIES_POSTER).or(series.image),
+                backdrop: best_artwork(&series.artworks, SERIES_BACKGROUND),
+                logo: best_artwork(&series.artworks, SERIES_CLEARLOGO),
+                banner: best_artwork(&series.artworks, SERIES_BANNER),
+                ..Default::default()
+            },
+            external_ids,
+            provider: "tvdb".to_string(),
+            season_count: Some(seasons.iter().filter(|s| s.number > 0).count() as i32),
+            seasons,
+            cast,
+            crew,
+            ..Default::default()
+        })
+    }
+
+    async fn get_movie_metadata(&self, id: &str, languages: &[&str]) -> Result<MediaMetadata> {
+        let endpoint = format!("/movies/{id}/extended");
+        let movie: MovieExtended = self.request(&endpoint, &[("meta", "translations")]).await?;
+
+        let (title, overview) = translated(movie.translations.as_ref(), languages);
+        let title = title.unwrap_or_else(|| movie.name.clone());
+        let release_date = movie.first_release.and_then(|r| r.date);
+        let year = year_of(release_date.as_deref());
+
+        let mut external_ids = ExternalIds {
+            tvdb: Some(movie.id.to_string()),
+            ..Default::default()
+        };
+        apply_remote_ids(&mut external_ids, &movie.remote_ids);
+
+        let companies = movie.companies.unwrap_or_default();
+        let (cast, crew) = people(movie.characters);
+
+        Ok(MediaMetadata {
+            id: movie.id.to_string(),
+            sort_title: Some(Self::generate_sort_title(&title, year)),
+            original_title: (movie.name != title).then_some(movie.name),
+            title,
+            media_type: MediaType::Movie,
+            overview,
+            release_date,
+            runtime: movie.runtime,
+            genres: movie.genres.into_iter().map(|g| g.name).collect(),
+            studios: companies
+                .studio
+                .into_iter()
+                .chain(companies.production)
+                .map(|c| c.name)
+                .collect(),
+            language: movie.original_language,
+            status: movie.status.and_then(|s| s.name),
+            images: ImageSet {
+                poster: best_artwork(&movie.artworks, MOVIE_POSTER).or(movie.image),
+                backdrop: best_artwork(&movie.artworks, MOVIE_BACKGROUND),
+                logo: best_artwork(&movie.artworks, MOVIE_CLEARLOGO),
+                banner: best_artwork(&movie.artworks, MOVIE_BANNER),
+                ..Default::default()
+            },
+            external_ids,
+            provider: "tvdb".to_string(),
+            cast,
+            crew,
+            ..Default::default()
+        })
+    }
+
+    fn generate_sort_title(title: &str, year: Option<i32>) -> String {
+        let articles = TitleArticles::active();
+        let sort_title = articles.strip(title);
+
+        if let Some(year) = year {
+            format!("{sort_title} ({year})")
+        } else {
+            sort_title.to_string()
+        }
+    }
+}
+
+/// TVDB language code (ISO 639-2) for an ISO 639-1 code, English when unknown
+fn tvdb_language(language: Option<&str>) -> &'static str {
+    let code = language
+        .and_then(|l| l.split(['-', '_']).next())
+        .unwrap_or("en");
+
+    match code.to_lowercase().as_str() {
+        "ja" => "jpn",
+        "zh" => "zho",
+        "ko" => "kor",
+        "fr" => "fra",
+        "de" => "deu",
+        "es" => "spa",
+        "it" => "ita",
+        "pt" => "por",
+        "ru" => "rus",
+        _ => DEFAULT_LANGUAGE,
+    }
+}
+
+/// Name and overview, each in the first of `languages` translating it
+fn translated(
+    translations: Option<&Translations>,
+    languages: &[&str],
+) -> (Option<String>, Option<String>) {
+    let Some(translations) = translations else {
+        return (None, None);
+    };
+
+    (
+        pick(&translations.name_translations, languages, |t| t.name.as_ref()),
+        pick(&translations.overview_translations, languages, |t| t.overview.as_ref()),
+    )
+}
+
+fn pick(
+    translations: &[Translation],
+    languages: &[&str],
+    field: impl Fn(&Translation) -> Option<&String>,
+) -> Option<String> {
+    languages.iter().find_map(|lang| {
+        translations
+            .iter()
+            .filter(|t| t.language == *lang)
+            .find_map(|t| field(t).filter(|v| !v.is_empty()).cloned())
+    })
+}
+
+/// Highest rated artwork of one type
+fn best_artwork(artworks: &[Artwork], kind: i32) -> Option<String> {
+    artworks
+        .iter()
+        .filter(|a| a.kind == kind)
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+        .map(|a| a.image.clone())
+}
+
+/// Copy the IDs other providers use from TVDB's remote IDs
+fn apply_remote_ids(external_ids: &mut ExternalIds, remote_ids: &[RemoteId]) {
+    for remote in remote_ids {
+        let slot = match remote.source_name.as_str() {
+            "IMDB" => &mut external_ids.imdb,
+            "TheMovieDB.com" | "TheMovieDB" => &mut external_ids.tmdb,
+            "MyAnimeList" => &mut external_ids.mal,
+            "AniDB" => &mut external_ids.anidb,
+            _ => continue,
+        };
+        slot.get_or_insert_with(|| remote.id.clone());
+    }
+}
+
+/// Split characters into actors and crew
+fn people(characters: Vec<Character>) -> (Vec<PersonInfo>, Vec<PersonInfo>) {
+    let mut cast = Vec::new();
+    let mut crew = Vec::new();
+
+    for character in characters {
+        let Some(name) = character.person_name else {
+            continue;
+        };
+        let id = character.people_id.map(|id| id.to_string()).unwrap_or_default();
+
+        match character.people_type.as_deref() {
+            Some("Actor") if cast.len() < 20 => cast.push(PersonInfo {
+                id,
+                name,
+                role: character.name,
+                image_url: character.person_img_url,
+                order: character.sort,
+            }),
+            Some(job @ ("Director" | "Writer" | "Creator")) => crew.push(PersonInfo {
+                id,
+                name,
+                role: Some(job.to_string()),
+                image_url: character.person_img_url,
+                order: None,
+            }),
+            _ => {}
+        }
+    }
+
+    cast.sort_by_key(|p| p.order.unwrap_or(i32::MAX));
+    (cast, crew)
+}
+
+fn year_of(date: Option<&str>) -> Option<i32> {
+    date.and_then(|d| d.split('-').next())
+        .and_then(|y| y.parse().ok())
+}
+
+#[async_trait]
+impl MetadataProvider for TvdbProvider {
+    fn id(&self) -> &'static str {
+        "tvdb"
+    }
+
+    fn name(&self) -> &'static str {
+        "TheTVDB"
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &[MediaType::Tv, MediaType::Anime, MediaType::Movie]
+    }
+
+    fn requires_api_key(&self) -> bool {
+        true
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        match media_type {
+            MediaType::Tv => 85,
+            MediaType::Anime => 60, // Follows the numbering most anime releases use
+            MediaType::Movie | MediaType::Unknown => 40,
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let mut params = vec![("query", query)];
+        match options.media_type {
+            Some(MediaType::Movie) => params.push(("type", "movie")),
+            Some(MediaType::Tv | MediaType::Anime) => params.push(("type", "series")),
+            _ => {}
+        }
+        let year_str;
+        if let Some(year) = options.year {
+            year_str = year.to_string();
+            params.push(("year", &year_str));
+        }
+        let limit_str;
+        if let Some(limit) = options.limit {
+            limit_str = limit.to_string();
+            params.push(("limit", &limit_str));
+        }
+
+        let response: Vec<SearchResult> = self.request("/search", &params).await?;
+        let languages = self.languages(options.language.as_deref());
+        let mut results: Vec<MediaInfo> = response
+            .into_iter()
+            .filter_map(|r| self.search_result_to_info(r, &languages))
+            .collect();
+
+        if results.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, id: &str, media_type: MediaType) -> Result<MediaMetadata> {
+        self.get_details(id, media_type, None).await
+    }
+
+    async fn get_metadata_in(
+        &self,
+        id: &str,
+        media_type: MediaType,
+        language: &str,
+    ) -> Result<MediaMetadata> {
+        self.get_details(id, media_type, Some(language)).await
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        let endpoint = format!("/series/{series_id}/episodes/default");
+        let season_str = season.to_string();
+        let episode_str = episode.to_string();
+        let page: EpisodePage = self
+            .request(
+                &endpoint,
+                &[("season", &season_str), ("episodeNumber", &episode_str)],
+            )
+            .await?;
+
+        let ep = page
+            .episodes
+            .into_iter()
+            .find(|e| e.season_number == season && e.number == episode)
+            .ok_or_else(|| {
+                ScraperError::NotFound(format!("S{season:02}E{episode:02} of series {series_id}"))
+            })?;
+
+        Ok(EpisodeInfo {
+            id: ep.id.to_string(),
+            title: ep.name.unwrap_or_default(),
+            season: ep.season_number,
+            episode: ep.number,
+            absolute_number: ep.absolute_number,
+            original_title: None,
+            air_date: ep.aired,
+            overview: ep.overview,
+            runtime: ep.runtime,
+            rating: None,
+            still_url: ep.image,
+            provider: "tvdb".to_string(),
+        })
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        if source == "tvdb" {
+            return Ok(None);
+        }
+
+        let endpoint = format!("/search/remoteid/{external_id}");
+        let results: Vec<RemoteIdResult> = self.request(&endpoint, &[]).await?;
+
+        // Return first series or movie result
+        Ok(results.into_iter().find_map(|result| {
+            let (record, media_type) = match (result.series, result.movie) {
+                (Some(series), _) => (series, MediaType::Tv),
+                (None, Some(movie)) => (movie, MediaType::Movie),
+                (None, None) => return None,
+            };
+
+            let mut external_ids = ExternalIds {
+                tvdb: Some(record.id.to_string()),
+                ..Default::default()
+            };
+            match source {
+                "imdb" => external_ids.imdb = Some(external_id.to_string()),
+                "tmdb" => external_ids.tmdb = Some(external_id.to_string()),
+                _ => {}
+            }
+
+            Some(
+                MediaInfo::new(record.id.to_string(), record.name, "tvdb")
+                    .with_type(media_type)
+                    .with_year(record.year.and_then(|y| y.parse().ok()))
+                    .with_poster(record.image)
+                    .with_overview(record.overview)
+                    .with_popularity(record.score)
+                    .with_external_ids(external_ids),
+            )
+        }))
+    }
+
+    /// Logs in when there's no token yet, checking the API key
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        let result = self.request::<IgnoredAny>("/languages", &[]).await;
+        ProviderHealth::probe(&result, started).with_quota(self.client.quota())
+    }
+}
@@ -0,0 +1,125 @@
+use serde::Deserialize;
+
+// Search responses
+#[derive(Debug, Deserialize)]
+pub struct SearchResult {
+    pub score: f64,
+    pub show: Show,
+}
+
+// Show responses; `_embedded` is present when requested with `embed[]`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Show {
+    pub id: i64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub language: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    pub status: Option<String>,
+    pub runtime: Option<i32>,
+    pub average_runtime: Option<i32>,
+    pub premiered: Option<String>,
+    pub ended: Option<String>,
+    #[serde(default)]
+    pub rating: Rating,
+    pub weight: Option<f64>,
+    pub network: Option<Network>,
+    pub web_channel: Option<Network>,
+    #[serde(default)]
+    pub externals: Externals,
+    pub image: Option<Image>,
+    pub summary: Option<String>,
+    #[serde(rename = "_embedded")]
+    pub embedded: Option<Embedded>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Rating {
+    pub average: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Network {
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Externals {
+    pub thetvdb: Option<i64>,
+    pub imdb: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Image {
+    pub medium: Option<String>,
+    pub original: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Embedded {
+    #[serde(default)]
+    pub episodes: Vec<Episode>,
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+    #[serde(default)]
+    pub cast: Vec<CastCredit>,
+    #[serde(default)]
+    pub crew: Vec<CrewCredit>,
+}
+
+// Episode responses; specials have no number
+#[derive(Debug, Deserialize)]
+pub struct Episode {
+    pub id: i64,
+    pub name: String,
+    pub season: i32,
+    pub number: Option<i32>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub airdate: Option<String>,
+    pub runtime: Option<i32>,
+    #[serde(default)]
+    pub rating: Rating,
+    pub image: Option<Image>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Season {
+    pub number: i32,
+    pub name: Option<String>,
+    pub episode_order: Option<i32>,
+    pub premiere_date: Option<String>,
+    pub image: Option<Image>,
+    pub summary: Option<String>,
+}
+
+// Credits
+#[derive(Debug, Deserialize)]
+pub struct Person {
+    pub id: i64,
+    pub name: String,
+    pub image: Option<Image>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Character {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CastCredit {
+    pub person: Person,
+    pub character: Character,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CrewCredit {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub person: Person,
+}
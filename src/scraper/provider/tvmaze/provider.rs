@@ -0,0 +1,342 @@
+use super::api_types::{Embedded, Episode, Image, SearchResult, Show};
+use crate::scraper::{
+    parser::TitleArticles,
+    provider::{
+        plain_text, HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions,
+    },
+    types::{
+        EpisodeInfo, ExternalIds, ImageSet, MediaInfo, MediaMetadata, MediaType, PersonInfo,
+        SeasonInfo,
+    },
+    Result, ScraperError,
+};
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use std::time::Instant;
+
+const TVMAZE_BASE_URL: &str = "https://api.tvmaze.com";
+/// TVmaze allows 20 calls every 10 seconds per IP
+const TVMAZE_RATE_LIMIT: f64 = 2.0;
+
+/// Provider for TVmaze, a free TV database that needs no API key
+pub struct TvMazeProvider {
+    client: HttpClient,
+}
+
+impl TvMazeProvider {
+    pub fn new() -> Self {
+        Self {
+            client: HttpClient::new(TVMAZE_BASE_URL).with_rate_limit(TVMAZE_RATE_LIMIT),
+        }
+    }
+
+    /// Route requests through a proxy or a mirror of the API
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    /// All regular episodes of a show, in airing order
+    ///
+    /// Specials are left out, so absolute numbers count regular episodes only.
+    pub async fn get_episodes(&self, show_id: &str) -> Result<Vec<EpisodeInfo>> {
+        let episodes: Vec<Episode> = self
+            .client
+            .get_json_or_not_found(&format!("/shows/{show_id}/episodes"), &[])
+            .await?;
+
+        Ok(regular_episodes(episodes))
+    }
+
+    fn show_to_metadata(&self, show: Show) -> MediaMetadata {
+        let media_type = media_type_of(&show);
+        let year = show.premiered.as_deref().and_then(year_of);
+        let Embedded {
+            episodes,
+            seasons,
+            cast,
+            crew,
+        } = show.embedded.unwrap_or_default();
+        let episodes = regular_episodes(episodes);
+
+        let seasons: Vec<SeasonInfo> = seasons
+            .into_iter()
+            .map(|s| SeasonInfo {
+                number: s.number,
+                name: s.name.filter(|n| !n.is_empty()),
+                overview: s.summary.as_deref().and_then(plain_text),
+                air_date: s.premiere_date,
+                episode_count: s.episode_order.or_else(|| {
+                    let count = episodes.iter().filter(|e| e.season == s.number).count();
+                    (count > 0).then_some(count as i32)
+                }),
+                poster_url: original(s.image),
+            })
+            .collect();
+
+        let cast = cast
+            .into_iter()
+            .enumerate()
+            .map(|(order, credit)| PersonInfo {
+                id: credit.person.id.to_string(),
+                name: credit.person.name,
+                role: Some(credit.character.name),
+                image_url: original(credit.person.image),
+                order: Some(order as i32),
+            })
+            .collect();
+        let crew = crew
+            .into_iter()
+            .map(|credit| PersonInfo {
+                id: credit.person.id.to_string(),
+                name: credit.person.name,
+                role: Some(credit.kind),
+                image_url: original(credit.person.image),
+                order: None,
+            })
+            .collect();
+
+        MediaMetadata {
+            id: show.id.to_string(),
+            sort_title: Some(Self::generate_sort_title(&show.name, year)),
+            media_type,
+            overview: show.summary.as_deref().and_then(plain_text),
+            release_date: show.premiered,
+            end_date: show.ended,
+            runtime: show.average_runtime.or(show.runtime),
+            rating: show.rating.average,
+            genres: show.genres,
+            studios: show
+                .network
+                .or(show.web_channel)
+                .map(|n| n.name)
+                .into_iter()
+                .collect(),
+            language: show.language,
+            status: show.status,
+            images: ImageSet {
+                poster: original(show.image),
+                ..Default::default()
+            },
+            external_ids: ExternalIds {
+                imdb: show.externals.imdb,
+                tvdb: show.externals.thetvdb.map(|id| id.to_string()),
+                ..Default::default()
+            },
+            provider: "tvmaze".to_string(),
+            season_count: Some(seasons.iter().filter(|s| s.number > 0).count() as i32),
+            episode_count: Some(episodes.len() as i32),
+            seasons,
+            cast,
+            crew,
+            title: show.name,
+            ..Default::default()
+        }
+    }
+
+    fn show_to_info(show: Show) -> MediaInfo {
+        let media_type = media_type_of(&show);
+        let year = show.premiered.as_deref().and_then(year_of);
+        let external_ids = ExternalIds {
+            imdb: show.externals.imdb,
+            tvdb: show.externals.thetvdb.map(|id| id.to_string()),
+            ..Default::default()
+        };
+
+        MediaInfo::new(show.id.to_string(), show.name, "tvmaze")
+            .with_type(media_type)
+            .with_year(year)
+            .with_poster(original(show.image))
+            .with_overview(show.summary.as_deref().and_then(plain_text))
+            .with_rating(show.rating.average)
+            .with_external_ids(external_ids)
+    }
+
+    fn generate_sort_title(title: &str, year: Option<i32>) -> String {
+        let articles = TitleArticles::active();
+        let sort_title = articles.strip(title);
+
+        if let Some(year) = year {
+            format!("{sort_title} ({year})")
+        } else {
+            sort_title.to_string()
+        }
+    }
+}
+
+impl Default for TvMazeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Japanese animation is listed as anime, everything else as TV
+fn media_type_of(show: &Show) -> MediaType {
+    if show.kind.as_deref() == Some("Animation") && show.language.as_deref() == Some("Japanese") {
+        MediaType::Anime
+    } else {
+        MediaType::Tv
+    }
+}
+
+/// Episodes with a number, numbered absolutely in airing order
+fn regular_episodes(episodes: Vec<Episode>) -> Vec<EpisodeInfo> {
+    episodes
+        .into_iter()
+        .filter(|e| e.number.is_some() && e.season > 0)
+        .enumerate()
+        .map(|(index, e)| {
+            let mut info = episode_info(e);
+            info.absolute_number = Some(index as i32 + 1);
+            info
+        })
+        .collect()
+}
+
+fn episode_info(episode: Episode) -> EpisodeInfo {
+    EpisodeInfo {
+        id: episode.id.to_string(),
+        title: episode.name,
+        season: episode.season,
+        episode: episode.number.unwrap_or(0),
+        absolute_number: None,
+        original_title: None,
+        air_date: episode.airdate.filter(|d| !d.is_empty()),
+        overview: episode.summary.as_deref().and_then(plain_text),
+        runtime: episode.runtime,
+        rating: episode.rating.average,
+        still_url: original(episode.image),
+        provider: "tvmaze".to_string(),
+    }
+}
+
+/// Full size image, falling back to the medium one
+fn original(image: Option<Image>) -> Option<String> {
+    image.and_then(|i| i.original.or(i.medium))
+}
+
+/// First year of "2008-01-20"
+fn year_of(value: &str) -> Option<i32> {
+    value.get(..4).and_then(|y| y.parse().ok())
+}
+
+#[async_trait]
+impl MetadataProvider for TvMazeProvider {
+    fn id(&self) -> &'static str {
+        "tvmaze"
+    }
+
+    fn name(&self) -> &'static str {
+        "TVmaze"
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &[MediaType::Tv, MediaType::Anime]
+    }
+
+    fn requires_api_key(&self) -> bool {
+        false
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        // Keyless fallback for TV, searched after TMDB and TVDB
+        match media_type {
+            MediaType::Tv => 30,
+            MediaType::Anime => 10,
+            MediaType::Movie | MediaType::Unknown => 0,
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let results: Vec<SearchResult> = self
+            .client
+            .get_json_or_not_found("/search/shows", &[("q", query)])
+            .await?;
+
+        let mut results: Vec<MediaInfo> = results
+            .into_iter()
+            .filter(|r| {
+                options.year.is_none_or(|year| {
+                    r.show.premiered.as_deref().and_then(year_of) == Some(year)
+                })
+            })
+            .map(|r| Self::show_to_info(r.show))
+            .collect();
+
+        if results.is_empty() {
+            return Err(ScraperError::NotFound(format!(
+                "No results found for: {query}"
+            )));
+        }
+
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, id: &str, _media_type: MediaType) -> Result<MediaMetadata> {
+        let show: Show = self
+            .client
+            .get_json_or_not_found(
+                &format!("/shows/{id}"),
+                &[
+                    ("embed[]", "episodes"),
+                    ("embed[]", "seasons"),
+                    ("embed[]", "cast"),
+                    ("embed[]", "crew"),
+                ],
+            )
+            .await?;
+
+        Ok(self.show_to_metadata(show))
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        let season_str = season.to_string();
+        let episode_str = episode.to_string();
+        let ep: Episode = self
+            .client
+            .get_json_or_not_found(
+                &format!("/shows/{series_id}/episodebynumber"),
+                &[("season", &season_str), ("number", &episode_str)],
+            )
+            .await?;
+
+        Ok(episode_info(ep))
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        let param = match source {
+            "imdb" => "imdb",
+            "tvdb" => "thetvdb",
+            _ => return Ok(None),
+        };
+
+        match self
+            .client
+            .get_json_or_not_found::<Show>("/lookup/shows", &[(param, external_id)])
+            .await
+        {
+            Ok(show) => Ok(Some(Self::show_to_info(show))),
+            Err(ScraperError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        let result = self.client.get_json_or_not_found::<IgnoredAny>("/shows/1", &[]).await;
+        ProviderHealth::probe(&result, started).with_quota(self.client.quota())
+    }
+}
@@ -0,0 +1,115 @@
+use crate::scraper::types::{ExternalIds, MediaType};
+use serde::{Deserialize, Serialize};
+
+// Request bodies
+#[derive(Debug, Serialize)]
+pub struct SearchRequest<'a> {
+    pub query: &'a str,
+    pub year: Option<i32>,
+    pub media_type: Option<MediaType>,
+    pub language: Option<&'a str>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetadataRequest<'a> {
+    pub id: &'a str,
+    pub media_type: MediaType,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EpisodeRequest<'a> {
+    pub series_id: &'a str,
+    pub season: i32,
+    pub episode: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeasonRequest<'a> {
+    pub series_id: &'a str,
+    pub season: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExternalIdRequest<'a> {
+    pub external_id: &'a str,
+    pub source: &'a str,
+}
+
+// Responses
+#[derive(Debug, Deserialize)]
+pub struct SearchResponse {
+    #[serde(default)]
+    pub results: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeasonResponse {
+    #[serde(default)]
+    pub episodes: Vec<Episode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExternalIdResponse {
+    pub result: Option<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Item {
+    pub id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    #[serde(default)]
+    pub alt_titles: Vec<String>,
+    pub media_type: Option<MediaType>,
+    pub year: Option<i32>,
+    pub overview: Option<String>,
+    pub tagline: Option<String>,
+    pub release_date: Option<String>,
+    pub end_date: Option<String>,
+    pub runtime: Option<i32>,
+    pub rating: Option<f64>,
+    pub vote_count: Option<i32>,
+    pub popularity: Option<f64>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub studios: Vec<String>,
+    pub language: Option<String>,
+    pub content_rating: Option<String>,
+    pub status: Option<String>,
+    pub season_count: Option<i32>,
+    pub episode_count: Option<i32>,
+    pub poster_url: Option<String>,
+    pub backdrop_url: Option<String>,
+    #[serde(default)]
+    pub external_ids: ExternalIds,
+    #[serde(default)]
+    pub cast: Vec<Person>,
+    #[serde(default)]
+    pub crew: Vec<Person>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Person {
+    pub id: Option<String>,
+    pub name: String,
+    pub role: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Episode {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub season: i32,
+    pub episode: i32,
+    pub absolute_number: Option<i32>,
+    pub air_date: Option<String>,
+    pub overview: Option<String>,
+    pub runtime: Option<i32>,
+    pub rating: Option<f64>,
+    pub still_url: Option<String>,
+}
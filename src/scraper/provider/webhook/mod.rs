@@ -0,0 +1,43 @@
+//! Provider backed by an external HTTP service, for sources without a
+//! built-in provider (private trackers, local databases...)
+//!
+//! Every call is a `POST` of a JSON body to a path under the configured URL,
+//! answered with JSON. A `404` answers "not found"; any other error status is
+//! reported as an API error.
+//!
+//! - `/search`: `{query, year?, media_type?, language?, limit?}` → `{"results": [Item]}`
+//! - `/metadata`: `{id, media_type}` → `Item`
+//! - `/episode`: `{series_id, season, episode}` → `Episode`
+//! - `/season`: `{series_id, season}` → `{"episodes": [Episode]}`
+//! - `/external_id`: `{external_id, source}` → `{"result": Item | null}`
+//!
+//! `/season` and `/external_id` are optional; a `404` there means the service
+//! doesn't offer them. Health checks send `GET /health`, which may answer
+//! with anything; a `404` still counts as healthy. `media_type` is one of `movie`, `tv` or `anime`.
+//!
+//! An `Item` needs `id` and `title`; everything else may be left out:
+//!
+//! ```json
+//! {
+//!   "id": "42", "title": "Title", "original_title": "Titre", "alt_titles": [],
+//!   "media_type": "tv", "year": 2020, "overview": "...", "tagline": "...",
+//!   "release_date": "2020-01-31", "end_date": null, "runtime": 24,
+//!   "rating": 7.5, "vote_count": 120, "popularity": 12.0,
+//!   "genres": [], "tags": [], "studios": [], "language": "ja",
+//!   "content_rating": "TV-14", "status": "Ended",
+//!   "season_count": 1, "episode_count": 12,
+//!   "poster_url": "https://...", "backdrop_url": "https://...",
+//!   "external_ids": {"imdb": "tt0000000", "tmdb": "1", "anilist": "1"},
+//!   "cast": [{"name": "...", "role": "...", "image_url": "https://..."}],
+//!   "crew": [{"name": "...", "role": "Director"}]
+//! }
+//! ```
+//!
+//! An `Episode` needs `season` and `episode`, with optional `id`, `title`,
+//! `absolute_number`, `air_date`, `overview`, `runtime`, `rating` and
+//! `still_url`.
+
+mod api_types;
+mod provider;
+
+pub use provider::{WebhookOptions, WebhookProvider};
@@ -0,0 +1,351 @@
+use super::api_types::{
+    Episode, EpisodeRequest, ExternalIdRequest, ExternalIdResponse, Item, MetadataRequest,
+    Person, SearchRequest, SearchResponse, SeasonRequest, SeasonResponse,
+};
+use crate::scraper::{
+    Result, ScraperError,
+    provider::{HttpClient, MetadataProvider, NetworkOptions, ProviderHealth, SearchOptions},
+    types::{EpisodeInfo, ImageSet, MediaInfo, MediaMetadata, MediaType, PersonInfo},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::time::Instant;
+
+/// An external metadata service, see the [module docs](super) for its contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookOptions {
+    /// Provider ID, used in match results and `rate_limits`/`network` settings
+    pub id: String,
+    /// Human-readable name, defaults to the ID
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Base URL of the service, e.g. `http://localhost:8000/ayiah`
+    pub url: String,
+    /// Sent as a bearer token with every request
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Media types the service knows about
+    #[serde(default = "default_media_types")]
+    pub media_types: Vec<MediaType>,
+    /// Priority over other providers for its media types (built-ins use 50-100)
+    #[serde(default)]
+    pub priority: Option<i32>,
+}
+
+fn default_media_types() -> Vec<MediaType> {
+    vec![MediaType::Movie, MediaType::Tv]
+}
+
+/// Provider delegating to an external HTTP service
+pub struct WebhookProvider {
+    client: HttpClient,
+    id: &'static str,
+    name: &'static str,
+    media_types: Vec<MediaType>,
+    priority: Option<i32>,
+}
+
+impl WebhookProvider {
+    /// Provider for the service described by `options`
+    ///
+    /// The ID and name are leaked to satisfy [`MetadataProvider`]; services
+    /// are configured once at startup. Fails when the token can't be sent in
+    /// a header.
+    pub fn new(options: WebhookOptions) -> Result<Self> {
+        let mut client = HttpClient::new(options.url.trim_end_matches('/'));
+        if let Some(ref token) = options.token {
+            client = client.with_header("authorization", &format!("Bearer {}", token.trim()))?;
+        }
+        let name = options.name.unwrap_or_else(|| options.id.clone());
+
+        Ok(Self {
+            client,
+            id: options.id.leak(),
+            name: name.leak(),
+            media_types: options.media_types,
+            priority: options.priority,
+        })
+    }
+
+    /// Route requests through a proxy, or to another instance of the service
+    pub fn with_network(mut self, network: &NetworkOptions) -> Result<Self> {
+        self.client = self.client.with_network(network)?;
+        Ok(self)
+    }
+
+    /// POST request, with a 404 reported as not found
+    async fn request<T: DeserializeOwned, B: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<T> {
+        match self.client.post_json(endpoint, body).await {
+            Err(ScraperError::Api { status: 404, .. }) => Err(ScraperError::NotFound(format!(
+                "Not found: {}{endpoint}",
+                self.id
+            ))),
+            result => result,
+        }
+    }
+
+    fn media_type_of(&self, item: &Item, requested: Option<MediaType>) -> MediaType {
+        item.media_type
+            .or(requested)
+            .or_else(|| self.media_types.first().copied())
+            .unwrap_or_default()
+    }
+
+    fn item_to_info(&self, item: Item, requested: Option<MediaType>) -> MediaInfo {
+        let media_type = self.media_type_of(&item, requested);
+        let year = item.year.or_else(|| year_of(item.release_date.as_deref()));
+
+        MediaInfo {
+            id: item.id,
+            title: item.title,
+            original_title: item.original_title,
+            alt_titles: item.alt_titles,
+            media_type,
+            year,
+            poster_url: item.poster_url,
+            overview: item.overview,
+            rating: item.rating,
+            provider: self.id.to_string(),
+            popularity: item.popularity,
+            external_ids: item.external_ids,
+            format: None,
+        }
+    }
+
+    fn item_to_metadata(&self, item: Item, requested: MediaType) -> MediaMetadata {
+        let media_type = self.media_type_of(&item, Some(requested));
+        let release_date = item
+            .release_date
+            .or_else(|| item.year.map(|y| format!("{y:04}-01-01")));
+
+        MediaMetadata {
+            id: item.id,
+            title: item.title,
+            original_title: item.original_title,
+            aliases: item.alt_titles,
+            media_type,
+            tagline: item.tagline,
+            overview: item.overview,
+            release_date,
+            end_date: item.end_date,
+            runtime: item.runtime,
+            rating: item.rating,
+            vote_count: item.vote_count,
+            genres: item.genres,
+            tags: item.tags,
+            studios: item.studios,
+            language: item.language,
+            content_rating: item.content_rating,
+            status: item.status,
+            images: ImageSet {
+                poster: item.poster_url,
+                backdrop: item.backdrop_url,
+                ..ImageSet::default()
+            },
+            external_ids: item.external_ids,
+            provider: self.id.to_string(),
+            season_count: item.season_count,
+            episode_count: item.episode_count,
+            cast: people(item.cast),
+            crew: people(item.crew),
+            ..MediaMetadata::default()
+        }
+    }
+
+    fn episode_to_info(&self, series_id: &str, episode: Episode) -> EpisodeInfo {
+        EpisodeInfo {
+            id: episode.id.unwrap_or_else(|| {
+                format!("{series_id}:{}:{}", episode.season, episode.episode)
+            }),
+            title: episode
+                .title
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| format!("Episode {}", episode.episode)),
+            season: episode.season,
+            episode: episode.episode,
+            absolute_number: episode.absolute_number,
+            original_title: None,
+            air_date: episode.air_date,
+            overview: episode.overview,
+            runtime: episode.runtime,
+            rating: episode.rating,
+            still_url: episode.still_url,
+            provider: self.id.to_string(),
+        }
+    }
+}
+
+fn year_of(date: Option<&str>) -> Option<i32> {
+    date?.get(..4)?.parse().ok()
+}
+
+fn people(people: Vec<Person>) -> Vec<PersonInfo> {
+    people
+        .into_iter()
+        .enumerate()
+        .map(|(i, p)| PersonInfo {
+            id: p.id.unwrap_or_else(|| p.name.clone()),
+            name: p.name,
+            role: p.role,
+            image_url: p.image_url,
+            order: Some(i as i32),
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MetadataProvider for WebhookProvider {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn supported_types(&self) -> &[MediaType] {
+        &self.media_types
+    }
+
+    fn priority_for(&self, media_type: MediaType) -> i32 {
+        if self.media_types.contains(&media_type) {
+            self.priority.unwrap_or(50)
+        } else {
+            0
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<MediaInfo>> {
+        let body = SearchRequest {
+            query,
+            year: options.year,
+            media_type: options.media_type,
+            language: options.language.as_deref(),
+            limit: options.limit,
+        };
+        let response: SearchResponse = self.request("/search", &body).await?;
+
+        if response.results.is_empty() {
+            return Err(ScraperError::NotFound(format!("No results found for: {query}")));
+        }
+
+        let mut results: Vec<MediaInfo> = response
+            .results
+            .into_iter()
+            .map(|item| self.item_to_info(item, options.media_type))
+            .collect();
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+        Ok(results)
+    }
+
+    async fn get_metadata(&self, id: &str, media_type: MediaType) -> Result<MediaMetadata> {
+        let item: Item = self
+            .request("/metadata", &MetadataRequest { id, media_type })
+            .await?;
+        Ok(self.item_to_metadata(item, media_type))
+    }
+
+    async fn get_episode(
+        &self,
+        series_id: &str,
+        season: i32,
+        episode: i32,
+    ) -> Result<EpisodeInfo> {
+        let body = EpisodeRequest {
+            series_id,
+            season,
+            episode,
+        };
+        let episode: Episode = self.request("/episode", &body).await?;
+        Ok(self.episode_to_info(series_id, episode))
+    }
+
+    async fn get_season(&self, series_id: &str, season: i32) -> Result<Vec<EpisodeInfo>> {
+        let response: SeasonResponse = self
+            .request("/season", &SeasonRequest { series_id, season })
+            .await?;
+        Ok(response
+            .episodes
+            .into_iter()
+            .map(|e| self.episode_to_info(series_id, e))
+            .collect())
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: &str,
+        source: &str,
+    ) -> Result<Option<MediaInfo>> {
+        let body = ExternalIdRequest {
+            external_id,
+            source,
+        };
+        match self.request::<ExternalIdResponse, _>("/external_id", &body).await {
+            Ok(response) => Ok(response.result.map(|item| self.item_to_info(item, None))),
+            Err(ScraperError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        let started = Instant::now();
+        let result = self.client.get_bytes("/health", &[]).await;
+        ProviderHealth::probe(&result, started).with_quota(self.client.quota())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider() -> WebhookProvider {
+        WebhookProvider::new(WebhookOptions {
+            id: "local".to_string(),
+            name: None,
+            url: "http://localhost:8000/".to_string(),
+            token: None,
+            media_types: vec![MediaType::Anime],
+            priority: Some(90),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_contract_minimal_item() {
+        let provider = provider();
+        assert_eq!(provider.name(), "local");
+        assert_eq!(provider.priority_for(MediaType::Anime), 90);
+        assert_eq!(provider.priority_for(MediaType::Movie), 0);
+
+        let item: Item = serde_json::from_str(r#"{"id": "7", "title": "Title"}"#).unwrap();
+        let info = provider.item_to_info(item, None);
+        assert_eq!(info.provider, "local");
+        assert_eq!(info.media_type, MediaType::Anime);
+
+        let item: Item = serde_json::from_str(
+            r#"{
+                "id": "7", "title": "Title", "media_type": "movie",
+                "release_date": "2021-05-01", "poster_url": "https://img/p.jpg",
+                "external_ids": {"imdb": "tt1234567"},
+                "cast": [{"name": "Someone", "role": "Lead"}]
+            }"#,
+        )
+        .unwrap();
+        let metadata = provider.item_to_metadata(item, MediaType::Anime);
+        assert_eq!(metadata.media_type, MediaType::Movie);
+        assert_eq!(metadata.images.poster.as_deref(), Some("https://img/p.jpg"));
+        assert_eq!(metadata.external_ids.imdb.as_deref(), Some("tt1234567"));
+        assert_eq!(metadata.cast[0].id, "Someone");
+
+        let episode: Episode = serde_json::from_str(r#"{"season": 1, "episode": 3}"#).unwrap();
+        let episode = provider.episode_to_info("7", episode);
+        assert_eq!(episode.id, "7:1:3");
+        assert_eq!(episode.title, "Episode 3");
+    }
+}
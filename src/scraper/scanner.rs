@@ -1,25 +1,125 @@
+use super::{IgnoreRules, is_iso, probe_iso};
+use parking_lot::Mutex;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::warn;
 use walkdir::WalkDir;
 
-/// Supported video file extensions
-const VIDEO_EXTENSIONS: &[&str] = &[
+/// Default limit on how deep scans descend below the scanned folder
+pub const DEFAULT_MAX_DEPTH: usize = 32;
+
+/// Video file extensions scanned by default
+pub const VIDEO_EXTENSIONS: &[&str] = &[
     "mkv", "mp4", "avi", "mov", "wmv", "flv", "webm", "m4v", "iso", "rmvb", "ts", "m2ts",
 ];
 
 /// Scanner for finding media files
 pub struct Scanner;
 
+/// Files found by [`Scanner::walk`]
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    pub files: Vec<PathBuf>,
+    /// Folders skipped because they were already reached through a symlink
+    pub skipped_cycles: usize,
+}
+
+/// Remembers the folders a scan entered, so symlink cycles are walked once
+///
+/// Clones share their state, letting parallel walkers of one scan skip folders
+/// another walker already covers.
+#[derive(Debug, Clone, Default)]
+pub struct LoopGuard {
+    visited: Arc<Mutex<HashSet<PathBuf>>>,
+    skipped: Arc<AtomicUsize>,
+}
+
+impl LoopGuard {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether to descend into a folder; false if its real path was already entered
+    pub fn enter(&self, dir: &Path) -> bool {
+        let Ok(real) = dir.canonicalize() else {
+            return true;
+        };
+        if self.visited.lock().insert(real) {
+            return true;
+        }
+
+        warn!("Skipping already scanned folder: {}", dir.display());
+        self.skip();
+        false
+    }
+
+    /// Record a cycle detected elsewhere (e.g. reported by the directory walker)
+    pub fn skip(&self) {
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of folders skipped so far
+    #[must_use]
+    pub fn skipped(&self) -> usize {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// `WalkDir::filter_entry` predicate: registers folders the walk descends into
+    ///
+    /// `max_depth` must match the walker's, so folders listed but not entered at
+    /// the depth limit stay available to other walkers.
+    pub fn allows(&self, entry: &walkdir::DirEntry, max_depth: usize) -> bool {
+        !entry.file_type().is_dir() || entry.depth() >= max_depth || self.enter(entry.path())
+    }
+}
+
 impl Scanner {
     /// Scan a directory for video files and disc structures
+    ///
+    /// `.ayiahignore`/`.plexignore` files found along the way are honored.
     pub fn scan<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+        let rules = IgnoreRules::new(path.as_ref());
+        Self::scan_with(path, rules)
+    }
+
+    /// Scan a directory, skipping entries excluded by `rules`
+    pub fn scan_with<P: AsRef<Path>>(path: P, rules: IgnoreRules) -> Vec<PathBuf> {
+        let extensions: Vec<String> = VIDEO_EXTENSIONS.iter().map(|e| e.to_string()).collect();
+        Self::walk(path, rules, DEFAULT_MAX_DEPTH, &extensions).files
+    }
+
+    /// Scan at most `max_depth` levels below `path` for files with one of the
+    /// lowercase `extensions`, reporting skipped symlink cycles
+    pub fn walk<P: AsRef<Path>>(
+        path: P,
+        mut rules: IgnoreRules,
+        max_depth: usize,
+        extensions: &[String],
+    ) -> ScanReport {
         let mut video_files = HashSet::new();
+        let guard = LoopGuard::new();
 
         for entry in WalkDir::new(path)
             .follow_links(true)
+            .max_depth(max_depth)
             .into_iter()
-            .filter_map(std::result::Result::ok)
+            .filter_entry(|e| {
+                rules.allows(e.path(), e.file_type().is_dir()) && guard.allows(e, max_depth)
+            })
         {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    if e.loop_ancestor().is_some() {
+                        guard.skip();
+                    }
+                    continue;
+                }
+            };
+
             let path = entry.path();
             if !path.is_file() {
                 continue;
@@ -51,8 +151,13 @@ impl Scanner {
             if path
                 .extension()
                 .and_then(|e| e.to_str())
-                .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+                .is_some_and(|ext| extensions.contains(&ext.to_lowercase()))
             {
+                // Images holding no DVD or Blu-ray folders are not video
+                if is_iso(path) && !probe_iso(path).may_be_video() {
+                    continue;
+                }
+
                 // If file is part of a disc structure (inside BDMV or VIDEO_TS), ignore it
                 // because we capture the root folder instead.
                 if !Self::is_inside_disc_structure(path) {
@@ -61,7 +166,10 @@ impl Scanner {
             }
         }
 
-        video_files.into_iter().collect()
+        ScanReport {
+            files: video_files.into_iter().collect(),
+            skipped_cycles: guard.skipped(),
+        }
     }
 
     /// Check if a path is part of a disc structure (BDMV or `VIDEO_TS`)
@@ -76,10 +184,15 @@ impl Scanner {
 
 #[cfg(test)]
 mod tests {
-    use super::Scanner;
+    use super::{DEFAULT_MAX_DEPTH, Scanner, VIDEO_EXTENSIONS};
+    use crate::scraper::IgnoreRules;
     use std::fs::{self, File};
     use tempfile::TempDir;
 
+    fn default_extensions() -> Vec<String> {
+        VIDEO_EXTENSIONS.iter().map(|e| e.to_string()).collect()
+    }
+
     #[test]
     fn test_scan_finds_video_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -144,4 +257,74 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert!(results[0].ends_with("Movie"));
     }
+
+    #[test]
+    fn test_scan_honors_ignore_rules() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir_path = temp_dir.path();
+
+        fs::create_dir_all(dir_path.join("@eaDir")).unwrap();
+        fs::create_dir_all(dir_path.join("Incomplete")).unwrap();
+        File::create(dir_path.join("movie.mkv")).unwrap();
+        File::create(dir_path.join("@eaDir").join("movie.mkv")).unwrap();
+        File::create(dir_path.join("Incomplete").join("show.mkv")).unwrap();
+        fs::write(dir_path.join(".ayiahignore"), "Incomplete/\n").unwrap();
+
+        let rules = IgnoreRules::new(dir_path).with_excludes(&["@eaDir"]).unwrap();
+        let results = Scanner::scan_with(dir_path, rules);
+
+        assert_eq!(results, [dir_path.join("movie.mkv")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_skips_symlink_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir_path = temp_dir.path();
+
+        let movies = dir_path.join("Movies");
+        fs::create_dir(&movies).unwrap();
+        File::create(movies.join("movie.mkv")).unwrap();
+        std::os::unix::fs::symlink(dir_path, movies.join("loop")).unwrap();
+        std::os::unix::fs::symlink(&movies, dir_path.join("Again")).unwrap();
+
+        let report = Scanner::walk(
+            dir_path,
+            IgnoreRules::new(dir_path),
+            DEFAULT_MAX_DEPTH,
+            &default_extensions(),
+        );
+
+        assert_eq!(report.files.len(), 1);
+        assert!(report.skipped_cycles >= 1);
+    }
+
+    #[test]
+    fn test_scan_max_depth() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir_path = temp_dir.path();
+
+        let deep = dir_path.join("a").join("b");
+        fs::create_dir_all(&deep).unwrap();
+        File::create(dir_path.join("a").join("movie.mkv")).unwrap();
+        File::create(deep.join("episode.mkv")).unwrap();
+
+        let report = Scanner::walk(dir_path, IgnoreRules::new(dir_path), 2, &default_extensions());
+
+        assert_eq!(report.files, [dir_path.join("a").join("movie.mkv")]);
+    }
+
+    #[test]
+    fn test_scan_custom_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir_path = temp_dir.path();
+
+        File::create(dir_path.join("movie.mkv")).unwrap();
+        File::create(dir_path.join("stream.STRM")).unwrap();
+
+        let extensions = ["strm".to_string()];
+        let report = Scanner::walk(dir_path, IgnoreRules::new(dir_path), 2, &extensions);
+
+        assert_eq!(report.files, [dir_path.join("stream.STRM")]);
+    }
 }
@@ -0,0 +1,238 @@
+use crate::scraper::{
+    matcher::{Confidence, Matcher, ScoreBreakdown},
+    parser::{MediaHint, ParsedMedia},
+    types::{MediaInfo, MediaType},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Scores a search result against parsed filename info
+///
+/// Implement this to plug a custom scorer into [`Matcher::rank_with`] or
+/// [`ScraperManager::set_strategy`](crate::scraper::ScraperManager::set_strategy).
+/// Duplicate merging and the cross-provider agreement bonus are applied on top
+/// by the matcher.
+pub trait MatchStrategy: Send + Sync {
+    /// Strategy name for logging
+    fn name(&self) -> &str;
+
+    /// Score the components of a match
+    fn score(&self, info: &MediaInfo, parsed: &ParsedMedia) -> ScoreBreakdown;
+
+    /// Map a total score to a confidence level
+    fn confidence(&self, total_score: i32, breakdown: &ScoreBreakdown) -> Confidence {
+        Matcher::calculate_confidence(total_score, breakdown)
+    }
+
+    /// Human-readable reasons for a score
+    fn explain(
+        &self,
+        info: &MediaInfo,
+        parsed: &ParsedMedia,
+        breakdown: &ScoreBreakdown,
+    ) -> Vec<String> {
+        Matcher::default_reasons(info, parsed, breakdown)
+    }
+}
+
+/// Balanced title/year/type/provider/popularity scoring
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultStrategy;
+
+impl MatchStrategy for DefaultStrategy {
+    fn name(&self) -> &'static str {
+        "default"
+    }
+
+    fn score(&self, info: &MediaInfo, parsed: &ParsedMedia) -> ScoreBreakdown {
+        Matcher::default_breakdown(info, parsed)
+    }
+}
+
+/// Only accepts exact (normalized) title matches with a consistent year
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrictStrategy;
+
+impl MatchStrategy for StrictStrategy {
+    fn name(&self) -> &'static str {
+        "strict"
+    }
+
+    fn score(&self, info: &MediaInfo, parsed: &ParsedMedia) -> ScoreBreakdown {
+        let query = Matcher::normalize_title(&parsed.title);
+        let exact_title = info
+            .all_titles()
+            .into_iter()
+            .any(|t| Matcher::normalize_title(t) == query);
+        let year_conflict =
+            matches!((info.year, parsed.year), (Some(a), Some(b)) if (a - b).abs() > 1);
+
+        if !exact_title || year_conflict {
+            return ScoreBreakdown::default();
+        }
+
+        Matcher::default_breakdown(info, parsed)
+    }
+}
+
+/// Favours anime providers and treats unhinted episodic files as anime
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnimeStrategy;
+
+impl MatchStrategy for AnimeStrategy {
+    fn name(&self) -> &'static str {
+        "anime"
+    }
+
+    fn score(&self, info: &MediaInfo, parsed: &ParsedMedia) -> ScoreBreakdown {
+        let mut breakdown = Matcher::default_breakdown(info, parsed);
+
+        if parsed.hint != MediaHint::Movie {
+            breakdown.type_score = match info.media_type {
+                MediaType::Anime => 20,
+                MediaType::Tv => 15,
+                _ => breakdown.type_score,
+            };
+        }
+
+        breakdown.provider_score = match info.provider.as_str() {
+            "anilist" => 10,
+            "bangumi" => 9,
+            _ => 5,
+        };
+
+        breakdown
+    }
+}
+
+/// Trusts provider IDs tagged in the filename over title similarity
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExternalIdFirstStrategy;
+
+impl MatchStrategy for ExternalIdFirstStrategy {
+    fn name(&self) -> &'static str {
+        "external_id_first"
+    }
+
+    fn score(&self, info: &MediaInfo, parsed: &ParsedMedia) -> ScoreBreakdown {
+        if parsed.external_ids.overlaps(&info.external_ids) {
+            return ScoreBreakdown {
+                title_score: 40,
+                year_score: 20,
+                type_score: 20,
+                provider_score: 10,
+                popularity_score: 10,
+                agreement_score: 0,
+            };
+        }
+
+        Matcher::default_breakdown(info, parsed)
+    }
+
+    fn explain(
+        &self,
+        info: &MediaInfo,
+        parsed: &ParsedMedia,
+        breakdown: &ScoreBreakdown,
+    ) -> Vec<String> {
+        if parsed.external_ids.overlaps(&info.external_ids) {
+            let mut reasons = vec!["External ID tagged in filename matches".to_string()];
+            if breakdown.agreement_score > 0 {
+                reasons.push("Confirmed by multiple providers".to_string());
+            }
+            return reasons;
+        }
+
+        Matcher::default_reasons(info, parsed, breakdown)
+    }
+}
+
+/// Built-in matching strategies selectable from configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStrategyKind {
+    #[default]
+    Default,
+    Strict,
+    Anime,
+    ExternalIdFirst,
+}
+
+impl MatchStrategyKind {
+    /// Create the strategy implementation
+    #[must_use]
+    pub fn build(self) -> Arc<dyn MatchStrategy> {
+        match self {
+            Self::Default => Arc::new(DefaultStrategy),
+            Self::Strict => Arc::new(StrictStrategy),
+            Self::Anime => Arc::new(AnimeStrategy),
+            Self::ExternalIdFirst => Arc::new(ExternalIdFirstStrategy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraper::types::ExternalIds;
+
+    fn parsed(title: &str) -> ParsedMedia {
+        ParsedMedia {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_strict_rejects_partial_titles() {
+        let results = vec![
+            MediaInfo::new("1", "The Matrix Reloaded", "tmdb").with_type(MediaType::Movie),
+            MediaInfo::new("2", "The Matrix", "tmdb").with_type(MediaType::Movie),
+        ];
+
+        let ranked = Matcher::rank_with(&StrictStrategy, results, &parsed("The Matrix"));
+
+        assert_eq!(ranked[0].info.id, "2");
+        assert_eq!(ranked[1].confidence, Confidence::None);
+    }
+
+    #[test]
+    fn test_anime_prefers_anime_providers() {
+        let results = vec![
+            MediaInfo::new("1", "Frieren", "tmdb").with_type(MediaType::Tv),
+            MediaInfo::new("2", "Frieren", "anilist").with_type(MediaType::Anime),
+        ];
+
+        let ranked = Matcher::rank_with(&AnimeStrategy, results, &parsed("Frieren"));
+
+        assert_eq!(ranked[0].info.provider, "anilist");
+    }
+
+    #[test]
+    fn test_external_id_first() {
+        let mut query = parsed("Matrix");
+        query.external_ids.tmdb = Some("603".to_string());
+
+        let results = vec![
+            MediaInfo::new("604", "Matrix", "tmdb").with_type(MediaType::Movie),
+            MediaInfo::new("603", "The Matrix", "tmdb")
+                .with_type(MediaType::Movie)
+                .with_external_ids(ExternalIds {
+                    tmdb: Some("603".to_string()),
+                    ..Default::default()
+                }),
+        ];
+
+        let ranked = Matcher::rank_with(&ExternalIdFirstStrategy, results, &query);
+
+        assert_eq!(ranked[0].info.id, "603");
+        assert_eq!(ranked[0].confidence, Confidence::Exact);
+    }
+
+    #[test]
+    fn test_strategy_kind_from_config() {
+        let kind: MatchStrategyKind = serde_json::from_str("\"external_id_first\"").unwrap();
+        assert_eq!(kind, MatchStrategyKind::ExternalIdFirst);
+        assert_eq!(kind.build().name(), "external_id_first");
+    }
+}
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::debug;
+
+use super::MediaMetadata;
+
+/// Default yt-dlp binary, looked up on `PATH`
+pub const DEFAULT_YTDLP_PATH: &str = "yt-dlp";
+
+/// Saves trailers of movies next to them with yt-dlp
+///
+/// Trailers are named `<movie file name>-trailer.mp4`, which Jellyfin, Emby
+/// and Kodi pick up as local trailers.
+#[derive(Debug, Clone)]
+pub struct TrailerDownloader {
+    ytdlp_path: PathBuf,
+    max_size: Option<u64>,
+}
+
+impl Default for TrailerDownloader {
+    fn default() -> Self {
+        Self::new(DEFAULT_YTDLP_PATH)
+    }
+}
+
+impl TrailerDownloader {
+    /// Create a downloader running the given yt-dlp binary
+    #[must_use]
+    pub fn new(ytdlp_path: impl Into<PathBuf>) -> Self {
+        Self {
+            ytdlp_path: ytdlp_path.into(),
+            max_size: None,
+        }
+    }
+
+    /// Skip trailers larger than `bytes`
+    #[must_use]
+    pub const fn with_max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Where the trailer of the movie in `video` is saved
+    #[must_use]
+    pub fn trailer_path(video: &Path) -> Option<PathBuf> {
+        let stem = video.file_stem()?.to_string_lossy();
+        Some(video.with_file_name(format!("{stem}-trailer.mp4")))
+    }
+
+    /// Trailer of `metadata` to save for `video`, with its URL
+    ///
+    /// Nothing is downloaded, so this is also what a dry run reports.
+    #[must_use]
+    pub fn plan(metadata: &MediaMetadata, video: &Path) -> Option<(PathBuf, String)> {
+        let trailer = metadata.trailer()?;
+        Some((Self::trailer_path(video)?, trailer.url.clone()))
+    }
+
+    /// Download the video at `url` to `output_path` as MP4
+    pub async fn download(&self, url: &str, output_path: &Path) -> Result<(), TrailerError> {
+        let mut command = Command::new(&self.ytdlp_path);
+        command
+            .args(["--quiet", "--no-warnings", "--no-playlist"])
+            .args(["--format", "bv*[ext=mp4]+ba[ext=m4a]/b[ext=mp4]/bv*+ba/b"])
+            .args(["--merge-output-format", "mp4"]);
+        if let Some(max_size) = self.max_size {
+            command.arg("--max-filesize").arg(max_size.to_string());
+        }
+
+        let output = command
+            .arg("--output")
+            .arg(output_path)
+            .arg(url)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    TrailerError::NotInstalled(self.ytdlp_path.display().to_string())
+                } else {
+                    TrailerError::Io(e)
+                }
+            })?;
+
+        if !output.status.success() {
+            return Err(TrailerError::Failed(format!(
+                "yt-dlp exited with {} for {url}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        // yt-dlp skips files over --max-filesize without failing
+        if !tokio::fs::try_exists(output_path).await? {
+            return Err(TrailerError::NotSaved(url.to_string()));
+        }
+
+        debug!("Saved trailer {url} to {}", output_path.display());
+        Ok(())
+    }
+}
+
+/// Trailer download errors
+#[derive(Debug, thiserror::Error)]
+pub enum TrailerError {
+    #[error("yt-dlp not found: {0}")]
+    NotInstalled(String),
+
+    #[error("Download failed: {0}")]
+    Failed(String),
+
+    #[error("Trailer not saved, probably over the size limit: {0}")]
+    NotSaved(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraper::VideoInfo;
+
+    #[test]
+    fn test_plan_trailer() {
+        let video = |kind: &str, official: bool, key: &str| VideoInfo {
+            name: kind.to_string(),
+            kind: kind.to_string(),
+            url: format!("https://www.youtube.com/watch?v={key}"),
+            language: Some("en".to_string()),
+            official,
+        };
+        let mut metadata = MediaMetadata {
+            videos: vec![video("Clip", true, "clip"), video("Teaser", true, "teaser")],
+            ..Default::default()
+        };
+        let movie = Path::new("Movies/Heat (1995)/Heat (1995).mkv");
+
+        let (path, url) = TrailerDownloader::plan(&metadata, movie).unwrap();
+        assert_eq!(path, Path::new("Movies/Heat (1995)/Heat (1995)-trailer.mp4"));
+        assert!(url.ends_with("teaser"));
+
+        metadata.videos.push(video("Trailer", false, "fan"));
+        metadata.videos.push(video("Trailer", true, "official"));
+        let (_, url) = TrailerDownloader::plan(&metadata, movie).unwrap();
+        assert!(url.ends_with("official"));
+
+        metadata.videos.clear();
+        assert!(TrailerDownloader::plan(&metadata, movie).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_missing_ytdlp() {
+        let dir = tempfile::tempdir().unwrap();
+        let trailer = TrailerDownloader::new(dir.path().join("no-yt-dlp"));
+        let result = trailer
+            .download("https://www.youtube.com/watch?v=x", &dir.path().join("t.mp4"))
+            .await;
+        assert!(matches!(result, Err(TrailerError::NotInstalled(_))));
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+/// Book search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookInfo {
+    /// Provider-specific ID
+    pub id: String,
+    /// Title
+    pub title: String,
+    /// Authors, main author first
+    pub authors: Vec<String>,
+    /// Year of first publication
+    pub year: Option<i32>,
+    /// An ISBN-13 of one of its editions
+    pub isbn: Option<String>,
+    /// Cover URL
+    pub cover_url: Option<String>,
+    /// Provider name
+    pub provider: String,
+}
+
+/// Complete metadata for a book
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookMetadata {
+    /// Provider-specific ID
+    pub id: String,
+    /// Title
+    pub title: String,
+    /// Subtitle
+    pub subtitle: Option<String>,
+    /// Authors, main author first
+    pub authors: Vec<String>,
+    /// Description
+    pub description: Option<String>,
+    /// Publisher
+    pub publisher: Option<String>,
+    /// Publication date (YYYY, YYYY-MM or YYYY-MM-DD)
+    pub published_date: Option<String>,
+    /// Language code
+    pub language: Option<String>,
+    /// Number of pages
+    pub page_count: Option<i32>,
+    /// ISBN-13
+    pub isbn: Option<String>,
+    /// Subjects and genres
+    pub subjects: Vec<String>,
+    /// Series the book is part of
+    pub series: Option<String>,
+    /// Position in the series (may be fractional, e.g. 1.5 for a novella)
+    pub series_index: Option<f32>,
+    /// Average rating (0-5)
+    pub rating: Option<f64>,
+    /// Cover URL
+    pub cover_url: Option<String>,
+    /// Provider name
+    pub provider: String,
+}
+
+/// ISBN-13 of an ISBN-10 or ISBN-13, ignoring hyphens and spaces
+///
+/// Returns `None` when the check digit doesn't match, or for anything but
+/// ASCII digits and a final `X`.
+#[must_use]
+pub fn normalize_isbn(value: &str) -> Option<String> {
+    let chars: Vec<char> = value
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let (last, digits) = chars.split_last()?;
+    if !digits.iter().all(char::is_ascii_digit) || !(last.is_ascii_digit() || *last == 'X') {
+        return None;
+    }
+
+    match chars.len() {
+        10 => {
+            let mut sum = 0;
+            for (i, c) in chars.iter().enumerate() {
+                let digit = match c {
+                    'X' if i == 9 => 10,
+                    _ => c.to_digit(10)?,
+                };
+                sum += digit * (10 - i as u32);
+            }
+            if sum % 11 != 0 {
+                return None;
+            }
+
+            let mut isbn: String = "978".chars().chain(chars[..9].iter().copied()).collect();
+            isbn.push(isbn13_check_digit(&isbn)?);
+            Some(isbn)
+        }
+        13 => {
+            let isbn: String = chars.into_iter().collect();
+            (isbn13_check_digit(&isbn[..12])? == isbn.chars().last()?).then_some(isbn)
+        }
+        _ => None,
+    }
+}
+
+/// Check digit of the first 12 digits of an ISBN-13
+fn isbn13_check_digit(digits: &str) -> Option<char> {
+    let mut sum = 0;
+    for (i, c) in digits.chars().enumerate() {
+        sum += c.to_digit(10)? * if i % 2 == 0 { 1 } else { 3 };
+    }
+    char::from_digit((10 - sum % 10) % 10, 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_isbn() {
+        assert_eq!(normalize_isbn("0-7475-3269-9").as_deref(), Some("9780747532699"));
+        assert_eq!(normalize_isbn("978-0-7475-3269-9").as_deref(), Some("9780747532699"));
+        assert_eq!(normalize_isbn("080442957x").as_deref(), Some("9780804429573"));
+
+        // Wrong check digit, wrong length, letters
+        assert_eq!(normalize_isbn("0-7475-3269-8"), None);
+        assert_eq!(normalize_isbn("978074753269"), None);
+        assert_eq!(normalize_isbn("97807475326AB"), None);
+
+        // Non-ASCII characters, including digits of other scripts
+        assert_eq!(normalize_isbn("97807475326é9"), None);
+        assert_eq!(normalize_isbn("٩٧٨٠٧٤٧٥٣٢٦٩٩"), None);
+    }
+}
@@ -0,0 +1,84 @@
+use super::ExternalIds;
+use serde::{Deserialize, Serialize};
+
+/// Comic or manga series search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComicInfo {
+    /// Provider-specific ID
+    pub id: String,
+    /// Title
+    pub title: String,
+    /// Year of first publication
+    pub year: Option<i32>,
+    /// Publication status
+    pub status: Option<String>,
+    /// Cover URL
+    pub cover_url: Option<String>,
+    /// Provider name
+    pub provider: String,
+}
+
+/// Complete metadata for a comic or manga series
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComicMetadata {
+    /// Provider-specific ID
+    pub id: String,
+    /// Title
+    pub title: String,
+    /// Alternative titles (original, romanized, translations...)
+    pub aliases: Vec<String>,
+    /// Description
+    pub description: Option<String>,
+    /// Writers
+    pub authors: Vec<String>,
+    /// Artists
+    pub artists: Vec<String>,
+    /// Genres and themes
+    pub genres: Vec<String>,
+    /// Publication status
+    pub status: Option<String>,
+    /// Year of first publication
+    pub year: Option<i32>,
+    /// Language of the original work
+    pub original_language: Option<String>,
+    /// Target demographic (shounen, seinen...)
+    pub demographic: Option<String>,
+    /// Last volume, once the series is complete
+    pub last_volume: Option<f32>,
+    /// Last chapter, once the series is complete
+    pub last_chapter: Option<f32>,
+    /// Cover URL
+    pub cover_url: Option<String>,
+    /// External IDs
+    pub external_ids: ExternalIds,
+    /// Provider name
+    pub provider: String,
+}
+
+/// Chapter of a comic or manga series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterInfo {
+    /// Provider-specific ID
+    pub id: String,
+    /// Volume number, when the chapter has been collected
+    pub volume: Option<f32>,
+    /// Chapter number (may be fractional, e.g. 10.5 for an extra); `None` for oneshots
+    pub chapter: Option<f32>,
+    /// Title
+    pub title: Option<String>,
+    /// Language of the translation
+    pub language: Option<String>,
+    /// Number of pages
+    pub pages: Option<i32>,
+    /// Publication date
+    pub published_at: Option<String>,
+}
+
+/// Cover of a volume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverInfo {
+    /// Volume number; `None` for covers of the whole series
+    pub volume: Option<f32>,
+    /// Image URL
+    pub url: String,
+}
@@ -1,3 +1,4 @@
+use super::ExternalIds;
 use serde::{Deserialize, Serialize};
 
 /// Media type classification
@@ -34,6 +35,20 @@ impl std::fmt::Display for MediaType {
     }
 }
 
+impl std::str::FromStr for MediaType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "movie" => Ok(Self::Movie),
+            "tv" | "tvshow" | "series" => Ok(Self::Tv),
+            "anime" => Ok(Self::Anime),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(format!("Unknown media type: {s}")),
+        }
+    }
+}
+
 /// Unified search result from any provider
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaInfo {
@@ -59,6 +74,12 @@ pub struct MediaInfo {
     pub provider: String,
     /// Provider-specific score for ranking
     pub popularity: Option<f64>,
+    /// External IDs known at search time (used for cross-provider deduplication)
+    #[serde(default)]
+    pub external_ids: ExternalIds,
+    /// Provider release format (e.g. AniList `TV`, `MOVIE`, `ONA`)
+    #[serde(default)]
+    pub format: Option<String>,
 }
 
 impl MediaInfo {
@@ -80,6 +101,8 @@ impl MediaInfo {
             rating: None,
             provider: provider.into(),
             popularity: None,
+            external_ids: ExternalIds::default(),
+            format: None,
         }
     }
 
@@ -138,6 +161,28 @@ impl MediaInfo {
         self
     }
 
+    /// Builder pattern: set external IDs
+    #[must_use]
+    pub fn with_external_ids(mut self, external_ids: ExternalIds) -> Self {
+        self.external_ids = external_ids;
+        self
+    }
+
+    /// Builder pattern: set provider release format
+    #[must_use]
+    pub fn with_format(mut self, format: Option<String>) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Whether the provider reports this entry as a film (e.g. an anime movie)
+    #[must_use]
+    pub fn is_movie_format(&self) -> bool {
+        self.format
+            .as_deref()
+            .is_some_and(|f| f.eq_ignore_ascii_case("movie"))
+    }
+
     /// Get all titles for matching (primary + original + alternatives)
     pub fn all_titles(&self) -> Vec<&str> {
         let mut titles = vec![self.title.as_str()];
@@ -1,5 +1,7 @@
 use super::MediaType;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Complete metadata for a media item
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +14,9 @@ pub struct MediaMetadata {
     pub original_title: Option<String>,
     /// Sort title
     pub sort_title: Option<String>,
+    /// Alternative titles (translations, working titles...)
+    #[serde(default)]
+    pub aliases: Vec<String>,
     /// Media type
     pub media_type: MediaType,
     /// Tagline
@@ -60,6 +65,59 @@ pub struct MediaMetadata {
     pub cast: Vec<PersonInfo>,
     /// Crew members
     pub crew: Vec<PersonInfo>,
+
+    /// Scores from rating sites (IMDb, Rotten Tomatoes...)
+    #[serde(default)]
+    pub ratings: Vec<ExternalRating>,
+
+    /// Collection (franchise) a movie belongs to
+    #[serde(default)]
+    pub collection: Option<CollectionInfo>,
+
+    /// Sequels, prequels, side stories and other related entries
+    #[serde(default)]
+    pub related: Vec<RelatedMedia>,
+
+    /// Next episode to air, for shows still running
+    #[serde(default)]
+    pub next_airing: Option<AiringEpisode>,
+    /// Episodes scheduled to air, in airing order
+    #[serde(default)]
+    pub airing_schedule: Vec<AiringEpisode>,
+
+    /// Trailers, teasers and clips
+    #[serde(default)]
+    pub videos: Vec<VideoInfo>,
+}
+
+impl MediaMetadata {
+    /// Entry continuing this one, e.g. the next season of an anime
+    #[must_use]
+    pub fn sequel(&self) -> Option<&RelatedMedia> {
+        self.related.iter().find(|r| r.relation == "sequel")
+    }
+
+    /// Entry this one continues
+    #[must_use]
+    pub fn prequel(&self) -> Option<&RelatedMedia> {
+        self.related.iter().find(|r| r.relation == "prequel")
+    }
+
+    /// Best trailer: an official one, then any trailer, then a teaser
+    #[must_use]
+    pub fn trailer(&self) -> Option<&VideoInfo> {
+        let rank = |v: &VideoInfo| match (v.kind.as_str(), v.official) {
+            ("Trailer", true) => Some(0),
+            ("Trailer", false) => Some(1),
+            ("Teaser", _) => Some(2),
+            _ => None,
+        };
+        self.videos
+            .iter()
+            .filter_map(|v| Some((rank(v)?, v)))
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, v)| v)
+    }
 }
 
 impl Default for MediaMetadata {
@@ -69,6 +127,7 @@ impl Default for MediaMetadata {
             title: String::new(),
             original_title: None,
             sort_title: None,
+            aliases: Vec::new(),
             media_type: MediaType::Unknown,
             tagline: None,
             overview: None,
@@ -91,6 +150,12 @@ impl Default for MediaMetadata {
             seasons: Vec::new(),
             cast: Vec::new(),
             crew: Vec::new(),
+            ratings: Vec::new(),
+            collection: None,
+            related: Vec::new(),
+            next_airing: None,
+            airing_schedule: Vec::new(),
+            videos: Vec::new(),
         }
     }
 }
@@ -131,6 +196,59 @@ mod tests {
         assert_eq!(ids1.tvdb, Some("789".to_string()));
     }
 
+    #[test]
+    fn test_external_ids_overlaps() {
+        let ids1 = ExternalIds {
+            tmdb: Some("123".to_string()),
+            mal: Some("5114".to_string()),
+            ..Default::default()
+        };
+        let ids2 = ExternalIds {
+            anilist: Some("5114".to_string()),
+            mal: Some("5114".to_string()),
+            ..Default::default()
+        };
+        let ids3 = ExternalIds {
+            anilist: Some("123".to_string()),
+            ..Default::default()
+        };
+
+        assert!(ids1.overlaps(&ids2));
+        assert!(!ids1.overlaps(&ids3));
+        assert!(!ExternalIds::default().overlaps(&ExternalIds::default()));
+    }
+
+    #[test]
+    fn test_artwork_preferred() {
+        let artwork = |url: &str, language: Option<&str>, vote_average: f64, vote_count: i32| {
+            Artwork {
+                url: url.to_string(),
+                language: language.map(str::to_string),
+                width: None,
+                height: None,
+                vote_average: Some(vote_average),
+                vote_count: Some(vote_count),
+            }
+        };
+        let images = [
+            artwork("en-1", Some("en"), 5.3, 4),
+            artwork("en-2", Some("en"), 5.7, 12),
+            // A single perfect vote doesn't beat a dozen good ones
+            artwork("en-3", Some("en"), 10.0, 1),
+            artwork("textless", None, 5.2, 3),
+            artwork("ja", Some("ja"), 5.0, 1),
+        ];
+
+        let pick = |images: &[Artwork], language| {
+            Artwork::preferred(images, language).map(|a| a.url.clone())
+        };
+        assert_eq!(pick(&images, Some("en")).as_deref(), Some("en-2"));
+        assert_eq!(pick(&images, Some("de")).as_deref(), Some("textless"));
+        assert_eq!(pick(&images, None).as_deref(), Some("textless"));
+        assert_eq!(pick(&images[4..], Some("de")).as_deref(), Some("ja"));
+        assert_eq!(pick(&[], Some("en")), None);
+    }
+
     #[test]
     fn test_external_ids_has_any() {
         let empty = ExternalIds::default();
@@ -144,6 +262,68 @@ mod tests {
     }
 }
 
+/// Collection of movies, e.g. a franchise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionInfo {
+    /// Provider-specific ID
+    pub id: String,
+    /// Name
+    pub name: String,
+    /// Poster URL
+    pub poster_url: Option<String>,
+    /// Backdrop URL
+    pub backdrop_url: Option<String>,
+}
+
+/// Entry of the same provider related to a media item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedMedia {
+    /// Provider-specific ID
+    pub id: String,
+    /// Primary title
+    pub title: String,
+    /// How it relates, e.g. "sequel", "prequel", "side_story", "spin_off"
+    pub relation: String,
+    /// Media type
+    pub media_type: MediaType,
+    /// Format (TV, MOVIE, OVA...)
+    pub format: Option<String>,
+    /// Release year
+    pub year: Option<i32>,
+    /// Poster URL
+    pub poster_url: Option<String>,
+}
+
+/// Scheduled airing of an episode, as shown on a calendar
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiringEpisode {
+    /// Provider-specific ID of the series
+    pub series_id: String,
+    /// Title of the series
+    pub series_title: String,
+    /// Episode number
+    pub episode: i32,
+    /// When the episode airs
+    pub airs_at: DateTime<Utc>,
+    /// Provider name
+    pub provider: String,
+}
+
+/// Trailer, teaser or clip hosted on a video site
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VideoInfo {
+    /// Title of the video
+    pub name: String,
+    /// "Trailer", "Teaser", "Clip", "Featurette"...
+    pub kind: String,
+    /// Page the video plays on, e.g. a YouTube watch URL
+    pub url: String,
+    /// Language code (e.g., "en")
+    pub language: Option<String>,
+    /// Published by the studio rather than uploaded by fans
+    pub official: bool,
+}
+
 /// Image URLs for a media item
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ImageSet {
@@ -159,8 +339,113 @@ pub struct ImageSet {
     pub banner: Option<String>,
 }
 
-/// External IDs for cross-referencing
+/// Every image a provider has for a media item
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageGallery {
+    pub posters: Vec<Artwork>,
+    pub backdrops: Vec<Artwork>,
+    pub logos: Vec<Artwork>,
+}
+
+/// One image of a gallery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Artwork {
+    /// Image URL
+    pub url: String,
+    /// Language of the text on the image; `None` for textless images
+    pub language: Option<String>,
+    /// Width in pixels of the original image
+    pub width: Option<i32>,
+    /// Height in pixels of the original image
+    pub height: Option<i32>,
+    /// Average of the votes (0-10 scale)
+    pub vote_average: Option<f64>,
+    /// Vote count
+    pub vote_count: Option<i32>,
+}
+
+impl Artwork {
+    /// Best voted image in `language`, falling back to textless images and
+    /// then to any image; `None` picks textless images first
+    #[must_use]
+    pub fn preferred<'a>(images: &'a [Self], language: Option<&str>) -> Option<&'a Self> {
+        let best = |language: Option<&str>| {
+            images
+                .iter()
+                .filter(|i| i.language.as_deref() == language)
+                .max_by(|a, b| a.score().total_cmp(&b.score()))
+        };
+
+        language
+            .and_then(|l| best(Some(l)))
+            .or_else(|| best(None))
+            .or_else(|| images.iter().max_by(|a, b| a.score().total_cmp(&b.score())))
+    }
+
+    /// Votes count for little until there are a few of them
+    fn score(&self) -> f64 {
+        let votes = f64::from(self.vote_count.unwrap_or(0));
+        self.vote_average.unwrap_or(0.0) * votes / (votes + 2.0)
+    }
+}
+
+/// Size of images picked from a gallery
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Original,
+}
+
+/// Where a movie or TV show can be watched, by region (ISO 3166-1 code)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchAvailability {
+    pub regions: BTreeMap<String, RegionAvailability>,
+}
+
+impl WatchAvailability {
+    /// Availability in one region, e.g. "US"
+    #[must_use]
+    pub fn region(&self, code: &str) -> Option<&RegionAvailability> {
+        self.regions.get(&code.to_ascii_uppercase())
+    }
+}
+
+/// Services offering a movie or TV show in one region
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionAvailability {
+    /// Page listing the offers, with links to each service
+    pub link: Option<String>,
+    /// Included in a subscription
+    pub flatrate: Vec<WatchProvider>,
+    /// Free with ads
+    pub ads: Vec<WatchProvider>,
+    /// Free
+    pub free: Vec<WatchProvider>,
+    /// For rent
+    pub rent: Vec<WatchProvider>,
+    /// For sale
+    pub buy: Vec<WatchProvider>,
+}
+
+/// Streaming service, store or channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchProvider {
+    /// Provider-specific ID
+    pub id: String,
+    /// Name, e.g. "Netflix"
+    pub name: String,
+    /// Logo URL
+    pub logo_url: Option<String>,
+    /// Lower is shown first
+    pub display_priority: Option<i32>,
+}
+
+/// External IDs for cross-referencing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExternalIds {
     pub imdb: Option<String>,
     pub tmdb: Option<String>,
@@ -169,6 +454,8 @@ pub struct ExternalIds {
     pub anidb: Option<String>,
     pub mal: Option<String>,
     pub bangumi: Option<String>,
+    pub douban: Option<String>,
+    pub trakt: Option<String>,
 }
 
 impl ExternalIds {
@@ -182,6 +469,43 @@ impl ExternalIds {
             || self.anidb.is_some()
             || self.mal.is_some()
             || self.bangumi.is_some()
+            || self.douban.is_some()
+            || self.trakt.is_some()
+    }
+
+    /// Check if both sets share at least one identical ID
+    #[must_use]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        fn same(a: Option<&String>, b: Option<&String>) -> bool {
+            matches!((a, b), (Some(a), Some(b)) if a == b)
+        }
+
+        same(self.imdb.as_ref(), other.imdb.as_ref())
+            || same(self.tmdb.as_ref(), other.tmdb.as_ref())
+            || same(self.tvdb.as_ref(), other.tvdb.as_ref())
+            || same(self.anilist.as_ref(), other.anilist.as_ref())
+            || same(self.anidb.as_ref(), other.anidb.as_ref())
+            || same(self.mal.as_ref(), other.mal.as_ref())
+            || same(self.bangumi.as_ref(), other.bangumi.as_ref())
+            || same(self.douban.as_ref(), other.douban.as_ref())
+            || same(self.trakt.as_ref(), other.trakt.as_ref())
+    }
+
+    /// ID of the entry on a provider, by provider ID (OMDb uses IMDb's)
+    #[must_use]
+    pub fn get(&self, provider: &str) -> Option<&str> {
+        match provider {
+            "imdb" | "omdb" => self.imdb.as_deref(),
+            "tmdb" => self.tmdb.as_deref(),
+            "tvdb" => self.tvdb.as_deref(),
+            "anilist" => self.anilist.as_deref(),
+            "anidb" => self.anidb.as_deref(),
+            "mal" => self.mal.as_deref(),
+            "bangumi" => self.bangumi.as_deref(),
+            "douban" => self.douban.as_deref(),
+            "trakt" => self.trakt.as_deref(),
+            _ => None,
+        }
     }
 
     /// Merge with another `ExternalIds`, preferring non-None values from other
@@ -207,9 +531,26 @@ impl ExternalIds {
         if other.bangumi.is_some() {
             self.bangumi = other.bangumi.clone();
         }
+        if other.douban.is_some() {
+            self.douban = other.douban.clone();
+        }
+        if other.trakt.is_some() {
+            self.trakt = other.trakt.clone();
+        }
     }
 }
 
+/// Score from a rating site
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalRating {
+    /// Rating site (e.g., "imdb", "rotten_tomatoes", "metacritic")
+    pub source: String,
+    /// Score (0-10 scale)
+    pub score: f64,
+    /// Vote count
+    pub votes: Option<i32>,
+}
+
 /// Season information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SeasonInfo {
@@ -240,6 +581,9 @@ pub struct EpisodeInfo {
     pub episode: i32,
     /// Absolute episode number (for anime)
     pub absolute_number: Option<i32>,
+    /// Title in the original language, e.g. Japanese for anime
+    #[serde(default)]
+    pub original_title: Option<String>,
     /// Air date
     pub air_date: Option<String>,
     /// Overview
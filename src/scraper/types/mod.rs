@@ -1,5 +1,15 @@
+mod book;
+mod comic;
 mod media;
 mod metadata;
+mod music;
 
+pub use book::{normalize_isbn, BookInfo, BookMetadata};
+pub use comic::{ChapterInfo, ComicInfo, ComicMetadata, CoverInfo};
 pub use media::{MediaInfo, MediaType};
-pub use metadata::{EpisodeInfo, ExternalIds, ImageSet, MediaMetadata, PersonInfo, SeasonInfo};
+pub use metadata::{
+    AiringEpisode, Artwork, CollectionInfo, EpisodeInfo, ExternalIds, ExternalRating,
+    ImageGallery, ImageSet, ImageSize, MediaMetadata, PersonInfo, RegionAvailability,
+    RelatedMedia, SeasonInfo, VideoInfo, WatchAvailability, WatchProvider,
+};
+pub use music::{AlbumInfo, AlbumMetadata, ArtistInfo, TrackInfo};
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// Artist search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtistInfo {
+    /// Provider-specific ID
+    pub id: String,
+    /// Name
+    pub name: String,
+    /// Name for sorting (e.g., "Beatles, The")
+    pub sort_name: Option<String>,
+    /// Comment telling apart artists of the same name
+    pub disambiguation: Option<String>,
+    /// Person, group, orchestra...
+    pub kind: Option<String>,
+    /// Country code (e.g., "GB")
+    pub country: Option<String>,
+    /// Match score (0-100)
+    pub score: Option<i32>,
+    /// Provider name
+    pub provider: String,
+}
+
+/// Album search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlbumInfo {
+    /// Provider-specific ID
+    pub id: String,
+    /// Title
+    pub title: String,
+    /// Credited artist(s)
+    pub artist: Option<String>,
+    /// ID of the first credited artist
+    pub artist_id: Option<String>,
+    /// Year of the first release
+    pub year: Option<i32>,
+    /// Album, EP, single...
+    pub kind: Option<String>,
+    /// Match score (0-100)
+    pub score: Option<i32>,
+    /// Provider name
+    pub provider: String,
+}
+
+/// Complete metadata for an album
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlbumMetadata {
+    /// Provider-specific ID
+    pub id: String,
+    /// Title
+    pub title: String,
+    /// Credited artist(s)
+    pub artist: Option<String>,
+    /// ID of the first credited artist
+    pub artist_id: Option<String>,
+    /// Date of the first release (YYYY, YYYY-MM or YYYY-MM-DD)
+    pub release_date: Option<String>,
+    /// Album, EP, single...
+    pub kind: Option<String>,
+    /// Genres
+    pub genres: Vec<String>,
+    /// Record label
+    pub label: Option<String>,
+    /// Release the track list was taken from
+    pub release_id: Option<String>,
+    /// Tracks, in disc and track order
+    pub tracks: Vec<TrackInfo>,
+    /// Front cover URL
+    pub cover_url: Option<String>,
+    /// Provider name
+    pub provider: String,
+}
+
+/// Track of an album
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackInfo {
+    /// Provider-specific recording ID
+    pub id: String,
+    /// Title
+    pub title: String,
+    /// Credited artist(s), when they differ from the album's
+    pub artist: Option<String>,
+    /// Disc number, from 1
+    pub disc_number: i32,
+    /// Track number on the disc, from 1
+    pub track_number: i32,
+    /// Length in milliseconds
+    pub length_ms: Option<i64>,
+}
@@ -0,0 +1,120 @@
+use super::Writer;
+use crate::scraper::types::{EpisodeInfo, MediaMetadata};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name of the JSON sidecar of a movie or series folder
+pub const METADATA_JSON: &str = "metadata.json";
+
+/// Sidecar files written next to organized or exported media
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum MetadataFormat {
+    /// Kodi-style XML NFOs
+    #[default]
+    Nfo,
+    /// The full metadata as JSON, for tools that prefer it over Kodi XML
+    Json,
+    /// Both NFOs and JSON
+    Both,
+}
+
+impl MetadataFormat {
+    #[must_use]
+    pub const fn writes_nfo(self) -> bool {
+        matches!(self, Self::Nfo | Self::Both)
+    }
+
+    #[must_use]
+    pub const fn writes_json(self) -> bool {
+        matches!(self, Self::Json | Self::Both)
+    }
+}
+
+impl Writer {
+    /// Write the full metadata of a movie or series as pretty-printed JSON
+    pub async fn write_metadata_json(path: &Path, metadata: &MediaMetadata) -> Result<()> {
+        Self::write_json(path, metadata).await
+    }
+
+    /// JSON counterpart of [`Writer::write_series_tree`]
+    ///
+    /// The series, seasons included, goes into `root`'s `metadata.json` and
+    /// each episode into a `.json` next to its video file. Returns the
+    /// written files.
+    pub async fn write_series_json(
+        root: &Path,
+        metadata: &MediaMetadata,
+        episodes: &[(PathBuf, EpisodeInfo)],
+    ) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+
+        let series = root.join(METADATA_JSON);
+        Self::write_metadata_json(&series, metadata).await?;
+        written.push(series);
+
+        for (video, episode) in episodes {
+            let path = video.with_extension("json");
+            Self::write_json(&path, episode).await?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    async fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+        let json = serde_json::to_string_pretty(value)?;
+        Self::write_atomic(path, json.as_bytes(), false).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraper::types::MediaType;
+
+    #[tokio::test]
+    async fn test_write_series_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = MediaMetadata {
+            title: "Show".to_string(),
+            media_type: MediaType::Tv,
+            ..Default::default()
+        };
+        let episode = EpisodeInfo {
+            id: "1".to_string(),
+            title: "Pilot".to_string(),
+            season: 1,
+            episode: 1,
+            absolute_number: None,
+            original_title: None,
+            air_date: None,
+            overview: None,
+            runtime: None,
+            rating: None,
+            still_url: None,
+            provider: "tmdb".to_string(),
+        };
+        let video = dir.path().join("Season 01/Show S01E01.mkv");
+
+        let written = Writer::write_series_json(dir.path(), &metadata, &[(video, episode)])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                dir.path().join("metadata.json"),
+                dir.path().join("Season 01/Show S01E01.json"),
+            ]
+        );
+        let json = tokio::fs::read_to_string(&written[0]).await.unwrap();
+        let parsed: MediaMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.title, "Show");
+        let json = tokio::fs::read_to_string(&written[1]).await.unwrap();
+        assert!(json.contains("\"title\": \"Pilot\""));
+    }
+}
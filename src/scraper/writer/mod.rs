@@ -1,3 +1,8 @@
+mod json;
+mod music;
 mod nfo;
+mod profile;
 
+pub use json::{METADATA_JSON, MetadataFormat};
 pub use nfo::Writer;
+pub use profile::{NfoDialect, NfoField, NfoProfile};
@@ -0,0 +1,194 @@
+use super::Writer;
+use crate::scraper::types::{AlbumMetadata, ArtistInfo, TrackInfo};
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+impl Writer {
+    /// Write artist NFO file (`artist.nfo` in the artist folder)
+    pub async fn write_artist_nfo(path: &Path, artist: &ArtistInfo) -> Result<()> {
+        let nfo = ArtistNfo::from(artist);
+        Self::write_nfo(path, &nfo).await
+    }
+
+    /// Write album NFO file (`album.nfo` in the album folder)
+    pub async fn write_album_nfo(path: &Path, album: &AlbumMetadata) -> Result<()> {
+        let nfo = AlbumNfo::from(album);
+        Self::write_nfo(path, &nfo).await
+    }
+}
+
+// NFO structures as Kodi's music library reads them
+
+#[derive(Serialize)]
+#[serde(rename = "artist")]
+struct ArtistNfo {
+    name: String,
+    #[serde(rename = "musicBrainzArtistID", skip_serializing_if = "Option::is_none")]
+    musicbrainz_id: Option<String>,
+    sortname: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    disambiguation: Option<String>,
+}
+
+impl From<&ArtistInfo> for ArtistNfo {
+    fn from(a: &ArtistInfo) -> Self {
+        Self {
+            name: a.name.clone(),
+            musicbrainz_id: (a.provider == "musicbrainz").then(|| a.id.clone()),
+            sortname: a.sort_name.clone(),
+            kind: a.kind.clone(),
+            disambiguation: a.disambiguation.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "album")]
+struct AlbumNfo {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    musicbrainzalbumid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    musicbrainzreleasegroupid: Option<String>,
+    artistdesc: Option<String>,
+    genre: Vec<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    label: Option<String>,
+    releasedate: Option<String>,
+    year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumb: Option<String>,
+    #[serde(rename = "albumArtistCredits", skip_serializing_if = "Option::is_none")]
+    artist_credits: Option<ArtistCreditNfo>,
+    track: Vec<TrackNfo>,
+}
+
+#[derive(Serialize)]
+struct ArtistCreditNfo {
+    artist: String,
+    #[serde(rename = "musicBrainzArtistID", skip_serializing_if = "Option::is_none")]
+    musicbrainz_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TrackNfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disc: Option<i32>,
+    position: i32,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artist: Option<String>,
+    duration: Option<String>,
+    #[serde(rename = "musicBrainzTrackID", skip_serializing_if = "Option::is_none")]
+    musicbrainz_id: Option<String>,
+}
+
+impl From<&AlbumMetadata> for AlbumNfo {
+    fn from(a: &AlbumMetadata) -> Self {
+        let musicbrainz = a.provider == "musicbrainz";
+        let multi_disc = a.tracks.iter().any(|t| t.disc_number > 1);
+
+        Self {
+            title: a.title.clone(),
+            musicbrainzalbumid: a.release_id.clone().filter(|_| musicbrainz),
+            musicbrainzreleasegroupid: musicbrainz.then(|| a.id.clone()),
+            artistdesc: a.artist.clone(),
+            genre: a.genres.clone(),
+            kind: a.kind.clone(),
+            label: a.label.clone(),
+            releasedate: a.release_date.clone(),
+            year: a
+                .release_date
+                .as_ref()
+                .and_then(|d| d.split('-').next())
+                .and_then(|y| y.parse().ok()),
+            thumb: a.cover_url.clone(),
+            artist_credits: a.artist.as_ref().map(|artist| ArtistCreditNfo {
+                artist: artist.clone(),
+                musicbrainz_id: a.artist_id.clone().filter(|_| musicbrainz),
+            }),
+            track: a
+                .tracks
+                .iter()
+                .map(|t| TrackNfo::new(t, multi_disc, musicbrainz))
+                .collect(),
+        }
+    }
+}
+
+impl TrackNfo {
+    fn new(t: &TrackInfo, multi_disc: bool, musicbrainz: bool) -> Self {
+        Self {
+            disc: multi_disc.then_some(t.disc_number),
+            position: t.track_number,
+            title: t.title.clone(),
+            artist: t.artist.clone(),
+            duration: t.length_ms.map(|ms| {
+                let seconds = (ms + 500) / 1000;
+                format!("{}:{:02}", seconds / 60, seconds % 60)
+            }),
+            musicbrainz_id: musicbrainz.then(|| t.id.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_music_nfos() {
+        let dir = tempfile::tempdir().unwrap();
+        let artist = ArtistInfo {
+            id: "b10bbbfc-cf9e-42e0-be17-e2c3e1d2600d".to_string(),
+            name: "The Beatles".to_string(),
+            sort_name: Some("Beatles, The".to_string()),
+            disambiguation: None,
+            kind: Some("Group".to_string()),
+            country: Some("GB".to_string()),
+            score: Some(100),
+            provider: "musicbrainz".to_string(),
+        };
+        let track = |disc, number, title: &str| TrackInfo {
+            id: format!("rec-{disc}-{number}"),
+            title: title.to_string(),
+            artist: None,
+            disc_number: disc,
+            track_number: number,
+            length_ms: Some(125_600),
+        };
+        let album = AlbumMetadata {
+            id: "rg-1".to_string(),
+            title: "The Beatles".to_string(),
+            artist: Some("The Beatles".to_string()),
+            artist_id: Some(artist.id.clone()),
+            release_date: Some("1968-11-22".to_string()),
+            kind: Some("Album".to_string()),
+            release_id: Some("rel-1".to_string()),
+            tracks: vec![track(1, 1, "Back in the U.S.S.R."), track(2, 1, "Birthday")],
+            provider: "musicbrainz".to_string(),
+            ..Default::default()
+        };
+
+        let path = dir.path().join("artist.nfo");
+        Writer::write_artist_nfo(&path, &artist).await.unwrap();
+        let xml = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(xml.contains(
+            "<name>The Beatles</name><musicBrainzArtistID>b10bbbfc-cf9e-42e0-be17-e2c3e1d2600d</musicBrainzArtistID><sortname>Beatles, The</sortname><type>Group</type>"
+        ), "{xml}");
+
+        let path = dir.path().join("album.nfo");
+        Writer::write_album_nfo(&path, &album).await.unwrap();
+        let xml = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(xml.contains(
+            "<musicbrainzalbumid>rel-1</musicbrainzalbumid><musicbrainzreleasegroupid>rg-1</musicbrainzreleasegroupid>"
+        ), "{xml}");
+        assert!(xml.contains("<year>1968</year>"));
+        assert!(xml.contains(
+            "<track><disc>2</disc><position>1</position><title>Birthday</title><duration>2:06</duration><musicBrainzTrackID>rec-2-1</musicBrainzTrackID></track>"
+        ));
+    }
+}
@@ -1,8 +1,14 @@
-use crate::scraper::types::{EpisodeInfo, MediaMetadata, MediaType};
+use crate::scraper::downloader::ArtworkKind;
+use super::{NfoDialect, NfoField, NfoProfile};
+use crate::scraper::types::{
+    CollectionInfo, EpisodeInfo, ExternalIds, MediaMetadata, MediaType, SeasonInfo,
+};
+use crate::scraper::{ArtworkOptions, Downloader};
 use anyhow::Result;
 use quick_xml::se::to_string;
 use serde::Serialize;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tokio::io::AsyncWriteExt;
 
 /// NFO file writer for Kodi/Jellyfin/Emby compatibility
@@ -27,6 +33,55 @@ impl Writer {
         Self::write_nfo(path, &nfo).await
     }
 
+    /// Write the `collection.nfo` of a movie collection from `provider`
+    pub async fn write_collection_nfo(
+        path: &Path,
+        collection: &CollectionInfo,
+        provider: &str,
+    ) -> Result<()> {
+        let nfo = CollectionNfo::new(collection, provider);
+        Self::write_nfo(path, &nfo).await
+    }
+
+    /// Write all NFOs of a series in one go
+    ///
+    /// `tvshow.nfo` goes into `root`, a `season.nfo` into every season folder
+    /// holding one of `episodes`, and each episode's NFO next to its video
+    /// file. Episode files directly in `root` get no `season.nfo`. Returns
+    /// the written files.
+    pub async fn write_series_tree(
+        root: &Path,
+        metadata: &MediaMetadata,
+        episodes: &[(PathBuf, EpisodeInfo)],
+    ) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+
+        let tvshow = root.join("tvshow.nfo");
+        Self::write_tvshow_nfo(&tvshow, metadata).await?;
+        written.push(tvshow);
+
+        let mut seasons = BTreeMap::new();
+        for (video, episode) in episodes {
+            if let Some(folder) = video.parent().filter(|p| *p != root) {
+                seasons.entry(folder.to_path_buf()).or_insert(episode.season);
+            }
+        }
+        for (folder, number) in seasons {
+            let season = metadata.seasons.iter().find(|s| s.number == number);
+            let path = folder.join("season.nfo");
+            Self::write_nfo(&path, &SeasonNfo::new(number, season)).await?;
+            written.push(path);
+        }
+
+        for (video, episode) in episodes {
+            let path = video.with_extension("nfo");
+            Self::write_episode_nfo(&path, episode).await?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
     /// Auto-detect type and write appropriate NFO
     pub async fn write_nfo_auto(path: &Path, metadata: &MediaMetadata) -> Result<()> {
         match metadata.media_type {
@@ -36,20 +91,86 @@ impl Writer {
         }
     }
 
-    async fn write_nfo<T: Serialize>(path: &Path, nfo: &T) -> Result<()> {
+    /// Like [`Writer::write_nfo_auto`], referencing the local artwork next to
+    /// the NFO and adding the fields of `profile`
+    ///
+    /// Files are named as [`Downloader::save_artwork`] saves them with
+    /// `artwork`; those present are listed first as `<thumb>`s and in an
+    /// `<art>` block, so Kodi uses them instead of fetching the remote URLs.
+    pub async fn write_nfo_with(
+        path: &Path,
+        metadata: &MediaMetadata,
+        artwork: &ArtworkOptions,
+        profile: &NfoProfile,
+    ) -> Result<()> {
+        let local = LocalArt::find(path, metadata, artwork);
+        match metadata.media_type {
+            MediaType::Tv | MediaType::Anime => {
+                let nfo = TvShowNfo::from(metadata)
+                    .with_local_art(local)
+                    .with_profile(profile, metadata);
+                Self::write_nfo_backup(path, &nfo, profile.backup).await
+            }
+            MediaType::Movie | MediaType::Unknown => {
+                let nfo = MovieNfo::from(metadata)
+                    .with_local_art(local)
+                    .with_profile(profile, metadata);
+                Self::write_nfo_backup(path, &nfo, profile.backup).await
+            }
+        }
+    }
+
+    pub(super) async fn write_nfo<T: Serialize>(path: &Path, nfo: &T) -> Result<()> {
+        Self::write_nfo_backup(path, nfo, false).await
+    }
+
+    async fn write_nfo_backup<T: Serialize>(path: &Path, nfo: &T, backup: bool) -> Result<()> {
         let xml = to_string(nfo)?;
         let content = format!(
             "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n{xml}"
         );
 
+        Self::write_atomic(path, content.as_bytes(), backup).await?;
+        Ok(())
+    }
+
+    /// Write `content` to `<path>.part`, then rename it over `path`
+    ///
+    /// A crash or error midway leaves the previous file untouched rather
+    /// than a truncated one media centers refuse to parse. With `backup`, a
+    /// file being replaced is first copied to `<path>.bak`.
+    pub(super) async fn write_atomic(
+        path: &Path,
+        content: &[u8],
+        backup: bool,
+    ) -> std::io::Result<()> {
         if let Some(parent) = path.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let mut file = tokio::fs::File::create(path).await?;
-        file.write_all(content.as_bytes()).await?;
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+        let written = async {
+            let mut file = tokio::fs::File::create(&partial).await?;
+            file.write_all(content).await?;
+            file.sync_all().await
+        };
+        if let Err(e) = written.await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e);
+        }
 
-        Ok(())
+        if backup && tokio::fs::try_exists(path).await? {
+            let mut bak = path.as_os_str().to_owned();
+            bak.push(".bak");
+            if let Err(e) = tokio::fs::copy(path, &bak).await {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(e);
+            }
+        }
+
+        tokio::fs::rename(&partial, path).await
     }
 }
 
@@ -66,12 +187,28 @@ struct MovieNfo {
     runtime: Option<i32>,
     year: Option<i32>,
     premiered: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     rating: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     votes: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ratings: Option<RatingsNfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    criticrating: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    imdbid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tmdbid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tvdbid: Option<String>,
     #[serde(rename = "uniqueid")]
     uniqueids: Vec<UniqueId>,
     genre: Vec<String>,
     tag: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set: Option<SetNfo>,
     studio: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     actor: Vec<ActorNfo>,
@@ -79,10 +216,16 @@ struct MovieNfo {
     director: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     credits: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    thumb: Option<ThumbNfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    thumb: Vec<ThumbNfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     fanart: Option<FanartNfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    art: Option<ArtNfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lockdata: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dateadded: Option<String>,
 }
 
 impl From<&MediaMetadata> for MovieNfo {
@@ -134,24 +277,67 @@ impl From<&MediaMetadata> for MovieNfo {
             premiered: m.release_date.clone(),
             rating: m.rating,
             votes: m.vote_count,
+            ratings: None,
+            criticrating: None,
+            id: None,
+            imdbid: None,
+            tmdbid: None,
+            tvdbid: None,
             uniqueids,
             genre: m.genres.clone(),
             tag: m.tags.clone(),
+            set: m.collection.as_ref().map(|c| SetNfo {
+                tmdbcolid: (m.provider == "tmdb").then(|| c.id.clone()),
+                name: c.name.clone(),
+            }),
             studio: m.studios.clone(),
             actor: m.cast.iter().map(ActorNfo::from).collect(),
             director: directors,
             credits: writers,
-            thumb: m.images.poster.as_ref().map(|url| ThumbNfo {
-                aspect: "poster".to_string(),
-                value: url.clone(),
-            }),
+            thumb: m.images.poster.iter().map(|url| ThumbNfo::new("poster", url)).collect(),
             fanart: m.images.backdrop.as_ref().map(|url| FanartNfo {
-                thumb: vec![ThumbNfo {
-                    aspect: "fanart".to_string(),
-                    value: url.clone(),
-                }],
+                thumb: vec![ThumbNfo::new("fanart", url)],
             }),
+            art: None,
+            lockdata: None,
+            dateadded: None,
+        }
+    }
+}
+
+impl MovieNfo {
+    fn with_local_art(mut self, local: LocalArt) -> Self {
+        let (thumbs, fanart, art) = local.into_nfo(self.thumb, self.fanart);
+        self.thumb = thumbs;
+        self.fanart = fanart;
+        self.art = art;
+        self
+    }
+
+    fn with_profile(mut self, profile: &NfoProfile, m: &MediaMetadata) -> Self {
+        let dialect = DialectFields::new(profile, m, m.external_ids.imdb.as_ref());
+        self.rating = dialect.rating;
+        self.votes = dialect.votes;
+        self.ratings = dialect.ratings;
+        self.criticrating = dialect.criticrating;
+        self.id = dialect.id;
+        self.imdbid = dialect.imdbid;
+        self.tmdbid = dialect.tmdbid;
+        self.tvdbid = dialect.tvdbid;
+        profile.limit(NfoField::Cast, &mut self.actor, profile.max_cast);
+        profile.limit(NfoField::Crew, &mut self.director, None);
+        profile.limit(NfoField::Crew, &mut self.credits, None);
+        profile.limit(NfoField::Genres, &mut self.genre, profile.max_items);
+        profile.limit(NfoField::Tags, &mut self.tag, profile.max_items);
+        profile.limit(NfoField::Studios, &mut self.studio, profile.max_items);
+        if !profile.includes(NfoField::Artwork) {
+            self.thumb.clear();
+            self.fanart = None;
+            self.art = None;
         }
+        self.lockdata = profile.lock_data.then_some(true);
+        self.dateadded = profile.date_added.then(date_added);
+        self
     }
 }
 
@@ -165,9 +351,23 @@ struct TvShowNfo {
     premiered: Option<String>,
     #[serde(rename = "enddate")]
     enddate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     rating: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     votes: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ratings: Option<RatingsNfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    criticrating: Option<i32>,
     status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    imdbid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tmdbid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tvdbid: Option<String>,
     #[serde(rename = "uniqueid")]
     uniqueids: Vec<UniqueId>,
     genre: Vec<String>,
@@ -175,10 +375,20 @@ struct TvShowNfo {
     studio: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     actor: Vec<ActorNfo>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    thumb: Option<ThumbNfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    thumb: Vec<ThumbNfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     fanart: Option<FanartNfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    art: Option<ArtNfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lockdata: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dateadded: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    episodeguide: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    displayorder: Option<String>,
 }
 
 impl From<&MediaMetadata> for TvShowNfo {
@@ -222,51 +432,309 @@ impl From<&MediaMetadata> for TvShowNfo {
             enddate: m.end_date.clone(),
             rating: m.rating,
             votes: m.vote_count,
+            ratings: None,
+            criticrating: None,
             status: m.status.clone(),
+            id: None,
+            imdbid: None,
+            tmdbid: None,
+            tvdbid: None,
             uniqueids,
             genre: m.genres.clone(),
             tag: m.tags.clone(),
             studio: m.studios.clone(),
             actor: m.cast.iter().map(ActorNfo::from).collect(),
-            thumb: m.images.poster.as_ref().map(|url| ThumbNfo {
-                aspect: "poster".to_string(),
-                value: url.clone(),
-            }),
+            thumb: m.images.poster.iter().map(|url| ThumbNfo::new("poster", url)).collect(),
             fanart: m.images.backdrop.as_ref().map(|url| FanartNfo {
-                thumb: vec![ThumbNfo {
-                    aspect: "fanart".to_string(),
-                    value: url.clone(),
-                }],
+                thumb: vec![ThumbNfo::new("fanart", url)],
             }),
+            art: None,
+            lockdata: None,
+            dateadded: None,
+            episodeguide: None,
+            displayorder: None,
+        }
+    }
+}
+
+impl TvShowNfo {
+    fn with_local_art(mut self, local: LocalArt) -> Self {
+        let (thumbs, fanart, art) = local.into_nfo(self.thumb, self.fanart);
+        self.thumb = thumbs;
+        self.fanart = fanart;
+        self.art = art;
+        self
+    }
+
+    fn with_profile(mut self, profile: &NfoProfile, m: &MediaMetadata) -> Self {
+        let dialect = DialectFields::new(profile, m, m.external_ids.tvdb.as_ref());
+        self.rating = dialect.rating;
+        self.votes = dialect.votes;
+        self.ratings = dialect.ratings;
+        self.criticrating = dialect.criticrating;
+        self.id = dialect.id;
+        self.imdbid = dialect.imdbid;
+        self.tmdbid = dialect.tmdbid;
+        self.tvdbid = dialect.tvdbid;
+        profile.limit(NfoField::Cast, &mut self.actor, profile.max_cast);
+        profile.limit(NfoField::Genres, &mut self.genre, profile.max_items);
+        profile.limit(NfoField::Tags, &mut self.tag, profile.max_items);
+        profile.limit(NfoField::Studios, &mut self.studio, profile.max_items);
+        if !profile.includes(NfoField::Artwork) {
+            self.thumb.clear();
+            self.fanart = None;
+            self.art = None;
+        }
+        self.lockdata = profile.lock_data.then_some(true);
+        self.dateadded = profile.date_added.then(date_added);
+        if profile.episode_guide {
+            self.episodeguide = episode_guide(&m.external_ids);
+        }
+        self.displayorder.clone_from(&profile.display_order);
+        self
+    }
+}
+
+/// Rating and ID elements, as the media center of a [`NfoDialect`] reads them
+#[derive(Default)]
+struct DialectFields {
+    rating: Option<f64>,
+    votes: Option<i32>,
+    ratings: Option<RatingsNfo>,
+    criticrating: Option<i32>,
+    id: Option<String>,
+    imdbid: Option<String>,
+    tmdbid: Option<String>,
+    tvdbid: Option<String>,
+}
+
+impl DialectFields {
+    /// `plex_id` is the ID Plex's agents expect in `<id>`
+    fn new(profile: &NfoProfile, m: &MediaMetadata, plex_id: Option<&String>) -> Self {
+        let mut fields = match profile.dialect {
+            NfoDialect::Kodi => Self {
+                ratings: RatingsNfo::new(m),
+                ..Default::default()
+            },
+            NfoDialect::Jellyfin => Self {
+                rating: m.rating.map(round_rating),
+                votes: m.vote_count,
+                criticrating: m
+                    .ratings
+                    .iter()
+                    .find(|r| r.source == "rotten_tomatoes")
+                    .map(|r| (r.score * 10.0).round() as i32),
+                imdbid: m.external_ids.imdb.clone(),
+                tmdbid: m.external_ids.tmdb.clone(),
+                tvdbid: m.external_ids.tvdb.clone(),
+                ..Default::default()
+            },
+            NfoDialect::Plex => Self {
+                rating: m.rating.map(round_rating),
+                votes: m.vote_count,
+                id: plex_id.cloned(),
+                ..Default::default()
+            },
+        };
+        if !profile.includes(NfoField::Ratings) {
+            fields.rating = None;
+            fields.votes = None;
+            fields.ratings = None;
+            fields.criticrating = None;
+        }
+        fields
+    }
+}
+
+/// A 0-10 score with one decimal, as media centers display it
+fn round_rating(score: f64) -> f64 {
+    (score * 10.0).round() / 10.0
+}
+
+/// Kodi's `<ratings>`: the provider's score as default, then rating sites
+#[derive(Serialize)]
+struct RatingsNfo {
+    rating: Vec<RatingNfo>,
+}
+
+#[derive(Serialize)]
+struct RatingNfo {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@max")]
+    max: u8,
+    #[serde(rename = "@default")]
+    default: bool,
+    value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    votes: Option<i32>,
+}
+
+impl RatingsNfo {
+    fn new(m: &MediaMetadata) -> Option<Self> {
+        let rating = |source: &str, score: f64, votes, default| RatingNfo {
+            name: kodi_rating_name(source).to_string(),
+            max: 10,
+            default,
+            value: round_rating(score),
+            votes,
+        };
+
+        let mut ratings: Vec<RatingNfo> = m
+            .rating
+            .map(|score| rating(&m.provider, score, m.vote_count, true))
+            .into_iter()
+            .collect();
+        for external in &m.ratings {
+            let name = kodi_rating_name(&external.source);
+            if ratings.iter().all(|r| r.name != name) {
+                ratings.push(rating(&external.source, external.score, external.votes, false));
+            }
+        }
+        (!ratings.is_empty()).then_some(Self { rating: ratings })
+    }
+}
+
+/// Names Kodi's skins know rating sources by
+fn kodi_rating_name(source: &str) -> &str {
+    match source {
+        "tmdb" => "themoviedb",
+        "rotten_tomatoes" => "tomatometerallcritics",
+        other => other,
+    }
+}
+
+/// Current local time, as `<dateadded>` holds it
+fn date_added() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// IDs of a show as a JSON object, the `<episodeguide>` format Kodi 20+ and
+/// Jellyfin read
+fn episode_guide(ids: &ExternalIds) -> Option<String> {
+    let mut guide = serde_json::Map::new();
+    for (source, id) in [("tvdb", &ids.tvdb), ("tmdb", &ids.tmdb), ("imdb", &ids.imdb)] {
+        if let Some(id) = id {
+            guide.insert(source.to_string(), id.clone().into());
         }
     }
+    (!guide.is_empty()).then(|| serde_json::Value::Object(guide).to_string())
 }
 
 #[derive(Serialize)]
 #[serde(rename = "episodedetails")]
 struct EpisodeNfo {
     title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    originaltitle: Option<String>,
     season: i32,
     episode: i32,
+    /// Position counting every regular episode, the way anime is numbered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    absolute_number: Option<i32>,
     plot: Option<String>,
     aired: Option<String>,
     runtime: Option<i32>,
     rating: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     thumb: Option<String>,
+    /// The episode's own ID, e.g. an AniDB episode ID
+    #[serde(rename = "uniqueid", skip_serializing_if = "Vec::is_empty")]
+    uniqueids: Vec<UniqueId>,
 }
 
 impl From<&EpisodeInfo> for EpisodeNfo {
     fn from(e: &EpisodeInfo) -> Self {
+        // Providers with IDs of single episodes; AniList's are made up
+        let id_type = match e.provider.as_str() {
+            "omdb" => Some("imdb"),
+            "anidb" | "tmdb" | "tvdb" | "imdb" | "bangumi" | "trakt" | "tvmaze" => {
+                Some(e.provider.as_str())
+            }
+            _ => None,
+        };
+        let uniqueids = id_type
+            .filter(|_| !e.id.is_empty())
+            .map(|id_type| UniqueId {
+                id_type: id_type.to_string(),
+                default: true,
+                value: e.id.clone(),
+            })
+            .into_iter()
+            .collect();
+
         Self {
             title: e.title.clone(),
+            originaltitle: e.original_title.clone(),
             season: e.season,
             episode: e.episode,
+            absolute_number: e.absolute_number,
             plot: e.overview.clone(),
             aired: e.air_date.clone(),
             runtime: e.runtime,
             rating: e.rating,
             thumb: e.still_url.clone(),
+            uniqueids,
+        }
+    }
+}
+
+/// Collection a movie is part of, which Kodi and Jellyfin group movies by
+#[derive(Serialize)]
+struct SetNfo {
+    #[serde(rename = "@tmdbcolid", skip_serializing_if = "Option::is_none")]
+    tmdbcolid: Option<String>,
+    name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "collection")]
+struct CollectionNfo {
+    title: String,
+    #[serde(rename = "uniqueid")]
+    uniqueids: Vec<UniqueId>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    thumb: Vec<ThumbNfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fanart: Option<FanartNfo>,
+}
+
+impl CollectionNfo {
+    fn new(c: &CollectionInfo, provider: &str) -> Self {
+        Self {
+            title: c.name.clone(),
+            uniqueids: vec![UniqueId {
+                id_type: provider.to_string(),
+                default: true,
+                value: c.id.clone(),
+            }],
+            thumb: c.poster_url.iter().map(|url| ThumbNfo::new("poster", url)).collect(),
+            fanart: c.backdrop_url.as_ref().map(|url| FanartNfo {
+                thumb: vec![ThumbNfo::new("fanart", url)],
+            }),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "season")]
+struct SeasonNfo {
+    title: Option<String>,
+    seasonnumber: i32,
+    plot: Option<String>,
+    premiered: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumb: Option<String>,
+}
+
+impl SeasonNfo {
+    fn new(number: i32, season: Option<&SeasonInfo>) -> Self {
+        Self {
+            title: season.and_then(|s| s.name.clone()),
+            seasonnumber: number,
+            plot: season.and_then(|s| s.overview.clone()),
+            premiered: season.and_then(|s| s.air_date.clone()),
+            thumb: season.and_then(|s| s.poster_url.clone()),
         }
     }
 }
@@ -306,11 +774,430 @@ impl From<&crate::scraper::types::PersonInfo> for ActorNfo {
 struct ThumbNfo {
     #[serde(rename = "@aspect")]
     aspect: String,
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(rename = "@season", skip_serializing_if = "Option::is_none")]
+    season: Option<i32>,
     #[serde(rename = "$value")]
     value: String,
 }
 
+impl ThumbNfo {
+    fn new(aspect: &str, value: &str) -> Self {
+        Self {
+            aspect: aspect.to_string(),
+            kind: None,
+            season: None,
+            value: value.to_string(),
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct FanartNfo {
     thumb: Vec<ThumbNfo>,
 }
+
+/// Local artwork, as Kodi's `<art>` block names it
+#[derive(Serialize)]
+struct ArtNfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    poster: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fanart: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clearlogo: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    season: Vec<SeasonArtNfo>,
+}
+
+#[derive(Serialize)]
+struct SeasonArtNfo {
+    #[serde(rename = "@num")]
+    num: i32,
+    poster: String,
+}
+
+/// Artwork files found next to an NFO, by file name
+#[derive(Default)]
+struct LocalArt {
+    poster: Option<String>,
+    fanart: Option<String>,
+    logo: Option<String>,
+    season_posters: Vec<(i32, String)>,
+}
+
+impl LocalArt {
+    /// Artwork planned for `metadata` in the NFO's folder that is there
+    fn find(nfo: &Path, metadata: &MediaMetadata, artwork: &ArtworkOptions) -> Self {
+        let mut local = Self::default();
+        let Some(folder) = nfo.parent() else {
+            return local;
+        };
+        for (path, _) in Downloader::plan_artwork(metadata, folder, artwork) {
+            if !path.exists() {
+                continue;
+            }
+            let (Some(kind), Some(name)) = (ArtworkKind::of(&path), path.file_name()) else {
+                continue;
+            };
+            let name = name.to_string_lossy().into_owned();
+            match kind {
+                ArtworkKind::Poster => local.poster = Some(name),
+                ArtworkKind::Fanart => local.fanart = Some(name),
+                ArtworkKind::Logo => local.logo = Some(name),
+                ArtworkKind::SeasonPoster(number) => local.season_posters.push((number, name)),
+            }
+        }
+        local
+    }
+
+    /// Thumbs and fanart with the local files ahead of `remote` ones, and
+    /// the `<art>` block
+    fn into_nfo(
+        self,
+        remote: Vec<ThumbNfo>,
+        remote_fanart: Option<FanartNfo>,
+    ) -> (Vec<ThumbNfo>, Option<FanartNfo>, Option<ArtNfo>) {
+        let mut thumbs = Vec::new();
+        if let Some(ref poster) = self.poster {
+            thumbs.push(ThumbNfo::new("poster", poster));
+        }
+        if let Some(ref logo) = self.logo {
+            thumbs.push(ThumbNfo::new("clearlogo", logo));
+        }
+        for (number, poster) in &self.season_posters {
+            thumbs.push(ThumbNfo {
+                kind: Some("season".to_string()),
+                season: Some(*number),
+                ..ThumbNfo::new("poster", poster)
+            });
+        }
+        thumbs.extend(remote);
+
+        let mut fanart = self
+            .fanart
+            .iter()
+            .map(|name| ThumbNfo::new("fanart", name))
+            .collect::<Vec<_>>();
+        fanart.extend(remote_fanart.into_iter().flat_map(|f| f.thumb));
+        let fanart = (!fanart.is_empty()).then_some(FanartNfo { thumb: fanart });
+
+        let has_art = self.poster.is_some()
+            || self.fanart.is_some()
+            || self.logo.is_some()
+            || !self.season_posters.is_empty();
+        let art = has_art.then(|| ArtNfo {
+            poster: self.poster,
+            fanart: self.fanart,
+            clearlogo: self.logo,
+            season: self
+                .season_posters
+                .into_iter()
+                .map(|(num, poster)| SeasonArtNfo { num, poster })
+                .collect(),
+        });
+
+        (thumbs, fanart, art)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_series_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("Show (2020)");
+        let metadata = MediaMetadata {
+            title: "Show".to_string(),
+            media_type: MediaType::Tv,
+            seasons: vec![SeasonInfo {
+                number: 1,
+                name: Some("Season One".to_string()),
+                overview: None,
+                air_date: None,
+                episode_count: Some(2),
+                poster_url: None,
+            }],
+            ..Default::default()
+        };
+        let episode = |season, number| EpisodeInfo {
+            id: format!("{season}x{number}"),
+            title: format!("Episode {number}"),
+            season,
+            episode: number,
+            absolute_number: None,
+            original_title: None,
+            air_date: None,
+            overview: None,
+            runtime: None,
+            rating: None,
+            still_url: None,
+            provider: "tmdb".to_string(),
+        };
+        let season_dir = root.join("Season 01");
+        let episodes = vec![
+            (season_dir.join("Show S01E01.mkv"), episode(1, 1)),
+            (season_dir.join("Show S01E02.mkv"), episode(1, 2)),
+            (root.join("Show S00E01.mkv"), episode(0, 1)),
+        ];
+
+        let written = Writer::write_series_tree(&root, &metadata, &episodes)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                root.join("tvshow.nfo"),
+                season_dir.join("season.nfo"),
+                season_dir.join("Show S01E01.nfo"),
+                season_dir.join("Show S01E02.nfo"),
+                root.join("Show S00E01.nfo"),
+            ]
+        );
+        let season = tokio::fs::read_to_string(season_dir.join("season.nfo"))
+            .await
+            .unwrap();
+        assert!(season.contains("<title>Season One</title><seasonnumber>1</seasonnumber>"));
+        let special = tokio::fs::read_to_string(root.join("Show S00E01.nfo"))
+            .await
+            .unwrap();
+        assert!(special.contains("<season>0</season><episode>1</episode>"));
+    }
+
+    #[tokio::test]
+    async fn test_nfo_local_artwork() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut metadata = MediaMetadata {
+            title: "Show".to_string(),
+            media_type: MediaType::Tv,
+            ..Default::default()
+        };
+        metadata.images.poster = Some("https://image.tmdb.org/t/p/w500/p.jpg".to_string());
+        metadata.images.logo = Some("https://image.tmdb.org/t/p/original/l.png".to_string());
+        metadata.seasons.push(crate::scraper::SeasonInfo {
+            number: 1,
+            name: None,
+            overview: None,
+            air_date: None,
+            episode_count: None,
+            poster_url: Some("https://image.tmdb.org/t/p/w500/s1.jpg".to_string()),
+        });
+        // The logo failed to download
+        for name in ["poster.jpg", "season01-poster.jpg"] {
+            tokio::fs::write(dir.path().join(name), b"image").await.unwrap();
+        }
+
+        let nfo = dir.path().join("tvshow.nfo");
+        let artwork = ArtworkOptions::default();
+        Writer::write_nfo_with(&nfo, &metadata, &artwork, &NfoProfile::default())
+            .await
+            .unwrap();
+        let xml = tokio::fs::read_to_string(&nfo).await.unwrap();
+
+        assert!(xml.contains(
+            "<thumb aspect=\"poster\">poster.jpg</thumb>\
+             <thumb aspect=\"poster\" type=\"season\" season=\"1\">season01-poster.jpg</thumb>\
+             <thumb aspect=\"poster\">https://image.tmdb.org/t/p/w500/p.jpg</thumb>"
+        ));
+        assert!(xml.contains(
+            "<art><poster>poster.jpg</poster>\
+             <season num=\"1\"><poster>season01-poster.jpg</poster></season></art>"
+        ));
+        assert!(!xml.contains("l.png"));
+        assert!(!xml.contains("clearlogo"));
+        assert!(!xml.contains("lockdata"));
+    }
+
+    #[tokio::test]
+    async fn test_nfo_atomic_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let nfo = dir.path().join("movie.nfo");
+        std::fs::write(&nfo, "<movie><title>Old</title></movie>").unwrap();
+        let metadata = MediaMetadata {
+            title: "New".to_string(),
+            media_type: MediaType::Movie,
+            ..Default::default()
+        };
+        let profile = NfoProfile {
+            backup: true,
+            ..Default::default()
+        };
+
+        Writer::write_nfo_with(&nfo, &metadata, &ArtworkOptions::default(), &profile)
+            .await
+            .unwrap();
+
+        let xml = std::fs::read_to_string(&nfo).unwrap();
+        assert!(xml.contains("<title>New</title>"));
+        let bak = std::fs::read_to_string(dir.path().join("movie.nfo.bak")).unwrap();
+        assert_eq!(bak, "<movie><title>Old</title></movie>");
+        assert!(!dir.path().join("movie.nfo.part").exists());
+
+        // Without a backup, nothing but the NFO is left behind
+        std::fs::remove_file(dir.path().join("movie.nfo.bak")).unwrap();
+        Writer::write_movie_nfo(&nfo, &metadata).await.unwrap();
+        let files = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(files, 1);
+    }
+
+    #[tokio::test]
+    async fn test_nfo_field_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let actor = |name: &str| crate::scraper::types::PersonInfo {
+            id: name.to_string(),
+            name: name.to_string(),
+            role: None,
+            image_url: None,
+            order: None,
+        };
+        let metadata = MediaMetadata {
+            title: "Show".to_string(),
+            media_type: MediaType::Tv,
+            rating: Some(8.0),
+            genres: vec!["Drama".to_string(), "Crime".to_string(), "Thriller".to_string()],
+            tags: vec!["heist".to_string()],
+            cast: vec![actor("First"), actor("Second"), actor("Third")],
+            ..Default::default()
+        };
+        let profile = NfoProfile {
+            dialect: NfoDialect::Jellyfin,
+            exclude: vec![NfoField::Tags, NfoField::Ratings],
+            max_cast: Some(2),
+            max_items: Some(1),
+            ..Default::default()
+        };
+
+        let nfo = dir.path().join("tvshow.nfo");
+        Writer::write_nfo_with(&nfo, &metadata, &ArtworkOptions::default(), &profile)
+            .await
+            .unwrap();
+        let xml = tokio::fs::read_to_string(&nfo).await.unwrap();
+
+        assert!(xml.contains("<genre>Drama</genre>"), "{xml}");
+        assert!(!xml.contains("Crime"));
+        assert!(!xml.contains("<tag>"));
+        assert!(!xml.contains("<rating>"));
+        assert!(xml.contains("Second"));
+        assert!(!xml.contains("Third"));
+    }
+
+    #[tokio::test]
+    async fn test_anime_episode_nfo() {
+        let dir = tempfile::tempdir().unwrap();
+        let episode = EpisodeInfo {
+            id: "185013".to_string(),
+            title: "The Journey's End".to_string(),
+            season: 1,
+            episode: 1,
+            absolute_number: Some(1),
+            original_title: Some("冒険の終わり".to_string()),
+            air_date: None,
+            overview: None,
+            runtime: None,
+            rating: None,
+            still_url: None,
+            provider: "anidb".to_string(),
+        };
+
+        let nfo = dir.path().join("Frieren - 01.nfo");
+        Writer::write_episode_nfo(&nfo, &episode).await.unwrap();
+        let xml = tokio::fs::read_to_string(&nfo).await.unwrap();
+
+        assert!(
+            xml.contains("<title>The Journey's End</title><originaltitle>冒険の終わり</originaltitle>"),
+            "{xml}"
+        );
+        assert!(xml.contains("<episode>1</episode><absolute_number>1</absolute_number>"));
+        assert!(xml.contains(r#"<uniqueid type="anidb" default="true">185013</uniqueid>"#));
+
+        // Made-up IDs aren't written
+        let episode = EpisodeInfo {
+            provider: "anilist".to_string(),
+            ..episode
+        };
+        Writer::write_episode_nfo(&nfo, &episode).await.unwrap();
+        let xml = tokio::fs::read_to_string(&nfo).await.unwrap();
+        assert!(!xml.contains("uniqueid"));
+    }
+
+    #[tokio::test]
+    async fn test_nfo_dialects() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut metadata = MediaMetadata {
+            title: "Heat".to_string(),
+            media_type: MediaType::Movie,
+            provider: "tmdb".to_string(),
+            rating: Some(7.912),
+            vote_count: Some(7000),
+            ..Default::default()
+        };
+        metadata.external_ids.imdb = Some("tt0113277".to_string());
+        metadata.external_ids.tmdb = Some("949".to_string());
+        metadata.ratings.push(crate::scraper::types::ExternalRating {
+            source: "rotten_tomatoes".to_string(),
+            score: 8.7,
+            votes: None,
+        });
+        let nfo = dir.path().join("movie.nfo");
+        let write = |dialect| {
+            let profile = NfoProfile {
+                dialect,
+                ..Default::default()
+            };
+            let (nfo, metadata) = (nfo.clone(), metadata.clone());
+            async move {
+                Writer::write_nfo_with(&nfo, &metadata, &ArtworkOptions::default(), &profile)
+                    .await
+                    .unwrap();
+                tokio::fs::read_to_string(&nfo).await.unwrap()
+            }
+        };
+
+        let kodi = write(NfoDialect::Kodi).await;
+        assert!(kodi.contains(
+            r#"<ratings><rating name="themoviedb" max="10" default="true"><value>7.9</value><votes>7000</votes></rating><rating name="tomatometerallcritics" max="10" default="false"><value>8.7</value></rating></ratings>"#
+        ));
+        assert!(!kodi.contains("<rating>"));
+        assert!(!kodi.contains("<imdbid>"));
+
+        let jellyfin = write(NfoDialect::Jellyfin).await;
+        assert!(jellyfin.contains("<votes>7000</votes><criticrating>87</criticrating>"));
+        assert!(jellyfin.contains("<imdbid>tt0113277</imdbid><tmdbid>949</tmdbid><uniqueid"));
+        assert!(!jellyfin.contains("<ratings>"));
+
+        let plex = write(NfoDialect::Plex).await;
+        assert!(plex.contains("<rating>7.9</rating>"));
+        assert!(plex.contains("<id>tt0113277</id>"));
+        assert!(!plex.contains("<criticrating>"));
+    }
+
+    #[tokio::test]
+    async fn test_nfo_jellyfin_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut metadata = MediaMetadata {
+            title: "Show".to_string(),
+            media_type: MediaType::Tv,
+            ..Default::default()
+        };
+        metadata.external_ids.tvdb = Some("81189".to_string());
+        metadata.external_ids.tmdb = Some("1396".to_string());
+        let profile = NfoProfile {
+            display_order: Some("absolute".to_string()),
+            ..NfoProfile::jellyfin()
+        };
+
+        let nfo = dir.path().join("tvshow.nfo");
+        Writer::write_nfo_with(&nfo, &metadata, &ArtworkOptions::default(), &profile)
+            .await
+            .unwrap();
+        let xml = tokio::fs::read_to_string(&nfo).await.unwrap();
+
+        assert!(xml.contains("<lockdata>true</lockdata><dateadded>"));
+        assert!(xml.contains(r#"<episodeguide>{"tmdb":"1396","tvdb":"81189"}</episodeguide>"#));
+        assert!(xml.contains("<displayorder>absolute</displayorder>"));
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// Optional NFO fields, for what the media server reading them expects
+///
+/// Nothing extra is written by default; [`NfoProfile::jellyfin`] turns on
+/// the fields Jellyfin uses to decide whether to refresh imported metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NfoProfile {
+    /// Media center whose rating and ID elements are written
+    pub dialect: NfoDialect,
+    /// `<lockdata>true</lockdata>`, so Jellyfin keeps the NFO's metadata
+    /// instead of replacing it on refresh
+    pub lock_data: bool,
+    /// `<dateadded>` with the time the NFO is written
+    pub date_added: bool,
+    /// `<episodeguide>` with the show's TVDB, TMDB and IMDb IDs, in
+    /// `tvshow.nfo`
+    pub episode_guide: bool,
+    /// `<displayorder>` of episodes in `tvshow.nfo`, e.g. "aired",
+    /// "absolute" or "dvd"
+    pub display_order: Option<String>,
+    /// Keep an NFO being replaced as `<name>.nfo.bak`
+    pub backup: bool,
+    /// Elements left out, for minimal NFOs
+    pub exclude: Vec<NfoField>,
+    /// Most actors listed, in billing order; some media centers choke on
+    /// hundreds of them
+    pub max_cast: Option<usize>,
+    /// Most genres, tags and studios listed, each
+    pub max_items: Option<usize>,
+}
+
+impl NfoProfile {
+    /// Fields Jellyfin reads, with imported metadata locked
+    #[must_use]
+    pub const fn jellyfin() -> Self {
+        Self {
+            dialect: NfoDialect::Jellyfin,
+            lock_data: true,
+            date_added: true,
+            episode_guide: true,
+            display_order: None,
+            backup: false,
+            exclude: Vec::new(),
+            max_cast: None,
+            max_items: None,
+        }
+    }
+
+    /// Whether `field` is written
+    #[must_use]
+    pub fn includes(&self, field: NfoField) -> bool {
+        !self.exclude.contains(&field)
+    }
+
+    /// Empty `list` if `field` is excluded, or cut it to `max` entries
+    pub(super) fn limit<T>(&self, field: NfoField, list: &mut Vec<T>, max: Option<usize>) {
+        if !self.includes(field) {
+            list.clear();
+        } else if let Some(max) = max {
+            list.truncate(max);
+        }
+    }
+}
+
+/// NFO elements [`NfoProfile::exclude`] can leave out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NfoField {
+    /// `<actor>`s
+    Cast,
+    /// `<director>`s and `<credits>`
+    Crew,
+    Genres,
+    Tags,
+    Studios,
+    /// Scores and vote counts
+    Ratings,
+    /// `<thumb>`s, `<fanart>` and `<art>`
+    Artwork,
+}
+
+/// Media center an NFO is written for
+///
+/// They agree on most elements but read ratings and IDs differently.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NfoDialect {
+    /// Kodi 17+: all scores in a `<ratings>` block, IDs as `<uniqueid>`s
+    #[default]
+    Kodi,
+    /// Jellyfin and Emby: `<rating>` out of 10, `<criticrating>` out of 100
+    /// and `<imdbid>`/`<tmdbid>`/`<tvdbid>` elements
+    #[serde(alias = "emby")]
+    Jellyfin,
+    /// Plex's NFO importer agents: `<rating>` out of 10 and the IMDb (movies)
+    /// or TVDB (shows) ID as `<id>`
+    Plex,
+}
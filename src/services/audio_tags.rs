@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+
+/// Tags embedded in an audio file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub track_number: Option<i64>,
+    pub disc_number: Option<i64>,
+    pub year: Option<i64>,
+}
+
+impl AudioTags {
+    /// Read the tags of a FLAC, MP3, M4A or Ogg/Opus file
+    ///
+    /// ID3v2 tags in front of an MP3 stream and tags inside the container are
+    /// merged; the container's win.
+    pub fn read(path: &Path) -> Result<Self, AudioTagsError> {
+        let file = File::open(path)?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
+        }
+
+        let mut probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(|e| AudioTagsError::Unsupported(e.to_string()))?;
+
+        let mut tags = Self::default();
+        if let Some(metadata) = probed.metadata.get()
+            && let Some(revision) = metadata.current()
+        {
+            tags.apply(revision.tags());
+        }
+        if let Some(revision) = probed.format.metadata().current() {
+            tags.apply(revision.tags());
+        }
+
+        Ok(tags)
+    }
+
+    fn apply(&mut self, tags: &[Tag]) {
+        for tag in tags {
+            let value = tag.value.to_string();
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+
+            match tag.std_key {
+                Some(StandardTagKey::TrackTitle) => self.title = Some(value.to_string()),
+                Some(StandardTagKey::Artist) => self.artist = Some(value.to_string()),
+                Some(StandardTagKey::AlbumArtist) => self.album_artist = Some(value.to_string()),
+                Some(StandardTagKey::Album) => self.album = Some(value.to_string()),
+                Some(StandardTagKey::TrackNumber) => self.track_number = leading_number(value),
+                Some(StandardTagKey::DiscNumber) => self.disc_number = leading_number(value),
+                Some(StandardTagKey::Date | StandardTagKey::ReleaseDate) => {
+                    self.year = leading_number(value).filter(|year| (1000..10000).contains(year));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Number at the start of a tag value, e.g. 3 in `3/12` or 1999 in `1999-05-01`
+fn leading_number(value: &str) -> Option<i64> {
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// Audio tag errors
+#[derive(Debug, thiserror::Error)]
+pub enum AudioTagsError {
+    #[error("Unsupported audio file: {0}")]
+    Unsupported(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// FLAC file with a Vorbis comment block and one frame of silence
+    pub(crate) fn flac_with_comments(comments: &[&str]) -> Vec<u8> {
+        let mut flac = b"fLaC".to_vec();
+
+        // STREAMINFO: 4096-sample blocks, 44.1 kHz, stereo, 16 bits
+        flac.extend([0x00, 0x00, 0x00, 34]);
+        let mut info = [0u8; 34];
+        info[0..2].copy_from_slice(&4096u16.to_be_bytes());
+        info[2..4].copy_from_slice(&4096u16.to_be_bytes());
+        info[10..14].copy_from_slice(&((44_100u32 << 12) | (1 << 9) | (15 << 4)).to_be_bytes());
+        flac.extend(info);
+
+        let mut block = Vec::new();
+        block.extend(4u32.to_le_bytes());
+        block.extend(b"test");
+        block.extend((comments.len() as u32).to_le_bytes());
+        for comment in comments {
+            block.extend((comment.len() as u32).to_le_bytes());
+            block.extend(comment.as_bytes());
+        }
+        flac.push(0x80 | 4);
+        flac.extend(&(block.len() as u32).to_be_bytes()[1..]);
+        flac.extend(block);
+
+        // One silent frame: 192 samples, 44.1 kHz, left/right, 16 bits, constant subframes
+        let mut frame = vec![0xff, 0xf8, 0x19, 0x18, 0x00];
+        frame.push(crc8(&frame));
+        frame.extend([0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        frame.extend(crc16(&frame).to_be_bytes());
+        flac.extend(frame);
+
+        flac
+    }
+
+    fn crc8(data: &[u8]) -> u8 {
+        data.iter().fold(0u8, |mut crc, &byte| {
+            crc ^= byte;
+            for _ in 0..8 {
+                crc = if crc & 0x80 == 0 { crc << 1 } else { (crc << 1) ^ 0x07 };
+            }
+            crc
+        })
+    }
+
+    fn crc16(data: &[u8]) -> u16 {
+        data.iter().fold(0u16, |mut crc, &byte| {
+            crc ^= u16::from(byte) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 == 0 { crc << 1 } else { (crc << 1) ^ 0x8005 };
+            }
+            crc
+        })
+    }
+
+    #[test]
+    fn test_read_flac_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("01 - Airbag.flac");
+        let flac = flac_with_comments(&[
+            "TITLE=Airbag",
+            "ARTIST=Radiohead",
+            "ALBUM=OK Computer",
+            "TRACKNUMBER=1/12",
+            "DATE=1997-05-21",
+        ]);
+        std::fs::write(&path, flac).unwrap();
+
+        let tags = AudioTags::read(&path).unwrap();
+
+        assert_eq!(tags.title.as_deref(), Some("Airbag"));
+        assert_eq!(tags.artist.as_deref(), Some("Radiohead"));
+        assert_eq!(tags.album.as_deref(), Some("OK Computer"));
+        assert_eq!((tags.track_number, tags.year), (Some(1), Some(1997)));
+        assert!(AudioTags::read(&dir.path().join("missing.flac")).is_err());
+    }
+}
@@ -1,141 +1,684 @@
-use crate::entities::{CreateMediaItem, LibraryFolder, MediaItem, MediaType};
+use super::{AudioTags, MediaProbe, MediaProbeError};
+use crate::entities::{
+    CreateMediaItem, CreateMusicMetadata, LibraryFolder, MediaItem, MediaStreamInfo, MediaType,
+    MusicMetadata,
+};
+use crate::scraper::{
+    DEFAULT_MAX_DEPTH, DiscKind, IgnoreRules, LoopGuard, ParsedMedia, Parser, is_iso, probe_iso,
+};
+use chrono::{Duration, Utc};
+use futures::stream::{self, StreamExt};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+use tokio::sync::Semaphore;
 use tracing::{debug, error, info, warn};
 use walkdir::WalkDir;
 
+/// Directory walkers running at once during a scan
+const MAX_WALKERS: usize = 4;
+
+/// Media items inserted per database transaction
+const INSERT_BATCH_SIZE: usize = 500;
+
+/// ffprobe processes running at once during a scan
+const MAX_PROBES: usize = 4;
+
+/// Files fingerprinted at once during a scan
+const MAX_FINGERPRINTS: usize = 4;
+
+/// Bytes hashed from each end of a file for its fingerprint
+const FINGERPRINT_CHUNK: u64 = 64 * 1024;
+
+/// Attempts at IO failing with transient errors, e.g. on a flaky network share
+const IO_ATTEMPTS: u32 = 3;
+
+/// Wait before retrying transient IO errors, doubled after every retry
+const IO_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Files between [`ScanThrottle::batch_pause_ms`] pauses
+const THROTTLE_BATCH_SIZE: usize = 100;
+
 /// File scanner service for detecting media files
 pub struct FileScanner {
     db: sqlx::SqlitePool,
+    purge_missing_after: Option<Duration>,
+    probe: Option<MediaProbe>,
+    max_depth: usize,
+    throttle: ScanThrottle,
+}
+
+/// Low-priority scan settings
+///
+/// A throttled scan walks with a single walker and probes one file at a time,
+/// so a spinning disk isn't made to seek between several folders at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanThrottle {
+    /// Most files and folders read per second (unlimited when unset)
+    #[serde(default)]
+    pub max_files_per_sec: Option<u32>,
+
+    /// Pause after every 100 files, in milliseconds
+    #[serde(default)]
+    pub batch_pause_ms: u64,
+
+    /// Run ffprobe with idle IO priority (`ionice -c3`, Linux only)
+    #[serde(default)]
+    pub idle_io_priority: bool,
+}
+
+impl ScanThrottle {
+    /// Whether scans are slowed down at all
+    #[must_use]
+    pub const fn is_active(&self) -> bool {
+        self.max_files_per_sec.is_some() || self.batch_pause_ms > 0 || self.idle_io_priority
+    }
+}
+
+/// Spaces out file access according to a [`ScanThrottle`], shared by all
+/// walkers and probes of a scan
+#[derive(Debug)]
+struct Pacer {
+    interval: std::time::Duration,
+    pause: std::time::Duration,
+    /// When the next file may be read, and how many were read so far
+    state: Mutex<(Instant, usize)>,
+}
+
+impl Pacer {
+    fn new(throttle: &ScanThrottle) -> Self {
+        Self {
+            interval: throttle
+                .max_files_per_sec
+                .filter(|&rate| rate > 0)
+                .map_or(std::time::Duration::ZERO, |rate| {
+                    std::time::Duration::from_secs(1) / rate
+                }),
+            pause: std::time::Duration::from_millis(throttle.batch_pause_ms),
+            state: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    /// How long to wait before reading the next file
+    fn delay(&self) -> std::time::Duration {
+        if self.interval.is_zero() && self.pause.is_zero() {
+            return std::time::Duration::ZERO;
+        }
+
+        let mut state = self.state.lock();
+        let now = Instant::now();
+        let start = state.0.max(now);
+        state.1 += 1;
+        state.0 = start + self.interval;
+        if state.1.is_multiple_of(THROTTLE_BATCH_SIZE) {
+            state.0 += self.pause;
+        }
+        start - now
+    }
+
+    /// Block the current thread until the next file may be read
+    fn wait_blocking(&self) {
+        let delay = self.delay();
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+    }
+
+    async fn wait(&self) {
+        let delay = self.delay();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
 }
 
 /// Scan result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ScanResult {
     pub total_files: usize,
     pub new_items: usize,
     pub existing_items: usize,
+    /// Items whose files disappeared since the last scan
+    pub missing_items: usize,
+    /// Missing items whose files are back
+    pub restored_items: usize,
+    /// Items deleted after being missing for too long
+    pub purged_items: usize,
+    /// Items relinked to their renamed or moved files
+    pub moved_items: usize,
+    /// Folders skipped because a symlink led back to an already scanned folder
+    pub skipped_cycles: usize,
     pub errors: usize,
+    /// The scan was cancelled; missing files were not tracked
+    pub cancelled: bool,
+}
+
+/// Live progress of a scan
+///
+/// Clones share the same counters, so the scan can be watched and cancelled
+/// through a clone kept by whoever started it.
+#[derive(Debug, Clone, Default)]
+pub struct ScanProgress {
+    state: Arc<ProgressState>,
 }
 
 #[derive(Debug, Default)]
-struct ScanCounters {
-    new_items: usize,
-    existing_items: usize,
+struct ProgressState {
+    walked: AtomicUsize,
+    added: AtomicUsize,
+    current_path: Mutex<Option<String>>,
+    cancelled: AtomicBool,
+}
+
+/// Point-in-time copy of [`ScanProgress`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanSnapshot {
+    /// Media files found so far
+    pub walked: usize,
+    /// Media items added to the library
+    pub added: usize,
+    /// Folder or file being worked on
+    pub current_path: Option<String>,
+}
+
+impl ScanProgress {
+    /// Ask the scan to stop; nothing more is written to the database
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> ScanSnapshot {
+        ScanSnapshot {
+            walked: self.state.walked.load(Ordering::Relaxed),
+            added: self.state.added.load(Ordering::Relaxed),
+            current_path: self.state.current_path.lock().clone(),
+        }
+    }
+
+    fn record_walked(&self) {
+        self.state.walked.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_added(&self, count: usize) {
+        self.state.added.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn set_current(&self, path: &Path) {
+        *self.state.current_path.lock() = Some(path.to_string_lossy().to_string());
+    }
+}
+
+/// A media file (or disc folder) found while walking a library
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScanEntry {
+    title: String,
+    file_path: String,
+    file_size: i64,
+}
+
+/// Everything one walker found
+#[derive(Debug, Default)]
+struct WalkOutput {
+    entries: Vec<ScanEntry>,
     errors: usize,
+    skipped_cycles: usize,
+    /// Paths that failed with transient errors, with the depth left below them
+    unreachable: Vec<(PathBuf, usize)>,
 }
 
 impl FileScanner {
     /// Create a new file scanner
     #[must_use]
     pub const fn new(db: sqlx::SqlitePool) -> Self {
-        Self { db }
+        Self {
+            db,
+            purge_missing_after: None,
+            probe: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+            throttle: ScanThrottle {
+                max_files_per_sec: None,
+                batch_pause_ms: 0,
+                idle_io_priority: false,
+            },
+        }
+    }
+
+    /// Slow scans down to leave disk bandwidth for playback
+    #[must_use]
+    pub fn with_throttle(mut self, throttle: ScanThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Limit how many folder levels below the library root are scanned
+    #[must_use]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth.max(1);
+        self
+    }
+
+    /// Read duration, codecs and resolution of new video files with ffprobe
+    #[must_use]
+    pub fn with_probe(mut self, probe: Option<MediaProbe>) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Delete items whose files have been missing for this many days
+    #[must_use]
+    pub fn with_purge_after_days(mut self, days: Option<u32>) -> Self {
+        self.purge_missing_after = days.map(|d| Duration::days(i64::from(d)));
+        self
     }
 
     /// Scan a library folder for media files
     pub async fn scan_library_folder(
         &self,
         folder: &LibraryFolder,
+    ) -> Result<ScanResult, FileScannerError> {
+        self.scan_library_folder_with_progress(folder, &ScanProgress::default())
+            .await
+    }
+
+    /// Scan a library folder, reporting progress and stopping when cancelled
+    ///
+    /// A cancelled scan keeps the items it already added but leaves missing
+    /// files alone, since it did not see the whole folder. So does a folder that
+    /// can't be reached or suddenly comes up empty, as a dropped network share
+    /// does; it is flagged offline and the scan fails with
+    /// [`FileScannerError::Offline`].
+    pub async fn scan_library_folder_with_progress(
+        &self,
+        folder: &LibraryFolder,
+        progress: &ScanProgress,
     ) -> Result<ScanResult, FileScannerError> {
         info!("Scanning library folder: {} ({})", folder.name, folder.path);
 
         let path = Path::new(&folder.path);
-        if !path.exists() {
-            return Err(FileScannerError::PathNotFound(folder.path.clone()));
-        }
+        let metadata = match retry_io(|| std::fs::metadata(path)).await {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(FileScannerError::PathNotFound(folder.path.clone()));
+            }
+            Err(e) => return self.go_offline(folder, &e.to_string()).await,
+        };
 
-        if !path.is_dir() {
+        if !metadata.is_dir() {
             return Err(FileScannerError::NotADirectory(folder.path.clone()));
         }
 
-        let mut total_files = 0;
-        let mut counters = ScanCounters::default();
+        let rules = IgnoreRules::new(path)
+            .with_excludes(&folder.exclude_patterns)
+            .map_err(|e| FileScannerError::InvalidPattern(e.to_string()))?;
+        let extensions = folder
+            .extensions
+            .apply(&get_supported_extensions(folder.media_type));
+        let pacer = Arc::new(Pacer::new(&self.throttle));
+        let walkers = if self.throttle.is_active() { 1 } else { MAX_WALKERS };
+        let walked = walk_library(
+            path,
+            extensions,
+            rules,
+            self.max_depth,
+            progress,
+            &pacer,
+            walkers,
+        )
+        .await?;
+        let total_files = walked.entries.len();
+        let cancelled = |new_items: usize| {
+            info!("Scan of {} cancelled", folder.name);
+            ScanResult {
+                total_files,
+                new_items,
+                skipped_cycles: walked.skipped_cycles,
+                errors: walked.errors,
+                cancelled: true,
+                ..Default::default()
+            }
+        };
+        if progress.is_cancelled() {
+            return Ok(cancelled(0));
+        }
+        if !walked.unreachable.is_empty() {
+            let reason = format!("{} paths unreachable", walked.unreachable.len());
+            return self.go_offline(folder, &reason).await;
+        }
+
+        let found: HashSet<String> = walked.entries.iter().map(|e| e.file_path.clone()).collect();
+        let mut new_items = 0;
+        let mut failed_items = 0;
 
-        // Get supported extensions for this media type
-        let extensions = get_supported_extensions(folder.media_type);
-        let mut processed_disc_roots: HashSet<PathBuf> = HashSet::new();
+        let db_error = |e: sqlx::Error| FileScannerError::DatabaseError(e.to_string());
+        let mut known = MediaItem::list_by_folder(&self.db, folder.id)
+            .await
+            .map_err(db_error)?;
+        // An unmounted share leaves an empty mount point behind
+        let present = known.iter().any(|item| item.missing_since.is_none());
+        if found.is_empty() && present && is_empty_dir(path) {
+            return self.go_offline(folder, "folder is empty").await;
+        }
 
-        // Walk through directory
-        for entry in WalkDir::new(path)
-            .follow_links(true)
+        let known_paths: HashSet<&str> = known.iter().map(|i| i.file_path.as_str()).collect();
+        let entries: Vec<_> = walked
+            .entries
             .into_iter()
-            .filter_map(std::result::Result::ok)
-        {
-            let entry_path = entry.path();
+            .filter(|entry| !known_paths.contains(entry.file_path.as_str()))
+            .collect();
 
-            // Skip directories unless they represent disc structures
-            if entry_path.is_dir() {
-                continue;
-            }
-
-            // Handle Blu-ray/DVD disc structures by looking for indicator files
-            if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str())
-                && let Some(_disc_type) = detect_disc_indicator(file_name)
-            {
-                if let Some(root) = entry_path.parent().and_then(|p| p.parent())
-                    && processed_disc_roots.insert(root.to_path_buf())
-                {
-                    total_files += 1;
-                    let file_path = root.to_string_lossy().to_string();
-                    let file_size = calculate_directory_size(root);
-                    let title = extract_title(root);
+        // Fingerprint new files, and known ones added before fingerprints were recorded
+        let (backfill_ids, mut paths): (Vec<i64>, Vec<PathBuf>) = known
+            .iter()
+            .filter(|item| item.fingerprint.is_none() && found.contains(&item.file_path))
+            .map(|item| (item.id, PathBuf::from(&item.file_path)))
+            .unzip();
+        paths.splice(0..0, entries.iter().map(|e| PathBuf::from(&e.file_path)));
+        let mut fingerprints = self.fingerprint_files(paths, progress, &pacer).await;
+        if progress.is_cancelled() {
+            return Ok(cancelled(0));
+        }
 
-                    self.handle_media_entry(folder, title, file_path, file_size, &mut counters)
-                        .await;
-                }
+        let backfilled: Vec<(i64, String)> = backfill_ids
+            .into_iter()
+            .zip(fingerprints.split_off(entries.len()))
+            .filter_map(|(id, fingerprint)| Some((id, fingerprint?)))
+            .collect();
+        MediaItem::set_fingerprints(&self.db, &backfilled)
+            .await
+            .map_err(db_error)?;
 
-                // We captured the disc root, skip files inside it
+        let mut moved = HashSet::new();
+        for (index, id) in match_moves(&known, &found, &fingerprints) {
+            let entry = &entries[index];
+            let group = (folder.media_type == MediaType::Movie)
+                .then(|| version_group(Path::new(&entry.file_path)));
+            if let Err(e) =
+                MediaItem::relink(&self.db, id, &entry.file_path, group.as_deref()).await
+            {
+                error!("Failed to relink moved file {}: {}", entry.file_path, e);
                 continue;
             }
 
-            if is_inside_disc_structure(entry_path) {
-                continue;
+            if let Some(item) = known.iter_mut().find(|item| item.id == id) {
+                info!("Media file moved: {} -> {}", item.file_path, entry.file_path);
+                item.file_path.clone_from(&entry.file_path);
+                item.missing_since = None;
             }
+            moved.insert(index);
+        }
+        let (entries, fingerprints): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .zip(fingerprints)
+            .enumerate()
+            .filter(|(index, _)| !moved.contains(index))
+            .map(|(_, pair)| pair)
+            .unzip();
 
-            // Check if file has supported extension
-            if let Some(ext) = entry_path.extension() {
-                let ext_str = ext.to_string_lossy().to_lowercase();
-                if !extensions.contains(&ext_str.as_str()) {
-                    continue;
-                }
-            } else {
-                continue;
-            }
+        let stream_infos = self
+            .probe_entries(folder.media_type, &entries, progress, &pacer)
+            .await;
+        let mut tags = if folder.media_type == MediaType::Music {
+            self.read_tags(&entries, progress, &pacer).await
+        } else {
+            Vec::new()
+        };
+        if progress.is_cancelled() {
+            return Ok(cancelled(0));
+        }
+        let tracks: Vec<(String, AudioTags)> = entries
+            .iter()
+            .map(|entry| entry.file_path.clone())
+            .zip(tags.iter().cloned())
+            .collect();
+        tags.resize(entries.len(), AudioTags::default());
+        let items: Vec<_> = entries
+            .into_iter()
+            .zip(stream_infos)
+            .zip(fingerprints)
+            .zip(tags)
+            .map(|(((entry, stream_info), fingerprint), tags)| CreateMediaItem {
+                library_folder_id: folder.id,
+                media_type: folder.media_type,
+                version_group: (folder.media_type == MediaType::Movie)
+                    .then(|| version_group(Path::new(&entry.file_path))),
+                title: tags.title.unwrap_or(entry.title),
+                file_path: entry.file_path,
+                file_size: entry.file_size,
+                fingerprint,
+                stream_info,
+            })
+            .collect();
 
-            total_files += 1;
+        for batch in items.chunks(INSERT_BATCH_SIZE) {
+            if progress.is_cancelled() {
+                break;
+            }
 
-            // Get file metadata
-            let file_path = entry_path.to_string_lossy().to_string();
-            let file_size = match entry.metadata() {
-                Ok(metadata) => metadata.len() as i64,
+            match MediaItem::create_many(&self.db, batch).await {
+                Ok(added) => {
+                    debug!("Added {} of {} media items", added, batch.len());
+                    new_items += added as usize;
+                    progress.record_added(added as usize);
+                }
                 Err(e) => {
-                    error!("Failed to get metadata for {}: {}", file_path, e);
-                    counters.errors += 1;
-                    continue;
+                    error!("Failed to add {} media items: {}", batch.len(), e);
+                    failed_items += batch.len();
                 }
-            };
-
-            // Extract title from filename
-            let title = extract_title(entry_path);
+            }
+        }
 
-            self.handle_media_entry(folder, title, file_path, file_size, &mut counters)
-                .await;
+        if !tracks.is_empty() {
+            self.save_tracks(folder, tracks).await?;
+        }
+        if progress.is_cancelled() {
+            return Ok(cancelled(new_items));
         }
 
+        let existing_items = total_files - new_items - failed_items;
+        let errors = walked.errors + failed_items;
+        let (missing_items, restored_items, purged_items) =
+            self.track_missing(folder, &known, &found).await?;
+        LibraryFolder::set_offline(&self.db, folder.id, false)
+            .await
+            .map_err(db_error)?;
+
         info!(
-            "Scan complete: {} total files, {} new, {} existing, {} errors",
-            total_files, counters.new_items, counters.existing_items, counters.errors
+            "Scan complete: {} total files, {} new, {} existing, {} missing, {} errors",
+            total_files, new_items, existing_items, missing_items, errors
         );
+        if walked.skipped_cycles > 0 {
+            warn!("Skipped {} symlink cycles", walked.skipped_cycles);
+        }
 
         Ok(ScanResult {
             total_files,
-            new_items: counters.new_items,
-            existing_items: counters.existing_items,
-            errors: counters.errors,
+            new_items,
+            existing_items,
+            missing_items,
+            restored_items,
+            purged_items,
+            moved_items: moved.len(),
+            skipped_cycles: walked.skipped_cycles,
+            errors,
+            cancelled: false,
         })
     }
 
+    /// Flag `folder` offline and fail its scan, leaving its items alone
+    async fn go_offline(
+        &self,
+        folder: &LibraryFolder,
+        reason: &str,
+    ) -> Result<ScanResult, FileScannerError> {
+        warn!("Library folder {} is offline: {}", folder.name, reason);
+        LibraryFolder::set_offline(&self.db, folder.id, true)
+            .await
+            .map_err(|e| FileScannerError::DatabaseError(e.to_string()))?;
+
+        Err(FileScannerError::Offline(folder.path.clone()))
+    }
+
+    /// Flag items of `folder` whose files were not `found`, unflag the ones that
+    /// came back, and purge items missing for too long
+    ///
+    /// Returns the number of newly missing, restored and purged items.
+    async fn track_missing(
+        &self,
+        folder: &LibraryFolder,
+        known: &[MediaItem],
+        found: &HashSet<String>,
+    ) -> Result<(usize, usize, usize), FileScannerError> {
+        let db_error = |e: sqlx::Error| FileScannerError::DatabaseError(e.to_string());
+
+        let mut missing = Vec::new();
+        let mut restored = Vec::new();
+        for item in known {
+            match (item.missing_since.is_some(), found.contains(&item.file_path)) {
+                (false, false) => {
+                    info!("Media file missing: {}", item.file_path);
+                    missing.push(item.id);
+                }
+                (true, true) => restored.push(item.id),
+                _ => {}
+            }
+        }
+
+        MediaItem::set_missing(&self.db, &missing, true)
+            .await
+            .map_err(db_error)?;
+        MediaItem::set_missing(&self.db, &restored, false)
+            .await
+            .map_err(db_error)?;
+
+        let purged = match self.purge_missing_after {
+            Some(after) => MediaItem::purge_missing(&self.db, folder.id, Utc::now() - after)
+                .await
+                .map_err(db_error)?,
+            None => 0,
+        };
+        if purged > 0 {
+            info!("Purged {} long-missing media items", purged);
+        }
+
+        Ok((missing.len(), restored.len(), purged as usize))
+    }
+
+    /// Fingerprint files, in the order of `paths`
+    async fn fingerprint_files(
+        &self,
+        paths: Vec<PathBuf>,
+        progress: &ScanProgress,
+        pacer: &Arc<Pacer>,
+    ) -> Vec<Option<String>> {
+        let workers = if self.throttle.is_active() { 1 } else { MAX_FINGERPRINTS };
+        stream::iter(paths)
+            .map(|path| fingerprint_file(path, progress.clone(), pacer.clone()))
+            .buffered(workers)
+            .collect()
+            .await
+    }
+
+    /// Read the tags of new audio files, in the order of `entries`
+    ///
+    /// Files whose tags can't be read get empty tags.
+    async fn read_tags(
+        &self,
+        entries: &[ScanEntry],
+        progress: &ScanProgress,
+        pacer: &Arc<Pacer>,
+    ) -> Vec<AudioTags> {
+        let workers = if self.throttle.is_active() { 1 } else { MAX_PROBES };
+        let paths: Vec<PathBuf> = entries.iter().map(|e| PathBuf::from(&e.file_path)).collect();
+        stream::iter(paths)
+            .map(|path| read_tags_file(path, progress.clone(), pacer.clone()))
+            .buffered(workers)
+            .collect()
+            .await
+    }
+
+    /// Store the tags of added tracks, grouped by album folder
+    async fn save_tracks(
+        &self,
+        folder: &LibraryFolder,
+        tracks: Vec<(String, AudioTags)>,
+    ) -> Result<(), FileScannerError> {
+        let db_error = |e: sqlx::Error| FileScannerError::DatabaseError(e.to_string());
+        let ids: HashMap<String, i64> = MediaItem::list_by_folder(&self.db, folder.id)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|item| (item.file_path, item.id))
+            .collect();
+
+        let metadata: Vec<_> = tracks
+            .into_iter()
+            .filter_map(|(file_path, tags)| {
+                Some(CreateMusicMetadata {
+                    media_item_id: *ids.get(&file_path)?,
+                    album_key: album_key(Path::new(&file_path)),
+                    title: tags.title,
+                    artist: tags.artist,
+                    album_artist: tags.album_artist,
+                    album: tags.album,
+                    track_number: tags.track_number,
+                    disc_number: tags.disc_number,
+                    year: tags.year,
+                })
+            })
+            .collect();
+
+        MusicMetadata::upsert_many(&self.db, &metadata)
+            .await
+            .map_err(db_error)
+    }
+
+    /// Probe new video files, in the order of `entries`
+    ///
+    /// Files that can't be probed get empty stream info. A missing ffprobe is
+    /// reported once and stops probing for the rest of the scan.
+    async fn probe_entries(
+        &self,
+        media_type: MediaType,
+        entries: &[ScanEntry],
+        progress: &ScanProgress,
+        pacer: &Arc<Pacer>,
+    ) -> Vec<MediaStreamInfo> {
+        let Some(ref probe) = self.probe else {
+            return vec![MediaStreamInfo::default(); entries.len()];
+        };
+        if !matches!(media_type, MediaType::Movie | MediaType::Tv) {
+            return vec![MediaStreamInfo::default(); entries.len()];
+        }
+
+        let unavailable = Arc::new(AtomicBool::new(false));
+        let paths: Vec<PathBuf> = entries.iter().map(|e| PathBuf::from(&e.file_path)).collect();
+        let probes = if self.throttle.is_active() { 1 } else { MAX_PROBES };
+        stream::iter(paths)
+            .map(|path| {
+                probe_file(
+                    probe.clone(),
+                    path,
+                    unavailable.clone(),
+                    progress.clone(),
+                    pacer.clone(),
+                )
+            })
+            .buffered(probes)
+            .collect()
+            .await
+    }
+
     /// Scan all enabled library folders
     pub async fn scan_all_libraries(
         &self,
@@ -144,10 +687,28 @@ impl FileScanner {
             .await
             .map_err(|e| FileScannerError::DatabaseError(e.to_string()))?;
 
+        Ok(self.scan_folders(folders, &ScanProgress::default()).await)
+    }
+
+    /// Scan folders one after another until done or cancelled
+    ///
+    /// A folder that fails to scan is reported with one error.
+    pub async fn scan_folders(
+        &self,
+        folders: Vec<LibraryFolder>,
+        progress: &ScanProgress,
+    ) -> Vec<(LibraryFolder, ScanResult)> {
         let mut results = Vec::new();
 
         for folder in folders {
-            match self.scan_library_folder(&folder).await {
+            if progress.is_cancelled() {
+                break;
+            }
+
+            match self
+                .scan_library_folder_with_progress(&folder, progress)
+                .await
+            {
                 Ok(result) => {
                     results.push((folder, result));
                 }
@@ -156,58 +717,430 @@ impl FileScanner {
                     results.push((
                         folder,
                         ScanResult {
-                            total_files: 0,
-                            new_items: 0,
-                            existing_items: 0,
                             errors: 1,
+                            ..Default::default()
                         },
                     ));
                 }
             }
         }
 
-        Ok(results)
+        results
     }
+}
 
-    async fn handle_media_entry(
-        &self,
-        folder: &LibraryFolder,
-        title: String,
-        file_path: String,
-        file_size: i64,
-        counters: &mut ScanCounters,
-    ) {
-        match MediaItem::find_by_path(&self.db, &file_path).await {
-            Ok(Some(_)) => {
-                debug!("Media item already exists: {}", file_path);
-                counters.existing_items += 1;
+/// Pair new files with known items whose files are gone and share their fingerprint
+///
+/// Returns `(index into fingerprints, item ID)` pairs. A fingerprint shared by
+/// several new files or several gone items is ambiguous and left alone.
+fn match_moves(
+    known: &[MediaItem],
+    found: &HashSet<String>,
+    fingerprints: &[Option<String>],
+) -> Vec<(usize, i64)> {
+    let mut gone: HashMap<&str, Vec<i64>> = HashMap::new();
+    for item in known {
+        if let Some(ref fingerprint) = item.fingerprint
+            && !found.contains(&item.file_path)
+        {
+            gone.entry(fingerprint).or_default().push(item.id);
+        }
+    }
+
+    let mut new: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, fingerprint) in fingerprints.iter().enumerate() {
+        if let Some(fingerprint) = fingerprint {
+            new.entry(fingerprint).or_default().push(index);
+        }
+    }
+
+    new.into_iter()
+        .filter_map(|(fingerprint, indexes)| {
+            match (indexes.as_slice(), gone.get(fingerprint)?.as_slice()) {
+                ([index], [id]) => Some((*index, *id)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Fingerprint one file for [`FileScanner::fingerprint_files`]
+async fn fingerprint_file(
+    path: PathBuf,
+    progress: ScanProgress,
+    pacer: Arc<Pacer>,
+) -> Option<String> {
+    // Disc folders have no single file to follow
+    if path.is_dir() || progress.is_cancelled() {
+        return None;
+    }
+
+    pacer.wait().await;
+    progress.set_current(&path);
+    let hashed = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || fingerprint(&path)
+    })
+    .await;
+
+    match hashed {
+        Ok(Ok(fingerprint)) => Some(fingerprint),
+        Ok(Err(e)) => {
+            warn!("Failed to fingerprint {}: {}", path.display(), e);
+            None
+        }
+        Err(e) => {
+            error!("Fingerprint task failed: {}", e);
+            None
+        }
+    }
+}
+
+async fn read_tags_file(path: PathBuf, progress: ScanProgress, pacer: Arc<Pacer>) -> AudioTags {
+    if progress.is_cancelled() {
+        return AudioTags::default();
+    }
+
+    pacer.wait().await;
+    progress.set_current(&path);
+    let read = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || AudioTags::read(&path)
+    })
+    .await;
+
+    match read {
+        Ok(Ok(tags)) => tags,
+        Ok(Err(e)) => {
+            warn!("Failed to read tags of {}: {}", path.display(), e);
+            AudioTags::default()
+        }
+        Err(e) => {
+            error!("Tag reading task failed: {}", e);
+            AudioTags::default()
+        }
+    }
+}
+
+/// BLAKE3 hash of a file's size and its first and last [`FINGERPRINT_CHUNK`] bytes
+///
+/// Cheap enough to compute for every new file, and unchanged by renames and
+/// moves, which don't touch the contents.
+fn fingerprint(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&size.to_le_bytes());
+    let mut chunk = Vec::with_capacity(FINGERPRINT_CHUNK as usize);
+    (&mut file).take(FINGERPRINT_CHUNK).read_to_end(&mut chunk)?;
+    hasher.update(&chunk);
+
+    if size > FINGERPRINT_CHUNK {
+        // The tail never overlaps the head, so small files aren't hashed twice
+        file.seek(SeekFrom::Start((size - FINGERPRINT_CHUNK).max(FINGERPRINT_CHUNK)))?;
+        chunk.clear();
+        file.take(FINGERPRINT_CHUNK).read_to_end(&mut chunk)?;
+        hasher.update(&chunk);
+    }
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Probe one file for [`FileScanner::probe_entries`]
+async fn probe_file(
+    probe: MediaProbe,
+    path: PathBuf,
+    unavailable: Arc<AtomicBool>,
+    progress: ScanProgress,
+    pacer: Arc<Pacer>,
+) -> MediaStreamInfo {
+    // Disc folders and images are left to later passes
+    let is_disc = path.is_dir() || is_iso(&path);
+    if is_disc || unavailable.load(Ordering::Relaxed) || progress.is_cancelled() {
+        return MediaStreamInfo::default();
+    }
+
+    pacer.wait().await;
+    progress.set_current(&path);
+    match probe.probe(&path).await {
+        Ok(info) => info,
+        Err(e @ MediaProbeError::NotInstalled(_)) => {
+            if !unavailable.swap(true, Ordering::Relaxed) {
+                warn!("Skipping media probing: {}", e);
+            }
+            MediaStreamInfo::default()
+        }
+        Err(e) => {
+            warn!("Failed to probe {}: {}", path.display(), e);
+            MediaStreamInfo::default()
+        }
+    }
+}
+
+/// Walk a library with one blocking walker per top-level folder
+///
+/// At most `walkers` walkers run at once, which keeps several requests in
+/// flight on network shares without flooding them. Excluded top-level folders
+/// are never walked, and walkers share a [`LoopGuard`] so each real folder is
+/// walked once.
+async fn walk_library(
+    root: &Path,
+    extensions: Vec<String>,
+    mut rules: IgnoreRules,
+    max_depth: usize,
+    progress: &ScanProgress,
+    pacer: &Arc<Pacer>,
+    walkers: usize,
+) -> Result<WalkOutput, FileScannerError> {
+    let semaphore = Arc::new(Semaphore::new(walkers));
+    let extensions: Arc<[String]> = extensions.into();
+    let guard = LoopGuard::new();
+
+    // Files directly in the root get their own shallow walker
+    rules.allows(root, true);
+    let mut trees = vec![(root.to_path_buf(), 1)];
+    if max_depth > 1 {
+        for entry in retry_io(|| std::fs::read_dir(root)).await? {
+            let path = entry?.path();
+            if path.is_dir() && rules.allows(&path, true) {
+                trees.push((path, max_depth - 1));
             }
-            Ok(None) => {
-                let create_item = CreateMediaItem {
-                    library_folder_id: folder.id,
-                    media_type: folder.media_type,
-                    title: title.clone(),
-                    file_path: file_path.clone(),
-                    file_size,
-                };
-
-                match MediaItem::create(&self.db, create_item).await {
-                    Ok(_) => {
-                        info!("Added new media item: {}", title);
-                        counters.new_items += 1;
-                    }
-                    Err(e) => {
-                        error!("Failed to create media item for {}: {}", file_path, e);
-                        counters.errors += 1;
-                    }
+        }
+    }
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (tree, depth) in trees {
+        let semaphore = semaphore.clone();
+        let extensions = extensions.clone();
+        let rules = rules.clone();
+        let guard = guard.clone();
+        let progress = progress.clone();
+        let pacer = pacer.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            tokio::task::spawn_blocking(move || {
+                walk_tree(&tree, depth, &extensions, rules, &guard, &progress, &pacer)
+            })
+            .await
+        });
+    }
+
+    let mut output = WalkOutput::default();
+    let mut seen = HashSet::new();
+    while let Some(joined) = tasks.join_next().await {
+        let walked = match joined {
+            Ok(Ok(walked)) => walked,
+            Ok(Err(e)) | Err(e) => {
+                error!("Directory walker failed: {}", e);
+                output.errors += 1;
+                continue;
+            }
+        };
+
+        output.errors += walked.errors;
+        // Files symlinked from several folders are still imported once
+        output.entries.extend(
+            walked
+                .entries
+                .into_iter()
+                .filter(|entry| seen.insert(entry.file_path.clone())),
+        );
+    }
+
+    // Walk paths a flaky share failed to list again; ones still failing are
+    // reported as unreachable
+    let mut delay = IO_RETRY_DELAY;
+    for _ in 1..IO_ATTEMPTS {
+        if output.unreachable.is_empty() || progress.is_cancelled() {
+            break;
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+
+        for (tree, depth) in std::mem::take(&mut output.unreachable) {
+            warn!("Retrying unreachable path: {}", tree.display());
+            let extensions = extensions.clone();
+            let rules = rules.clone();
+            let progress = progress.clone();
+            let pacer = pacer.clone();
+            let walked = tokio::task::spawn_blocking(move || {
+                walk_tree(&tree, depth, &extensions, rules, &LoopGuard::new(), &progress, &pacer)
+            })
+            .await;
+
+            match walked {
+                Ok(walked) => {
+                    output.errors += walked.errors;
+                    output.unreachable.extend(walked.unreachable);
+                    output.entries.extend(
+                        walked
+                            .entries
+                            .into_iter()
+                            .filter(|entry| seen.insert(entry.file_path.clone())),
+                    );
+                }
+                Err(e) => {
+                    error!("Directory walker failed: {}", e);
+                    output.errors += 1;
                 }
             }
+        }
+    }
+
+    output.skipped_cycles = guard.skipped();
+    Ok(output)
+}
+
+/// Run blocking IO, retrying transient errors with exponential backoff
+async fn retry_io<T>(mut op: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    let mut delay = IO_RETRY_DELAY;
+    for _ in 1..IO_ATTEMPTS {
+        match op() {
+            Err(e) if is_transient(&e) => {
+                warn!("Transient IO error, retrying in {:?}: {}", delay, e);
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    op()
+}
+
+/// IO errors a network share may recover from
+fn is_transient(e: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    matches!(
+        e.kind(),
+        ErrorKind::Interrupted
+            | ErrorKind::TimedOut
+            | ErrorKind::WouldBlock
+            | ErrorKind::NotConnected
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::HostUnreachable
+            | ErrorKind::NetworkUnreachable
+            | ErrorKind::NetworkDown
+            | ErrorKind::StaleNetworkFileHandle
+    ) || (cfg!(unix) && e.raw_os_error() == Some(5)) // EIO, from servers gone away
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    std::fs::read_dir(path).map_or(true, |mut entries| entries.next().is_none())
+}
+
+/// Collect media files at most `max_depth` levels below `root`
+fn walk_tree(
+    root: &Path,
+    max_depth: usize,
+    extensions: &[String],
+    mut rules: IgnoreRules,
+    guard: &LoopGuard,
+    progress: &ScanProgress,
+    pacer: &Pacer,
+) -> WalkOutput {
+    let mut output = WalkOutput::default();
+    let mut processed_disc_roots: HashSet<PathBuf> = HashSet::new();
+
+    for entry in WalkDir::new(root)
+        .follow_links(true)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(|e| {
+            rules.allows(e.path(), e.file_type().is_dir()) && guard.allows(e, max_depth)
+        })
+    {
+        if progress.is_cancelled() {
+            break;
+        }
+
+        let entry = match entry {
+            Ok(entry) => entry,
             Err(e) => {
-                error!("Database error while checking {}: {}", file_path, e);
-                counters.errors += 1;
+                if e.loop_ancestor().is_some() {
+                    guard.skip();
+                } else if let Some(path) = e.path()
+                    && e.io_error().is_some_and(is_transient)
+                {
+                    warn!("Failed to read {}: {}", path.display(), e);
+                    output.unreachable.push((path.to_path_buf(), max_depth - e.depth()));
+                }
+                continue;
+            }
+        };
+
+        pacer.wait_blocking();
+        let entry_path = entry.path();
+
+        // Skip directories unless they represent disc structures
+        if entry_path.is_dir() {
+            progress.set_current(entry_path);
+            continue;
+        }
+
+        // Handle Blu-ray/DVD disc structures by looking for indicator files
+        if let Some(file_name) = entry_path.file_name().and_then(|n| n.to_str())
+            && let Some(_disc_type) = detect_disc_indicator(file_name)
+        {
+            if let Some(root) = entry_path.parent().and_then(|p| p.parent())
+                && processed_disc_roots.insert(root.to_path_buf())
+            {
+                output.entries.push(ScanEntry {
+                    title: extract_title(root),
+                    file_path: root.to_string_lossy().to_string(),
+                    file_size: calculate_directory_size(root),
+                });
+                progress.record_walked();
             }
+
+            // We captured the disc root, skip files inside it
+            continue;
+        }
+
+        if is_inside_disc_structure(entry_path) {
+            continue;
         }
+
+        // Check if file has supported extension
+        if let Some(ext) = entry_path.extension() {
+            let ext_str = ext.to_string_lossy().to_lowercase();
+            if !extensions.contains(&ext_str) {
+                continue;
+            }
+        } else {
+            continue;
+        }
+
+        // Images holding no DVD or Blu-ray folders are not video
+        if is_iso(entry_path) && !probe_iso(entry_path).may_be_video() {
+            debug!("Skipping data disc image: {}", entry_path.display());
+            continue;
+        }
+
+        // Get file metadata
+        let file_path = entry_path.to_string_lossy().to_string();
+        let file_size = match entry.metadata() {
+            Ok(metadata) => metadata.len() as i64,
+            Err(e) => {
+                error!("Failed to get metadata for {}: {}", file_path, e);
+                output.errors += 1;
+                continue;
+            }
+        };
+
+        output.entries.push(ScanEntry {
+            // Extract title from filename
+            title: extract_title(entry_path),
+            file_path,
+            file_size,
+        });
+        progress.record_walked();
     }
+
+    output
 }
 
 /// Get supported file extensions for a media type
@@ -219,6 +1152,7 @@ fn get_supported_extensions(media_type: MediaType) -> Vec<&'static str> {
         ],
         MediaType::Comic => vec!["cbz", "cbr", "cb7", "cbt", "pdf"],
         MediaType::Book => vec!["epub", "mobi", "azw3", "pdf"],
+        MediaType::Music => vec!["flac", "mp3", "m4a", "opus"],
     }
 }
 
@@ -231,16 +1165,72 @@ fn extract_title(path: &Path) -> String {
         .to_string()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum DiscType {
-    BluRay,
-    Dvd,
+/// Parse a scanned file for matching, with the runtime its probe found
+///
+/// The runtime tells movies from episodes when the name alone can't.
+pub fn parse_scanned(path: &Path, stream_info: &MediaStreamInfo) -> ParsedMedia {
+    let mut parsed = Parser::parse(path);
+    parsed.runtime = stream_info
+        .duration
+        .filter(|seconds| *seconds > 0.0)
+        .map(|seconds| (seconds / 60.0).round() as i32);
+    parsed
+}
+
+/// Group key shared by versions of one movie
+///
+/// Follows the Jellyfin convention: `Movie (2020)/Movie (2020) - 2160p.mkv` is a
+/// version of `Movie (2020)/Movie (2020).mkv` because its name starts with the
+/// folder name followed by ` - `.
+fn version_group(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let folder = parent
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+
+    let base = stem
+        .strip_prefix(folder)
+        .filter(|rest| !folder.is_empty() && (rest.is_empty() || rest.starts_with(" - ")))
+        .map_or(stem, |_| folder);
+
+    parent.join(base).to_string_lossy().to_string()
 }
 
-fn detect_disc_indicator(file_name: &str) -> Option<DiscType> {
+/// Album a track belongs to: its folder, or the folder above a `CD1`/`Disc 2` folder
+fn album_key(path: &Path) -> String {
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let in_disc_folder = parent
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(is_disc_folder);
+
+    match parent.parent() {
+        Some(album) if in_disc_folder => album,
+        _ => parent,
+    }
+    .to_string_lossy()
+    .to_string()
+}
+
+/// Whether a folder name is `CD1`, `Disc 2`, `disk_3` and the like
+fn is_disc_folder(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ["cd", "disc", "disk"].iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .map(|rest| rest.trim_start_matches([' ', '_', '-', '.']))
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+fn detect_disc_indicator(file_name: &str) -> Option<DiscKind> {
     match file_name.to_ascii_lowercase().as_str() {
-        "index.bdmv" | "movieobject.bdmv" => Some(DiscType::BluRay),
-        "video_ts.ifo" => Some(DiscType::Dvd),
+        "index.bdmv" | "movieobject.bdmv" => Some(DiscKind::BluRay),
+        "video_ts.ifo" => Some(DiscKind::Dvd),
         _ => None,
     }
 }
@@ -284,20 +1274,28 @@ pub enum FileScannerError {
 
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    #[error("Invalid exclude pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Library folder offline: {0}")]
+    Offline(String),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entities::ExtensionOverrides;
+    use crate::scraper::{MediaInfo, MediaType as ScraperMediaType, Matcher};
 
     #[test]
     fn test_detect_disc_indicator() {
-        assert_eq!(detect_disc_indicator("index.bdmv"), Some(DiscType::BluRay));
+        assert_eq!(detect_disc_indicator("index.bdmv"), Some(DiscKind::BluRay));
         assert_eq!(
             detect_disc_indicator("movieobject.bdmv"),
-            Some(DiscType::BluRay)
+            Some(DiscKind::BluRay)
         );
-        assert_eq!(detect_disc_indicator("video_ts.ifo"), Some(DiscType::Dvd));
+        assert_eq!(detect_disc_indicator("video_ts.ifo"), Some(DiscKind::Dvd));
         assert!(detect_disc_indicator("random.mkv").is_none());
     }
 
@@ -315,4 +1313,338 @@ mod tests {
         let regular_file = Path::new("Movie.mkv");
         assert!(!is_inside_disc_structure(regular_file));
     }
+
+    #[test]
+    fn test_version_group() {
+        let dir = Path::new("Movies").join("Tenet (2020)");
+
+        assert_eq!(
+            version_group(&dir.join("Tenet (2020).mkv")),
+            version_group(&dir.join("Tenet (2020) - 2160p.mkv"))
+        );
+        assert_ne!(
+            version_group(&dir.join("Tenet (2020).mkv")),
+            version_group(&dir.join("Trailer.mkv"))
+        );
+    }
+
+    #[test]
+    fn test_parse_scanned_uses_probed_runtime() {
+        let path = Path::new("Cowboy Bebop.mkv");
+        let results = vec![
+            MediaInfo::new("1", "Cowboy Bebop", "tmdb")
+                .with_type(ScraperMediaType::Movie)
+                .with_year(Some(1998)),
+            MediaInfo::new("2", "Cowboy Bebop", "tmdb")
+                .with_type(ScraperMediaType::Tv)
+                .with_year(Some(1998)),
+        ];
+        let probed = |seconds| MediaStreamInfo {
+            duration: Some(seconds),
+            ..Default::default()
+        };
+
+        let parsed = parse_scanned(path, &probed(1450.0));
+        assert_eq!(parsed.runtime, Some(24));
+        let ranked = Matcher::rank(results.clone(), &parsed);
+        assert_eq!(ranked[0].info.media_type, ScraperMediaType::Tv);
+
+        let parsed = parse_scanned(path, &probed(6900.0));
+        let ranked = Matcher::rank(results, &parsed);
+        assert_eq!(ranked[0].info.media_type, ScraperMediaType::Movie);
+
+        assert_eq!(parse_scanned(path, &MediaStreamInfo::default()).runtime, None);
+    }
+
+    #[test]
+    fn test_extension_overrides() {
+        let defaults = get_supported_extensions(MediaType::Movie);
+        let extended = ExtensionOverrides {
+            include: vec![".RMVB".to_string(), "strm".to_string()],
+            exclude: vec!["iso".to_string()],
+            ..Default::default()
+        }
+        .apply(&defaults);
+        assert!(extended.contains(&"mkv".to_string()));
+        assert!(extended.contains(&"rmvb".to_string()) && extended.contains(&"strm".to_string()));
+        assert!(!extended.contains(&"iso".to_string()));
+
+        let replaced = ExtensionOverrides {
+            replace: Some(vec!["mkv".to_string(), "MKV".to_string()]),
+            ..Default::default()
+        }
+        .apply(&defaults);
+        assert_eq!(replaced, ["mkv"]);
+    }
+
+    #[test]
+    fn test_pacer_spaces_files() {
+        let pacer = Pacer::new(&ScanThrottle {
+            max_files_per_sec: Some(10),
+            batch_pause_ms: 5_000,
+            ..Default::default()
+        });
+        let ms = |delay: std::time::Duration| delay.as_millis().div_ceil(10) * 10;
+
+        let delays: Vec<_> = (0..THROTTLE_BATCH_SIZE + 1).map(|_| ms(pacer.delay())).collect();
+
+        assert_eq!(&delays[..3], [0, 100, 200]);
+        assert_eq!(delays[THROTTLE_BATCH_SIZE], 15_000);
+        assert!(Pacer::new(&ScanThrottle::default()).delay().is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_walk_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("Heat.1995.mkv"), b"video").unwrap();
+        std::fs::write(root.join("notes.txt"), b"text").unwrap();
+        std::fs::create_dir_all(root.join("#recycle")).unwrap();
+        std::fs::write(root.join("#recycle").join("Old.mkv"), b"video").unwrap();
+        std::fs::create_dir_all(root.join("Tenet (2020)")).unwrap();
+        std::fs::write(root.join("Tenet (2020)").join("Tenet (2020).mkv"), b"video").unwrap();
+        let disc = root.join("Dune").join("Disc").join("BDMV");
+        std::fs::create_dir_all(disc.join("STREAM")).unwrap();
+        std::fs::write(disc.join("index.bdmv"), b"index").unwrap();
+        std::fs::write(disc.join("MovieObject.bdmv"), b"object").unwrap();
+        std::fs::write(disc.join("STREAM").join("00001.m2ts"), b"stream").unwrap();
+
+        let rules = IgnoreRules::new(root).with_excludes(&["#recycle"]).unwrap();
+        let walked = walk_library(
+            root,
+            ExtensionOverrides::default().apply(&get_supported_extensions(MediaType::Movie)),
+            rules,
+            DEFAULT_MAX_DEPTH,
+            &ScanProgress::default(),
+            &Arc::new(Pacer::new(&ScanThrottle::default())),
+            MAX_WALKERS,
+        )
+        .await
+        .unwrap();
+
+        let mut titles: Vec<_> = walked.entries.iter().map(|e| e.title.as_str()).collect();
+        titles.sort_unstable();
+        assert_eq!(titles, ["Disc", "Heat.1995", "Tenet (2020)"]);
+        assert_eq!(walked.errors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_files_are_flagged() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Heat.1995.mkv");
+        std::fs::write(&file, b"video").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"not media").unwrap();
+        let folder = LibraryFolder::create(
+            &db,
+            crate::entities::CreateLibraryFolder {
+                name: "Movies".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+                media_type: MediaType::Movie,
+                exclude_patterns: Vec::new(),
+                extensions: Default::default(),
+                metadata_format: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+        let scanner = FileScanner::new(db.clone()).with_purge_after_days(Some(30));
+
+        assert_eq!(scanner.scan_library_folder(&folder).await.unwrap().new_items, 1);
+
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(scanner.scan_library_folder(&folder).await.unwrap().missing_items, 1);
+        let items = MediaItem::list_by_folder(&db, folder.id).await.unwrap();
+        assert!(items[0].missing_since.is_some());
+
+        std::fs::write(&file, b"video").unwrap();
+        let result = scanner.scan_library_folder(&folder).await.unwrap();
+        assert_eq!((result.existing_items, result.restored_items), (1, 1));
+
+        std::fs::remove_file(&file).unwrap();
+        scanner.scan_library_folder(&folder).await.unwrap();
+        sqlx::query("UPDATE media_items SET missing_since = '2000-01-01 00:00:00'")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(scanner.scan_library_folder(&folder).await.unwrap().purged_items, 1);
+        assert!(MediaItem::list_by_folder(&db, folder.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_moved_files_are_relinked() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Heat.1995.mkv");
+        std::fs::write(&file, vec![7u8; 200 * 1024]).unwrap();
+        std::fs::write(dir.path().join("Tenet.2020.mkv"), b"other video").unwrap();
+        let folder = LibraryFolder::create(
+            &db,
+            crate::entities::CreateLibraryFolder {
+                name: "Movies".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+                media_type: MediaType::Movie,
+                exclude_patterns: Vec::new(),
+                extensions: Default::default(),
+                metadata_format: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+        let scanner = FileScanner::new(db.clone());
+        scanner.scan_library_folder(&folder).await.unwrap();
+        let before = MediaItem::find_by_path(&db, &file.to_string_lossy())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let moved = dir.path().join("Heat (1995)").join("Heat (1995).mkv");
+        std::fs::create_dir(moved.parent().unwrap()).unwrap();
+        std::fs::rename(&file, &moved).unwrap();
+        let result = scanner.scan_library_folder(&folder).await.unwrap();
+
+        assert_eq!((result.moved_items, result.new_items, result.missing_items), (1, 0, 0));
+        let after = MediaItem::find_by_id(&db, before.id).await.unwrap().unwrap();
+        assert_eq!(after.file_path, moved.to_string_lossy());
+        assert_eq!(MediaItem::list_by_folder(&db, folder.id).await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_album_key() {
+        let album = Path::new("Music").join("Radiohead").join("OK Computer");
+
+        assert_eq!(album_key(&album.join("01 - Airbag.flac")), album.to_string_lossy());
+        assert_eq!(album_key(&album.join("CD1").join("01.flac")), album.to_string_lossy());
+        assert_eq!(album_key(&album.join("Disc 2").join("01.flac")), album.to_string_lossy());
+        assert!(!is_disc_folder("Discovery"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_music_folder() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let album = dir.path().join("OK Computer");
+        std::fs::create_dir_all(album.join("CD2")).unwrap();
+        let flac = crate::services::audio_tags::tests::flac_with_comments(&[
+            "TITLE=Airbag",
+            "ALBUM=OK Computer",
+            "TRACKNUMBER=1",
+        ]);
+        std::fs::write(album.join("01.flac"), flac).unwrap();
+        std::fs::write(album.join("CD2").join("02 - Bonus.mp3"), b"not audio").unwrap();
+        std::fs::write(album.join("cover.jpg"), b"image").unwrap();
+        let folder = LibraryFolder::create(
+            &db,
+            crate::entities::CreateLibraryFolder {
+                name: "Music".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+                media_type: MediaType::Music,
+                exclude_patterns: Vec::new(),
+                extensions: Default::default(),
+                metadata_format: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let result = FileScanner::new(db.clone())
+            .scan_library_folder(&folder)
+            .await
+            .unwrap();
+
+        assert_eq!(result.new_items, 2);
+        let tracks = MusicMetadata::list_album(&db, &album.to_string_lossy())
+            .await
+            .unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].album.as_deref(), Some("OK Computer"));
+        assert_eq!(tracks[0].track_number, Some(1));
+        let item = MediaItem::find_by_id(&db, tracks[0].media_item_id).await.unwrap().unwrap();
+        assert_eq!((item.title.as_str(), item.media_type), ("Airbag", MediaType::Music));
+    }
+
+    #[tokio::test]
+    async fn test_emptied_folder_goes_offline() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Heat.1995.mkv");
+        std::fs::write(&file, b"video").unwrap();
+        let folder = LibraryFolder::create(
+            &db,
+            crate::entities::CreateLibraryFolder {
+                name: "Share".to_string(),
+                path: dir.path().to_string_lossy().to_string(),
+                media_type: MediaType::Movie,
+                exclude_patterns: Vec::new(),
+                extensions: Default::default(),
+                metadata_format: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+        let scanner = FileScanner::new(db.clone());
+        scanner.scan_library_folder(&folder).await.unwrap();
+
+        std::fs::remove_file(&file).unwrap();
+        let result = scanner.scan_library_folder(&folder).await;
+
+        assert!(matches!(result, Err(FileScannerError::Offline(_))));
+        let items = MediaItem::list_by_folder(&db, folder.id).await.unwrap();
+        assert!(items[0].missing_since.is_none());
+        let offline = LibraryFolder::find_by_id(&db, folder.id).await.unwrap().unwrap();
+        assert!(offline.offline_since.is_some());
+
+        std::fs::write(&file, b"video").unwrap();
+        scanner.scan_library_folder(&folder).await.unwrap();
+        let online = LibraryFolder::find_by_id(&db, folder.id).await.unwrap().unwrap();
+        assert!(online.offline_since.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_retry_io() {
+        let mut attempts = 0;
+        let result = retry_io(|| {
+            attempts += 1;
+            if attempts < IO_ATTEMPTS {
+                Err(std::io::Error::from(std::io::ErrorKind::TimedOut))
+            } else {
+                Ok(attempts)
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), IO_ATTEMPTS);
+
+        let mut attempts = 0;
+        let result: std::io::Result<()> = retry_io(|| {
+            attempts += 1;
+            Err(std::io::Error::from(std::io::ErrorKind::NotFound))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
 }
@@ -0,0 +1,192 @@
+use crate::entities::MediaStreamInfo;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::debug;
+
+/// Default ffprobe binary, looked up on `PATH`
+pub const DEFAULT_FFPROBE_PATH: &str = "ffprobe";
+
+/// Reads technical details of video files with ffprobe
+#[derive(Debug, Clone)]
+pub struct MediaProbe {
+    ffprobe_path: PathBuf,
+    idle_io: bool,
+}
+
+impl Default for MediaProbe {
+    fn default() -> Self {
+        Self::new(DEFAULT_FFPROBE_PATH)
+    }
+}
+
+impl MediaProbe {
+    /// Create a probe running the given ffprobe binary
+    #[must_use]
+    pub fn new(ffprobe_path: impl Into<PathBuf>) -> Self {
+        Self {
+            ffprobe_path: ffprobe_path.into(),
+            idle_io: false,
+        }
+    }
+
+    /// Run ffprobe with idle IO priority through `ionice` (Linux only)
+    #[must_use]
+    pub const fn with_idle_io(mut self, idle_io: bool) -> Self {
+        self.idle_io = idle_io;
+        self
+    }
+
+    /// Probe a video file
+    pub async fn probe(&self, path: &Path) -> Result<MediaStreamInfo, MediaProbeError> {
+        let mut command = if self.idle_io && cfg!(target_os = "linux") {
+            let mut command = Command::new("ionice");
+            command.arg("-c3").arg(&self.ffprobe_path);
+            command
+        } else {
+            Command::new(&self.ffprobe_path)
+        };
+
+        let output = command
+            .args(["-v", "quiet", "-print_format", "json", "-show_format", "-show_streams"])
+            .arg(path)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    let program = command.as_std().get_program();
+                    MediaProbeError::NotInstalled(program.to_string_lossy().to_string())
+                } else {
+                    MediaProbeError::Io(e)
+                }
+            })?;
+
+        // ionice exits with 127 when it can't run ffprobe
+        if output.status.code() == Some(127) {
+            return Err(MediaProbeError::NotInstalled(
+                self.ffprobe_path.display().to_string(),
+            ));
+        }
+
+        if !output.status.success() {
+            return Err(MediaProbeError::Failed(format!(
+                "ffprobe exited with {} for {}",
+                output.status,
+                path.display()
+            )));
+        }
+
+        let info = parse_ffprobe_output(&output.stdout)?;
+        debug!("Probed {}: {:?}", path.display(), info);
+        Ok(info)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeStream {
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<i64>,
+    height: Option<i64>,
+    #[serde(default)]
+    disposition: FfprobeDisposition,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FfprobeDisposition {
+    #[serde(default)]
+    attached_pic: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeFormat {
+    format_name: Option<String>,
+    /// ffprobe prints numbers in `format` as strings
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+/// Extract stream info from ffprobe's JSON output
+fn parse_ffprobe_output(json: &[u8]) -> Result<MediaStreamInfo, MediaProbeError> {
+    let output: FfprobeOutput =
+        serde_json::from_slice(json).map_err(|e| MediaProbeError::Failed(e.to_string()))?;
+
+    // Cover art is reported as a video stream too
+    let video = output
+        .streams
+        .iter()
+        .find(|s| s.codec_type.as_deref() == Some("video") && s.disposition.attached_pic == 0);
+    let audio = output
+        .streams
+        .iter()
+        .find(|s| s.codec_type.as_deref() == Some("audio"));
+    let format = output.format.as_ref();
+
+    Ok(MediaStreamInfo {
+        duration: format
+            .and_then(|f| f.duration.as_deref())
+            .and_then(|d| d.parse().ok()),
+        container: format
+            .and_then(|f| f.format_name.as_deref())
+            .and_then(|name| name.split(',').next())
+            .map(str::to_string),
+        video_codec: video.and_then(|s| s.codec_name.clone()),
+        audio_codec: audio.and_then(|s| s.codec_name.clone()),
+        width: video.and_then(|s| s.width),
+        height: video.and_then(|s| s.height),
+        bit_rate: format
+            .and_then(|f| f.bit_rate.as_deref())
+            .and_then(|b| b.parse().ok()),
+    })
+}
+
+/// Media probe errors
+#[derive(Debug, thiserror::Error)]
+pub enum MediaProbeError {
+    #[error("ffprobe not found: {0}")]
+    NotInstalled(String),
+
+    #[error("Probe failed: {0}")]
+    Failed(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffprobe_output() {
+        let json = br#"{
+            "streams": [
+                {"codec_type": "video", "codec_name": "mjpeg", "width": 600, "height": 900,
+                 "disposition": {"attached_pic": 1}},
+                {"codec_type": "video", "codec_name": "hevc", "width": 3840, "height": 2160,
+                 "disposition": {"attached_pic": 0}},
+                {"codec_type": "audio", "codec_name": "eac3"},
+                {"codec_type": "subtitle", "codec_name": "subrip"}
+            ],
+            "format": {"format_name": "matroska,webm", "duration": "7265.120000",
+                       "bit_rate": "18234567"}
+        }"#;
+
+        let info = parse_ffprobe_output(json).unwrap();
+
+        assert_eq!(info.container.as_deref(), Some("matroska"));
+        assert_eq!(info.video_codec.as_deref(), Some("hevc"));
+        assert_eq!(info.audio_codec.as_deref(), Some("eac3"));
+        assert_eq!((info.width, info.height), (Some(3840), Some(2160)));
+        assert_eq!(info.duration, Some(7265.12));
+        assert_eq!(info.bit_rate, Some(18_234_567));
+    }
+}
@@ -1,15 +1,81 @@
 use crate::{
-    entities::{CreateVideoMetadata, MediaItem, MediaType as EntityMediaType, VideoMetadata},
-    scraper::{Confidence, MediaMetadata, MediaType, Parser, ScraperManager},
+    entities::{
+        AlbumMetadata, BookMetadata, ComicMetadata, CreateAlbumMetadata, CreateBookMetadata,
+        CreateComicMetadata, CreateVideoMetadata, LibraryFolder, MediaItem,
+        MediaType as EntityMediaType, MusicMetadata, VideoMetadata,
+    },
+    scraper::{
+        self, AlbumInfo, ChapterInfo, Confidence, CoverInfo, EpisodeMatch, GoogleBooksProvider,
+        MangaDexProvider, MediaMetadata, MediaType, MusicBrainzProvider, OpenLibraryProvider,
+        ParsedMedia, Parser, ScrapeResult, ScraperError, ScraperManager, Writer, normalize_isbn,
+    },
+    services::parse_scanned,
 };
-use std::path::Path;
-use std::sync::Arc;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
+/// Files scraped at once by [`MetadataAgent::batch_fetch_metadata`]
+pub const BATCH_CONCURRENCY: usize = 4;
+/// Albums MusicBrainz is asked for when looking up a track's album
+const ALBUM_SEARCH_LIMIT: usize = 5;
+/// Lowest MusicBrainz search score (0-100) taken as the album of a track
+const MIN_ALBUM_SCORE: i32 = 80;
+/// Books a title search asks each provider for
+const BOOK_SEARCH_LIMIT: usize = 5;
+/// Series a comic title search asks MangaDex for
+const COMIC_SEARCH_LIMIT: usize = 5;
+
+/// ISBN-10 or ISBN-13 in a file name, with or without hyphens
+static ISBN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:97[89][- ]?)?(?:[0-9][- ]?){9}[0-9x]\b").unwrap()
+});
+/// "v01", "Vol. 3", "Volume 12"
+static VOLUME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:v|vol\.?|volume)\s*(\d+(?:\.\d+)?)\b").unwrap()
+});
+/// "c012", "Ch. 12.5", "Chapter 7"
+static CHAPTER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:c|ch\.?|chapter)\s*(\d+(?:\.\d+)?)\b").unwrap()
+});
+/// Bare chapter number ending a file name, e.g. "Berserk 042"
+static TRAILING_NUMBER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\s_-]+(\d{1,4}(?:\.\d+)?)$").unwrap());
+/// `[Retail]`, `(2019)` and the like
+static BRACKETS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[[^\]]*\]|\([^)]*\)|\{[^}]*\}").unwrap());
+
+/// Metadata saved for a media item, by kind of library
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "metadata", rename_all = "lowercase")]
+pub enum SavedMetadata {
+    /// Movie or episode details
+    Video(VideoMetadata),
+    /// Album the track belongs to, shared with its other tracks
+    Album(AlbumMetadata),
+    /// Ebook details
+    Book(BookMetadata),
+    /// Series of a comic or manga file, with the volume and chapter it holds
+    Comic(ComicMetadata),
+}
+
 /// Metadata agent service for fetching and saving metadata
 pub struct MetadataAgent {
     scraper_manager: Arc<ScraperManager>,
     db: sqlx::SqlitePool,
+    /// Looks up the albums of music libraries
+    music: Option<MusicBrainzProvider>,
+    /// Looks up books, tried first
+    open_library: Option<OpenLibraryProvider>,
+    /// Looks up books Open Library doesn't know
+    google_books: Option<GoogleBooksProvider>,
+    /// Looks up the series of comic libraries
+    manga: Option<MangaDexProvider>,
 }
 
 impl MetadataAgent {
@@ -19,13 +85,62 @@ impl MetadataAgent {
         Self {
             scraper_manager,
             db,
+            music: None,
+            open_library: None,
+            google_books: None,
+            manga: None,
         }
     }
 
-    /// Fetch and save metadata for a media item
+    /// Look up the albums of music libraries with `provider`
+    #[must_use]
+    pub fn with_music_provider(mut self, provider: MusicBrainzProvider) -> Self {
+        self.music = Some(provider);
+        self
+    }
+
+    /// Look up books on Open Library first
+    #[must_use]
+    pub fn with_open_library(mut self, provider: OpenLibraryProvider) -> Self {
+        self.open_library = Some(provider);
+        self
+    }
+
+    /// Look up books on Google Books when Open Library doesn't know them
+    #[must_use]
+    pub fn with_google_books(mut self, provider: GoogleBooksProvider) -> Self {
+        self.google_books = Some(provider);
+        self
+    }
+
+    /// Look up the series of comic libraries with `provider`
+    #[must_use]
+    pub fn with_manga_provider(mut self, provider: MangaDexProvider) -> Self {
+        self.manga = Some(provider);
+        self
+    }
+
+    /// Fetch and save metadata for a media item, from the providers of its
+    /// kind of library
     pub async fn fetch_and_save_metadata(
         &self,
         media_item: &MediaItem,
+    ) -> Result<SavedMetadata, MetadataAgentError> {
+        match media_item.media_type {
+            EntityMediaType::Music => self.fetch_album(media_item).await.map(SavedMetadata::Album),
+            EntityMediaType::Book => self.fetch_book(media_item).await.map(SavedMetadata::Book),
+            EntityMediaType::Comic => self
+                .fetch_comic(media_item, &mut HashMap::new())
+                .await
+                .map(SavedMetadata::Comic),
+            _ => self.fetch_video(media_item).await.map(SavedMetadata::Video),
+        }
+    }
+
+    /// Fetch and save metadata for a movie or episode, searching by its title
+    async fn fetch_video(
+        &self,
+        media_item: &MediaItem,
     ) -> Result<VideoMetadata, MetadataAgentError> {
         info!(
             "Fetching metadata for {} (ID: {})",
@@ -39,7 +154,7 @@ impl MetadataAgent {
         let media_type = match media_item.media_type {
             EntityMediaType::Movie => Some(MediaType::Movie),
             EntityMediaType::Tv => Some(MediaType::Tv),
-            EntityMediaType::Comic | EntityMediaType::Book => None,
+            EntityMediaType::Comic | EntityMediaType::Book | EntityMediaType::Music => None,
         };
 
         // Search and rank results
@@ -80,8 +195,10 @@ impl MetadataAgent {
                 MetadataAgentError::DetailsFailed(e.to_string())
             })?;
 
+        let episode = self.scraper_manager.resolve_episode(&parsed, &metadata).await;
+
         // Convert to database format and save
-        let saved = self.save_metadata(media_item.id, &metadata).await?;
+        let saved = self.save_metadata(media_item.id, &metadata, episode).await?;
 
         info!(
             "Successfully saved metadata for {} (ID: {}, confidence: {:?})",
@@ -103,32 +220,60 @@ impl MetadataAgent {
             file_path.display()
         );
 
-        // Use the scraper's built-in path parsing
-        let scrape_result = self.scraper_manager.scrape(file_path).await.map_err(|e| {
-            error!("Failed to scrape {}: {}", file_path.display(), e);
-            MetadataAgentError::SearchFailed(e.to_string())
-        })?;
+        // Parse the path, with the runtime the scan probed
+        let parsed = parse_scanned(file_path, &media_item.stream_info);
+        let scrape_result = self
+            .scraper_manager
+            .scrape_file(file_path, parsed)
+            .await
+            .map_err(|e| {
+                error!("Failed to scrape {}: {}", file_path.display(), e);
+                MetadataAgentError::SearchFailed(e.to_string())
+            })?;
 
+        self.save_scraped(media_item, scrape_result).await
+    }
+
+    /// Save what scraping an item's file found, fetching the metadata when
+    /// the match was too unsure for the scrape to fetch it
+    async fn save_scraped(
+        &self,
+        media_item: &MediaItem,
+        scrape_result: ScrapeResult,
+    ) -> Result<VideoMetadata, MetadataAgentError> {
         debug!(
             "Scrape result: {} (score: {}, confidence: {:?})",
             scrape_result.info.title, scrape_result.score, scrape_result.confidence
         );
 
         // Get or use existing metadata
-        let metadata = if let Some(m) = scrape_result.metadata {
-            m
+        let (metadata, episode) = if let Some(m) = scrape_result.metadata {
+            (m, scrape_result.episode)
         } else {
-            self.scraper_manager
+            let m = self
+                .scraper_manager
                 .get_metadata(&scrape_result.info)
                 .await
                 .map_err(|e| {
                     error!("Failed to get details: {}", e);
                     MetadataAgentError::DetailsFailed(e.to_string())
-                })?
+                })?;
+            let episode = self
+                .scraper_manager
+                .resolve_episode(&scrape_result.parsed, &m)
+                .await;
+            (m, episode)
         };
 
+        if let Some(episode) = episode {
+            debug!(
+                "Resolved episode: S{:02}E{:02} ({:?})",
+                episode.season, episode.episode, episode.kind
+            );
+        }
+
         // Save to database
-        let saved = self.save_metadata(media_item.id, &metadata).await?;
+        let saved = self.save_metadata(media_item.id, &metadata, episode).await?;
 
         info!(
             "Successfully saved metadata for {} (ID: {})",
@@ -138,11 +283,416 @@ impl MetadataAgent {
         Ok(saved)
     }
 
-    /// Save metadata to database
+    /// Look up the album a track belongs to and save it for all of its tracks
+    async fn fetch_album(
+        &self,
+        media_item: &MediaItem,
+    ) -> Result<AlbumMetadata, MetadataAgentError> {
+        let track = self.track_of(media_item).await?;
+        self.fetch_album_of(&track).await
+    }
+
+    /// Tags the scan read from a track
+    async fn track_of(&self, media_item: &MediaItem) -> Result<MusicMetadata, MetadataAgentError> {
+        MusicMetadata::find_by_media_item_id(&self.db, media_item.id)
+            .await
+            .map_err(|e| MetadataAgentError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| {
+                warn!("No tags were read from {}", media_item.file_path);
+                MetadataAgentError::NoMatchingResults
+            })
+    }
+
+    /// Look up an album on MusicBrainz by the tags of one of its tracks,
+    /// falling back to the album folder's name
+    async fn fetch_album_of(
+        &self,
+        track: &MusicMetadata,
+    ) -> Result<AlbumMetadata, MetadataAgentError> {
+        let music = self
+            .music
+            .as_ref()
+            .ok_or_else(|| MetadataAgentError::UnsupportedMediaType("music".to_string()))?;
+
+        let folder = Path::new(&track.album_key)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+        let title = track
+            .album
+            .clone()
+            .filter(|a| !a.trim().is_empty())
+            .or(folder)
+            .ok_or(MetadataAgentError::NoMatchingResults)?;
+        let artist = track.album_artist.as_ref().or(track.artist.as_ref());
+        info!("Fetching album metadata for {title} ({})", track.album_key);
+
+        let albums = music
+            .search_albums(&title, artist.map(String::as_str), ALBUM_SEARCH_LIMIT)
+            .await
+            .map_err(|e| match e {
+                ScraperError::NotFound(_) => MetadataAgentError::NoMatchingResults,
+                e => {
+                    error!("Failed to search for album {title}: {e}");
+                    MetadataAgentError::SearchFailed(e.to_string())
+                }
+            })?;
+        let best_match = best_album(albums, track.year).ok_or_else(|| {
+            warn!("No matching album found for {title}");
+            MetadataAgentError::NoMatchingResults
+        })?;
+        debug!(
+            "Found album: {} by {:?} (score: {:?})",
+            best_match.title, best_match.artist, best_match.score
+        );
+
+        let album = music.get_album(&best_match.id).await.map_err(|e| {
+            error!("Failed to get album details: {}", e);
+            MetadataAgentError::DetailsFailed(e.to_string())
+        })?;
+
+        let saved = AlbumMetadata::upsert(
+            &self.db,
+            CreateAlbumMetadata {
+                album_key: track.album_key.clone(),
+                musicbrainz_id: album.id,
+                release_id: album.release_id,
+                artist_id: album.artist_id,
+                title: album.title,
+                artist: album.artist,
+                release_date: album.release_date,
+                kind: album.kind,
+                label: album.label,
+                genres: album.genres,
+                cover_url: album.cover_url,
+            },
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to save album metadata to database: {}", e);
+            MetadataAgentError::DatabaseError(e.to_string())
+        })?;
+
+        info!("Successfully saved album metadata for {}", track.album_key);
+        Ok(saved)
+    }
+
+    /// Write `album.nfo` into the folder of the album a track belongs to, and
+    /// `artist.nfo` into the artist folder above it, from MusicBrainz
+    ///
+    /// Albums not looked up yet are looked up first. No `artist.nfo` is
+    /// written when the album folder sits right in the library. Returns the
+    /// written files.
+    pub async fn export_album_nfos(
+        &self,
+        media_item: &MediaItem,
+    ) -> Result<Vec<PathBuf>, MetadataAgentError> {
+        let music = self
+            .music
+            .as_ref()
+            .ok_or_else(|| MetadataAgentError::UnsupportedMediaType("music".to_string()))?;
+        let db_error = |e: sqlx::Error| MetadataAgentError::DatabaseError(e.to_string());
+
+        let track = self.track_of(media_item).await?;
+        let saved = match AlbumMetadata::find_by_album_key(&self.db, &track.album_key)
+            .await
+            .map_err(db_error)?
+        {
+            Some(saved) => saved,
+            None => self.fetch_album_of(&track).await?,
+        };
+        let album = music.get_album(&saved.musicbrainz_id).await.map_err(|e| {
+            error!("Failed to get album details: {}", e);
+            MetadataAgentError::DetailsFailed(e.to_string())
+        })?;
+
+        let write_failed = |e: anyhow::Error| MetadataAgentError::WriteFailed(e.to_string());
+        let album_dir = PathBuf::from(&track.album_key);
+        let mut written = Vec::new();
+        let path = album_dir.join("album.nfo");
+        Writer::write_album_nfo(&path, &album).await.map_err(write_failed)?;
+        written.push(path);
+
+        let library = LibraryFolder::find_by_id(&self.db, media_item.library_folder_id)
+            .await
+            .map_err(db_error)?
+            .map(|folder| PathBuf::from(folder.path));
+        let artist_dir = album_dir.parent().filter(|dir| {
+            library
+                .as_deref()
+                .is_some_and(|root| dir.starts_with(root) && *dir != root)
+        });
+        if let (Some(artist_dir), Some(name), Some(id)) =
+            (artist_dir, album.artist.as_deref(), album.artist_id.as_deref())
+        {
+            let artists = music
+                .search_artists(name, ALBUM_SEARCH_LIMIT)
+                .await
+                .inspect_err(|e| warn!("Failed to search for artist {name}: {e}"))
+                .unwrap_or_default();
+            if let Some(artist) = artists.iter().find(|a| a.id == id) {
+                let path = artist_dir.join("artist.nfo");
+                Writer::write_artist_nfo(&path, artist).await.map_err(write_failed)?;
+                written.push(path);
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// Look up an ebook by the ISBN in its file name, falling back to a
+    /// search by its title and author
+    async fn fetch_book(&self, media_item: &MediaItem) -> Result<BookMetadata, MetadataAgentError> {
+        if self.open_library.is_none() && self.google_books.is_none() {
+            return Err(MetadataAgentError::UnsupportedMediaType("book".to_string()));
+        }
+
+        let query = BookQuery::from_path(Path::new(&media_item.file_path));
+        info!(
+            "Fetching book metadata for {} (ID: {}, ISBN: {:?})",
+            query.title, media_item.id, query.isbn
+        );
+
+        let mut book = None;
+        if let Some(isbn) = &query.isbn {
+            book = self.book_by_isbn(isbn).await;
+        }
+        if book.is_none() {
+            book = self.book_by_title(&query).await?;
+        }
+        let book = book.ok_or_else(|| {
+            warn!("No matching book found for {}", query.title);
+            MetadataAgentError::NoMatchingResults
+        })?;
+        debug!("Found book: {} ({} {})", book.title, book.provider, book.id);
+
+        let saved = BookMetadata::upsert(
+            &self.db,
+            CreateBookMetadata {
+                media_item_id: media_item.id,
+                provider: book.provider,
+                provider_id: book.id,
+                title: book.title,
+                subtitle: book.subtitle,
+                authors: book.authors,
+                description: book.description,
+                publisher: book.publisher,
+                published_date: book.published_date,
+                language: book.language,
+                page_count: book.page_count,
+                isbn: book.isbn.or(query.isbn),
+                subjects: book.subjects,
+                series: book.series,
+                series_index: book.series_index,
+                rating: book.rating,
+                cover_url: book.cover_url,
+            },
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to save book metadata to database: {}", e);
+            MetadataAgentError::DatabaseError(e.to_string())
+        })?;
+
+        info!(
+            "Successfully saved book metadata for {} (ID: {})",
+            media_item.title, media_item.id
+        );
+        Ok(saved)
+    }
+
+    /// Edition with an ISBN, from the first provider that knows it
+    async fn book_by_isbn(&self, isbn: &str) -> Option<scraper::BookMetadata> {
+        if let Some(provider) = &self.open_library {
+            match provider.find_by_isbn(isbn).await {
+                Ok(Some(book)) => return Some(book),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to look up ISBN {isbn} on Open Library: {e}"),
+            }
+        }
+        if let Some(provider) = &self.google_books {
+            match provider.find_by_isbn(isbn).await {
+                Ok(Some(book)) => return Some(book),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to look up ISBN {isbn} on Google Books: {e}"),
+            }
+        }
+        None
+    }
+
+    /// Best title search result of the first provider finding any
+    async fn book_by_title(
+        &self,
+        query: &BookQuery,
+    ) -> Result<Option<scraper::BookMetadata>, MetadataAgentError> {
+        let author = query.author.as_deref();
+        let not_found = |e: ScraperError| match e {
+            ScraperError::NotFound(_) => Ok(Vec::new()),
+            e => {
+                error!("Failed to search for book {}: {e}", query.title);
+                Err(MetadataAgentError::SearchFailed(e.to_string()))
+            }
+        };
+        let details_failed = |e: ScraperError| {
+            error!("Failed to get book details: {}", e);
+            MetadataAgentError::DetailsFailed(e.to_string())
+        };
+
+        if let Some(provider) = &self.open_library {
+            let books = provider
+                .search(&query.title, author, BOOK_SEARCH_LIMIT)
+                .await
+                .or_else(not_found)?;
+            if let Some(found) = books.first() {
+                return provider.get_book(&found.id).await.map(Some).map_err(details_failed);
+            }
+        }
+        if let Some(provider) = &self.google_books {
+            let books = provider
+                .search(&query.title, author, BOOK_SEARCH_LIMIT)
+                .await
+                .or_else(not_found)?;
+            if let Some(found) = books.first() {
+                return provider.get_book(&found.id).await.map(Some).map_err(details_failed);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Look up the series a comic file belongs to on MangaDex, with the
+    /// cover of its volume and the title of its chapter
+    ///
+    /// Series already in `known` are not looked up again.
+    async fn fetch_comic(
+        &self,
+        media_item: &MediaItem,
+        known: &mut HashMap<String, Result<ComicSeries, MetadataAgentError>>,
+    ) -> Result<ComicMetadata, MetadataAgentError> {
+        let manga = self
+            .manga
+            .as_ref()
+            .ok_or_else(|| MetadataAgentError::UnsupportedMediaType("comic".to_string()))?;
+
+        let query = ComicQuery::from_path(Path::new(&media_item.file_path));
+        info!(
+            "Fetching comic metadata for {} (ID: {}, volume: {:?}, chapter: {:?})",
+            query.series, media_item.id, query.volume, query.chapter
+        );
+
+        let key = query.series.to_lowercase();
+        if !known.contains_key(&key) {
+            let series = self.fetch_series(manga, &query.series).await;
+            known.insert(key.clone(), series);
+        }
+        let series = match known.get_mut(&key) {
+            Some(Ok(series)) => series,
+            Some(Err(e)) => return Err(e.clone()),
+            None => return Err(MetadataAgentError::NoMatchingResults),
+        };
+
+        // The chapter feed can run to many pages, so it's only read when needed
+        if query.chapter.is_some() && series.chapters.is_none() {
+            let chapters = manga
+                .get_chapters(&series.metadata.id, None)
+                .await
+                .inspect_err(|e| warn!("Failed to fetch chapters of {}: {e}", query.series))
+                .unwrap_or_default();
+            series.chapters = Some(chapters);
+        }
+        let chapter = query.chapter.and_then(|number| {
+            series
+                .chapters
+                .iter()
+                .flatten()
+                .filter(|c| c.chapter == Some(number))
+                .min_by_key(|c| c.title.is_none())
+        });
+        let volume = query.volume.or_else(|| chapter.and_then(|c| c.volume));
+        let cover_url = volume
+            .and_then(|v| series.covers.iter().find(|c| c.volume == Some(v)))
+            .map(|c| c.url.clone())
+            .or_else(|| series.metadata.cover_url.clone());
+
+        let metadata = &series.metadata;
+        let saved = ComicMetadata::upsert(
+            &self.db,
+            CreateComicMetadata {
+                media_item_id: media_item.id,
+                provider: metadata.provider.clone(),
+                provider_id: metadata.id.clone(),
+                title: metadata.title.clone(),
+                description: metadata.description.clone(),
+                authors: metadata.authors.clone(),
+                artists: metadata.artists.clone(),
+                genres: metadata.genres.clone(),
+                status: metadata.status.clone(),
+                year: metadata.year,
+                volume,
+                chapter: query.chapter,
+                chapter_title: chapter.and_then(|c| c.title.clone()),
+                cover_url,
+                anilist_id: metadata.external_ids.anilist.clone(),
+                mal_id: metadata.external_ids.mal.clone(),
+            },
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to save comic metadata to database: {}", e);
+            MetadataAgentError::DatabaseError(e.to_string())
+        })?;
+
+        info!(
+            "Successfully saved comic metadata for {} (ID: {})",
+            media_item.title, media_item.id
+        );
+        Ok(saved)
+    }
+
+    /// Best MangaDex match of a series title, with its volume covers
+    async fn fetch_series(
+        &self,
+        manga: &MangaDexProvider,
+        title: &str,
+    ) -> Result<ComicSeries, MetadataAgentError> {
+        let found = manga
+            .search(title, COMIC_SEARCH_LIMIT)
+            .await
+            .map_err(|e| match e {
+                ScraperError::NotFound(_) => MetadataAgentError::NoMatchingResults,
+                e => {
+                    error!("Failed to search for comic {title}: {e}");
+                    MetadataAgentError::SearchFailed(e.to_string())
+                }
+            })?
+            .into_iter()
+            .next()
+            .ok_or(MetadataAgentError::NoMatchingResults)?;
+        debug!("Found series: {} ({})", found.title, found.id);
+
+        let metadata = manga.get_series(&found.id).await.map_err(|e| {
+            error!("Failed to get series details: {}", e);
+            MetadataAgentError::DetailsFailed(e.to_string())
+        })?;
+        // Missing covers shouldn't cost the rest of the metadata
+        let covers = manga
+            .get_covers(&found.id)
+            .await
+            .inspect_err(|e| warn!("Failed to fetch covers of {title}: {e}"))
+            .unwrap_or_default();
+
+        Ok(ComicSeries {
+            metadata,
+            covers,
+            chapters: None,
+        })
+    }
+
+    /// Save metadata to database, with the season and episode the item's
+    /// file was resolved to
     async fn save_metadata(
         &self,
         media_item_id: i64,
         metadata: &MediaMetadata,
+        episode: Option<EpisodeMatch>,
     ) -> Result<VideoMetadata, MetadataAgentError> {
         let create_metadata = CreateVideoMetadata {
             media_item_id,
@@ -165,6 +715,8 @@ impl MetadataAgent {
             vote_average: metadata.rating,
             vote_count: metadata.vote_count,
             genres: metadata.genres.clone(),
+            season_number: episode.map(|e| e.season),
+            episode_number: episode.map(|e| e.episode),
         };
 
         VideoMetadata::upsert(&self.db, create_metadata)
@@ -179,7 +731,7 @@ impl MetadataAgent {
     pub async fn refresh_metadata(
         &self,
         media_item_id: i64,
-    ) -> Result<VideoMetadata, MetadataAgentError> {
+    ) -> Result<SavedMetadata, MetadataAgentError> {
         let media_item = MediaItem::find_by_id(&self.db, media_item_id)
             .await
             .map_err(|e| MetadataAgentError::DatabaseError(e.to_string()))?
@@ -188,22 +740,76 @@ impl MetadataAgent {
         self.fetch_and_save_metadata(&media_item).await
     }
 
-    /// Batch fetch metadata for multiple media items
+    /// Batch fetch metadata for multiple media items, scraping the files of
+    /// movies and episodes [`BATCH_CONCURRENCY`] at a time
+    ///
+    /// Tracks of the same album, and files of the same comic series, are
+    /// looked up once.
     pub async fn batch_fetch_metadata(
         &self,
         media_items: Vec<MediaItem>,
-    ) -> Vec<Result<VideoMetadata, MetadataAgentError>> {
-        let mut results = Vec::new();
+    ) -> Vec<Result<SavedMetadata, MetadataAgentError>> {
+        let (videos, others): (Vec<_>, Vec<_>) = media_items
+            .iter()
+            .enumerate()
+            .partition(|(_, i)| {
+                matches!(i.media_type, EntityMediaType::Movie | EntityMediaType::Tv)
+            });
+        let mut results: Vec<Option<Result<SavedMetadata, MetadataAgentError>>> =
+            media_items.iter().map(|_| None).collect();
 
-        for item in media_items {
-            let result = self.fetch_and_save_metadata(&item).await;
-            results.push(result);
+        let files: Vec<(PathBuf, ParsedMedia)> = videos
+            .iter()
+            .map(|(_, i)| {
+                let path = PathBuf::from(&i.file_path);
+                let parsed = parse_scanned(&path, &i.stream_info);
+                (path, parsed)
+            })
+            .collect();
+        let scrapes =
+            self.scraper_manager
+                .scrape_many(files, BATCH_CONCURRENCY, CancellationToken::new());
 
-            // Add a small delay to respect rate limits
-            tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+        // Scrapes come in the order of the files
+        let mut scrapes = std::pin::pin!(scrapes.zip(stream::iter(&videos)));
+        while let Some(((path, scraped), &(index, item))) = scrapes.next().await {
+            let result = match scraped {
+                Ok(scraped) => self.save_scraped(item, scraped).await,
+                Err(e) => {
+                    error!("Failed to scrape {}: {}", path.display(), e);
+                    Err(MetadataAgentError::SearchFailed(e.to_string()))
+                }
+            };
+            results[index] = Some(result.map(SavedMetadata::Video));
         }
 
-        results
+        let mut albums: HashMap<String, Result<AlbumMetadata, MetadataAgentError>> =
+            HashMap::new();
+        let mut series = HashMap::new();
+        for (index, item) in others {
+            let result = match item.media_type {
+                EntityMediaType::Music => match self.track_of(item).await {
+                    Ok(track) => match albums.get(&track.album_key) {
+                        Some(album) => album.clone(),
+                        None => {
+                            let album = self.fetch_album_of(&track).await;
+                            albums.insert(track.album_key, album.clone());
+                            album
+                        }
+                    },
+                    Err(e) => Err(e),
+                }
+                .map(SavedMetadata::Album),
+                EntityMediaType::Comic => self
+                    .fetch_comic(item, &mut series)
+                    .await
+                    .map(SavedMetadata::Comic),
+                _ => self.fetch_and_save_metadata(item).await,
+            };
+            results[index] = Some(result);
+        }
+
+        results.into_iter().flatten().collect()
     }
 
     /// Search for media without saving
@@ -235,8 +841,150 @@ impl MetadataAgent {
     }
 }
 
+/// What an ebook's path tells about it
+#[derive(Debug, PartialEq, Eq)]
+struct BookQuery {
+    title: String,
+    author: Option<String>,
+    /// ISBN-13 found in the file name
+    isbn: Option<String>,
+}
+
+impl BookQuery {
+    /// Read `Author - Title [ISBN].epub`, or Calibre's `Author/Title/Title -
+    /// Author.epub` layout
+    fn from_path(path: &Path) -> Self {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let isbn = ISBN_RE
+            .find_iter(&stem)
+            .find_map(|m| normalize_isbn(m.as_str()));
+        let clean = |name: &str| {
+            let name = ISBN_RE.replace_all(name, "");
+            BRACKETS_RE
+                .replace_all(&name, "")
+                .replace('_', " ")
+                .trim_matches(|c: char| c.is_whitespace() || c == '-')
+                .to_string()
+        };
+        let name = |p: Option<&Path>| {
+            p.and_then(Path::file_name)
+                .map(|n| clean(&n.to_string_lossy()))
+        };
+        let cleaned = clean(&stem);
+
+        // Calibre names folders "Title (42)" after the book's ID
+        let folder = name(path.parent());
+        let calibre_author = name(path.parent().and_then(Path::parent));
+        if let (Some(folder), Some(author)) = (&folder, &calibre_author)
+            && cleaned == format!("{folder} - {author}")
+        {
+            return Self {
+                title: folder.clone(),
+                author: Some(author.clone()),
+                isbn,
+            };
+        }
+
+        match cleaned.split_once(" - ") {
+            Some((author, title)) => Self {
+                title: title.trim().to_string(),
+                author: Some(author.trim().to_string()),
+                isbn,
+            },
+            None => Self {
+                title: cleaned,
+                author: None,
+                isbn,
+            },
+        }
+    }
+}
+
+/// Series looked up for the files of a comic library
+#[derive(Debug, Clone)]
+struct ComicSeries {
+    metadata: scraper::ComicMetadata,
+    covers: Vec<CoverInfo>,
+    /// Read once a file names a chapter
+    chapters: Option<Vec<ChapterInfo>>,
+}
+
+/// What a comic file's path tells about it
+#[derive(Debug, PartialEq)]
+struct ComicQuery {
+    series: String,
+    volume: Option<f32>,
+    chapter: Option<f32>,
+}
+
+impl ComicQuery {
+    /// Read `Series v01.cbz`, `Series - Ch. 12.cbz` or `Series 042.cbz`; a
+    /// file named after its volume or chapter only takes its folder's name
+    fn from_path(path: &Path) -> Self {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let stem = BRACKETS_RE.replace_all(&stem, "").replace('_', " ");
+        let stem = stem.trim();
+
+        let number = |re: &Regex| {
+            re.captures(stem)
+                .map(|caps| (caps.get(0).map_or(0, |m| m.start()), caps[1].parse().ok()))
+        };
+        let volume = number(&VOLUME_RE);
+        let mut chapter = number(&CHAPTER_RE);
+        if volume.is_none() && chapter.is_none() {
+            chapter = number(&TRAILING_NUMBER_RE);
+        }
+
+        let end = [volume, chapter]
+            .iter()
+            .flatten()
+            .map(|(start, _)| *start)
+            .min()
+            .unwrap_or(stem.len());
+        let series = stem[..end]
+            .trim_matches(|c: char| c.is_whitespace() || matches!(c, '-' | ',' | '.'))
+            .to_string();
+        let series = if series.is_empty() {
+            path.parent()
+                .and_then(Path::file_name)
+                .map(|n| BRACKETS_RE.replace_all(&n.to_string_lossy(), "").trim().to_string())
+                .unwrap_or_default()
+        } else {
+            series
+        };
+
+        Self {
+            series,
+            volume: volume.and_then(|(_, n)| n),
+            chapter: chapter.and_then(|(_, n)| n),
+        }
+    }
+}
+
+/// Search result taken as an album: the best scored one, preferring the
+/// tagged year among close scores
+fn best_album(albums: Vec<AlbumInfo>, year: Option<i64>) -> Option<AlbumInfo> {
+    let mut candidates = albums
+        .into_iter()
+        .filter(|a| a.score.unwrap_or(0) >= MIN_ALBUM_SCORE);
+    let first = candidates.next()?;
+    let Some(year) = year.and_then(|y| i32::try_from(y).ok()) else {
+        return Some(first);
+    };
+    if first.year == Some(year) {
+        return Some(first);
+    }
+    Some(candidates.find(|a| a.year == Some(year)).unwrap_or(first))
+}
+
 /// Metadata agent errors
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum MetadataAgentError {
     #[error("Search failed: {0}")]
     SearchFailed(String),
@@ -255,4 +1003,376 @@ pub enum MetadataAgentError {
 
     #[error("Unsupported media type: {0}")]
     UnsupportedMediaType(String),
+
+    #[error("Failed to write metadata files: {0}")]
+    WriteFailed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{CreateLibraryFolder, LibraryFolder},
+        scraper::NetworkOptions,
+        services::{FileScanner, audio_tags::tests::flac_with_comments},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serves JSON bodies by request path prefix, and 404 to anything else;
+    /// returns its base URL and how often each route was asked for
+    async fn serve_json(
+        routes: Vec<(&'static str, String)>,
+    ) -> (String, Arc<Vec<AtomicUsize>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let hits: Arc<Vec<AtomicUsize>> =
+            Arc::new(routes.iter().map(|_| AtomicUsize::new(0)).collect());
+        let counted = Arc::clone(&hits);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split(' ').nth(1).unwrap_or_default();
+                let route = routes.iter().position(|(prefix, _)| path.starts_with(prefix));
+                let response = match route {
+                    Some(i) => {
+                        counted[i].fetch_add(1, Ordering::SeqCst);
+                        let body = &routes[i].1;
+                        format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                             content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                    }
+                    None => "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n".to_string(),
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (base_url, hits)
+    }
+
+    async fn library(
+        db: &sqlx::SqlitePool,
+        path: &Path,
+        media_type: EntityMediaType,
+    ) -> LibraryFolder {
+        LibraryFolder::create(
+            db,
+            CreateLibraryFolder {
+                name: "Library".to_string(),
+                path: path.to_string_lossy().to_string(),
+                media_type,
+                exclude_patterns: Vec::new(),
+                extensions: Default::default(),
+                metadata_format: Default::default(),
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_scanned_album_is_looked_up_once_and_exported() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let album = dir.path().join("Radiohead").join("OK Computer");
+        std::fs::create_dir_all(&album).unwrap();
+        for (number, title) in [(1, "Airbag"), (2, "Paranoid Android")] {
+            let flac = flac_with_comments(&[
+                &format!("TITLE={title}"),
+                "ARTIST=Radiohead",
+                "ALBUM=OK Computer",
+                &format!("TRACKNUMBER={number}"),
+            ]);
+            std::fs::write(album.join(format!("0{number}.flac")), flac).unwrap();
+        }
+        let folder = library(&db, dir.path(), EntityMediaType::Music).await;
+        FileScanner::new(db.clone())
+            .scan_library_folder(&folder)
+            .await
+            .unwrap();
+
+        let search = r#"{"release-groups":[{"id":"rg-1","title":"OK Computer",
+            "primary-type":"Album","first-release-date":"1997-05-21","score":100,
+            "artist-credit":[{"name":"Radiohead","artist":{"id":"a-1","name":"Radiohead"}}]}]}"#;
+        let group = r#"{"id":"rg-1","title":"OK Computer","primary-type":"Album",
+            "first-release-date":"1997-05-21",
+            "artist-credit":[{"name":"Radiohead","artist":{"id":"a-1","name":"Radiohead"}}],
+            "releases":[{"id":"rel-1","title":"OK Computer","status":"Official",
+                "date":"1997-05-21"}],
+            "genres":[{"name":"alternative rock","count":5}]}"#;
+        let release = r#"{"id":"rel-1","title":"OK Computer","media":[{"position":1,"tracks":[
+            {"position":1,"title":"Airbag","length":284000,"recording":{"id":"rec-1"}}]}],
+            "label-info":[{"label":{"name":"Parlophone"}}]}"#;
+        let cover = r#"{"images":[{"front":true,"image":"https://caa.example/front.jpg"}]}"#;
+        let artists = r#"{"artists":[{"id":"a-1","name":"Radiohead",
+            "sort-name":"Radiohead","type":"Group","score":100}]}"#;
+        let (base_url, hits) = serve_json(vec![
+            ("/release-group?", search.to_string()),
+            ("/release-group/rg-1", group.to_string()),
+            ("/release/rel-1", release.to_string()),
+            ("/caa/release-group/rg-1", cover.to_string()),
+            ("/artist?", artists.to_string()),
+        ])
+        .await;
+        let music = MusicBrainzProvider::new()
+            .with_network(&NetworkOptions {
+                base_url: Some(base_url.clone()),
+                image_base_url: Some(format!("{base_url}/caa")),
+                ..NetworkOptions::default()
+            })
+            .unwrap();
+        let agent = MetadataAgent::new(Arc::new(ScraperManager::new()), db.clone())
+            .with_music_provider(music);
+
+        let items = MediaItem::list_without_metadata(&db, folder.id).await.unwrap();
+        assert_eq!(items.len(), 2);
+        let results = agent.batch_fetch_metadata(items.clone()).await;
+
+        assert_eq!(results.len(), 2);
+        for result in results {
+            let Ok(SavedMetadata::Album(saved)) = result else {
+                panic!("album not saved: {result:?}");
+            };
+            assert_eq!(saved.album_key, album.to_string_lossy());
+            assert_eq!(saved.musicbrainz_id, "rg-1");
+            assert_eq!(saved.label.as_deref(), Some("Parlophone"));
+            assert_eq!(saved.cover_url.as_deref(), Some("https://caa.example/front.jpg"));
+        }
+        assert_eq!(hits[0].load(Ordering::SeqCst), 1);
+        assert!(MediaItem::list_without_metadata(&db, folder.id).await.unwrap().is_empty());
+
+        // Exporting goes by the saved release group, without searching again
+        let written = agent.export_album_nfos(&items[0]).await.unwrap();
+        let artist = dir.path().join("Radiohead");
+        assert_eq!(written, vec![album.join("album.nfo"), artist.join("artist.nfo")]);
+        assert_eq!(hits[0].load(Ordering::SeqCst), 1);
+        let xml = std::fs::read_to_string(album.join("album.nfo")).unwrap();
+        assert!(xml.contains("<musicbrainzreleasegroupid>rg-1</musicbrainzreleasegroupid>"));
+        assert!(xml.contains("<label>Parlophone</label>"), "{xml}");
+        let xml = std::fs::read_to_string(artist.join("artist.nfo")).unwrap();
+        assert!(xml.contains("<musicBrainzArtistID>a-1</musicBrainzArtistID>"), "{xml}");
+    }
+
+    #[test]
+    fn test_book_query_from_path() {
+        let path = Path::new("Books/J.K. Rowling - Harry Potter [0-7475-3269-9].epub");
+        let query = BookQuery::from_path(path);
+        assert_eq!(
+            query,
+            BookQuery {
+                title: "Harry Potter".to_string(),
+                author: Some("J.K. Rowling".to_string()),
+                isbn: Some("9780747532699".to_string()),
+            }
+        );
+
+        let calibre = Path::new("Books/Frank Herbert/Dune (12)/Dune - Frank Herbert.epub");
+        let query = BookQuery::from_path(calibre);
+        assert_eq!(query.title, "Dune");
+        assert_eq!(query.author.as_deref(), Some("Frank Herbert"));
+        assert_eq!(query.isbn, None);
+
+        let query = BookQuery::from_path(Path::new("Neuromancer (1984).mobi"));
+        assert_eq!((query.title.as_str(), query.author), ("Neuromancer", None));
+
+        // Full-width digits are no ISBN
+        let query = BookQuery::from_path(Path::new("Dune [９７８０４４１０１３５９３].epub"));
+        assert_eq!(query.isbn, None);
+    }
+
+    #[tokio::test]
+    async fn test_scanned_books_are_looked_up_by_isbn_then_title() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let by_isbn = dir.path().join("J.K. Rowling - Harry Potter [9780747532699].epub");
+        let by_title = dir.path().join("Frank Herbert - Dune.epub");
+        std::fs::write(&by_isbn, b"book").unwrap();
+        std::fs::write(&by_title, b"book").unwrap();
+        let folder = library(&db, dir.path(), EntityMediaType::Book).await;
+        FileScanner::new(db.clone())
+            .scan_library_folder(&folder)
+            .await
+            .unwrap();
+
+        // Open Library knows the ISBN but finds no "Dune", Google Books does
+        let edition = r#"{"key":"/books/OL1M","title":"Harry Potter and the Philosopher's Stone",
+            "publishers":["Bloomsbury"],"publish_date":"June 26, 1997",
+            "series":["Harry Potter #1"],"works":[{"key":"/works/OL1W"}]}"#;
+        let work = r#"{"key":"/works/OL1W","title":"Harry Potter and the Philosopher's Stone",
+            "authors":[{"author":{"key":"/authors/OL2A"}}]}"#;
+        let volume = r#"{"id":"gb-1","volumeInfo":{"title":"Dune","authors":["Frank Herbert"],
+            "publishedDate":"1965-08-01"}}"#;
+        let (base_url, hits) = serve_json(vec![
+            ("/isbn/9780747532699.json", edition.to_string()),
+            ("/works/OL1W.json", work.to_string()),
+            ("/authors/OL2A.json", r#"{"name":"J. K. Rowling"}"#.to_string()),
+            ("/search.json", r#"{"docs":[]}"#.to_string()),
+            ("/gb/volumes/gb-1", volume.to_string()),
+            ("/gb/volumes?", format!(r#"{{"items":[{volume}]}}"#)),
+        ])
+        .await;
+        let network = |base_url: String| NetworkOptions {
+            base_url: Some(base_url),
+            ..NetworkOptions::default()
+        };
+        let agent = MetadataAgent::new(Arc::new(ScraperManager::new()), db.clone())
+            .with_open_library(
+                OpenLibraryProvider::new()
+                    .with_network(&network(base_url.clone()))
+                    .unwrap(),
+            )
+            .with_google_books(
+                GoogleBooksProvider::new(None)
+                    .with_network(&network(format!("{base_url}/gb")))
+                    .unwrap(),
+            );
+
+        let items = MediaItem::list_without_metadata(&db, folder.id).await.unwrap();
+        let results = agent.batch_fetch_metadata(items.clone()).await;
+
+        for (item, result) in items.iter().zip(results) {
+            let Ok(SavedMetadata::Book(book)) = result else {
+                panic!("book not saved: {result:?}");
+            };
+            if item.file_path == by_isbn.to_string_lossy() {
+                assert_eq!(book.provider, "openlibrary");
+                assert_eq!(book.isbn.as_deref(), Some("9780747532699"));
+                assert_eq!(book.series.as_deref(), Some("Harry Potter"));
+                assert_eq!(book.authors.as_deref(), Some(r#"["J. K. Rowling"]"#));
+            } else {
+                assert_eq!(book.provider, "googlebooks");
+                assert_eq!(book.provider_id, "gb-1");
+                assert_eq!(book.title, "Dune");
+            }
+        }
+        // The ISBN spared a title search
+        assert_eq!(hits[3].load(Ordering::SeqCst), 1);
+        assert!(MediaItem::list_without_metadata(&db, folder.id).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_comic_query_from_path() {
+        let query = |path: &str| ComicQuery::from_path(Path::new(path));
+
+        assert_eq!(
+            query("/comics/Berserk/Berserk v01 (2003) [Digital].cbz"),
+            ComicQuery {
+                series: "Berserk".to_string(),
+                volume: Some(1.0),
+                chapter: None,
+            }
+        );
+        assert_eq!(
+            query("/comics/One_Piece_-_Vol._3_Ch._21.5.cbz"),
+            ComicQuery {
+                series: "One Piece".to_string(),
+                volume: Some(3.0),
+                chapter: Some(21.5),
+            }
+        );
+        assert_eq!(query("/comics/Berserk 042.cbr").chapter, Some(42.0));
+        assert_eq!(query("/comics/Berserk 042.cbr").series, "Berserk");
+        // Named after the volume only
+        assert_eq!(query("/comics/Vinland Saga/Volume 2.cbz").series, "Vinland Saga");
+    }
+
+    #[tokio::test]
+    async fn test_scanned_comics_are_looked_up_once_per_series() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let by_volume = dir.path().join("Berserk v02.cbz");
+        let by_chapter = dir.path().join("Berserk 042.cbz");
+        std::fs::write(&by_volume, b"comic").unwrap();
+        std::fs::write(&by_chapter, b"comic").unwrap();
+        let folder = library(&db, dir.path(), EntityMediaType::Comic).await;
+        FileScanner::new(db.clone())
+            .scan_library_folder(&folder)
+            .await
+            .unwrap();
+
+        let manga = r#"{"id":"md-1","attributes":{"title":{"en":"Berserk"},"year":1989,
+            "status":"ongoing","links":{"al":"30002","mal":"2"}},
+            "relationships":[{"id":"a-1","type":"author","attributes":{"name":"Miura Kentarou"}},
+            {"id":"c-1","type":"cover_art","attributes":{"fileName":"main.jpg"}}]}"#;
+        let (base_url, hits) = serve_json(vec![
+            (
+                "/manga/md-1/feed",
+                r#"{"data":[{"id":"ch-42","attributes":{"volume":"7","chapter":"42",
+                    "title":"Guardians of Desire"}}],"total":1}"#
+                    .to_string(),
+            ),
+            ("/manga/md-1", format!(r#"{{"data":{manga}}}"#)),
+            ("/manga?", format!(r#"{{"data":[{manga}],"total":1}}"#)),
+            (
+                "/cover?",
+                r#"{"data":[{"attributes":{"volume":"2","fileName":"v2.jpg"}},
+                    {"attributes":{"volume":"7","fileName":"v7.jpg"}}],"total":2}"#
+                    .to_string(),
+            ),
+        ])
+        .await;
+        let agent = MetadataAgent::new(Arc::new(ScraperManager::new()), db.clone())
+            .with_manga_provider(
+                MangaDexProvider::new()
+                    .with_network(&NetworkOptions {
+                        base_url: Some(base_url),
+                        ..NetworkOptions::default()
+                    })
+                    .unwrap(),
+            );
+
+        let items = MediaItem::list_without_metadata(&db, folder.id).await.unwrap();
+        let results = agent.batch_fetch_metadata(items.clone()).await;
+
+        for (item, result) in items.iter().zip(results) {
+            let Ok(SavedMetadata::Comic(comic)) = result else {
+                panic!("comic not saved: {result:?}");
+            };
+            assert_eq!(comic.provider_id, "md-1");
+            assert_eq!(comic.title, "Berserk");
+            assert_eq!(comic.authors.as_deref(), Some(r#"["Miura Kentarou"]"#));
+            assert_eq!(comic.anilist_id.as_deref(), Some("30002"));
+            if item.file_path == by_volume.to_string_lossy() {
+                assert_eq!(comic.volume, Some(2.0));
+                assert_eq!(comic.chapter, None);
+                assert!(comic.cover_url.unwrap().ends_with("/md-1/v2.jpg"));
+            } else {
+                assert_eq!(comic.volume, Some(7.0));
+                assert_eq!(comic.chapter, Some(42.0));
+                assert_eq!(comic.chapter_title.as_deref(), Some("Guardians of Desire"));
+                assert!(comic.cover_url.unwrap().ends_with("/md-1/v7.jpg"));
+            }
+        }
+        // One search and one chapter feed for the whole series
+        assert_eq!(hits[0].load(Ordering::SeqCst), 1);
+        assert_eq!(hits[2].load(Ordering::SeqCst), 1);
+        assert!(MediaItem::list_without_metadata(&db, folder.id).await.unwrap().is_empty());
+    }
 }
@@ -1,5 +1,16 @@
+pub mod audio_tags;
 pub mod file_scanner;
+pub mod media_probe;
 pub mod metadata_agent;
+pub mod organize_jobs;
+pub mod scan_jobs;
 
-pub use file_scanner::{FileScanner, FileScannerError, ScanResult};
-pub use metadata_agent::{MetadataAgent, MetadataAgentError};
+pub use audio_tags::{AudioTags, AudioTagsError};
+pub use file_scanner::{
+    FileScanner, FileScannerError, ScanProgress, ScanResult, ScanSnapshot, ScanThrottle,
+    parse_scanned,
+};
+pub use media_probe::{DEFAULT_FFPROBE_PATH, MediaProbe, MediaProbeError};
+pub use metadata_agent::{MetadataAgent, MetadataAgentError, SavedMetadata};
+pub use organize_jobs::{JobStatus, OrganizeJob, OrganizeJobs};
+pub use scan_jobs::{ScanJob, ScanJobs};
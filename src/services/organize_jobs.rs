@@ -0,0 +1,225 @@
+use crate::scraper::{BatchOrganizeResult, OrganizeProgress, Organizer, ProgressSnapshot};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Finished jobs kept around for clients to collect results
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Organize job status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug)]
+struct JobState {
+    status: JobStatus,
+    result: Option<Arc<BatchOrganizeResult>>,
+    error: Option<String>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+/// A background organize run
+#[derive(Debug)]
+pub struct OrganizeJob {
+    pub id: String,
+    pub dry_run: bool,
+    pub created_at: DateTime<Utc>,
+    progress: Arc<OrganizeProgress>,
+    state: Mutex<JobState>,
+}
+
+impl OrganizeJob {
+    #[must_use]
+    pub fn status(&self) -> JobStatus {
+        self.state.lock().status
+    }
+
+    #[must_use]
+    pub fn progress(&self) -> ProgressSnapshot {
+        self.progress.snapshot()
+    }
+
+    /// Batch result once the job has finished
+    #[must_use]
+    pub fn result(&self) -> Option<Arc<BatchOrganizeResult>> {
+        self.state.lock().result.clone()
+    }
+
+    /// Error message if the job failed
+    #[must_use]
+    pub fn error(&self) -> Option<String> {
+        self.state.lock().error.clone()
+    }
+
+    #[must_use]
+    pub fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.state.lock().finished_at
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.status() != JobStatus::Running
+    }
+
+    /// Request cancellation; the job stops after the file in progress
+    pub fn cancel(&self) {
+        self.progress.cancel();
+    }
+
+    fn finish(&self, outcome: Result<BatchOrganizeResult, String>) {
+        let mut state = self.state.lock();
+        match outcome {
+            Ok(result) => {
+                state.status = if result.cancelled {
+                    JobStatus::Cancelled
+                } else {
+                    JobStatus::Completed
+                };
+                state.result = Some(Arc::new(result));
+            }
+            Err(e) => {
+                state.status = JobStatus::Failed;
+                state.error = Some(e);
+            }
+        }
+        state.finished_at = Some(Utc::now());
+    }
+}
+
+/// Registry of background organize jobs
+#[derive(Debug, Default)]
+pub struct OrganizeJobs {
+    jobs: DashMap<String, Arc<OrganizeJob>>,
+}
+
+impl OrganizeJobs {
+    /// Create an empty job registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run an organizer in the background and return its job
+    pub fn start(&self, organizer: Organizer, dry_run: bool) -> Arc<OrganizeJob> {
+        self.prune();
+
+        let job = Arc::new(OrganizeJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            dry_run,
+            created_at: Utc::now(),
+            progress: Arc::new(OrganizeProgress::default()),
+            state: Mutex::new(JobState {
+                status: JobStatus::Running,
+                result: None,
+                error: None,
+                finished_at: None,
+            }),
+        });
+        self.jobs.insert(job.id.clone(), job.clone());
+
+        let task = job.clone();
+        tokio::spawn(async move {
+            info!("Organize job {} started", task.id);
+            let outcome = organizer
+                .organize_all_with_progress(&task.progress)
+                .await
+                .map_err(|e| e.to_string());
+            if let Err(ref e) = outcome {
+                error!("Organize job {} failed: {}", task.id, e);
+            }
+            task.finish(outcome);
+            info!("Organize job {} finished: {:?}", task.id, task.status());
+        });
+
+        job
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<Arc<OrganizeJob>> {
+        self.jobs.get(id).map(|job| job.clone())
+    }
+
+    /// All known jobs, newest first
+    #[must_use]
+    pub fn list(&self) -> Vec<Arc<OrganizeJob>> {
+        let mut jobs: Vec<_> = self.jobs.iter().map(|job| job.clone()).collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+        jobs
+    }
+
+    /// Cancel a running job, returns false if the job does not exist
+    pub fn cancel(&self, id: &str) -> bool {
+        self.get(id).is_some_and(|job| {
+            job.cancel();
+            true
+        })
+    }
+
+    /// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`]
+    fn prune(&self) {
+        let mut finished: Vec<_> = self
+            .jobs
+            .iter()
+            .filter(|job| job.is_finished())
+            .map(|job| (job.created_at, job.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+
+        finished.sort();
+        let excess = finished.len() - MAX_FINISHED_JOBS;
+        for (_, id) in finished.into_iter().take(excess) {
+            self.jobs.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraper::OrganizerConfig;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_job_runs_in_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("downloads");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::write(source_dir.join("Tenet.2020.mkv"), b"video").unwrap();
+
+        let jobs = OrganizeJobs::new();
+        let job = jobs.start(
+            Organizer::new(OrganizerConfig {
+                source_dir,
+                target_dir: dir.path().join("library"),
+                dry_run: true,
+                min_file_size: 0,
+                ..Default::default()
+            }),
+            true,
+        );
+
+        for _ in 0..100 {
+            if job.is_finished() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(job.status(), JobStatus::Completed);
+        assert_eq!(job.progress().linked, 1);
+        assert_eq!(job.result().unwrap().success_count(), 1);
+        assert!(jobs.get(&job.id).is_some());
+        assert!(!jobs.cancel("missing"));
+    }
+}
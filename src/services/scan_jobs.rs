@@ -0,0 +1,275 @@
+use super::{FileScanner, JobStatus, ScanProgress, ScanResult, ScanSnapshot};
+use crate::entities::LibraryFolder;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tracing::{error, info};
+
+/// Finished jobs kept around for clients to collect results
+const MAX_FINISHED_JOBS: usize = 50;
+
+#[derive(Debug)]
+struct JobState {
+    status: JobStatus,
+    results: Vec<(LibraryFolder, ScanResult)>,
+    error: Option<String>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+/// A background library scan over one or more folders
+#[derive(Debug)]
+pub struct ScanJob {
+    pub id: String,
+    /// Folders in scan order
+    pub folder_ids: Vec<i64>,
+    pub created_at: DateTime<Utc>,
+    progress: ScanProgress,
+    state: Mutex<JobState>,
+    finished: Notify,
+}
+
+impl ScanJob {
+    #[must_use]
+    pub fn status(&self) -> JobStatus {
+        self.state.lock().status
+    }
+
+    #[must_use]
+    pub fn progress(&self) -> ScanSnapshot {
+        self.progress.snapshot()
+    }
+
+    /// Results of the folders scanned so far
+    #[must_use]
+    pub fn results(&self) -> Vec<(LibraryFolder, ScanResult)> {
+        self.state.lock().results.clone()
+    }
+
+    /// Error message if the job failed
+    #[must_use]
+    pub fn error(&self) -> Option<String> {
+        self.state.lock().error.clone()
+    }
+
+    #[must_use]
+    pub fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.state.lock().finished_at
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.status() != JobStatus::Running
+    }
+
+    /// Request cancellation; the scan stops at the next file or batch
+    pub fn cancel(&self) {
+        self.progress.cancel();
+    }
+
+    /// Wait until the job has finished
+    pub async fn wait(&self) {
+        let finished = self.finished.notified();
+        if !self.is_finished() {
+            finished.await;
+        }
+    }
+
+    fn finish(&self, results: Vec<(LibraryFolder, ScanResult)>, error: Option<String>) {
+        let mut state = self.state.lock();
+        state.status = if error.is_some() {
+            JobStatus::Failed
+        } else if self.progress.is_cancelled() {
+            JobStatus::Cancelled
+        } else {
+            JobStatus::Completed
+        };
+        state.results = results;
+        state.error = error;
+        state.finished_at = Some(Utc::now());
+        drop(state);
+
+        self.finished.notify_waiters();
+    }
+}
+
+/// Registry of background library scans
+#[derive(Debug, Default)]
+pub struct ScanJobs {
+    jobs: DashMap<String, Arc<ScanJob>>,
+}
+
+impl ScanJobs {
+    /// Create an empty job registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan folders in the background and return the job
+    ///
+    /// A single folder that fails to scan fails the job; when scanning several
+    /// folders the failure is counted in that folder's result instead.
+    pub fn start(&self, scanner: FileScanner, folders: Vec<LibraryFolder>) -> Arc<ScanJob> {
+        self.prune();
+
+        let job = Arc::new(ScanJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            folder_ids: folders.iter().map(|f| f.id).collect(),
+            created_at: Utc::now(),
+            progress: ScanProgress::default(),
+            state: Mutex::new(JobState {
+                status: JobStatus::Running,
+                results: Vec::new(),
+                error: None,
+                finished_at: None,
+            }),
+            finished: Notify::new(),
+        });
+        self.jobs.insert(job.id.clone(), job.clone());
+
+        let task = job.clone();
+        tokio::spawn(async move {
+            info!("Scan job {} started", task.id);
+            let (results, error) = match <[LibraryFolder; 1]>::try_from(folders) {
+                Ok([folder]) => {
+                    match scanner
+                        .scan_library_folder_with_progress(&folder, &task.progress)
+                        .await
+                    {
+                        Ok(result) => (vec![(folder, result)], None),
+                        Err(e) => {
+                            error!("Scan job {} failed: {}", task.id, e);
+                            (Vec::new(), Some(e.to_string()))
+                        }
+                    }
+                }
+                Err(folders) => (scanner.scan_folders(folders, &task.progress).await, None),
+            };
+            task.finish(results, error);
+            info!("Scan job {} finished: {:?}", task.id, task.status());
+        });
+
+        job
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<Arc<ScanJob>> {
+        self.jobs.get(id).map(|job| job.clone())
+    }
+
+    /// All known jobs, newest first
+    #[must_use]
+    pub fn list(&self) -> Vec<Arc<ScanJob>> {
+        let mut jobs: Vec<_> = self.jobs.iter().map(|job| job.clone()).collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+        jobs
+    }
+
+    /// Cancel a running job, returns false if the job does not exist
+    pub fn cancel(&self, id: &str) -> bool {
+        self.get(id).is_some_and(|job| {
+            job.cancel();
+            true
+        })
+    }
+
+    /// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`]
+    fn prune(&self) {
+        let mut finished: Vec<_> = self
+            .jobs
+            .iter()
+            .filter(|job| job.is_finished())
+            .map(|job| (job.created_at, job.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+
+        finished.sort();
+        let excess = finished.len() - MAX_FINISHED_JOBS;
+        for (_, id) in finished.into_iter().take(excess) {
+            self.jobs.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{CreateLibraryFolder, MediaType};
+
+    async fn movie_folder(db: &sqlx::SqlitePool, path: &std::path::Path) -> LibraryFolder {
+        LibraryFolder::create(
+            db,
+            CreateLibraryFolder {
+                name: "Movies".to_string(),
+                path: path.to_string_lossy().to_string(),
+                media_type: MediaType::Movie,
+                exclude_patterns: Vec::new(),
+                extensions: Default::default(),
+                metadata_format: Default::default(),
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_scan_job_reports_progress() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Heat.1995.mkv"), b"video").unwrap();
+        std::fs::write(dir.path().join("Tenet.2020.mkv"), b"video").unwrap();
+        let folder = movie_folder(&db, dir.path()).await;
+
+        let jobs = ScanJobs::new();
+        let job = jobs.start(FileScanner::new(db.clone()), vec![folder]);
+        job.wait().await;
+
+        assert_eq!(job.status(), JobStatus::Completed);
+        assert_eq!((job.progress().walked, job.progress().added), (2, 2));
+        assert_eq!(job.results()[0].1.new_items, 2);
+        assert!(jobs.get(&job.id).is_some());
+        assert!(!jobs.cancel("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_scan_leaves_library_alone() {
+        let db = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Heat.1995.mkv");
+        std::fs::write(&file, b"video").unwrap();
+        let folder = movie_folder(&db, dir.path()).await;
+        let scanner = FileScanner::new(db.clone());
+        scanner.scan_library_folder(&folder).await.unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        let progress = ScanProgress::default();
+        progress.cancel();
+        let result = scanner
+            .scan_library_folder_with_progress(&folder, &progress)
+            .await
+            .unwrap();
+
+        assert!(result.cancelled);
+        assert_eq!(result.missing_items, 0);
+        let items = crate::entities::MediaItem::list_by_folder(&db, folder.id)
+            .await
+            .unwrap();
+        assert!(items[0].missing_since.is_none());
+    }
+}